@@ -0,0 +1,6 @@
+locals {
+  opensearch_database_tags = merge (var.database_tags, {
+    database_identifier = var.opensearch_domain_name
+    creationDate = time_static.on_db_create.rfc3339
+  })
+}
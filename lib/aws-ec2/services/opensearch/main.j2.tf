@@ -0,0 +1,103 @@
+data "aws_vpc" "selected" {
+  filter {
+    name   = "tag:QoveryProduct"
+    values = ["EC2"]
+  }
+  filter {
+    name = "tag:ClusterId"
+    values = [var.kubernetes_cluster_id]
+  }
+}
+
+data "aws_subnet_ids" "selected" {
+  vpc_id = data.aws_vpc.selected.id
+  filter {
+    name = "tag:ClusterId"
+    values = [var.kubernetes_cluster_id]
+  }
+  filter {
+    name = "tag:Service"
+    values = ["Elasticsearch"]
+  }
+}
+
+data "aws_security_group" "selected" {
+  name = "qovery-ec2-${var.kubernetes_cluster_id}"
+  filter {
+    name = "tag:ClusterId"
+    values = [var.kubernetes_cluster_id]
+  }
+  filter {
+    name   = "tag:QoveryProduct"
+    values = ["EC2"]
+  }
+}
+
+resource "aws_opensearch_domain" "opensearch_domain" {
+  domain_name = var.opensearch_domain_name
+  engine_version = var.opensearch_version
+
+  tags = local.opensearch_database_tags
+
+  # OpenSearch instance basics
+  cluster_config {
+    instance_type = var.instance_class
+    instance_count = 1
+    zone_awareness_enabled = false
+  }
+
+  ebs_options {
+    ebs_enabled = true
+    volume_size = var.disk_size
+    volume_type = "gp2"
+  }
+
+  # Network
+  # a single node domain only accepts one subnet
+  vpc_options {
+    subnet_ids = [sort(data.aws_subnet_ids.selected.ids)[0]]
+    security_group_ids = data.aws_security_group.selected.*.id
+  }
+
+  # Security
+  encrypt_at_rest {
+    enabled = true
+  }
+
+  node_to_node_encryption {
+    enabled = true
+  }
+
+  domain_endpoint_options {
+    enforce_https = true
+    tls_security_policy = "Policy-Min-TLS-1-2-2019-07"
+  }
+
+  advanced_security_options {
+    enabled = true
+    internal_user_database_enabled = true
+    master_user_options {
+      master_user_name = var.username
+      master_user_password = var.password
+    }
+  }
+
+  access_policies = <<POLICY
+{
+  "Version": "2012-10-17",
+  "Statement": [
+    {
+      "Effect": "Allow",
+      "Principal": { "AWS": "*" },
+      "Action": "es:*",
+      "Resource": "arn:aws:es:${var.region}:*:domain/${var.opensearch_domain_name}/*"
+    }
+  ]
+}
+POLICY
+
+  # Backups
+  snapshot_options {
+    automated_snapshot_start_hour = 23
+  }
+}
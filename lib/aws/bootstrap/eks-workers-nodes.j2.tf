@@ -1,7 +1,7 @@
 {% for eks_worker_node in eks_worker_nodes %}
 resource "aws_eks_node_group" "eks_cluster_workers_{{ loop.index }}" {
   cluster_name           = aws_eks_cluster.eks_cluster.name
-  version                = var.eks_k8s_versions.workers
+  version                = {% if eks_worker_node.version %}"{{ eks_worker_node.version }}"{% else %}var.eks_k8s_versions.workers{% endif %}
   node_role_arn          = aws_iam_role.eks_workers.arn
   node_group_name_prefix = "qovery-"
   {% if user_provided_network -%}
@@ -12,6 +12,7 @@ resource "aws_eks_node_group" "eks_cluster_workers_{{ loop.index }}" {
   instance_types   = ["{{ eks_worker_node.instance_type }}"]
   ami_type         = "AL2_x86_64"
   disk_size = "{{ eks_worker_node.disk_size_in_gib }}"
+  capacity_type    = "{{ eks_worker_node.capacity_type }}"
 
   tags = merge(
   local.tags_eks,
@@ -21,6 +22,22 @@ resource "aws_eks_node_group" "eks_cluster_workers_{{ loop.index }}" {
   }
   )
 
+  labels = {
+    {%- for key, value in eks_worker_node.labels %}
+    "{{ key }}" = "{{ value }}"
+    {%- endfor %}
+    "qovery.com/node-group-id" = "${var.kubernetes_cluster_id}-{{ eks_worker_node.name }}"
+    "qovery.com/node-group-name" = "{{ eks_worker_node.name }}"
+  }
+
+  {%- for taint in eks_worker_node.taints %}
+  taint {
+    key    = "{{ taint.key }}"
+    value  = "{{ taint.value }}"
+    effect = "{% if taint.effect == "NoExecute" %}NO_EXECUTE{% elif taint.effect == "PreferNoSchedule" %}PREFER_NO_SCHEDULE{% else %}NO_SCHEDULE{% endif %}"
+  }
+  {%- endfor %}
+
   scaling_config {
     desired_size = "{{ eks_worker_node.desired_size }}"
     max_size     = "{{ eks_worker_node.max_nodes }}"
@@ -0,0 +1,37 @@
+# OpenSearch domain basics
+
+variable "opensearch_domain_name" {
+  description = "OpenSearch domain name, limited to 28 characters"
+  default = "{{ opensearch_domain_name }}"
+  type = string
+}
+
+variable "opensearch_version" {
+  description = "OpenSearch engine version"
+  default = "{{ version }}"
+  type = string
+}
+
+variable "disk_size" {
+  description = "EBS volume size per data node"
+  default = "{{ database_disk_size_in_gib }}"
+  type = number
+}
+
+variable "username" {
+  description = "Admin username for the fine grained access control master user"
+  default = "{{ database_login }}"
+  type = string
+}
+
+variable "password" {
+  description = "Admin password for the fine grained access control master user"
+  default = "{{ database_password }}"
+  type = string
+}
+
+variable "instance_class" {
+  description = "Type of instance: https://docs.aws.amazon.com/opensearch-service/latest/developerguide/supported-instance-types.html"
+  default = "{{ database_instance_type }}"
+  type = string
+}
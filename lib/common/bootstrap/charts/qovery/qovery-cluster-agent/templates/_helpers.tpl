@@ -42,6 +42,14 @@ app.kubernetes.io/name: {{ include "qovery-cluster-agent.name" . }}
 app.kubernetes.io/instance: {{ .Release.Name }}
 {{- end }}
 
+{{/*
+Activator selector labels
+*/}}
+{{- define "qovery-cluster-agent.activatorSelectorLabels" -}}
+app.kubernetes.io/name: qovery-activator
+app.kubernetes.io/instance: {{ .Release.Name }}
+{{- end }}
+
 {{/*
 Create the name of the service account to use
 */}}
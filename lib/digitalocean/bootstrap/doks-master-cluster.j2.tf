@@ -18,5 +18,20 @@ resource "digitalocean_kubernetes_cluster" "kubernetes_cluster" {
     auto_scale = true
     min_nodes  = "{{ doks_worker_nodes[0].min_nodes }}"
     max_nodes  = "{{ doks_worker_nodes[0].max_nodes }}"
+
+    labels = {
+      {%- for key, value in doks_worker_nodes[0].labels %}
+      "{{ key }}" = "{{ value }}"
+      {%- endfor %}
+      "qovery.com/node-group-name" = "{{ doks_worker_nodes[0].name }}"
+    }
+
+    {%- for taint in doks_worker_nodes[0].taints %}
+    taint {
+      key    = "{{ taint.key }}"
+      value  = "{{ taint.value }}"
+      effect = "{{ taint.effect }}"
+    }
+    {%- endfor %}
   }
 }
\ No newline at end of file
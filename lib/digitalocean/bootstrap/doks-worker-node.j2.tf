@@ -8,11 +8,26 @@ resource "digitalocean_kubernetes_node_pool" "app_node_pool_{{ loop.index }}" {
 
   name = "qovery-{{kubernetes_cluster_id}}-{{ loop.index }}"
   size = "{{ doks_worker_node.instance_type }}"
-  tags =  concat(local.tags_doks_list, ["QoveryNodeGroupId:${var.kubernetes_cluster_id}-{{ loop.index }}", "QoveryNodeGroupName:{{ eks_worker_node.name }}"])
+  tags =  concat(local.tags_doks_list, ["QoveryNodeGroupId:${var.kubernetes_cluster_id}-{{ loop.index }}", "QoveryNodeGroupName:{{ doks_worker_node.name }}"])
   auto_scale = true
   min_nodes  = {{ doks_worker_node.min_nodes }}
   max_nodes  = {{ doks_worker_node.max_nodes }}
 
+  labels = {
+    {%- for key, value in doks_worker_node.labels %}
+    "{{ key }}" = "{{ value }}"
+    {%- endfor %}
+    "qovery.com/node-group-name" = "{{ doks_worker_node.name }}"
+  }
+
+  {%- for taint in doks_worker_node.taints %}
+  taint {
+    key    = "{{ taint.key }}"
+    value  = "{{ taint.value }}"
+    effect = "{{ taint.effect }}"
+  }
+  {%- endfor %}
+
   depends_on = [
     digitalocean_kubernetes_cluster.kubernetes_cluster,
   ]
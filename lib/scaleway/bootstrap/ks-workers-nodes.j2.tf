@@ -23,6 +23,17 @@ resource "scaleway_k8s_pool" "kubernetes_cluster_workers_{{ loop.index }}" {
   lifecycle {
     create_before_destroy = true
   }
-  tags          =  concat(local.tags_ks_list, ["QoveryNodeGroupName:{{ scw_ks_worker_node.name }}", "QoveryNodeGroupId:${var.kubernetes_cluster_id}_{{ scw_ks_worker_node.instance_type }}_{{ loop.index }}"])
+  # Kapsule sets nodes labels and taints from the "noprefix=key=value" and "taint=noprefix=key=value:Effect" pool tags
+  tags          =  concat(local.tags_ks_list, [
+    "QoveryNodeGroupName:{{ scw_ks_worker_node.name }}",
+    "QoveryNodeGroupId:${var.kubernetes_cluster_id}_{{ scw_ks_worker_node.instance_type }}_{{ loop.index }}",
+    "noprefix=qovery.com/node-group-name={{ scw_ks_worker_node.name }}",
+    {%- for key, value in scw_ks_worker_node.labels %}
+    "noprefix={{ key }}={{ value }}",
+    {%- endfor %}
+    {%- for taint in scw_ks_worker_node.taints %}
+    "taint=noprefix={{ taint.key }}={{ taint.value }}:{{ taint.effect }}",
+    {%- endfor %}
+  ])
 }
 {% endfor %}
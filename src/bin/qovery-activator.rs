@@ -0,0 +1,55 @@
+//! Runs next to the cluster agent: scales idle environments to zero, and holds the requests sent to them
+//! until they are woken up.
+//!
+//! Configuration, from the environment:
+//! * `PROMETHEUS_URL`: Prometheus holding the ingress controller requests metrics, i.e `http://prometheus-operated.prometheus.svc:9090`
+//! * `ACTIVATOR_PORT`: port of the activator, 8081 by default
+//! * `IDLE_CHECK_INTERVAL_SECONDS`: delay between two checks of the idle environments, 60 by default
+//! * `WAKE_UP_TIMEOUT_SECONDS`: maximum time a request is held while its environment is woken up, 300 by default
+
+use qovery_engine::scale_to_zero::{scale_idle_environments_to_zero, serve_activator};
+use std::env;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tracing::{error, info};
+
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+#[tokio::main]
+async fn main() {
+    tracing_subscriber::fmt::init();
+
+    let prometheus_url = env::var("PROMETHEUS_URL").expect("PROMETHEUS_URL environment variable is required");
+    let addr = SocketAddr::from(([0, 0, 0, 0], env_or("ACTIVATOR_PORT", 8081)));
+    let idle_check_interval = Duration::from_secs(env_or("IDLE_CHECK_INTERVAL_SECONDS", 60));
+    let wake_up_timeout = Duration::from_secs(env_or("WAKE_UP_TIMEOUT_SECONDS", 300));
+    let kube = kube::Client::try_default()
+        .await
+        .expect("cannot create kubernetes client");
+
+    let idle_checker_kube = kube.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(idle_check_interval);
+        loop {
+            interval.tick().await;
+            match scale_idle_environments_to_zero(&idle_checker_kube, &prometheus_url).await {
+                Ok(namespaces) if !namespaces.is_empty() => {
+                    info!("environments scaled to zero: {}", namespaces.join(", "))
+                }
+                Ok(_) => {}
+                Err(err) => error!("cannot check idle environments: {}", err.message_safe()),
+            }
+        }
+    });
+
+    info!("activator listening on {}", addr);
+    if let Err(err) = serve_activator(addr, kube, wake_up_timeout).await {
+        error!("activator stopped: {}", err);
+        std::process::exit(1);
+    }
+}
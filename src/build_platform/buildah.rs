@@ -0,0 +1,175 @@
+use uuid::Uuid;
+
+use crate::build_platform::{
+    image_to_build, prepare_dockerfile_build, remove_docker_config, send_build_progress, write_docker_config, Build,
+    BuildError, BuildPlatform, Kind,
+};
+use crate::cmd::command::CommandError::Killed;
+use crate::cmd::command::{CommandError, CommandKiller, ExecutableCommand, QoveryCommand};
+use crate::cmd::docker::BuildResult;
+use crate::events::Transmitter;
+use crate::io_models::context::Context;
+use crate::io_models::progress_listener::{Listener, Listeners, ProgressLevel};
+use crate::logger::Logger;
+use crate::utilities::to_short_id;
+
+/// use buildah, which builds images without any daemon and can run rootless
+pub struct Buildah {
+    context: Context,
+    id: String,
+    long_id: Uuid,
+    name: String,
+    listeners: Listeners,
+    logger: Box<dyn Logger>,
+}
+
+impl Buildah {
+    pub fn new(context: Context, long_id: Uuid, name: &str, logger: Box<dyn Logger>) -> Result<Self, BuildError> {
+        Ok(Buildah {
+            context,
+            id: to_short_id(&long_id),
+            long_id,
+            name: name.to_string(),
+            listeners: vec![],
+            logger,
+        })
+    }
+
+    fn buildah_exec(
+        &self,
+        build: &Build,
+        args: &[&str],
+        envs: &[(&str, &str)],
+        cmd_killer: &CommandKiller,
+    ) -> Result<(), CommandError> {
+        QoveryCommand::new("buildah", args, envs).exec_with_abort(
+            &mut |line| send_build_progress(self, build, ProgressLevel::Info, line),
+            &mut |line| send_build_progress(self, build, ProgressLevel::Warn, line),
+            cmd_killer,
+        )
+    }
+}
+
+impl BuildPlatform for Buildah {
+    fn context(&self) -> &Context {
+        &self.context
+    }
+
+    fn kind(&self) -> Kind {
+        Kind::Buildah
+    }
+
+    fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    fn long_id(&self) -> &Uuid {
+        &self.long_id
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn build(&self, build: &mut Build, is_task_canceled: &dyn Fn() -> bool) -> Result<BuildResult, BuildError> {
+        let dockerfile_build = prepare_dockerfile_build(self, build, is_task_canceled)?;
+        let docker_config_dir = write_docker_config(build, &dockerfile_build.repository_root_path)?;
+        let auth_file = docker_config_dir.join("config.json");
+        // chroot isolation does not require any privilege, so buildah can run in an unprivileged pod
+        let envs = [
+            ("REGISTRY_AUTH_FILE", auth_file.to_str().unwrap_or_default()),
+            ("BUILDAH_ISOLATION", "chroot"),
+        ];
+
+        let image_to_build = image_to_build(build);
+        let image_names = image_to_build.image_names();
+        let mut build_result = BuildResult::new();
+        build_result.build_candidate_image(Some(image_to_build.clone()));
+
+        send_build_progress(
+            self,
+            build,
+            ProgressLevel::Info,
+            format!("⛏️ Building image {} with buildah", image_to_build.image_name()),
+        );
+        let mut args = vec![
+            "build".to_string(),
+            "--layers".to_string(),
+            "-f".to_string(),
+            dockerfile_build
+                .dockerfile_path
+                .to_str()
+                .unwrap_or_default()
+                .to_string(),
+        ];
+        if build.disable_cache {
+            args.push("--no-cache".to_string());
+        }
+        for image_name in &image_names {
+            args.push("-t".to_string());
+            args.push(image_name.to_string());
+        }
+        for (key, value) in &build.environment_variables {
+            args.push("--build-arg".to_string());
+            args.push(format!("{}={}", key, value));
+        }
+        args.push(
+            dockerfile_build
+                .build_context_path
+                .to_str()
+                .unwrap_or_default()
+                .to_string(),
+        );
+
+        let cmd_killer = CommandKiller::from(build.timeout, is_task_canceled);
+        let mut exit_status =
+            self.buildah_exec(build, &args.iter().map(String::as_str).collect::<Vec<_>>(), &envs, &cmd_killer);
+        if exit_status.is_ok() {
+            build_result.built(true);
+            for image_name in &image_names {
+                let destination = format!("docker://{}", image_name);
+                exit_status = self.buildah_exec(build, &["push", image_name, &destination], &envs, &cmd_killer);
+                if exit_status.is_err() {
+                    break;
+                }
+            }
+        }
+
+        // there is no daemon to prune images, so remove them once pushed, the layers are kept for the next build
+        let mut rmi_args = vec!["rmi"];
+        rmi_args.extend(image_names.iter().map(String::as_str));
+        let _ = self.buildah_exec(build, &rmi_args, &envs, &CommandKiller::never());
+        remove_docker_config(&docker_config_dir);
+
+        match exit_status {
+            Ok(_) => {
+                build_result.pushed(true);
+                send_build_progress(self, build, ProgressLevel::Info, build_result.to_string());
+                Ok(build_result)
+            }
+            Err(Killed(_)) => Err(BuildError::Aborted {
+                application: build.image.application_id.clone(),
+            }),
+            Err(err) => Err(BuildError::BuildahError {
+                application: build.image.application_id.clone(),
+                raw_error: err,
+            }),
+        }
+    }
+
+    fn logger(&self) -> Box<dyn Logger> {
+        self.logger.clone()
+    }
+
+    fn listeners(&self) -> &Listeners {
+        &self.listeners
+    }
+
+    fn add_listener(&mut self, listener: Listener) {
+        self.listeners.push(listener);
+    }
+
+    fn to_transmitter(&self) -> Transmitter {
+        Transmitter::BuildPlatform(self.long_id, self.name().to_string())
+    }
+}
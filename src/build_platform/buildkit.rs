@@ -0,0 +1,188 @@
+use std::path::Path;
+
+use uuid::Uuid;
+
+use crate::build_platform::{
+    image_to_build, prepare_dockerfile_build, remove_docker_config, send_build_progress, write_docker_config, Build,
+    BuildError, BuildPlatform, Kind,
+};
+use crate::cmd::command::CommandError::Killed;
+use crate::cmd::command::{CommandKiller, ExecutableCommand, QoveryCommand};
+use crate::cmd::docker::{BuildResult, ContainerImage};
+use crate::events::Transmitter;
+use crate::io_models::context::Context;
+use crate::io_models::progress_listener::{Listener, Listeners, ProgressLevel};
+use crate::logger::Logger;
+use crate::utilities::to_short_id;
+
+/// use a remote BuildKit daemon through `buildctl`, no docker daemon is needed next to the engine
+pub struct BuildKit {
+    context: Context,
+    id: String,
+    long_id: Uuid,
+    name: String,
+    // i.e: tcp://buildkitd.qovery.svc.cluster.local:1234
+    buildkitd_address: String,
+    listeners: Listeners,
+    logger: Box<dyn Logger>,
+}
+
+impl BuildKit {
+    pub fn new(
+        context: Context,
+        long_id: Uuid,
+        name: &str,
+        buildkitd_address: &str,
+        logger: Box<dyn Logger>,
+    ) -> Result<Self, BuildError> {
+        Ok(BuildKit {
+            context,
+            id: to_short_id(&long_id),
+            long_id,
+            name: name.to_string(),
+            buildkitd_address: buildkitd_address.to_string(),
+            listeners: vec![],
+            logger,
+        })
+    }
+}
+
+fn buildctl_args(
+    buildkitd_address: &str,
+    build: &Build,
+    build_context_path: &Path,
+    dockerfile_path: &Path,
+    image_to_build: &ContainerImage,
+) -> Vec<String> {
+    let mut args = vec![
+        "--addr".to_string(),
+        buildkitd_address.to_string(),
+        "build".to_string(),
+        "--progress=plain".to_string(),
+        "--frontend=dockerfile.v0".to_string(),
+        "--local".to_string(),
+        format!("context={}", build_context_path.to_str().unwrap_or_default()),
+        "--local".to_string(),
+        format!(
+            "dockerfile={}",
+            dockerfile_path.parent().and_then(Path::to_str).unwrap_or_default()
+        ),
+        "--opt".to_string(),
+        format!(
+            "filename={}",
+            dockerfile_path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or_default()
+        ),
+        "--output".to_string(),
+        format!("type=image,\"name={}\",push=true", image_to_build.image_names().join(",")),
+    ];
+
+    if build.disable_cache {
+        args.push("--no-cache".to_string());
+    } else {
+        // the latest image embeds its cache metadata, so it can be used as cache source of the next build
+        args.push("--import-cache".to_string());
+        args.push(format!("type=registry,ref={}:latest", build.image.full_image_name()));
+        args.push("--export-cache".to_string());
+        args.push("type=inline".to_string());
+    }
+
+    for (key, value) in &build.environment_variables {
+        args.push("--opt".to_string());
+        args.push(format!("build-arg:{}={}", key, value));
+    }
+
+    args
+}
+
+impl BuildPlatform for BuildKit {
+    fn context(&self) -> &Context {
+        &self.context
+    }
+
+    fn kind(&self) -> Kind {
+        Kind::BuildKit
+    }
+
+    fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    fn long_id(&self) -> &Uuid {
+        &self.long_id
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn build(&self, build: &mut Build, is_task_canceled: &dyn Fn() -> bool) -> Result<BuildResult, BuildError> {
+        let dockerfile_build = prepare_dockerfile_build(self, build, is_task_canceled)?;
+
+        // buildctl reads the registry credentials from the docker config of DOCKER_CONFIG directory
+        let docker_config_dir = write_docker_config(build, &dockerfile_build.repository_root_path)?;
+
+        let image_to_build = image_to_build(build);
+        let mut build_result = BuildResult::new();
+        build_result.build_candidate_image(Some(image_to_build.clone()));
+
+        send_build_progress(
+            self,
+            build,
+            ProgressLevel::Info,
+            format!("⛏️ Building image {} with BuildKit", image_to_build.image_name()),
+        );
+        let args = buildctl_args(
+            &self.buildkitd_address,
+            build,
+            &dockerfile_build.build_context_path,
+            &dockerfile_build.dockerfile_path,
+            &image_to_build,
+        );
+        let mut cmd = QoveryCommand::new(
+            "buildctl",
+            &args.iter().map(String::as_str).collect::<Vec<_>>(),
+            &[("DOCKER_CONFIG", docker_config_dir.to_str().unwrap_or_default())],
+        );
+        // buildctl writes its progress on stderr
+        let exit_status = cmd.exec_with_abort(
+            &mut |line| send_build_progress(self, build, ProgressLevel::Info, line),
+            &mut |line| send_build_progress(self, build, ProgressLevel::Info, line),
+            &CommandKiller::from(build.timeout, is_task_canceled),
+        );
+        remove_docker_config(&docker_config_dir);
+
+        match exit_status {
+            Ok(_) => {
+                build_result.built(true).pushed(true);
+                send_build_progress(self, build, ProgressLevel::Info, build_result.to_string());
+                Ok(build_result)
+            }
+            Err(Killed(_)) => Err(BuildError::Aborted {
+                application: build.image.application_id.clone(),
+            }),
+            Err(err) => Err(BuildError::BuildKitError {
+                application: build.image.application_id.clone(),
+                raw_error: err,
+            }),
+        }
+    }
+
+    fn logger(&self) -> Box<dyn Logger> {
+        self.logger.clone()
+    }
+
+    fn listeners(&self) -> &Listeners {
+        &self.listeners
+    }
+
+    fn add_listener(&mut self, listener: Listener) {
+        self.listeners.push(listener);
+    }
+
+    fn to_transmitter(&self) -> Transmitter {
+        Transmitter::BuildPlatform(self.long_id, self.name().to_string())
+    }
+}
@@ -1,22 +1,21 @@
 #![allow(clippy::redundant_closure)]
 
 use std::io::{Error, ErrorKind};
-use std::path::{Path, PathBuf};
+use std::path::Path;
 use std::{env, fs};
 
-use git2::{Cred, CredentialType};
 use sysinfo::{DiskExt, RefreshKind, SystemExt};
 use uuid::Uuid;
 
-use crate::build_platform::dockerfile_utils::extract_dockerfile_args;
-use crate::build_platform::{Build, BuildError, BuildPlatform, Credentials, Kind};
+use crate::build_platform::{
+    build_context_path, clone_repository, dockerfile_path, image_to_build, repository_build_root_path,
+    retain_dockerfile_args, Build, BuildError, BuildPlatform, Kind,
+};
 use crate::cmd::command;
 use crate::cmd::command::CommandError::Killed;
 use crate::cmd::command::{CommandKiller, ExecutableCommand, QoveryCommand};
 use crate::cmd::docker::{BuildResult, ContainerImage, DockerError};
 use crate::events::{EngineEvent, EventMessage, Transmitter};
-use crate::fs::workspace_directory;
-use crate::git;
 use crate::io_models::context::Context;
 use crate::io_models::progress_listener::{
     Listener, Listeners, ListenersHelper, ProgressInfo, ProgressLevel, ProgressScope,
@@ -146,34 +145,12 @@ impl LocalDocker {
 
         // Going to inject only env var that are used by the dockerfile
         // so extracting it and modifying the image tag and env variables
-        let dockerfile_content = fs::read(dockerfile_complete_path).map_err(|err| BuildError::IoError {
-            application: build.image.application_id.clone(),
-            action_description: "reading dockerfile content".to_string(),
-            raw_error: err,
-        })?;
-        let dockerfile_args = match extract_dockerfile_args(dockerfile_content) {
-            Ok(dockerfile_args) => dockerfile_args,
-            Err(err) => {
-                return Err(BuildError::InvalidConfig {
-                    application: build.image.application_id.clone(),
-                    raw_error_message: format!("Cannot extract env vars from your dockerfile {}", err),
-                });
-            }
-        };
-
-        // Keep only the env variables we want for our build
-        // and force re-compute the image tag
-        build.environment_variables.retain(|k, _| dockerfile_args.contains(k));
-        build.compute_image_tag();
+        retain_dockerfile_args(build, Path::new(dockerfile_complete_path))?;
 
         let mut build_result = BuildResult::new();
 
         // Prepare image we want to build
-        let image_to_build = ContainerImage {
-            registry: build.image.registry_url.clone(),
-            name: build.image.name(),
-            tags: vec![build.image.tag.clone(), "latest".to_string()],
-        };
+        let image_to_build = image_to_build(build);
         build_result.build_candidate_image(Some(image_to_build.clone()));
 
         let image_cache = ContainerImage {
@@ -371,19 +348,6 @@ impl LocalDocker {
             }),
         }
     }
-
-    fn get_repository_build_root_path(&self, build: &Build) -> Result<String, BuildError> {
-        workspace_directory(
-            self.context.workspace_root_dir(),
-            self.context.execution_id(),
-            format!("build/{}", build.image.name.as_str()),
-        )
-        .map_err(|err| BuildError::IoError {
-            application: build.image.application_id.clone(),
-            action_description: "when creating build workspace".to_string(),
-            raw_error: err,
-        })
-    }
 }
 
 impl BuildPlatform for LocalDocker {
@@ -420,7 +384,7 @@ impl BuildPlatform for LocalDocker {
         }
 
         // LOGGING
-        let repository_root_path = PathBuf::from(self.get_repository_build_root_path(build)?);
+        let repository_root_path = repository_build_root_path(&self.context, build)?;
         let msg = format!(
             "📥 Cloning repository: {} to {}",
             build.git_repository.url,
@@ -435,51 +399,7 @@ impl BuildPlatform for LocalDocker {
         self.logger
             .log(EngineEvent::Info(event_details.clone(), EventMessage::new_from_safe(msg)));
 
-        // Create callback that will be called by git to provide credentials per user
-        // If people use submodule, they need to provide us their ssh key
-        let get_credentials = |user: &str| {
-            let mut creds: Vec<(CredentialType, Cred)> = Vec::with_capacity(build.git_repository.ssh_keys.len() + 1);
-            for ssh_key in build.git_repository.ssh_keys.iter() {
-                let public_key = ssh_key.public_key.as_deref();
-                let passphrase = ssh_key.passphrase.as_deref();
-                if let Ok(cred) = Cred::ssh_key_from_memory(user, public_key, &ssh_key.private_key, passphrase) {
-                    creds.push((CredentialType::SSH_MEMORY, cred));
-                }
-            }
-
-            if let Some(Credentials { login, password }) = &build.git_repository.credentials {
-                creds.push((
-                    CredentialType::USER_PASS_PLAINTEXT,
-                    Cred::userpass_plaintext(login, password).unwrap(),
-                ));
-            }
-
-            creds
-        };
-
-        // Cleanup, mono repo can require to clone multiple time the same repo
-        // FIXME: re-use the same repo and just checkout at the correct commit
-        if repository_root_path.exists() {
-            let app_id = app_id;
-            fs::remove_dir_all(&repository_root_path).map_err(|err| BuildError::IoError {
-                application: app_id,
-                action_description: "cleaning old repository".to_string(),
-                raw_error: err,
-            })?;
-        }
-
-        // Do the real git clone
-        if let Err(clone_error) = git::clone_at_commit(
-            &build.git_repository.url,
-            &build.git_repository.commit_id,
-            &repository_root_path,
-            &get_credentials,
-        ) {
-            return Err(BuildError::GitError {
-                application: build.image.application_id.clone(),
-                raw_error: clone_error,
-            });
-        }
+        clone_repository(build, &repository_root_path)?;
 
         if is_task_canceled() {
             return Err(BuildError::Aborted {
@@ -491,52 +411,14 @@ impl BuildPlatform for LocalDocker {
         // ex: this cause regular cleanup on CI, leading to random tests errors
         self.reclaim_space_if_needed();
 
-        let app_id = build.image.application_id.clone();
-
         // Check that the build context is correct
-        let build_context_path = repository_root_path.join(&build.git_repository.root_path);
-        if !build_context_path.is_dir() {
-            return Err(BuildError::InvalidConfig {
-                application: app_id,
-                raw_error_message: format!(
-                    "Specified build context path {:?} does not exist within the repository",
-                    &build.git_repository.root_path
-                ),
-            });
-        }
-
-        // Safety check to ensure we can't go up in the directory
-        if !build_context_path
-            .canonicalize()
-            .unwrap_or_default()
-            .starts_with(repository_root_path.canonicalize().unwrap_or_default())
-        {
-            return Err(BuildError::InvalidConfig {
-                application: app_id,
-                raw_error_message: format!(
-                    "Specified build context path {:?} tries to access directory outside of his git repository",
-                    &build.git_repository.root_path,
-                ),
-            });
-        }
+        let build_context_path = build_context_path(build, &repository_root_path)?;
 
         // now we have to decide if we use buildpack or docker to build our application
         // If no Dockerfile specified, we should use BuildPacks
-        let result = if let Some(dockerfile_path) = &build.git_repository.dockerfile_path {
+        let result = if let Some(dockerfile_relative_path) = &build.git_repository.dockerfile_path {
             // build container from the provided Dockerfile
-
-            let dockerfile_absolute_path = repository_root_path.join(dockerfile_path);
-
-            // If the dockerfile does not exist, abort
-            if !dockerfile_absolute_path.is_file() {
-                return Err(BuildError::InvalidConfig {
-                    application: app_id,
-                    raw_error_message: format!(
-                        "Specified dockerfile path {:?} does not exist within the repository",
-                        &dockerfile_path
-                    ),
-                });
-            }
+            let dockerfile_absolute_path = dockerfile_path(build, dockerfile_relative_path, &repository_root_path)?;
 
             self.build_image_with_docker(
                 build,
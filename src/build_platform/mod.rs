@@ -1,24 +1,34 @@
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
+use crate::build_platform::dockerfile_utils::extract_dockerfile_args;
 use crate::cloud_provider::kubernetes::Kind as KubernetesKind;
 use crate::cmd::command::CommandError;
-use crate::cmd::docker::{BuildResult, DockerError};
+use crate::cmd::docker::{BuildResult, ContainerImage, DockerError};
 use crate::errors::EngineError;
-use crate::events::{EnvironmentStep, EventDetails, Stage, Transmitter};
+use crate::events::{EngineEvent, EnvironmentStep, EventDetails, EventMessage, Stage, Transmitter};
+use crate::fs::workspace_directory;
+use crate::git;
 use crate::io_models::context::Context;
-use crate::io_models::progress_listener::{Listener, Listeners};
+use crate::io_models::progress_listener::{
+    Listener, Listeners, ListenersHelper, ProgressInfo, ProgressLevel, ProgressScope,
+};
 use crate::io_models::QoveryIdentifier;
 use crate::logger::Logger;
 use crate::utilities::compute_image_tag;
+use git2::{Cred, CredentialType};
 use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::fs;
 use std::hash::Hash;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use url::Url;
 use uuid::Uuid;
 
+pub mod buildah;
+pub mod buildkit;
 pub mod dockerfile_utils;
+pub mod kaniko;
 pub mod local_docker;
 
 #[derive(thiserror::Error, Debug)]
@@ -56,6 +66,24 @@ pub enum BuildError {
         application: String,
         raw_error: CommandError,
     },
+
+    #[error("Cannot build Application {application:?} due to an error with buildkit: {raw_error:?}")]
+    BuildKitError {
+        application: String,
+        raw_error: CommandError,
+    },
+
+    #[error("Cannot build Application {application:?} due to an error with buildah: {raw_error:?}")]
+    BuildahError {
+        application: String,
+        raw_error: CommandError,
+    },
+
+    #[error("Cannot build Application {application:?} due to an error with kaniko: {raw_error_message:?}")]
+    KanikoError {
+        application: String,
+        raw_error_message: String,
+    },
 }
 
 pub fn to_engine_error(event_details: EventDetails, err: BuildError) -> EngineError {
@@ -222,4 +250,322 @@ impl Display for Image {
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Kind {
     LocalDocker,
+    BuildKit,
+    Buildah,
+    Kaniko,
+}
+
+/// Workspace directory where the repository of the build is cloned
+pub(crate) fn repository_build_root_path(context: &Context, build: &Build) -> Result<PathBuf, BuildError> {
+    workspace_directory(
+        context.workspace_root_dir(),
+        context.execution_id(),
+        format!("build/{}", build.image.name.as_str()),
+    )
+    .map(PathBuf::from)
+    .map_err(|err| BuildError::IoError {
+        application: build.image.application_id.clone(),
+        action_description: "when creating build workspace".to_string(),
+        raw_error: err,
+    })
+}
+
+/// Clone the repository of the build at its commit, any previous clone is removed
+pub(crate) fn clone_repository(build: &Build, repository_root_path: &Path) -> Result<(), BuildError> {
+    // Create callback that will be called by git to provide credentials per user
+    // If people use submodule, they need to provide us their ssh key
+    let get_credentials = |user: &str| {
+        let mut creds: Vec<(CredentialType, Cred)> = Vec::with_capacity(build.git_repository.ssh_keys.len() + 1);
+        for ssh_key in build.git_repository.ssh_keys.iter() {
+            let public_key = ssh_key.public_key.as_deref();
+            let passphrase = ssh_key.passphrase.as_deref();
+            if let Ok(cred) = Cred::ssh_key_from_memory(user, public_key, &ssh_key.private_key, passphrase) {
+                creds.push((CredentialType::SSH_MEMORY, cred));
+            }
+        }
+
+        if let Some(Credentials { login, password }) = &build.git_repository.credentials {
+            creds.push((
+                CredentialType::USER_PASS_PLAINTEXT,
+                Cred::userpass_plaintext(login, password).unwrap(),
+            ));
+        }
+
+        creds
+    };
+
+    // Cleanup, mono repo can require to clone multiple time the same repo
+    // FIXME: re-use the same repo and just checkout at the correct commit
+    if repository_root_path.exists() {
+        fs::remove_dir_all(repository_root_path).map_err(|err| BuildError::IoError {
+            application: build.image.application_id.clone(),
+            action_description: "cleaning old repository".to_string(),
+            raw_error: err,
+        })?;
+    }
+
+    // Do the real git clone
+    if let Err(clone_error) = git::clone_at_commit(
+        &build.git_repository.url,
+        &build.git_repository.commit_id,
+        repository_root_path,
+        &get_credentials,
+    ) {
+        return Err(BuildError::GitError {
+            application: build.image.application_id.clone(),
+            raw_error: clone_error,
+        });
+    }
+
+    Ok(())
+}
+
+/// Absolute path of the build context, which must be a directory within the repository
+pub(crate) fn build_context_path(build: &Build, repository_root_path: &Path) -> Result<PathBuf, BuildError> {
+    let build_context_path = repository_root_path.join(&build.git_repository.root_path);
+    if !build_context_path.is_dir() {
+        return Err(BuildError::InvalidConfig {
+            application: build.image.application_id.clone(),
+            raw_error_message: format!(
+                "Specified build context path {:?} does not exist within the repository",
+                &build.git_repository.root_path
+            ),
+        });
+    }
+
+    // Safety check to ensure we can't go up in the directory
+    if !build_context_path
+        .canonicalize()
+        .unwrap_or_default()
+        .starts_with(repository_root_path.canonicalize().unwrap_or_default())
+    {
+        return Err(BuildError::InvalidConfig {
+            application: build.image.application_id.clone(),
+            raw_error_message: format!(
+                "Specified build context path {:?} tries to access directory outside of his git repository",
+                &build.git_repository.root_path,
+            ),
+        });
+    }
+
+    Ok(build_context_path)
+}
+
+/// Absolute path of the dockerfile, which must be a file within the repository
+pub(crate) fn dockerfile_path(
+    build: &Build,
+    dockerfile_path: &Path,
+    repository_root_path: &Path,
+) -> Result<PathBuf, BuildError> {
+    let dockerfile_absolute_path = repository_root_path.join(dockerfile_path);
+
+    // If the dockerfile does not exist, abort
+    if !dockerfile_absolute_path.is_file() {
+        return Err(BuildError::InvalidConfig {
+            application: build.image.application_id.clone(),
+            raw_error_message: format!(
+                "Specified dockerfile path {:?} does not exist within the repository",
+                &dockerfile_path
+            ),
+        });
+    }
+
+    Ok(dockerfile_absolute_path)
+}
+
+/// Keep only the env variables used as build args by the dockerfile, and re-compute the image tag accordingly
+pub(crate) fn retain_dockerfile_args(build: &mut Build, dockerfile_path: &Path) -> Result<(), BuildError> {
+    let dockerfile_content = fs::read(dockerfile_path).map_err(|err| BuildError::IoError {
+        application: build.image.application_id.clone(),
+        action_description: "reading dockerfile content".to_string(),
+        raw_error: err,
+    })?;
+    let dockerfile_args = match extract_dockerfile_args(dockerfile_content) {
+        Ok(dockerfile_args) => dockerfile_args,
+        Err(err) => {
+            return Err(BuildError::InvalidConfig {
+                application: build.image.application_id.clone(),
+                raw_error_message: format!("Cannot extract env vars from your dockerfile {}", err),
+            });
+        }
+    };
+
+    build.environment_variables.retain(|k, _| dockerfile_args.contains(k));
+    build.compute_image_tag();
+
+    Ok(())
+}
+
+/// Image to build, with the commit tag and `latest`, which is also used as cache source
+pub(crate) fn image_to_build(build: &Build) -> ContainerImage {
+    ContainerImage {
+        registry: build.image.registry_url.clone(),
+        name: build.image.name(),
+        tags: vec![build.image.tag.clone(), "latest".to_string()],
+    }
+}
+
+/// Docker `config.json` content allowing to push to the registry, from the credentials of its url
+pub(crate) fn docker_config_json(registry_url: &Url) -> String {
+    let password = urlencoding::decode(registry_url.password().unwrap_or_default())
+        .unwrap_or_default()
+        .to_string();
+    let auth = base64::encode(format!("{}:{}", registry_url.username(), password));
+    // credentials are looked up by registry host and port, as written in image names
+    let registry = match registry_url.port() {
+        Some(port) => format!("{}:{}", registry_url.host_str().unwrap_or_default(), port),
+        None => registry_url.host_str().unwrap_or_default().to_string(),
+    };
+
+    serde_json::json!({
+        "auths": {
+            registry: {
+                "auth": auth
+            }
+        }
+    })
+    .to_string()
+}
+
+/// Write the docker config of the build registry next to its repository, returns the directory holding `config.json`
+pub(crate) fn write_docker_config(build: &Build, repository_root_path: &Path) -> Result<PathBuf, BuildError> {
+    let docker_config_dir = PathBuf::from(format!("{}-docker", repository_root_path.to_string_lossy()));
+    fs::create_dir_all(&docker_config_dir)
+        .and_then(|_| {
+            fs::write(
+                docker_config_dir.join("config.json"),
+                docker_config_json(&build.image.registry_url),
+            )
+        })
+        .map_err(|err| BuildError::IoError {
+            application: build.image.application_id.clone(),
+            action_description: "writing registry credentials".to_string(),
+            raw_error: err,
+        })?;
+
+    Ok(docker_config_dir)
+}
+
+/// Remove the docker config written for a build, so registry credentials do not outlive it
+pub(crate) fn remove_docker_config(docker_config_dir: &Path) {
+    if let Err(err) = fs::remove_dir_all(docker_config_dir) {
+        warn!("Cannot remove docker config {}: {}", docker_config_dir.display(), err);
+    }
+}
+
+/// Repository and dockerfile of a build, ready to be built by a backend which does not support buildpacks
+pub(crate) struct DockerfileBuild {
+    pub repository_root_path: PathBuf,
+    pub build_context_path: PathBuf,
+    pub dockerfile_path: PathBuf,
+}
+
+/// Clone the repository of the build, check its dockerfile and compute the image tag from the build args it uses
+pub(crate) fn prepare_dockerfile_build(
+    build_platform: &dyn BuildPlatform,
+    build: &mut Build,
+    is_task_canceled: &dyn Fn() -> bool,
+) -> Result<DockerfileBuild, BuildError> {
+    // check if we should already abort the task
+    if is_task_canceled() {
+        return Err(BuildError::Aborted {
+            application: build.image.application_id.clone(),
+        });
+    }
+
+    let repository_root_path = repository_build_root_path(build_platform.context(), build)?;
+    send_build_progress(
+        build_platform,
+        build,
+        ProgressLevel::Info,
+        format!(
+            "📥 Cloning repository: {} to {}",
+            build.git_repository.url,
+            repository_root_path.to_string_lossy()
+        ),
+    );
+    clone_repository(build, &repository_root_path)?;
+
+    if is_task_canceled() {
+        return Err(BuildError::Aborted {
+            application: build.image.application_id.clone(),
+        });
+    }
+
+    let build_context_path = build_context_path(build, &repository_root_path)?;
+    let dockerfile_path = match &build.git_repository.dockerfile_path {
+        Some(dockerfile_path) => self::dockerfile_path(build, dockerfile_path, &repository_root_path)?,
+        None => {
+            return Err(BuildError::InvalidConfig {
+                application: build.image.application_id.clone(),
+                raw_error_message: format!(
+                    "A dockerfile is required to build with {:?}, buildpacks are only supported by local docker",
+                    build_platform.kind()
+                ),
+            })
+        }
+    };
+    retain_dockerfile_args(build, &dockerfile_path)?;
+
+    Ok(DockerfileBuild {
+        repository_root_path,
+        build_context_path,
+        dockerfile_path,
+    })
+}
+
+/// Send a build log line to the listeners of the build platform, and log it
+pub(crate) fn send_build_progress(
+    build_platform: &dyn BuildPlatform,
+    build: &Build,
+    level: ProgressLevel,
+    msg: String,
+) {
+    let event_message = EventMessage::new_from_safe(msg.clone());
+    build_platform.logger().log(match level {
+        ProgressLevel::Warn | ProgressLevel::Error => {
+            EngineEvent::Warning(build_platform.get_event_details(), event_message)
+        }
+        ProgressLevel::Info | ProgressLevel::Debug => {
+            EngineEvent::Info(build_platform.get_event_details(), event_message)
+        }
+    });
+
+    ListenersHelper::new(build_platform.listeners()).deployment_in_progress(ProgressInfo::new(
+        ProgressScope::Application {
+            id: build.image.application_id.clone(),
+        },
+        level,
+        Some(msg),
+        build_platform.context().execution_id(),
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_docker_config_json() {
+        // setup:
+        let mut registry_url = Url::parse("https://registry.example.com").unwrap();
+        let _ = registry_url.set_username("qovery");
+        let _ = registry_url.set_password(Some("p@ss"));
+
+        // execute:
+        let config: serde_json::Value = serde_json::from_str(&docker_config_json(&registry_url)).unwrap();
+
+        // verify:
+        assert_eq!(
+            serde_json::json!({ "auths": { "registry.example.com": { "auth": base64::encode("qovery:p@ss") } } }),
+            config
+        );
+
+        // execute:
+        let registry_url = Url::parse("https://qovery:p@ss@registry.example.com:5000").unwrap();
+        let config: serde_json::Value = serde_json::from_str(&docker_config_json(&registry_url)).unwrap();
+
+        // verify:
+        assert!(config["auths"].get("registry.example.com:5000").is_some());
+    }
 }
@@ -416,6 +416,8 @@ pub fn ec2_aws_helm_charts(
         } else {
             None
         },
+        // no prometheus on EC2, so no scale to zero
+        prometheus_url: None,
     };
     let cluster_agent_resources = vec![
         ChartSetValue {
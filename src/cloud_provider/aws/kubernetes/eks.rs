@@ -4,9 +4,12 @@ use crate::cloud_provider::aws::kubernetes::Options;
 use crate::cloud_provider::aws::regions::{AwsRegion, AwsZones};
 use crate::cloud_provider::io::ClusterAdvancedSettings;
 use crate::cloud_provider::kubernetes::{
-    send_progress_on_long_task, Kind, Kubernetes, KubernetesNodesType, KubernetesUpgradeStatus,
+    check_node_group_upgrade_status, is_node_group_upgraded, send_progress_on_long_task,
+    wait_for_pod_disruption_budgets, Kind, Kubernetes, KubernetesNodesType, KubernetesUpgradeStatus,
+};
+use crate::cloud_provider::models::{
+    KubernetesClusterAction, NodeGroupCapacityType, NodeGroups, NodeGroupsWithDesiredState,
 };
-use crate::cloud_provider::models::{KubernetesClusterAction, NodeGroups, NodeGroupsWithDesiredState};
 use crate::cloud_provider::utilities::print_action;
 use crate::cloud_provider::CloudProvider;
 use crate::cmd::kubectl::{kubectl_exec_scale_replicas, ScalingKind};
@@ -22,6 +25,7 @@ use crate::object_storage::s3::S3;
 use crate::object_storage::ObjectStorage;
 use function_name::named;
 use std::borrow::Borrow;
+use std::collections::BTreeMap;
 use std::str::FromStr;
 use std::sync::Arc;
 use uuid::Uuid;
@@ -120,6 +124,14 @@ impl EKS {
                     return Err(err);
                 }
             }
+
+            if let Some(label_key) = node_group.reserved_label_key() {
+                return Err(EngineError::new_node_group_reserved_label(
+                    event_details.clone(),
+                    node_group.name.as_str(),
+                    label_key,
+                ));
+            }
         }
         Ok(())
     }
@@ -412,38 +424,44 @@ impl Kubernetes for EKS {
 
         // disable cluster autoscaler to avoid interfering with AWS upgrade procedure
         context.insert("enable_cluster_autoscaler", &false);
+        // node groups are upgraded one at a time, the ones not upgraded yet keep their current version
         context.insert(
             "eks_workers_version",
-            format!("{}", &kubernetes_upgrade_status.requested_version).as_str(),
+            format!("{}", &kubernetes_upgrade_status.deployed_workers_version).as_str(),
         );
 
-        if let Err(e) = crate::template::generate_and_copy_all_files_into_dir(
-            self.template_directory.as_str(),
-            temp_dir.as_str(),
-            context.clone(),
-        ) {
-            return Err(EngineError::new_cannot_copy_files_from_one_directory_to_another(
-                event_details,
-                self.template_directory.to_string(),
-                temp_dir,
-                e,
-            ));
-        }
+        let generate_terraform_files = |context: &tera::Context| -> Result<(), EngineError> {
+            if let Err(e) = crate::template::generate_and_copy_all_files_into_dir(
+                self.template_directory.as_str(),
+                temp_dir.as_str(),
+                context.clone(),
+            ) {
+                return Err(EngineError::new_cannot_copy_files_from_one_directory_to_another(
+                    event_details.clone(),
+                    self.template_directory.to_string(),
+                    temp_dir.to_string(),
+                    e,
+                ));
+            }
 
-        // copy lib/common/bootstrap/charts directory (and sub directory) into the lib/aws/bootstrap/common/charts directory.
-        // this is due to the required dependencies of lib/aws/bootstrap/*.tf files
-        let common_charts_temp_dir = format!("{}/common/charts", temp_dir.as_str());
-        let common_bootstrap_charts = format!("{}/common/bootstrap/charts", self.context.lib_root_dir());
-        if let Err(e) =
-            crate::template::copy_non_template_files(common_bootstrap_charts.as_str(), common_charts_temp_dir.as_str())
-        {
-            return Err(EngineError::new_cannot_copy_files_from_one_directory_to_another(
-                event_details,
-                common_bootstrap_charts,
-                common_charts_temp_dir,
-                e,
-            ));
-        }
+            // copy lib/common/bootstrap/charts directory (and sub directory) into the lib/aws/bootstrap/common/charts directory.
+            // this is due to the required dependencies of lib/aws/bootstrap/*.tf files
+            let common_charts_temp_dir = format!("{}/common/charts", temp_dir.as_str());
+            let common_bootstrap_charts = format!("{}/common/bootstrap/charts", self.context.lib_root_dir());
+            if let Err(e) = crate::template::copy_non_template_files(
+                common_bootstrap_charts.as_str(),
+                common_charts_temp_dir.as_str(),
+            ) {
+                return Err(EngineError::new_cannot_copy_files_from_one_directory_to_another(
+                    event_details.clone(),
+                    common_bootstrap_charts,
+                    common_charts_temp_dir,
+                    e,
+                ));
+            }
+
+            Ok(())
+        };
 
         self.logger().log(EngineEvent::Info(
             event_details.clone(),
@@ -474,10 +492,70 @@ impl Kubernetes for EKS {
             let _ = self.set_cluster_autoscaler_replicas(event_details.clone(), 1);
         });
 
+        let kubeconfig = self.get_kubeconfig_file_path()?;
+        let requested_version = format!("{}", &kubernetes_upgrade_status.requested_version);
+        let mut staged_node_groups = node_groups_with_desired_states.clone();
+        let nb_node_groups = staged_node_groups.len();
+        for index in 0..nb_node_groups {
+            staged_node_groups[index].version = Some(requested_version.clone());
+            let node_group_name = staged_node_groups[index].name.clone();
+            let node_group_id = format!("{}-{}", self.id(), node_group_name);
+
+            // an interrupted upgrade resumes from the first node group not upgraded yet
+            if is_node_group_upgraded(
+                &kubeconfig,
+                self.cloud_provider().credentials_environment_variables(),
+                &node_group_id,
+                &requested_version,
+            )
+            .unwrap_or(false)
+            {
+                self.logger().log(EngineEvent::Info(
+                    event_details.clone(),
+                    EventMessage::new_from_safe(format!(
+                        "Node group {} is already upgraded, skipping it.",
+                        node_group_name
+                    )),
+                ));
+                continue;
+            }
+
+            // pause until the pods evicted by the previous stage are healthy again
+            wait_for_pod_disruption_budgets(&kubeconfig, self.cloud_provider().credentials_environment_variables())
+                .map_err(|e| EngineError::new_k8s_pod_disruption_budgets_not_healthy(event_details.clone(), e))?;
+
+            self.logger().log(EngineEvent::Info(
+                event_details.clone(),
+                EventMessage::new_from_safe(format!(
+                    "Upgrading node group {} ({}/{}).",
+                    node_group_name,
+                    index + 1,
+                    nb_node_groups
+                )),
+            ));
+
+            context.insert("eks_worker_nodes", &staged_node_groups);
+            generate_terraform_files(&context)?;
+            terraform_init_validate_plan_apply(temp_dir.as_str(), self.context.is_dry_run_deploy())
+                .map_err(|e| EngineError::new_terraform_error(event_details.clone(), e))?;
+
+            check_node_group_upgrade_status(
+                &kubeconfig,
+                self.cloud_provider().credentials_environment_variables(),
+                &node_group_id,
+                &requested_version,
+            )
+            .map_err(|e| EngineError::new_k8s_node_not_ready(event_details.clone(), e))?;
+        }
+
+        // all node groups are upgraded, they follow the workers version again
+        context.insert("eks_workers_version", requested_version.as_str());
+        context.insert("eks_worker_nodes", &node_groups_with_desired_states);
+        generate_terraform_files(&context)?;
         terraform_init_validate_plan_apply(temp_dir.as_str(), self.context.is_dry_run_deploy())
             .map_err(|e| EngineError::new_terraform_error(event_details.clone(), e))?;
 
-        self.check_workers_on_upgrade(kubernetes_upgrade_status.requested_version.to_string())
+        self.check_workers_on_upgrade(requested_version)
             .map_err(|e| EngineError::new_k8s_node_not_ready(event_details.clone(), e))?;
 
         self.logger().log(EngineEvent::Info(
@@ -488,6 +566,13 @@ impl Kubernetes for EKS {
         Ok(())
     }
 
+    fn upgradable_node_groups(&self) -> Vec<String> {
+        self.nodes_groups
+            .iter()
+            .map(|node_group| node_group.name.clone())
+            .collect()
+    }
+
     #[named]
     fn on_upgrade(&self) -> Result<(), EngineError> {
         let event_details = self.get_event_details(Infrastructure(InfrastructureStep::Upgrade));
@@ -642,6 +727,10 @@ impl NodeGroupsWithDesiredState {
             enable_desired_size,
             instance_type,
             disk_size_in_gib,
+            version: None,
+            labels: BTreeMap::new(),
+            taints: vec![],
+            capacity_type: NodeGroupCapacityType::OnDemand,
         }
     }
 }
@@ -830,4 +919,33 @@ mod tests {
             &Tag::UnsupportedInstanceType
         );
     }
+
+    #[test]
+    fn test_eks_node_groups_reserved_labels() {
+        // setup:
+        let event_details = EventDetails::new(
+            None,
+            QoveryIdentifier::new_random(),
+            QoveryIdentifier::new_random(),
+            Uuid::new_v4().to_string(),
+            None,
+            Stage::Infrastructure(InfrastructureStep::LoadConfiguration),
+            Transmitter::Kubernetes(Uuid::new_v4(), "".to_string()),
+        );
+        let mut user_labels = NodeGroups::new("batch".to_string(), 3, 5, "t3.medium".to_string(), 20).unwrap();
+        user_labels.labels.insert("workload".to_string(), "batch".to_string());
+        let mut reserved_labels = user_labels.clone();
+        reserved_labels
+            .labels
+            .insert("qovery.com/node-group-name".to_string(), "default".to_string());
+
+        // execute & verify:
+        assert!(EKS::validate_node_groups(vec![user_labels], &event_details).is_ok());
+        assert_eq!(
+            EKS::validate_node_groups(vec![reserved_labels], &event_details)
+                .unwrap_err()
+                .tag(),
+            &Tag::NodeGroupReservedLabel
+        );
+    }
 }
@@ -909,6 +909,15 @@ datasources:
                     key: "controller.admissionWebhooks.enabled".to_string(),
                     value: "false".to_string(),
                 },
+                // requests metrics tell when an environment is idle and can be scaled to zero
+                ChartSetValue {
+                    key: "controller.metrics.enabled".to_string(),
+                    value: chart_config_prerequisites.ff_metrics_history_enabled.to_string(),
+                },
+                ChartSetValue {
+                    key: "controller.metrics.serviceMonitor.enabled".to_string(),
+                    value: chart_config_prerequisites.ff_metrics_history_enabled.to_string(),
+                },
                 // Controller resources limits
                 ChartSetValue {
                     key: "controller.resources.limits.cpu".to_string(),
@@ -975,6 +984,7 @@ datasources:
         },
     };
 
+    let prometheus_activator_url = format!("{}:9090", prometheus_internal_url);
     let cluster_agent_context = ClusterAgentContext {
         api_url: &chart_config_prerequisites.infra_options.qovery_api_url,
         api_token: &chart_config_prerequisites.infra_options.agent_version_controller_token,
@@ -988,6 +998,11 @@ datasources:
         } else {
             None
         },
+        prometheus_url: if chart_config_prerequisites.ff_metrics_history_enabled {
+            Some(&prometheus_activator_url)
+        } else {
+            None
+        },
     };
     let cluster_agent = get_chart_for_cluster_agent(cluster_agent_context, chart_path, None)?;
 
@@ -34,6 +34,7 @@ use crate::cloud_provider::models::{
 use crate::cloud_provider::qovery::EngineLocation;
 use crate::cloud_provider::utilities::{wait_until_port_is_open, TcpCheckSource};
 use crate::cloud_provider::CloudProvider;
+use crate::cmd;
 use crate::cmd::helm::{to_engine_error, Helm};
 use crate::cmd::kubectl::{kubectl_exec_api_custom_metrics, kubectl_exec_get_all_namespaces, kubectl_exec_get_events};
 use crate::cmd::kubectl_utils::kubectl_are_qovery_infra_pods_executed;
@@ -50,9 +51,12 @@ use crate::io_models::domain::{ToHelmString, ToTerraformString};
 use crate::io_models::QoveryIdentifier;
 use crate::object_storage::s3::S3;
 use crate::runtime::block_on;
-use crate::secret_manager::vault::QVaultClient;
+use crate::secret_manager::cluster_secrets::{
+    delete_cluster_terraform_state_credentials, store_cluster_terraform_state_credentials,
+};
+use crate::secret_manager::new_secret_manager;
+use crate::secret_manager::vault::get_vault_mount_name;
 use crate::string::terraform_list_format;
-use crate::{cmd, secret_manager};
 
 use self::eks::select_nodegroups_autoscaling_group_behavior;
 
@@ -891,6 +895,7 @@ fn create(
         return Err(EngineError::new_terraform_error(event_details, e));
     }
 
+    let vault_mount_name = get_vault_mount_name(kubernetes.context().is_test_cluster());
     let mut cluster_secrets = ClusterSecretsAws::new_from_cluster_secrets_io(
         ClusterSecretsIoAws::new(
             kubernetes.cloud_provider().access_key_id(),
@@ -925,14 +930,10 @@ fn create(
             // send cluster info to vault if info mismatch
             // create vault connection (Vault connectivity should not be on the critical deployment path,
             // if it temporarily fails, just ignore it, data will be pushed on the next sync)
-            let vault_conn = match QVaultClient::new(event_details.clone()) {
-                Ok(x) => Some(x),
-                Err(_) => None,
-            };
-            if let Some(vault) = vault_conn {
+            if let Ok(secret_manager) = new_secret_manager(&vault_mount_name, event_details.clone()) {
                 cluster_secrets.k8s_cluster_endpoint = Some(qovery_terraform_config.aws_ec2_public_hostname.clone());
                 // update info without taking care of the kubeconfig because we don't have it yet
-                let _ = cluster_secrets.create_or_update_secret(&vault, true, event_details.clone());
+                let _ = cluster_secrets.create_or_update_secret(secret_manager.as_ref(), true, event_details.clone());
             };
 
             let port = match qovery_terraform_config.kubernetes_port_to_u16() {
@@ -1040,11 +1041,7 @@ fn create(
     // send cluster info with kubeconfig
     // create vault connection (Vault connectivity should not be on the critical deployment path,
     // if it temporarily fails, just ignore it, data will be pushed on the next sync)
-    let vault_conn = match QVaultClient::new(event_details.clone()) {
-        Ok(x) => Some(x),
-        Err(_) => None,
-    };
-    if let Some(vault) = vault_conn {
+    if let Ok(secret_manager) = new_secret_manager(&vault_mount_name, event_details.clone()) {
         // encode base64 kubeconfig
         let kubeconfig_content =
             fs::read_to_string(kubeconfig_path).expect("kubeconfig was not found while it should be present");
@@ -1052,8 +1049,9 @@ fn create(
         cluster_secrets.kubeconfig_b64 = Some(kubeconfig_b64);
 
         // update info without taking care of the kubeconfig because we don't have it yet
-        let _ = cluster_secrets.create_or_update_secret(&vault, false, event_details.clone());
+        let _ = cluster_secrets.create_or_update_secret(secret_manager.as_ref(), false, event_details.clone());
     };
+    store_cluster_terraform_state_credentials(kubernetes, event_details.clone());
 
     kubernetes.logger().log(EngineEvent::Info(
         event_details.clone(),
@@ -1715,14 +1713,13 @@ fn delete(
         Err(err) => return Err(EngineError::new_terraform_error(event_details, err)),
     }?;
 
-    // delete info on vault
-    let vault_conn = QVaultClient::new(event_details);
-    if let Ok(vault_conn) = vault_conn {
-        let mount = secret_manager::vault::get_vault_mount_name(kubernetes.context().is_test_cluster());
-
+    // delete info on the secret manager
+    let mount = get_vault_mount_name(kubernetes.context().is_test_cluster());
+    if let Ok(secret_manager) = new_secret_manager(&mount, event_details.clone()) {
         // ignore on failure
-        let _ = vault_conn.delete_secret(mount.as_str(), kubernetes.id());
+        let _ = secret_manager.delete_secret(kubernetes.id());
     };
+    delete_cluster_terraform_state_credentials(kubernetes, event_details);
 
     Ok(())
 }
@@ -128,7 +128,8 @@ impl FromStr for AwsInstancesType {
 mod tests {
     use crate::cloud_provider::aws::kubernetes::node::AwsInstancesType;
     use crate::cloud_provider::kubernetes::InstanceType;
-    use crate::cloud_provider::models::NodeGroups;
+    use crate::cloud_provider::models::{NodeGroupCapacityType, NodeGroups};
+    use std::collections::BTreeMap;
     use std::str::FromStr;
     use strum::IntoEnumIterator;
 
@@ -184,7 +185,10 @@ mod tests {
                 max_nodes: 2,
                 instance_type: "t2.large".to_string(),
                 disk_size_in_gib: 20,
-                desired_nodes: None
+                desired_nodes: None,
+                labels: BTreeMap::new(),
+                taints: vec![],
+                capacity_type: NodeGroupCapacityType::OnDemand
             }
         );
     }
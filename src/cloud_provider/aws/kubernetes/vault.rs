@@ -1,10 +1,10 @@
 use crate::cloud_provider::kubernetes::Kind;
 use crate::errors::{CommandError, EngineError};
 use crate::events::EventDetails;
+use crate::secret_manager;
 use crate::secret_manager::vault;
-use crate::secret_manager::vault::QVaultClient;
+use crate::secret_manager::{SecretManager, SecretVersion};
 use serde_derive::{Deserialize, Serialize};
-use vaultrs::api::kv2::responses::SecretVersionMetadata;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct ClusterSecretsIoAws {
@@ -146,35 +146,22 @@ impl ClusterSecretsAws {
     }
 
     pub fn get_secret(
-        qvault_client: &QVaultClient,
+        secret_manager: &dyn SecretManager,
         cluster_id: &str,
-        is_test_cluster: bool,
         event_details: EventDetails,
     ) -> Result<ClusterSecretsAws, EngineError> {
-        let mount = vault::get_vault_mount_name(is_test_cluster);
-
-        match qvault_client.get_secret(mount.as_str(), cluster_id) {
-            Ok(x) => Ok(x),
-            Err(e) => Err(EngineError::new_vault_secret_could_not_be_retrieved(
-                event_details,
-                CommandError::new("Vault secret couldn't be retrieved".to_string(), Some(format!("{}", e)), None),
-            )),
-        }
+        secret_manager::get_secret(secret_manager, cluster_id)
+            .map_err(|e| EngineError::new_secret_manager_error(event_details, e.into()))
     }
 
     pub fn create_or_update_secret(
         &self,
-        qvault_client: &QVaultClient,
+        secret_manager: &dyn SecretManager,
         ignore_kubeconfig_compare: bool,
         event_details: EventDetails,
-    ) -> Result<Option<SecretVersionMetadata>, EngineError> {
+    ) -> Result<Option<SecretVersion>, EngineError> {
         // check if secret already exists and has the same content to avoid to create a new version
-        match Self::get_secret(
-            qvault_client,
-            self.cluster_id.as_str(),
-            self.test_cluster,
-            event_details.clone(),
-        ) {
+        match Self::get_secret(secret_manager, self.cluster_id.as_str(), event_details.clone()) {
             Ok(mut x) if ignore_kubeconfig_compare => {
                 x.kubeconfig_b64 = None;
                 let mut current_secret = x.clone();
@@ -191,33 +178,20 @@ impl ClusterSecretsAws {
             Err(_) => {}
         };
 
-        match qvault_client.crate_update_secret(self.vault_mount_name.as_str(), self.cluster_id.as_str(), self) {
-            Ok(x) => Ok(Some(x)),
-            Err(e) => Err(EngineError::new_vault_secret_could_not_be_created_or_updated(
-                event_details,
-                CommandError::new(
-                    "Vault secret couldn't be created or updated".to_string(),
-                    Some(format!("{:?}", e)),
-                    None,
-                ),
-            )),
-        }
+        secret_manager::create_or_update_secret(secret_manager, self.cluster_id.as_str(), self)
+            .map_err(|e| EngineError::new_secret_manager_error(event_details, e.into()))
     }
 
     // required for tests
     #[allow(dead_code)]
-    pub fn delete_secret(&self, qvault_client: &QVaultClient, event_details: EventDetails) -> Result<(), EngineError> {
-        match qvault_client.delete_secret(self.vault_mount_name.as_str(), self.cluster_id.as_str()) {
-            Ok(_) => Ok(()),
-            Err(e) => Err(EngineError::new_vault_secret_could_not_be_created_or_updated(
-                event_details,
-                CommandError::new(
-                    "Vault secret couldn't be created or updated".to_string(),
-                    Some(format!("{:?}", e)),
-                    None,
-                ),
-            )),
-        }
+    pub fn delete_secret(
+        &self,
+        secret_manager: &dyn SecretManager,
+        event_details: EventDetails,
+    ) -> Result<(), EngineError> {
+        secret_manager
+            .delete_secret(self.cluster_id.as_str())
+            .map_err(|e| EngineError::new_secret_manager_error(event_details, e.into()))
     }
 }
 
@@ -227,13 +201,12 @@ mod tests {
     use crate::cloud_provider::kubernetes::Kind;
     use crate::events::{EventDetails, InfrastructureStep, Stage, Transmitter};
     use crate::io_models::QoveryIdentifier;
-    use crate::runtime::block_on;
+    use crate::secret_manager::local::LocalSecretManager;
     use crate::secret_manager::vault;
-    use crate::secret_manager::vault::QVaultClient;
+    use crate::secret_manager::vault::{QVaultClient, VaultSecretManager};
+    use crate::secret_manager::SecretManager;
+    use tempfile::tempdir;
     use uuid::Uuid;
-    use vaultrs::api::kv2::responses::ReadSecretMetadataResponse;
-    use vaultrs::error::ClientError;
-    use vaultrs::kv2;
 
     fn get_event_details() -> EventDetails {
         EventDetails::new(
@@ -264,25 +237,14 @@ mod tests {
         )
     }
 
-    impl QVaultClient {
-        pub fn get_secret_metadata(&self, secret_name_path: &str) -> Result<ReadSecretMetadataResponse, ClientError> {
-            let mount = vault::get_vault_mount_name(true);
-            block_on(kv2::read_metadata(&self.connection, mount.as_str(), secret_name_path))
-        }
-    }
-
-    #[test]
-    fn manage_secret() {
-        // todo(pmavro): check both auth (token/app_role), not only the one set
+    fn check_manage_secret(secret_manager: &dyn SecretManager) {
         let event_details = get_event_details();
         let mut cluster_secret = cluster_secret();
-        let qvault_client =
-            QVaultClient::new(event_details.clone()).expect("should have a vault connexion but something is missing");
 
         // create (none already exists)
         assert_eq!(
             cluster_secret
-                .create_or_update_secret(&qvault_client, false, event_details.clone())
+                .create_or_update_secret(secret_manager, false, event_details.clone())
                 .unwrap()
                 .unwrap()
                 .version,
@@ -291,12 +253,7 @@ mod tests {
 
         // read created secret to ensure it's present
         assert_eq!(
-            ClusterSecretsAws::get_secret(
-                &qvault_client,
-                cluster_secret.cluster_id.as_str(),
-                true, // keep it this way to avoid searching in the wrong path
-                event_details.clone()
-            ),
+            ClusterSecretsAws::get_secret(secret_manager, cluster_secret.cluster_id.as_str(), event_details.clone()),
             Ok(cluster_secret.clone())
         );
 
@@ -305,7 +262,7 @@ mod tests {
         cluster_secret.organization_id = org_uuid.to_string();
         assert_eq!(
             cluster_secret
-                .create_or_update_secret(&qvault_client, false, event_details.clone())
+                .create_or_update_secret(secret_manager, false, event_details.clone())
                 .unwrap()
                 .unwrap()
                 .version,
@@ -314,37 +271,56 @@ mod tests {
 
         // read updated secret
         assert_eq!(
-            ClusterSecretsAws::get_secret(
-                &qvault_client,
-                cluster_secret.cluster_id.as_str(),
-                true,
-                event_details.clone()
-            ),
+            ClusterSecretsAws::get_secret(secret_manager, cluster_secret.cluster_id.as_str(), event_details.clone()),
             Ok(cluster_secret.clone())
         );
 
         // ask to update secret with the same content (no update should be made)
         assert!(cluster_secret
-            .create_or_update_secret(&qvault_client, false, event_details.clone())
+            .create_or_update_secret(secret_manager, false, event_details.clone())
             .unwrap()
             .is_none());
 
         // ask to update secret with the same content and ignoring kubeconfig (no update should be made)
         assert!(cluster_secret
-            .create_or_update_secret(&qvault_client, true, event_details.clone())
+            .create_or_update_secret(secret_manager, true, event_details.clone())
             .unwrap()
             .is_none());
 
         // ensure we're still on v2 and no update have been made
         assert_eq!(
-            qvault_client
-                .get_secret_metadata(cluster_secret.cluster_id.as_str())
+            secret_manager
+                .list_versions(cluster_secret.cluster_id.as_str())
                 .unwrap()
-                .current_version,
-            2
+                .last()
+                .map(|v| v.version),
+            Some(2)
         );
 
         // delete
-        assert_eq!(cluster_secret.delete_secret(&qvault_client, event_details), Ok(()))
+        assert_eq!(cluster_secret.delete_secret(secret_manager, event_details), Ok(()))
+    }
+
+    #[test]
+    fn manage_secret() {
+        // todo(pmavro): check both auth (token/app_role), not only the one set
+        let qvault_client =
+            QVaultClient::new(get_event_details()).expect("should have a vault connexion but something is missing");
+        // keep the test mount to avoid searching in the wrong path
+        let secret_manager = VaultSecretManager::new(qvault_client, &vault::get_vault_mount_name(true));
+
+        check_manage_secret(&secret_manager);
+    }
+
+    #[test]
+    fn manage_secret_local() {
+        let dir = tempdir().unwrap();
+        let secret_manager = LocalSecretManager::new(
+            &dir.path().join("secrets.json"),
+            "passphrase",
+            &vault::get_vault_mount_name(true),
+        );
+
+        check_manage_secret(&secret_manager);
     }
 }
@@ -23,7 +23,7 @@ use crate::cloud_provider::kubernetes::{
     is_kubernetes_upgrade_required, send_progress_on_long_task, uninstall_cert_manager, Kind, Kubernetes,
     KubernetesUpgradeStatus, ProviderOptions,
 };
-use crate::cloud_provider::models::NodeGroups;
+use crate::cloud_provider::models::{NodeGroupCapacityType, NodeGroups};
 use crate::cloud_provider::qovery::EngineLocation;
 use crate::cloud_provider::utilities::print_action;
 use crate::cloud_provider::CloudProvider;
@@ -51,6 +51,9 @@ use crate::models::types::VersionsNumber;
 use crate::object_storage::spaces::{BucketDeleteStrategy, Spaces};
 use crate::object_storage::ObjectStorage;
 use crate::runtime::block_on;
+use crate::secret_manager::cluster_secrets::{
+    delete_cluster_terraform_state_credentials, store_cluster_terraform_state_credentials,
+};
 use crate::string::terraform_list_format;
 use crate::utilities::to_short_id;
 use ::function_name::named;
@@ -148,6 +151,47 @@ impl DOKS {
 
                 return Err(err);
             }
+
+            // spot instances are only offered by AWS
+            if node_group.capacity_type != NodeGroupCapacityType::OnDemand {
+                let err = EngineError::new_unsupported_node_group_capacity_type(
+                    EventDetails::new(
+                        Some(cloud_provider.kind()),
+                        QoveryIdentifier::new(*context.organization_long_id()),
+                        QoveryIdentifier::new(*context.cluster_long_id()),
+                        context.execution_id().to_string(),
+                        Some(region.to_string()),
+                        Infrastructure(InfrastructureStep::LoadConfiguration),
+                        Transmitter::Kubernetes(long_id, name),
+                    ),
+                    node_group.name.as_str(),
+                    node_group.capacity_type,
+                );
+
+                logger.log(EngineEvent::Error(err.clone(), None));
+
+                return Err(err);
+            }
+
+            if let Some(label_key) = node_group.reserved_label_key() {
+                let err = EngineError::new_node_group_reserved_label(
+                    EventDetails::new(
+                        Some(cloud_provider.kind()),
+                        QoveryIdentifier::new(*context.organization_long_id()),
+                        QoveryIdentifier::new(*context.cluster_long_id()),
+                        context.execution_id().to_string(),
+                        Some(region.to_string()),
+                        Infrastructure(InfrastructureStep::LoadConfiguration),
+                        Transmitter::Kubernetes(long_id, name),
+                    ),
+                    node_group.name.as_str(),
+                    label_key,
+                );
+
+                logger.log(EngineEvent::Error(err.clone(), None));
+
+                return Err(err);
+            }
         }
 
         let spaces = Spaces::new(
@@ -572,6 +616,7 @@ impl DOKS {
         if let Err(e) = terraform_init_validate_plan_apply(temp_dir.as_str(), self.context.is_dry_run_deploy()) {
             return Err(EngineError::new_terraform_error(event_details, e));
         }
+        store_cluster_terraform_state_credentials(self, event_details.clone());
 
         let kubeconfig_path = &self.get_kubeconfig_file_path()?;
         let kubeconfig_path = Path::new(kubeconfig_path);
@@ -1036,6 +1081,7 @@ impl DOKS {
 
         match cmd::terraform::terraform_init_validate_destroy(temp_dir.as_str(), false) {
             Ok(_) => {
+                delete_cluster_terraform_state_credentials(self, event_details.clone());
                 self.logger().log(EngineEvent::Info(
                     event_details,
                     EventMessage::new_from_safe("Kubernetes cluster successfully deleted".to_string()),
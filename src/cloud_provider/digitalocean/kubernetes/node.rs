@@ -121,7 +121,8 @@ impl FromStr for DoInstancesType {
 
 #[cfg(test)]
 mod tests {
-    use crate::cloud_provider::models::NodeGroups;
+    use crate::cloud_provider::models::{NodeGroupCapacityType, NodeGroups};
+    use std::collections::BTreeMap;
 
     #[test]
     fn test_groups_nodes() {
@@ -138,7 +139,10 @@ mod tests {
                 max_nodes: 2,
                 instance_type: "s-2vcpu-4gb".to_string(),
                 disk_size_in_gib: 20,
-                desired_nodes: None
+                desired_nodes: None,
+                labels: BTreeMap::new(),
+                taints: vec![],
+                capacity_type: NodeGroupCapacityType::OnDemand
             }
         );
     }
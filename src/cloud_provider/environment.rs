@@ -2,8 +2,11 @@ use crate::cloud_provider::service::Action;
 use crate::models::application::ApplicationService;
 use crate::models::container::ContainerService;
 use crate::models::database::DatabaseService;
+use crate::models::job::JobService;
 use crate::models::router::RouterService;
 use crate::utilities::to_short_id;
+use std::collections::HashMap;
+use std::time::Duration;
 use uuid::Uuid;
 
 pub struct Environment {
@@ -18,8 +21,13 @@ pub struct Environment {
     pub action: Action,
     pub applications: Vec<Box<dyn ApplicationService>>,
     pub containers: Vec<Box<dyn ContainerService>>,
+    pub jobs: Vec<Box<dyn JobService>>,
     pub routers: Vec<Box<dyn RouterService>>,
     pub databases: Vec<Box<dyn DatabaseService>>,
+    /// environment whose data is copied into the cloned services of this one
+    pub clone_source: Option<EnvironmentCloneSource>,
+    /// without ingress traffic for this long, the environment is scaled to zero until the next request
+    pub idle_timeout: Option<Duration>,
 }
 
 impl Environment {
@@ -30,6 +38,7 @@ impl Environment {
         action: Action,
         applications: Vec<Box<dyn ApplicationService>>,
         containers: Vec<Box<dyn ContainerService>>,
+        jobs: Vec<Box<dyn JobService>>,
         routers: Vec<Box<dyn RouterService>>,
         databases: Vec<Box<dyn DatabaseService>>,
     ) -> Self {
@@ -47,8 +56,11 @@ impl Environment {
             action,
             applications,
             containers,
+            jobs,
             routers,
             databases,
+            clone_source: None,
+            idle_timeout: None,
         }
     }
 
@@ -56,3 +68,30 @@ impl Environment {
         self.namespace.as_str()
     }
 }
+
+pub struct EnvironmentCloneSource {
+    pub long_id: Uuid,
+    namespace: String,
+    /// long id of the cloned service => long id of the service it is cloned from
+    services: HashMap<Uuid, Uuid>,
+}
+
+impl EnvironmentCloneSource {
+    /// The cloned environment is expected to live in the same project, thus in the same cluster
+    pub fn new(long_id: Uuid, project_long_id: &Uuid, services: HashMap<Uuid, Uuid>) -> Self {
+        EnvironmentCloneSource {
+            namespace: format!("{}-{}", to_short_id(project_long_id), to_short_id(&long_id)),
+            long_id,
+            services,
+        }
+    }
+
+    pub fn namespace(&self) -> &str {
+        self.namespace.as_str()
+    }
+
+    /// Long id of the service the given one is cloned from, if any
+    pub fn source_service_long_id(&self, service_long_id: &Uuid) -> Option<&Uuid> {
+        self.services.get(service_long_id)
+    }
+}
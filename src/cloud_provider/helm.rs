@@ -1,6 +1,8 @@
 use crate::cloud_provider::helm::HelmAction::Deploy;
 use crate::cloud_provider::helm::HelmChartNamespaces::KubeSystem;
-use crate::cloud_provider::qovery::{get_qovery_app_version, EngineLocation, QoveryAppName, QoveryShellAgent};
+use crate::cloud_provider::qovery::{
+    get_qovery_app_version, EngineLocation, QoveryAppName, QoveryEngine, QoveryShellAgent,
+};
 use crate::cmd::helm::{to_command_error, Helm};
 use crate::cmd::helm_utils::{
     apply_chart_backup, delete_unused_chart_backup, prepare_chart_backup_on_upgrade, update_crds_on_upgrade,
@@ -13,6 +15,7 @@ use crate::cmd::kubectl::{
 use crate::cmd::structs::HelmHistoryRow;
 use crate::dns_provider::DnsProviderConfiguration;
 use crate::errors::{CommandError, ErrorMessageVerbosity};
+use crate::metrics::ExecutionTimings;
 use crate::utilities::calculate_hash;
 use semver::Version;
 use std::collections::HashMap;
@@ -371,10 +374,12 @@ fn deploy_parallel_charts(
     for chart in charts.into_iter() {
         let environment_variables = envs.to_owned();
         let path = kubernetes_config.to_path_buf();
+        // Propagate the current span and execution timings into the thread
         let current_span = tracing::Span::current();
+        let current_timings = ExecutionTimings::current();
         let handle = spawn(move || {
-            // making sure to pass the current span to the new thread not to lose any tracing info
-            let _ = current_span.enter();
+            let _span = current_span.enter();
+            let _timings = current_timings.as_ref().map(|timings| timings.enter());
             chart.run(path.as_path(), &environment_variables)
         });
         handles.push(handle);
@@ -808,6 +813,8 @@ pub struct ClusterAgentContext<'a> {
     pub cluster_jwt_token: &'a str,
     pub grpc_url: &'a str,
     pub loki_url: Option<&'a str>,
+    /// Prometheus storing the ingress controller metrics, the activator scaling idle environments to zero is deployed only when set
+    pub prometheus_url: Option<&'a str>,
 }
 
 // This one is the new agent in rust
@@ -873,6 +880,31 @@ pub fn get_chart_for_cluster_agent(
         });
     }
 
+    // Scale to zero relies on the ingress requests history, so the activator is only deployed along the metrics history
+    if let Some(url) = context.prometheus_url {
+        // the activator is shipped as a binary of the engine image
+        let engine_version: QoveryEngine =
+            get_qovery_app_version(QoveryAppName::Engine, context.api_token, context.api_url, context.cluster_id)?;
+        cluster_agent.chart_info.values.extend(vec![
+            ChartSetValue {
+                key: "activator.enabled".to_string(),
+                value: "true".to_string(),
+            },
+            ChartSetValue {
+                key: "activator.image.tag".to_string(),
+                value: engine_version.version,
+            },
+            ChartSetValue {
+                key: "activator.prometheusUrl".to_string(),
+                value: url.to_string(),
+            },
+            ChartSetValue {
+                key: "activator.ingressControllerNamespace".to_string(),
+                value: HelmChartNamespaces::NginxIngress.to_string(),
+            },
+        ]);
+    }
+
     // resources limits
     match custom_resources {
         None => {
@@ -968,6 +1000,7 @@ pub fn get_chart_for_cert_manager_config(
                 value: q.api_key.clone(),
             });
         }
+        DnsProviderConfiguration::NoOp => {}
     };
 
     cert_manager_config
@@ -8,6 +8,7 @@ pub enum Kind {
     Aws,
     Do,
     Scw,
+    SelfManaged,
 }
 
 impl From<KindModel> for Kind {
@@ -16,6 +17,7 @@ impl From<KindModel> for Kind {
             KindModel::Aws => Kind::Aws,
             KindModel::Do => Kind::Do,
             KindModel::Scw => Kind::Scw,
+            KindModel::SelfManaged => Kind::SelfManaged,
         }
     }
 }
@@ -35,6 +37,8 @@ pub struct ClusterAdvancedSettings {
     pub aws_iam_user_mapper_group_name: String,
     #[serde(alias = "cloud_provider.container_registry.tags")]
     pub cloud_provider_container_registry_tags: HashMap<String, String>,
+    #[serde(alias = "deployment.max_parallel_services")]
+    pub deployment_max_parallel_services: u32,
 }
 
 impl Default for ClusterAdvancedSettings {
@@ -46,6 +50,7 @@ impl Default for ClusterAdvancedSettings {
             loki_log_retention_in_week: 12,
             aws_iam_user_mapper_group_name: "Admins".to_string(),
             cloud_provider_container_registry_tags: HashMap::new(),
+            deployment_max_parallel_services: 4,
         }
     }
 }
@@ -7,6 +7,7 @@ use retry::Error::Operation;
 use retry::OperationResult;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
+use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
 use std::fs::File;
 use std::os::unix::fs::PermissionsExt;
@@ -21,7 +22,8 @@ use uuid::Uuid;
 use crate::cloud_provider::aws::regions::AwsZones;
 use crate::cloud_provider::environment::Environment;
 use crate::cloud_provider::io::ClusterAdvancedSettings;
-use crate::cloud_provider::models::{CpuLimits, InstanceEc2, NodeGroups};
+use crate::cloud_provider::models::{CpuLimits, InstanceEc2, NodeGroupCapacityType, NodeGroups};
+use crate::cloud_provider::upgrade_plan::plan_kubernetes_upgrade;
 use crate::cloud_provider::CloudProvider;
 use crate::cloud_provider::Kind as CloudProviderKind;
 use crate::cmd::kubectl::{kubectl_delete_apiservice, kubectl_delete_completed_jobs};
@@ -50,7 +52,7 @@ use super::models::NodeGroupsWithDesiredState;
 
 pub trait ProviderOptions {}
 
-pub trait Kubernetes {
+pub trait Kubernetes: Send + Sync {
     fn context(&self) -> &Context;
     fn kind(&self) -> Kind;
     fn id(&self) -> &str;
@@ -346,23 +348,46 @@ pub trait Kubernetes {
             Err(e) => return Err(e),
         };
 
-        match is_kubernetes_upgradable(
+        is_kubernetes_upgradable(
             kubeconfig.clone(),
             self.cloud_provider().credentials_environment_variables(),
             event_details.clone(),
-        ) {
-            Err(e) => Err(e),
-            Ok(..) => match is_kubernetes_upgrade_required(
+        )?;
+        let upgrade_status = is_kubernetes_upgrade_required(
+            kubeconfig.clone(),
+            self.version(),
+            self.cloud_provider().credentials_environment_variables(),
+            event_details.clone(),
+            self.logger(),
+        )?;
+
+        if upgrade_status.required_upgrade_on.is_some() {
+            let plan = plan_kubernetes_upgrade(
                 kubeconfig,
-                self.version(),
                 self.cloud_provider().credentials_environment_variables(),
-                event_details,
-                self.logger(),
-            ) {
-                Ok(x) => self.upgrade_with_status(x),
-                Err(e) => Err(e),
-            },
+                &upgrade_status,
+                &self.upgradable_node_groups(),
+            )
+            .map_err(|e| EngineError::new_k8s_cannot_plan_upgrade(event_details.clone(), e))?;
+
+            self.logger().log(EngineEvent::Info(
+                event_details.clone(),
+                EventMessage::new_from_safe(plan.report()),
+            ));
+            if plan.is_blocked() {
+                return Err(EngineError::new_k8s_upgrade_blocked(
+                    event_details,
+                    plan.blockers.iter().map(|blocker| blocker.to_string()).collect(),
+                ));
+            }
         }
+
+        self.upgrade_with_status(upgrade_status)
+    }
+
+    /// Node groups upgraded one at a time, empty when the cloud provider upgrades all the workers at once
+    fn upgradable_node_groups(&self) -> Vec<String> {
+        vec![]
     }
 
     fn check_workers_on_upgrade(&self, targeted_version: String) -> Result<(), CommandError>
@@ -502,6 +527,7 @@ pub enum Kind {
     Ec2,
     Doks,
     ScwKapsule,
+    SelfManaged,
 }
 
 impl Kind {
@@ -511,6 +537,7 @@ impl Kind {
             Kind::Ec2 => CloudProviderKind::Aws,
             Kind::Doks => CloudProviderKind::Do,
             Kind::ScwKapsule => CloudProviderKind::Scw,
+            Kind::SelfManaged => CloudProviderKind::SelfManaged,
         }
     }
 }
@@ -522,6 +549,7 @@ impl Display for Kind {
             Kind::Ec2 => "EC2",
             Kind::Doks => "DOKS",
             Kind::ScwKapsule => "ScwKapsule",
+            Kind::SelfManaged => "SelfManaged",
         })
     }
 }
@@ -627,6 +655,10 @@ impl NodeGroupsWithDesiredState {
             enable_desired_size: enable_desired_nodes,
             instance_type: nodegroup.instance_type.clone(),
             disk_size_in_gib: nodegroup.disk_size_in_gib,
+            version: None,
+            labels: nodegroup.labels.clone(),
+            taints: nodegroup.taints.clone(),
+            capacity_type: nodegroup.capacity_type,
         }
     }
 }
@@ -752,6 +784,93 @@ where
     };
 }
 
+/// Prefix of the node labels managed by Qovery, node group labels can't use it
+pub const QOVERY_RESERVED_LABEL_PREFIX: &str = "qovery.com/";
+
+/// Label set on the nodes of each node group, to follow them during staged upgrades
+pub const NODE_GROUP_ID_LABEL: &str = "qovery.com/node-group-id";
+
+/// Whether all the nodes of the node group run the target version, a node group without labelled nodes being unknown
+pub fn is_node_group_upgraded<P>(
+    kubernetes_config: P,
+    envs: Vec<(&str, &str)>,
+    node_group_id: &str,
+    target_version: &str,
+) -> Result<bool, CommandError>
+where
+    P: AsRef<Path>,
+{
+    let nodes = kubectl_exec_get_node(kubernetes_config, envs)?;
+    let kubelet_versions = nodes
+        .items
+        .iter()
+        .filter(|node| node.metadata.labels.get(NODE_GROUP_ID_LABEL).map(|id| id.as_str()) == Some(node_group_id))
+        .map(|node| node.status.node_info.kubelet_version.as_str())
+        .collect::<Vec<_>>();
+
+    Ok(!kubelet_versions.is_empty()
+        && kubelet_versions
+            .iter()
+            .all(|version| version.contains(&format!("v{}.", target_version))))
+}
+
+pub fn check_node_group_upgrade_status<P>(
+    kubernetes_config: P,
+    envs: Vec<(&str, &str)>,
+    node_group_id: &str,
+    target_version: &str,
+) -> Result<(), CommandError>
+where
+    P: AsRef<Path>,
+{
+    let result = retry::retry(Fixed::from_millis(10000).take(360), || {
+        match is_node_group_upgraded(kubernetes_config.as_ref(), envs.clone(), node_group_id, target_version) {
+            Err(e) => OperationResult::Retry(e),
+            Ok(false) => OperationResult::Retry(CommandError::new_from_safe_message(format!(
+                "There are still not upgraded nodes in node group {}.",
+                node_group_id
+            ))),
+            Ok(true) => OperationResult::Ok(()),
+        }
+    });
+
+    match result {
+        Ok(_) => check_workers_status(kubernetes_config.as_ref(), envs),
+        Err(Operation { error, .. }) => Err(error),
+        Err(retry::Error::Internal(e)) => Err(CommandError::new_from_safe_message(e)),
+    }
+}
+
+/// Wait for the pods evicted by the previous stage to be healthy again, before draining other nodes
+pub fn wait_for_pod_disruption_budgets<P>(kubernetes_config: P, envs: Vec<(&str, &str)>) -> Result<(), CommandError>
+where
+    P: AsRef<Path>,
+{
+    let result = retry::retry(Fixed::from_millis(10000).take(60), || {
+        match kubernetes_get_all_pdbs(kubernetes_config.as_ref(), envs.clone(), None) {
+            Err(e) => OperationResult::Retry(e),
+            Ok(pdbs) => match pdbs
+                .items
+                .unwrap_or_default()
+                .into_iter()
+                .find(|pdb| pdb.status.current_healthy < pdb.status.desired_healthy)
+            {
+                Some(pdb) => OperationResult::Retry(CommandError::new_from_safe_message(format!(
+                    "Pod disruption budget {}/{} is not healthy yet.",
+                    pdb.metadata.namespace, pdb.metadata.name
+                ))),
+                None => OperationResult::Ok(()),
+            },
+        }
+    });
+
+    match result {
+        Ok(_) => Ok(()),
+        Err(Operation { error, .. }) => Err(error),
+        Err(retry::Error::Internal(e)) => Err(CommandError::new_from_safe_message(e)),
+    }
+}
+
 pub fn check_workers_status<P>(kubernetes_config: P, envs: Vec<(&str, &str)>) -> Result<(), CommandError>
 where
     P: AsRef<Path>,
@@ -1055,6 +1174,9 @@ impl NodeGroups {
             instance_type,
             disk_size_in_gib,
             desired_nodes: None,
+            labels: BTreeMap::new(),
+            taints: vec![],
+            capacity_type: NodeGroupCapacityType::OnDemand,
         })
     }
 
@@ -1076,6 +1198,14 @@ impl NodeGroups {
             self.desired_nodes = Some(desired_nodes)
         }
     }
+
+    /// First label key reserved to Qovery, those labels are set on every node and can't be overridden
+    pub fn reserved_label_key(&self) -> Option<&str> {
+        self.labels
+            .keys()
+            .map(|key| key.as_str())
+            .find(|key| key.starts_with(QOVERY_RESERVED_LABEL_PREFIX))
+    }
 }
 
 impl InstanceEc2 {
@@ -1283,6 +1413,23 @@ pub async fn kube_list_services(
     }
 }
 
+pub async fn kube_list_secrets(
+    kube: &kube::Client,
+    namespace_name: &str,
+    labels_selector: &str,
+) -> Result<ObjectList<Secret>, CommandError> {
+    let client: Api<Secret> = Api::namespaced(kube.clone(), namespace_name);
+
+    match client.list(&ListParams::default().labels(labels_selector)).await {
+        Ok(x) => Ok(x),
+        Err(e) => Err(CommandError::new(
+            "Error while trying to get kubernetes secrets".to_string(),
+            Some(e.to_string()),
+            None,
+        )),
+    }
+}
+
 pub fn filter_svc_loadbalancers(load_balancers: ObjectList<Service>) -> Vec<Service> {
     let mut filtered_load_balancers = Vec::new();
 
@@ -1367,7 +1514,9 @@ mod tests {
         filter_svc_loadbalancers, kube_create_namespace_if_not_exists, kube_does_secret_exists, kube_list_services,
         validate_k8s_required_cpu_and_burstable, KubernetesNodesType,
     };
-    use crate::cloud_provider::models::CpuLimits;
+    use crate::cloud_provider::models::{
+        CpuLimits, NodeGroupCapacityType, NodeGroupTaint, NodeGroups, NodeGroupsWithDesiredState, TaintEffect,
+    };
     use crate::cmd::structs::{KubernetesList, KubernetesNode, KubernetesVersion};
     use crate::events::{EventDetails, InfrastructureStep, Stage, Transmitter};
     use crate::io_models::progress_listener::ListenersHelper;
@@ -2093,4 +2242,38 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    pub fn test_node_groups_scheduling_settings() {
+        // setup:
+        let on_demand: NodeGroups = serde_json::from_str(
+            r#"{"name": "default", "id": null, "min_nodes": 3, "max_nodes": 5, "desired_nodes": null, "instance_type": "t3a.large", "disk_size_in_gib": 100}"#,
+        )
+        .unwrap();
+        let spot: NodeGroups = serde_json::from_str(
+            r#"{"name": "batch", "id": null, "min_nodes": 0, "max_nodes": 10, "desired_nodes": null, "instance_type": "t3a.large", "disk_size_in_gib": 100,
+                "labels": {"workload": "batch"}, "taints": [{"key": "workload", "value": "batch", "effect": "NoSchedule"}], "capacity_type": "SPOT"}"#,
+        )
+        .unwrap();
+
+        // execute:
+        let on_demand = NodeGroupsWithDesiredState::new_from_node_groups(&on_demand, 3, true);
+        let spot = NodeGroupsWithDesiredState::new_from_node_groups(&spot, 0, true);
+
+        // verify:
+        assert!(on_demand.labels.is_empty());
+        assert!(on_demand.taints.is_empty());
+        assert_eq!(on_demand.capacity_type, NodeGroupCapacityType::OnDemand);
+        assert_eq!(spot.labels.get("workload"), Some(&"batch".to_string()));
+        assert_eq!(
+            spot.taints,
+            vec![NodeGroupTaint {
+                key: "workload".to_string(),
+                value: "batch".to_string(),
+                effect: TaintEffect::NoSchedule,
+            }]
+        );
+        assert_eq!(spot.capacity_type, NodeGroupCapacityType::Spot);
+        assert_eq!(serde_json::to_value(&spot).unwrap()["capacity_type"], "SPOT");
+    }
 }
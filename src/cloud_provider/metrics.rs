@@ -9,3 +9,14 @@ pub struct KubernetesApiMetrics {
 pub struct MetricValue {
     pub value: String,
 }
+
+/// Metrics listed by a metrics adapter, i.e `pods/http_requests_per_second`
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct KubernetesApiMetricsResources {
+    pub resources: Vec<MetricResource>,
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct MetricResource {
+    pub name: String,
+}
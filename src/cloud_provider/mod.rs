@@ -29,10 +29,12 @@ pub mod metrics;
 pub mod models;
 pub mod qovery;
 pub mod scaleway;
+pub mod self_managed;
 pub mod service;
+pub mod upgrade_plan;
 pub mod utilities;
 
-pub trait CloudProvider {
+pub trait CloudProvider: Send + Sync {
     fn context(&self) -> &Context;
     fn kind(&self) -> Kind;
     fn kubernetes_kind(&self) -> kubernetes::Kind;
@@ -68,6 +70,7 @@ pub enum Kind {
     Aws,
     Do,
     Scw,
+    SelfManaged,
 }
 
 impl FromStr for Kind {
@@ -78,6 +81,7 @@ impl FromStr for Kind {
             "aws" | "amazon" => Ok(Kind::Aws),
             "do" | "digitalocean" => Ok(Kind::Do),
             "scw" | "scaleway" => Ok(Kind::Scw),
+            "self-managed" | "selfmanaged" | "local" => Ok(Kind::SelfManaged),
             _ => Err(()),
         }
     }
@@ -89,12 +93,14 @@ impl Display for Kind {
             Kind::Aws => "AWS",
             Kind::Do => "Digital Ocean",
             Kind::Scw => "Scaleway",
+            Kind::SelfManaged => "Self-managed",
         })
     }
 }
 
 pub trait CloudProviderZones {}
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct TerraformStateCredentials {
     pub access_key_id: String,
     pub secret_access_key: String,
@@ -190,6 +196,12 @@ mod tests {
             ("SCW ", Ok(Kind::Scw)),
             ("Scw", Ok(Kind::Scw)),
             ("scw_blabla", Err(())),
+            ("self-managed", Ok(Kind::SelfManaged)),
+            ("selfmanaged", Ok(Kind::SelfManaged)),
+            ("local", Ok(Kind::SelfManaged)),
+            (" Local ", Ok(Kind::SelfManaged)),
+            ("SELF-MANAGED", Ok(Kind::SelfManaged)),
+            ("self_managed", Err(())),
         ];
 
         for tc in test_cases {
@@ -1,4 +1,8 @@
+use crate::io_models::application::{SecretReference, SnapshotSchedule};
+use crate::io_models::router::CustomDomainCertificate;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
 use uuid::Uuid;
 
 #[derive(Serialize, Debug, Clone, Eq, PartialEq, Hash)]
@@ -7,6 +11,13 @@ pub struct EnvironmentVariable {
     pub value: String,
 }
 
+/// Environment variable whose value is read from a secret manager at deploy time
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct SecretEnvironmentVariable {
+    pub key: String,
+    pub secret: SecretReference,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct EnvironmentVariableDataTemplate {
     pub key: String,
@@ -22,6 +33,8 @@ pub struct Storage<T> {
     pub size_in_gib: u16,
     pub mount_point: String,
     pub snapshot_retention_in_days: u16,
+    pub snapshot_schedule: SnapshotSchedule,
+    pub restore_from_snapshot: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -39,6 +52,8 @@ pub struct StorageDataTemplate {
 pub struct CustomDomain {
     pub domain: String,
     pub target_domain: String,
+    /// user supplied certificate, cert-manager is not used for this domain when set
+    pub certificate: Option<CustomDomainCertificate>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -46,9 +61,33 @@ pub struct CustomDomainDataTemplate {
     pub domain: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CustomCertificateDataTemplate {
+    pub domain: String,
+    /// Kubernetes TLS secret holding the user supplied certificate
+    pub secret_name: String,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct IngressDataTemplate {
+    pub name: String,
+    /// routes of an ingress share the same rewrite, as nginx only allows it per ingress
+    pub rewrite_target: Option<String>,
+    pub hosts: Vec<HostDataTemplate>,
+    /// domains served with a user supplied certificate are kept apart from the ones managed by cert-manager
+    pub custom_certificates: Vec<CustomCertificateDataTemplate>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
 pub struct HostDataTemplate {
     pub domain_name: String,
+    pub paths: Vec<HostPathDataTemplate>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct HostPathDataTemplate {
+    pub path: String,
+    pub path_type: String,
     pub service_name: String,
     pub service_port: u16,
 }
@@ -56,6 +95,8 @@ pub struct HostDataTemplate {
 pub struct Route {
     pub path: String,
     pub service_long_id: Uuid,
+    pub rewrite_path: Option<String>,
+    pub service_port: Option<u16>,
 }
 
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
@@ -73,6 +114,47 @@ pub struct NodeGroups {
     pub desired_nodes: Option<i32>,
     pub instance_type: String,
     pub disk_size_in_gib: i32,
+    /// labels set on every node of the group, in addition to the Qovery ones
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    #[serde(default)]
+    pub taints: Vec<NodeGroupTaint>,
+    #[serde(default)]
+    pub capacity_type: NodeGroupCapacityType,
+}
+
+/// Taint set on every node of a group, only pods tolerating it are scheduled there
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Debug, Clone)]
+pub struct NodeGroupTaint {
+    pub key: String,
+    pub value: String,
+    pub effect: TaintEffect,
+}
+
+/// Effect of a taint, named as in Kubernetes
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub enum TaintEffect {
+    NoSchedule,
+    PreferNoSchedule,
+    NoExecute,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum NodeGroupCapacityType {
+    #[default]
+    OnDemand,
+    /// spot or preemptible instances, cheaper but which can be reclaimed at any time
+    Spot,
+}
+
+impl Display for NodeGroupCapacityType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            NodeGroupCapacityType::OnDemand => "ON_DEMAND",
+            NodeGroupCapacityType::Spot => "SPOT",
+        })
+    }
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
@@ -85,6 +167,11 @@ pub struct NodeGroupsWithDesiredState {
     pub enable_desired_size: bool,
     pub instance_type: String,
     pub disk_size_in_gib: i32,
+    /// kubernetes version of the node group when it differs from the other ones, during a staged upgrade
+    pub version: Option<String>,
+    pub labels: BTreeMap<String, String>,
+    pub taints: Vec<NodeGroupTaint>,
+    pub capacity_type: NodeGroupCapacityType,
 }
 
 #[derive(Serialize, Deserialize)]
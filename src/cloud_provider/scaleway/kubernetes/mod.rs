@@ -8,7 +8,7 @@ use crate::cloud_provider::kubernetes::{
     is_kubernetes_upgrade_required, send_progress_on_long_task, uninstall_cert_manager, Kind, Kubernetes,
     KubernetesUpgradeStatus, ProviderOptions,
 };
-use crate::cloud_provider::models::{NodeGroups, NodeGroupsFormat};
+use crate::cloud_provider::models::{NodeGroupCapacityType, NodeGroups, NodeGroupsFormat};
 use crate::cloud_provider::qovery::EngineLocation;
 use crate::cloud_provider::scaleway::kubernetes::helm_charts::{scw_helm_charts, ChartsConfigPrerequisites};
 use crate::cloud_provider::scaleway::kubernetes::node::{ScwInstancesType, ScwNodeGroup};
@@ -34,6 +34,9 @@ use crate::models::scaleway::ScwZone;
 use crate::object_storage::scaleway_object_storage::{BucketDeleteStrategy, ScalewayOS};
 use crate::object_storage::ObjectStorage;
 use crate::runtime::block_on;
+use crate::secret_manager::cluster_secrets::{
+    delete_cluster_terraform_state_credentials, store_cluster_terraform_state_credentials,
+};
 use crate::string::terraform_list_format;
 use crate::utilities::to_short_id;
 use ::function_name::named;
@@ -185,6 +188,47 @@ impl Kapsule {
 
                 return Err(err);
             }
+
+            // spot instances are only offered by AWS
+            if node_group.capacity_type != NodeGroupCapacityType::OnDemand {
+                let err = EngineError::new_unsupported_node_group_capacity_type(
+                    EventDetails::new(
+                        Some(cloud_provider.kind()),
+                        QoveryIdentifier::new(*context.organization_long_id()),
+                        QoveryIdentifier::new(*context.cluster_long_id()),
+                        context.execution_id().to_string(),
+                        Some(zone.region_str().to_string()),
+                        Infrastructure(InfrastructureStep::LoadConfiguration),
+                        Transmitter::Kubernetes(long_id, name),
+                    ),
+                    node_group.name.as_str(),
+                    node_group.capacity_type,
+                );
+
+                logger.log(EngineEvent::Error(err.clone(), None));
+
+                return Err(err);
+            }
+
+            if let Some(label_key) = node_group.reserved_label_key() {
+                let err = EngineError::new_node_group_reserved_label(
+                    EventDetails::new(
+                        Some(cloud_provider.kind()),
+                        QoveryIdentifier::new(*context.organization_long_id()),
+                        QoveryIdentifier::new(*context.cluster_long_id()),
+                        context.execution_id().to_string(),
+                        Some(zone.region_str().to_string()),
+                        Infrastructure(InfrastructureStep::LoadConfiguration),
+                        Transmitter::Kubernetes(long_id, name),
+                    ),
+                    node_group.name.as_str(),
+                    label_key,
+                );
+
+                logger.log(EngineEvent::Error(err.clone(), None));
+
+                return Err(err);
+            }
         }
 
         let object_storage = ScalewayOS::new(
@@ -685,6 +729,7 @@ impl Kapsule {
         if let Err(e) = terraform_init_validate_plan_apply(temp_dir.as_str(), self.context.is_dry_run_deploy()) {
             return Err(EngineError::new_terraform_error(event_details, e));
         }
+        store_cluster_terraform_state_credentials(self, event_details.clone());
 
         // push config file to object storage
         let kubeconfig_path = &self.get_kubeconfig_file_path()?;
@@ -1406,6 +1451,7 @@ impl Kapsule {
 
         match cmd::terraform::terraform_init_validate_destroy(temp_dir.as_str(), false) {
             Ok(_) => {
+                delete_cluster_terraform_state_credentials(self, event_details.clone());
                 self.logger().log(EngineEvent::Info(
                     event_details,
                     EventMessage::new_from_safe("Kubernetes cluster successfully deleted".to_string()),
@@ -131,7 +131,8 @@ impl ScwNodeGroup {
 
 #[cfg(test)]
 mod tests {
-    use crate::cloud_provider::models::NodeGroups;
+    use crate::cloud_provider::models::{NodeGroupCapacityType, NodeGroups};
+    use std::collections::BTreeMap;
 
     #[test]
     fn test_groups_nodes() {
@@ -148,7 +149,10 @@ mod tests {
                 max_nodes: 2,
                 instance_type: "dev1-l".to_string(),
                 disk_size_in_gib: 20,
-                desired_nodes: None
+                desired_nodes: None,
+                labels: BTreeMap::new(),
+                taints: vec![],
+                capacity_type: NodeGroupCapacityType::OnDemand
             }
         );
     }
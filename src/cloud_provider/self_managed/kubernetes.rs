@@ -0,0 +1,338 @@
+use std::borrow::Borrow;
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+use ::function_name::named;
+use uuid::Uuid;
+
+use crate::cloud_provider::aws::regions::AwsZones;
+use crate::cloud_provider::io::ClusterAdvancedSettings;
+use crate::cloud_provider::kubernetes::{Kind, Kubernetes, KubernetesUpgradeStatus};
+use crate::cloud_provider::utilities::print_action;
+use crate::cloud_provider::CloudProvider;
+use crate::cmd::kubectl::kubectl_exec_version;
+use crate::dns_provider::DnsProvider;
+use crate::errors::{CommandError, EngineError};
+use crate::events::Stage::Infrastructure;
+use crate::events::{EngineEvent, EventDetails, EventMessage, GeneralStep, InfrastructureStep, Stage};
+use crate::io_models::context::Context;
+use crate::io_models::domain::StringPath;
+use crate::logger::Logger;
+use crate::object_storage::local_filesystem::LocalFileSystem;
+use crate::object_storage::ObjectStorage;
+use crate::utilities::to_short_id;
+
+/// Kubernetes cluster created and operated outside of Qovery (i.e: kind, k3s, on premise).
+/// The engine only gets a kubeconfig: there is no Terraform, nothing is created nor deleted on the infrastructure side.
+pub struct SelfManagedKubernetes {
+    context: Context,
+    id: String,
+    long_id: Uuid,
+    name: String,
+    version: String,
+    region: String,
+    cloud_provider: Arc<Box<dyn CloudProvider>>,
+    dns_provider: Arc<Box<dyn DnsProvider>>,
+    object_storage: LocalFileSystem,
+    kubeconfig_path: String,
+    logger: Box<dyn Logger>,
+    advanced_settings: ClusterAdvancedSettings,
+}
+
+impl SelfManagedKubernetes {
+    pub fn new(
+        context: Context,
+        long_id: Uuid,
+        name: String,
+        version: String,
+        region: String,
+        cloud_provider: Arc<Box<dyn CloudProvider>>,
+        dns_provider: Arc<Box<dyn DnsProvider>>,
+        kubeconfig_path: String,
+        logger: Box<dyn Logger>,
+        advanced_settings: ClusterAdvancedSettings,
+    ) -> SelfManagedKubernetes {
+        let object_storage = LocalFileSystem::new(
+            context.clone(),
+            "local-fs-temp-id".to_string(),
+            "default-local-fs".to_string(),
+            Path::new(context.workspace_root_dir()).join(".qovery-object-storage"),
+        );
+
+        SelfManagedKubernetes {
+            context,
+            id: to_short_id(&long_id),
+            long_id,
+            name,
+            version,
+            region,
+            cloud_provider,
+            dns_provider,
+            object_storage,
+            kubeconfig_path,
+            logger,
+            advanced_settings,
+        }
+    }
+
+    fn check_cluster_is_reachable(&self, event_details: EventDetails) -> Result<(), EngineError> {
+        let kubeconfig_path = self.get_kubeconfig_file_path()?;
+
+        match kubectl_exec_version(kubeconfig_path, self.cloud_provider().credentials_environment_variables()) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(EngineError::new_cannot_execute_k8s_exec_version(event_details, e)),
+        }
+    }
+
+    fn log_nothing_to_do(&self, event_details: EventDetails, action: &str) {
+        self.logger().log(EngineEvent::Info(
+            event_details,
+            EventMessage::new_from_safe(format!(
+                "Kubernetes cluster `{}` is self-managed, nothing to {} on the infrastructure side.",
+                self.name(),
+                action
+            )),
+        ));
+    }
+
+    fn cloud_provider_name(&self) -> &str {
+        "self-managed"
+    }
+
+    fn struct_name(&self) -> &str {
+        "kubernetes"
+    }
+}
+
+impl Kubernetes for SelfManagedKubernetes {
+    fn context(&self) -> &Context {
+        &self.context
+    }
+
+    fn kind(&self) -> Kind {
+        Kind::SelfManaged
+    }
+
+    fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    fn long_id(&self) -> &Uuid {
+        &self.long_id
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn version(&self) -> &str {
+        self.version.as_str()
+    }
+
+    fn region(&self) -> &str {
+        self.region.as_str()
+    }
+
+    fn zone(&self) -> &str {
+        ""
+    }
+
+    fn aws_zones(&self) -> Option<Vec<AwsZones>> {
+        None
+    }
+
+    fn cloud_provider(&self) -> &dyn CloudProvider {
+        self.cloud_provider.as_ref().borrow()
+    }
+
+    fn dns_provider(&self) -> &dyn DnsProvider {
+        self.dns_provider.as_ref().borrow()
+    }
+
+    fn logger(&self) -> &dyn Logger {
+        self.logger.borrow()
+    }
+
+    fn config_file_store(&self) -> &dyn ObjectStorage {
+        &self.object_storage
+    }
+
+    fn is_valid(&self) -> Result<(), EngineError> {
+        self.get_kubeconfig_file().map(|_| ())
+    }
+
+    fn is_network_managed_by_user(&self) -> bool {
+        true
+    }
+
+    fn get_kubeconfig_file(&self) -> Result<(String, File), EngineError> {
+        // kubeconfig is provided by the user, we never retrieve it nor change its permissions
+        match File::open(&self.kubeconfig_path) {
+            Ok(file) => Ok((StringPath::from(&self.kubeconfig_path), file)),
+            Err(e) => Err(EngineError::new_cannot_retrieve_cluster_config_file(
+                self.get_event_details(Stage::General(GeneralStep::RetrieveClusterConfig)),
+                CommandError::new(
+                    format!("Error while trying to open Kubeconfig file `{}`.", self.kubeconfig_path),
+                    Some(e.to_string()),
+                    None,
+                ),
+            )),
+        }
+    }
+
+    #[named]
+    fn on_create(&self) -> Result<(), EngineError> {
+        let event_details = self.get_event_details(Infrastructure(InfrastructureStep::Create));
+        print_action(
+            self.cloud_provider_name(),
+            self.struct_name(),
+            function_name!(),
+            self.name(),
+            event_details.clone(),
+            self.logger(),
+        );
+        self.log_nothing_to_do(event_details.clone(), "create");
+        self.check_cluster_is_reachable(event_details)
+    }
+
+    #[named]
+    fn on_create_error(&self) -> Result<(), EngineError> {
+        let event_details = self.get_event_details(Infrastructure(InfrastructureStep::Create));
+        print_action(
+            self.cloud_provider_name(),
+            self.struct_name(),
+            function_name!(),
+            self.name(),
+            event_details,
+            self.logger(),
+        );
+        Ok(())
+    }
+
+    fn upgrade_with_status(&self, _kubernetes_upgrade_status: KubernetesUpgradeStatus) -> Result<(), EngineError> {
+        let event_details = self.get_event_details(Infrastructure(InfrastructureStep::Upgrade));
+        self.log_nothing_to_do(event_details, "upgrade");
+        Ok(())
+    }
+
+    #[named]
+    fn on_upgrade(&self) -> Result<(), EngineError> {
+        let event_details = self.get_event_details(Infrastructure(InfrastructureStep::Upgrade));
+        print_action(
+            self.cloud_provider_name(),
+            self.struct_name(),
+            function_name!(),
+            self.name(),
+            event_details.clone(),
+            self.logger(),
+        );
+        self.log_nothing_to_do(event_details, "upgrade");
+        Ok(())
+    }
+
+    #[named]
+    fn on_upgrade_error(&self) -> Result<(), EngineError> {
+        let event_details = self.get_event_details(Infrastructure(InfrastructureStep::Upgrade));
+        print_action(
+            self.cloud_provider_name(),
+            self.struct_name(),
+            function_name!(),
+            self.name(),
+            event_details,
+            self.logger(),
+        );
+        Ok(())
+    }
+
+    #[named]
+    fn on_downgrade(&self) -> Result<(), EngineError> {
+        let event_details = self.get_event_details(Infrastructure(InfrastructureStep::Downgrade));
+        print_action(
+            self.cloud_provider_name(),
+            self.struct_name(),
+            function_name!(),
+            self.name(),
+            event_details.clone(),
+            self.logger(),
+        );
+        self.log_nothing_to_do(event_details, "downgrade");
+        Ok(())
+    }
+
+    #[named]
+    fn on_downgrade_error(&self) -> Result<(), EngineError> {
+        let event_details = self.get_event_details(Infrastructure(InfrastructureStep::Downgrade));
+        print_action(
+            self.cloud_provider_name(),
+            self.struct_name(),
+            function_name!(),
+            self.name(),
+            event_details,
+            self.logger(),
+        );
+        Ok(())
+    }
+
+    #[named]
+    fn on_pause(&self) -> Result<(), EngineError> {
+        let event_details = self.get_event_details(Infrastructure(InfrastructureStep::Pause));
+        print_action(
+            self.cloud_provider_name(),
+            self.struct_name(),
+            function_name!(),
+            self.name(),
+            event_details.clone(),
+            self.logger(),
+        );
+        self.log_nothing_to_do(event_details, "pause");
+        Ok(())
+    }
+
+    #[named]
+    fn on_pause_error(&self) -> Result<(), EngineError> {
+        let event_details = self.get_event_details(Infrastructure(InfrastructureStep::Pause));
+        print_action(
+            self.cloud_provider_name(),
+            self.struct_name(),
+            function_name!(),
+            self.name(),
+            event_details,
+            self.logger(),
+        );
+        Ok(())
+    }
+
+    #[named]
+    fn on_delete(&self) -> Result<(), EngineError> {
+        let event_details = self.get_event_details(Infrastructure(InfrastructureStep::Delete));
+        print_action(
+            self.cloud_provider_name(),
+            self.struct_name(),
+            function_name!(),
+            self.name(),
+            event_details.clone(),
+            self.logger(),
+        );
+        self.log_nothing_to_do(event_details, "delete");
+        Ok(())
+    }
+
+    #[named]
+    fn on_delete_error(&self) -> Result<(), EngineError> {
+        let event_details = self.get_event_details(Infrastructure(InfrastructureStep::Delete));
+        print_action(
+            self.cloud_provider_name(),
+            self.struct_name(),
+            function_name!(),
+            self.name(),
+            event_details,
+            self.logger(),
+        );
+        Ok(())
+    }
+
+    fn advanced_settings(&self) -> &ClusterAdvancedSettings {
+        &self.advanced_settings
+    }
+}
@@ -0,0 +1,151 @@
+use std::any::Any;
+use uuid::Uuid;
+
+use crate::cloud_provider::{
+    kubernetes::Kind as KubernetesKind, CloudProvider, EngineError, Kind, TerraformStateCredentials,
+};
+use crate::events::{EventDetails, Stage, Transmitter};
+use crate::io_models::context::Context;
+use crate::io_models::progress_listener::{Listener, Listeners};
+use crate::io_models::QoveryIdentifier;
+use crate::utilities::to_short_id;
+
+pub mod kubernetes;
+
+/// Cloud provider for clusters not managed by Qovery (i.e: kind, k3s, on premise).
+/// There is no cloud account behind it, so no credentials and no Terraform state.
+pub struct SelfManaged {
+    context: Context,
+    id: String,
+    long_id: Uuid,
+    name: String,
+    organization_id: String,
+    organization_long_id: Uuid,
+    region: String,
+    zones: Vec<String>,
+    terraform_state_credentials: TerraformStateCredentials,
+    listeners: Listeners,
+}
+
+impl SelfManaged {
+    pub fn new(
+        context: Context,
+        long_id: Uuid,
+        organization_id: &str,
+        organization_long_id: Uuid,
+        name: &str,
+        region: &str,
+    ) -> SelfManaged {
+        SelfManaged {
+            context,
+            id: to_short_id(&long_id),
+            long_id,
+            organization_id: organization_id.to_string(),
+            organization_long_id,
+            name: name.to_string(),
+            region: region.to_string(),
+            zones: vec![],
+            terraform_state_credentials: TerraformStateCredentials::new("", "", ""),
+            listeners: vec![],
+        }
+    }
+}
+
+impl CloudProvider for SelfManaged {
+    fn context(&self) -> &Context {
+        &self.context
+    }
+
+    fn kind(&self) -> Kind {
+        Kind::SelfManaged
+    }
+
+    fn kubernetes_kind(&self) -> KubernetesKind {
+        KubernetesKind::SelfManaged
+    }
+
+    fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    fn organization_id(&self) -> &str {
+        self.organization_id.as_str()
+    }
+
+    fn organization_long_id(&self) -> Uuid {
+        self.organization_long_id
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn access_key_id(&self) -> String {
+        "".to_string()
+    }
+
+    fn secret_access_key(&self) -> String {
+        "".to_string()
+    }
+
+    fn region(&self) -> String {
+        self.region.clone()
+    }
+
+    fn aws_sdk_client(&self) -> Option<aws_config::SdkConfig> {
+        None
+    }
+
+    fn token(&self) -> &str {
+        ""
+    }
+
+    fn is_valid(&self) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn zones(&self) -> &Vec<String> {
+        &self.zones
+    }
+
+    fn credentials_environment_variables(&self) -> Vec<(&str, &str)> {
+        vec![]
+    }
+
+    fn tera_context_environment_variables(&self) -> Vec<(&str, &str)> {
+        vec![]
+    }
+
+    fn terraform_state_credentials(&self) -> &TerraformStateCredentials {
+        &self.terraform_state_credentials
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn get_event_details(&self, stage: Stage) -> EventDetails {
+        let context = self.context();
+        EventDetails::new(
+            None,
+            QoveryIdentifier::new(*context.organization_long_id()),
+            QoveryIdentifier::new(*context.cluster_long_id()),
+            context.execution_id().to_string(),
+            None,
+            stage,
+            self.to_transmitter(),
+        )
+    }
+
+    fn listeners(&self) -> &Listeners {
+        &self.listeners
+    }
+
+    fn add_listener(&mut self, listener: Listener) {
+        self.listeners.push(listener);
+    }
+
+    fn to_transmitter(&self) -> Transmitter {
+        Transmitter::CloudProvider(self.long_id, self.name.to_string())
+    }
+}
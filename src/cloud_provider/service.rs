@@ -22,7 +22,7 @@ use crate::logger::Logger;
 use crate::models::types::VersionsNumber;
 
 // todo: delete this useless trait
-pub trait Service {
+pub trait Service: Send + Sync {
     fn context(&self) -> &Context;
     fn service_type(&self) -> ServiceType;
     fn id(&self) -> &str;
@@ -35,6 +35,7 @@ pub trait Service {
             ServiceType::Database(_) => "databases",
             ServiceType::Router => "routers",
             ServiceType::Container => "containers",
+            ServiceType::Job => "jobs",
         };
 
         crate::fs::workspace_directory(
@@ -72,6 +73,7 @@ pub trait Service {
             ServiceType::Database(_) => ProgressScope::Database { id },
             ServiceType::Router => ProgressScope::Router { id },
             ServiceType::Container => ProgressScope::Container { id: *self.long_id() },
+            ServiceType::Job => ProgressScope::Job { id: *self.long_id() },
         }
     }
 
@@ -92,6 +94,10 @@ pub enum DatabaseType {
     MongoDB,
     MySQL,
     Redis,
+    OpenSearch,
+    ClickHouse,
+    RabbitMQ,
+    Kafka,
 }
 
 impl ToString for DatabaseType {
@@ -101,6 +107,10 @@ impl ToString for DatabaseType {
             DatabaseType::MongoDB => "MongoDB".to_string(),
             DatabaseType::MySQL => "MySQL".to_string(),
             DatabaseType::Redis => "Redis".to_string(),
+            DatabaseType::OpenSearch => "OpenSearch".to_string(),
+            DatabaseType::ClickHouse => "ClickHouse".to_string(),
+            DatabaseType::RabbitMQ => "RabbitMQ".to_string(),
+            DatabaseType::Kafka => "Kafka".to_string(),
         }
     }
 }
@@ -111,6 +121,7 @@ pub enum ServiceType {
     Database(DatabaseType),
     Router,
     Container,
+    Job,
 }
 
 impl ServiceType {
@@ -120,6 +131,7 @@ impl ServiceType {
             ServiceType::Database(db_type) => format!("{} database", db_type.to_string()),
             ServiceType::Router => "Router".to_string(),
             ServiceType::Container => "Container".to_string(),
+            ServiceType::Job => "Job".to_string(),
         }
     }
 }
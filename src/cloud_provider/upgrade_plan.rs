@@ -0,0 +1,616 @@
+use crate::cloud_provider::kubernetes::{KubernetesNodesType, KubernetesUpgradeStatus};
+use crate::cmd::helm::{to_command_error, Helm};
+use crate::cmd::kubectl::{kubectl_get_resource_yaml, kubernetes_get_all_pdbs};
+use crate::cmd::structs::PDB;
+use crate::errors::CommandError;
+use crate::models::types::VersionsNumber;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+const LAST_APPLIED_CONFIGURATION_ANNOTATION: &str = "kubectl.kubernetes.io/last-applied-configuration";
+
+/// API version no longer served from a Kubernetes minor version
+struct RemovedApi {
+    api_version: &'static str,
+    kind: &'static str,
+    /// resource listed with kubectl, served under another version by the current cluster
+    resource: &'static str,
+    removed_in_minor: u32,
+    replacement: &'static str,
+}
+
+// https://kubernetes.io/docs/reference/using-api/deprecation-guide/
+// objects only created by controllers (events, leases, endpoint slices...) are left aside
+const REMOVED_APIS: [RemovedApi; 25] = [
+    RemovedApi {
+        api_version: "admissionregistration.k8s.io/v1beta1",
+        kind: "MutatingWebhookConfiguration",
+        resource: "mutatingwebhookconfigurations.admissionregistration.k8s.io",
+        removed_in_minor: 22,
+        replacement: "admissionregistration.k8s.io/v1",
+    },
+    RemovedApi {
+        api_version: "admissionregistration.k8s.io/v1beta1",
+        kind: "ValidatingWebhookConfiguration",
+        resource: "validatingwebhookconfigurations.admissionregistration.k8s.io",
+        removed_in_minor: 22,
+        replacement: "admissionregistration.k8s.io/v1",
+    },
+    RemovedApi {
+        api_version: "apiextensions.k8s.io/v1beta1",
+        kind: "CustomResourceDefinition",
+        resource: "customresourcedefinitions.apiextensions.k8s.io",
+        removed_in_minor: 22,
+        replacement: "apiextensions.k8s.io/v1",
+    },
+    RemovedApi {
+        api_version: "apiregistration.k8s.io/v1beta1",
+        kind: "APIService",
+        resource: "apiservices.apiregistration.k8s.io",
+        removed_in_minor: 22,
+        replacement: "apiregistration.k8s.io/v1",
+    },
+    RemovedApi {
+        api_version: "extensions/v1beta1",
+        kind: "Ingress",
+        resource: "ingresses.networking.k8s.io",
+        removed_in_minor: 22,
+        replacement: "networking.k8s.io/v1",
+    },
+    RemovedApi {
+        api_version: "networking.k8s.io/v1beta1",
+        kind: "Ingress",
+        resource: "ingresses.networking.k8s.io",
+        removed_in_minor: 22,
+        replacement: "networking.k8s.io/v1",
+    },
+    RemovedApi {
+        api_version: "networking.k8s.io/v1beta1",
+        kind: "IngressClass",
+        resource: "ingressclasses.networking.k8s.io",
+        removed_in_minor: 22,
+        replacement: "networking.k8s.io/v1",
+    },
+    RemovedApi {
+        api_version: "rbac.authorization.k8s.io/v1beta1",
+        kind: "ClusterRole",
+        resource: "clusterroles.rbac.authorization.k8s.io",
+        removed_in_minor: 22,
+        replacement: "rbac.authorization.k8s.io/v1",
+    },
+    RemovedApi {
+        api_version: "rbac.authorization.k8s.io/v1beta1",
+        kind: "ClusterRoleBinding",
+        resource: "clusterrolebindings.rbac.authorization.k8s.io",
+        removed_in_minor: 22,
+        replacement: "rbac.authorization.k8s.io/v1",
+    },
+    RemovedApi {
+        api_version: "rbac.authorization.k8s.io/v1beta1",
+        kind: "Role",
+        resource: "roles.rbac.authorization.k8s.io",
+        removed_in_minor: 22,
+        replacement: "rbac.authorization.k8s.io/v1",
+    },
+    RemovedApi {
+        api_version: "rbac.authorization.k8s.io/v1beta1",
+        kind: "RoleBinding",
+        resource: "rolebindings.rbac.authorization.k8s.io",
+        removed_in_minor: 22,
+        replacement: "rbac.authorization.k8s.io/v1",
+    },
+    RemovedApi {
+        api_version: "scheduling.k8s.io/v1beta1",
+        kind: "PriorityClass",
+        resource: "priorityclasses.scheduling.k8s.io",
+        removed_in_minor: 22,
+        replacement: "scheduling.k8s.io/v1",
+    },
+    RemovedApi {
+        api_version: "storage.k8s.io/v1beta1",
+        kind: "CSIDriver",
+        resource: "csidrivers.storage.k8s.io",
+        removed_in_minor: 22,
+        replacement: "storage.k8s.io/v1",
+    },
+    RemovedApi {
+        api_version: "storage.k8s.io/v1beta1",
+        kind: "StorageClass",
+        resource: "storageclasses.storage.k8s.io",
+        removed_in_minor: 22,
+        replacement: "storage.k8s.io/v1",
+    },
+    RemovedApi {
+        api_version: "batch/v1beta1",
+        kind: "CronJob",
+        resource: "cronjobs.batch",
+        removed_in_minor: 25,
+        replacement: "batch/v1",
+    },
+    RemovedApi {
+        api_version: "autoscaling/v2beta1",
+        kind: "HorizontalPodAutoscaler",
+        resource: "horizontalpodautoscalers.autoscaling",
+        removed_in_minor: 25,
+        replacement: "autoscaling/v2",
+    },
+    RemovedApi {
+        api_version: "policy/v1beta1",
+        kind: "PodDisruptionBudget",
+        resource: "poddisruptionbudgets.policy",
+        removed_in_minor: 25,
+        replacement: "policy/v1",
+    },
+    RemovedApi {
+        api_version: "policy/v1beta1",
+        kind: "PodSecurityPolicy",
+        resource: "podsecuritypolicies.policy",
+        removed_in_minor: 25,
+        replacement: "Pod Security Admission",
+    },
+    RemovedApi {
+        api_version: "node.k8s.io/v1beta1",
+        kind: "RuntimeClass",
+        resource: "runtimeclasses.node.k8s.io",
+        removed_in_minor: 25,
+        replacement: "node.k8s.io/v1",
+    },
+    RemovedApi {
+        api_version: "autoscaling/v2beta2",
+        kind: "HorizontalPodAutoscaler",
+        resource: "horizontalpodautoscalers.autoscaling",
+        removed_in_minor: 26,
+        replacement: "autoscaling/v2",
+    },
+    RemovedApi {
+        api_version: "flowcontrol.apiserver.k8s.io/v1beta1",
+        kind: "FlowSchema",
+        resource: "flowschemas.flowcontrol.apiserver.k8s.io",
+        removed_in_minor: 26,
+        replacement: "flowcontrol.apiserver.k8s.io/v1beta3",
+    },
+    RemovedApi {
+        api_version: "flowcontrol.apiserver.k8s.io/v1beta1",
+        kind: "PriorityLevelConfiguration",
+        resource: "prioritylevelconfigurations.flowcontrol.apiserver.k8s.io",
+        removed_in_minor: 26,
+        replacement: "flowcontrol.apiserver.k8s.io/v1beta3",
+    },
+    RemovedApi {
+        api_version: "storage.k8s.io/v1beta1",
+        kind: "CSIStorageCapacity",
+        resource: "csistoragecapacities.storage.k8s.io",
+        removed_in_minor: 27,
+        replacement: "storage.k8s.io/v1",
+    },
+    RemovedApi {
+        api_version: "flowcontrol.apiserver.k8s.io/v1beta2",
+        kind: "FlowSchema",
+        resource: "flowschemas.flowcontrol.apiserver.k8s.io",
+        removed_in_minor: 29,
+        replacement: "flowcontrol.apiserver.k8s.io/v1",
+    },
+    RemovedApi {
+        api_version: "flowcontrol.apiserver.k8s.io/v1beta2",
+        kind: "PriorityLevelConfiguration",
+        resource: "prioritylevelconfigurations.flowcontrol.apiserver.k8s.io",
+        removed_in_minor: 29,
+        replacement: "flowcontrol.apiserver.k8s.io/v1",
+    },
+];
+
+/// Item preventing the cluster to be upgraded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpgradeBlocker {
+    pub kind: String,
+    pub namespace: Option<String>,
+    pub name: String,
+    pub reason: String,
+}
+
+impl Display for UpgradeBlocker {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.namespace {
+            Some(namespace) => write!(f, "{} {}/{}: {}", self.kind, namespace, self.name, self.reason),
+            None => write!(f, "{} {}: {}", self.kind, self.name, self.reason),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UpgradeStage {
+    Masters {
+        version: String,
+    },
+    /// all the workers at once, when the cloud provider does not expose node groups
+    Workers {
+        version: String,
+    },
+    NodeGroup {
+        name: String,
+        version: String,
+    },
+}
+
+impl Display for UpgradeStage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UpgradeStage::Masters { version } => write!(f, "upgrade masters to {}", version),
+            UpgradeStage::Workers { version } => write!(f, "upgrade workers to {}", version),
+            UpgradeStage::NodeGroup { name, version } => write!(f, "upgrade node group `{}` to {}", name, version),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpgradePlan {
+    pub requested_version: String,
+    pub stages: Vec<UpgradeStage>,
+    pub blockers: Vec<UpgradeBlocker>,
+}
+
+impl UpgradePlan {
+    pub fn is_blocked(&self) -> bool {
+        !self.blockers.is_empty()
+    }
+
+    pub fn report(&self) -> String {
+        let mut report = format!("Kubernetes upgrade plan to {}:", self.requested_version);
+        for (index, stage) in self.stages.iter().enumerate() {
+            report.push_str(&format!("\n  {}. {}", index + 1, stage));
+        }
+        if self.is_blocked() {
+            report.push_str(&format!("\n{} item(s) blocking the upgrade:", self.blockers.len()));
+            for blocker in &self.blockers {
+                report.push_str(&format!("\n  - {}", blocker));
+            }
+        }
+
+        report
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ObjectMetadata {
+    #[serde(default)]
+    name: String,
+    namespace: Option<String>,
+    #[serde(default)]
+    annotations: std::collections::BTreeMap<String, String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Object {
+    #[serde(default)]
+    api_version: String,
+    #[serde(default)]
+    kind: String,
+    metadata: Option<ObjectMetadata>,
+}
+
+#[derive(Deserialize)]
+struct ObjectList {
+    #[serde(default)]
+    items: Vec<Object>,
+}
+
+fn minor_version(version: &VersionsNumber) -> Option<u32> {
+    version.minor.as_deref().and_then(|minor| minor.parse::<u32>().ok())
+}
+
+/// APIs removed after the current minor version, up to the requested one
+fn removed_apis_between(current_minor: u32, requested_minor: u32) -> Vec<&'static RemovedApi> {
+    REMOVED_APIS
+        .iter()
+        .filter(|api| api.removed_in_minor > current_minor && api.removed_in_minor <= requested_minor)
+        .collect()
+}
+
+fn find_removed_api<'a>(apis: &[&'a RemovedApi], api_version: &str, kind: &str) -> Option<&'a RemovedApi> {
+    apis.iter()
+        .find(|api| api.api_version == api_version && api.kind == kind)
+        .copied()
+}
+
+fn removed_api_blocker(api: &RemovedApi, metadata: &ObjectMetadata, source: &str) -> UpgradeBlocker {
+    UpgradeBlocker {
+        kind: api.kind.to_string(),
+        namespace: metadata.namespace.clone(),
+        name: metadata.name.clone(),
+        reason: format!(
+            "{} uses {} removed in Kubernetes 1.{}, migrate to {}",
+            source, api.api_version, api.removed_in_minor, api.replacement
+        ),
+    }
+}
+
+/// Objects of a helm release manifest still declared with a removed API
+fn removed_apis_in_manifest(manifest: &str, apis: &[&RemovedApi], release_name: &str) -> Vec<UpgradeBlocker> {
+    serde_yaml::Deserializer::from_str(manifest)
+        .filter_map(|document| Object::deserialize(document).ok())
+        .filter_map(|object| {
+            let api = find_removed_api(apis, &object.api_version, &object.kind)?;
+            let metadata = object.metadata?;
+            Some(removed_api_blocker(api, &metadata, &format!("helm release `{}`", release_name)))
+        })
+        .collect()
+}
+
+/// Objects last applied with kubectl using a removed API, the cluster serving them under the current version
+fn removed_apis_in_live_objects(objects: &str, apis: &[&RemovedApi]) -> Vec<UpgradeBlocker> {
+    let objects = match serde_yaml::from_str::<ObjectList>(objects) {
+        Ok(objects) => objects,
+        Err(_) => return vec![],
+    };
+
+    objects
+        .items
+        .into_iter()
+        .filter_map(|object| {
+            let metadata = object.metadata?;
+            let last_applied = metadata.annotations.get(LAST_APPLIED_CONFIGURATION_ANNOTATION)?;
+            let last_applied = serde_json::from_str::<Object>(last_applied).ok()?;
+            let api = find_removed_api(apis, &last_applied.api_version, &last_applied.kind)?;
+            Some(removed_api_blocker(api, &metadata, "last applied configuration"))
+        })
+        .collect()
+}
+
+/// Budgets never allowing any disruption would prevent nodes from being drained
+fn pod_disruption_budgets_blockers(pdbs: &PDB) -> Vec<UpgradeBlocker> {
+    pdbs.items
+        .iter()
+        .flatten()
+        .filter(|pdb| pdb.status.expected_pods > 0 && pdb.status.desired_healthy >= pdb.status.expected_pods)
+        .map(|pdb| UpgradeBlocker {
+            kind: "PodDisruptionBudget".to_string(),
+            namespace: Some(pdb.metadata.namespace.clone()),
+            name: pdb.metadata.name.clone(),
+            reason: format!(
+                "requires {} healthy pods out of {}, nodes cannot be drained",
+                pdb.status.desired_healthy, pdb.status.expected_pods
+            ),
+        })
+        .collect()
+}
+
+fn upgrade_stages(upgrade_status: &KubernetesUpgradeStatus, node_groups: &[String]) -> Vec<UpgradeStage> {
+    let version = upgrade_status.requested_version.to_string();
+    let mut stages = vec![];
+
+    if let Some(KubernetesNodesType::Masters) = upgrade_status.required_upgrade_on {
+        stages.push(UpgradeStage::Masters {
+            version: version.clone(),
+        });
+    }
+    if upgrade_status.required_upgrade_on.is_some() {
+        match node_groups.is_empty() {
+            true => stages.push(UpgradeStage::Workers { version }),
+            false => stages.extend(node_groups.iter().map(|name| UpgradeStage::NodeGroup {
+                name: name.to_string(),
+                version: version.clone(),
+            })),
+        }
+    }
+
+    stages
+}
+
+/// Plan the stages of the upgrade, and report what would prevent it to succeed:
+/// objects declared with APIs removed in the requested version, and disruption budgets preventing nodes to be drained.
+///
+/// # Arguments
+///
+/// * `node_groups` - node groups upgraded one at a time, empty when all the workers are upgraded at once
+pub fn plan_kubernetes_upgrade<P>(
+    kubernetes_config: P,
+    envs: Vec<(&str, &str)>,
+    upgrade_status: &KubernetesUpgradeStatus,
+    node_groups: &[String],
+) -> Result<UpgradePlan, CommandError>
+where
+    P: AsRef<Path>,
+{
+    let mut blockers = vec![];
+
+    let current_minor = minor_version(&upgrade_status.deployed_masters_version);
+    let requested_minor = minor_version(&upgrade_status.requested_version);
+    if let (Some(current_minor), Some(requested_minor)) = (current_minor, requested_minor) {
+        let apis = removed_apis_between(current_minor, requested_minor);
+
+        // a resource not served by the cluster has no object to migrate
+        let resources = apis.iter().map(|api| api.resource).collect::<HashSet<_>>();
+        for resource in resources {
+            if let Ok(objects) = kubectl_get_resource_yaml(&kubernetes_config, envs.clone(), resource, None) {
+                blockers.extend(removed_apis_in_live_objects(&objects, &apis));
+            }
+        }
+
+        if !apis.is_empty() {
+            let helm = Helm::new(&kubernetes_config, &envs).map_err(to_command_error)?;
+            for release in helm.list_release(None, &envs).map_err(to_command_error)? {
+                let manifest = helm
+                    .get_manifest(&release.name, &release.namespace, &envs)
+                    .map_err(to_command_error)?;
+                blockers.extend(removed_apis_in_manifest(&manifest, &apis, &release.name));
+            }
+        }
+    }
+
+    let pdbs = kubernetes_get_all_pdbs(&kubernetes_config, envs.clone(), None)?;
+    blockers.extend(pod_disruption_budgets_blockers(&pdbs));
+
+    Ok(UpgradePlan {
+        requested_version: upgrade_status.requested_version.to_string(),
+        stages: upgrade_stages(upgrade_status, node_groups),
+        blockers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cloud_provider::kubernetes::{KubernetesNodesType, KubernetesUpgradeStatus};
+    use crate::cloud_provider::upgrade_plan::{
+        pod_disruption_budgets_blockers, removed_apis_between, removed_apis_in_live_objects, removed_apis_in_manifest,
+        upgrade_stages, UpgradeStage,
+    };
+    use crate::cmd::structs::{PDBItem, PDBMetadata, PDBStatus, PDB};
+    use crate::models::types::VersionsNumber;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_removed_apis_between() {
+        assert!(removed_apis_between(22, 22).is_empty());
+        assert!(removed_apis_between(21, 22)
+            .iter()
+            .all(|api| api.removed_in_minor == 22));
+        assert!(removed_apis_between(24, 26)
+            .iter()
+            .any(|api| api.api_version == "autoscaling/v2beta2"));
+        assert!(!removed_apis_between(25, 26)
+            .iter()
+            .any(|api| api.api_version == "batch/v1beta1"));
+    }
+
+    #[test]
+    fn test_removed_apis_in_manifest() {
+        // setup:
+        let manifest = r#"---
+# Source: app/templates/horizontal_autoscaler.yaml
+apiVersion: autoscaling/v2beta2
+kind: HorizontalPodAutoscaler
+metadata:
+  name: app-z1234
+  namespace: z5678
+---
+apiVersion: batch/v1beta1
+kind: CronJob
+metadata:
+  name: cleanup
+  namespace: z5678
+---
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: app-z1234
+"#;
+
+        // execute:
+        let blockers = removed_apis_in_manifest(manifest, &removed_apis_between(24, 26), "app-z1234");
+
+        // verify:
+        assert_eq!(blockers.len(), 2);
+        assert_eq!(blockers[0].kind, "HorizontalPodAutoscaler");
+        assert_eq!(blockers[0].namespace.as_deref(), Some("z5678"));
+        assert_eq!(blockers[1].name, "cleanup");
+        assert!(blockers[1].reason.contains("batch/v1"));
+    }
+
+    #[test]
+    fn test_removed_apis_in_live_objects() {
+        // setup:
+        let objects = r#"
+apiVersion: v1
+kind: List
+items:
+  - apiVersion: policy/v1
+    kind: PodDisruptionBudget
+    metadata:
+      name: legacy
+      namespace: default
+      annotations:
+        kubectl.kubernetes.io/last-applied-configuration: '{"apiVersion":"policy/v1beta1","kind":"PodDisruptionBudget"}'
+  - apiVersion: policy/v1
+    kind: PodDisruptionBudget
+    metadata:
+      name: up-to-date
+      namespace: default
+      annotations:
+        kubectl.kubernetes.io/last-applied-configuration: '{"apiVersion":"policy/v1","kind":"PodDisruptionBudget"}'
+  - apiVersion: policy/v1
+    kind: PodDisruptionBudget
+    metadata:
+      name: from-helm
+      namespace: default
+"#;
+
+        // execute:
+        let blockers = removed_apis_in_live_objects(objects, &removed_apis_between(24, 25));
+
+        // verify:
+        assert_eq!(blockers.len(), 1);
+        assert_eq!(blockers[0].name, "legacy");
+    }
+
+    #[test]
+    fn test_pod_disruption_budgets_blockers() {
+        // setup:
+        let pdb = |name: &str, desired_healthy, expected_pods| PDBItem {
+            metadata: PDBMetadata {
+                name: name.to_string(),
+                namespace: "default".to_string(),
+            },
+            status: PDBStatus {
+                current_healthy: expected_pods,
+                desired_healthy,
+                expected_pods,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let pdbs = PDB {
+            items: Some(vec![pdb("strict", 2, 2), pdb("lenient", 1, 2), pdb("empty", 0, 0)]),
+            ..Default::default()
+        };
+
+        // execute:
+        let blockers = pod_disruption_budgets_blockers(&pdbs);
+
+        // verify:
+        assert_eq!(blockers.len(), 1);
+        assert_eq!(blockers[0].name, "strict");
+    }
+
+    #[test]
+    fn test_upgrade_stages() {
+        // setup:
+        let status = |required_upgrade_on| KubernetesUpgradeStatus {
+            required_upgrade_on,
+            requested_version: VersionsNumber::from_str("1.23").unwrap(),
+            deployed_masters_version: VersionsNumber::from_str("1.22").unwrap(),
+            deployed_workers_version: VersionsNumber::from_str("1.22").unwrap(),
+            older_masters_version_detected: false,
+            older_workers_version_detected: false,
+        };
+        let node_groups = vec!["default".to_string(), "gpu".to_string()];
+
+        // execute & verify:
+        assert_eq!(
+            upgrade_stages(&status(Some(KubernetesNodesType::Masters)), &node_groups),
+            vec![
+                UpgradeStage::Masters {
+                    version: "1.23".to_string()
+                },
+                UpgradeStage::NodeGroup {
+                    name: "default".to_string(),
+                    version: "1.23".to_string()
+                },
+                UpgradeStage::NodeGroup {
+                    name: "gpu".to_string(),
+                    version: "1.23".to_string()
+                },
+            ]
+        );
+        assert_eq!(
+            upgrade_stages(&status(Some(KubernetesNodesType::Workers)), &[]),
+            vec![UpgradeStage::Workers {
+                version: "1.23".to_string()
+            }]
+        );
+        assert!(upgrade_stages(&status(None), &node_groups).is_empty());
+    }
+}
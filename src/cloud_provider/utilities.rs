@@ -118,6 +118,11 @@ pub fn managed_db_name_sanitizer(max_size: usize, prefix: &str, name: &str) -> S
     new_name
 }
 
+/// AWS OpenSearch domain names must start with a letter and are limited to 28 characters
+pub fn opensearch_domain_name(fqdn_id: &str) -> String {
+    managed_db_name_sanitizer(28, "os", &fqdn_id.to_lowercase())
+}
+
 #[derive(PartialEq, Eq, Debug)]
 pub enum TcpCheckErrors {
     DomainNotResolvable,
@@ -5,6 +5,7 @@ use std::path::Path;
 use std::process::{Child, Command, ExitStatus, Stdio};
 
 use crate::cmd::command::CommandError::{ExecutionError, ExitStatusError, Killed, TimeoutError};
+use crate::metrics::{StepTimer, TimingKind};
 
 use itertools::Itertools;
 use std::time::{Duration, Instant};
@@ -138,6 +139,27 @@ impl QoveryCommand {
     pub fn set_current_dir<P: AsRef<Path>>(&mut self, root_dir: P) {
         self.command.current_dir(root_dir);
     }
+
+    /// Binary and sub command, i.e `helm upgrade`. Other arguments are left out, they can contain secrets
+    /// (i.e scripts given to `sh -c`)
+    fn timing_name(&self) -> String {
+        let binary = Path::new(self.command.get_program())
+            .file_name()
+            .and_then(OsStr::to_str)
+            .unwrap_or_default()
+            .to_string();
+        let sub_command = self
+            .command
+            .get_args()
+            .filter_map(OsStr::to_str)
+            .find(|arg| !arg.starts_with('-'))
+            .filter(|arg| arg.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+
+        match sub_command {
+            Some(sub_command) => format!("{} {}", binary, sub_command),
+            None => binary,
+        }
+    }
 }
 
 impl ExecutableCommand for QoveryCommand {
@@ -186,6 +208,7 @@ impl ExecutableCommand for QoveryCommand {
         STDERR: FnMut(String),
     {
         info!("command: {:?}", self.command);
+        let _timer = StepTimer::start(TimingKind::Command, self.timing_name());
         let mut cmd_handle = self
             .command
             .stdout(Stdio::piped())
@@ -384,6 +407,16 @@ mod tests {
         assert!(ret.contains("GNU"))
     }
 
+    #[test]
+    fn test_timing_name() {
+        assert_eq!(
+            QoveryCommand::new("/usr/bin/helm", &["upgrade", "--install", "release"], &[]).timing_name(),
+            "helm upgrade"
+        );
+        assert_eq!(QoveryCommand::new("sh", &["-c", "echo secret"], &[]).timing_name(), "sh");
+        assert_eq!(QoveryCommand::new("ls", &[], &[]).timing_name(), "ls");
+    }
+
     #[test]
     fn test_error() {
         let mut cmd = QoveryCommand::new("false", &[], &[]);
@@ -5,7 +5,7 @@ use tracing::{error, info};
 
 use crate::cloud_provider::helm::ChartInfo;
 use crate::cmd::command::{ExecutableCommand, QoveryCommand};
-use crate::cmd::helm::HelmCommand::{LIST, ROLLBACK, STATUS, UNINSTALL, UPGRADE};
+use crate::cmd::helm::HelmCommand::{LIST, MANIFEST, ROLLBACK, STATUS, UNINSTALL, UPGRADE};
 use crate::cmd::helm::HelmError::{CannotRollback, CmdError, InvalidKubeConfig, ReleaseDoesNotExist};
 use crate::cmd::structs::{HelmChart, HelmChartVersions, HelmListItem};
 use crate::errors::{CommandError, EngineError, ErrorMessageVerbosity};
@@ -69,6 +69,7 @@ pub enum HelmCommand {
     UPGRADE,
     UNINSTALL,
     LIST,
+    MANIFEST,
     DIFF,
     TEMPLATE,
 }
@@ -164,11 +165,37 @@ impl Helm {
             return Err(CannotRollback(chart.name.clone()));
         }
 
+        self.rollback_release(chart, None, envs)
+    }
+
+    /// Rollback the release to the given revision, even if it is not the previous one
+    pub fn rollback_to_revision(
+        &self,
+        chart: &ChartInfo,
+        revision: u64,
+        envs: &[(&str, &str)],
+    ) -> Result<(), HelmError> {
+        if revision == 0 {
+            return Err(CannotRollback(chart.name.clone()));
+        }
+
+        self.rollback_release(chart, Some(revision), envs)
+    }
+
+    fn rollback_release(
+        &self,
+        chart: &ChartInfo,
+        revision: Option<u64>,
+        envs: &[(&str, &str)],
+    ) -> Result<(), HelmError> {
         let timeout = format!("{}s", &chart.timeout_in_seconds);
         let namespace = chart.get_namespace_string();
-        let args = vec![
-            "rollback",
-            &chart.name,
+        let revision = revision.map(|r| r.to_string());
+        let mut args = vec!["rollback", &chart.name];
+        if let Some(revision) = &revision {
+            args.push(revision);
+        }
+        args.extend_from_slice(&[
             "--kubeconfig",
             self.kubernetes_config.to_str().unwrap_or_default(),
             "--namespace",
@@ -180,7 +207,7 @@ impl Helm {
             "--cleanup-on-fail",
             "--force",
             "--wait",
-        ];
+        ]);
 
         let mut stderr = String::new();
         match helm_exec_with_output(&args, &self.get_all_envs(envs), &mut |_| {}, &mut |line| stderr.push_str(&line)) {
@@ -328,6 +355,42 @@ impl Helm {
         }
     }
 
+    /// Returns the manifest of the deployed release, its resources separated by `---`
+    ///
+    /// # Arguments
+    ///
+    /// * `release_name` - name of the helm release
+    /// * `namespace` - namespace of the helm release
+    /// * `envs` - environment variables required for kubernetes connection
+    pub fn get_manifest(
+        &self,
+        release_name: &str,
+        namespace: &str,
+        envs: &[(&str, &str)],
+    ) -> Result<String, HelmError> {
+        let args = vec![
+            "get",
+            "manifest",
+            release_name,
+            "--kubeconfig",
+            self.kubernetes_config.to_str().unwrap_or_default(),
+            "--namespace",
+            namespace,
+        ];
+
+        let mut output_string: Vec<String> = Vec::with_capacity(50);
+        if let Err(cmd_error) = helm_exec_with_output(
+            &args,
+            &self.get_all_envs(envs),
+            &mut |line| output_string.push(line),
+            &mut |line| error!("{}", line),
+        ) {
+            return Err(CmdError(release_name.to_string(), MANIFEST, cmd_error));
+        }
+
+        Ok(output_string.join("\n"))
+    }
+
     pub fn get_chart_version(
         &self,
         chart_name: &str,
@@ -348,13 +411,15 @@ impl Helm {
         Ok(None)
     }
 
-    pub fn upgrade_diff(&self, chart: &ChartInfo, envs: &[(&str, &str)]) -> Result<(), HelmError> {
+    /// Returns the diff output, one line per element
+    pub fn upgrade_diff(&self, chart: &ChartInfo, envs: &[(&str, &str)]) -> Result<Vec<String>, HelmError> {
         let mut args_string: Vec<String> = vec![
             "diff".to_string(),
             "upgrade".to_string(),
             "--kubeconfig".to_string(),
             self.kubernetes_config.to_str().unwrap_or_default().to_string(),
             "--install".to_string(),
+            "--no-color".to_string(),
             "--namespace".to_string(),
             chart.get_namespace_string(),
         ];
@@ -399,12 +464,14 @@ impl Helm {
         args_string.push(chart.name.clone());
         args_string.push(chart.path.clone());
 
+        let mut stdout_lines = vec![];
         let mut stderr_msg = String::new();
         let helm_ret = helm_exec_with_output(
             &args_string.iter().map(|x| x.as_str()).collect::<Vec<&str>>(),
             &self.get_all_envs(envs),
             &mut |line| {
                 info!("{}", line);
+                stdout_lines.push(line);
             },
             &mut |line| {
                 stderr_msg.push_str(&line);
@@ -414,7 +481,7 @@ impl Helm {
 
         match helm_ret {
             // Ok is ok
-            Ok(_) => Ok(()),
+            Ok(_) => Ok(stdout_lines),
             Err(err) => {
                 error!("Helm error: {:?}", err);
                 Err(CmdError(
@@ -722,6 +789,7 @@ mod tests {
     use crate::cloud_provider::helm::{ChartInfo, ChartSetValue};
     use crate::cmd::command::{ExecutableCommand, QoveryCommand};
     use crate::cmd::helm::{helm_exec_with_output, Helm, HelmError};
+    use crate::metrics::ExecutionTimings;
     use semver::Version;
     use std::sync::{Arc, Barrier};
     use std::thread;
@@ -825,7 +893,7 @@ mod tests {
         let HelmTestCtx { ref helm, ref charts } = HelmTestCtx::new("test-upgrade-diff");
 
         let ret = helm.upgrade_diff(&charts[0], &[]);
-        assert!(matches!(ret, Ok(())));
+        assert!(matches!(ret, Ok(_)));
     }
 
     #[test]
@@ -853,6 +921,27 @@ mod tests {
         assert!(matches!(ret, Ok(())));
     }
 
+    #[test]
+    fn test_rollback_to_revision() {
+        let HelmTestCtx { ref helm, ref charts } = HelmTestCtx::new("test-rollback-to-revision");
+
+        // install it and upgrade it twice
+        for _ in 0..3 {
+            let ret = helm.upgrade(&charts[0], &[]);
+            assert!(matches!(ret, Ok(())));
+        }
+        assert_eq!(helm.check_release_exist(&charts[0], &[]).unwrap().version, 3);
+
+        // Rollback to the first revision creates a new one
+        let ret = helm.rollback_to_revision(&charts[0], 1, &[]);
+        assert!(matches!(ret, Ok(())));
+        assert_eq!(helm.check_release_exist(&charts[0], &[]).unwrap().version, 4);
+
+        // Revision 0 does not exist
+        let ret = helm.rollback_to_revision(&charts[0], 0, &[]);
+        assert!(matches!(ret, Err(HelmError::CannotRollback(_))));
+    }
+
     #[test]
     fn test_upgrade() {
         let HelmTestCtx { ref helm, ref charts } = HelmTestCtx::new("test-upgrade");
@@ -908,7 +997,11 @@ mod tests {
         thread::spawn({
             let barrier = barrier.clone();
             let chart_name = charts[0].name.clone();
+            let current_span = tracing::Span::current();
+            let current_timings = ExecutionTimings::current();
             move || {
+                let _span = current_span.enter();
+                let _timings = current_timings.as_ref().map(|timings| timings.enter());
                 barrier.wait();
                 thread::sleep(Duration::from_millis(5000));
                 let mut cmd = QoveryCommand::new("pkill", &["-9", "-f", &format!("helm.*{}", chart_name)], &[]);
@@ -959,7 +1052,11 @@ mod tests {
         thread::spawn({
             let barrier = barrier.clone();
             let chart_name = charts[0].name.clone();
+            let current_span = tracing::Span::current();
+            let current_timings = ExecutionTimings::current();
             move || {
+                let _span = current_span.enter();
+                let _timings = current_timings.as_ref().map(|timings| timings.enter());
                 barrier.wait();
                 thread::sleep(Duration::from_millis(5000));
                 let mut cmd = QoveryCommand::new("pkill", &["-9", "-f", &format!("helm.*{}", chart_name)], &[]);
@@ -8,7 +8,7 @@ use retry::OperationResult;
 use serde::de::DeserializeOwned;
 
 use crate::cloud_provider::digitalocean::models::svc::DoLoadBalancer;
-use crate::cloud_provider::metrics::KubernetesApiMetrics;
+use crate::cloud_provider::metrics::{KubernetesApiMetrics, KubernetesApiMetricsResources};
 use crate::cmd::command::{ExecutableCommand, QoveryCommand};
 use crate::cmd::structs::{
     Configmap, Daemonset, Item, KubernetesDeployment, KubernetesEvent, KubernetesIngress,
@@ -863,6 +863,52 @@ where
     kubectl_exec::<P, KubernetesApiMetrics>(vec!["get", "--raw", api_url.as_str()], kubernetes_config, envs)
 }
 
+/// List the custom metrics served by the custom metrics adapter, whether pods exposing them are running or not
+///
+/// # Arguments
+///
+/// * `kubernetes_config` - kubernetes config path
+/// * `envs` - environment variables required for kubernetes connection
+pub fn kubectl_exec_api_custom_metrics_resources<P>(
+    kubernetes_config: P,
+    envs: Vec<(&str, &str)>,
+) -> Result<KubernetesApiMetricsResources, CommandError>
+where
+    P: AsRef<Path>,
+{
+    kubectl_exec::<P, KubernetesApiMetricsResources>(
+        vec!["get", "--raw", "/apis/custom.metrics.k8s.io/v1beta1"],
+        kubernetes_config,
+        envs,
+    )
+}
+
+/// Get external metrics values
+///
+/// # Arguments
+///
+/// * `kubernetes_config` - kubernetes config path
+/// * `envs` - environment variables required for kubernetes connection
+/// * `namespace` - kubernetes namespace
+/// * `metric_name` - metric name
+/// * `label_selector` - labels of the metric, empty to select all of them
+pub fn kubectl_exec_api_external_metrics<P>(
+    kubernetes_config: P,
+    envs: Vec<(&str, &str)>,
+    namespace: &str,
+    metric_name: &str,
+    label_selector: &str,
+) -> Result<KubernetesApiMetrics, CommandError>
+where
+    P: AsRef<Path>,
+{
+    let mut api_url = format!("/apis/external.metrics.k8s.io/v1beta1/namespaces/{}/{}", namespace, metric_name);
+    if !label_selector.is_empty() {
+        api_url = format!("{}?labelSelector={}", api_url, urlencoding::encode(label_selector));
+    }
+    kubectl_exec::<P, KubernetesApiMetrics>(vec!["get", "--raw", api_url.as_str()], kubernetes_config, envs)
+}
+
 /// scale down replicas by name
 ///
 /// # Arguments
@@ -1,7 +1,7 @@
 use crate::cmd::structs::KubernetesPodStatusReason::Unknown;
 use semver::Version;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(Deserialize, Clone, Eq, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -304,9 +304,18 @@ pub struct KubernetesJobStatus {
 #[derive(Deserialize, Clone, Eq, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct KubernetesNode {
+    #[serde(default)]
+    pub metadata: KubernetesNodeMetadata,
     pub status: KubernetesNodeStatus,
 }
 
+#[derive(Deserialize, Clone, Eq, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct KubernetesNodeMetadata {
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+}
+
 #[derive(Deserialize, Clone, Eq, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct KubernetesNodeStatus {
@@ -16,7 +16,8 @@ bitflags! {
     struct TerraformAction: u32 {
         const INIT = 0b00000001;
         const VALIDATE = 0b00000010;
-        // const PLAN = 0b00000100; Not needed, apply and destroy should call plan on their end
+        // apply and destroy already call plan on their end, only needed to plan without applying
+        const PLAN = 0b00000100;
         const APPLY = 0b00001000;
         const DESTROY = 0b00010000;
         const STATE_LIST = 0b00100000;
@@ -883,6 +884,10 @@ fn terraform_run(actions: TerraformAction, root_dir: &str, dry_run: bool) -> Res
         output.extend(terraform_state_list(root_dir)?);
     }
 
+    if actions.contains(TerraformAction::PLAN) {
+        output.extend(terraform_plan(root_dir)?);
+    }
+
     if actions.contains(TerraformAction::APPLY) && !dry_run {
         output.extend(terraform_apply(root_dir)?);
     }
@@ -903,6 +908,15 @@ pub fn terraform_init_validate_plan_apply(root_dir: &str, dry_run: bool) -> Resu
     )
 }
 
+pub fn terraform_init_validate_plan(root_dir: &str) -> Result<Vec<String>, TerraformError> {
+    // Terraform init, validate and plan, nothing is applied
+    terraform_run(
+        TerraformAction::INIT | TerraformAction::VALIDATE | TerraformAction::PLAN,
+        root_dir,
+        false,
+    )
+}
+
 pub fn terraform_init_validate(root_dir: &str) -> Result<Vec<String>, TerraformError> {
     // Terraform init & validate
     terraform_run(TerraformAction::INIT | TerraformAction::VALIDATE, root_dir, false)
@@ -0,0 +1,681 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use reqwest::header::{ACCEPT, LINK, WWW_AUTHENTICATE};
+use reqwest::{Method, StatusCode};
+use serde::Deserialize;
+use url::Url;
+use uuid::Uuid;
+
+use crate::build_platform::{Credentials, Image};
+use crate::container_registry::errors::ContainerRegistryError;
+use crate::container_registry::{ContainerRegistry, ContainerRegistryInfo, Kind};
+use crate::io_models::context::Context;
+
+const MANIFEST_MEDIA_TYPES: [&str; 4] = [
+    "application/vnd.oci.image.manifest.v1+json",
+    "application/vnd.oci.image.index.v1+json",
+    "application/vnd.docker.distribution.manifest.v2+json",
+    "application/vnd.docker.distribution.manifest.list.v2+json",
+];
+const CONTENT_DIGEST_HEADER: &str = "Docker-Content-Digest";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GenericCrFlavor {
+    /// Any registry implementing the OCI distribution API (registry:2, GitLab, GHCR, ...),
+    /// repositories are created when the first image is pushed into them
+    OciDistribution,
+    /// Harbor, images are stored in projects which must be created before pushing into them
+    Harbor,
+}
+
+/// Container registry implementing the OCI distribution API. Images and retention are managed through the
+/// registry v2 HTTP API, only Harbor projects need a registry specific API.
+pub struct GenericCr {
+    context: Context,
+    id: String,
+    long_id: Uuid,
+    name: String,
+    flavor: GenericCrFlavor,
+    // Images are pushed under this namespace, i.e: the Harbor project or the GitLab group
+    namespace: Option<String>,
+    client: RegistryClient,
+    registry_info: ContainerRegistryInfo,
+}
+
+impl GenericCr {
+    pub fn new(
+        context: Context,
+        id: &str,
+        long_id: Uuid,
+        name: &str,
+        registry_url: Url,
+        namespace: Option<&str>,
+        credentials: Option<Credentials>,
+        flavor: GenericCrFlavor,
+    ) -> Result<GenericCr, ContainerRegistryError> {
+        if flavor == GenericCrFlavor::Harbor && namespace.is_none() {
+            return Err(ContainerRegistryError::CannotCreateRegistry {
+                registry_name: name.to_string(),
+                raw_error_message: "A Harbor project is required to push images into".to_string(),
+            });
+        }
+
+        let mut endpoint = registry_url.clone();
+        if let Some(credentials) = &credentials {
+            let _ = endpoint.set_username(&credentials.login);
+            let _ = endpoint.set_password(Some(&urlencoding::encode(&credentials.password)));
+        }
+
+        let client = RegistryClient::new(registry_url, credentials.clone());
+        client.check_credentials()?;
+
+        // Docker is still used to push images built locally
+        if credentials.is_some() && context.docker.login(&endpoint).is_err() {
+            return Err(ContainerRegistryError::InvalidCredentials);
+        }
+
+        let image_namespace = namespace.map(str::to_string);
+        let repository_namespace = image_namespace.clone();
+        let registry_info = ContainerRegistryInfo {
+            endpoint,
+            registry_name: name.to_string(),
+            registry_docker_json_config: None,
+            get_image_name: Box::new(move |img_name| match &image_namespace {
+                Some(namespace) => format!("{}/{}", namespace, img_name),
+                None => img_name.to_string(),
+            }),
+            get_repository_name: Box::new(move |img_name| match &repository_namespace {
+                Some(namespace) => format!("{}/{}", namespace, img_name),
+                None => img_name.to_string(),
+            }),
+        };
+
+        Ok(GenericCr {
+            context,
+            id: id.to_string(),
+            long_id,
+            name: name.to_string(),
+            flavor,
+            namespace: namespace.map(str::to_string),
+            client,
+            registry_info,
+        })
+    }
+
+    fn create_harbor_project(&self, project: &str) -> Result<(), ContainerRegistryError> {
+        let body = serde_json::json!({ "project_name": project, "metadata": { "public": "false" } });
+        let response = self
+            .client
+            .send_api(Method::POST, "/api/v2.0/projects", |request| request.json(&body))
+            .map_err(|err| ContainerRegistryError::CannotCreateRepository {
+                registry_name: self.name.clone(),
+                repository_name: project.to_string(),
+                raw_error_message: err,
+            })?;
+
+        match response.status() {
+            StatusCode::CREATED | StatusCode::CONFLICT => Ok(()),
+            status => Err(ContainerRegistryError::CannotCreateRepository {
+                registry_name: self.name.clone(),
+                repository_name: project.to_string(),
+                raw_error_message: format!("Harbor API returned status `{}` when creating the project", status),
+            }),
+        }
+    }
+
+    fn delete_harbor_repository(&self, repository_name: &str) -> Result<(), ContainerRegistryError> {
+        let (project, repository) = repository_name.split_once('/').unwrap_or((repository_name, ""));
+        // Harbor expects the slashes of the repository name to be encoded twice
+        let path = format!(
+            "/api/v2.0/projects/{}/repositories/{}",
+            project,
+            urlencoding::encode(&urlencoding::encode(repository))
+        );
+        let response = self
+            .client
+            .send_api(Method::DELETE, &path, |request| request)
+            .map_err(|err| ContainerRegistryError::CannotDeleteRepository {
+                registry_name: self.name.clone(),
+                repository_name: repository_name.to_string(),
+                raw_error_message: err,
+            })?;
+
+        match response.status() {
+            status if status.is_success() => Ok(()),
+            StatusCode::NOT_FOUND => Ok(()),
+            status => Err(ContainerRegistryError::CannotDeleteRepository {
+                registry_name: self.name.clone(),
+                repository_name: repository_name.to_string(),
+                raw_error_message: format!("Harbor API returned status `{}` when deleting the repository", status),
+            }),
+        }
+    }
+
+    /// Delete the images of the repository which have been created before the retention time
+    fn delete_expired_images(&self, repository_name: &str, retention: Duration) -> Result<(), String> {
+        let expiration = Utc::now() - chrono::Duration::from_std(retention).map_err(|err| err.to_string())?;
+
+        for tag in self.client.list_tags(repository_name)? {
+            // multi-arch images do not have a creation date, they are kept
+            match self.client.image_created_at(repository_name, &tag)? {
+                Some(created_at) if created_at < expiration => {
+                    info!("Deleting expired image {}:{}", repository_name, tag);
+                    if let Some(digest) = self.client.manifest_digest(repository_name, &tag)? {
+                        self.client.delete_manifest(repository_name, &digest)?;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl ContainerRegistry for GenericCr {
+    fn context(&self) -> &Context {
+        &self.context
+    }
+
+    fn kind(&self) -> Kind {
+        Kind::GenericCr
+    }
+
+    fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    fn long_id(&self) -> &Uuid {
+        &self.long_id
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn registry_info(&self) -> &ContainerRegistryInfo {
+        &self.registry_info
+    }
+
+    fn create_registry(&self) -> Result<(), ContainerRegistryError> {
+        // The registry is provided by the user, only Harbor projects are created
+        match (&self.flavor, &self.namespace) {
+            (GenericCrFlavor::Harbor, Some(project)) => self.create_harbor_project(project),
+            _ => Ok(()),
+        }
+    }
+
+    fn create_repository(
+        &self,
+        repository_name: &str,
+        image_retention_time_in_seconds: u32,
+    ) -> Result<(), ContainerRegistryError> {
+        // Repositories are created on the flight when images are pushed, except for Harbor projects
+        if self.flavor == GenericCrFlavor::Harbor {
+            let project = repository_name.split('/').next().unwrap_or(repository_name);
+            self.create_harbor_project(project)?;
+        }
+
+        if image_retention_time_in_seconds == 0 {
+            return Ok(());
+        }
+
+        self.delete_expired_images(repository_name, Duration::from_secs(image_retention_time_in_seconds as u64))
+            .map_err(|err| ContainerRegistryError::CannotSetRepositoryLifecyclePolicy {
+                registry_name: self.name.clone(),
+                repository_name: repository_name.to_string(),
+                raw_error_message: err,
+            })
+    }
+
+    fn delete_repository(&self, repository_name: &str) -> Result<(), ContainerRegistryError> {
+        if self.flavor == GenericCrFlavor::Harbor {
+            return self.delete_harbor_repository(repository_name);
+        }
+
+        // The distribution API has no repository deletion, so all its images are deleted
+        let to_error = |err: String| ContainerRegistryError::CannotDeleteRepository {
+            registry_name: self.name.clone(),
+            repository_name: repository_name.to_string(),
+            raw_error_message: err,
+        };
+        let mut digests = HashSet::new();
+        for tag in self.client.list_tags(repository_name).map_err(to_error)? {
+            if let Some(digest) = self.client.manifest_digest(repository_name, &tag).map_err(to_error)? {
+                digests.insert(digest);
+            }
+        }
+
+        for digest in digests {
+            self.client
+                .delete_manifest(repository_name, &digest)
+                .map_err(to_error)?;
+        }
+
+        Ok(())
+    }
+
+    /// The image is deleted by digest, so all the tags pointing to it are deleted too
+    fn delete_image(&self, image: &Image) -> Result<(), ContainerRegistryError> {
+        let to_error = |err: String| ContainerRegistryError::CannotDeleteImage {
+            registry_name: self.name.clone(),
+            repository_name: image.repository_name().to_string(),
+            image_name: image.name(),
+            raw_error_message: err,
+        };
+
+        match self.client.manifest_digest(&image.name, &image.tag).map_err(to_error)? {
+            Some(digest) => self.client.delete_manifest(&image.name, &digest).map_err(to_error),
+            None => Ok(()),
+        }
+    }
+
+    fn does_image_exists(&self, image: &Image) -> bool {
+        matches!(self.client.manifest_digest(&image.name, &image.tag), Ok(Some(_)))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum AuthChallenge {
+    Basic,
+    Bearer {
+        realm: String,
+        service: Option<String>,
+        scope: Option<String>,
+    },
+}
+
+/// Parse the `WWW-Authenticate` header returned by the registry when a request is not authorized
+fn parse_auth_challenge(header: &str) -> Option<AuthChallenge> {
+    let (scheme, params) = header.trim().split_once(' ').unwrap_or((header.trim(), ""));
+    if scheme.eq_ignore_ascii_case("basic") {
+        return Some(AuthChallenge::Basic);
+    }
+    if !scheme.eq_ignore_ascii_case("bearer") {
+        return None;
+    }
+
+    // params are key="value" separated by commas, but values can contain commas (i.e: scope with several actions)
+    let param = |key: &str| -> Option<String> {
+        let start = params.find(&format!("{}=\"", key))? + key.len() + 2;
+        let end = params[start..].find('"')? + start;
+        Some(params[start..end].to_string())
+    };
+
+    Some(AuthChallenge::Bearer {
+        realm: param("realm")?,
+        service: param("service"),
+        scope: param("scope"),
+    })
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    token: Option<String>,
+    access_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TagList {
+    tags: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+struct ManifestConfig {
+    digest: String,
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+    config: Option<ManifestConfig>,
+}
+
+#[derive(Deserialize)]
+struct ImageConfig {
+    created: Option<DateTime<Utc>>,
+}
+
+/// Client of the registry v2 HTTP API, handling basic and token authentication
+struct RegistryClient {
+    base_url: Url,
+    credentials: Option<Credentials>,
+    http: Client,
+}
+
+impl RegistryClient {
+    fn new(mut base_url: Url, credentials: Option<Credentials>) -> RegistryClient {
+        let _ = base_url.set_username("");
+        let _ = base_url.set_password(None);
+        base_url.set_path("");
+
+        RegistryClient {
+            base_url,
+            credentials,
+            http: Client::builder()
+                .timeout(Duration::from_secs(60))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url.as_str().trim_end_matches('/'), path)
+    }
+
+    fn with_basic_auth(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.credentials {
+            Some(credentials) => request.basic_auth(&credentials.login, Some(&credentials.password)),
+            None => request,
+        }
+    }
+
+    /// Call the registry specific API, i.e: Harbor API, which only supports basic authentication
+    fn send_api<F>(&self, method: Method, path: &str, build_request: F) -> Result<Response, String>
+    where
+        F: Fn(RequestBuilder) -> RequestBuilder,
+    {
+        build_request(self.with_basic_auth(self.http.request(method, self.url(path))))
+            .send()
+            .map_err(|err| err.to_string())
+    }
+
+    /// Call the registry v2 API, authenticating with the scheme asked by the registry if it is needed
+    fn send<F>(&self, method: Method, url: &str, scope: &str, build_request: F) -> Result<Response, String>
+    where
+        F: Fn(RequestBuilder) -> RequestBuilder,
+    {
+        let response = build_request(self.http.request(method.clone(), url))
+            .send()
+            .map_err(|err| err.to_string())?;
+        if response.status() != StatusCode::UNAUTHORIZED {
+            return Ok(response);
+        }
+
+        let challenge = response
+            .headers()
+            .get(WWW_AUTHENTICATE)
+            .and_then(|header| header.to_str().ok())
+            .and_then(parse_auth_challenge);
+        let request = match challenge {
+            Some(AuthChallenge::Basic) => self.with_basic_auth(self.http.request(method, url)),
+            Some(AuthChallenge::Bearer { realm, service, .. }) => {
+                let token = self.get_token(&realm, service.as_deref(), scope)?;
+                self.http.request(method, url).bearer_auth(token)
+            }
+            None => return Ok(response),
+        };
+
+        build_request(request).send().map_err(|err| err.to_string())
+    }
+
+    fn get_token(&self, realm: &str, service: Option<&str>, scope: &str) -> Result<String, String> {
+        let mut query = vec![("scope", scope)];
+        if let Some(service) = service {
+            query.push(("service", service));
+        }
+
+        let response = self
+            .with_basic_auth(self.http.get(realm).query(&query))
+            .send()
+            .map_err(|err| err.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("Token endpoint returned status `{}`", response.status()));
+        }
+
+        let token = response.json::<TokenResponse>().map_err(|err| err.to_string())?;
+        token
+            .token
+            .or(token.access_token)
+            .ok_or_else(|| "Token endpoint did not return any token".to_string())
+    }
+
+    fn check_credentials(&self) -> Result<(), ContainerRegistryError> {
+        match self.send(Method::GET, &self.url("/v2/"), "registry:catalog:*", |request| request) {
+            Ok(response) if response.status().is_success() => Ok(()),
+            Ok(response) if response.status() == StatusCode::UNAUTHORIZED => {
+                Err(ContainerRegistryError::InvalidCredentials)
+            }
+            Ok(response) => Err(ContainerRegistryError::RegistryDoesntExist {
+                registry_name: self.base_url.to_string(),
+                raw_error_message: format!("Registry returned status `{}` on its v2 API", response.status()),
+            }),
+            Err(err) => Err(ContainerRegistryError::RegistryDoesntExist {
+                registry_name: self.base_url.to_string(),
+                raw_error_message: err,
+            }),
+        }
+    }
+
+    /// Digest of the manifest of the image, None if the image does not exist
+    fn manifest_digest(&self, repository: &str, reference: &str) -> Result<Option<String>, String> {
+        let url = self.url(&format!("/v2/{}/manifests/{}", repository, reference));
+        let response = self.send(Method::HEAD, &url, &format!("repository:{}:pull", repository), |request| {
+            request.header(ACCEPT, MANIFEST_MEDIA_TYPES.join(", "))
+        })?;
+
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => Ok(response
+                .headers()
+                .get(CONTENT_DIGEST_HEADER)
+                .and_then(|digest| digest.to_str().ok())
+                .map(str::to_string)),
+            status => Err(format!("Registry returned status `{}` when reading manifest", status)),
+        }
+    }
+
+    fn delete_manifest(&self, repository: &str, digest: &str) -> Result<(), String> {
+        let url = self.url(&format!("/v2/{}/manifests/{}", repository, digest));
+        let response = self.send(
+            Method::DELETE,
+            &url,
+            &format!("repository:{}:pull,push,delete", repository),
+            |request| request,
+        )?;
+
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(()),
+            status if status.is_success() => Ok(()),
+            status => Err(format!(
+                "Registry returned status `{}` when deleting manifest, is deletion enabled on the registry ?",
+                status
+            )),
+        }
+    }
+
+    fn list_tags(&self, repository: &str) -> Result<Vec<String>, String> {
+        let scope = format!("repository:{}:pull", repository);
+        let mut tags = vec![];
+        let mut next_url = Some(self.url(&format!("/v2/{}/tags/list?n=1000", repository)));
+
+        while let Some(url) = next_url {
+            let response = self.send(Method::GET, &url, &scope, |request| request)?;
+            match response.status() {
+                StatusCode::NOT_FOUND => return Ok(tags),
+                status if status.is_success() => {}
+                status => return Err(format!("Registry returned status `{}` when listing tags", status)),
+            }
+
+            // pagination is given by a link header, i.e: </v2/app/tags/list?n=1000&last=v42>; rel="next"
+            next_url = response
+                .headers()
+                .get(LINK)
+                .and_then(|link| link.to_str().ok())
+                .and_then(|link| link.split_once('<'))
+                .and_then(|(_, link)| link.split_once('>'))
+                .map(|(path, _)| self.url(path));
+
+            let tag_list = response.json::<TagList>().map_err(|err| err.to_string())?;
+            tags.extend(tag_list.tags.unwrap_or_default());
+        }
+
+        Ok(tags)
+    }
+
+    /// Creation date of the image, from its config blob. None for multi-arch images, which have no config.
+    fn image_created_at(&self, repository: &str, reference: &str) -> Result<Option<DateTime<Utc>>, String> {
+        let scope = format!("repository:{}:pull", repository);
+        let url = self.url(&format!("/v2/{}/manifests/{}", repository, reference));
+        let response = self.send(Method::GET, &url, &scope, |request| {
+            request.header(ACCEPT, MANIFEST_MEDIA_TYPES.join(", "))
+        })?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "Registry returned status `{}` when reading manifest",
+                response.status()
+            ));
+        }
+
+        let config_digest = match response.json::<Manifest>().map_err(|err| err.to_string())?.config {
+            Some(config) => config.digest,
+            None => return Ok(None),
+        };
+
+        let url = self.url(&format!("/v2/{}/blobs/{}", repository, config_digest));
+        let response = self.send(Method::GET, &url, &scope, |request| request)?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "Registry returned status `{}` when reading image config",
+                response.status()
+            ));
+        }
+
+        Ok(response.json::<ImageConfig>().map_err(|err| err.to_string())?.created)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_auth_challenge() {
+        // execute & verify:
+        assert_eq!(
+            Some(AuthChallenge::Basic),
+            parse_auth_challenge(r#"Basic realm="Registry Realm""#)
+        );
+        assert_eq!(
+            Some(AuthChallenge::Bearer {
+                realm: "https://harbor.example.com/service/token".to_string(),
+                service: Some("harbor-registry".to_string()),
+                scope: Some("repository:qovery/app:pull,push".to_string()),
+            }),
+            parse_auth_challenge(
+                r#"Bearer realm="https://harbor.example.com/service/token",service="harbor-registry",scope="repository:qovery/app:pull,push""#
+            )
+        );
+        assert_eq!(
+            Some(AuthChallenge::Bearer {
+                realm: "https://ghcr.io/token".to_string(),
+                service: None,
+                scope: None,
+            }),
+            parse_auth_challenge(r#"Bearer realm="https://ghcr.io/token""#)
+        );
+        assert_eq!(None, parse_auth_challenge(r#"Bearer service="ghcr.io""#));
+        assert_eq!(None, parse_auth_challenge("Negotiate"));
+    }
+}
+
+// start a local registry, with deletion enabled, to run this test
+// docker run --rm -d -p 5000:5000 -e REGISTRY_STORAGE_DELETE_ENABLED=true --name registry registry:2
+#[cfg(feature = "test-local-docker")]
+#[cfg(test)]
+mod tests_local_registry {
+    use super::*;
+    use crate::cmd::command::CommandKiller;
+    use crate::cmd::docker::{ContainerImage, Docker};
+
+    fn local_registry(namespace: Option<&str>) -> GenericCr {
+        let context = Context::new(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4().to_string(),
+            std::env::temp_dir().to_string_lossy().to_string(),
+            "lib".to_string(),
+            true,
+            None,
+            vec![],
+            None,
+            Docker::new_with_options(false, None).expect("cannot create docker client"),
+        );
+
+        GenericCr::new(
+            context,
+            "local-registry",
+            Uuid::new_v4(),
+            "local-registry",
+            Url::parse("http://localhost:5000").unwrap(),
+            namespace,
+            None,
+            GenericCrFlavor::OciDistribution,
+        )
+        .expect("cannot reach local registry")
+    }
+
+    fn push_image(registry: &GenericCr, repository_name: &str, tag: &str) -> Image {
+        let source = ContainerImage {
+            registry: Url::parse("https://docker.io").unwrap(),
+            name: "alpine".to_string(),
+            tags: vec!["3.15".to_string()],
+        };
+        let destination = ContainerImage {
+            registry: registry.registry_info().endpoint.clone(),
+            name: repository_name.to_string(),
+            tags: vec![tag.to_string()],
+        };
+        registry
+            .context()
+            .docker
+            .mirror(&source, &destination, &mut |_| {}, &mut |_| {}, &CommandKiller::never())
+            .expect("cannot push image into local registry");
+
+        Image {
+            application_id: "app".to_string(),
+            name: repository_name.to_string(),
+            tag: tag.to_string(),
+            commit_id: tag.to_string(),
+            registry_name: registry.name().to_string(),
+            registry_docker_json_config: None,
+            registry_url: registry.registry_info().endpoint.clone(),
+            repository_name: repository_name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_image_lifecycle() {
+        // setup:
+        let registry = local_registry(Some("qovery"));
+        let repository_name = (registry.registry_info().get_repository_name)(&format!("app-{}", Uuid::new_v4()));
+        let image = push_image(&registry, &repository_name, "v1");
+
+        // execute & verify:
+        assert!(registry.create_repository(&repository_name, 0).is_ok());
+        assert!(registry.does_image_exists(&image));
+        assert!(registry.delete_image(&image).is_ok());
+        assert!(!registry.does_image_exists(&image));
+        // deleting a missing image is not an error
+        assert!(registry.delete_image(&image).is_ok());
+    }
+
+    #[test]
+    fn test_retention_and_repository_deletion() {
+        // setup:
+        let registry = local_registry(None);
+        let repository_name = format!("app-{}", Uuid::new_v4());
+        let image = push_image(&registry, &repository_name, "v1");
+
+        // execute & verify:
+        // alpine image has been created long before 1 hour ago, so it is expired
+        assert!(registry.create_repository(&repository_name, 3600).is_ok());
+        assert!(!registry.does_image_exists(&image));
+
+        let image = push_image(&registry, &repository_name, "v2");
+        assert!(registry.delete_repository(&repository_name).is_ok());
+        assert!(!registry.does_image_exists(&image));
+    }
+}
@@ -12,9 +12,10 @@ use crate::io_models::QoveryIdentifier;
 pub mod docr;
 pub mod ecr;
 pub mod errors;
+pub mod generic_cr;
 pub mod scaleway_container_registry;
 
-pub trait ContainerRegistry {
+pub trait ContainerRegistry: Send + Sync {
     fn context(&self) -> &Context;
     fn kind(&self) -> Kind;
     fn id(&self) -> &str;
@@ -75,10 +76,10 @@ pub struct ContainerRegistryInfo {
     // i.e: for DigitalOcean => registry_name/image_name
     // i.e: fo scaleway => image_name/image_name
     // i.e: for AWS => image_name
-    pub get_image_name: Box<dyn Fn(&str) -> String>,
+    pub get_image_name: Box<dyn Fn(&str) -> String + Send + Sync>,
 
     // Give it the name of your image, and it return the name of the repository that will be used
-    pub get_repository_name: Box<dyn Fn(&str) -> String>,
+    pub get_repository_name: Box<dyn Fn(&str) -> String + Send + Sync>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Copy, Debug)]
@@ -87,4 +88,5 @@ pub enum Kind {
     Ecr,
     Docr,
     ScalewayCr,
+    GenericCr,
 }
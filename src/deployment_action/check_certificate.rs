@@ -0,0 +1,542 @@
+use crate::cloud_provider::DeploymentTarget;
+use crate::deployment_action::DeploymentAction;
+use crate::errors::{CommandError, EngineError};
+use crate::events::{EngineEvent, EventDetails, EventMessage};
+use crate::logger::Logger;
+use crate::runtime::block_on;
+use kube::api::{ApiResource, DynamicObject, GroupVersionKind, ListParams};
+use kube::Api;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fmt;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const POLLING_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Why cert-manager cannot issue the certificate of custom domains
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CertificateIssue {
+    /// the domain does not point to the cluster, so the HTTP-01 challenge cannot be reached
+    WrongDnsConfiguration {
+        domain: String,
+        details: String,
+    },
+    /// Let's Encrypt refuses to issue more certificates for now
+    RateLimited {
+        details: String,
+    },
+    /// Let's Encrypt has not been able to validate the HTTP-01 challenge
+    ChallengeFailed {
+        domain: String,
+        details: String,
+    },
+    /// the HTTP-01 challenge has not been validated yet
+    ChallengePending {
+        domain: String,
+        details: String,
+    },
+    /// the certificate request or the Let's Encrypt order has failed
+    OrderFailed {
+        details: String,
+    },
+    Other {
+        details: String,
+    },
+}
+
+impl CertificateIssue {
+    pub fn details(&self) -> &str {
+        match self {
+            CertificateIssue::WrongDnsConfiguration { details, .. } => details,
+            CertificateIssue::RateLimited { details } => details,
+            CertificateIssue::ChallengeFailed { details, .. } => details,
+            CertificateIssue::ChallengePending { details, .. } => details,
+            CertificateIssue::OrderFailed { details } => details,
+            CertificateIssue::Other { details } => details,
+        }
+    }
+
+    pub fn hint(&self) -> String {
+        match self {
+            CertificateIssue::WrongDnsConfiguration { domain, .. } => format!(
+                "Please check that the CNAME of domain {} targets the domain given by Qovery.",
+                domain
+            ),
+            CertificateIssue::RateLimited { .. } => {
+                "Let's Encrypt rate limit has been reached, the certificate will be requested again later.".to_string()
+            }
+            CertificateIssue::ChallengeFailed { domain, .. } | CertificateIssue::ChallengePending { domain, .. } => {
+                format!(
+                    "Please check that domain {} is reachable over HTTP on port 80, and not behind a CDN or a proxy.",
+                    domain
+                )
+            }
+            CertificateIssue::OrderFailed { .. } | CertificateIssue::Other { .. } => {
+                "Please check the configuration of your custom domains.".to_string()
+            }
+        }
+    }
+}
+
+impl fmt::Display for CertificateIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CertificateIssue::WrongDnsConfiguration { domain, .. } => {
+                write!(f, "domain {} does not point to the cluster", domain)
+            }
+            CertificateIssue::RateLimited { .. } => write!(f, "Let's Encrypt rate limit reached"),
+            CertificateIssue::ChallengeFailed { domain, .. } => {
+                write!(f, "HTTP-01 challenge failed for domain {}", domain)
+            }
+            CertificateIssue::ChallengePending { domain, .. } => {
+                write!(f, "HTTP-01 challenge of domain {} is still pending", domain)
+            }
+            CertificateIssue::OrderFailed { .. } => write!(f, "certificate request has failed"),
+            CertificateIssue::Other { .. } => write!(f, "certificate cannot be issued"),
+        }
+    }
+}
+
+/// Issuance status of a certificate, from its cert-manager Certificate, Orders and Challenges
+#[derive(Debug, Default, PartialEq, Eq)]
+struct CertificateStatus {
+    issued: bool,
+    progress: Vec<String>,
+    issues: Vec<CertificateIssue>,
+    /// challenges not validated yet, without any known issue
+    pending_challenges: Vec<CertificateIssue>,
+}
+
+/// Watch cert-manager until the TLS certificate of custom domains is issued, or only report its status when there is no timeout
+pub struct CheckCertificateForDomains {
+    /// cert-manager Certificate, named after the TLS secret of the ingress
+    pub certificate_name: String,
+    pub domains: Vec<String>,
+    pub timeout: Duration,
+    /// fail if the certificate is not issued before the timeout, otherwise only warn
+    pub fail_on_timeout: bool,
+    pub event_details: EventDetails,
+    pub logger: Box<dyn Logger>,
+    pub log: Box<dyn Fn(String)>,
+}
+
+impl CheckCertificateForDomains {
+    fn fetch_status(&self, target: &DeploymentTarget) -> Result<CertificateStatus, CommandError> {
+        let namespace = target.environment.namespace();
+        let api = |group: &str, kind: &str| -> Api<DynamicObject> {
+            let resource = ApiResource::from_gvk(&GroupVersionKind::gvk(group, "v1", kind));
+            Api::namespaced_with(target.kube.clone(), namespace, &resource)
+        };
+        let to_error = |err: kube::Error| {
+            CommandError::new(
+                "Error while trying to get cert-manager resources".to_string(),
+                Some(err.to_string()),
+                None,
+            )
+        };
+
+        let certificate = match block_on(api("cert-manager.io", "Certificate").get(&self.certificate_name)) {
+            Ok(certificate) => Some(certificate.data),
+            // ingress-shim has not created it yet
+            Err(kube::Error::Api(err)) if err.code == 404 => None,
+            Err(err) => return Err(to_error(err)),
+        };
+        let orders = block_on(api("acme.cert-manager.io", "Order").list(&ListParams::default())).map_err(to_error)?;
+        let challenges =
+            block_on(api("acme.cert-manager.io", "Challenge").list(&ListParams::default())).map_err(to_error)?;
+
+        Ok(certificate_status(
+            certificate.as_ref(),
+            &orders.items.into_iter().map(|order| order.data).collect::<Vec<_>>(),
+            &challenges
+                .items
+                .into_iter()
+                .map(|challenge| challenge.data)
+                .collect::<Vec<_>>(),
+            &self.domains,
+        ))
+    }
+}
+
+impl DeploymentAction for CheckCertificateForDomains {
+    fn on_create(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        if self.domains.is_empty() {
+            return Ok(());
+        }
+
+        if self.timeout.is_zero() {
+            (self.log)(format!(
+                "🔒 Checking the TLS certificate of domains {}...",
+                self.domains.join(", ")
+            ));
+        } else {
+            (self.log)(format!(
+                "🔒 Waiting for the TLS certificate of domains {} to be issued...",
+                self.domains.join(", ")
+            ));
+        }
+        let started_at = Instant::now();
+        let mut reported_progress: HashSet<String> = HashSet::new();
+        let mut reported_issues: HashSet<CertificateIssue> = HashSet::new();
+        let mut last_issue: Option<CertificateIssue> = None;
+        let mut pending_challenge: Option<CertificateIssue> = None;
+
+        loop {
+            match self.fetch_status(target) {
+                Ok(status) if status.issued => {
+                    (self.log)(format!(
+                        "✅ TLS certificate of domains {} has been issued",
+                        self.domains.join(", ")
+                    ));
+                    return Ok(());
+                }
+                Ok(status) => {
+                    for progress in status.progress {
+                        if reported_progress.insert(progress.clone()) {
+                            (self.log)(format!("🔒 {}", progress));
+                        }
+                    }
+                    for issue in status.issues {
+                        // logged as tagged errors, so issues can be looked up by kind, the deployment goes on
+                        if reported_issues.insert(issue.clone()) {
+                            self.logger.log(EngineEvent::Error(
+                                EngineError::new_router_certificate_not_issued(
+                                    self.event_details.clone(),
+                                    self.domains.clone(),
+                                    &issue,
+                                ),
+                                Some(EventMessage::new_from_safe(format!(
+                                    "TLS certificate cannot be issued yet, {}. {}",
+                                    issue,
+                                    issue.hint()
+                                ))),
+                            ));
+                        }
+                        last_issue = Some(issue);
+                    }
+                    pending_challenge = status.pending_challenges.into_iter().next();
+                }
+                Err(err) => self.logger.log(EngineEvent::Warning(
+                    self.event_details.clone(),
+                    EventMessage::new(err.message_safe(), err.message_raw()),
+                )),
+            }
+
+            if started_at.elapsed() >= self.timeout {
+                break;
+            }
+            thread::sleep(POLLING_INTERVAL);
+        }
+
+        let issue = last_issue
+            .or(pending_challenge)
+            .unwrap_or_else(|| CertificateIssue::Other {
+                details: format!("certificate not issued after {} seconds", started_at.elapsed().as_secs()),
+            });
+        let error =
+            EngineError::new_router_certificate_not_issued(self.event_details.clone(), self.domains.clone(), &issue);
+        if self.fail_on_timeout {
+            return Err(error);
+        }
+
+        self.logger.log(EngineEvent::Error(
+            error,
+            Some(EventMessage::new_from_safe(format!(
+                "TLS certificate of domains {} is not issued yet, {}. It will keep being requested in background. {}",
+                self.domains.join(", "),
+                issue,
+                issue.hint()
+            ))),
+        ));
+        Ok(())
+    }
+
+    fn on_pause(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn on_delete(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
+        Ok(())
+    }
+}
+
+fn certificate_status(
+    certificate: Option<&Value>,
+    orders: &[Value],
+    challenges: &[Value],
+    domains: &[String],
+) -> CertificateStatus {
+    let mut status = CertificateStatus::default();
+    let certificate = match certificate {
+        Some(certificate) => certificate,
+        None => {
+            status
+                .progress
+                .push("Certificate request has not been created yet".to_string());
+            return status;
+        }
+    };
+
+    for condition in certificate["status"]["conditions"].as_array().into_iter().flatten() {
+        let reason = condition["reason"].as_str().unwrap_or_default();
+        let message = condition["message"].as_str().unwrap_or_default();
+        match (condition["type"].as_str(), condition["status"].as_str()) {
+            (Some("Ready"), Some("True")) => {
+                status.issued = true;
+                return status;
+            }
+            (Some("Issuing"), Some("False")) if reason == "Failed" => {
+                status
+                    .issues
+                    .push(classify_issue(None, message).unwrap_or(CertificateIssue::OrderFailed {
+                        details: message.to_string(),
+                    }));
+            }
+            (Some("Issuing"), Some("True")) => status
+                .progress
+                .push(format!("Certificate is being issued: {}", message)),
+            _ => {}
+        }
+    }
+
+    let is_watched_domain = |domain: &Value| domain.as_str().map(|d| domains.iter().any(|x| x == d)) == Some(true);
+    for order in orders {
+        if !order["spec"]["dnsNames"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .any(is_watched_domain)
+        {
+            continue;
+        }
+
+        let state = order["status"]["state"].as_str().unwrap_or("pending");
+        match state {
+            "invalid" | "errored" => {
+                let reason = order["status"]["reason"].as_str().unwrap_or(state);
+                status
+                    .issues
+                    .push(classify_issue(None, reason).unwrap_or(CertificateIssue::OrderFailed {
+                        details: reason.to_string(),
+                    }))
+            }
+            _ => status.progress.push(format!("Let's Encrypt order is {}", state)),
+        }
+    }
+
+    for challenge in challenges {
+        if !is_watched_domain(&challenge["spec"]["dnsName"]) {
+            continue;
+        }
+
+        let domain = challenge["spec"]["dnsName"].as_str().unwrap_or_default();
+        let state = challenge["status"]["state"].as_str().unwrap_or("pending");
+        let reason = challenge["status"]["reason"].as_str().unwrap_or_default();
+        match (state, classify_issue(Some(domain), reason)) {
+            // the self check of cert-manager keeps failing while the domain does not point to the cluster
+            (_, Some(issue)) => status.issues.push(issue),
+            ("invalid" | "errored", None) => status.issues.push(CertificateIssue::ChallengeFailed {
+                domain: domain.to_string(),
+                details: reason.to_string(),
+            }),
+            (_, None) => {
+                status
+                    .progress
+                    .push(format!("HTTP-01 challenge of domain {} is {}", domain, state));
+                status.pending_challenges.push(CertificateIssue::ChallengePending {
+                    domain: domain.to_string(),
+                    details: reason.to_string(),
+                });
+            }
+        }
+    }
+
+    status
+}
+
+/// Known issue behind a cert-manager message, None when the message does not tell why the certificate is not issued
+fn classify_issue(domain: Option<&str>, message: &str) -> Option<CertificateIssue> {
+    let details = message.to_string();
+    let lowercase_message = message.to_lowercase();
+
+    if lowercase_message.contains("ratelimited") || lowercase_message.contains("too many certificates") {
+        return Some(CertificateIssue::RateLimited { details });
+    }
+
+    let is_dns_issue = [
+        "no such host",
+        "nxdomain",
+        "wrong status code",
+        "connection refused",
+        "i/o timeout",
+    ]
+    .iter()
+    .any(|pattern| lowercase_message.contains(pattern));
+    match domain {
+        Some(domain) if is_dns_issue => Some(CertificateIssue::WrongDnsConfiguration {
+            domain: domain.to_string(),
+            details,
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::deployment_action::check_certificate::{certificate_status, CertificateIssue};
+    use crate::errors::{EngineError, Tag};
+    use crate::events::{EnvironmentStep, EventDetails, Stage, Transmitter};
+    use crate::io_models::QoveryIdentifier;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_certificate_status_issued() {
+        // setup:
+        let certificate = json!({
+            "status": {"conditions": [{"type": "Ready", "status": "True", "reason": "Ready", "message": "Certificate is up to date"}]}
+        });
+
+        // execute:
+        let status = certificate_status(Some(&certificate), &[], &[], &["www.example.com".to_string()]);
+
+        // verify:
+        assert!(status.issued);
+        assert!(status.issues.is_empty());
+    }
+
+    #[test]
+    fn test_certificate_status_issues() {
+        // setup:
+        let domains = vec!["www.example.com".to_string(), "api.example.com".to_string()];
+        let certificate = json!({
+            "status": {"conditions": [{"type": "Ready", "status": "False", "reason": "DoesNotExist", "message": "Issuing certificate as Secret does not exist"}]}
+        });
+        let orders = vec![
+            json!({"spec": {"dnsNames": ["www.example.com"]}, "status": {"state": "pending"}}),
+            json!({"spec": {"dnsNames": ["other.example.com"]}, "status": {"state": "errored", "reason": "unrelated"}}),
+        ];
+        let challenges = vec![
+            json!({
+                "spec": {"dnsName": "www.example.com"},
+                "status": {"state": "pending", "reason": "Waiting for HTTP-01 challenge propagation: failed to perform self check GET request 'http://www.example.com/.well-known/acme-challenge/abc': Get \"http://www.example.com/.well-known/acme-challenge/abc\": dial tcp: lookup www.example.com on 10.0.0.10:53: no such host"}
+            }),
+            json!({
+                "spec": {"dnsName": "api.example.com"},
+                "status": {"state": "invalid", "reason": "Error accepting authorization: acme: authorization error for api.example.com: 403 urn:ietf:params:acme:error:unauthorized: Invalid response from http://api.example.com/.well-known/acme-challenge/def"}
+            }),
+        ];
+
+        // execute:
+        let status = certificate_status(Some(&certificate), &orders, &challenges, &domains);
+
+        // verify:
+        assert!(!status.issued);
+        assert_eq!(status.progress, vec!["Let's Encrypt order is pending".to_string()]);
+        assert_eq!(status.issues.len(), 2);
+        assert!(matches!(
+            &status.issues[0],
+            CertificateIssue::WrongDnsConfiguration { domain, .. } if domain == "www.example.com"
+        ));
+        assert!(matches!(
+            &status.issues[1],
+            CertificateIssue::ChallengeFailed { domain, .. } if domain == "api.example.com"
+        ));
+    }
+
+    #[test]
+    fn test_certificate_status_rate_limited() {
+        // setup:
+        let certificate = json!({
+            "status": {"conditions": [
+                {"type": "Ready", "status": "False", "reason": "DoesNotExist", "message": ""},
+                {"type": "Issuing", "status": "False", "reason": "Failed", "message": "The certificate request has failed to complete and will be retried: Failed to wait for order resource \"router-tls-abc-1\" to become ready: order is in \"errored\" state: Failed to create Order: 429 urn:ietf:params:acme:error:rateLimited: Error creating new order :: too many certificates already issued"}
+            ]}
+        });
+
+        // execute:
+        let status = certificate_status(Some(&certificate), &[], &[], &["www.example.com".to_string()]);
+
+        // verify:
+        assert!(!status.issued);
+        assert!(matches!(status.issues[0], CertificateIssue::RateLimited { .. }));
+    }
+
+    #[test]
+    fn test_certificate_status_pending_challenge() {
+        // setup:
+        let challenges = vec![json!({
+            "spec": {"dnsName": "www.example.com"},
+            "status": {"state": "pending", "reason": "Waiting for authorization"}
+        })];
+
+        // execute:
+        let status = certificate_status(Some(&json!({})), &[], &challenges, &["www.example.com".to_string()]);
+
+        // verify:
+        assert!(status.issues.is_empty());
+        assert_eq!(
+            status.pending_challenges,
+            vec![CertificateIssue::ChallengePending {
+                domain: "www.example.com".to_string(),
+                details: "Waiting for authorization".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_certificate_not_issued_tags() {
+        // setup:
+        let event_details = EventDetails::new(
+            None,
+            QoveryIdentifier::new_random(),
+            QoveryIdentifier::new_random(),
+            QoveryIdentifier::new_random().to_string(),
+            None,
+            Stage::Environment(EnvironmentStep::Deploy),
+            Transmitter::Router(Uuid::new_v4(), "router".to_string()),
+        );
+        let tag = |issue: CertificateIssue| {
+            EngineError::new_router_certificate_not_issued(
+                event_details.clone(),
+                vec!["www.example.com".to_string()],
+                &issue,
+            )
+            .tag()
+            .clone()
+        };
+        let domain = "www.example.com".to_string();
+        let details = "details".to_string();
+
+        // execute & verify:
+        assert_eq!(
+            tag(CertificateIssue::WrongDnsConfiguration {
+                domain: domain.clone(),
+                details: details.clone()
+            }),
+            Tag::RouterCertificateDnsMismatch
+        );
+        assert_eq!(
+            tag(CertificateIssue::ChallengePending {
+                domain: domain.clone(),
+                details: details.clone()
+            }),
+            Tag::RouterCertificateAcmeChallengePending
+        );
+        assert_eq!(
+            tag(CertificateIssue::ChallengeFailed {
+                domain,
+                details: details.clone()
+            }),
+            Tag::RouterCertificateAcmeChallengeFailed
+        );
+        assert_eq!(
+            tag(CertificateIssue::OrderFailed {
+                details: details.clone()
+            }),
+            Tag::RouterCertificateOrderFailed
+        );
+        assert_eq!(tag(CertificateIssue::Other { details }), Tag::RouterCertificateNotIssued);
+    }
+}
@@ -0,0 +1,239 @@
+use crate::cloud_provider::metrics::KubernetesApiMetricsResources;
+use crate::cloud_provider::DeploymentTarget;
+use crate::cmd::kubectl::{kubectl_exec_api_custom_metrics_resources, kubectl_exec_api_external_metrics};
+use crate::errors::{CommandError, EngineError};
+use crate::events::EventDetails;
+use crate::io_models::application::{HpaMetric, HpaScalingRules};
+
+// limits enforced by Kubernetes on HPAScalingRules
+const MAX_STABILIZATION_WINDOW_SECONDS: u32 = 3600;
+const MAX_POLICY_PERIOD_SECONDS: u32 = 1800;
+
+const QUANTITY_SUFFIXES: [&str; 15] = [
+    "Ki", "Mi", "Gi", "Ti", "Pi", "Ei", "n", "u", "m", "k", "M", "G", "T", "P", "E",
+];
+
+/// Whether the value is a positive Kubernetes quantity, such as `100`, `0.5` or `500m`
+fn is_positive_quantity(value: &str) -> bool {
+    let number = QUANTITY_SUFFIXES
+        .iter()
+        .find_map(|suffix| value.strip_suffix(suffix))
+        .unwrap_or(value);
+
+    matches!(number.parse::<f64>(), Ok(number) if number > 0.0 && number.is_finite())
+}
+
+fn validate_hpa_metric(metric: &HpaMetric) -> Result<(), String> {
+    match metric {
+        HpaMetric::Memory {
+            average_utilization_percent,
+        } => {
+            if *average_utilization_percent == 0 {
+                return Err("memory average utilization must be greater than 0".to_string());
+            }
+        }
+        HpaMetric::Custom { name, average_value }
+        | HpaMetric::External {
+            name, average_value, ..
+        } => {
+            if name.is_empty() || name.contains(char::is_whitespace) || name.contains('/') {
+                return Err(format!("metric name `{}` is invalid", name));
+            }
+            if !is_positive_quantity(average_value) {
+                return Err(format!(
+                    "average value `{}` of metric {} is not a positive quantity",
+                    average_value, name
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_scaling_rules(direction: &str, rules: &HpaScalingRules) -> Result<(), String> {
+    if rules.stabilization_window_seconds.unwrap_or_default() > MAX_STABILIZATION_WINDOW_SECONDS {
+        return Err(format!(
+            "{} stabilization window cannot exceed {} seconds",
+            direction, MAX_STABILIZATION_WINDOW_SECONDS
+        ));
+    }
+
+    for policy in &rules.policies {
+        if policy.value == 0 {
+            return Err(format!("{} policy value must be greater than 0", direction));
+        }
+        if policy.period_seconds == 0 || policy.period_seconds > MAX_POLICY_PERIOD_SECONDS {
+            return Err(format!(
+                "{} policy period must be between 1 and {} seconds",
+                direction, MAX_POLICY_PERIOD_SECONDS
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether the custom metrics adapter serves the metric for pods
+fn is_custom_metric_listed(listed_metrics: &KubernetesApiMetricsResources, metric_name: &str) -> bool {
+    let resource_name = format!("pods/{}", metric_name);
+    listed_metrics
+        .resources
+        .iter()
+        .any(|resource| resource.name == resource_name)
+}
+
+/// Check the pod autoscaler configuration, and that the custom and external metrics it relies on are served by the cluster.
+/// Otherwise the autoscaler would be deployed but never scale.
+pub(super) fn check_hpa_metrics(
+    metrics: &[HpaMetric],
+    scale_up: Option<&HpaScalingRules>,
+    scale_down: Option<&HpaScalingRules>,
+    target: &DeploymentTarget,
+    event_details: &EventDetails,
+) -> Result<(), EngineError> {
+    let invalid = |reason: String| EngineError::new_invalid_hpa_configuration(event_details.clone(), reason);
+
+    for metric in metrics {
+        validate_hpa_metric(metric).map_err(invalid)?;
+    }
+    if let Some(scale_up) = scale_up {
+        validate_scaling_rules("scale up", scale_up).map_err(invalid)?;
+    }
+    if let Some(scale_down) = scale_down {
+        validate_scaling_rules("scale down", scale_down).map_err(invalid)?;
+    }
+
+    let kubeconfig = target.kubernetes.get_kubeconfig_file_path()?;
+    let envs = target.kubernetes.cloud_provider().credentials_environment_variables();
+    let namespace = target.environment.namespace();
+    // custom metrics values only exist once pods expose them, i.e never before the first deployment,
+    // so only the metrics listed by the adapter are checked
+    let custom_metrics = match metrics.iter().any(|metric| matches!(metric, HpaMetric::Custom { .. })) {
+        true => Some(kubectl_exec_api_custom_metrics_resources(&kubeconfig, envs.clone())),
+        false => None,
+    };
+    for metric in metrics {
+        let result = match metric {
+            HpaMetric::Memory { .. } => continue,
+            HpaMetric::Custom { name, .. } => match &custom_metrics {
+                Some(Ok(listed_metrics)) if is_custom_metric_listed(listed_metrics, name) => Ok(()),
+                Some(Err(err)) => Err(err.clone()),
+                _ => Err(CommandError::new_from_safe_message(format!(
+                    "metric {} is not listed by the custom metrics adapter",
+                    name
+                ))),
+            },
+            HpaMetric::External { name, selector, .. } => {
+                let label_selector = selector
+                    .iter()
+                    .map(|(key, value)| format!("{}={}", key, value))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                kubectl_exec_api_external_metrics(&kubeconfig, envs.clone(), namespace, name, &label_selector)
+                    .map(|_| ())
+            }
+        };
+
+        if let Err(err) = result {
+            return Err(EngineError::new_hpa_metric_not_served(
+                event_details.clone(),
+                metric.name().to_string(),
+                err,
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cloud_provider::metrics::{KubernetesApiMetricsResources, MetricResource};
+    use crate::deployment_action::check_hpa_metrics::{
+        is_custom_metric_listed, is_positive_quantity, validate_hpa_metric, validate_scaling_rules,
+    };
+    use crate::io_models::application::{HpaMetric, HpaScalingPolicy, HpaScalingPolicyType, HpaScalingRules};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_is_positive_quantity() {
+        assert!(is_positive_quantity("100"));
+        assert!(is_positive_quantity("0.5"));
+        assert!(is_positive_quantity("500m"));
+        assert!(is_positive_quantity("1Gi"));
+        assert!(!is_positive_quantity("0"));
+        assert!(!is_positive_quantity("-1"));
+        assert!(!is_positive_quantity("ten"));
+        assert!(!is_positive_quantity(""));
+    }
+
+    #[test]
+    fn test_validate_hpa_metric() {
+        assert!(validate_hpa_metric(&HpaMetric::Memory {
+            average_utilization_percent: 80
+        })
+        .is_ok());
+        assert!(validate_hpa_metric(&HpaMetric::Memory {
+            average_utilization_percent: 0
+        })
+        .is_err());
+        assert!(validate_hpa_metric(&HpaMetric::Custom {
+            name: "http_requests_per_second".to_string(),
+            average_value: "100".to_string(),
+        })
+        .is_ok());
+        assert!(validate_hpa_metric(&HpaMetric::Custom {
+            name: "".to_string(),
+            average_value: "100".to_string(),
+        })
+        .is_err());
+        assert!(validate_hpa_metric(&HpaMetric::External {
+            name: "sqs_messages_visible".to_string(),
+            selector: BTreeMap::from([("queue".to_string(), "jobs".to_string())]),
+            average_value: "many".to_string(),
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_validate_scaling_rules() {
+        // setup:
+        let policy = |value, period_seconds| HpaScalingPolicy {
+            policy_type: HpaScalingPolicyType::Pods,
+            value,
+            period_seconds,
+        };
+        let rules = |stabilization_window_seconds, policies| HpaScalingRules {
+            stabilization_window_seconds,
+            select_policy: None,
+            policies,
+        };
+
+        // execute & verify:
+        assert!(validate_scaling_rules("scale down", &rules(Some(300), vec![policy(1, 60)])).is_ok());
+        assert!(validate_scaling_rules("scale down", &rules(Some(7200), vec![])).is_err());
+        assert!(validate_scaling_rules("scale up", &rules(None, vec![policy(0, 60)])).is_err());
+        assert!(validate_scaling_rules("scale up", &rules(None, vec![policy(4, 3600)])).is_err());
+    }
+
+    #[test]
+    fn test_is_custom_metric_listed() {
+        // setup:
+        let listed_metrics = KubernetesApiMetricsResources {
+            resources: vec![
+                MetricResource {
+                    name: "pods/http_requests_per_second".to_string(),
+                },
+                MetricResource {
+                    name: "namespaces/queue_length".to_string(),
+                },
+            ],
+        };
+
+        // execute & verify:
+        assert!(is_custom_metric_listed(&listed_metrics, "http_requests_per_second"));
+        assert!(!is_custom_metric_listed(&listed_metrics, "queue_length"));
+        assert!(!is_custom_metric_listed(&listed_metrics, "unknown"));
+    }
+}
@@ -0,0 +1,326 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::cloud_provider::kubernetes::kube_list_secrets;
+use crate::cloud_provider::models::EnvironmentVariable;
+use crate::cloud_provider::service::{Action, DatabaseType as ServiceDatabaseType, Service};
+use crate::cloud_provider::DeploymentTarget;
+use crate::constants::KUBECONFIG;
+use crate::deployment_action::database_backup::{
+    backup_extension, copy_dump_from_pod, dump_command, primary_pod_selector, restore_dump, running_pod_name, DumpScope,
+};
+use crate::deployment_report::logger::get_loggers;
+use crate::errors::{CommandError, EngineError};
+use crate::events::EventDetails;
+use crate::io_models::database::DatabaseOptions;
+use crate::models::database::{Container, Database, DatabaseType};
+use crate::models::types::CloudProvider;
+use crate::runtime::block_on;
+use crate::utilities::to_short_id;
+use k8s_openapi::api::core::v1::PersistentVolumeClaim;
+use kube::api::{Patch, PatchParams};
+use kube::Api;
+use serde_json::json;
+
+const CLONE_FILE: &str = "/tmp/qovery-clone";
+/// Annotation of the volume of a cloned database, tracking the copy of the data of the source database
+const CLONE_STATUS_ANNOTATION: &str = "qovery.com/clone-status";
+const CLONE_STATUS_PENDING: &str = "pending";
+const CLONE_STATUS_DONE: &str = "done";
+
+/// Environment variables of a cloned service: the ones of the service it is cloned from, overridden by its own.
+/// Source variables are only merged on the first deployment of the service, so later changes of the source environment
+/// do not leak into the clone.
+/// Returns None if the service is not cloned, or has already been deployed.
+///
+/// `secret_label`: label holding the service long id on the Kubernetes secret of its environment variables
+pub(super) fn cloned_environment_variables(
+    service: &dyn Service,
+    target: &DeploymentTarget,
+    secret_label: &str,
+    environment_variables: &[EnvironmentVariable],
+    event_details: EventDetails,
+) -> Result<Option<Vec<EnvironmentVariable>>, EngineError> {
+    let clone_source = match &target.environment.clone_source {
+        Some(clone_source) => clone_source,
+        None => return Ok(None),
+    };
+    let source_long_id = match clone_source.source_service_long_id(service.long_id()) {
+        Some(source_long_id) => source_long_id,
+        None => return Ok(None),
+    };
+
+    let to_error = |err: CommandError| {
+        EngineError::new_environment_cannot_be_cloned(
+            event_details.clone(),
+            service.name().to_string(),
+            clone_source.long_id.to_string(),
+            err,
+        )
+    };
+
+    // the secret of its environment variables only exists once the service has been deployed
+    let own_secrets = block_on(kube_list_secrets(
+        &target.kube,
+        target.environment.namespace(),
+        &format!("{}={}", secret_label, service.long_id()),
+    ))
+    .map_err(to_error)?;
+    if !own_secrets.items.is_empty() {
+        return Ok(None);
+    }
+
+    let secrets = block_on(kube_list_secrets(
+        &target.kube,
+        clone_source.namespace(),
+        &format!("{}={}", secret_label, source_long_id),
+    ))
+    .map_err(to_error)?;
+
+    // values are kept base64 encoded, as expected by the secret templates
+    let source_variables: BTreeMap<String, String> = secrets
+        .into_iter()
+        .filter(|secret| secret.type_.as_deref() == Some("Opaque"))
+        .flat_map(|secret| secret.data.unwrap_or_default())
+        .map(|(key, value)| (key, base64::encode(value.0)))
+        .collect();
+
+    Ok(Some(merge_environment_variables(environment_variables, source_variables)))
+}
+
+/// Copy the data of the database the given one is cloned from.
+/// The copy starts on the creation of the database, and is retried on next deployments until it succeeds: its progress
+/// is recorded on the volume of the database, so data of a database which has been cloned are never replaced.
+pub(super) fn clone_database_data<C, T>(
+    db: &Database<C, Container, T>,
+    target: &DeploymentTarget,
+    event_details: EventDetails,
+    is_new_database: bool,
+) -> Result<(), EngineError>
+where
+    C: CloudProvider,
+    T: DatabaseType<C, Container, DatabaseOptions = DatabaseOptions>,
+{
+    let clone_source = match &target.environment.clone_source {
+        Some(clone_source) => clone_source,
+        None => return Ok(()),
+    };
+    let source_long_id = match clone_source.source_service_long_id(&db.long_id) {
+        Some(source_long_id) => source_long_id,
+        None => return Ok(()),
+    };
+    let to_error = |err: CommandError| {
+        EngineError::new_environment_cannot_be_cloned(
+            event_details.clone(),
+            db.name().to_string(),
+            clone_source.long_id.to_string(),
+            err,
+        )
+    };
+
+    let pvcs: Api<PersistentVolumeClaim> = Api::namespaced(target.kube.clone(), target.environment.namespace());
+    let pvc_name = format!("data-{}-0", db.sanitized_name());
+    let set_clone_status = |status: &str| {
+        let patch = Patch::Merge(json!({ "metadata": { "annotations": { CLONE_STATUS_ANNOTATION: status } } }));
+        block_on(pvcs.patch(&pvc_name, &PatchParams::default(), &patch))
+            .map(|_| ())
+            .map_err(|err| to_error(CommandError::new_from_safe_message(err.to_string())))
+    };
+    if !is_new_database {
+        let clone_status = block_on(pvcs.get_opt(&pvc_name))
+            .map_err(|err| to_error(CommandError::new_from_safe_message(err.to_string())))?
+            .and_then(|pvc| pvc.metadata.annotations?.remove(CLONE_STATUS_ANNOTATION));
+        if !is_clone_to_retry(clone_status.as_deref()) {
+            return Ok(());
+        }
+    }
+
+    let logger = get_loggers(db, Action::Create);
+    let (dump_command, extension) = match (
+        source_database_credentials(T::db_type()).and_then(|(login, database_name)| {
+            dump_command(
+                T::db_type(),
+                DumpScope::Database,
+                "127.0.0.1",
+                db.private_port,
+                login,
+                database_name,
+                CLONE_FILE,
+            )
+        }),
+        backup_extension(T::db_type(), DumpScope::Database),
+    ) {
+        (Some(dump_command), Some(extension)) => (dump_command, extension),
+        _ => {
+            (logger.send_progress)(format!(
+                "⚠️ Data of database {} are not copied, {} databases cannot be dumped",
+                db.name(),
+                T::db_type().to_string()
+            ));
+            return Ok(());
+        }
+    };
+
+    set_clone_status(CLONE_STATUS_PENDING)?;
+    (logger.send_progress)(format!(
+        "🧬 Copying data of database {} from environment {}",
+        db.name(),
+        clone_source.long_id
+    ));
+    let kubeconfig = target.kubernetes.get_kubeconfig_file_path()?;
+    let mut envs = vec![(KUBECONFIG, kubeconfig.as_str())];
+    envs.extend(target.kubernetes.cloud_provider().credentials_environment_variables());
+
+    let source_namespace = clone_source.namespace();
+    let source_pod = running_pod_name(
+        &kubeconfig,
+        &envs,
+        source_namespace,
+        &primary_pod_selector(&format!("databaseId={}", to_short_id(source_long_id))),
+    )
+    .map_err(to_error)?;
+
+    let dump_path = Path::new(&db.workspace_directory())
+        .join(format!("qovery-clone.{}", extension))
+        .to_str()
+        .unwrap_or_default()
+        .to_string();
+    copy_dump_from_pod(&envs, source_namespace, &source_pod, &dump_command, CLONE_FILE, &dump_path)
+        .map_err(to_error)?;
+
+    let result = restore_dump(
+        db,
+        target,
+        &kubeconfig,
+        &envs,
+        &dump_path,
+        DumpScope::Database,
+        &logger.send_progress,
+    );
+    let _ = fs::remove_file(&dump_path);
+    result.map_err(to_error)?;
+    set_clone_status(CLONE_STATUS_DONE)?;
+
+    (logger.send_success)(format!(
+        "✅ Data of database {} has been copied from environment {}",
+        db.name(),
+        clone_source.long_id
+    ));
+    Ok(())
+}
+
+/// Only a copy which has been started but has not succeeded is retried, databases created before copies were tracked
+/// are left untouched
+fn is_clone_to_retry(clone_status: Option<&str>) -> bool {
+    clone_status == Some(CLONE_STATUS_PENDING)
+}
+
+/// Login and database name of the database a clone is copied from.
+/// The source database may use other credentials, they are read from its container environment.
+fn source_database_credentials(db_type: ServiceDatabaseType) -> Option<(&'static str, &'static str)> {
+    match db_type {
+        ServiceDatabaseType::PostgreSQL => Some(("\"$POSTGRES_USER\"", "\"$POSTGRES_DB\"")),
+        // all databases are dumped with the root user
+        ServiceDatabaseType::MySQL => Some(("root", "\"$MYSQL_DATABASE\"")),
+        ServiceDatabaseType::MongoDB => Some(("root", "admin")),
+        ServiceDatabaseType::Redis => Some(("default", "0")),
+        ServiceDatabaseType::OpenSearch
+        | ServiceDatabaseType::ClickHouse
+        | ServiceDatabaseType::RabbitMQ
+        | ServiceDatabaseType::Kafka => None,
+    }
+}
+
+/// Own environment variables first, then the ones of the source service which are not overridden
+fn merge_environment_variables(
+    environment_variables: &[EnvironmentVariable],
+    source_variables: BTreeMap<String, String>,
+) -> Vec<EnvironmentVariable> {
+    let mut merged = environment_variables.to_vec();
+    for (key, value) in source_variables {
+        if !environment_variables.iter().any(|ev| ev.key == key) {
+            merged.push(EnvironmentVariable { key, value });
+        }
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cloud_provider::models::EnvironmentVariable;
+    use crate::cloud_provider::service::DatabaseType as ServiceDatabaseType;
+    use crate::deployment_action::clone_environment::{
+        is_clone_to_retry, merge_environment_variables, source_database_credentials, CLONE_STATUS_DONE,
+        CLONE_STATUS_PENDING,
+    };
+    use crate::deployment_action::database_backup::{dump_command, DumpScope};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_merge_environment_variables() {
+        // setup:
+        let environment_variables = vec![EnvironmentVariable {
+            key: "DATABASE_URL".to_string(),
+            value: "Y2xvbmU=".to_string(),
+        }];
+        let source_variables = BTreeMap::from([
+            ("DATABASE_URL".to_string(), "c291cmNl".to_string()),
+            ("API_KEY".to_string(), "a2V5".to_string()),
+        ]);
+
+        // execute:
+        let merged = merge_environment_variables(&environment_variables, source_variables);
+
+        // verify:
+        assert_eq!(
+            merged,
+            vec![
+                EnvironmentVariable {
+                    key: "DATABASE_URL".to_string(),
+                    value: "Y2xvbmU=".to_string(),
+                },
+                EnvironmentVariable {
+                    key: "API_KEY".to_string(),
+                    value: "a2V5".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_source_database_credentials() {
+        // setup:
+        let dump = |db_type| {
+            source_database_credentials(db_type).and_then(|(login, database_name)| {
+                dump_command(
+                    db_type,
+                    DumpScope::Database,
+                    "127.0.0.1",
+                    5432,
+                    login,
+                    database_name,
+                    "/tmp/dump",
+                )
+            })
+        };
+
+        // execute & verify:
+        assert!(dump(ServiceDatabaseType::PostgreSQL)
+            .unwrap()
+            .contains("-U \"$POSTGRES_USER\" -d \"$POSTGRES_DB\""));
+        assert!(!dump(ServiceDatabaseType::MySQL).unwrap().contains("POSTGRES"));
+        assert!(!dump(ServiceDatabaseType::MongoDB).unwrap().contains("POSTGRES"));
+        assert!(!dump(ServiceDatabaseType::Redis).unwrap().contains("POSTGRES"));
+        assert!(dump(ServiceDatabaseType::OpenSearch).is_none());
+        assert!(dump(ServiceDatabaseType::Kafka).is_none());
+    }
+
+    #[test]
+    fn test_is_clone_to_retry() {
+        assert!(is_clone_to_retry(Some(CLONE_STATUS_PENDING)));
+        assert!(!is_clone_to_retry(Some(CLONE_STATUS_DONE)));
+        assert!(!is_clone_to_retry(None));
+    }
+}
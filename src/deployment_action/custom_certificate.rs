@@ -0,0 +1,350 @@
+use std::collections::BTreeMap;
+
+use k8s_openapi::api::core::v1::Secret;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use k8s_openapi::ByteString;
+use kube::api::{DeleteParams, ListParams, Patch, PatchParams};
+use kube::Api;
+use openssl::asn1::{Asn1Time, Asn1TimeRef};
+use openssl::nid::Nid;
+use openssl::pkey::PKey;
+use openssl::x509::X509;
+use serde::Deserialize;
+
+use crate::cloud_provider::service::{Action, Service};
+use crate::cloud_provider::DeploymentTarget;
+use crate::deployment_report::logger::get_loggers;
+use crate::errors::{CommandError, EngineError};
+use crate::events::{EngineEvent, EventDetails, EventMessage};
+use crate::io_models::router::CustomDomainCertificate;
+use crate::kubers_utils::kube_delete_all_from_selector;
+use crate::models::router::{Router, RouterService};
+use crate::models::types::CloudProvider;
+use crate::runtime::block_on;
+use crate::secret_manager::vault::QVaultClient;
+use itertools::Itertools;
+
+const EXPIRATION_WARNING_DAYS: i32 = 30;
+const FIELD_MANAGER: &str = "qovery-engine";
+
+/// PEM encoded certificate chain and private key supplied by the user for a custom domain
+#[derive(Deserialize)]
+struct CustomCertificate {
+    certificate: String,
+    private_key: String,
+}
+
+/// Store certificates supplied by the user for custom domains into TLS secrets of the router namespace.
+/// Must be done before deploying the router ingress, as it references those secrets.
+pub(super) fn deploy_custom_certificates<T: CloudProvider>(
+    router: &Router<T>,
+    target: &DeploymentTarget,
+    event_details: &EventDetails,
+) -> Result<(), EngineError>
+where
+    Router<T>: RouterService,
+{
+    let logger = get_loggers(router, Action::Create);
+    let secrets: Api<Secret> = Api::namespaced(target.kube.clone(), target.environment.namespace());
+    let now = Asn1Time::days_from_now(0).map_err(|err| {
+        EngineError::new_router_cannot_apply_custom_certificate(
+            event_details.clone(),
+            String::new(),
+            CommandError::new_from_safe_message(err.to_string()),
+        )
+    })?;
+
+    for custom_domain in &router.custom_domains {
+        let certificate_source = match &custom_domain.certificate {
+            Some(certificate_source) => certificate_source,
+            None => continue,
+        };
+        let domain = custom_domain.domain.as_str();
+        let certificate = get_custom_certificate(certificate_source, event_details)?;
+        let expires_in_days = validate_certificate(domain, &certificate, &now).map_err(|reason| {
+            EngineError::new_router_invalid_custom_certificate(event_details.clone(), domain.to_string(), reason)
+        })?;
+
+        if expires_in_days < EXPIRATION_WARNING_DAYS {
+            router.logger().log(EngineEvent::Warning(
+                event_details.clone(),
+                EventMessage::new_from_safe(format!(
+                    "⚠️ TLS certificate of domain {} expires in {} days, please renew it",
+                    domain, expires_in_days
+                )),
+            ));
+        }
+
+        let secret_name = router.custom_certificate_secret_name(domain);
+        let secret = Secret {
+            metadata: ObjectMeta {
+                name: Some(secret_name.clone()),
+                labels: Some(BTreeMap::from([("routerLongId".to_string(), router.long_id.to_string())])),
+                ..Default::default()
+            },
+            type_: Some("kubernetes.io/tls".to_string()),
+            data: Some(BTreeMap::from([
+                ("tls.crt".to_string(), ByteString(certificate.certificate.into_bytes())),
+                ("tls.key".to_string(), ByteString(certificate.private_key.into_bytes())),
+            ])),
+            ..Default::default()
+        };
+        block_on(secrets.patch(&secret_name, &PatchParams::apply(FIELD_MANAGER).force(), &Patch::Apply(&secret)))
+            .map_err(|err| {
+                EngineError::new_router_cannot_apply_custom_certificate(
+                    event_details.clone(),
+                    domain.to_string(),
+                    CommandError::new_from_safe_message(err.to_string()),
+                )
+            })?;
+
+        (logger.send_progress)(format!("🔒 TLS certificate of domain {} has been applied", domain));
+    }
+
+    Ok(())
+}
+
+/// Remove TLS secrets holding the certificates supplied by the user for custom domains
+pub(super) fn delete_custom_certificates<T: CloudProvider>(
+    router: &Router<T>,
+    target: &DeploymentTarget,
+    event_details: &EventDetails,
+) -> Result<(), EngineError>
+where
+    Router<T>: RouterService,
+{
+    block_on(kube_delete_all_from_selector::<Secret>(
+        &target.kube,
+        &format!("routerLongId={}", router.long_id),
+        target.environment.namespace(),
+    ))
+    .map_err(|err| {
+        EngineError::new_router_cannot_apply_custom_certificate(
+            event_details.clone(),
+            router.custom_domains.iter().map(|cd| cd.domain.as_str()).join(", "),
+            CommandError::new_from_safe_message(err.to_string()),
+        )
+    })
+}
+
+/// Remove TLS secrets of certificates no longer supplied, i.e the domain has been removed from the router
+/// or its certificate is now issued by cert-manager. Must be done once the router ingress does not reference them anymore.
+pub(super) fn delete_stale_custom_certificates<T: CloudProvider>(
+    router: &Router<T>,
+    target: &DeploymentTarget,
+    event_details: &EventDetails,
+) -> Result<(), EngineError>
+where
+    Router<T>: RouterService,
+{
+    let secrets: Api<Secret> = Api::namespaced(target.kube.clone(), target.environment.namespace());
+    let to_error = |domain: String, err: kube::Error| {
+        EngineError::new_router_cannot_apply_custom_certificate(
+            event_details.clone(),
+            domain,
+            CommandError::new_from_safe_message(err.to_string()),
+        )
+    };
+    let expected_secrets: Vec<String> = router
+        .custom_domains
+        .iter()
+        .filter(|cd| cd.certificate.is_some())
+        .map(|cd| router.custom_certificate_secret_name(&cd.domain))
+        .collect();
+
+    let deployed_secrets =
+        block_on(secrets.list(&ListParams::default().labels(&format!("routerLongId={}", router.long_id))))
+            .map_err(|err| to_error(String::new(), err))?;
+    for secret_name in deployed_secrets
+        .into_iter()
+        .filter_map(|secret| secret.metadata.name)
+        .filter(|name| !expected_secrets.contains(name))
+    {
+        block_on(secrets.delete(&secret_name, &DeleteParams::default())).map_err(|err| to_error(secret_name, err))?;
+    }
+
+    Ok(())
+}
+
+fn get_custom_certificate(
+    certificate_source: &CustomDomainCertificate,
+    event_details: &EventDetails,
+) -> Result<CustomCertificate, EngineError> {
+    match certificate_source {
+        CustomDomainCertificate::Inline {
+            certificate,
+            private_key,
+        } => Ok(CustomCertificate {
+            certificate: certificate.clone(),
+            private_key: private_key.clone(),
+        }),
+        CustomDomainCertificate::Vault { mount, path } => QVaultClient::new(event_details.clone())?
+            .get_organization_secret::<CustomCertificate>(event_details.organisation_id(), mount, path)
+            .map_err(|err| EngineError::new_vault_secret_could_not_be_retrieved(event_details.clone(), err)),
+    }
+}
+
+/// Check that a certificate can be served for the domain.
+/// Returns the number of days before it expires, or why it cannot be used.
+fn validate_certificate(domain: &str, certificate: &CustomCertificate, now: &Asn1TimeRef) -> Result<i32, String> {
+    let chain = X509::stack_from_pem(certificate.certificate.as_bytes())
+        .map_err(|err| format!("certificate chain is not valid PEM: {}", err))?;
+    let leaf = chain.first().ok_or_else(|| "certificate chain is empty".to_string())?;
+    let private_key = PKey::private_key_from_pem(certificate.private_key.as_bytes())
+        .map_err(|err| format!("private key is not valid PEM: {}", err))?;
+
+    let public_key = leaf
+        .public_key()
+        .map_err(|err| format!("cannot read certificate public key: {}", err))?;
+    if !public_key.public_eq(&private_key) {
+        return Err("private key does not match the certificate".to_string());
+    }
+
+    if leaf.not_before() > now {
+        return Err(format!("certificate is not valid before {}", leaf.not_before()));
+    }
+    if leaf.not_after() < now {
+        return Err(format!("certificate has expired on {}", leaf.not_after()));
+    }
+
+    let certificate_domains = certificate_domains(leaf);
+    if !certificate_domains.iter().any(|name| domain_matches(name, domain)) {
+        return Err(format!(
+            "certificate is issued for {}, not for {}",
+            certificate_domains.join(", "),
+            domain
+        ));
+    }
+
+    now.diff(leaf.not_after())
+        .map(|diff| diff.days)
+        .map_err(|err| format!("cannot read certificate expiration: {}", err))
+}
+
+/// DNS names of the certificate, the common name is only used when there is no subject alternative name
+fn certificate_domains(certificate: &X509) -> Vec<String> {
+    let alt_names = certificate
+        .subject_alt_names()
+        .map(|names| {
+            names
+                .iter()
+                .filter_map(|name| name.dnsname().map(|dns| dns.to_string()))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    if !alt_names.is_empty() {
+        return alt_names;
+    }
+
+    certificate
+        .subject_name()
+        .entries_by_nid(Nid::COMMONNAME)
+        .filter_map(|entry| entry.data().as_utf8().ok().map(|cn| cn.to_string()))
+        .collect()
+}
+
+/// A wildcard only covers a single label: *.example.com matches www.example.com, not example.com or a.b.example.com
+fn domain_matches(certificate_domain: &str, domain: &str) -> bool {
+    let certificate_domain = certificate_domain.to_lowercase();
+    let domain = domain.to_lowercase();
+
+    match certificate_domain.strip_prefix("*.") {
+        Some(parent) => match domain.split_once('.') {
+            Some((label, domain_parent)) => !label.is_empty() && domain_parent == parent,
+            None => false,
+        },
+        None => certificate_domain == domain,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::deployment_action::custom_certificate::{domain_matches, validate_certificate, CustomCertificate};
+    use openssl::asn1::Asn1Time;
+    use openssl::ec::{EcGroup, EcKey};
+    use openssl::hash::MessageDigest;
+    use openssl::nid::Nid;
+    use openssl::pkey::{PKey, Private};
+    use openssl::x509::extension::SubjectAlternativeName;
+    use openssl::x509::{X509Builder, X509NameBuilder};
+
+    fn private_key() -> PKey<Private> {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap()
+    }
+
+    fn self_signed_certificate(key: &PKey<Private>, domain: &str, valid_days: u32) -> String {
+        let mut name = X509NameBuilder::new().unwrap();
+        name.append_entry_by_nid(Nid::COMMONNAME, domain).unwrap();
+        let name = name.build();
+
+        let mut builder = X509Builder::new().unwrap();
+        builder.set_version(2).unwrap();
+        builder.set_subject_name(&name).unwrap();
+        builder.set_issuer_name(&name).unwrap();
+        builder.set_pubkey(key).unwrap();
+        builder.set_not_before(&Asn1Time::days_from_now(0).unwrap()).unwrap();
+        builder
+            .set_not_after(&Asn1Time::days_from_now(valid_days).unwrap())
+            .unwrap();
+        let alt_names = SubjectAlternativeName::new()
+            .dns(domain)
+            .build(&builder.x509v3_context(None, None))
+            .unwrap();
+        builder.append_extension(alt_names).unwrap();
+        builder.sign(key, MessageDigest::sha256()).unwrap();
+
+        String::from_utf8(builder.build().to_pem().unwrap()).unwrap()
+    }
+
+    fn custom_certificate(key: &PKey<Private>, certificate: String) -> CustomCertificate {
+        CustomCertificate {
+            certificate,
+            private_key: String::from_utf8(key.private_key_to_pem_pkcs8().unwrap()).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_validate_certificate() {
+        // setup:
+        let key = private_key();
+        let certificate = custom_certificate(&key, self_signed_certificate(&key, "*.example.com", 90));
+        let now = Asn1Time::days_from_now(0).unwrap();
+
+        // execute & verify:
+        let expires_in_days = validate_certificate("www.example.com", &certificate, &now).unwrap();
+        assert!((89..=90).contains(&expires_in_days));
+        assert!(validate_certificate("example.com", &certificate, &now)
+            .unwrap_err()
+            .contains("not for example.com"));
+
+        let in_a_year = Asn1Time::days_from_now(365).unwrap();
+        assert!(validate_certificate("www.example.com", &certificate, &in_a_year)
+            .unwrap_err()
+            .contains("expired"));
+    }
+
+    #[test]
+    fn test_validate_certificate_with_invalid_key() {
+        // setup:
+        let key = private_key();
+        let other_key = private_key();
+        let certificate = custom_certificate(&other_key, self_signed_certificate(&key, "example.com", 90));
+        let now = Asn1Time::days_from_now(0).unwrap();
+
+        // execute:
+        let result = validate_certificate("example.com", &certificate, &now);
+
+        // verify:
+        assert_eq!(result, Err("private key does not match the certificate".to_string()));
+    }
+
+    #[test]
+    fn test_domain_matches() {
+        assert!(domain_matches("example.com", "example.com"));
+        assert!(domain_matches("*.example.com", "WWW.example.com"));
+        assert!(!domain_matches("*.example.com", "example.com"));
+        assert!(!domain_matches("*.example.com", "a.b.example.com"));
+        assert!(!domain_matches("www.example.com", "api.example.com"));
+    }
+}
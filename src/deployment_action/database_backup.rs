@@ -0,0 +1,1040 @@
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::cloud_provider::service::{Action, DatabaseType as ServiceDatabaseType, Service};
+use crate::cloud_provider::DeploymentTarget;
+use crate::cmd::kubectl::{kubectl_exec_get_pods, kubectl_exec_with_output};
+use crate::cmd::structs::KubernetesPodStatusPhase;
+use crate::constants::KUBECONFIG;
+use crate::deployment_report::logger::get_loggers;
+use crate::errors::{CommandError, EngineError};
+use crate::events::{EngineEvent, EnvironmentStep, EventDetails, EventMessage, Stage};
+use crate::io_models::database::{DatabaseBackupPolicy, DatabaseOptions};
+use crate::models::database::{Container, Database, DatabaseType, Managed};
+use crate::models::types::CloudProvider;
+use crate::object_storage::S3Access;
+
+const BACKUP_TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+const AWS_CLI_IMAGE: &str = "amazon/aws-cli:2.8.2";
+const RESTORE_FILE: &str = "/tmp/qovery-restore";
+const BACKUP_FILE: &str = "/tmp/qovery-backup";
+/// Label set by the database charts on the pods of the read replicas
+const DATABASE_ROLE_LABEL: &str = "qovery.com/database-role";
+
+/// What a dump holds
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum DumpScope {
+    /// the database of the service
+    Database,
+    /// every database of the instance along with roles and grants. Dumps of other engines than PostgreSQL
+    /// already hold the whole instance
+    Instance,
+}
+
+/// A logical backup of a database, stored into the cluster object storage
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DatabaseBackup {
+    /// object storage key, i.e: `<database long id>/20221018T030000Z.dump`
+    pub key: String,
+    pub created_at: DateTime<Utc>,
+}
+
+pub trait DatabaseBackupAction {
+    /// backups of the database, oldest first
+    fn list_backups(&self, target: &DeploymentTarget) -> Result<Vec<DatabaseBackup>, EngineError>;
+    /// replace the content of the database by the one of the backup
+    fn restore_backup(&self, target: &DeploymentTarget, backup: &DatabaseBackup) -> Result<(), EngineError>;
+}
+
+impl<C: CloudProvider, T: DatabaseType<C, Managed>> DatabaseBackupAction for Database<C, Managed, T> {
+    fn list_backups(&self, _target: &DeploymentTarget) -> Result<Vec<DatabaseBackup>, EngineError> {
+        Err(EngineError::new_database_backups_not_supported(
+            self.get_event_details(Stage::Environment(EnvironmentStep::Deploy)),
+            self.name().to_string(),
+            "managed database".to_string(),
+        ))
+    }
+
+    fn restore_backup(&self, _target: &DeploymentTarget, _backup: &DatabaseBackup) -> Result<(), EngineError> {
+        Err(EngineError::new_database_backups_not_supported(
+            self.get_event_details(Stage::Environment(EnvironmentStep::Deploy)),
+            self.name().to_string(),
+            "managed database".to_string(),
+        ))
+    }
+}
+
+impl<C: CloudProvider, T: DatabaseType<C, Container, DatabaseOptions = DatabaseOptions>> DatabaseBackupAction
+    for Database<C, Container, T>
+{
+    fn list_backups(&self, target: &DeploymentTarget) -> Result<Vec<DatabaseBackup>, EngineError> {
+        let event_details = self.get_event_details(Stage::Environment(EnvironmentStep::Deploy));
+        list_backups(target, &self.long_id, event_details)
+    }
+
+    fn restore_backup(&self, target: &DeploymentTarget, backup: &DatabaseBackup) -> Result<(), EngineError> {
+        let event_details = self.get_event_details(Stage::Environment(EnvironmentStep::Deploy));
+        let logger = get_loggers(self, Action::Create);
+        let to_error = |err: CommandError| {
+            EngineError::new_database_cannot_restore_backup(
+                event_details.clone(),
+                self.name().to_string(),
+                backup.key.clone(),
+                err,
+            )
+        };
+
+        if !backup.key.starts_with(&backup_prefix(&self.long_id)) {
+            return Err(to_error(CommandError::new_from_safe_message(format!(
+                "Backup `{}` has not been taken from this database",
+                backup.key
+            ))));
+        }
+
+        (logger.send_progress)(format!("⏪ Restoring database {} from backup {}", self.name(), backup.key));
+        let object_storage = target.kubernetes.config_file_store();
+        let (backup_path, _) = object_storage
+            .get(&backup_bucket_name(target.kubernetes.id()), &backup.key, false)
+            .map_err(|err| EngineError::new_object_storage_error(event_details.clone(), err))?;
+
+        let kubeconfig = target.kubernetes.get_kubeconfig_file_path()?;
+        let mut envs = vec![(KUBECONFIG, kubeconfig.as_str())];
+        envs.extend(target.kubernetes.cloud_provider().credentials_environment_variables());
+
+        let scope = backup_scope(T::db_type(), &backup.key);
+        let result = restore_dump(self, target, &kubeconfig, &envs, &backup_path, scope, &logger.send_progress);
+        let _ = fs::remove_file(&backup_path);
+        result.map_err(to_error)?;
+
+        (logger.send_success)(format!(
+            "✅ Database {} has been restored from backup {}",
+            self.name(),
+            backup.key
+        ));
+        Ok(())
+    }
+}
+
+/// Name of a running pod of the database
+pub(super) fn running_pod_name(
+    kubeconfig: &str,
+    envs: &[(&str, &str)],
+    namespace: &str,
+    selector: &str,
+) -> Result<String, CommandError> {
+    let pods = kubectl_exec_get_pods(kubeconfig, Some(namespace), Some(selector), envs.to_vec())?;
+
+    pods.items
+        .iter()
+        .find(|pod| pod.status.phase == KubernetesPodStatusPhase::Running)
+        .map(|pod| pod.metadata.name.clone())
+        .ok_or_else(|| {
+            CommandError::new_from_safe_message(format!(
+                "No running database pod found in namespace {} for selector {}",
+                namespace, selector
+            ))
+        })
+}
+
+/// Selector of the primary pod of a database, read replicas must not be targeted by commands writing data
+pub(super) fn primary_pod_selector(selector: &str) -> String {
+    format!("{},{}!=replica", selector, DATABASE_ROLE_LABEL)
+}
+
+/// Replace the content of the container database by the one of a dump made by `dump_command`
+pub(super) fn restore_dump<C, T>(
+    db: &Database<C, Container, T>,
+    target: &DeploymentTarget,
+    kubeconfig: &str,
+    envs: &[(&str, &str)],
+    dump_path: &str,
+    scope: DumpScope,
+    send_progress: &dyn Fn(String),
+) -> Result<(), CommandError>
+where
+    C: CloudProvider,
+    T: DatabaseType<C, Container, DatabaseOptions = DatabaseOptions>,
+{
+    // passwords are read from the database container environment, so they never appear in the command line
+    let restore_command =
+        restore_command(T::db_type(), scope, db.private_port, &db.options.login, db.name(), RESTORE_FILE).ok_or_else(
+            || {
+                CommandError::new_from_safe_message(format!(
+                    "{} databases cannot be restored from a dump",
+                    T::db_type().to_string()
+                ))
+            },
+        )?;
+    let namespace = target.environment.namespace();
+    let pod_name = running_pod_name(kubeconfig, envs, namespace, &primary_pod_selector(&db.selector()))?;
+
+    let destination = format!("{}/{}:{}", namespace, pod_name, RESTORE_FILE);
+    kubectl_exec_with_output(
+        vec!["cp", dump_path, destination.as_str()],
+        envs.to_vec(),
+        &mut |line| info!("{}", line),
+        &mut |line| warn!("{}", line),
+    )?;
+
+    kubectl_exec_with_output(
+        vec![
+            "exec",
+            "-n",
+            namespace,
+            pod_name.as_str(),
+            "--",
+            "bash",
+            "-c",
+            restore_command.as_str(),
+        ],
+        envs.to_vec(),
+        &mut |line| send_progress(line),
+        &mut |line| send_progress(line),
+    )
+}
+
+/// Run `dump_command` into the database pod, then copy the dump it writes at `remote_file` to `local_path`.
+/// The dump never stays into the database container.
+pub(super) fn copy_dump_from_pod(
+    envs: &[(&str, &str)],
+    namespace: &str,
+    pod_name: &str,
+    dump_command: &str,
+    remote_file: &str,
+    local_path: &str,
+) -> Result<(), CommandError> {
+    let exec_in_pod = |command: &str| {
+        kubectl_exec_with_output(
+            vec!["exec", "-n", namespace, pod_name, "--", "bash", "-c", command],
+            envs.to_vec(),
+            &mut |line| info!("{}", line),
+            &mut |line| warn!("{}", line),
+        )
+    };
+
+    let source = format!("{}/{}:{}", namespace, pod_name, remote_file);
+    let result = exec_in_pod(dump_command).and_then(|_| {
+        kubectl_exec_with_output(
+            vec!["cp", source.as_str(), local_path],
+            envs.to_vec(),
+            &mut |line| info!("{}", line),
+            &mut |line| warn!("{}", line),
+        )
+    });
+    let _ = exec_in_pod(&format!("rm -f {}", remote_file));
+
+    result
+}
+
+/// Dump the running container database right away, the dump is stored along the scheduled backups
+pub(super) fn take_backup<C, T>(
+    db: &Database<C, Container, T>,
+    target: &DeploymentTarget,
+    kubeconfig: &str,
+    envs: &[(&str, &str)],
+    scope: DumpScope,
+) -> Result<DatabaseBackup, CommandError>
+where
+    C: CloudProvider,
+    T: DatabaseType<C, Container, DatabaseOptions = DatabaseOptions>,
+{
+    let dump_command = dump_command(
+        T::db_type(),
+        scope,
+        "127.0.0.1",
+        db.private_port,
+        &db.options.login,
+        db.name(),
+        BACKUP_FILE,
+    );
+    let (dump_command, extension) = match (dump_command, backup_extension(T::db_type(), scope)) {
+        (Some(dump_command), Some(extension)) => (dump_command, extension),
+        _ => {
+            return Err(CommandError::new_from_safe_message(format!(
+                "{} databases cannot be dumped",
+                T::db_type().to_string()
+            )))
+        }
+    };
+    let key = format!(
+        "{}{}.{}",
+        backup_prefix(&db.long_id),
+        Utc::now().format(BACKUP_TIMESTAMP_FORMAT),
+        extension
+    );
+    let backup = parse_backup_key(&key)
+        .ok_or_else(|| CommandError::new_from_safe_message(format!("Invalid backup key `{}`", key)))?;
+
+    let namespace = target.environment.namespace();
+    let pod_name = running_pod_name(kubeconfig, envs, namespace, &primary_pod_selector(&db.selector()))?;
+    let dump_path = Path::new(&db.workspace_directory())
+        .join(format!("qovery-backup.{}", extension))
+        .to_str()
+        .unwrap_or_default()
+        .to_string();
+    let object_storage = target.kubernetes.config_file_store();
+    let bucket_name = backup_bucket_name(target.kubernetes.id());
+
+    let result =
+        copy_dump_from_pod(envs, namespace, &pod_name, &dump_command, BACKUP_FILE, &dump_path).and_then(|_| {
+            object_storage
+                .create_bucket(&bucket_name)
+                .and_then(|_| object_storage.put(&bucket_name, &backup.key, &dump_path))
+                .map_err(|err| {
+                    CommandError::new(
+                        "Cannot upload the database dump into the object storage".to_string(),
+                        Some(err.to_string()),
+                        None,
+                    )
+                })
+        });
+    let _ = fs::remove_file(&dump_path);
+    result?;
+
+    Ok(backup)
+}
+
+/// Create, or remove, the Kubernetes CronJob taking backups of the container database, and delete expired backups
+pub(super) fn schedule_backups<C, T>(
+    db: &Database<C, Container, T>,
+    target: &DeploymentTarget,
+    event_details: EventDetails,
+) -> Result<(), EngineError>
+where
+    C: CloudProvider,
+    T: DatabaseType<C, Container, DatabaseOptions = DatabaseOptions>,
+{
+    if !db.options.activate_backups {
+        return unschedule_backups(db, target, event_details);
+    }
+
+    let dump_command = dump_command(
+        T::db_type(),
+        DumpScope::Database,
+        &db.fqdn_id,
+        db.private_port,
+        &db.options.login,
+        db.name(),
+        "/backup/dump",
+    );
+    let (dump_command, extension) = match (dump_command, backup_extension(T::db_type(), DumpScope::Database)) {
+        (Some(dump_command), Some(extension)) => (dump_command, extension),
+        _ => {
+            return Err(EngineError::new_database_backups_not_supported(
+                event_details,
+                db.name().to_string(),
+                format!("{} databases have no logical backup tool", T::db_type().to_string()),
+            ))
+        }
+    };
+
+    let kubeconfig = target.kubernetes.get_kubeconfig_file_path()?;
+    let mut envs = vec![(KUBECONFIG, kubeconfig.as_str())];
+    envs.extend(target.kubernetes.cloud_provider().credentials_environment_variables());
+    let to_error = |err: CommandError| {
+        EngineError::new_database_cannot_schedule_backups(event_details.clone(), db.name().to_string(), err)
+    };
+    let job_name = backup_job_name(&db.sanitized_name());
+    let object_storage = target.kubernetes.config_file_store();
+    let bucket_name = backup_bucket_name(target.kubernetes.id());
+
+    // credentials are issued once, then kept in the backup secret for the next deployments
+    let s3_access = match existing_backup_access(&envs, target.environment.namespace(), &job_name).map_err(to_error)? {
+        Some(s3_access) => s3_access,
+        // the credentials are readable by any workload of the namespace, they can only write backups of this database
+        None => match object_storage
+            .issue_prefix_write_access(&bucket_name, &backup_prefix(&db.long_id))
+            .map_err(|err| EngineError::new_object_storage_error(event_details.clone(), err))?
+        {
+            Some(s3_access) => s3_access,
+            None => {
+                db.logger().log(EngineEvent::Warning(
+                    event_details,
+                    EventMessage::new_from_safe(format!(
+                        "⚠️ Backups of database {} are not scheduled: the cluster object storage cannot issue credentials restricted to them",
+                        db.name()
+                    )),
+                ));
+                return Ok(());
+            }
+        },
+    };
+    object_storage
+        .create_bucket(&bucket_name)
+        .map_err(|err| EngineError::new_object_storage_error(event_details.clone(), err))?;
+
+    let version = db.get_version(event_details.clone())?.matched_version().to_string();
+    let manifest = backup_cronjob_manifest(&BackupJob {
+        name: job_name,
+        namespace: target.environment.namespace().to_string(),
+        selector: db.selector(),
+        db_type: T::db_type(),
+        dump_command,
+        extension,
+        // same image as the database, so dump tools match the database version
+        image: format!("docker.io/bitnami/{}:{}", T::lib_directory_name(), version),
+        password: db.options.password.clone(),
+        policy: db.options.backup_policy.clone(),
+        bucket_name: bucket_name.clone(),
+        object_prefix: backup_prefix(&db.long_id),
+        s3_access,
+    });
+    let manifest_path = Path::new(&db.workspace_directory()).join("qovery-backup.json");
+    fs::write(&manifest_path, manifest.to_string()).map_err(|err| {
+        to_error(CommandError::new(
+            "Cannot write backup manifest".to_string(),
+            Some(err.to_string()),
+            None,
+        ))
+    })?;
+    let result = kubectl_exec_with_output(
+        vec!["apply", "-f", manifest_path.to_str().unwrap_or_default()],
+        envs,
+        &mut |line| info!("{}", line),
+        &mut |line| warn!("{}", line),
+    );
+    // the manifest contains credentials, it must not stay in the workspace
+    let _ = fs::remove_file(&manifest_path);
+    result.map_err(to_error)?;
+
+    delete_expired_backups(target, &db.long_id, &db.options.backup_policy, event_details.clone())
+}
+
+/// Object storage credentials of the backup secret of a database, if backups have already been scheduled
+fn existing_backup_access(
+    envs: &[(&str, &str)],
+    namespace: &str,
+    job_name: &str,
+) -> Result<Option<S3Access>, CommandError> {
+    let mut output = String::new();
+    kubectl_exec_with_output(
+        vec![
+            "get",
+            "secret",
+            job_name,
+            "-n",
+            namespace,
+            "--ignore-not-found",
+            "-o",
+            "json",
+        ],
+        envs.to_vec(),
+        &mut |line| output.push_str(&line),
+        &mut |line| warn!("{}", line),
+    )?;
+    if output.trim().is_empty() {
+        return Ok(None);
+    }
+
+    let secret: serde_json::Value = serde_json::from_str(&output).map_err(|err| {
+        CommandError::new(
+            "Cannot parse the database backup secret".to_string(),
+            Some(err.to_string()),
+            None,
+        )
+    })?;
+    Ok(backup_access_from_secret(&secret))
+}
+
+/// Credentials of a backup secret, none when the secret misses one of them
+fn backup_access_from_secret(secret: &serde_json::Value) -> Option<S3Access> {
+    let value = |key: &str| {
+        secret["data"][key]
+            .as_str()
+            .and_then(|value| base64::decode(value).ok())
+            .and_then(|value| String::from_utf8(value).ok())
+    };
+
+    Some(S3Access {
+        endpoint_url: value("endpoint-url").filter(|endpoint_url| !endpoint_url.is_empty()),
+        region: value("region")?,
+        access_key_id: value("access-key-id")?,
+        secret_access_key: value("secret-access-key")?,
+    })
+}
+
+/// Remove the backup CronJob of a deleted database and revoke its object storage credentials,
+/// its backups are kept until they expire
+pub(super) fn unschedule_backups<C, T>(
+    db: &Database<C, Container, T>,
+    target: &DeploymentTarget,
+    event_details: EventDetails,
+) -> Result<(), EngineError>
+where
+    C: CloudProvider,
+    T: DatabaseType<C, Container, DatabaseOptions = DatabaseOptions>,
+{
+    let kubeconfig = target.kubernetes.get_kubeconfig_file_path()?;
+    let mut envs = vec![(KUBECONFIG, kubeconfig.as_str())];
+    envs.extend(target.kubernetes.cloud_provider().credentials_environment_variables());
+    let job_name = backup_job_name(&db.sanitized_name());
+    let to_error = |err: CommandError| {
+        EngineError::new_database_cannot_schedule_backups(event_details.clone(), db.name().to_string(), err)
+    };
+
+    // credentials are only issued along with the backup secret, nothing to revoke for databases never backed up
+    let mut backup_resources = String::new();
+    kubectl_exec_with_output(
+        vec![
+            "get",
+            "-n",
+            target.environment.namespace(),
+            "cronjob,secret",
+            job_name.as_str(),
+            "--ignore-not-found",
+            "-o",
+            "name",
+        ],
+        envs.clone(),
+        &mut |line| backup_resources.push_str(&line),
+        &mut |line| warn!("{}", line),
+    )
+    .map_err(to_error)?;
+    if backup_resources.trim().is_empty() {
+        return Ok(());
+    }
+
+    kubectl_exec_with_output(
+        vec![
+            "delete",
+            "-n",
+            target.environment.namespace(),
+            "cronjob,secret",
+            job_name.as_str(),
+            "--ignore-not-found",
+        ],
+        envs,
+        &mut |line| info!("{}", line),
+        &mut |line| warn!("{}", line),
+    )
+    .map_err(to_error)?;
+
+    target
+        .kubernetes
+        .config_file_store()
+        .revoke_prefix_write_access(&backup_bucket_name(target.kubernetes.id()), &backup_prefix(&db.long_id))
+        .map_err(|err| EngineError::new_object_storage_error(event_details, err))
+}
+
+fn list_backups(
+    target: &DeploymentTarget,
+    database_long_id: &Uuid,
+    event_details: EventDetails,
+) -> Result<Vec<DatabaseBackup>, EngineError> {
+    let keys = target
+        .kubernetes
+        .config_file_store()
+        .list_objects(&backup_bucket_name(target.kubernetes.id()), &backup_prefix(database_long_id))
+        .map_err(|err| EngineError::new_object_storage_error(event_details, err))?;
+
+    let mut backups: Vec<DatabaseBackup> = keys.iter().filter_map(|key| parse_backup_key(key)).collect();
+    backups.sort_by_key(|backup| backup.created_at);
+    Ok(backups)
+}
+
+fn delete_expired_backups(
+    target: &DeploymentTarget,
+    database_long_id: &Uuid,
+    policy: &DatabaseBackupPolicy,
+    event_details: EventDetails,
+) -> Result<(), EngineError> {
+    let backups = list_backups(target, database_long_id, event_details.clone())?;
+    let bucket_name = backup_bucket_name(target.kubernetes.id());
+
+    for backup in expired_backups(&backups, Utc::now(), policy.retention_in_days) {
+        info!("Deleting expired database backup {}", backup.key);
+        target
+            .kubernetes
+            .config_file_store()
+            .ensure_file_is_absent(&bucket_name, &backup.key)
+            .map_err(|err| EngineError::new_object_storage_error(event_details.clone(), err))?;
+    }
+
+    Ok(())
+}
+
+fn backup_bucket_name(cluster_id: &str) -> String {
+    format!("qovery-db-backups-{}", cluster_id)
+}
+
+fn backup_prefix(database_long_id: &Uuid) -> String {
+    format!("{}/", database_long_id)
+}
+
+fn backup_job_name(database_sanitized_name: &str) -> String {
+    format!("{}-backup", database_sanitized_name)
+}
+
+/// Backups are named after their creation date, i.e: `<database long id>/20221018T030000Z.dump`
+fn parse_backup_key(key: &str) -> Option<DatabaseBackup> {
+    let file_name = key.rsplit('/').next()?;
+    let timestamp = file_name.split('.').next()?;
+    let created_at = NaiveDateTime::parse_from_str(timestamp, BACKUP_TIMESTAMP_FORMAT).ok()?;
+
+    Some(DatabaseBackup {
+        key: key.to_string(),
+        created_at: DateTime::from_utc(created_at, Utc),
+    })
+}
+
+/// Backups older than the retention, the most recent backup is always kept to be able to restore the database
+fn expired_backups(backups: &[DatabaseBackup], now: DateTime<Utc>, retention_in_days: u32) -> Vec<&DatabaseBackup> {
+    let most_recent = backups.iter().map(|backup| backup.created_at).max();
+    let expiration = now - Duration::days(retention_in_days as i64);
+
+    backups
+        .iter()
+        .filter(|backup| backup.created_at < expiration && Some(backup.created_at) != most_recent)
+        .collect()
+}
+
+/// Extension of the dumps, `None` when the database image ships no tool to take a logical backup
+pub(super) fn backup_extension(db_type: ServiceDatabaseType, scope: DumpScope) -> Option<&'static str> {
+    match db_type {
+        ServiceDatabaseType::PostgreSQL => match scope {
+            DumpScope::Database => Some("dump"),
+            DumpScope::Instance => Some("sql.gz"),
+        },
+        ServiceDatabaseType::MySQL => Some("sql.gz"),
+        ServiceDatabaseType::MongoDB => Some("archive.gz"),
+        ServiceDatabaseType::Redis => Some("rdb"),
+        ServiceDatabaseType::OpenSearch
+        | ServiceDatabaseType::ClickHouse
+        | ServiceDatabaseType::RabbitMQ
+        | ServiceDatabaseType::Kafka => None,
+    }
+}
+
+/// Scope of the dump of a backup, told by its extension
+fn backup_scope(db_type: ServiceDatabaseType, key: &str) -> DumpScope {
+    match (
+        backup_extension(db_type, DumpScope::Database),
+        backup_extension(db_type, DumpScope::Instance),
+    ) {
+        (Some(database), Some(instance)) if database != instance && key.ends_with(&format!(".{}", instance)) => {
+            DumpScope::Instance
+        }
+        _ => DumpScope::Database,
+    }
+}
+
+/// Environment variable holding the password in the database containers
+fn password_env_var(db_type: ServiceDatabaseType) -> &'static str {
+    match db_type {
+        ServiceDatabaseType::PostgreSQL => "POSTGRES_PASSWORD",
+        ServiceDatabaseType::MySQL => "MYSQL_ROOT_PASSWORD",
+        ServiceDatabaseType::MongoDB => "MONGODB_ROOT_PASSWORD",
+        ServiceDatabaseType::Redis => "REDIS_PASSWORD",
+        ServiceDatabaseType::OpenSearch => "OPENSEARCH_PASSWORD",
+        ServiceDatabaseType::ClickHouse => "CLICKHOUSE_ADMIN_PASSWORD",
+        ServiceDatabaseType::RabbitMQ => "RABBITMQ_PASSWORD",
+        ServiceDatabaseType::Kafka => "KAFKA_CLIENT_PASSWORDS",
+    }
+}
+
+pub(super) fn dump_command(
+    db_type: ServiceDatabaseType,
+    scope: DumpScope,
+    host: &str,
+    port: u16,
+    login: &str,
+    database_name: &str,
+    output_file: &str,
+) -> Option<String> {
+    let password = format!("\"${}\"", password_env_var(db_type));
+    let command = match db_type {
+        ServiceDatabaseType::PostgreSQL => match scope {
+            DumpScope::Database => format!(
+                "PGPASSWORD={} pg_dump -h {} -p {} -U {} -d {} -Fc -f {}",
+                password, host, port, login, database_name, output_file
+            ),
+            DumpScope::Instance => format!(
+                "PGPASSWORD={} pg_dumpall -h {} -p {} -U {} | gzip > {}",
+                password, host, port, login, output_file
+            ),
+        },
+        ServiceDatabaseType::MySQL => format!(
+            "mysqldump -h {} -P {} -u root --password={} --all-databases --single-transaction --routines --events | gzip > {}",
+            host, port, password, output_file
+        ),
+        ServiceDatabaseType::MongoDB => format!(
+            "mongodump --host {} --port {} -u root -p {} --authenticationDatabase admin --archive={} --gzip",
+            host, port, password, output_file
+        ),
+        ServiceDatabaseType::Redis => format!(
+            "redis-cli -h {} -p {} -a {} --no-auth-warning --rdb {}",
+            host, port, password, output_file
+        ),
+        ServiceDatabaseType::OpenSearch
+        | ServiceDatabaseType::ClickHouse
+        | ServiceDatabaseType::RabbitMQ
+        | ServiceDatabaseType::Kafka => return None,
+    };
+
+    Some(format!("set -eo pipefail; {}", command))
+}
+
+/// Restore the backup from inside the database container, the backup file is removed once restored
+fn restore_command(
+    db_type: ServiceDatabaseType,
+    scope: DumpScope,
+    port: u16,
+    login: &str,
+    database_name: &str,
+    backup_file: &str,
+) -> Option<String> {
+    let password = format!("\"${}\"", password_env_var(db_type));
+    let command = match db_type {
+        ServiceDatabaseType::PostgreSQL => match scope {
+            DumpScope::Database => format!(
+                "PGPASSWORD={} pg_restore -h 127.0.0.1 -p {} -U {} -d {} --clean --if-exists --no-owner {}",
+                password, port, login, database_name, backup_file
+            ),
+            // roles and databases created by the chart already exist, their creation fails without stopping the restore
+            DumpScope::Instance => format!(
+                "gunzip -c {} | PGPASSWORD={} psql -q -h 127.0.0.1 -p {} -U {} -d postgres",
+                backup_file, password, port, login
+            ),
+        },
+        ServiceDatabaseType::MySQL => format!(
+            "gunzip -c {} | mysql -h 127.0.0.1 -P {} -u root --password={}",
+            backup_file, port, password
+        ),
+        ServiceDatabaseType::MongoDB => format!(
+            "mongorestore --host 127.0.0.1 --port {} -u root -p {} --authenticationDatabase admin --archive={} --gzip --drop",
+            port, password, backup_file
+        ),
+        // the RDB file is loaded in place of the dataset, AOF is rewritten from it once reloaded
+        ServiceDatabaseType::Redis => {
+            let redis_cli = format!("redis-cli -p {} -a {} --no-auth-warning", port, password);
+            format!(
+                "{cli} CONFIG SET appendonly no; cp {file} \"$({cli} CONFIG GET dir | tail -1)/$({cli} CONFIG GET dbfilename | tail -1)\" && {cli} DEBUG RELOAD NOSAVE && {cli} CONFIG SET appendonly yes",
+                cli = redis_cli,
+                file = backup_file
+            )
+        }
+        ServiceDatabaseType::OpenSearch
+        | ServiceDatabaseType::ClickHouse
+        | ServiceDatabaseType::RabbitMQ
+        | ServiceDatabaseType::Kafka => return None,
+    };
+
+    Some(format!("set -eo pipefail; trap 'rm -f {}' EXIT; {}", backup_file, command))
+}
+
+struct BackupJob {
+    name: String,
+    namespace: String,
+    selector: String,
+    db_type: ServiceDatabaseType,
+    dump_command: String,
+    extension: &'static str,
+    image: String,
+    password: String,
+    policy: DatabaseBackupPolicy,
+    bucket_name: String,
+    object_prefix: String,
+    s3_access: S3Access,
+}
+
+/// Secret and CronJob taking the backups: the database is dumped by an init container running the database image,
+/// then the dump is uploaded into the object storage
+fn backup_cronjob_manifest(job: &BackupJob) -> serde_json::Value {
+    let (label_key, label_value) = job.selector.split_once('=').unwrap_or((job.selector.as_str(), ""));
+    let labels = json!({ label_key: label_value, "qovery.com/database-backup": "true" });
+    // backup pods must not match the database selector, commands run into the database pods would target them
+    let pod_labels = json!({ "qovery.com/database-backup": job.name });
+    let secret_env = |name: &str, key: &str| json!({ "name": name, "valueFrom": { "secretKeyRef": { "name": job.name, "key": key } } });
+
+    let mut upload_command = format!(
+        "aws s3 cp /backup/dump \"s3://{}/{}$(date -u +{}).{}\"",
+        job.bucket_name, job.object_prefix, BACKUP_TIMESTAMP_FORMAT, job.extension
+    );
+    if let Some(endpoint_url) = &job.s3_access.endpoint_url {
+        upload_command.push_str(&format!(" --endpoint-url {}", endpoint_url));
+    }
+
+    json!({
+        "apiVersion": "v1",
+        "kind": "List",
+        "items": [
+            {
+                "apiVersion": "v1",
+                "kind": "Secret",
+                "metadata": { "name": job.name, "namespace": job.namespace, "labels": labels },
+                "type": "Opaque",
+                "stringData": {
+                    "database-password": job.password,
+                    "access-key-id": job.s3_access.access_key_id,
+                    "secret-access-key": job.s3_access.secret_access_key,
+                    // kept so next deployments reuse the credentials instead of issuing new ones
+                    "region": job.s3_access.region,
+                    "endpoint-url": job.s3_access.endpoint_url.clone().unwrap_or_default(),
+                }
+            },
+            {
+                "apiVersion": "batch/v1",
+                "kind": "CronJob",
+                "metadata": { "name": job.name, "namespace": job.namespace, "labels": labels },
+                "spec": {
+                    "schedule": job.policy.schedule,
+                    "concurrencyPolicy": "Forbid",
+                    "successfulJobsHistoryLimit": 1,
+                    "failedJobsHistoryLimit": 1,
+                    "jobTemplate": {
+                        "spec": {
+                            "backoffLimit": 1,
+                            "template": {
+                                "metadata": { "labels": pod_labels },
+                                "spec": {
+                                    "restartPolicy": "Never",
+                                    "volumes": [{ "name": "backup", "emptyDir": {} }],
+                                    "initContainers": [{
+                                        "name": "dump",
+                                        "image": job.image,
+                                        "command": ["bash", "-c", job.dump_command],
+                                        "env": [secret_env(password_env_var(job.db_type), "database-password")],
+                                        "volumeMounts": [{ "name": "backup", "mountPath": "/backup" }],
+                                    }],
+                                    "containers": [{
+                                        "name": "upload",
+                                        "image": AWS_CLI_IMAGE,
+                                        "command": ["sh", "-c", upload_command],
+                                        "env": [
+                                            secret_env("AWS_ACCESS_KEY_ID", "access-key-id"),
+                                            secret_env("AWS_SECRET_ACCESS_KEY", "secret-access-key"),
+                                            { "name": "AWS_DEFAULT_REGION", "value": job.s3_access.region },
+                                        ],
+                                        "volumeMounts": [{ "name": "backup", "mountPath": "/backup" }],
+                                    }],
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        ]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn backup(key: &str) -> DatabaseBackup {
+        parse_backup_key(key).expect("cannot parse backup key")
+    }
+
+    #[test]
+    fn test_parse_backup_key() {
+        // execute & verify:
+        assert_eq!(
+            Some(DatabaseBackup {
+                key: "e1b1d2c8-4cb2-4b0e-9a0e-2f1f7fd5b1f0/20221018T030000Z.sql.gz".to_string(),
+                created_at: Utc.ymd(2022, 10, 18).and_hms(3, 0, 0),
+            }),
+            parse_backup_key("e1b1d2c8-4cb2-4b0e-9a0e-2f1f7fd5b1f0/20221018T030000Z.sql.gz")
+        );
+        assert_eq!(None, parse_backup_key("e1b1d2c8-4cb2-4b0e-9a0e-2f1f7fd5b1f0/latest.sql.gz"));
+    }
+
+    #[test]
+    fn test_expired_backups() {
+        // setup:
+        let now = Utc.ymd(2022, 10, 18).and_hms(12, 0, 0);
+        let backups = vec![
+            backup("db/20221001T030000Z.dump"),
+            backup("db/20221010T030000Z.dump"),
+            backup("db/20221017T030000Z.dump"),
+        ];
+
+        // execute & verify:
+        assert_eq!(vec![&backups[0], &backups[1]], expired_backups(&backups, now, 7));
+        assert_eq!(vec![&backups[0]], expired_backups(&backups, now, 10));
+        // the most recent backup is kept even if it has expired
+        assert_eq!(vec![&backups[0], &backups[1]], expired_backups(&backups, now, 0));
+        assert!(expired_backups(&[], now, 0).is_empty());
+    }
+
+    #[test]
+    fn test_dump_and_restore_commands_do_not_contain_passwords() {
+        // setup:
+        let db_types = [
+            ServiceDatabaseType::PostgreSQL,
+            ServiceDatabaseType::MySQL,
+            ServiceDatabaseType::MongoDB,
+            ServiceDatabaseType::Redis,
+        ];
+
+        for db_type in db_types {
+            for scope in [DumpScope::Database, DumpScope::Instance] {
+                // execute:
+                let dump = dump_command(db_type, scope, "postgresql-z1", 5432, "superuser", "my-db", "/backup/dump")
+                    .expect("missing dump command");
+                let restore = restore_command(db_type, scope, 5432, "superuser", "my-db", RESTORE_FILE)
+                    .expect("missing restore command");
+
+                // verify:
+                let password_var = format!("\"${}\"", password_env_var(db_type));
+                assert!(dump.starts_with("set -eo pipefail;"));
+                assert!(dump.contains(&password_var));
+                assert!(dump.contains("/backup/dump"));
+                assert!(restore.contains(&password_var));
+                assert!(restore.contains(&format!("trap 'rm -f {}' EXIT", RESTORE_FILE)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_postgresql_instance_dump() {
+        // execute:
+        let dump = dump_command(
+            ServiceDatabaseType::PostgreSQL,
+            DumpScope::Instance,
+            "127.0.0.1",
+            5432,
+            "superuser",
+            "my-db",
+            BACKUP_FILE,
+        )
+        .expect("missing dump command");
+
+        // verify:
+        assert!(dump.contains("pg_dumpall"));
+        assert!(!dump.contains("my-db"));
+        assert_eq!(
+            DumpScope::Instance,
+            backup_scope(ServiceDatabaseType::PostgreSQL, "db/20221018T030000Z.sql.gz")
+        );
+        assert_eq!(
+            DumpScope::Database,
+            backup_scope(ServiceDatabaseType::PostgreSQL, "db/20221018T030000Z.dump")
+        );
+        assert_eq!(
+            DumpScope::Database,
+            backup_scope(ServiceDatabaseType::MySQL, "db/20221018T030000Z.sql.gz")
+        );
+    }
+
+    #[test]
+    fn test_engines_without_dump_tools_cannot_be_backed_up() {
+        // setup:
+        let db_types = [
+            ServiceDatabaseType::OpenSearch,
+            ServiceDatabaseType::ClickHouse,
+            ServiceDatabaseType::RabbitMQ,
+            ServiceDatabaseType::Kafka,
+        ];
+
+        for db_type in db_types {
+            // execute & verify:
+            assert_eq!(None, backup_extension(db_type, DumpScope::Database));
+            assert_eq!(
+                None,
+                dump_command(
+                    db_type,
+                    DumpScope::Database,
+                    "kafka-z1",
+                    9092,
+                    "superuser",
+                    "my-db",
+                    "/backup/dump"
+                )
+            );
+            assert_eq!(
+                None,
+                restore_command(db_type, DumpScope::Database, 9092, "superuser", "my-db", RESTORE_FILE)
+            );
+        }
+    }
+
+    #[test]
+    fn test_backup_cronjob_manifest() {
+        // setup:
+        let job = BackupJob {
+            name: "postgresql-z1-backup".to_string(),
+            namespace: "z2-z3".to_string(),
+            selector: "databaseId=z1".to_string(),
+            db_type: ServiceDatabaseType::PostgreSQL,
+            dump_command: dump_command(
+                ServiceDatabaseType::PostgreSQL,
+                DumpScope::Database,
+                "postgresql-z1",
+                5432,
+                "superuser",
+                "my-db",
+                "/backup/dump",
+            )
+            .expect("missing dump command"),
+            extension: "dump",
+            image: "docker.io/bitnami/postgresql:13".to_string(),
+            password: "db-s3cr3t".to_string(),
+            policy: DatabaseBackupPolicy::default(),
+            bucket_name: "qovery-db-backups-z4".to_string(),
+            object_prefix: "db/".to_string(),
+            s3_access: S3Access {
+                endpoint_url: Some("https://s3.fr-par.scw.cloud".to_string()),
+                region: "fr-par".to_string(),
+                access_key_id: "access".to_string(),
+                secret_access_key: "key-s3cr3t".to_string(),
+            },
+        };
+
+        // execute:
+        let manifest = backup_cronjob_manifest(&job);
+
+        // verify:
+        let secret = &manifest["items"][0];
+        assert_eq!("db-s3cr3t", secret["stringData"]["database-password"]);
+        let cronjob = &manifest["items"][1];
+        assert_eq!("0 3 * * *", cronjob["spec"]["schedule"]);
+        assert_eq!("z1", cronjob["metadata"]["labels"]["databaseId"]);
+        let pod_template = &cronjob["spec"]["jobTemplate"]["spec"]["template"];
+        assert_eq!(None, pod_template["metadata"]["labels"].get("databaseId"));
+        let pod_spec = &pod_template["spec"];
+        assert_eq!("docker.io/bitnami/postgresql:13", pod_spec["initContainers"][0]["image"]);
+        assert_eq!("POSTGRES_PASSWORD", pod_spec["initContainers"][0]["env"][0]["name"]);
+        assert_eq!(
+            "aws s3 cp /backup/dump \"s3://qovery-db-backups-z4/db/$(date -u +%Y%m%dT%H%M%SZ).dump\" --endpoint-url https://s3.fr-par.scw.cloud",
+            pod_spec["containers"][0]["command"][2]
+        );
+        // credentials are only in the secret
+        assert!(!cronjob.to_string().contains("s3cr3t"));
+    }
+
+    #[test]
+    fn test_backup_access_from_secret() {
+        // setup:
+        let secret = |data: serde_json::Value| json!({ "apiVersion": "v1", "kind": "Secret", "data": data });
+        let encoded = |value: &str| base64::encode(value);
+
+        // execute & verify:
+        assert_eq!(
+            Some(S3Access {
+                endpoint_url: None,
+                region: "eu-west-3".to_string(),
+                access_key_id: "access".to_string(),
+                secret_access_key: "key-s3cr3t".to_string(),
+            }),
+            backup_access_from_secret(&secret(json!({
+                "region": encoded("eu-west-3"),
+                "endpoint-url": encoded(""),
+                "access-key-id": encoded("access"),
+                "secret-access-key": encoded("key-s3cr3t"),
+            })))
+        );
+        // secret written without the credentials location, they have to be issued again
+        assert_eq!(
+            None,
+            backup_access_from_secret(&secret(json!({
+                "access-key-id": encoded("access"),
+                "secret-access-key": encoded("key-s3cr3t"),
+            })))
+        );
+    }
+}
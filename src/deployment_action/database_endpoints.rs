@@ -0,0 +1,359 @@
+use std::path::PathBuf;
+
+use k8s_openapi::api::apps::v1::StatefulSet;
+use k8s_openapi::api::core::v1::Secret;
+use kube::Api;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+
+use crate::cloud_provider::helm::{ChartInfo, HelmChartNamespaces};
+use crate::cloud_provider::service::{Action, DatabaseType as ServiceDatabaseType, Service};
+use crate::cloud_provider::DeploymentTarget;
+use crate::cmd::kubectl::kubectl_exec_with_output;
+use crate::constants::KUBECONFIG;
+use crate::deployment_action::database_backup::{primary_pod_selector, running_pod_name};
+use crate::deployment_action::deploy_database::external_name_service_values;
+use crate::deployment_action::deploy_helm::HelmDeployment;
+use crate::deployment_action::DeploymentAction;
+use crate::deployment_report::logger::get_loggers;
+use crate::errors::{CommandError, EngineError};
+use crate::events::EventDetails;
+use crate::io_models::database::{DatabaseConnectionPooling, DatabaseOptions};
+use crate::models::database::{Container, Database, DatabaseType};
+use crate::models::types::{CloudProvider, ToTeraContext};
+use crate::runtime::block_on;
+
+const PGBOUNCER_IMAGE: &str = "docker.io/bitnami/pgbouncer:1.17.0";
+const PROXYSQL_IMAGE: &str = "docker.io/proxysql/proxysql:2.4.4";
+const POOLER_ADMIN_PASSWORD_KEY: &str = "adminPassword";
+
+/// Read/write or read-only endpoint of a database, exposed as `<fqdn_id>-<suffix>` in the environment namespace
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum DatabaseEndpoint {
+    ReadWrite,
+    ReadOnly,
+}
+
+impl DatabaseEndpoint {
+    fn suffix(&self) -> &'static str {
+        match self {
+            DatabaseEndpoint::ReadWrite => "rw",
+            DatabaseEndpoint::ReadOnly => "ro",
+        }
+    }
+}
+
+/// Make sure the primary of an existing database can be replicated before read replicas are added to it.
+/// The replication user is only created by the database image when its data directory is initialized.
+///
+/// `deploy`: deploys the database chart with the given values overriding the database ones
+pub(super) fn prepare_read_replicas<C, T>(
+    db: &Database<C, Container, T>,
+    target: &DeploymentTarget,
+    event_details: EventDetails,
+    deploy: &dyn Fn(tera::Context) -> Result<(), EngineError>,
+) -> Result<(), EngineError>
+where
+    C: CloudProvider,
+    T: DatabaseType<C, Container, DatabaseOptions = DatabaseOptions>,
+{
+    if db.options.read_replicas == 0 {
+        return Ok(());
+    }
+
+    let to_error = |err: CommandError| {
+        EngineError::new_database_cannot_setup_read_replicas(event_details.clone(), db.name().to_string(), err)
+    };
+    let statefulsets: Api<StatefulSet> = Api::namespaced(target.kube.clone(), target.environment.namespace());
+    let replicas = block_on(statefulsets.get_opt(&replicas_statefulset_name(&db.sanitized_name()))).map_err(|err| {
+        to_error(CommandError::new(
+            "Cannot get the read replicas of the database".to_string(),
+            Some(err.to_string()),
+            None,
+        ))
+    })?;
+    if replicas.is_some() {
+        return Ok(());
+    }
+
+    match T::db_type() {
+        ServiceDatabaseType::PostgreSQL => {
+            let logger = get_loggers(db, Action::Create);
+            (logger.send_progress)(format!("🔁 Enabling replication on database {}", db.name()));
+
+            // the primary is restarted with replication enabled, replicas are only added once their user exists
+            let mut overrides = tera::Context::new();
+            overrides.insert("database_replication_enabled", &true);
+            overrides.insert("database_read_replicas", &0);
+            deploy(overrides)?;
+
+            let kubeconfig = target.kubernetes.get_kubeconfig_file_path()?;
+            let mut envs = vec![(KUBECONFIG, kubeconfig.as_str())];
+            envs.extend(target.kubernetes.cloud_provider().credentials_environment_variables());
+            let namespace = target.environment.namespace();
+            let pod_name = running_pod_name(&kubeconfig, &envs, namespace, &primary_pod_selector(&db.selector()))
+                .map_err(to_error)?;
+            kubectl_exec_with_output(
+                vec![
+                    "exec",
+                    "-n",
+                    namespace,
+                    pod_name.as_str(),
+                    "--",
+                    "bash",
+                    "-c",
+                    create_postgresql_replication_user_command(db.private_port).as_str(),
+                ],
+                envs.clone(),
+                &mut |line| info!("{}", line),
+                &mut |line| warn!("{}", line),
+            )
+            .map_err(to_error)
+        }
+        // a MySQL replica only receives what is written once the binary log is enabled
+        _ => Err(to_error(CommandError::new_from_safe_message(format!(
+            "Read replicas can only be added to a {} database when it is created",
+            T::db_type().to_string()
+        )))),
+    }
+}
+
+/// Deploy the connection pooler and the read/write and read-only endpoints of a database,
+/// or remove them when they are not requested anymore.
+/// The pooler only fronts the primary, read-only queries are sent to the replicas through the read-only endpoint.
+pub(super) fn deploy_database_endpoints<C, T>(
+    db: &Database<C, Container, T>,
+    target: &DeploymentTarget,
+    event_details: EventDetails,
+) -> Result<(), EngineError>
+where
+    C: CloudProvider,
+    T: DatabaseType<C, Container, DatabaseOptions = DatabaseOptions>,
+    Database<C, Container, T>: ToTeraContext,
+{
+    let namespace = target.environment.namespace();
+    let read_replicas = db.options.read_replicas;
+    let replicas_host = cluster_hostname(&replicas_service_name(T::db_type(), &db.sanitized_name()), namespace);
+
+    let primary_host = match &db.options.connection_pooling {
+        Some(pooling) => {
+            let mut tera_context = db.to_tera_context(target)?;
+            tera_context.insert(
+                "pooler_engine",
+                match T::db_type() {
+                    ServiceDatabaseType::MySQL => "proxysql",
+                    _ => "pgbouncer",
+                },
+            );
+            tera_context.insert(
+                "pooler_image",
+                match T::db_type() {
+                    ServiceDatabaseType::MySQL => PROXYSQL_IMAGE,
+                    _ => PGBOUNCER_IMAGE,
+                },
+            );
+            tera_context.insert("pooler_name", &pooler_name(&db.fqdn_id));
+            tera_context.insert("pooler_primary_host", &cluster_hostname(&db.fqdn_id, namespace));
+            let admin_password = pooler_admin_password(target, &pooler_name(&db.fqdn_id)).map_err(|err| {
+                EngineError::new_database_cannot_setup_connection_pooling(
+                    event_details.clone(),
+                    db.name().to_string(),
+                    err,
+                )
+            })?;
+            tera_context.insert("pooler_admin_password", &admin_password);
+            let DatabaseConnectionPooling {
+                pool_size,
+                max_client_connections,
+            } = pooling;
+            tera_context.insert("pooler_pool_size", pool_size);
+            tera_context.insert("pooler_max_client_connections", max_client_connections);
+
+            pooler_helm_deployment(db, target, event_details.clone(), tera_context).on_create(target)?;
+            cluster_hostname(&pooler_name(&db.fqdn_id), namespace)
+        }
+        None => {
+            pooler_helm_deployment(db, target, event_details.clone(), tera::Context::new()).on_delete(target)?;
+            cluster_hostname(&db.fqdn_id, namespace)
+        }
+    };
+
+    let read_write =
+        endpoint_helm_deployment(db, target, event_details.clone(), DatabaseEndpoint::ReadWrite, primary_host);
+    let read_only = endpoint_helm_deployment(db, target, event_details, DatabaseEndpoint::ReadOnly, replicas_host);
+    if read_replicas > 0 {
+        read_write.on_create(target)?;
+        read_only.on_create(target)
+    } else if db.options.connection_pooling.is_some() {
+        read_write.on_create(target)?;
+        read_only.on_delete(target)
+    } else {
+        read_write.on_delete(target)?;
+        read_only.on_delete(target)
+    }
+}
+
+/// Remove the connection pooler and the endpoints of a database
+pub(super) fn delete_database_endpoints<C, T>(
+    db: &Database<C, Container, T>,
+    target: &DeploymentTarget,
+    event_details: EventDetails,
+) -> Result<(), EngineError>
+where
+    C: CloudProvider,
+    T: DatabaseType<C, Container>,
+{
+    pooler_helm_deployment(db, target, event_details.clone(), tera::Context::new()).on_delete(target)?;
+    for endpoint in [DatabaseEndpoint::ReadWrite, DatabaseEndpoint::ReadOnly] {
+        endpoint_helm_deployment(db, target, event_details.clone(), endpoint, String::new()).on_delete(target)?;
+    }
+
+    Ok(())
+}
+
+fn pooler_helm_deployment<C: CloudProvider, T: DatabaseType<C, Container>>(
+    db: &Database<C, Container, T>,
+    target: &DeploymentTarget,
+    event_details: EventDetails,
+    tera_context: tera::Context,
+) -> HelmDeployment {
+    let chart = ChartInfo {
+        name: pooler_name(&db.fqdn_id),
+        path: format!("{}/{}", db.workspace_directory(), "pooler-chart"),
+        namespace: HelmChartNamespaces::Custom,
+        custom_namespace: Some(target.environment.namespace().to_string()),
+        ..Default::default()
+    };
+
+    HelmDeployment::new(
+        event_details,
+        tera_context,
+        PathBuf::from(format!("{}/common/charts/database-pooler", db.context.lib_root_dir())),
+        None,
+        chart,
+    )
+}
+
+/// Endpoints are only reachable from inside the cluster, the database public endpoint always targets the primary
+fn endpoint_helm_deployment<C: CloudProvider, T: DatabaseType<C, Container>>(
+    db: &Database<C, Container, T>,
+    target: &DeploymentTarget,
+    event_details: EventDetails,
+    endpoint: DatabaseEndpoint,
+    target_hostname: String,
+) -> HelmDeployment {
+    let service_name = format!("{}-{}", db.fqdn_id, endpoint.suffix());
+    let chart = ChartInfo {
+        name: format!("{}-externalname", service_name),
+        path: format!("{}/endpoint-{}-chart", db.workspace_directory(), endpoint.suffix()),
+        namespace: HelmChartNamespaces::Custom,
+        custom_namespace: Some(target.environment.namespace().to_string()),
+        values: external_name_service_values(db, target, service_name, target_hostname, String::new(), false),
+        ..Default::default()
+    };
+    let mut tera_context = tera::Context::new();
+    tera_context.insert("publicly_accessible", &false);
+
+    HelmDeployment::new(
+        event_details,
+        tera_context,
+        PathBuf::from(db.helm_chart_external_name_service_dir()),
+        None,
+        chart,
+    )
+}
+
+/// Password of the pooler admin interface: kept from its secret when the pooler is already deployed,
+/// so its configuration and pod are not changed on every deployment
+fn pooler_admin_password(target: &DeploymentTarget, pooler_name: &str) -> Result<String, CommandError> {
+    let secrets: Api<Secret> = Api::namespaced(target.kube.clone(), target.environment.namespace());
+    let secret = block_on(secrets.get_opt(pooler_name)).map_err(|err| {
+        CommandError::new(
+            "Cannot get the connection pooler secret".to_string(),
+            Some(err.to_string()),
+            None,
+        )
+    })?;
+    let existing_password = secret
+        .and_then(|secret| secret.data)
+        .and_then(|data| data.get(POOLER_ADMIN_PASSWORD_KEY).cloned())
+        .and_then(|password| String::from_utf8(password.0).ok())
+        .filter(|password| !password.is_empty());
+
+    Ok(existing_password.unwrap_or_else(generate_pooler_admin_password))
+}
+
+fn generate_pooler_admin_password() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect()
+}
+
+fn pooler_name(fqdn_id: &str) -> String {
+    format!("{}-pooler", fqdn_id)
+}
+
+fn cluster_hostname(service_name: &str, namespace: &str) -> String {
+    format!("{}.{}.svc.cluster.local", service_name, namespace)
+}
+
+/// StatefulSet of the read replicas, named the same way by the PostgreSQL and MySQL charts
+fn replicas_statefulset_name(sanitized_name: &str) -> String {
+    format!("{}-slave", sanitized_name)
+}
+
+/// Service load balancing the connections between the read replicas
+fn replicas_service_name(db_type: ServiceDatabaseType, sanitized_name: &str) -> String {
+    match db_type {
+        ServiceDatabaseType::PostgreSQL => format!("{}-read", sanitized_name),
+        _ => format!("{}-slave", sanitized_name),
+    }
+}
+
+/// The passwords are read from the database container environment, so they never appear in the command line
+fn create_postgresql_replication_user_command(port: u16) -> String {
+    format!(
+        "PGPASSWORD=\"${{POSTGRES_POSTGRES_PASSWORD:-$POSTGRES_PASSWORD}}\" psql -h 127.0.0.1 -p {} -U postgres -d postgres -v ON_ERROR_STOP=1 -v user=\"$POSTGRES_REPLICATION_USER\" -v password=\"$POSTGRES_REPLICATION_PASSWORD\" <<'SQL'
+SELECT format('CREATE ROLE %I REPLICATION LOGIN PASSWORD %L', :'user', :'password') WHERE NOT EXISTS (SELECT FROM pg_roles WHERE rolname = :'user')
+\\gexec
+SQL",
+        port
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replicas_service_name() {
+        assert_eq!(
+            replicas_service_name(ServiceDatabaseType::PostgreSQL, "postgresql-z1"),
+            "postgresql-z1-read"
+        );
+        assert_eq!(replicas_service_name(ServiceDatabaseType::MySQL, "mysql-z1"), "mysql-z1-slave");
+    }
+
+    #[test]
+    fn test_generate_pooler_admin_password() {
+        // execute:
+        let password = generate_pooler_admin_password();
+
+        // verify:
+        assert_eq!(password.len(), 32);
+        assert!(password.chars().all(|c| c.is_ascii_alphanumeric()));
+        assert_ne!(password, generate_pooler_admin_password());
+    }
+
+    #[test]
+    fn test_create_postgresql_replication_user_command_does_not_contain_password() {
+        let command = create_postgresql_replication_user_command(5432);
+
+        assert!(command.contains("-p 5432"));
+        assert!(command.contains("-v password=\"$POSTGRES_REPLICATION_PASSWORD\""));
+        assert!(command.contains("WHERE NOT EXISTS (SELECT FROM pg_roles WHERE rolname = :'user')"));
+        assert!(command.ends_with("\\gexec\nSQL"));
+    }
+}
@@ -1,18 +1,28 @@
 use crate::cloud_provider::helm::{ChartInfo, HelmAction, HelmChartNamespaces};
+use crate::cloud_provider::models::EnvironmentVariable;
 use crate::cloud_provider::service::{delete_pending_service, Action, Service};
 use crate::cloud_provider::utilities::print_action;
 use crate::cloud_provider::DeploymentTarget;
+use crate::deployment_action::check_hpa_metrics::check_hpa_metrics;
+use crate::deployment_action::clone_environment::cloned_environment_variables;
 use crate::deployment_action::deploy_helm::HelmDeployment;
+use crate::deployment_action::deployment_plan::DeploymentPlan;
 use crate::deployment_action::pause_service::PauseServiceAction;
+use crate::deployment_action::progressive_deployment::{delete_preview, ProgressiveDeployment};
+use crate::deployment_action::secret_environment_variables::with_secret_environment_variables;
+use crate::deployment_action::volume_snapshot::{
+    check_volume_snapshots_support, create_pre_deploy_volume_snapshots, delete_volume_snapshots,
+    prune_volume_snapshots, restore_volume_snapshots, schedule_volume_snapshots, ServiceStorage,
+};
 use crate::deployment_action::DeploymentAction;
 use crate::deployment_report::application::reporter::ApplicationDeploymentReporter;
 use crate::deployment_report::execute_long_deployment;
 use crate::deployment_report::logger::get_loggers;
 use crate::errors::{CommandError, EngineError};
-use crate::events::{EnvironmentStep, Stage};
+use crate::events::{EnvironmentStep, EventDetails, Stage};
 use crate::kubers_utils::kube_delete_all_from_selector;
 use crate::models::application::{Application, ApplicationService};
-use crate::models::types::{CloudProvider, ToTeraContext};
+use crate::models::types::{CloudProvider, ToK8sStorageClass, ToTeraContext};
 use crate::runtime::block_on;
 use function_name::named;
 use k8s_openapi::api::core::v1::PersistentVolumeClaim;
@@ -20,6 +30,130 @@ use std::path::PathBuf;
 use std::time::Duration;
 use tera::Context;
 
+/// Own environment variables, the ones of the service it is cloned from, and the values of secret ones
+fn environment_variables<T: CloudProvider>(
+    application: &Application<T>,
+    target: &DeploymentTarget,
+    event_details: &EventDetails,
+) -> Result<Vec<EnvironmentVariable>, EngineError>
+where
+    Application<T>: ToTeraContext,
+{
+    let environment_variables = cloned_environment_variables(
+        application,
+        target,
+        "appLongId",
+        application.environment_variables(),
+        event_details.clone(),
+    )?
+    .unwrap_or_else(|| application.environment_variables().to_vec());
+
+    with_secret_environment_variables(
+        application,
+        target,
+        environment_variables,
+        application.secret_environment_variables(),
+        event_details,
+    )
+}
+
+fn storages<T: CloudProvider>(application: &Application<T>) -> Vec<ServiceStorage<'_>> {
+    application
+        .storages()
+        .iter()
+        .map(|storage| ServiceStorage {
+            long_id: storage.long_id,
+            storage_class: storage.storage_type.to_k8s_storage_class(),
+            volume_snapshot_class: T::volume_snapshot_class(),
+            size_in_gib: storage.size_in_gib,
+            snapshot_schedule: &storage.snapshot_schedule,
+            snapshot_retention_in_days: storage.snapshot_retention_in_days,
+            restore_from_snapshot: storage.restore_from_snapshot.as_deref(),
+            pvc_selector: format!("{},diskId={}", application.selector(), storage.id),
+            first_pvc_name: format!("{}-{}-0", storage.id, application.sanitized_name()),
+        })
+        .collect()
+}
+
+fn helm_deployment<T: CloudProvider>(
+    application: &Application<T>,
+    target: &DeploymentTarget,
+    event_details: EventDetails,
+) -> Result<HelmDeployment, EngineError>
+where
+    Application<T>: ToTeraContext,
+{
+    let chart = ChartInfo {
+        name: application.helm_release_name(),
+        path: application.workspace_directory(),
+        namespace: HelmChartNamespaces::Custom,
+        custom_namespace: Some(target.environment.namespace().to_string()),
+        timeout_in_seconds: application.startup_timeout().as_secs() as i64,
+        k8s_selector: Some(application.selector()),
+        ..Default::default()
+    };
+
+    let mut tera_context = application.to_tera_context(target)?;
+    tera_context.insert(
+        "environment_variables",
+        &environment_variables(application, target, &event_details)?,
+    );
+
+    Ok(HelmDeployment::new(
+        event_details,
+        tera_context,
+        PathBuf::from(application.helm_chart_dir()),
+        None,
+        chart,
+    ))
+}
+
+fn preview_chart<T: CloudProvider>(application: &Application<T>, target: &DeploymentTarget) -> ChartInfo
+where
+    Application<T>: ToTeraContext,
+{
+    ChartInfo {
+        name: application.preview_helm_release_name(),
+        path: format!("{}-preview", application.workspace_directory()),
+        namespace: HelmChartNamespaces::Custom,
+        custom_namespace: Some(target.environment.namespace().to_string()),
+        timeout_in_seconds: application.startup_timeout().as_secs() as i64,
+        k8s_selector: Some(application.preview_selector()),
+        ..Default::default()
+    }
+}
+
+fn progressive_deployment<T: CloudProvider>(
+    application: &Application<T>,
+    target: &DeploymentTarget,
+    event_details: EventDetails,
+) -> Result<ProgressiveDeployment, EngineError>
+where
+    Application<T>: ToTeraContext,
+{
+    let mut preview_tera_context = application.to_preview_tera_context(target)?;
+    preview_tera_context.insert(
+        "environment_variables",
+        &environment_variables(application, target, &event_details)?,
+    );
+    let preview = HelmDeployment::new(
+        event_details.clone(),
+        preview_tera_context,
+        PathBuf::from(application.helm_chart_dir()),
+        None,
+        preview_chart(application, target),
+    );
+
+    Ok(ProgressiveDeployment {
+        strategy: application.deployment_strategy().clone(),
+        stable: helm_deployment(application, target, event_details.clone())?,
+        preview,
+        stable_kube_service_name: application.sanitized_name(),
+        preview_kube_service_name: application.preview_sanitized_name(),
+        event_details,
+    })
+}
+
 impl<T: CloudProvider> DeploymentAction for Application<T>
 where
     Application<T>: ToTeraContext,
@@ -46,25 +180,33 @@ where
             )
             .unpause_if_needed(target);
 
-            let chart = ChartInfo {
-                name: self.helm_release_name(),
-                path: self.workspace_directory(),
-                namespace: HelmChartNamespaces::Custom,
-                custom_namespace: Some(target.environment.namespace().to_string()),
-                timeout_in_seconds: self.startup_timeout().as_secs() as i64,
-                k8s_selector: Some(self.selector()),
-                ..Default::default()
-            };
+            if self.is_autoscaled() {
+                let advanced_settings = self.advanced_settings();
+                check_hpa_metrics(
+                    &advanced_settings.hpa_metrics,
+                    advanced_settings.hpa_scale_up_behavior.as_ref(),
+                    advanced_settings.hpa_scale_down_behavior.as_ref(),
+                    target,
+                    &event_details,
+                )?;
+            }
 
-            let helm = HelmDeployment::new(
-                event_details.clone(),
-                self.to_tera_context(target)?,
-                PathBuf::from(self.helm_chart_dir()),
-                None,
-                chart,
-            );
+            // Statefulset pods keep their volumes, so a new version cannot run next to the current one
+            if self.is_stateful() {
+                let storages = storages(self);
+                check_volume_snapshots_support(self, &storages, target, &event_details)?;
+                create_pre_deploy_volume_snapshots(self, &storages, target, &event_details)?;
+                restore_volume_snapshots(self, &storages, target, &event_details)?;
+                prune_volume_snapshots(self, &storages, target, &event_details);
+                schedule_volume_snapshots(self, &storages, target, &event_details)?;
 
-            helm.on_create(target)?;
+                let helm = helm_deployment(self, target, event_details.clone())?;
+                helm.on_create(target)?;
+            } else {
+                let logger = get_loggers(self, Action::Create);
+                progressive_deployment(self, target, event_details.clone())?
+                    .on_create(target, &*logger.send_progress)?;
+            }
 
             delete_pending_service(
                 target.kubernetes.get_kubeconfig_file_path()?.as_str(),
@@ -78,6 +220,11 @@ where
         })
     }
 
+    fn on_create_plan(&self, target: &DeploymentTarget) -> Result<DeploymentPlan, EngineError> {
+        let event_details = self.get_event_details(Stage::Environment(EnvironmentStep::Deploy));
+        helm_deployment(self, target, event_details)?.on_create_plan(target)
+    }
+
     #[named]
     fn on_pause(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
         let event_details = self.get_event_details(Stage::Environment(EnvironmentStep::Pause));
@@ -122,6 +269,14 @@ where
 
             helm.on_delete(target)?;
 
+            // A preview is left behind only if the engine has been interrupted during a deployment
+            delete_preview(
+                target,
+                &preview_chart(self, target),
+                &self.preview_sanitized_name(),
+                &event_details,
+            )?;
+
             let logger = get_loggers(self, Action::Delete);
             // Delete pvc of statefulset if needed
             // FIXME: Remove this after kubernetes 1.23 is deployed, at it should be done by kubernetes
@@ -138,6 +293,7 @@ where
                         CommandError::new_from_safe_message(err.to_string()),
                     ));
                 }
+                delete_volume_snapshots(self.long_id(), target);
             }
 
             // Delete container repository created for this application
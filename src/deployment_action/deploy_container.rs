@@ -1,22 +1,32 @@
 use crate::build_platform::Image;
 use crate::cloud_provider::helm::{ChartInfo, HelmAction, HelmChartNamespaces};
+use crate::cloud_provider::models::EnvironmentVariable;
 use crate::cloud_provider::service::{delete_pending_service, Action, Service};
 use crate::cloud_provider::DeploymentTarget;
 use crate::cmd::command::CommandKiller;
 use crate::cmd::docker::ContainerImage;
 use crate::container_registry::ecr::ECR;
+use crate::deployment_action::check_hpa_metrics::check_hpa_metrics;
+use crate::deployment_action::clone_environment::cloned_environment_variables;
 use crate::deployment_action::deploy_helm::HelmDeployment;
+use crate::deployment_action::deployment_plan::DeploymentPlan;
 use crate::deployment_action::pause_service::PauseServiceAction;
+use crate::deployment_action::progressive_deployment::{delete_preview, ProgressiveDeployment};
+use crate::deployment_action::secret_environment_variables::with_secret_environment_variables;
+use crate::deployment_action::volume_snapshot::{
+    check_volume_snapshots_support, create_pre_deploy_volume_snapshots, delete_volume_snapshots,
+    prune_volume_snapshots, restore_volume_snapshots, schedule_volume_snapshots, ServiceStorage,
+};
 use crate::deployment_action::DeploymentAction;
 use crate::deployment_report::application::reporter::ApplicationDeploymentReporter;
 use crate::deployment_report::execute_long_deployment;
-use crate::deployment_report::logger::get_loggers;
+use crate::deployment_report::logger::{get_loggers, Loggers};
 use crate::errors::{CommandError, EngineError};
-use crate::events::{EnvironmentStep, Stage};
+use crate::events::{EnvironmentStep, EventDetails, Stage};
 use crate::io_models::container::Registry;
 use crate::kubers_utils::kube_delete_all_from_selector;
 use crate::models::container::{Container, ContainerService};
-use crate::models::types::{CloudProvider, ToTeraContext};
+use crate::models::types::{CloudProvider, ToK8sStorageClass, ToTeraContext};
 use crate::runtime::block_on;
 use k8s_openapi::api::apps::v1::{Deployment, StatefulSet};
 use k8s_openapi::api::core::v1::PersistentVolumeClaim;
@@ -30,72 +40,147 @@ use std::str::FromStr;
 use std::time::Duration;
 use url::Url;
 
-impl<T: CloudProvider> DeploymentAction for Container<T>
+fn storages<T: CloudProvider>(container: &Container<T>) -> Vec<ServiceStorage<'_>> {
+    container
+        .storages()
+        .iter()
+        .map(|storage| ServiceStorage {
+            long_id: storage.long_id,
+            storage_class: storage.storage_type.to_k8s_storage_class(),
+            volume_snapshot_class: T::volume_snapshot_class(),
+            size_in_gib: storage.size_in_gib,
+            snapshot_schedule: &storage.snapshot_schedule,
+            snapshot_retention_in_days: storage.snapshot_retention_in_days,
+            restore_from_snapshot: storage.restore_from_snapshot.as_deref(),
+            pvc_selector: format!("qovery.com/disk-id={}", storage.long_id),
+            first_pvc_name: format!("{}-{}-0", storage.long_id, container.kube_service_name()),
+        })
+        .collect()
+}
+
+/// Own environment variables, the ones of the service it is cloned from, and the values of secret ones
+fn environment_variables<T: CloudProvider>(
+    container: &Container<T>,
+    target: &DeploymentTarget,
+    event_details: &EventDetails,
+) -> Result<Vec<EnvironmentVariable>, EngineError>
 where
     Container<T>: ToTeraContext,
 {
-    fn on_create(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
-        let event_details = self.get_event_details(Stage::Environment(EnvironmentStep::Deploy));
+    let environment_variables = cloned_environment_variables(
+        container,
+        target,
+        "qovery.com/service-id",
+        container.environment_variables(),
+        event_details.clone(),
+    )?
+    .unwrap_or_else(|| container.environment_variables().to_vec());
 
-        let loggers = get_loggers(self, *self.action());
+    with_secret_environment_variables(
+        container,
+        target,
+        environment_variables,
+        container.secret_environment_variables(),
+        event_details,
+    )
+}
 
-        // We need to login to the registry to get access to the image
-        let url = get_url_with_credentials(&self.registry);
-        if url.password().is_some() {
-            (loggers.send_progress)(format!(
-                "🔓 Login to registry {} as user {}",
-                url.host_str().unwrap_or_default(),
-                url.username()
-            ));
-            if let Err(err) = target.docker.login(&url) {
-                let err = EngineError::new_docker_error(event_details, err);
-                let user_err = EngineError::new_engine_error(
-                    err.clone(),
-                    format!("❌ Failed to login to registry {}", url.host_str().unwrap_or_default()),
-                    None,
-                );
-                (loggers.send_error)(user_err);
+fn helm_deployment<T: CloudProvider>(
+    container: &Container<T>,
+    target: &DeploymentTarget,
+    event_details: EventDetails,
+) -> Result<HelmDeployment, EngineError>
+where
+    Container<T>: ToTeraContext,
+{
+    let chart = ChartInfo {
+        name: container.helm_release_name(),
+        path: container.workspace_directory(),
+        namespace: HelmChartNamespaces::Custom,
+        custom_namespace: Some(target.environment.namespace().to_string()),
+        timeout_in_seconds: container.startup_timeout().as_secs() as i64,
+        k8s_selector: Some(container.selector()),
+        ..Default::default()
+    };
 
-                return Err(err);
-            }
-        }
+    let mut tera_context = container.to_tera_context(target)?;
+    tera_context.insert(
+        "environment_variables",
+        &environment_variables(container, target, &event_details)?,
+    );
 
-        // Once we are logged to the registry, we mirror the user image into our cluster private registry
-        // This is required only to avoid to manage rotating credentials
-        (loggers.send_progress)("🪞 Mirroring image to private cluster registry to ensure reproducibility".to_string());
-        let registry_info = target.container_registry.registry_info();
+    Ok(HelmDeployment::new(
+        event_details,
+        tera_context,
+        PathBuf::from(container.helm_chart_dir()),
+        None,
+        chart,
+    ))
+}
 
-        target
-            .container_registry
-            .create_repository(
-                Self::QOVERY_MIRROR_REPOSITORY_NAME,
-                target.kubernetes.advanced_settings().registry_image_retention_time_sec,
-            )
-            .map_err(|err| EngineError::new_container_registry_error(event_details.clone(), err))?;
-
-        let source_image = ContainerImage::new(self.registry.url().clone(), self.image.clone(), vec![self.tag.clone()]);
-        let dest_image = ContainerImage::new(
-            target.container_registry.registry_info().endpoint.clone(),
-            (registry_info.get_image_name)(Self::QOVERY_MIRROR_REPOSITORY_NAME),
-            vec![self.tag_for_mirror()],
-        );
-        if let Err(err) = target.docker.mirror(
-            &source_image,
-            &dest_image,
-            &mut |line| info!("{}", line),
-            &mut |line| warn!("{}", line),
-            &CommandKiller::from_timeout(Duration::from_secs(60 * 10)),
-        ) {
-            let err = EngineError::new_docker_error(event_details, err);
-            let user_err = EngineError::new_engine_error(
-                err.clone(),
-                format!("❌ Failed to mirror image {}: {}", self.image_with_tag(), err),
-                None,
-            );
-            (loggers.send_error)(user_err);
+fn preview_chart<T: CloudProvider>(container: &Container<T>, target: &DeploymentTarget) -> ChartInfo
+where
+    Container<T>: ToTeraContext,
+{
+    ChartInfo {
+        name: container.preview_helm_release_name(),
+        path: format!("{}-preview", container.workspace_directory()),
+        namespace: HelmChartNamespaces::Custom,
+        custom_namespace: Some(target.environment.namespace().to_string()),
+        timeout_in_seconds: container.startup_timeout().as_secs() as i64,
+        k8s_selector: Some(container.preview_selector()),
+        ..Default::default()
+    }
+}
 
-            return Err(err);
-        }
+fn progressive_deployment<T: CloudProvider>(
+    container: &Container<T>,
+    target: &DeploymentTarget,
+    event_details: EventDetails,
+) -> Result<ProgressiveDeployment, EngineError>
+where
+    Container<T>: ToTeraContext,
+{
+    let mut preview_tera_context = container.to_preview_tera_context(target)?;
+    preview_tera_context.insert(
+        "environment_variables",
+        &environment_variables(container, target, &event_details)?,
+    );
+    let preview = HelmDeployment::new(
+        event_details.clone(),
+        preview_tera_context,
+        PathBuf::from(container.helm_chart_dir()),
+        None,
+        preview_chart(container, target),
+    );
+
+    Ok(ProgressiveDeployment {
+        strategy: container.deployment_strategy().clone(),
+        stable: helm_deployment(container, target, event_details.clone())?,
+        preview,
+        stable_kube_service_name: container.kube_service_name(),
+        preview_kube_service_name: container.preview_kube_service_name(),
+        event_details,
+    })
+}
+
+impl<T: CloudProvider> DeploymentAction for Container<T>
+where
+    Container<T>: ToTeraContext,
+{
+    fn on_create(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        let event_details = self.get_event_details(Stage::Environment(EnvironmentStep::Deploy));
+
+        let loggers = get_loggers(self, *self.action());
+        mirror_image(
+            &self.registry,
+            &self.image,
+            &self.tag,
+            self.tag_for_mirror(),
+            target,
+            &event_details,
+            &loggers,
+        )?;
 
         // At last we deploy our container
         execute_long_deployment(
@@ -110,6 +195,17 @@ where
                 )
                 .unpause_if_needed(target);
 
+                if self.is_autoscaled() {
+                    let advanced_settings = self.advanced_settings();
+                    check_hpa_metrics(
+                        &advanced_settings.hpa_metrics,
+                        advanced_settings.hpa_scale_up_behavior.as_ref(),
+                        advanced_settings.hpa_scale_down_behavior.as_ref(),
+                        target,
+                        &event_details,
+                    )?;
+                }
+
                 let last_image = block_on(get_last_deployed_image(
                     target.kube.clone(),
                     &self.selector(),
@@ -117,25 +213,21 @@ where
                     target.environment.namespace(),
                 ));
 
-                let chart = ChartInfo {
-                    name: self.helm_release_name(),
-                    path: self.workspace_directory(),
-                    namespace: HelmChartNamespaces::Custom,
-                    custom_namespace: Some(target.environment.namespace().to_string()),
-                    timeout_in_seconds: self.startup_timeout().as_secs() as i64,
-                    k8s_selector: Some(self.selector()),
-                    ..Default::default()
-                };
-
-                let helm = HelmDeployment::new(
-                    event_details.clone(),
-                    self.to_tera_context(target)?,
-                    PathBuf::from(self.helm_chart_dir()),
-                    None,
-                    chart,
-                );
+                // Statefulset pods keep their volumes, so a new version cannot run next to the current one
+                if self.is_stateful() {
+                    let storages = storages(self);
+                    check_volume_snapshots_support(self, &storages, target, &event_details)?;
+                    create_pre_deploy_volume_snapshots(self, &storages, target, &event_details)?;
+                    restore_volume_snapshots(self, &storages, target, &event_details)?;
+                    prune_volume_snapshots(self, &storages, target, &event_details);
+                    schedule_volume_snapshots(self, &storages, target, &event_details)?;
 
-                helm.on_create(target)?;
+                    let helm = helm_deployment(self, target, event_details.clone())?;
+                    helm.on_create(target)?;
+                } else {
+                    progressive_deployment(self, target, event_details.clone())?
+                        .on_create(target, &*loggers.send_progress)?;
+                }
 
                 delete_pending_service(
                     target.kubernetes.get_kubeconfig_file_path()?.as_str(),
@@ -171,6 +263,12 @@ where
         )
     }
 
+    fn on_create_plan(&self, target: &DeploymentTarget) -> Result<DeploymentPlan, EngineError> {
+        // the image is only mirrored during the deployment, the chart is rendered with its future location
+        let event_details = self.get_event_details(Stage::Environment(EnvironmentStep::Deploy));
+        helm_deployment(self, target, event_details)?.on_create_plan(target)
+    }
+
     fn on_pause(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
         execute_long_deployment(
             ApplicationDeploymentReporter::new_for_container(self, target, Action::Pause),
@@ -209,6 +307,14 @@ where
 
                 helm.on_delete(target)?;
 
+                // A preview is left behind only if the engine has been interrupted during a deployment
+                delete_preview(
+                    target,
+                    &preview_chart(self, target),
+                    &self.preview_kube_service_name(),
+                    &event_details,
+                )?;
+
                 let logger = get_loggers(self, Action::Delete);
                 // Delete pvc of statefulset if needed
                 // FIXME: Remove this after kubernetes 1.23 is deployed, at it should be done by kubernetes
@@ -225,6 +331,7 @@ where
                             CommandError::new_from_safe_message(err.to_string()),
                         ));
                     }
+                    delete_volume_snapshots(self.long_id(), target);
                 }
 
                 Ok(())
@@ -248,6 +355,79 @@ where
     }
 }
 
+/// Mirror a user image into our cluster private registry, under the `qovery-mirror` repository
+pub(super) fn mirror_image(
+    registry: &Registry,
+    image: &str,
+    tag: &str,
+    tag_for_mirror: String,
+    target: &DeploymentTarget,
+    event_details: &EventDetails,
+    loggers: &Loggers,
+) -> Result<(), EngineError> {
+    const QOVERY_MIRROR_REPOSITORY_NAME: &str = "qovery-mirror";
+
+    // We need to login to the registry to get access to the image
+    let url = get_url_with_credentials(registry);
+    if url.password().is_some() {
+        (loggers.send_progress)(format!(
+            "🔓 Login to registry {} as user {}",
+            url.host_str().unwrap_or_default(),
+            url.username()
+        ));
+        if let Err(err) = target.docker.login(&url) {
+            let err = EngineError::new_docker_error(event_details.clone(), err);
+            let user_err = EngineError::new_engine_error(
+                err.clone(),
+                format!("❌ Failed to login to registry {}", url.host_str().unwrap_or_default()),
+                None,
+            );
+            (loggers.send_error)(user_err);
+
+            return Err(err);
+        }
+    }
+
+    // Once we are logged to the registry, we mirror the user image into our cluster private registry
+    // This is required only to avoid to manage rotating credentials
+    (loggers.send_progress)("🪞 Mirroring image to private cluster registry to ensure reproducibility".to_string());
+    let registry_info = target.container_registry.registry_info();
+
+    target
+        .container_registry
+        .create_repository(
+            QOVERY_MIRROR_REPOSITORY_NAME,
+            target.kubernetes.advanced_settings().registry_image_retention_time_sec,
+        )
+        .map_err(|err| EngineError::new_container_registry_error(event_details.clone(), err))?;
+
+    let source_image = ContainerImage::new(registry.url().clone(), image.to_string(), vec![tag.to_string()]);
+    let dest_image = ContainerImage::new(
+        target.container_registry.registry_info().endpoint.clone(),
+        (registry_info.get_image_name)(QOVERY_MIRROR_REPOSITORY_NAME),
+        vec![tag_for_mirror],
+    );
+    if let Err(err) = target.docker.mirror(
+        &source_image,
+        &dest_image,
+        &mut |line| info!("{}", line),
+        &mut |line| warn!("{}", line),
+        &CommandKiller::from_timeout(Duration::from_secs(60 * 10)),
+    ) {
+        let err = EngineError::new_docker_error(event_details.clone(), err);
+        let user_err = EngineError::new_engine_error(
+            err.clone(),
+            format!("❌ Failed to mirror image {}:{}: {}", image, tag, err),
+            None,
+        );
+        (loggers.send_error)(user_err);
+
+        return Err(err);
+    }
+
+    Ok(())
+}
+
 fn get_url_with_credentials(registry: &Registry) -> Url {
     let url = match registry {
         Registry::DockerHub { url, credentials, .. } => {
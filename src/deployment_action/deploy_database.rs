@@ -1,25 +1,39 @@
 use crate::cloud_provider::helm::{ChartInfo, ChartSetValue, HelmAction, HelmChartNamespaces};
 use crate::cloud_provider::service::{delete_pending_service, get_database_terraform_config, Action, Service};
-use crate::cloud_provider::utilities::print_action;
+use crate::cloud_provider::utilities::{opensearch_domain_name, print_action};
 use crate::cloud_provider::Kind::Aws;
 use crate::cloud_provider::{service, DeploymentTarget};
 use crate::cmd;
 use crate::cmd::command::{ExecutableCommand, QoveryCommand};
+use crate::cmd::helm::HelmError;
 use crate::constants::AWS_DEFAULT_REGION;
 use crate::deployment_action::check_dns::CheckDnsForDomains;
+use crate::deployment_action::clone_environment::clone_database_data;
+use crate::deployment_action::database_backup::{schedule_backups, unschedule_backups};
+use crate::deployment_action::database_endpoints::{
+    delete_database_endpoints, deploy_database_endpoints, prepare_read_replicas,
+};
 use crate::deployment_action::deploy_helm::HelmDeployment;
 use crate::deployment_action::deploy_terraform::TerraformDeployment;
+use crate::deployment_action::deployment_plan::DeploymentPlan;
 use crate::deployment_action::pause_service::PauseServiceAction;
+use crate::deployment_action::upgrade_database::upgrade_database_version;
+use crate::deployment_action::volume_snapshot::{
+    check_volume_snapshots_support, create_pre_deploy_volume_snapshots, delete_volume_snapshots,
+    prune_volume_snapshots, schedule_volume_snapshots, ServiceStorage,
+};
 use crate::deployment_action::DeploymentAction;
 use crate::deployment_report::database::reporter::DatabaseDeploymentReporter;
 use crate::deployment_report::execute_long_deployment;
 use crate::deployment_report::logger::get_loggers;
 use crate::errors::{CommandError, EngineError};
 use crate::events::{EnvironmentStep, EventDetails, Stage};
+use crate::io_models::database::DatabaseOptions;
 use crate::models::database::{Container, Database, DatabaseService, DatabaseType, Managed};
 use crate::models::types::{CloudProvider, ToTeraContext};
 use function_name::named;
 use serde::Deserialize;
+use std::convert::TryFrom;
 use std::path::PathBuf;
 use std::thread;
 use std::time::{Duration, Instant};
@@ -64,6 +78,20 @@ struct DocDbClustersResponse {
     pub db_cluster: Vec<DocDbCluster>,
 }
 
+#[derive(Deserialize, Default)]
+struct OpenSearchDomainStatus {
+    #[serde(alias = "Created")]
+    pub created: bool,
+    #[serde(alias = "Processing")]
+    pub processing: bool,
+}
+
+#[derive(Deserialize, Default)]
+struct OpenSearchDomainResponse {
+    #[serde(alias = "DomainStatus")]
+    pub domain_status: OpenSearchDomainStatus,
+}
+
 fn get_managed_database_status(
     db_type: service::DatabaseType,
     db_id: &str,
@@ -96,6 +124,20 @@ fn get_managed_database_status(
                 credentials,
             )
         }
+        service::DatabaseType::OpenSearch => QoveryCommand::new(
+            "aws",
+            &[
+                "opensearch",
+                "describe-domain",
+                "--domain-name",
+                &opensearch_domain_name(db_id),
+            ],
+            credentials,
+        ),
+        // those engines are only deployed as container databases
+        service::DatabaseType::ClickHouse | service::DatabaseType::RabbitMQ | service::DatabaseType::Kafka => {
+            return Ok(DB_READY_STATE.to_string());
+        }
     };
 
     let mut output_stdout: Vec<String> = vec![];
@@ -131,6 +173,18 @@ fn get_managed_database_status(
                 .map(|c| c.cache_cluster_status.clone())
                 .unwrap_or_default())
         }
+        // OpenSearch domains have no status, only flags telling if a change is being applied
+        service::DatabaseType::OpenSearch => {
+            let payload: OpenSearchDomainResponse =
+                serde_json::from_str(output_stdout.join("").as_str()).unwrap_or_default();
+            match payload.domain_status.created && !payload.domain_status.processing {
+                true => Ok(DB_READY_STATE.to_string()),
+                false => Ok("processing".to_string()),
+            }
+        }
+        service::DatabaseType::ClickHouse | service::DatabaseType::RabbitMQ | service::DatabaseType::Kafka => {
+            Ok(DB_READY_STATE.to_string())
+        }
     }
 }
 
@@ -213,6 +267,11 @@ fn start_stop_managed_database(
             // can't pause elasticache
             Ok(())
         }
+        service::DatabaseType::OpenSearch => {
+            // can't pause opensearch domains
+            Ok(())
+        }
+        service::DatabaseType::ClickHouse | service::DatabaseType::RabbitMQ | service::DatabaseType::Kafka => Ok(()),
     };
 
     if let Err(cmd_error) = ret {
@@ -245,42 +304,23 @@ fn await_db_state(
     }
 }
 
-fn on_create_managed_impl<C: CloudProvider, T: DatabaseType<C, Managed>>(
-    db: &Database<C, Managed, T>,
-    event_details: EventDetails,
+/// Values of the `external-name-svc` chart, exposing `target_hostname` in the environment namespace under `service_name`
+pub(super) fn external_name_service_values(
+    db: &dyn Service,
     target: &DeploymentTarget,
-) -> Result<(), EngineError>
-where
-    Database<C, Managed, T>: DatabaseService,
-{
-    let workspace_dir = db.workspace_directory();
-    let tera_context = db.to_tera_context(target)?;
-
-    // Execute terraform to provision database on cloud provider side
-    let terraform_deploy = TerraformDeployment::new(
-        tera_context.clone(),
-        PathBuf::from(db.terraform_common_resource_dir_path()),
-        PathBuf::from(db.terraform_resource_dir_path()),
-        PathBuf::from(&workspace_dir),
-        event_details.clone(),
-        db.context.is_dry_run_deploy(),
-    );
-    terraform_deploy.on_create(target)?;
-
-    // Our terrraform give us back a file with all the info we need to deploy the remaining stuff
-    let database_config =
-        get_database_terraform_config(format!("{}/database-tf-config.json", &workspace_dir,).as_str())
-            .map_err(|err| EngineError::new_terraform_error(event_details.clone(), err))?;
-
-    // Deploy the external service name
-    let values = vec![
+    service_name: String,
+    target_hostname: String,
+    source_fqdn: String,
+    publicly_accessible: bool,
+) -> Vec<ChartSetValue> {
+    vec![
         ChartSetValue {
             key: "target_hostname".to_string(),
-            value: database_config.target_hostname,
+            value: target_hostname,
         },
         ChartSetValue {
             key: "source_fqdn".to_string(),
-            value: database_config.target_fqdn,
+            value: source_fqdn,
         },
         ChartSetValue {
             key: "database_id".to_string(), // here we use the id and not the fqdn_id ¯\_(ツ)_/¯
@@ -304,13 +344,51 @@ where
         },
         ChartSetValue {
             key: "service_name".to_string(),
-            value: database_config.target_fqdn_id,
+            value: service_name,
         },
         ChartSetValue {
             key: "publicly_accessible".to_string(),
-            value: db.publicly_accessible.to_string(),
+            value: publicly_accessible.to_string(),
         },
-    ];
+    ]
+}
+
+fn on_create_managed_impl<C: CloudProvider, T: DatabaseType<C, Managed>>(
+    db: &Database<C, Managed, T>,
+    event_details: EventDetails,
+    target: &DeploymentTarget,
+) -> Result<(), EngineError>
+where
+    Database<C, Managed, T>: DatabaseService,
+{
+    let workspace_dir = db.workspace_directory();
+    let tera_context = db.to_tera_context(target)?;
+
+    // Execute terraform to provision database on cloud provider side
+    let terraform_deploy = TerraformDeployment::new(
+        tera_context.clone(),
+        PathBuf::from(db.terraform_common_resource_dir_path()),
+        PathBuf::from(db.terraform_resource_dir_path()),
+        PathBuf::from(&workspace_dir),
+        event_details.clone(),
+        db.context.is_dry_run_deploy(),
+    );
+    terraform_deploy.on_create(target)?;
+
+    // Our terrraform give us back a file with all the info we need to deploy the remaining stuff
+    let database_config =
+        get_database_terraform_config(format!("{}/database-tf-config.json", &workspace_dir,).as_str())
+            .map_err(|err| EngineError::new_terraform_error(event_details.clone(), err))?;
+
+    // Deploy the external service name
+    let values = external_name_service_values(
+        db,
+        target,
+        database_config.target_fqdn_id,
+        database_config.target_hostname,
+        database_config.target_fqdn,
+        db.publicly_accessible,
+    );
 
     let chart = ChartInfo {
         name: format!("{}-externalname", db.fqdn_id), // here it is the fqdn id :O
@@ -404,6 +482,21 @@ where
         })
     }
 
+    fn on_create_plan(&self, target: &DeploymentTarget) -> Result<DeploymentPlan, EngineError> {
+        let event_details = self.get_event_details(Stage::Environment(EnvironmentStep::Deploy));
+        let terraform_deploy = TerraformDeployment::new(
+            self.to_tera_context(target)?,
+            PathBuf::from(self.terraform_common_resource_dir_path()),
+            PathBuf::from(self.terraform_resource_dir_path()),
+            PathBuf::from(self.workspace_directory()),
+            event_details,
+            self.context.is_dry_run_deploy(),
+        );
+
+        // the external name service can only be rendered once terraform has been applied
+        terraform_deploy.on_create_plan(target)
+    }
+
     #[named]
     fn on_create_check(&self) -> Result<(), EngineError> {
         let event_details = self.get_event_details(Stage::Environment(EnvironmentStep::Deploy));
@@ -448,8 +541,8 @@ where
                 return Ok(());
             }
 
-            // Elasticache does not support being stopped/paused
-            if self.db_type() == service::DatabaseType::Redis {
+            // Elasticache and OpenSearch do not support being stopped/paused
+            if matches!(self.db_type(), service::DatabaseType::Redis | service::DatabaseType::OpenSearch) {
                 return Ok(());
             }
 
@@ -549,8 +642,62 @@ where
     }
 }
 
+/// `overrides`: values replacing the database ones, i.e the version to deploy the intermediate versions of an upgrade
+fn container_database_helm_deployment<C: CloudProvider, T: DatabaseType<C, Container>>(
+    db: &Database<C, Container, T>,
+    target: &DeploymentTarget,
+    event_details: EventDetails,
+    overrides: Option<tera::Context>,
+) -> Result<HelmDeployment, EngineError>
+where
+    Database<C, Container, T>: ToTeraContext,
+{
+    let chart = ChartInfo {
+        name: db.helm_release_name(),
+        path: db.workspace_directory(),
+        namespace: HelmChartNamespaces::Custom,
+        custom_namespace: Some(target.environment.namespace().to_string()),
+        k8s_selector: Some(db.selector()),
+        values_files: vec![format!("{}/qovery-values.yaml", db.workspace_directory())],
+        ..Default::default()
+    };
+    let mut tera_context = db.to_tera_context(target)?;
+    if let Some(overrides) = overrides {
+        tera_context.extend(overrides);
+    }
+
+    Ok(HelmDeployment::new(
+        event_details,
+        tera_context,
+        PathBuf::from(db.helm_chart_dir()),
+        Some(PathBuf::from(format!("{}/qovery-values.j2.yaml", db.helm_chart_values_dir()))),
+        chart,
+    ))
+}
+
+/// The volumes of a container database, snapshotted as a single storage
+fn container_database_storage<C, T>(db: &Database<C, Container, T>) -> ServiceStorage<'_>
+where
+    C: CloudProvider,
+    T: DatabaseType<C, Container, DatabaseOptions = DatabaseOptions>,
+{
+    ServiceStorage {
+        long_id: db.long_id,
+        storage_class: &db.options.database_disk_type,
+        volume_snapshot_class: C::volume_snapshot_class(),
+        size_in_gib: u16::try_from(db.options.disk_size_in_gib).unwrap_or(u16::MAX),
+        snapshot_schedule: &db.options.snapshot_schedule,
+        snapshot_retention_in_days: db.options.snapshot_retention_in_days,
+        // volumes of a database are restored from its backups
+        restore_from_snapshot: None,
+        pvc_selector: db.selector(),
+        first_pvc_name: format!("data-{}-0", db.sanitized_name()),
+    }
+}
+
 // For Container database
-impl<C: CloudProvider, T: DatabaseType<C, Container>> DeploymentAction for Database<C, Container, T>
+impl<C: CloudProvider, T: DatabaseType<C, Container, DatabaseOptions = DatabaseOptions>> DeploymentAction
+    for Database<C, Container, T>
 where
     Database<C, Container, T>: ToTeraContext,
 {
@@ -567,24 +714,33 @@ where
         );
 
         execute_long_deployment(DatabaseDeploymentReporter::new(self, target, Action::Create), || {
-            let chart = ChartInfo {
-                name: self.helm_release_name(),
-                path: self.workspace_directory(),
-                namespace: HelmChartNamespaces::Custom,
-                custom_namespace: Some(target.environment.namespace().to_string()),
-                k8s_selector: Some(self.selector()),
-                values_files: vec![format!("{}/qovery-values.yaml", self.workspace_directory())],
-                ..Default::default()
-            };
-            let helm = HelmDeployment::new(
-                event_details.clone(),
-                self.to_tera_context(target)?,
-                PathBuf::from(self.helm_chart_dir()),
-                Some(PathBuf::from(format!("{}/qovery-values.j2.yaml", self.helm_chart_values_dir()))),
-                chart,
+            let helm = container_database_helm_deployment(self, target, event_details.clone(), None)?;
+            // data of a cloned database are copied on its creation, and the copy is retried until it succeeds
+            let is_new_database = matches!(
+                target.helm.check_release_exist(&helm.helm_chart, &[]),
+                Err(HelmError::ReleaseDoesNotExist(_))
             );
-
+            let deploy_with = |overrides: tera::Context| {
+                container_database_helm_deployment(self, target, event_details.clone(), Some(overrides))?
+                    .on_create(target)
+            };
+            let storages = [container_database_storage(self)];
+            check_volume_snapshots_support(self, &storages, target, &event_details)?;
+            if !is_new_database {
+                create_pre_deploy_volume_snapshots(self, &storages, target, &event_details)?;
+                upgrade_database_version(self, target, event_details.clone(), &|version| {
+                    let mut overrides = tera::Context::new();
+                    overrides.insert("version", version);
+                    deploy_with(overrides)
+                })?;
+                prepare_read_replicas(self, target, event_details.clone(), &deploy_with)?;
+            }
             helm.on_create(target)?;
+            clone_database_data(self, target, event_details.clone(), is_new_database)?;
+            deploy_database_endpoints(self, target, event_details.clone())?;
+            schedule_backups(self, target, event_details.clone())?;
+            prune_volume_snapshots(self, &storages, target, &event_details);
+            schedule_volume_snapshots(self, &storages, target, &event_details)?;
 
             delete_pending_service(
                 target.kubernetes.get_kubeconfig_file_path()?.as_str(),
@@ -598,6 +754,11 @@ where
         })
     }
 
+    fn on_create_plan(&self, target: &DeploymentTarget) -> Result<DeploymentPlan, EngineError> {
+        let event_details = self.get_event_details(Stage::Environment(EnvironmentStep::Deploy));
+        container_database_helm_deployment(self, target, event_details, None)?.on_create_plan(target)
+    }
+
     #[named]
     fn on_create_check(&self) -> Result<(), EngineError> {
         let event_details = self.get_event_details(Stage::Environment(EnvironmentStep::Deploy));
@@ -676,8 +837,11 @@ where
                 chart,
             );
 
-            helm.on_delete(target)
+            helm.on_delete(target)?;
+            delete_database_endpoints(self, target, event_details.clone())?;
+            delete_volume_snapshots(&self.long_id, target);
             // FIXME delete pvc
+            unschedule_backups(self, target, event_details.clone())
         })
     }
 }
@@ -1,18 +1,24 @@
 use crate::cloud_provider::aws::load_balancers::clean_up_deleted_k8s_nlb;
 use crate::cloud_provider::environment::Environment;
+use crate::cloud_provider::service::{Action, Service};
 use crate::cloud_provider::DeploymentTarget;
 use crate::cmd::kubectl::kubectl_exec_is_namespace_present;
 use crate::deployment_action::deploy_namespace::NamespaceDeployment;
+use crate::deployment_action::deployment_graph::DeploymentGraph;
+use crate::deployment_action::deployment_plan::{plan_service_action, EnvironmentPlan};
+use crate::deployment_action::helm_revisions::{record_helm_revisions, record_workload_replicas, HelmReleaseRevision};
 use crate::deployment_action::DeploymentAction;
 use crate::engine::EngineConfig;
 use crate::errors::EngineError;
 use crate::events::EventDetails;
+use crate::metrics::{StepTimer, TimingKind};
 use std::collections::HashSet;
 use std::time::Duration;
 use uuid::Uuid;
 
 pub struct EnvironmentDeployment<'a> {
     pub deployed_services: HashSet<Uuid>,
+    pub helm_revisions: Vec<HelmReleaseRevision>,
     deployment_target: DeploymentTarget<'a>,
     event_details: EventDetails,
 }
@@ -26,6 +32,7 @@ impl<'a> EnvironmentDeployment<'a> {
         let deployment_target = DeploymentTarget::new(engine_config, environment, &event_details)?;
         Ok(EnvironmentDeployment {
             deployed_services: Default::default(),
+            helm_revisions: vec![],
             deployment_target,
             event_details,
         })
@@ -42,36 +49,17 @@ impl<'a> EnvironmentDeployment<'a> {
                 .context()
                 .resource_expiration_in_seconds()
                 .map(|ttl| Duration::from_secs(ttl as u64)),
+            idle_timeout: environment.idle_timeout,
             event_details: self.event_details.clone(),
         };
         ns.exec_action(target, environment.action)?;
 
-        // create all stateful services (database)
-        for service in &environment.databases {
-            self.deployed_services.insert(*service.long_id());
-            service.exec_action(target, *service.action())?;
-            service.exec_check_action(*service.action())?;
-        }
-
-        for service in &environment.containers {
-            self.deployed_services.insert(*service.long_id());
-            service.exec_action(target, *service.action())?;
-            service.exec_check_action(*service.action())?;
-        }
+        // keep track of the current revisions, to be able to rollback services if the deployment fails
+        self.helm_revisions = record_helm_revisions(target);
 
-        // create all applications
-        for service in &environment.applications {
-            self.deployed_services.insert(*service.long_id());
-            service.exec_action(target, *service.action())?;
-            service.exec_check_action(*service.action())?;
-        }
-
-        // create all routers
-        for service in &environment.routers {
-            self.deployed_services.insert(*service.long_id());
-            service.exec_action(target, *service.action())?;
-            service.exec_check_action(*service.action())?;
-        }
+        // deploy services following their dependencies, independent ones are deployed concurrently
+        let graph = DeploymentGraph::from_environment(environment);
+        execute_services_action(target, &graph, None, &mut self.deployed_services, &self.event_details)?;
 
         // clean up nlb
         clean_up_deleted_k8s_nlb(self.event_details.clone(), target)?;
@@ -80,32 +68,21 @@ impl<'a> EnvironmentDeployment<'a> {
     }
 
     pub fn on_pause(&mut self) -> Result<(), EngineError> {
-        let target = &mut self.deployment_target;
+        let target = &self.deployment_target;
         let environment = &target.environment;
 
-        for service in &environment.routers {
-            self.deployed_services.insert(*service.long_id());
-            service.on_pause(target)?;
-            service.on_pause_check()?;
-        }
-
-        for service in &environment.applications {
-            self.deployed_services.insert(*service.long_id());
-            service.on_pause(target)?;
-            service.on_pause_check()?;
-        }
+        self.helm_revisions = record_helm_revisions(target);
+        record_workload_replicas(target, &mut self.helm_revisions);
 
-        for service in &environment.containers {
-            self.deployed_services.insert(*service.long_id());
-            service.on_pause(target)?;
-            service.on_pause_check()?;
-        }
-
-        for service in &environment.databases {
-            self.deployed_services.insert(*service.long_id());
-            service.on_pause(target)?;
-            service.on_pause_check()?;
-        }
+        // services are paused before the ones they depend on, databases last
+        let graph = DeploymentGraph::teardown_from_environment(environment);
+        execute_services_action(
+            target,
+            &graph,
+            Some(Action::Pause),
+            &mut self.deployed_services,
+            &self.event_details,
+        )?;
 
         let ns = NamespaceDeployment {
             resource_expiration: target
@@ -113,6 +90,7 @@ impl<'a> EnvironmentDeployment<'a> {
                 .context()
                 .resource_expiration_in_seconds()
                 .map(|ttl| Duration::from_secs(ttl as u64)),
+            idle_timeout: environment.idle_timeout,
             event_details: self.event_details.clone(),
         };
         ns.on_pause(target)?;
@@ -137,31 +115,17 @@ impl<'a> EnvironmentDeployment<'a> {
             return Ok(());
         };
 
-        // delete all stateless services (router, application...)
-        for service in &environment.routers {
-            self.deployed_services.insert(*service.long_id());
-            service.on_delete(target)?;
-            service.on_delete_check()?;
-        }
-
-        for service in &environment.applications {
-            self.deployed_services.insert(*service.long_id());
-            service.on_delete(target)?;
-            service.on_delete_check()?;
-        }
+        self.helm_revisions = record_helm_revisions(target);
 
-        for service in &environment.containers {
-            self.deployed_services.insert(*service.long_id());
-            service.on_delete(target)?;
-            service.on_delete_check()?;
-        }
-
-        // delete all stateful services (database)
-        for service in &environment.databases {
-            self.deployed_services.insert(*service.long_id());
-            service.on_delete(target)?;
-            service.on_delete_check()?
-        }
+        // services are deleted before the ones they depend on, databases last
+        let graph = DeploymentGraph::teardown_from_environment(environment);
+        execute_services_action(
+            target,
+            &graph,
+            Some(Action::Delete),
+            &mut self.deployed_services,
+            &self.event_details,
+        )?;
 
         let ns = NamespaceDeployment {
             resource_expiration: target
@@ -169,10 +133,141 @@ impl<'a> EnvironmentDeployment<'a> {
                 .context()
                 .resource_expiration_in_seconds()
                 .map(|ttl| Duration::from_secs(ttl as u64)),
+            idle_timeout: environment.idle_timeout,
             event_details: self.event_details.clone(),
         };
         ns.on_delete(target)?;
 
         Ok(())
     }
+
+    /// Compute what the deployment would create, update or delete, without touching the cluster.
+    /// `forced_action` overrides the action of every service, as for a pause or a delete of the environment.
+    pub fn plan(&self, forced_action: Option<Action>) -> Result<EnvironmentPlan, EngineError> {
+        let target = &self.deployment_target;
+        let environment = target.environment;
+
+        let mut services = vec![];
+        for database in &environment.databases {
+            let action = forced_action.unwrap_or(*database.action());
+            services.push(plan_service_action(
+                database.as_ref(),
+                database.helm_release_name(),
+                target,
+                action,
+            )?);
+        }
+        for container in &environment.containers {
+            let action = forced_action.unwrap_or(*container.action());
+            services.push(plan_service_action(
+                container.as_ref(),
+                Some(container.helm_release_name()),
+                target,
+                action,
+            )?);
+        }
+        for application in &environment.applications {
+            let action = forced_action.unwrap_or(*application.action());
+            services.push(plan_service_action(
+                application.as_ref(),
+                Some(application.helm_release_name()),
+                target,
+                action,
+            )?);
+        }
+        for job in &environment.jobs {
+            let action = forced_action.unwrap_or(*job.action());
+            services.push(plan_service_action(
+                job.as_ref(),
+                Some(job.helm_release_name()),
+                target,
+                action,
+            )?);
+        }
+        for router in &environment.routers {
+            // routers are left as is when the environment is paused
+            let action = match forced_action.unwrap_or(*router.action()) {
+                Action::Pause => Action::Nothing,
+                action => action,
+            };
+            services.push(plan_service_action(
+                router.as_ref(),
+                Some(router.helm_release_name()),
+                target,
+                action,
+            )?);
+        }
+
+        Ok(EnvironmentPlan {
+            long_id: environment.long_id,
+            namespace: environment.namespace().to_string(),
+            services,
+        })
+    }
+
+    /// Recorded revisions of the services the deployment has started to deploy/pause/delete
+    pub fn touched_helm_revisions(&self) -> Vec<HelmReleaseRevision> {
+        self.helm_revisions
+            .iter()
+            .filter(|revision| self.deployed_services.contains(&revision.service_long_id))
+            .cloned()
+            .collect()
+    }
+}
+
+fn execute_services_action(
+    target: &DeploymentTarget,
+    graph: &DeploymentGraph,
+    forced_action: Option<Action>,
+    deployed_services: &mut HashSet<Uuid>,
+    event_details: &EventDetails,
+) -> Result<(), EngineError> {
+    if let Some(cycle) = graph.find_cycle() {
+        return Err(EngineError::new_environment_services_dependency_cycle(
+            event_details.clone(),
+            cycle,
+        ));
+    }
+
+    fn exec<S: Service + DeploymentAction + ?Sized>(
+        service: &S,
+        target: &DeploymentTarget,
+        forced_action: Option<Action>,
+    ) -> Result<(), EngineError> {
+        let action = forced_action.unwrap_or(*service.action());
+        let action_name = match action {
+            Action::Create => "deploy",
+            Action::Pause => "pause",
+            Action::Delete => "delete",
+            Action::Nothing => "nothing",
+        };
+        let _timer = StepTimer::start(
+            TimingKind::ServiceAction,
+            format!("{} {} {}", action_name, service.service_type().name(), service.name()),
+        );
+        service.exec_action(target, action)?;
+        service.exec_check_action(action)
+    }
+
+    let environment = target.environment;
+    let max_parallel = target.kubernetes.advanced_settings().deployment_max_parallel_services as usize;
+    graph.execute(max_parallel, deployed_services, |service_id| {
+        if let Some(service) = environment.databases.iter().find(|s| s.long_id() == service_id) {
+            return exec(service.as_ref(), target, forced_action);
+        }
+        if let Some(service) = environment.containers.iter().find(|s| s.long_id() == service_id) {
+            return exec(service.as_ref(), target, forced_action);
+        }
+        if let Some(service) = environment.applications.iter().find(|s| s.long_id() == service_id) {
+            return exec(service.as_ref(), target, forced_action);
+        }
+        if let Some(service) = environment.jobs.iter().find(|s| s.long_id() == service_id) {
+            return exec(service.as_ref(), target, forced_action);
+        }
+        if let Some(service) = environment.routers.iter().find(|s| s.long_id() == service_id) {
+            return exec(service.as_ref(), target, forced_action);
+        }
+
+        Ok(())
+    })
 }
@@ -1,5 +1,7 @@
 use crate::cloud_provider::helm::ChartInfo;
 use crate::cloud_provider::DeploymentTarget;
+use crate::cmd::helm::HelmError;
+use crate::deployment_action::deployment_plan::DeploymentPlan;
 use crate::deployment_action::DeploymentAction;
 use crate::errors::EngineError;
 use crate::events::EventDetails;
@@ -92,6 +94,23 @@ impl DeploymentAction for HelmDeployment {
             .map_err(|e| EngineError::new_helm_error(self.event_details.clone(), e))
     }
 
+    fn on_create_plan(&self, target: &DeploymentTarget) -> Result<DeploymentPlan, EngineError> {
+        self.prepare_helm_chart()?;
+
+        let release_exists = match target.helm.check_release_exist(&self.helm_chart, &[]) {
+            Ok(_) => true,
+            Err(HelmError::ReleaseDoesNotExist(_)) => false,
+            Err(err) => return Err(EngineError::new_helm_error(self.event_details.clone(), err)),
+        };
+
+        let diff = target
+            .helm
+            .upgrade_diff(&self.helm_chart, &[])
+            .map_err(|e| EngineError::new_helm_error(self.event_details.clone(), e))?;
+
+        Ok(DeploymentPlan::from_helm_diff(release_exists, diff))
+    }
+
     fn on_pause(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
         Ok(())
     }
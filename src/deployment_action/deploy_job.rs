@@ -0,0 +1,255 @@
+use crate::build_platform::Image;
+use crate::cloud_provider::helm::{ChartInfo, HelmAction, HelmChartNamespaces};
+use crate::cloud_provider::service::{Action, Service};
+use crate::cloud_provider::DeploymentTarget;
+use crate::deployment_action::clone_environment::cloned_environment_variables;
+use crate::deployment_action::deploy_container::mirror_image;
+use crate::deployment_action::deploy_helm::HelmDeployment;
+use crate::deployment_action::deployment_plan::{DeploymentPlan, PlannedChange};
+use crate::deployment_action::secret_environment_variables::with_secret_environment_variables;
+use crate::deployment_action::DeploymentAction;
+use crate::deployment_report::execute_long_deployment;
+use crate::deployment_report::job::reporter::JobDeploymentReporter;
+use crate::deployment_report::logger::get_loggers;
+use crate::errors::{CommandError, EngineError, Tag};
+use crate::events::{EnvironmentStep, EventDetails, Stage};
+use crate::kubers_utils::kube_delete_all_from_selector;
+use crate::models::job::{ImageSource, Job, JobService};
+use crate::models::types::{CloudProvider, ToTeraContext};
+use crate::runtime::block_on;
+use k8s_openapi::api::batch::v1::{CronJob, Job as K8sJob};
+use kube::api::{ListParams, Patch, PatchParams};
+use kube::Api;
+use std::path::PathBuf;
+use std::time::Duration;
+
+fn helm_deployment<T: CloudProvider>(
+    job: &Job<T>,
+    target: &DeploymentTarget,
+    event_details: EventDetails,
+) -> Result<HelmDeployment, EngineError>
+where
+    Job<T>: ToTeraContext,
+{
+    // For one-off jobs, helm waits for the job to complete as it is a post-install/upgrade hook
+    let timeout = if job.is_cron_job() {
+        Duration::from_secs(10 * 60)
+    } else {
+        job.max_duration() + Duration::from_secs(5 * 60)
+    };
+
+    let chart = ChartInfo {
+        name: job.helm_release_name(),
+        path: job.workspace_directory(),
+        namespace: HelmChartNamespaces::Custom,
+        custom_namespace: Some(target.environment.namespace().to_string()),
+        timeout_in_seconds: timeout.as_secs() as i64,
+        k8s_selector: Some(job.selector()),
+        ..Default::default()
+    };
+
+    let mut tera_context = job.to_tera_context(target)?;
+    let environment_variables = cloned_environment_variables(
+        job,
+        target,
+        "qovery.com/service-id",
+        job.environment_variables(),
+        event_details.clone(),
+    )?
+    .unwrap_or_else(|| job.environment_variables().to_vec());
+    let environment_variables = with_secret_environment_variables(
+        job,
+        target,
+        environment_variables,
+        job.secret_environment_variables(),
+        &event_details,
+    )?;
+    tera_context.insert("environment_variables", &environment_variables);
+
+    Ok(HelmDeployment::new(
+        event_details,
+        tera_context,
+        PathBuf::from(job.helm_chart_dir()),
+        None,
+        chart,
+    ))
+}
+
+impl<T: CloudProvider> DeploymentAction for Job<T>
+where
+    Job<T>: ToTeraContext,
+{
+    fn on_create(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        let event_details = self.get_event_details(Stage::Environment(EnvironmentStep::Deploy));
+
+        // Images from a registry are mirrored into our cluster private registry, built ones are already there
+        if let ImageSource::Registry { registry, image, tag } = self.image_source() {
+            let loggers = get_loggers(self, *self.action());
+            mirror_image(
+                registry,
+                image,
+                tag,
+                self.tag_for_mirror().unwrap_or_default(),
+                target,
+                &event_details,
+                &loggers,
+            )?;
+        }
+
+        execute_long_deployment(JobDeploymentReporter::new(self, target, Action::Create), || {
+            let helm = helm_deployment(self, target, event_details.clone())?;
+            helm.on_create(target).map_err(|err| {
+                // A timeout is reported as such, otherwise try to tell the user the job itself has failed
+                if self.is_cron_job() || err.tag() == &Tag::HelmDeployTimeout {
+                    return err;
+                }
+
+                match block_on(get_failed_job_name(
+                    target.kube.clone(),
+                    &self.selector(),
+                    target.environment.namespace(),
+                )) {
+                    Some(job_name) => EngineError::new_k8s_job_failed(
+                        event_details.clone(),
+                        job_name,
+                        err.underlying_error()
+                            .unwrap_or_else(|| CommandError::new_from_safe_message(err.user_log_message().to_string())),
+                    ),
+                    None => err,
+                }
+            })
+        })
+    }
+
+    fn on_create_plan(&self, target: &DeploymentTarget) -> Result<DeploymentPlan, EngineError> {
+        // the image is only mirrored during the deployment, the chart is rendered with its future location
+        let event_details = self.get_event_details(Stage::Environment(EnvironmentStep::Deploy));
+        let plan = helm_deployment(self, target, event_details)?.on_create_plan(target)?;
+
+        // a one-off job runs at each deployment, even if its chart has not changed
+        if !self.is_cron_job() && plan.change.is_none() {
+            return Ok(plan.merge(DeploymentPlan::new_change(
+                PlannedChange::Update,
+                vec![format!("job {} would run", self.name())],
+            )));
+        }
+
+        Ok(plan)
+    }
+
+    fn on_pause(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        // A one-off job has already run, there is nothing left to pause
+        if !self.is_cron_job() {
+            return Ok(());
+        }
+
+        let event_details = self.get_event_details(Stage::Environment(EnvironmentStep::Pause));
+        execute_long_deployment(JobDeploymentReporter::new(self, target, Action::Pause), || {
+            let suspend = block_on(async {
+                tokio::time::timeout(
+                    Duration::from_secs(5 * 60),
+                    suspend_cron_jobs(&target.kube, target.environment.namespace(), &self.selector()),
+                )
+                .await
+            });
+
+            match suspend {
+                Ok(Ok(())) => Ok(()),
+                Ok(Err(kube_err)) => Err(EngineError::new_k8s_cannot_suspend_cronjob(
+                    event_details.clone(),
+                    self.selector(),
+                    target.environment.namespace().to_string(),
+                    CommandError::new_from_safe_message(kube_err.to_string()),
+                )),
+                Err(_) => Err(EngineError::new_k8s_cannot_suspend_cronjob(
+                    event_details.clone(),
+                    self.selector(),
+                    target.environment.namespace().to_string(),
+                    CommandError::new_from_safe_message("Timeout while suspending the cron job".to_string()),
+                )),
+            }
+        })
+    }
+
+    fn on_delete(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        let event_details = self.get_event_details(Stage::Environment(EnvironmentStep::Delete));
+
+        execute_long_deployment(JobDeploymentReporter::new(self, target, Action::Delete), || {
+            let chart = ChartInfo {
+                name: self.helm_release_name(),
+                namespace: HelmChartNamespaces::Custom,
+                custom_namespace: Some(target.environment.namespace().to_string()),
+                action: HelmAction::Destroy,
+                ..Default::default()
+            };
+            let helm = HelmDeployment::new(
+                event_details.clone(),
+                self.to_tera_context(target)?,
+                PathBuf::from(self.helm_chart_dir().as_str()),
+                None,
+                chart,
+            );
+
+            helm.on_delete(target)?;
+
+            // Jobs created by helm hooks are not part of the release, so they are not removed with it
+            if let Err(err) = block_on(kube_delete_all_from_selector::<K8sJob>(
+                &target.kube,
+                &self.selector(),
+                target.environment.namespace(),
+            )) {
+                return Err(EngineError::new_k8s_cannot_delete_completed_jobs(
+                    event_details.clone(),
+                    CommandError::new_from_safe_message(err.to_string()),
+                ));
+            }
+
+            Ok(())
+        })?;
+
+        let tag_for_mirror = match self.tag_for_mirror() {
+            Some(tag) => tag,
+            None => return Ok(()),
+        };
+
+        let image = Image {
+            name: Self::QOVERY_MIRROR_REPOSITORY_NAME.to_string(),
+            tag: tag_for_mirror,
+            registry_url: target.container_registry.registry_info().endpoint.clone(),
+            repository_name: Self::QOVERY_MIRROR_REPOSITORY_NAME.to_string(),
+            ..Default::default()
+        };
+
+        let logger = get_loggers(self, Action::Delete);
+        (logger.send_success)("🪓 Deleting cached image of the job".to_string());
+        target
+            .container_registry
+            .delete_image(&image)
+            .map_err(|err| EngineError::new_container_registry_error(event_details, err))
+    }
+}
+
+async fn suspend_cron_jobs(kube: &kube::Client, namespace: &str, selector: &str) -> Result<(), kube::Error> {
+    let cron_jobs: Api<CronJob> = Api::namespaced(kube.clone(), namespace);
+    let patch = Patch::Merge(serde_json::json!({ "spec": { "suspend": true } }));
+
+    for cron_job in cron_jobs.list(&ListParams::default().labels(selector)).await? {
+        if let Some(name) = cron_job.metadata.name {
+            cron_jobs.patch(&name, &PatchParams::default(), &patch).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn get_failed_job_name(client: kube::Client, selector: &str, namespace: &str) -> Option<String> {
+    let api: Api<K8sJob> = Api::namespaced(client, namespace);
+    api.list(&ListParams::default().labels(selector))
+        .await
+        .ok()?
+        .items
+        .into_iter()
+        .find(|job| job.status.as_ref().and_then(|status| status.failed).unwrap_or(0) > 0)?
+        .metadata
+        .name
+}
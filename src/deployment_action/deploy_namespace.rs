@@ -2,6 +2,7 @@ use crate::cloud_provider::kubernetes::{
     kube_copy_secret_to_another_namespace, kube_create_namespace_if_not_exists, kube_does_secret_exists, Kind,
 };
 use crate::cloud_provider::DeploymentTarget;
+use crate::deployment_action::scale_to_zero::configure_scale_to_zero;
 use crate::deployment_action::DeploymentAction;
 use crate::errors::{CommandError, EngineError};
 use crate::events::EventDetails;
@@ -14,9 +15,31 @@ use std::time::Duration;
 
 pub struct NamespaceDeployment {
     pub resource_expiration: Option<Duration>,
+    pub idle_timeout: Option<Duration>,
     pub event_details: EventDetails,
 }
 
+impl NamespaceDeployment {
+    fn configure_scale_to_zero(
+        &self,
+        target: &DeploymentTarget,
+        idle_timeout: Option<Duration>,
+    ) -> Result<(), EngineError> {
+        block_on(configure_scale_to_zero(
+            &target.kube,
+            target.environment.namespace(),
+            idle_timeout,
+        ))
+        .map_err(|e| {
+            EngineError::new_k8s_configure_scale_to_zero(
+                self.event_details.clone(),
+                target.environment.namespace().to_string(),
+                e,
+            )
+        })
+    }
+}
+
 impl DeploymentAction for NamespaceDeployment {
     fn on_create(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
         let mut namespace_labels: Option<BTreeMap<String, String>> = None;
@@ -70,11 +93,12 @@ impl DeploymentAction for NamespaceDeployment {
             };
         };
 
-        Ok(())
+        self.configure_scale_to_zero(target, self.idle_timeout)
     }
 
-    fn on_pause(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
-        Ok(())
+    fn on_pause(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        // a paused environment must not be woken up by an incoming request
+        self.configure_scale_to_zero(target, None)
     }
 
     fn on_delete(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
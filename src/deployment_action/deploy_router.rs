@@ -3,18 +3,60 @@ use crate::cloud_provider::models::CustomDomain;
 use crate::cloud_provider::service::{Action, Service};
 use crate::cloud_provider::utilities::print_action;
 use crate::cloud_provider::DeploymentTarget;
+use crate::deployment_action::check_certificate::CheckCertificateForDomains;
 use crate::deployment_action::check_dns::CheckDnsForDomains;
+use crate::deployment_action::custom_certificate::{
+    delete_custom_certificates, delete_stale_custom_certificates, deploy_custom_certificates,
+};
 use crate::deployment_action::deploy_helm::HelmDeployment;
+use crate::deployment_action::deployment_plan::DeploymentPlan;
 use crate::deployment_action::DeploymentAction;
 use crate::deployment_report::execute_long_deployment;
 use crate::deployment_report::logger::get_loggers;
 use crate::deployment_report::router::reporter::RouterDeploymentReporter;
 use crate::errors::EngineError;
-use crate::events::{EnvironmentStep, Stage};
-use crate::models::router::{Router, RouterService};
+use crate::events::{EnvironmentStep, EventDetails, Stage};
+use crate::models::router::{Router, RouterAdvancedSettings, RouterService};
 use crate::models::types::{CloudProvider, ToTeraContext};
 use function_name::named;
 use std::path::PathBuf;
+use std::time::Duration;
+
+// a required certificate is waited for even when no timeout has been set
+const REQUIRED_CERTIFICATE_DEFAULT_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// The deployment only waits for the certificate when a timeout is set, or when it is required
+fn certificate_check_timeout(advanced_settings: &RouterAdvancedSettings) -> Duration {
+    match advanced_settings.custom_domain_certificate_timeout_sec {
+        0 if advanced_settings.custom_domain_certificate_required => REQUIRED_CERTIFICATE_DEFAULT_TIMEOUT,
+        timeout_sec => Duration::from_secs(timeout_sec as u64),
+    }
+}
+
+fn helm_deployment<T: CloudProvider>(
+    router: &Router<T>,
+    target: &DeploymentTarget,
+    event_details: EventDetails,
+) -> Result<HelmDeployment, EngineError>
+where
+    Router<T>: RouterService,
+{
+    let chart = ChartInfo {
+        name: router.helm_release_name(),
+        path: router.workspace_directory(),
+        namespace: HelmChartNamespaces::Custom,
+        custom_namespace: Some(target.environment.namespace().to_string()),
+        ..Default::default()
+    };
+
+    Ok(HelmDeployment::new(
+        event_details,
+        router.to_tera_context(target)?,
+        PathBuf::from(router.helm_chart_dir()),
+        None,
+        chart,
+    ))
+}
 
 impl<T: CloudProvider> DeploymentAction for Router<T>
 where
@@ -33,26 +75,38 @@ where
         );
 
         execute_long_deployment(RouterDeploymentReporter::new(self, target, Action::Create), || {
-            let chart = ChartInfo {
-                name: self.helm_release_name(),
-                path: self.workspace_directory(),
-                namespace: HelmChartNamespaces::Custom,
-                custom_namespace: Some(target.environment.namespace().to_string()),
-                ..Default::default()
-            };
-
-            let helm = HelmDeployment::new(
-                event_details.clone(),
-                self.to_tera_context(target)?,
-                PathBuf::from(self.helm_chart_dir()),
-                None,
-                chart,
-            );
+            deploy_custom_certificates(self, target, &event_details)?;
+            helm_deployment(self, target, event_details.clone())?.on_create(target)?;
+            delete_stale_custom_certificates(self, target, &event_details)?;
 
-            helm.on_create(target)
+            // custom domains without a supplied certificate get one from cert-manager, default domains use the cluster wildcard one
+            let managed_domains: Vec<String> = self
+                .custom_domains
+                .iter()
+                .filter(|cd| cd.certificate.is_none())
+                .map(|cd| cd.domain.clone())
+                .collect();
+            if !self.advanced_settings.custom_domain_check_enabled || managed_domains.is_empty() {
+                return Ok(());
+            }
+            let certificate_check = CheckCertificateForDomains {
+                certificate_name: format!("router-tls-{}", self.id()),
+                domains: managed_domains,
+                timeout: certificate_check_timeout(&self.advanced_settings),
+                fail_on_timeout: self.advanced_settings.custom_domain_certificate_required,
+                event_details: event_details.clone(),
+                logger: self.logger().clone_dyn(),
+                log: get_loggers(self, Action::Create).send_progress,
+            };
+            certificate_check.on_create(target)
         })
     }
 
+    fn on_create_plan(&self, target: &DeploymentTarget) -> Result<DeploymentPlan, EngineError> {
+        let event_details = self.get_event_details(Stage::Environment(EnvironmentStep::Deploy));
+        helm_deployment(self, target, event_details)?.on_create_plan(target)
+    }
+
     #[named]
     fn on_create_check(&self) -> Result<(), EngineError> {
         let event_details = self.get_event_details(Stage::Environment(EnvironmentStep::Deploy));
@@ -105,7 +159,7 @@ where
             "router",
             function_name!(),
             self.name(),
-            event_details,
+            event_details.clone(),
             self.logger(),
         );
 
@@ -125,8 +179,8 @@ where
                 chart,
             );
 
-            helm.on_delete(target)
-            // FIXME: Delete also certificates
+            helm.on_delete(target)?;
+            delete_custom_certificates(self, target, &event_details)
         })
     }
 }
@@ -2,6 +2,7 @@ use crate::cloud_provider::kubernetes::Kubernetes;
 use crate::cloud_provider::DeploymentTarget;
 use crate::cmd;
 use crate::cmd::kubectl::kubectl_exec_delete_secret;
+use crate::deployment_action::deployment_plan::DeploymentPlan;
 use crate::deployment_action::DeploymentAction;
 use crate::errors::EngineError;
 use crate::events::EventDetails;
@@ -106,6 +107,14 @@ impl DeploymentAction for TerraformDeployment {
         }
     }
 
+    fn on_create_plan(&self, _target: &DeploymentTarget) -> Result<DeploymentPlan, EngineError> {
+        self.prepare_terraform_files()?;
+        match cmd::terraform::terraform_init_validate_plan(&self.destination_folder.to_string_lossy()) {
+            Ok(output) => Ok(DeploymentPlan::from_terraform_plan(output)),
+            Err(err) => Err(EngineError::new_terraform_error(self.event_details.clone(), err)),
+        }
+    }
+
     fn on_pause(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
         Ok(())
     }
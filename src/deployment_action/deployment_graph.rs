@@ -0,0 +1,492 @@
+use crate::cloud_provider::environment::Environment;
+use crate::metrics::ExecutionTimings;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::panic;
+use std::panic::AssertUnwindSafe;
+use std::sync::mpsc;
+use std::thread;
+use uuid::Uuid;
+
+/// Dependencies between the services of an environment.
+/// A service is only started once all the services it depends on are done,
+/// services without dependencies between them are executed concurrently.
+pub struct DeploymentGraph {
+    // services in insertion order, used to keep scheduling deterministic
+    services: Vec<Uuid>,
+    // service long id -> services it depends on
+    dependencies: HashMap<Uuid, HashSet<Uuid>>,
+}
+
+impl DeploymentGraph {
+    pub fn new() -> Self {
+        DeploymentGraph {
+            services: vec![],
+            dependencies: HashMap::new(),
+        }
+    }
+
+    /// Build the graph used to deploy an environment.
+    /// Databases are deployed first, then routers once the services they route to are done,
+    /// and applications, containers and jobs once their declared dependencies are done.
+    pub fn from_environment(environment: &Environment) -> Self {
+        let mut graph = DeploymentGraph::new();
+
+        let databases = environment
+            .databases
+            .iter()
+            .map(|database| *database.long_id())
+            .collect::<Vec<_>>();
+        for database in &databases {
+            graph.add_service(*database, vec![]);
+        }
+        for container in &environment.containers {
+            graph.add_service(*container.long_id(), container.depends_on().to_vec());
+        }
+        for application in &environment.applications {
+            graph.add_service(*application.long_id(), application.depends_on().to_vec());
+        }
+        for job in &environment.jobs {
+            graph.add_service(*job.long_id(), job.depends_on().to_vec());
+        }
+        for router in &environment.routers {
+            graph.add_service(*router.long_id(), router.routed_services());
+        }
+
+        graph.with_first_wave(&databases)
+    }
+
+    /// Build the graph used to pause or delete an environment.
+    /// Services are stopped before the ones they depend on, and databases only once all the other services are stopped.
+    pub fn teardown_from_environment(environment: &Environment) -> Self {
+        let databases = environment
+            .databases
+            .iter()
+            .map(|database| *database.long_id())
+            .collect::<Vec<_>>();
+
+        DeploymentGraph::from_environment(environment)
+            .reversed()
+            .with_final_wave(&databases)
+    }
+
+    pub fn add_service(&mut self, service_long_id: Uuid, depends_on: Vec<Uuid>) {
+        if !self.dependencies.contains_key(&service_long_id) {
+            self.services.push(service_long_id);
+        }
+
+        self.dependencies
+            .entry(service_long_id)
+            .or_default()
+            .extend(depends_on.into_iter().filter(|id| id != &service_long_id));
+    }
+
+    /// Graph with all dependencies inverted, used to pause/delete dependents before their dependencies.
+    pub fn reversed(&self) -> Self {
+        let mut graph = DeploymentGraph::new();
+        for service in self.services.iter().rev() {
+            graph.add_service(*service, vec![]);
+        }
+
+        for (service, dependencies) in &self.dependencies {
+            for dependency in dependencies.iter().filter(|id| self.dependencies.contains_key(id)) {
+                graph.add_service(*dependency, vec![*service]);
+            }
+        }
+
+        graph
+    }
+
+    /// Make all the other services of the graph wait for the given services
+    fn with_first_wave(mut self, first_services: &[Uuid]) -> Self {
+        let others = self
+            .services
+            .iter()
+            .filter(|service| !first_services.contains(service))
+            .copied()
+            .collect::<Vec<_>>();
+        let first_services = first_services
+            .iter()
+            .filter(|id| self.dependencies.contains_key(id))
+            .copied()
+            .collect::<Vec<_>>();
+        for service in others {
+            self.add_service(service, first_services.clone());
+        }
+
+        self
+    }
+
+    /// Make the given services wait for all the other services of the graph
+    fn with_final_wave(mut self, final_services: &[Uuid]) -> Self {
+        let others = self
+            .services
+            .iter()
+            .filter(|service| !final_services.contains(service))
+            .copied()
+            .collect::<Vec<_>>();
+        let final_services = final_services
+            .iter()
+            .filter(|id| self.dependencies.contains_key(id))
+            .copied()
+            .collect::<Vec<_>>();
+        for service in final_services {
+            self.add_service(service, others.clone());
+        }
+
+        self
+    }
+
+    // dependencies on services outside of the graph are considered as already satisfied
+    fn known_dependencies(&self, service_long_id: &Uuid) -> impl Iterator<Item = &Uuid> + '_ {
+        self.dependencies
+            .get(service_long_id)
+            .into_iter()
+            .flatten()
+            .filter(move |id| self.dependencies.contains_key(id))
+    }
+
+    /// Returns the services being part of a dependency cycle, if any.
+    pub fn find_cycle(&self) -> Option<Vec<Uuid>> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum State {
+            InProgress,
+            Done,
+        }
+
+        fn visit(
+            graph: &DeploymentGraph,
+            service: Uuid,
+            states: &mut HashMap<Uuid, State>,
+            path: &mut Vec<Uuid>,
+        ) -> Option<Vec<Uuid>> {
+            match states.get(&service) {
+                Some(State::Done) => return None,
+                Some(State::InProgress) => {
+                    let cycle_start = path.iter().position(|id| id == &service).unwrap_or(0);
+                    let mut cycle = path[cycle_start..].to_vec();
+                    cycle.push(service);
+                    return Some(cycle);
+                }
+                None => {}
+            }
+
+            states.insert(service, State::InProgress);
+            path.push(service);
+            let mut dependencies = graph.known_dependencies(&service).copied().collect::<Vec<_>>();
+            dependencies.sort();
+            for dependency in dependencies {
+                if let Some(cycle) = visit(graph, dependency, states, path) {
+                    return Some(cycle);
+                }
+            }
+            path.pop();
+            states.insert(service, State::Done);
+
+            None
+        }
+
+        let mut states = HashMap::with_capacity(self.services.len());
+        self.services
+            .iter()
+            .find_map(|service| visit(self, *service, &mut states, &mut vec![]))
+    }
+
+    /// Execute `action` on every service of the graph, with at most `max_parallel` services at the same time.
+    /// Once a service fails, no new service is started: we wait for the running ones and return the first error.
+    /// Every started service is added to `started_services`.
+    /// The graph must not contain any cycle (see `find_cycle`), services of a cycle are never started.
+    pub fn execute<E, F>(&self, max_parallel: usize, started_services: &mut HashSet<Uuid>, action: F) -> Result<(), E>
+    where
+        E: Send,
+        F: Fn(&Uuid) -> Result<(), E> + Sync,
+    {
+        let max_parallel = max_parallel.max(1);
+        let mut remaining_dependencies: HashMap<Uuid, usize> = HashMap::with_capacity(self.services.len());
+        let mut dependents: HashMap<Uuid, Vec<Uuid>> = HashMap::with_capacity(self.services.len());
+        for service in &self.services {
+            let dependencies = self.known_dependencies(service).copied().collect::<Vec<_>>();
+            remaining_dependencies.insert(*service, dependencies.len());
+            for dependency in dependencies {
+                dependents.entry(dependency).or_default().push(*service);
+            }
+        }
+
+        let mut ready: VecDeque<Uuid> = self
+            .services
+            .iter()
+            .filter(|service| remaining_dependencies.get(service) == Some(&0))
+            .copied()
+            .collect();
+
+        let action = &action;
+        let (tx, rx) = mpsc::channel();
+        thread::scope(|th_scope| {
+            let mut running = 0;
+            let mut first_error: Option<E> = None;
+            let mut panic_payload = None;
+
+            loop {
+                while first_error.is_none() && panic_payload.is_none() && running < max_parallel {
+                    let service = match ready.pop_front() {
+                        Some(service) => service,
+                        None => break,
+                    };
+
+                    started_services.insert(service);
+                    running += 1;
+                    let tx = tx.clone();
+                    // Propagate the current span and execution timings into the thread
+                    let current_span = tracing::Span::current();
+                    let current_timings = ExecutionTimings::current();
+                    th_scope.spawn(move || {
+                        let _span = current_span.enter();
+                        let _timings = current_timings.as_ref().map(|timings| timings.enter());
+                        let result = panic::catch_unwind(AssertUnwindSafe(|| action(&service)));
+                        let _ = tx.send((service, result));
+                    });
+                }
+
+                if running == 0 {
+                    break;
+                }
+
+                let (service, result) = rx
+                    .recv()
+                    .expect("deployment thread has terminated without sending its result");
+                running -= 1;
+
+                match result {
+                    Ok(Ok(())) => {
+                        for dependent in dependents.get(&service).into_iter().flatten() {
+                            if let Some(count) = remaining_dependencies.get_mut(dependent) {
+                                *count -= 1;
+                                if *count == 0 {
+                                    ready.push_back(*dependent);
+                                }
+                            }
+                        }
+                    }
+                    Ok(Err(err)) => {
+                        if first_error.is_none() {
+                            first_error = Some(err);
+                        }
+                    }
+                    Err(payload) => {
+                        if panic_payload.is_none() {
+                            panic_payload = Some(payload);
+                        }
+                    }
+                }
+            }
+
+            if let Some(payload) = panic_payload {
+                panic::resume_unwind(payload);
+            }
+
+            match first_error {
+                Some(err) => Err(err),
+                None => Ok(()),
+            }
+        })
+    }
+}
+
+impl Default for DeploymentGraph {
+    fn default() -> Self {
+        DeploymentGraph::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    #[test]
+    fn test_execute_respects_dependencies() {
+        // setup:
+        let (db, app, container, router) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let mut graph = DeploymentGraph::new();
+        graph.add_service(router, vec![app]);
+        graph.add_service(app, vec![db, container]);
+        graph.add_service(container, vec![]);
+        graph.add_service(db, vec![Uuid::new_v4()]); // unknown dependency is ignored
+        let done = Mutex::new(vec![]);
+        let mut started = HashSet::new();
+
+        // execute:
+        let result: Result<(), ()> = graph.execute(4, &mut started, |service| {
+            done.lock().unwrap().push(*service);
+            Ok(())
+        });
+
+        // verify:
+        assert!(result.is_ok());
+        assert_eq!(4, started.len());
+        let done = done.into_inner().unwrap();
+        let position = |id: &Uuid| done.iter().position(|d| d == id).unwrap();
+        assert!(position(&db) < position(&app));
+        assert!(position(&container) < position(&app));
+        assert!(position(&app) < position(&router));
+    }
+
+    #[test]
+    fn test_execute_runs_independent_services_in_parallel() {
+        // setup:
+        let mut graph = DeploymentGraph::new();
+        for _ in 0..6 {
+            graph.add_service(Uuid::new_v4(), vec![]);
+        }
+        let running = AtomicUsize::new(0);
+        let max_running = AtomicUsize::new(0);
+
+        // execute:
+        let result: Result<(), ()> = graph.execute(3, &mut HashSet::new(), |_| {
+            let current = running.fetch_add(1, Ordering::SeqCst) + 1;
+            max_running.fetch_max(current, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(50));
+            running.fetch_sub(1, Ordering::SeqCst);
+            Ok(())
+        });
+
+        // verify: the exact parallelism depends on threads scheduling
+        assert!(result.is_ok());
+        let max_running = max_running.load(Ordering::SeqCst);
+        assert!(max_running <= 3);
+        assert!(max_running > 1);
+    }
+
+    #[test]
+    fn test_execute_stops_on_first_error() {
+        // setup:
+        let (db, app) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut graph = DeploymentGraph::new();
+        graph.add_service(db, vec![]);
+        graph.add_service(app, vec![db]);
+        let mut started = HashSet::new();
+
+        // execute:
+        let result = graph.execute(2, &mut started, |service| {
+            if service == &db {
+                return Err("db failed");
+            }
+            Ok(())
+        });
+
+        // verify:
+        assert_eq!(Err("db failed"), result);
+        assert!(started.contains(&db));
+        assert!(!started.contains(&app));
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        // setup:
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let mut graph = DeploymentGraph::new();
+        graph.add_service(a, vec![b, a]); // self dependency is ignored
+        graph.add_service(b, vec![c]);
+        graph.add_service(c, vec![]);
+
+        // execute & verify:
+        assert_eq!(None, graph.find_cycle());
+
+        graph.add_service(c, vec![a]);
+        let cycle = graph.find_cycle().expect("cycle should be detected");
+        assert_eq!(cycle.first(), cycle.last());
+        assert_eq!(4, cycle.len());
+    }
+
+    #[test]
+    fn test_reversed() {
+        // setup:
+        let (db, app) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut graph = DeploymentGraph::new();
+        graph.add_service(db, vec![]);
+        graph.add_service(app, vec![db]);
+        let done = Mutex::new(vec![]);
+
+        // execute:
+        let result: Result<(), ()> = graph.reversed().execute(4, &mut HashSet::new(), |service| {
+            done.lock().unwrap().push(*service);
+            Ok(())
+        });
+
+        // verify:
+        assert!(result.is_ok());
+        assert_eq!(vec![app, db], done.into_inner().unwrap());
+    }
+
+    #[test]
+    fn test_with_final_wave() {
+        // setup:
+        let (db, other_db, app, container, router) =
+            (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let mut graph = DeploymentGraph::new();
+        graph.add_service(db, vec![]);
+        graph.add_service(other_db, vec![]);
+        graph.add_service(container, vec![]);
+        graph.add_service(app, vec![db]);
+        graph.add_service(router, vec![app]);
+        let done = Mutex::new(vec![]);
+
+        // execute:
+        let result: Result<(), ()> =
+            graph
+                .reversed()
+                .with_final_wave(&[db, other_db])
+                .execute(4, &mut HashSet::new(), |service| {
+                    done.lock().unwrap().push(*service);
+                    Ok(())
+                });
+
+        // verify:
+        assert!(result.is_ok());
+        let done = done.into_inner().unwrap();
+        assert_eq!(5, done.len());
+        let position = |id: &Uuid| done.iter().position(|d| d == id).unwrap();
+        assert!(position(&router) < position(&app));
+        for database in [db, other_db] {
+            for service in [app, container, router] {
+                assert!(position(&service) < position(&database));
+            }
+        }
+    }
+
+    #[test]
+    fn test_with_first_wave() {
+        // setup:
+        let (db, other_db, app, container, router) =
+            (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let mut graph = DeploymentGraph::new();
+        graph.add_service(db, vec![]);
+        graph.add_service(other_db, vec![]);
+        graph.add_service(container, vec![]);
+        graph.add_service(app, vec![]);
+        graph.add_service(router, vec![app]);
+        let done = Mutex::new(vec![]);
+
+        // execute:
+        let result: Result<(), ()> =
+            graph
+                .with_first_wave(&[db, other_db])
+                .execute(4, &mut HashSet::new(), |service| {
+                    done.lock().unwrap().push(*service);
+                    Ok(())
+                });
+
+        // verify:
+        assert!(result.is_ok());
+        let done = done.into_inner().unwrap();
+        assert_eq!(5, done.len());
+        let position = |id: &Uuid| done.iter().position(|d| d == id).unwrap();
+        assert!(position(&app) < position(&router));
+        for database in [db, other_db] {
+            for service in [app, container, router] {
+                assert!(position(&database) < position(&service));
+            }
+        }
+    }
+}
@@ -0,0 +1,306 @@
+use crate::cloud_provider::helm::ChartInfo;
+use crate::cloud_provider::service::{Action, Service};
+use crate::cloud_provider::DeploymentTarget;
+use crate::cmd::helm::HelmError;
+use crate::deployment_action::DeploymentAction;
+use crate::errors::EngineError;
+use crate::events::{EnvironmentStep, Stage};
+use regex::Regex;
+use serde::Serialize;
+use uuid::Uuid;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PlannedChange {
+    Create,
+    Update,
+    Delete,
+}
+
+/// Kubernetes object (from Helm) or cloud provider resource (from Terraform) which would be changed
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct PlannedResourceChange {
+    pub kind: String,
+    pub name: String,
+    pub change: PlannedChange,
+}
+
+/// What a deployment action would do, computed without changing anything
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeploymentPlan {
+    /// None if nothing would change
+    pub change: Option<PlannedChange>,
+    pub resources: Vec<PlannedResourceChange>,
+    /// raw output of helm diff / terraform plan
+    pub details: Vec<String>,
+}
+
+impl DeploymentPlan {
+    pub fn new_change(change: PlannedChange, details: Vec<String>) -> DeploymentPlan {
+        DeploymentPlan {
+            change: Some(change),
+            resources: vec![],
+            details,
+        }
+    }
+
+    /// Parse the output of `helm diff upgrade --install --no-color`
+    pub fn from_helm_diff(release_exists: bool, diff_output: Vec<String>) -> DeploymentPlan {
+        let re = Regex::new(
+            r"^(?P<namespace>\S+), (?P<name>\S+), (?P<kind>\S+) \([^)]*\) has (?P<change>been added|changed|been removed):$",
+        )
+        .expect("invalid helm diff regex");
+
+        let resources: Vec<PlannedResourceChange> = diff_output
+            .iter()
+            .filter_map(|line| re.captures(line.trim()))
+            .map(|caps| PlannedResourceChange {
+                kind: caps["kind"].to_string(),
+                name: caps["name"].to_string(),
+                change: match &caps["change"] {
+                    "been added" => PlannedChange::Create,
+                    "been removed" => PlannedChange::Delete,
+                    _ => PlannedChange::Update,
+                },
+            })
+            .collect();
+
+        let change = match (release_exists, resources.is_empty()) {
+            (false, _) => Some(PlannedChange::Create),
+            (true, true) => None,
+            (true, false) => Some(PlannedChange::Update),
+        };
+
+        DeploymentPlan {
+            change,
+            resources,
+            details: diff_output,
+        }
+    }
+
+    /// Parse the output of `terraform plan -no-color`
+    pub fn from_terraform_plan(plan_output: Vec<String>) -> DeploymentPlan {
+        let re = Regex::new(
+            r"^# (?P<address>\S+) (?P<change>will be created|will be updated in-place|will be destroyed|must be replaced)",
+        )
+        .expect("invalid terraform plan regex");
+
+        let resources: Vec<PlannedResourceChange> = plan_output
+            .iter()
+            .filter_map(|line| re.captures(line.trim()))
+            .map(|caps| {
+                // address is `[module.<name>.]<resource type>.<resource name>`
+                let mut address = caps["address"].rsplitn(3, '.');
+                let name = address.next().unwrap_or_default().to_string();
+                let kind = address.next().unwrap_or_default().to_string();
+                PlannedResourceChange {
+                    kind,
+                    name,
+                    change: match &caps["change"] {
+                        "will be created" => PlannedChange::Create,
+                        "will be destroyed" => PlannedChange::Delete,
+                        _ => PlannedChange::Update,
+                    },
+                }
+            })
+            .collect();
+
+        let change = if resources.is_empty() {
+            None
+        } else if resources.iter().all(|r| r.change == PlannedChange::Create) {
+            Some(PlannedChange::Create)
+        } else if resources.iter().all(|r| r.change == PlannedChange::Delete) {
+            Some(PlannedChange::Delete)
+        } else {
+            Some(PlannedChange::Update)
+        };
+
+        DeploymentPlan {
+            change,
+            resources,
+            details: plan_output,
+        }
+    }
+
+    /// Combine the plans of the different steps of a same deployment
+    pub fn merge(mut self, other: DeploymentPlan) -> DeploymentPlan {
+        self.change = match (self.change, other.change) {
+            (None, change) | (change, None) => change,
+            (Some(lhs), Some(rhs)) if lhs == rhs => Some(lhs),
+            _ => Some(PlannedChange::Update),
+        };
+        self.resources.extend(other.resources);
+        self.details.extend(other.details);
+        self
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ServicePlan {
+    pub long_id: Uuid,
+    pub name: String,
+    pub service_type: String,
+    #[serde(flatten)]
+    pub plan: DeploymentPlan,
+}
+
+/// Report of what an environment deployment would create, update or delete
+#[derive(Serialize, Debug, Clone)]
+pub struct EnvironmentPlan {
+    pub long_id: Uuid,
+    pub namespace: String,
+    pub services: Vec<ServicePlan>,
+}
+
+impl EnvironmentPlan {
+    pub fn has_changes(&self) -> bool {
+        self.services.iter().any(|service| service.plan.change.is_some())
+    }
+}
+
+/// Plan `action` for the service. `helm_release_name` is used to know if there is something to delete.
+pub fn plan_service_action<S: Service + DeploymentAction + ?Sized>(
+    service: &S,
+    helm_release_name: Option<String>,
+    target: &DeploymentTarget,
+    action: Action,
+) -> Result<ServicePlan, EngineError> {
+    let plan = match action {
+        Action::Create => service.on_create_plan(target)?,
+        Action::Pause => {
+            DeploymentPlan::new_change(PlannedChange::Update, vec![format!("{} would be paused", service.name())])
+        }
+        Action::Delete => match helm_release_name {
+            // managed services are not deployed by helm, we can't know if they still exist
+            None => DeploymentPlan::new_change(PlannedChange::Delete, vec![]),
+            Some(release_name) => {
+                let chart = ChartInfo::new_from_release_name(&release_name, target.environment.namespace());
+                match target.helm.check_release_exist(&chart, &[]) {
+                    Ok(_) => DeploymentPlan::new_change(
+                        PlannedChange::Delete,
+                        vec![format!("release {} would be uninstalled", release_name)],
+                    ),
+                    Err(HelmError::ReleaseDoesNotExist(_)) => DeploymentPlan::default(),
+                    Err(err) => {
+                        return Err(EngineError::new_helm_error(
+                            service.get_event_details(Stage::Environment(EnvironmentStep::Delete)),
+                            err,
+                        ))
+                    }
+                }
+            }
+        },
+        Action::Nothing => DeploymentPlan::default(),
+    };
+
+    Ok(ServicePlan {
+        long_id: *service.long_id(),
+        name: service.name().to_string(),
+        service_type: service.service_type().name(),
+        plan,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(output: &str) -> Vec<String> {
+        output.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_from_helm_diff() {
+        // setup:
+        let output = lines(
+            r#"my-env, app-z85ba6759, Deployment (apps) has changed:
+  # Source: q-application/templates/deployment.yaml
+-         image: "nginx:1.22"
++         image: "nginx:1.23"
+my-env, app-z85ba6759-hpa, HorizontalPodAutoscaler (autoscaling) has been added:
+my-env, app-z85ba6759-pdb, PodDisruptionBudget (policy) has been removed:"#,
+        );
+
+        // execute:
+        let plan = DeploymentPlan::from_helm_diff(true, output.clone());
+        let empty_plan = DeploymentPlan::from_helm_diff(true, vec![]);
+        let install_plan = DeploymentPlan::from_helm_diff(false, vec![]);
+
+        // verify:
+        assert_eq!(Some(PlannedChange::Update), plan.change);
+        assert_eq!(
+            vec![
+                PlannedResourceChange {
+                    kind: "Deployment".to_string(),
+                    name: "app-z85ba6759".to_string(),
+                    change: PlannedChange::Update,
+                },
+                PlannedResourceChange {
+                    kind: "HorizontalPodAutoscaler".to_string(),
+                    name: "app-z85ba6759-hpa".to_string(),
+                    change: PlannedChange::Create,
+                },
+                PlannedResourceChange {
+                    kind: "PodDisruptionBudget".to_string(),
+                    name: "app-z85ba6759-pdb".to_string(),
+                    change: PlannedChange::Delete,
+                },
+            ],
+            plan.resources
+        );
+        assert_eq!(output, plan.details);
+        assert_eq!(None, empty_plan.change);
+        assert_eq!(Some(PlannedChange::Create), install_plan.change);
+    }
+
+    #[test]
+    fn test_from_terraform_plan() {
+        // setup:
+        let output = lines(
+            r#"Terraform will perform the following actions:
+
+  # aws_db_instance.postgresql_instance will be updated in-place
+  ~ resource "aws_db_instance" "postgresql_instance" {
+  # aws_db_parameter_group.postgresql_parameter_group must be replaced
+  # module.vpc.aws_security_group.sg will be created
+
+Plan: 1 to add, 1 to change, 1 to destroy."#,
+        );
+
+        // execute:
+        let plan = DeploymentPlan::from_terraform_plan(output);
+        let create_plan = DeploymentPlan::from_terraform_plan(lines("  # aws_db_instance.db will be created"));
+
+        // verify:
+        assert_eq!(Some(PlannedChange::Update), plan.change);
+        assert_eq!(
+            vec![
+                ("aws_db_instance", "postgresql_instance", PlannedChange::Update),
+                ("aws_db_parameter_group", "postgresql_parameter_group", PlannedChange::Update),
+                ("aws_security_group", "sg", PlannedChange::Create),
+            ],
+            plan.resources
+                .iter()
+                .map(|r| (r.kind.as_str(), r.name.as_str(), r.change))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(Some(PlannedChange::Create), create_plan.change);
+    }
+
+    #[test]
+    fn test_merge() {
+        // setup:
+        let create = DeploymentPlan::new_change(PlannedChange::Create, vec!["a".to_string()]);
+        let delete = DeploymentPlan::new_change(PlannedChange::Delete, vec!["b".to_string()]);
+
+        // execute & verify:
+        assert_eq!(
+            Some(PlannedChange::Create),
+            create.clone().merge(DeploymentPlan::default()).change
+        );
+        assert_eq!(Some(PlannedChange::Create), create.clone().merge(create.clone()).change);
+        let merged = create.merge(delete);
+        assert_eq!(Some(PlannedChange::Update), merged.change);
+        assert_eq!(vec!["a".to_string(), "b".to_string()], merged.details);
+    }
+}
@@ -0,0 +1,420 @@
+use crate::cloud_provider::helm::ChartInfo;
+use crate::cloud_provider::service::Service;
+use crate::cloud_provider::DeploymentTarget;
+use crate::cmd::helm::{Helm, HelmError};
+use crate::errors::{CommandError, EngineError};
+use crate::events::{EngineEvent, EnvironmentStep, EventDetails, EventMessage, Stage};
+use crate::logger::Logger;
+use crate::runtime::block_on;
+use k8s_openapi::api::apps::v1::{Deployment, StatefulSet};
+use k8s_openapi::api::autoscaling::v1::{Scale, ScaleSpec};
+use kube::api::{ListParams, Patch, PatchParams};
+use kube::Api;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// Helm revision of a service release, recorded before the deployment touches it.
+#[derive(Clone, Debug)]
+pub struct HelmReleaseRevision {
+    pub service_long_id: Uuid,
+    pub service_name: String,
+    pub release_name: String,
+    pub namespace: String,
+    /// None if the release didn't exist yet
+    pub revision: Option<u64>,
+    pub selector: Option<String>,
+    /// Replicas of the service workloads, only recorded before a pause as it doesn't change the revision
+    pub replicas: Vec<WorkloadReplicas>,
+    pub event_details: EventDetails,
+}
+
+/// Number of replicas of a deployment/statefulset of a service release
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WorkloadReplicas {
+    pub name: String,
+    pub is_statefulset: bool,
+    pub replicas: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RevisionRollback {
+    Nothing,
+    RollbackTo(u64),
+    // release has been created by the deployment, there is no previous revision to go back to
+    Uninstall,
+    // release has been uninstalled, helm doesn't keep its history
+    CannotRestore(u64),
+}
+
+fn revision_rollback(previous_revision: Option<u64>, current_revision: Option<u64>) -> RevisionRollback {
+    match (previous_revision, current_revision) {
+        (None, None) => RevisionRollback::Nothing,
+        (None, Some(_)) => RevisionRollback::Uninstall,
+        (Some(previous), Some(current)) if previous == current => RevisionRollback::Nothing,
+        (Some(previous), Some(_)) => RevisionRollback::RollbackTo(previous),
+        (Some(previous), None) => RevisionRollback::CannotRestore(previous),
+    }
+}
+
+/// Record the current Helm revision of every service release of the environment.
+/// Services whose revision cannot be retrieved are not recorded, and so will not be rolled back.
+pub fn record_helm_revisions(target: &DeploymentTarget) -> Vec<HelmReleaseRevision> {
+    let environment = target.environment;
+    let namespace = environment.namespace();
+    let logger = target.kubernetes.logger();
+
+    let mut releases: Vec<(&dyn Service, String)> = vec![];
+    for database in &environment.databases {
+        if let Some(release_name) = database.helm_release_name() {
+            releases.push((database.as_service(), release_name));
+        }
+    }
+    for container in &environment.containers {
+        releases.push((container.as_service(), container.helm_release_name()));
+    }
+    for application in &environment.applications {
+        releases.push((application.as_service(), application.helm_release_name()));
+    }
+    for job in &environment.jobs {
+        releases.push((job.as_service(), job.helm_release_name()));
+    }
+    for router in &environment.routers {
+        releases.push((router.as_service(), router.helm_release_name()));
+    }
+
+    let existing_releases: HashSet<String> = match target.helm.list_release(Some(namespace), &[]) {
+        Ok(charts) => charts.into_iter().map(|chart| chart.name).collect(),
+        Err(err) => {
+            let event_details = target
+                .kubernetes
+                .get_event_details(Stage::Environment(EnvironmentStep::Rollback));
+            logger.log(EngineEvent::Warning(
+                event_details,
+                EventMessage::new(
+                    format!(
+                        "Cannot list Helm releases of namespace `{}`, services will not be rolled back on failure.",
+                        namespace
+                    ),
+                    Some(err.to_string()),
+                ),
+            ));
+            return vec![];
+        }
+    };
+
+    let mut revisions = Vec::with_capacity(releases.len());
+    for (service, release_name) in releases {
+        let event_details = service.get_event_details(Stage::Environment(EnvironmentStep::Rollback));
+        let revision = if existing_releases.contains(&release_name) {
+            let chart = ChartInfo::new_from_release_name(&release_name, namespace);
+            match target.helm.check_release_exist(&chart, &[]) {
+                Ok(status) => Some(status.version),
+                Err(HelmError::ReleaseDoesNotExist(_)) => None,
+                Err(err) => {
+                    logger.log(EngineEvent::Warning(
+                        event_details,
+                        EventMessage::new(
+                            format!(
+                                "Cannot retrieve Helm revision of service `{}`, it will not be rolled back on failure.",
+                                service.name()
+                            ),
+                            Some(err.to_string()),
+                        ),
+                    ));
+                    continue;
+                }
+            }
+        } else {
+            None
+        };
+
+        revisions.push(HelmReleaseRevision {
+            service_long_id: *service.long_id(),
+            service_name: service.name().to_string(),
+            release_name,
+            namespace: namespace.to_string(),
+            revision,
+            selector: service.selector(),
+            replicas: vec![],
+            event_details,
+        });
+    }
+
+    revisions
+}
+
+// k8s defaults to 1 replica when it is not set
+fn workload_replicas(deployments: Vec<Deployment>, statefulsets: Vec<StatefulSet>) -> Vec<WorkloadReplicas> {
+    let deployments = deployments.into_iter().filter_map(|deployment| {
+        Some(WorkloadReplicas {
+            name: deployment.metadata.name?,
+            is_statefulset: false,
+            replicas: deployment.spec.and_then(|spec| spec.replicas).unwrap_or(1),
+        })
+    });
+    let statefulsets = statefulsets.into_iter().filter_map(|statefulset| {
+        Some(WorkloadReplicas {
+            name: statefulset.metadata.name?,
+            is_statefulset: true,
+            replicas: statefulset.spec.and_then(|spec| spec.replicas).unwrap_or(1),
+        })
+    });
+
+    deployments.chain(statefulsets).collect()
+}
+
+async fn list_workload_replicas(
+    kube: &kube::Client,
+    namespace: &str,
+    selector: &str,
+) -> Result<Vec<WorkloadReplicas>, kube::Error> {
+    let list_params = ListParams::default().labels(selector);
+    let deployments: Api<Deployment> = Api::namespaced(kube.clone(), namespace);
+    let statefulsets: Api<StatefulSet> = Api::namespaced(kube.clone(), namespace);
+
+    Ok(workload_replicas(
+        deployments.list(&list_params).await?.items,
+        statefulsets.list(&list_params).await?.items,
+    ))
+}
+
+async fn scale_workload(kube: &kube::Client, namespace: &str, workload: &WorkloadReplicas) -> Result<(), kube::Error> {
+    let patch = Patch::Merge(Scale {
+        metadata: Default::default(),
+        spec: Some(ScaleSpec {
+            replicas: Some(workload.replicas),
+        }),
+        status: None,
+    });
+
+    if workload.is_statefulset {
+        let statefulsets: Api<StatefulSet> = Api::namespaced(kube.clone(), namespace);
+        statefulsets
+            .patch_scale(&workload.name, &PatchParams::default(), &patch)
+            .await?;
+    } else {
+        let deployments: Api<Deployment> = Api::namespaced(kube.clone(), namespace);
+        deployments
+            .patch_scale(&workload.name, &PatchParams::default(), &patch)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Record the replicas of the workloads of every recorded service, as pausing scales them down without any new revision.
+/// Services whose replicas cannot be retrieved keep their paused replicas on rollback.
+pub fn record_workload_replicas(target: &DeploymentTarget, revisions: &mut [HelmReleaseRevision]) {
+    let logger = target.kubernetes.logger();
+    for revision in revisions.iter_mut() {
+        let selector = match &revision.selector {
+            Some(selector) => selector,
+            None => continue,
+        };
+
+        match block_on(list_workload_replicas(&target.kube, &revision.namespace, selector)) {
+            Ok(replicas) => revision.replicas = replicas,
+            Err(err) => logger.log(EngineEvent::Warning(
+                revision.event_details.clone(),
+                EventMessage::new(
+                    format!(
+                        "Cannot retrieve replicas of service `{}`, they will not be restored on failure.",
+                        revision.service_name
+                    ),
+                    Some(err.to_string()),
+                ),
+            )),
+        }
+    }
+}
+
+/// Scale back the workloads of a service release to their recorded replicas.
+fn restore_workload_replicas(kube: &kube::Client, revision: &HelmReleaseRevision, logger: &dyn Logger) {
+    for workload in &revision.replicas {
+        match block_on(scale_workload(kube, &revision.namespace, workload)) {
+            Ok(()) => logger.log(EngineEvent::Info(
+                revision.event_details.clone(),
+                EventMessage::new_from_safe(format!(
+                    "Workload `{}` of service `{}` has been scaled back to {} replicas.",
+                    workload.name, revision.service_name, workload.replicas
+                )),
+            )),
+            Err(err) => logger.log(EngineEvent::Error(
+                EngineError::new_k8s_scale_replicas(
+                    revision.event_details.clone(),
+                    workload.name.clone(),
+                    revision.namespace.clone(),
+                    workload.replicas.max(0) as u32,
+                    CommandError::new_from_safe_message(err.to_string()),
+                ),
+                Some(EventMessage::new_from_safe(format!(
+                    "Cannot restore the replicas of service `{}`.",
+                    revision.service_name
+                ))),
+            )),
+        }
+    }
+}
+
+/// Roll back every release to its recorded revision, if it has changed since.
+/// Releases created by the deployment are uninstalled, and workloads of paused releases are scaled back.
+/// Failures are logged and don't stop the rollback of the other services.
+pub fn rollback_helm_revisions(
+    helm: &Helm,
+    kube: &kube::Client,
+    revisions: &[HelmReleaseRevision],
+    logger: &dyn Logger,
+) {
+    for revision in revisions {
+        let chart = ChartInfo::new_from_release_name(&revision.release_name, &revision.namespace);
+        let current_revision = match helm.check_release_exist(&chart, &[]) {
+            Ok(status) => Some(status.version),
+            Err(HelmError::ReleaseDoesNotExist(_)) => None,
+            Err(err) => {
+                logger.log(EngineEvent::Error(
+                    EngineError::new_helm_error(revision.event_details.clone(), err),
+                    Some(EventMessage::new_from_safe(format!(
+                        "Cannot retrieve Helm revision of service `{}`, it cannot be rolled back.",
+                        revision.service_name
+                    ))),
+                ));
+                continue;
+            }
+        };
+
+        let rollback = revision_rollback(revision.revision, current_revision);
+        match rollback {
+            RevisionRollback::Nothing => {}
+            RevisionRollback::Uninstall => {
+                logger.log(EngineEvent::Info(
+                    revision.event_details.clone(),
+                    EventMessage::new_from_safe(format!(
+                        "Uninstalling service `{}`, it didn't exist before the deployment.",
+                        revision.service_name
+                    )),
+                ));
+
+                match helm.uninstall(&chart, &[]) {
+                    Ok(()) => logger.log(EngineEvent::Info(
+                        revision.event_details.clone(),
+                        EventMessage::new_from_safe(format!(
+                            "Service `{}` has been uninstalled.",
+                            revision.service_name
+                        )),
+                    )),
+                    Err(err) => logger.log(EngineEvent::Error(
+                        EngineError::new_helm_error(revision.event_details.clone(), err),
+                        Some(EventMessage::new_from_safe(format!(
+                            "Cannot uninstall service `{}`.",
+                            revision.service_name
+                        ))),
+                    )),
+                }
+            }
+            RevisionRollback::CannotRestore(previous) => {
+                logger.log(EngineEvent::Warning(
+                    revision.event_details.clone(),
+                    EventMessage::new_from_safe(format!(
+                        "Service `{}` has been uninstalled, it cannot be rolled back to its revision {}.",
+                        revision.service_name, previous
+                    )),
+                ));
+            }
+            RevisionRollback::RollbackTo(previous) => {
+                logger.log(EngineEvent::Info(
+                    revision.event_details.clone(),
+                    EventMessage::new_from_safe(format!(
+                        "Rolling back service `{}` to its revision {}.",
+                        revision.service_name, previous
+                    )),
+                ));
+
+                match helm.rollback_to_revision(&chart, previous, &[]) {
+                    Ok(()) => logger.log(EngineEvent::Info(
+                        revision.event_details.clone(),
+                        EventMessage::new_from_safe(format!(
+                            "Service `{}` has been rolled back to its revision {}.",
+                            revision.service_name, previous
+                        )),
+                    )),
+                    Err(err) => logger.log(EngineEvent::Error(
+                        EngineError::new_helm_error(revision.event_details.clone(), err),
+                        Some(EventMessage::new_from_safe(format!(
+                            "Cannot roll back service `{}` to its revision {}.",
+                            revision.service_name, previous
+                        ))),
+                    )),
+                }
+            }
+        }
+
+        // a paused release keeps its revision, its workloads have to be scaled back to their previous replicas
+        if matches!(rollback, RevisionRollback::Nothing | RevisionRollback::RollbackTo(_)) {
+            restore_workload_replicas(kube, revision, logger);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_revision_rollback() {
+        // setup:
+        let test_cases = vec![
+            (None, None, RevisionRollback::Nothing),
+            (None, Some(1), RevisionRollback::Uninstall),
+            (Some(3), Some(3), RevisionRollback::Nothing),
+            (Some(3), Some(4), RevisionRollback::RollbackTo(3)),
+            (Some(3), None, RevisionRollback::CannotRestore(3)),
+        ];
+
+        for (previous_revision, current_revision, expected) in test_cases {
+            // execute:
+            let result = revision_rollback(previous_revision, current_revision);
+
+            // verify:
+            assert_eq!(
+                expected, result,
+                "previous revision {:?}, current revision {:?}",
+                previous_revision, current_revision
+            );
+        }
+    }
+
+    #[test]
+    fn test_workload_replicas() {
+        // setup:
+        let mut deployment = Deployment::default();
+        deployment.metadata.name = Some("app-z1234".to_string());
+        deployment.spec = Some(k8s_openapi::api::apps::v1::DeploymentSpec {
+            replicas: Some(3),
+            ..Default::default()
+        });
+        let mut unnamed_deployment = Deployment::default();
+        unnamed_deployment.spec = deployment.spec.clone();
+        let mut statefulset = StatefulSet::default();
+        statefulset.metadata.name = Some("postgresql-z5678".to_string());
+
+        // execute:
+        let result = workload_replicas(vec![deployment, unnamed_deployment], vec![statefulset]);
+
+        // verify:
+        assert_eq!(
+            vec![
+                WorkloadReplicas {
+                    name: "app-z1234".to_string(),
+                    is_statefulset: false,
+                    replicas: 3,
+                },
+                WorkloadReplicas {
+                    name: "postgresql-z5678".to_string(),
+                    is_statefulset: true,
+                    replicas: 1,
+                },
+            ],
+            result
+        );
+    }
+}
@@ -1,25 +1,45 @@
 use crate::cloud_provider::service::Action;
 use crate::cloud_provider::DeploymentTarget;
+use crate::deployment_action::deployment_plan::DeploymentPlan;
 use crate::errors::EngineError;
 
+pub mod check_certificate;
 mod check_dns;
+mod check_hpa_metrics;
+mod clone_environment;
+mod custom_certificate;
+pub mod database_backup;
+mod database_endpoints;
 mod deploy_application;
 mod deploy_container;
 mod deploy_database;
 pub mod deploy_environment;
 mod deploy_helm;
+mod deploy_job;
 pub mod deploy_namespace;
 mod deploy_router;
 mod deploy_terraform;
+pub mod deployment_graph;
+pub mod deployment_plan;
+pub mod helm_revisions;
 mod pause_service;
+mod progressive_deployment;
+pub mod scale_to_zero;
+mod secret_environment_variables;
 #[cfg(test)]
 mod test_utils;
+mod upgrade_database;
+mod volume_snapshot;
 
 pub trait DeploymentAction {
     fn on_create(&self, target: &DeploymentTarget) -> Result<(), EngineError>;
     fn on_create_check(&self) -> Result<(), EngineError> {
         Ok(())
     }
+    // what on_create would change, without modifying anything on the cluster nor on the cloud provider
+    fn on_create_plan(&self, _target: &DeploymentTarget) -> Result<DeploymentPlan, EngineError> {
+        Ok(DeploymentPlan::default())
+    }
     fn on_pause(&self, target: &DeploymentTarget) -> Result<(), EngineError>;
     fn on_pause_check(&self) -> Result<(), EngineError> {
         Ok(())
@@ -31,7 +31,7 @@ fn has_statefulset_ready_replicas(nb_ready_replicas: usize) -> impl Condition<St
     }
 }
 
-async fn pause_service(
+pub(super) async fn pause_service(
     kube: &kube::Client,
     namespace: &str,
     selector: &str,
@@ -84,7 +84,7 @@ async fn pause_service(
     Ok(())
 }
 
-async fn unpause_service_if_needed(
+pub(super) async fn unpause_service_if_needed(
     kube: &kube::Client,
     namespace: &str,
     selector: &str,
@@ -0,0 +1,481 @@
+use crate::cloud_provider::helm::ChartInfo;
+use crate::cloud_provider::DeploymentTarget;
+use crate::cmd::helm::HelmError;
+use crate::deployment_action::deploy_helm::HelmDeployment;
+use crate::deployment_action::DeploymentAction;
+use crate::errors::{CommandError, EngineError};
+use crate::events::EventDetails;
+use crate::kubers_utils::kube_delete_all_from_selector;
+use crate::models::deployment_strategy::{DeploymentStrategy, PromotionGate};
+use crate::runtime::block_on;
+use k8s_openapi::api::core::v1::Pod;
+use k8s_openapi::api::networking::v1::{HTTPIngressPath, HTTPIngressRuleValue, Ingress, IngressRule, IngressSpec};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::api::{ListParams, Patch, PatchParams};
+use kube::Api;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+const PREVIEW_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+/// Label set on the canary ingresses, with the name of the preview service as value
+const CANARY_INGRESS_LABEL: &str = "qovery.com/canary-of";
+/// Requests with this header set to `always` are always sent to the preview, useful to test it before its promotion
+const PREVIEW_HEADER: &str = "x-qovery-preview";
+
+/// Deploy a new version of a stateless service according to its deployment strategy.
+/// For blue/green and canary deployments, the new version is first deployed as a preview next to the current one.
+/// The preview receives a part of the ingress traffic (none for blue/green) through nginx canary ingresses, and is
+/// promoted if it passes the promotion gate. Otherwise it is removed and the current version is left untouched.
+pub(super) struct ProgressiveDeployment {
+    pub strategy: DeploymentStrategy,
+    /// Deployment of the service itself
+    pub stable: HelmDeployment,
+    /// Deployment of the preview, its chart `k8s_selector` must select the preview pods
+    pub preview: HelmDeployment,
+    pub stable_kube_service_name: String,
+    pub preview_kube_service_name: String,
+    pub event_details: EventDetails,
+}
+
+impl ProgressiveDeployment {
+    pub fn on_create(&self, target: &DeploymentTarget, send_progress: &dyn Fn(String)) -> Result<(), EngineError> {
+        let gate = match self.strategy.promotion_gate() {
+            Some(gate) => gate,
+            None => return self.stable.on_create(target),
+        };
+
+        // On the first deployment there is no current version to protect
+        match target.helm.check_release_exist(&self.stable.helm_chart, &[]) {
+            Ok(_) => {}
+            Err(HelmError::ReleaseDoesNotExist(_)) => return self.stable.on_create(target),
+            Err(err) => return Err(EngineError::new_helm_error(self.event_details.clone(), err)),
+        }
+
+        let result = self.deploy_and_promote_preview(target, gate, send_progress);
+
+        // Once promoted the preview is no more needed, and if it has failed the current version keeps the traffic
+        send_progress("🧹 Removing the preview of the new version".to_string());
+        let cleanup = delete_preview(
+            target,
+            &self.preview.helm_chart,
+            &self.preview_kube_service_name,
+            &self.event_details,
+        );
+
+        result.and(cleanup)
+    }
+
+    fn deploy_and_promote_preview(
+        &self,
+        target: &DeploymentTarget,
+        gate: &PromotionGate,
+        send_progress: &dyn Fn(String),
+    ) -> Result<(), EngineError> {
+        // helm waits for the preview pods to be ready, it is the first part of the promotion gate
+        send_progress("🔭 Deploying a preview of the new version next to the current one".to_string());
+        self.preview.on_create(target)?;
+
+        let weight_percent = self.strategy.preview_traffic_weight_percent();
+        send_progress(format!(
+            "🔀 Sending {}% of the traffic to the preview, requests with header `{}: always` are always sent to it",
+            weight_percent, PREVIEW_HEADER
+        ));
+        self.set_preview_traffic_weight(target, weight_percent)?;
+
+        send_progress(format!(
+            "🔬 Watching the preview pods during {} seconds before promoting it",
+            gate.analysis_duration.as_secs()
+        ));
+        let preview_selector = self.preview.helm_chart.k8s_selector.clone().unwrap_or_default();
+        if let Err(reason) = block_on(watch_preview(
+            &target.kube,
+            target.environment.namespace(),
+            &preview_selector,
+            gate,
+        )) {
+            return Err(EngineError::new_k8s_promotion_gate_failed(
+                self.event_details.clone(),
+                self.stable_kube_service_name.clone(),
+                reason,
+            ));
+        }
+
+        // All the traffic goes to the preview while the service is updated, so users never see both versions
+        send_progress("🚦 Preview has passed the promotion gate, promoting it".to_string());
+        self.set_preview_traffic_weight(target, 100)?;
+        self.stable.on_create(target)
+    }
+
+    fn set_preview_traffic_weight(&self, target: &DeploymentTarget, weight_percent: u32) -> Result<(), EngineError> {
+        block_on(apply_canary_ingresses(
+            &target.kube,
+            target.environment.namespace(),
+            &self.stable_kube_service_name,
+            &self.preview_kube_service_name,
+            weight_percent,
+        ))
+        .map_err(|err| {
+            EngineError::new_k8s_cannot_apply_canary_ingress(
+                self.event_details.clone(),
+                self.preview_kube_service_name.clone(),
+                CommandError::new_from_safe_message(err.to_string()),
+            )
+        })
+    }
+}
+
+/// Remove the preview release and its canary ingresses, if any
+pub(super) fn delete_preview(
+    target: &DeploymentTarget,
+    preview_chart: &ChartInfo,
+    preview_kube_service_name: &str,
+    event_details: &EventDetails,
+) -> Result<(), EngineError> {
+    if let Err(err) = block_on(kube_delete_all_from_selector::<Ingress>(
+        &target.kube,
+        &format!("{}={}", CANARY_INGRESS_LABEL, preview_kube_service_name),
+        target.environment.namespace(),
+    )) {
+        return Err(EngineError::new_k8s_cannot_apply_canary_ingress(
+            event_details.clone(),
+            preview_kube_service_name.to_string(),
+            CommandError::new_from_safe_message(err.to_string()),
+        ));
+    }
+
+    HelmDeployment::new(
+        event_details.clone(),
+        tera::Context::default(),
+        PathBuf::from(&preview_chart.path),
+        None,
+        preview_chart.clone(),
+    )
+    .on_delete(target)
+}
+
+/// Create or update a canary ingress for every ingress routing to the stable service
+async fn apply_canary_ingresses(
+    kube: &kube::Client,
+    namespace: &str,
+    stable_kube_service_name: &str,
+    preview_kube_service_name: &str,
+    weight_percent: u32,
+) -> Result<(), kube::Error> {
+    let api: Api<Ingress> = Api::namespaced(kube.clone(), namespace);
+    let patch_params = PatchParams::apply("qovery-engine").force();
+
+    for ingress in api.list(&ListParams::default()).await? {
+        let is_canary = ingress
+            .metadata
+            .labels
+            .as_ref()
+            .map(|labels| labels.contains_key(CANARY_INGRESS_LABEL))
+            .unwrap_or(false);
+        if is_canary {
+            continue;
+        }
+
+        if let Some(canary) =
+            to_canary_ingress(&ingress, stable_kube_service_name, preview_kube_service_name, weight_percent)
+        {
+            let name = canary.metadata.name.clone().unwrap_or_default();
+            api.patch(&name, &patch_params, &Patch::Apply(&canary)).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Canary version of the ingress, with only the paths routed to the stable service, now routed to the preview.
+/// None if the ingress does not route anything to the stable service.
+fn to_canary_ingress(
+    ingress: &Ingress,
+    stable_kube_service_name: &str,
+    preview_kube_service_name: &str,
+    weight_percent: u32,
+) -> Option<Ingress> {
+    let spec = ingress.spec.as_ref()?;
+    let rules: Vec<IngressRule> = spec
+        .rules
+        .iter()
+        .flatten()
+        .filter_map(|rule| {
+            let paths: Vec<HTTPIngressPath> = rule
+                .http
+                .as_ref()?
+                .paths
+                .iter()
+                .filter(|path| {
+                    path.backend.service.as_ref().map(|service| service.name.as_str()) == Some(stable_kube_service_name)
+                })
+                .cloned()
+                .map(|mut path| {
+                    if let Some(service) = path.backend.service.as_mut() {
+                        service.name = preview_kube_service_name.to_string();
+                    }
+                    path
+                })
+                .collect();
+
+            if paths.is_empty() {
+                return None;
+            }
+
+            Some(IngressRule {
+                host: rule.host.clone(),
+                http: Some(HTTPIngressRuleValue { paths }),
+            })
+        })
+        .collect();
+
+    if rules.is_empty() {
+        return None;
+    }
+
+    let mut annotations = BTreeMap::new();
+    if let Some(ingress_class) = ingress
+        .metadata
+        .annotations
+        .as_ref()
+        .and_then(|annotations| annotations.get("kubernetes.io/ingress.class"))
+    {
+        annotations.insert("kubernetes.io/ingress.class".to_string(), ingress_class.clone());
+    }
+    annotations.insert("nginx.ingress.kubernetes.io/canary".to_string(), "true".to_string());
+    annotations.insert(
+        "nginx.ingress.kubernetes.io/canary-weight".to_string(),
+        weight_percent.to_string(),
+    );
+    annotations.insert(
+        "nginx.ingress.kubernetes.io/canary-by-header".to_string(),
+        PREVIEW_HEADER.to_string(),
+    );
+
+    Some(Ingress {
+        metadata: ObjectMeta {
+            name: Some(format!(
+                "{}-{}",
+                ingress.metadata.name.as_deref().unwrap_or_default(),
+                preview_kube_service_name
+            )),
+            namespace: ingress.metadata.namespace.clone(),
+            labels: Some(BTreeMap::from([(
+                CANARY_INGRESS_LABEL.to_string(),
+                preview_kube_service_name.to_string(),
+            )])),
+            annotations: Some(annotations),
+            ..Default::default()
+        },
+        spec: Some(IngressSpec {
+            ingress_class_name: spec.ingress_class_name.clone(),
+            rules: Some(rules),
+            ..Default::default()
+        }),
+        status: None,
+    })
+}
+
+/// Check the preview pods until the end of the analysis, returns why the preview has failed the gate if so
+async fn watch_preview(
+    kube: &kube::Client,
+    namespace: &str,
+    preview_selector: &str,
+    gate: &PromotionGate,
+) -> Result<(), String> {
+    let pods_api: Api<Pod> = Api::namespaced(kube.clone(), namespace);
+    let started = Instant::now();
+
+    loop {
+        let is_last_check = started.elapsed() >= gate.analysis_duration;
+        let pods = pods_api
+            .list(&ListParams::default().labels(preview_selector))
+            .await
+            .map_err(|err| format!("cannot list the preview pods: {}", err))?;
+
+        check_promotion_gate(&pods.items, gate, is_last_check)?;
+        if is_last_check {
+            return Ok(());
+        }
+
+        tokio::time::sleep(PREVIEW_CHECK_INTERVAL).await;
+    }
+}
+
+fn check_promotion_gate(pods: &[Pod], gate: &PromotionGate, is_last_check: bool) -> Result<(), String> {
+    if pods.is_empty() {
+        return Err("no pod of the preview is running".to_string());
+    }
+
+    let nb_pods_not_ready = pods.iter().filter(|pod| !is_pod_ready(pod)).count();
+    let nb_pods_in_error = pods
+        .iter()
+        .filter(|pod| !is_pod_ready(pod) || pod_restart_count(pod) > 0)
+        .count();
+    let error_rate_percent = (nb_pods_in_error * 100 / pods.len()) as u32;
+
+    if let Some(max_error_rate_percent) = gate.max_error_rate_percent {
+        if error_rate_percent > max_error_rate_percent {
+            return Err(format!(
+                "{}% of the preview pods are not ready or have restarted, only {}% is allowed",
+                error_rate_percent, max_error_rate_percent
+            ));
+        }
+    }
+
+    if is_last_check && nb_pods_not_ready > 0 {
+        return Err(format!(
+            "{} of the {} preview pods are not ready at the end of the analysis",
+            nb_pods_not_ready,
+            pods.len()
+        ));
+    }
+
+    Ok(())
+}
+
+fn is_pod_ready(pod: &Pod) -> bool {
+    pod.status
+        .as_ref()
+        .and_then(|status| status.conditions.as_ref())
+        .map(|conditions| {
+            conditions
+                .iter()
+                .any(|condition| condition.type_ == "Ready" && condition.status == "True")
+        })
+        .unwrap_or(false)
+}
+
+fn pod_restart_count(pod: &Pod) -> i32 {
+    pod.status
+        .as_ref()
+        .and_then(|status| status.container_statuses.as_ref())
+        .map(|statuses| statuses.iter().map(|status| status.restart_count).sum())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::api::core::v1::{ContainerStatus, PodCondition, PodStatus};
+    use k8s_openapi::api::networking::v1::{IngressBackend, IngressServiceBackend};
+
+    fn pod(is_ready: bool, restart_count: i32) -> Pod {
+        Pod {
+            status: Some(PodStatus {
+                conditions: Some(vec![PodCondition {
+                    type_: "Ready".to_string(),
+                    status: if is_ready { "True" } else { "False" }.to_string(),
+                    ..Default::default()
+                }]),
+                container_statuses: Some(vec![ContainerStatus {
+                    restart_count,
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn path(path: &str, service_name: &str) -> HTTPIngressPath {
+        HTTPIngressPath {
+            path: Some(path.to_string()),
+            path_type: Some("Prefix".to_string()),
+            backend: IngressBackend {
+                service: Some(IngressServiceBackend {
+                    name: service_name.to_string(),
+                    port: None,
+                }),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_check_promotion_gate() {
+        // setup:
+        let gate = PromotionGate {
+            analysis_duration: Duration::from_secs(60),
+            max_error_rate_percent: Some(30),
+        };
+        let readiness_only_gate = PromotionGate {
+            analysis_duration: Duration::from_secs(60),
+            max_error_rate_percent: None,
+        };
+        let healthy_pods = vec![pod(true, 0), pod(true, 0), pod(true, 0)];
+        let restarted_pods = vec![pod(true, 0), pod(true, 0), pod(true, 0), pod(true, 2)];
+        let failing_pods = vec![pod(true, 0), pod(false, 3)];
+
+        // execute & verify:
+        assert!(check_promotion_gate(&healthy_pods, &gate, true).is_ok());
+        assert!(check_promotion_gate(&restarted_pods, &gate, true).is_ok());
+        assert!(check_promotion_gate(&failing_pods, &gate, false).is_err());
+        assert!(check_promotion_gate(&failing_pods, &readiness_only_gate, false).is_ok());
+        assert!(check_promotion_gate(&failing_pods, &readiness_only_gate, true).is_err());
+        assert!(check_promotion_gate(&[], &readiness_only_gate, false).is_err());
+    }
+
+    #[test]
+    fn test_to_canary_ingress() {
+        // setup:
+        let ingress = Ingress {
+            metadata: ObjectMeta {
+                name: Some("router-z85ba6759".to_string()),
+                namespace: Some("my-env".to_string()),
+                annotations: Some(BTreeMap::from([(
+                    "kubernetes.io/ingress.class".to_string(),
+                    "nginx-qovery".to_string(),
+                )])),
+                ..Default::default()
+            },
+            spec: Some(IngressSpec {
+                rules: Some(vec![
+                    IngressRule {
+                        host: Some("app.example.com".to_string()),
+                        http: Some(HTTPIngressRuleValue {
+                            paths: vec![path("/", "app-z85ba6759"), path("/api", "container-z12345678")],
+                        }),
+                    },
+                    IngressRule {
+                        host: Some("other.example.com".to_string()),
+                        http: Some(HTTPIngressRuleValue {
+                            paths: vec![path("/", "container-z12345678")],
+                        }),
+                    },
+                ]),
+                ..Default::default()
+            }),
+            status: None,
+        };
+
+        // execute:
+        let canary = to_canary_ingress(&ingress, "app-z85ba6759", "app-z85ba6759-preview", 10);
+        let no_canary = to_canary_ingress(&ingress, "app-z11111111", "app-z11111111-preview", 10);
+
+        // verify:
+        assert!(no_canary.is_none());
+        let canary = canary.expect("ingress routes to the service");
+        assert_eq!(Some("router-z85ba6759-app-z85ba6759-preview".to_string()), canary.metadata.name);
+        let annotations = canary.metadata.annotations.unwrap_or_default();
+        assert_eq!(Some(&"true".to_string()), annotations.get("nginx.ingress.kubernetes.io/canary"));
+        assert_eq!(
+            Some(&"10".to_string()),
+            annotations.get("nginx.ingress.kubernetes.io/canary-weight")
+        );
+        assert_eq!(
+            Some(&"nginx-qovery".to_string()),
+            annotations.get("kubernetes.io/ingress.class")
+        );
+        let rules = canary.spec.and_then(|spec| spec.rules).unwrap_or_default();
+        assert_eq!(1, rules.len());
+        assert_eq!(Some("app.example.com".to_string()), rules[0].host);
+        let paths = &rules[0].http.as_ref().expect("http rule").paths;
+        assert_eq!(1, paths.len());
+        assert_eq!(Some("/".to_string()), paths[0].path);
+        assert_eq!(
+            "app-z85ba6759-preview",
+            paths[0].backend.service.as_ref().expect("service backend").name
+        );
+    }
+}
@@ -0,0 +1,530 @@
+//! Scale-to-zero of idle environments.
+//!
+//! The engine only flags the namespace of an environment with its idle timeout, and redirects its ingresses
+//! to the activator once their services have no endpoints anymore. Checking for idle environments and serving
+//! the activator is done by a long running process in the cluster, the `qovery-activator` binary deployed along
+//! the cluster agent, with [`scale_idle_environments_to_zero`] and [`serve_activator`].
+
+use crate::deployment_action::pause_service::{pause_service, unpause_service_if_needed};
+use crate::errors::CommandError;
+use chrono::{DateTime, Utc};
+use hyper::header::{CACHE_CONTROL, LOCATION, RETRY_AFTER};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, Server, StatusCode};
+use k8s_openapi::api::apps::v1::{Deployment, StatefulSet};
+use k8s_openapi::api::core::v1::{Namespace, Service, ServicePort, ServiceSpec};
+use k8s_openapi::api::networking::v1::Ingress;
+use kube::api::{DeleteParams, ListParams, ObjectMeta, Patch, PatchParams};
+use kube::runtime::wait::{await_condition, Condition};
+use kube::Api;
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+const IDLE_TIMEOUT_ANNOTATION: &str = "qovery.com/scale-to-zero-after-minutes";
+const LAST_ACTIVITY_ANNOTATION: &str = "qovery.com/last-activity-at";
+const SCALED_TO_ZERO_ANNOTATION: &str = "qovery.com/scaled-to-zero-at";
+
+/// Default backend of the ingresses of an idle environment, resolving to the activator served by the cluster agent
+pub const ACTIVATOR_SERVICE_NAME: &str = "qovery-activator";
+const ACTIVATOR_NAMESPACE: &str = "qovery";
+const ACTIVATOR_EXTERNAL_NAME: &str = "qovery-activator.qovery.svc.cluster.local";
+const ACTIVATOR_PORT: i32 = 80;
+
+// Only services reachable through a router are scaled, databases are left running
+const SCALABLE_SERVICES_SELECTORS: [&str; 2] = ["appId", "qovery.com/service-type=container"];
+
+// Once woken up, the ingress controller can still answer 503 until it sees the new endpoints
+const WAKE_UP_ENDPOINTS_DELAY: Duration = Duration::from_secs(2);
+// ingress annotation telling which backend receives the errors of the services
+const DEFAULT_BACKEND_ANNOTATION: &str = "nginx.ingress.kubernetes.io/default-backend";
+const SCALE_DOWN_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+struct Workload {
+    name: String,
+    selector: String,
+    is_statefulset: bool,
+}
+
+fn match_labels_selector(labels: &BTreeMap<String, String>) -> String {
+    labels
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn to_command_error(err: kube::Error) -> CommandError {
+    CommandError::new_from_safe_message(err.to_string())
+}
+
+async fn list_workloads(kube: &kube::Client, namespace: &str) -> Result<Vec<Workload>, kube::Error> {
+    let deployments: Api<Deployment> = Api::namespaced(kube.clone(), namespace);
+    let statefulsets: Api<StatefulSet> = Api::namespaced(kube.clone(), namespace);
+    let mut workloads = vec![];
+
+    for selector in SCALABLE_SERVICES_SELECTORS {
+        let list_params = ListParams::default().labels(selector);
+        for deployment in deployments.list(&list_params).await? {
+            let match_labels = deployment.spec.and_then(|spec| spec.selector.match_labels);
+            if let (Some(name), Some(match_labels)) = (deployment.metadata.name, match_labels) {
+                workloads.push(Workload {
+                    name,
+                    selector: match_labels_selector(&match_labels),
+                    is_statefulset: false,
+                });
+            }
+        }
+        for statefulset in statefulsets.list(&list_params).await? {
+            let match_labels = statefulset.spec.and_then(|spec| spec.selector.match_labels);
+            if let (Some(name), Some(match_labels)) = (statefulset.metadata.name, match_labels) {
+                workloads.push(Workload {
+                    name,
+                    selector: match_labels_selector(&match_labels),
+                    is_statefulset: true,
+                });
+            }
+        }
+    }
+
+    Ok(workloads)
+}
+
+fn is_deployment_available() -> impl Condition<Deployment> {
+    |deployment: Option<&Deployment>| {
+        deployment
+            .and_then(|d| d.status.as_ref())
+            .and_then(|status| status.ready_replicas)
+            .unwrap_or(0)
+            > 0
+    }
+}
+
+fn is_statefulset_available() -> impl Condition<StatefulSet> {
+    |statefulset: Option<&StatefulSet>| {
+        statefulset
+            .and_then(|s| s.status.as_ref())
+            .and_then(|status| status.ready_replicas)
+            .unwrap_or(0)
+            > 0
+    }
+}
+
+async fn annotate_namespace(
+    kube: &kube::Client,
+    namespace: &str,
+    annotations: serde_json::Value,
+) -> Result<(), kube::Error> {
+    let namespaces: Api<Namespace> = Api::all(kube.clone());
+    let patch = Patch::Merge(serde_json::json!({ "metadata": { "annotations": annotations } }));
+    namespaces.patch(namespace, &PatchParams::default(), &patch).await?;
+
+    Ok(())
+}
+
+/// Flag the namespace with the idle timeout of the environment, and make the activator resolvable from it.
+/// Without idle timeout, the namespace is never scaled to zero nor woken up.
+pub(super) async fn configure_scale_to_zero(
+    kube: &kube::Client,
+    namespace: &str,
+    idle_timeout: Option<Duration>,
+) -> Result<(), CommandError> {
+    let services: Api<Service> = Api::namespaced(kube.clone(), namespace);
+
+    let idle_timeout = match idle_timeout {
+        Some(idle_timeout) => idle_timeout,
+        None => {
+            annotate_namespace(
+                kube,
+                namespace,
+                serde_json::json!({
+                    IDLE_TIMEOUT_ANNOTATION: null,
+                    LAST_ACTIVITY_ANNOTATION: null,
+                    SCALED_TO_ZERO_ANNOTATION: null,
+                }),
+            )
+            .await
+            .map_err(to_command_error)?;
+            return match services.delete(ACTIVATOR_SERVICE_NAME, &DeleteParams::default()).await {
+                Err(kube::Error::Api(api_err)) if api_err.code != 404 => {
+                    Err(to_command_error(kube::Error::Api(api_err)))
+                }
+                _ => Ok(()),
+            };
+        }
+    };
+
+    // without activator, the errors of the services would be sent to a backend which does not exist
+    let activators: Api<Service> = Api::namespaced(kube.clone(), ACTIVATOR_NAMESPACE);
+    match activators.get(ACTIVATOR_SERVICE_NAME).await {
+        Ok(_) => {}
+        Err(kube::Error::Api(api_err)) if api_err.code == 404 => {
+            return Err(CommandError::new_from_safe_message(
+                "Scale to zero is not available on this cluster, its activator is only deployed when metrics history is enabled".to_string(),
+            ))
+        }
+        Err(err) => return Err(to_command_error(err)),
+    }
+
+    // ingresses can only use a default backend from their own namespace
+    let activator = Service {
+        metadata: ObjectMeta {
+            name: Some(ACTIVATOR_SERVICE_NAME.to_string()),
+            namespace: Some(namespace.to_string()),
+            ..Default::default()
+        },
+        spec: Some(ServiceSpec {
+            type_: Some("ExternalName".to_string()),
+            external_name: Some(ACTIVATOR_EXTERNAL_NAME.to_string()),
+            ports: Some(vec![ServicePort {
+                name: Some("http".to_string()),
+                port: ACTIVATOR_PORT,
+                ..Default::default()
+            }]),
+            ..Default::default()
+        }),
+        status: None,
+    };
+    services
+        .patch(
+            ACTIVATOR_SERVICE_NAME,
+            &PatchParams::apply("qovery-engine").force(),
+            &Patch::Apply(&activator),
+        )
+        .await
+        .map_err(to_command_error)?;
+
+    // a deployment is an activity, and it scales the services back up
+    annotate_namespace(
+        kube,
+        namespace,
+        serde_json::json!({
+            IDLE_TIMEOUT_ANNOTATION: (idle_timeout.as_secs() / 60).to_string(),
+            LAST_ACTIVITY_ANNOTATION: Utc::now().to_rfc3339(),
+            SCALED_TO_ZERO_ANNOTATION: null,
+        }),
+    )
+    .await
+    .map_err(to_command_error)
+}
+
+fn ingress_requests_query(namespace: &str, window: Duration) -> String {
+    format!(
+        "sum(increase(nginx_ingress_controller_requests{{exported_namespace=\"{}\"}}[{}m]))",
+        namespace,
+        window.as_secs() / 60
+    )
+}
+
+/// Sum of the samples of a Prometheus instant vector.
+/// An empty vector gives None: the series do not exist, i.e the ingress controller does not export its metrics
+fn prometheus_vector_sum(response: &serde_json::Value) -> Option<f64> {
+    let samples = response["data"]["result"].as_array()?;
+    if samples.is_empty() {
+        return None;
+    }
+
+    samples.iter().try_fold(0.0, |sum, sample| {
+        let value = sample["value"][1].as_str()?.parse::<f64>().ok()?;
+        Some(sum + value)
+    })
+}
+
+fn is_idle(idle_timeout: Duration, last_activity: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+    match last_activity {
+        Some(last_activity) => now
+            .signed_duration_since(last_activity)
+            .to_std()
+            .map(|inactivity| inactivity >= idle_timeout)
+            .unwrap_or(false),
+        // unknown activity, the ingress metrics alone decide
+        None => true,
+    }
+}
+
+fn annotation<'a>(namespace: &'a Namespace, key: &str) -> Option<&'a String> {
+    namespace.metadata.annotations.as_ref().and_then(|a| a.get(key))
+}
+
+fn last_activity(namespace: &Namespace) -> Option<DateTime<Utc>> {
+    annotation(namespace, LAST_ACTIVITY_ANNOTATION)
+        .and_then(|date| DateTime::parse_from_rfc3339(date).ok())
+        .map(|date| date.with_timezone(&Utc))
+}
+
+async fn count_ingress_requests(
+    http: &reqwest::Client,
+    prometheus_url: &str,
+    namespace: &str,
+    window: Duration,
+) -> Result<f64, CommandError> {
+    let response = http
+        .get(format!("{}/api/v1/query", prometheus_url))
+        .query(&[("query", ingress_requests_query(namespace, window))])
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|err| CommandError::new_from_safe_message(format!("Cannot query Prometheus: {}", err)))?
+        .json::<serde_json::Value>()
+        .await
+        .map_err(|err| CommandError::new_from_safe_message(format!("Invalid Prometheus response: {}", err)))?;
+
+    prometheus_vector_sum(&response).ok_or_else(|| {
+        CommandError::new_from_safe_message(format!(
+            "No ingress requests metrics for namespace {}, is the ingress controller exporting its metrics?",
+            namespace
+        ))
+    })
+}
+
+async fn scale_environment_to_zero(kube: &kube::Client, namespace: &str) -> Result<(), kube::Error> {
+    for workload in list_workloads(kube, namespace).await? {
+        pause_service(kube, namespace, &workload.selector, 0, workload.is_statefulset).await?;
+    }
+
+    annotate_namespace(
+        kube,
+        namespace,
+        serde_json::json!({ SCALED_TO_ZERO_ANNOTATION: Utc::now().to_rfc3339() }),
+    )
+    .await
+}
+
+/// Scale to zero the environments which did not receive any request through their ingresses during their idle timeout.
+/// Returns the namespaces which have been scaled to zero.
+pub async fn scale_idle_environments_to_zero(
+    kube: &kube::Client,
+    prometheus_url: &str,
+) -> Result<Vec<String>, CommandError> {
+    let namespaces: Api<Namespace> = Api::all(kube.clone());
+    let http = reqwest::Client::new();
+    let mut scaled_namespaces = vec![];
+
+    for namespace in namespaces
+        .list(&ListParams::default())
+        .await
+        .map_err(to_command_error)?
+    {
+        let idle_timeout = match annotation(&namespace, IDLE_TIMEOUT_ANNOTATION).and_then(|m| m.parse::<u64>().ok()) {
+            Some(minutes) if minutes > 0 => Duration::from_secs(minutes * 60),
+            _ => continue,
+        };
+        if annotation(&namespace, SCALED_TO_ZERO_ANNOTATION).is_some()
+            || !is_idle(idle_timeout, last_activity(&namespace), Utc::now())
+        {
+            continue;
+        }
+        let name = match &namespace.metadata.name {
+            Some(name) => name,
+            None => continue,
+        };
+
+        // without metrics, we cannot tell if the environment is idle
+        match count_ingress_requests(&http, prometheus_url, name, idle_timeout).await {
+            Ok(nb_requests) if nb_requests > 0.0 => continue,
+            Ok(_) => {}
+            Err(err) => {
+                error!("cannot check if environment {} is idle: {}", name, err.message_safe());
+                continue;
+            }
+        }
+
+        info!("scaling idle environment {} to zero", name);
+        match tokio::time::timeout(SCALE_DOWN_TIMEOUT, scale_environment_to_zero(kube, name)).await {
+            Ok(Ok(())) => scaled_namespaces.push(name.to_string()),
+            Ok(Err(err)) => error!("cannot scale environment {} to zero: {}", name, err),
+            Err(_) => error!("timeout while scaling environment {} to zero", name),
+        }
+    }
+
+    Ok(scaled_namespaces)
+}
+
+/// Scale back the services of an environment scaled to zero, and wait for them to be ready
+pub async fn wake_up_environment(kube: &kube::Client, namespace: &str) -> Result<(), kube::Error> {
+    let deployments: Api<Deployment> = Api::namespaced(kube.clone(), namespace);
+    let statefulsets: Api<StatefulSet> = Api::namespaced(kube.clone(), namespace);
+    let workloads = list_workloads(kube, namespace).await?;
+
+    for workload in &workloads {
+        unpause_service_if_needed(kube, namespace, &workload.selector, workload.is_statefulset).await?;
+    }
+    for workload in &workloads {
+        if workload.is_statefulset {
+            await_condition(statefulsets.clone(), &workload.name, is_statefulset_available())
+                .await
+                .map_err(|err| kube::Error::Service(Box::new(err)))?;
+        } else {
+            await_condition(deployments.clone(), &workload.name, is_deployment_available())
+                .await
+                .map_err(|err| kube::Error::Service(Box::new(err)))?;
+        }
+    }
+
+    annotate_namespace(
+        kube,
+        namespace,
+        serde_json::json!({
+            LAST_ACTIVITY_ANNOTATION: Utc::now().to_rfc3339(),
+            SCALED_TO_ZERO_ANNOTATION: null,
+        }),
+    )
+    .await
+}
+
+enum Activation {
+    /// the environment has been woken up, the request can be sent again to the service
+    Ready,
+    /// the service answered 503 by itself, or is not managed by the activator
+    Unavailable,
+}
+
+/// Whether the request has been forwarded by an ingress using the activator, so a caller cannot wake up
+/// any namespace by forging the headers of the ingress controller
+async fn is_activator_ingress(kube: &kube::Client, namespace: &str, ingress_name: &str) -> bool {
+    let ingresses: Api<Ingress> = Api::namespaced(kube.clone(), namespace);
+    match ingresses.get(ingress_name).await {
+        Ok(ingress) => {
+            ingress
+                .metadata
+                .annotations
+                .as_ref()
+                .and_then(|annotations| annotations.get(DEFAULT_BACKEND_ANNOTATION))
+                .map(|backend| backend.as_str())
+                == Some(ACTIVATOR_SERVICE_NAME)
+        }
+        Err(_) => false,
+    }
+}
+
+async fn activate(kube: &kube::Client, namespace: &str, ingress_name: &str, wake_up_timeout: Duration) -> Activation {
+    let namespaces: Api<Namespace> = Api::all(kube.clone());
+    let namespace = match namespaces.get(namespace).await {
+        Ok(namespace) => namespace,
+        Err(_) => return Activation::Unavailable,
+    };
+    let name = namespace.metadata.name.clone().unwrap_or_default();
+    // only environments scaled to zero are woken up: a 503 answered by a running service is sent back as is,
+    // redirecting it would loop between the service and the activator
+    if annotation(&namespace, IDLE_TIMEOUT_ANNOTATION).is_none()
+        || annotation(&namespace, SCALED_TO_ZERO_ANNOTATION).is_none()
+        || !is_activator_ingress(kube, &name, ingress_name).await
+    {
+        return Activation::Unavailable;
+    }
+
+    info!("waking up environment {}", name);
+    match tokio::time::timeout(wake_up_timeout, wake_up_environment(kube, &name)).await {
+        Ok(Ok(())) => {
+            // give some time to the ingress controller to route to the new endpoints
+            tokio::time::sleep(WAKE_UP_ENDPOINTS_DELAY).await;
+            Activation::Ready
+        }
+        Ok(Err(err)) => {
+            error!("cannot wake up environment {}: {}", name, err);
+            Activation::Unavailable
+        }
+        Err(_) => {
+            error!("timeout while waking up environment {}", name);
+            Activation::Unavailable
+        }
+    }
+}
+
+async fn handle_activator_request(
+    request: Request<Body>,
+    kube: kube::Client,
+    wake_up_timeout: Duration,
+) -> Result<Response<Body>, Infallible> {
+    // headers set by the ingress controller when forwarding an error to the default backend
+    // https://kubernetes.github.io/ingress-nginx/user-guide/custom-errors/
+    let header = |name: &str| request.headers().get(name).and_then(|value| value.to_str().ok());
+    let activation = match (header("X-Namespace"), header("X-Ingress-Name")) {
+        (Some(namespace), Some(ingress_name)) => activate(&kube, namespace, ingress_name, wake_up_timeout).await,
+        _ => Activation::Unavailable,
+    };
+
+    let response = match activation {
+        Activation::Ready => Response::builder()
+            .status(StatusCode::TEMPORARY_REDIRECT)
+            .header(LOCATION, header("X-Original-URI").unwrap_or("/"))
+            .header(CACHE_CONTROL, "no-store")
+            .body(Body::empty()),
+        Activation::Unavailable => Response::builder()
+            .status(StatusCode::SERVICE_UNAVAILABLE)
+            .header(RETRY_AFTER, "10")
+            .header(CACHE_CONTROL, "no-store")
+            .body(Body::from("Service Unavailable")),
+    };
+
+    Ok(response.unwrap_or_else(|_| Response::new(Body::empty())))
+}
+
+/// Serve the activator: requests to an environment scaled to zero are held until it is woken up,
+/// then redirected to the same location to reach the service
+pub async fn serve_activator(
+    addr: SocketAddr,
+    kube: kube::Client,
+    wake_up_timeout: Duration,
+) -> Result<(), hyper::Error> {
+    let make_service = make_service_fn(move |_| {
+        let kube = kube.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                handle_activator_request(request, kube.clone(), wake_up_timeout)
+            }))
+        }
+    });
+
+    Server::bind(&addr).serve(make_service).await
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::deployment_action::scale_to_zero::{ingress_requests_query, is_idle, prometheus_vector_sum};
+    use chrono::{Duration as ChronoDuration, Utc};
+    use std::time::Duration;
+
+    #[test]
+    fn test_ingress_requests_query() {
+        assert_eq!(
+            ingress_requests_query("zabc-zdef", Duration::from_secs(30 * 60)),
+            "sum(increase(nginx_ingress_controller_requests{exported_namespace=\"zabc-zdef\"}[30m]))"
+        );
+    }
+
+    #[test]
+    fn test_prometheus_vector_sum() {
+        // setup:
+        let with_samples = serde_json::json!({
+            "status": "success",
+            "data": { "resultType": "vector", "result": [
+                { "metric": {}, "value": [1666000000.0, "2.5"] },
+                { "metric": {}, "value": [1666000000.0, "1"] }
+            ]}
+        });
+        let without_samples = serde_json::json!({
+            "status": "success",
+            "data": { "resultType": "vector", "result": [] }
+        });
+
+        // execute & verify:
+        assert_eq!(prometheus_vector_sum(&with_samples), Some(3.5));
+        assert_eq!(prometheus_vector_sum(&without_samples), None);
+        assert_eq!(prometheus_vector_sum(&serde_json::json!({ "status": "error" })), None);
+    }
+
+    #[test]
+    fn test_is_idle() {
+        // setup:
+        let now = Utc::now();
+        let idle_timeout = Duration::from_secs(30 * 60);
+
+        // execute & verify:
+        assert!(is_idle(idle_timeout, None, now));
+        assert!(is_idle(idle_timeout, Some(now - ChronoDuration::minutes(45)), now));
+        assert!(!is_idle(idle_timeout, Some(now - ChronoDuration::minutes(10)), now));
+        assert!(!is_idle(idle_timeout, Some(now + ChronoDuration::minutes(5)), now));
+    }
+}
@@ -0,0 +1,161 @@
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::cloud_provider::models::{EnvironmentVariable, SecretEnvironmentVariable};
+use crate::cloud_provider::service::{Action, Service};
+use crate::cloud_provider::{DeploymentTarget, Kind as CPKind};
+use crate::constants::{AWS_ACCESS_KEY_ID, AWS_SECRET_ACCESS_KEY, SCALEWAY_SECRET_KEY};
+use crate::deployment_report::logger::get_loggers;
+use crate::errors::{CommandError, EngineError};
+use crate::events::EventDetails;
+use crate::io_models::application::SecretReference;
+use crate::secret_manager::aws_secrets_manager::AwsSecretsManager;
+use crate::secret_manager::scaleway_secret_manager::ScalewaySecretManager;
+use crate::secret_manager::vault::QVaultClient;
+
+/// Environment variables of a service with the values of its secret ones read from their secret manager.
+/// Secret values are base64 encoded, as the other values, and win over a plain variable with the same name.
+pub(super) fn with_secret_environment_variables<S: Service>(
+    service: &S,
+    target: &DeploymentTarget,
+    environment_variables: Vec<EnvironmentVariable>,
+    secret_environment_variables: &[SecretEnvironmentVariable],
+    event_details: &EventDetails,
+) -> Result<Vec<EnvironmentVariable>, EngineError> {
+    if secret_environment_variables.is_empty() {
+        return Ok(environment_variables);
+    }
+
+    let logger = get_loggers(service, Action::Create);
+    (logger.send_progress)(format!(
+        "🔐 Reading {} environment variables from secret managers",
+        secret_environment_variables.len()
+    ));
+
+    let mut resolved = BTreeMap::new();
+    for variable in secret_environment_variables {
+        let value = read_secret(&variable.secret, target, event_details).map_err(|err| {
+            EngineError::new_environment_variable_secret_cannot_be_resolved(
+                event_details.clone(),
+                variable.key.clone(),
+                err,
+            )
+        })?;
+        resolved.insert(variable.key.clone(), base64::encode(value));
+    }
+
+    let mut environment_variables: Vec<EnvironmentVariable> = environment_variables
+        .into_iter()
+        .filter(|ev| !resolved.contains_key(&ev.key))
+        .collect();
+    environment_variables.extend(
+        resolved
+            .into_iter()
+            .map(|(key, value)| EnvironmentVariable { key, value }),
+    );
+
+    Ok(environment_variables)
+}
+
+fn read_secret(
+    secret: &SecretReference,
+    target: &DeploymentTarget,
+    event_details: &EventDetails,
+) -> Result<String, CommandError> {
+    let cloud_provider = target.kubernetes.cloud_provider();
+    let credentials = cloud_provider.credentials_environment_variables();
+    let credential = |name: &str| {
+        credentials
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| *value)
+            .unwrap_or_default()
+    };
+
+    match secret {
+        SecretReference::Vault { mount, path, key } => {
+            let vault = QVaultClient::new(event_details.clone()).map_err(|err| {
+                err.underlying_error()
+                    .unwrap_or_else(|| CommandError::new_from_safe_message(err.user_log_message().to_string()))
+            })?;
+            let fields = vault.get_organization_secret::<BTreeMap<String, Value>>(
+                event_details.organisation_id(),
+                mount,
+                path,
+            )?;
+
+            fields
+                .get(key)
+                .map(value_to_string)
+                .ok_or_else(|| CommandError::new_from_safe_message(format!("Vault secret has no field `{}`", key)))
+        }
+        SecretReference::AwsSecretsManager { secret_id, key } => {
+            if cloud_provider.kind() != CPKind::Aws {
+                return Err(CommandError::new_from_safe_message(
+                    "AWS Secrets Manager can only be used on AWS clusters".to_string(),
+                ));
+            }
+            let secrets_manager = AwsSecretsManager::new(
+                credential(AWS_ACCESS_KEY_ID),
+                credential(AWS_SECRET_ACCESS_KEY),
+                target.kubernetes.region(),
+            )?;
+
+            secret_field(&secrets_manager.get_secret(secret_id)?, key.as_deref())
+                .map_err(CommandError::new_from_safe_message)
+        }
+        SecretReference::ScalewaySecretManager { secret_id, key } => {
+            if cloud_provider.kind() != CPKind::Scw {
+                return Err(CommandError::new_from_safe_message(
+                    "Scaleway Secret Manager can only be used on Scaleway clusters".to_string(),
+                ));
+            }
+            let secret_manager =
+                ScalewaySecretManager::new(credential(SCALEWAY_SECRET_KEY), target.kubernetes.region());
+
+            secret_field(&secret_manager.get_secret(secret_id)?, key.as_deref())
+                .map_err(CommandError::new_from_safe_message)
+        }
+    }
+}
+
+/// The whole secret, or one of its fields when it is a JSON object
+fn secret_field(secret: &str, key: Option<&str>) -> Result<String, String> {
+    let key = match key {
+        Some(key) => key,
+        None => return Ok(secret.to_string()),
+    };
+
+    let fields = serde_json::from_str::<BTreeMap<String, Value>>(secret)
+        .map_err(|_| format!("secret is not a JSON object, field `{}` cannot be read", key))?;
+    fields
+        .get(key)
+        .map(value_to_string)
+        .ok_or_else(|| format!("secret has no field `{}`", key))
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::deployment_action::secret_environment_variables::secret_field;
+
+    #[test]
+    fn test_secret_field() {
+        // setup:
+        let secret = r#"{"username": "qovery", "port": 5432}"#;
+
+        // execute & verify:
+        assert_eq!(secret_field(secret, None), Ok(secret.to_string()));
+        assert_eq!(secret_field(secret, Some("username")), Ok("qovery".to_string()));
+        assert_eq!(secret_field(secret, Some("port")), Ok("5432".to_string()));
+        assert!(secret_field(secret, Some("password")).is_err());
+        assert!(secret_field("not json", Some("username")).is_err());
+    }
+}
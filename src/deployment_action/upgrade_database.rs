@@ -0,0 +1,404 @@
+use std::time::Duration;
+
+use k8s_openapi::api::apps::v1::StatefulSet;
+use k8s_openapi::api::core::v1::{PersistentVolume, PersistentVolumeClaim};
+use kube::api::{DeleteParams, ListParams, Patch, PatchParams};
+use kube::Api;
+
+use crate::cloud_provider::service::{Action, Service};
+use crate::cloud_provider::DeploymentTarget;
+use crate::cmd::kubectl::kubectl_exec_with_output;
+use crate::constants::KUBECONFIG;
+use crate::deployment_action::database_backup::{
+    primary_pod_selector, running_pod_name, take_backup, DatabaseBackupAction, DumpScope,
+};
+use crate::deployment_report::logger::get_loggers;
+use crate::errors::{CommandError, EngineError};
+use crate::events::EventDetails;
+use crate::io_models::database::DatabaseOptions;
+use crate::models::database::{Container, Database, DatabaseType};
+use crate::models::database_utils::{
+    get_database_version_upgrade, get_self_hosted_mongodb_version, DatabaseVersionUpgrade,
+};
+use crate::models::types::CloudProvider;
+use crate::runtime::block_on;
+use itertools::Itertools;
+
+/// Volume of a database kept while it is recreated, with the reclaim policy to set back once it can be released
+struct RetainedVolume {
+    name: String,
+    reclaim_policy: String,
+}
+
+/// Upgrade a deployed container database to its requested version when its data files are rewritten by the upgrade.
+/// A backup is taken first, then the engine upgrade path is run. Minor upgrades are left to the helm deployment,
+/// which only swaps the image.
+///
+/// `deploy`: deploys the database chart with the given image version, and waits for the database to be ready
+pub(super) fn upgrade_database_version<C, T>(
+    db: &Database<C, Container, T>,
+    target: &DeploymentTarget,
+    event_details: EventDetails,
+    deploy: &dyn Fn(&str) -> Result<(), EngineError>,
+) -> Result<(), EngineError>
+where
+    C: CloudProvider,
+    T: DatabaseType<C, Container, DatabaseOptions = DatabaseOptions>,
+{
+    let namespace = target.environment.namespace();
+    let deployed_version = match block_on(get_deployed_version(
+        &target.kube,
+        namespace,
+        &db.selector(),
+        T::lib_directory_name(),
+    )) {
+        Some(deployed_version) => deployed_version,
+        None => return Ok(()),
+    };
+    let requested_version = db.get_version(event_details.clone())?.matched_version().to_string();
+    let upgrade_not_supported = |err: CommandError| {
+        EngineError::new_database_version_upgrade_not_supported(
+            event_details.clone(),
+            db.name().to_string(),
+            deployed_version.clone(),
+            requested_version.clone(),
+            err,
+        )
+    };
+    let upgrade = get_database_version_upgrade(T::db_type(), &deployed_version, &requested_version)
+        .map_err(upgrade_not_supported)?;
+    if !upgrade.is_major() {
+        return Ok(());
+    }
+
+    let logger = get_loggers(db, Action::Create);
+    (logger.send_progress)(format!(
+        "💾 Taking a backup of database {} before upgrading it from version {} to {}",
+        db.name(),
+        deployed_version,
+        requested_version
+    ));
+    let kubeconfig = target.kubernetes.get_kubeconfig_file_path()?;
+    let mut envs = vec![(KUBECONFIG, kubeconfig.as_str())];
+    envs.extend(target.kubernetes.cloud_provider().credentials_environment_variables());
+    // nothing has been changed yet, the upgrade is refused if the data cannot be saved.
+    // the whole instance is dumped, as the databases not managed by Qovery and the roles are recreated too
+    let backup = take_backup(db, target, &kubeconfig, &envs, DumpScope::Instance).map_err(upgrade_not_supported)?;
+    let to_error = |err: CommandError| {
+        EngineError::new_database_cannot_upgrade_version(
+            event_details.clone(),
+            db.name().to_string(),
+            backup.key.clone(),
+            err,
+        )
+    };
+
+    match upgrade {
+        // the helm deployment following the upgrade swaps the image
+        DatabaseVersionUpgrade::Minor | DatabaseVersionUpgrade::InPlace => {}
+        DatabaseVersionUpgrade::DumpAndRestore => {
+            (logger.send_progress)(format!(
+                "🔄 Recreating database {} with version {}, its data are restored from backup {}",
+                db.name(),
+                requested_version,
+                backup.key
+            ));
+            let list_databases = || -> Result<Vec<String>, CommandError> {
+                let pod_name = running_pod_name(&kubeconfig, &envs, namespace, &primary_pod_selector(&db.selector()))?;
+                let mut databases = vec![];
+                kubectl_exec_with_output(
+                    vec![
+                        "exec",
+                        "-n",
+                        namespace,
+                        pod_name.as_str(),
+                        "--",
+                        "bash",
+                        "-c",
+                        list_postgresql_databases_command(db.private_port, &db.options.login).as_str(),
+                    ],
+                    envs.clone(),
+                    &mut |line| databases.push(line),
+                    &mut |line| warn!("{}", line),
+                )?;
+                Ok(databases)
+            };
+
+            let deployed_databases = list_databases().map_err(upgrade_not_supported)?;
+            // the previous volumes are kept until the restore is verified, the data can be recovered from them
+            let retained_volumes =
+                block_on(delete_database_volumes(&target.kube, namespace, &db.selector())).map_err(to_error)?;
+            let retained_volume_names = retained_volumes.iter().map(|volume| volume.name.as_str()).join(", ");
+            let retained_volumes_error = |err: CommandError| {
+                to_error(CommandError::new(
+                    format!(
+                        "{} The data of the previous version are kept in persistent volumes {}",
+                        err.message_safe(),
+                        retained_volume_names
+                    ),
+                    err.message_raw(),
+                    None,
+                ))
+            };
+            let restored = deploy(&requested_version).and_then(|_| db.restore_backup(target, &backup));
+            if restored.is_err() {
+                (logger.send_progress)(format!(
+                    "⚠️ Data of database {} before the upgrade are kept in persistent volumes {}",
+                    db.name(),
+                    retained_volume_names
+                ));
+            }
+            restored?;
+
+            let restored_databases = list_databases().map_err(retained_volumes_error)?;
+            let missing = missing_databases(&deployed_databases, &restored_databases);
+            if !missing.is_empty() {
+                return Err(retained_volumes_error(CommandError::new_from_safe_message(format!(
+                    "Databases {} are missing once restored.",
+                    missing.join(", ")
+                ))));
+            }
+
+            block_on(release_volumes(&target.kube, &retained_volumes)).map_err(to_error)?;
+        }
+        DatabaseVersionUpgrade::FeatureCompatibilityVersions(release_series) => {
+            let mongo_exec = |release_series: &str| -> Result<(), CommandError> {
+                let pod_name = running_pod_name(&kubeconfig, &envs, namespace, &db.selector())?;
+                kubectl_exec_with_output(
+                    vec![
+                        "exec",
+                        "-n",
+                        namespace,
+                        pod_name.as_str(),
+                        "--",
+                        "bash",
+                        "-c",
+                        set_feature_compatibility_version_command(db.private_port, release_series).as_str(),
+                    ],
+                    envs.clone(),
+                    &mut |line| info!("{}", line),
+                    &mut |line| warn!("{}", line),
+                )
+            };
+
+            // the next release series only starts if the deployed one has all its features enabled
+            mongo_exec(&release_series_of(&deployed_version)).map_err(to_error)?;
+            for (index, series) in release_series.iter().enumerate() {
+                let version = if index == release_series.len() - 1 {
+                    requested_version.clone()
+                } else {
+                    get_self_hosted_mongodb_version(series.to_string()).map_err(to_error)?
+                };
+
+                (logger.send_progress)(format!("⬆️ Upgrading database {} to version {}", db.name(), version));
+                deploy(&version)?;
+                mongo_exec(series).map_err(to_error)?;
+            }
+        }
+    }
+
+    (logger.send_success)(format!(
+        "✅ Database {} has been upgraded from version {} to {}",
+        db.name(),
+        deployed_version,
+        requested_version
+    ));
+    Ok(())
+}
+
+/// `major.minor` of a version
+fn release_series_of(version: &str) -> String {
+    version.splitn(3, '.').take(2).collect::<Vec<&str>>().join(".")
+}
+
+/// The password is read from the database container environment, so it never appears in the command line
+fn set_feature_compatibility_version_command(port: u16, release_series: &str) -> String {
+    format!(
+        "mongo --quiet --host 127.0.0.1 --port {} -u root -p \"$MONGODB_ROOT_PASSWORD\" --authenticationDatabase admin --eval 'assert.commandWorked(db.adminCommand({{ setFeatureCompatibilityVersion: \"{}\" }}))'",
+        port, release_series
+    )
+}
+
+/// Names of the databases of a PostgreSQL instance, one per line
+fn list_postgresql_databases_command(port: u16, login: &str) -> String {
+    format!(
+        "PGPASSWORD=\"$POSTGRES_PASSWORD\" psql -At -h 127.0.0.1 -p {} -U {} -d postgres -c 'SELECT datname FROM pg_database WHERE NOT datistemplate ORDER BY datname'",
+        port, login
+    )
+}
+
+/// Databases deployed before the upgrade which have not been restored
+fn missing_databases(deployed: &[String], restored: &[String]) -> Vec<String> {
+    deployed
+        .iter()
+        .filter(|database| !restored.contains(database))
+        .cloned()
+        .collect()
+}
+
+/// Version of the database image running, None if the database has never been deployed
+async fn get_deployed_version(
+    kube: &kube::Client,
+    namespace: &str,
+    selector: &str,
+    image_name: &str,
+) -> Option<String> {
+    let statefulsets: Api<StatefulSet> = Api::namespaced(kube.clone(), namespace);
+    let image_prefix = format!("{}:", image_name);
+
+    statefulsets
+        .list(&ListParams::default().labels(selector))
+        .await
+        .ok()?
+        .items
+        .first()?
+        .spec
+        .as_ref()?
+        .template
+        .spec
+        .as_ref()?
+        .containers
+        .iter()
+        .filter_map(|container| container.image.as_ref())
+        .find_map(|image| image.rsplit('/').next()?.strip_prefix(&image_prefix))
+        .map(|version| version.to_string())
+}
+
+/// Delete the StatefulSets of the database along with their volume claims, so the next deployment starts with empty
+/// data files. The persistent volumes are retained, they are only released by `release_volumes`
+async fn delete_database_volumes(
+    kube: &kube::Client,
+    namespace: &str,
+    selector: &str,
+) -> Result<Vec<RetainedVolume>, CommandError> {
+    let to_error = |err: kube::Error| {
+        CommandError::new("Cannot delete the database volumes".to_string(), Some(err.to_string()), None)
+    };
+    let statefulsets: Api<StatefulSet> = Api::namespaced(kube.clone(), namespace);
+    let pvcs: Api<PersistentVolumeClaim> = Api::namespaced(kube.clone(), namespace);
+    let pvs: Api<PersistentVolume> = Api::all(kube.clone());
+
+    // volumes of a StatefulSet are named `<claim template>-<statefulset>-<ordinal>`, they outlive the StatefulSet
+    let mut volume_prefixes = vec![];
+    for statefulset in statefulsets
+        .list(&ListParams::default().labels(selector))
+        .await
+        .map_err(to_error)?
+    {
+        let name = match statefulset.metadata.name {
+            Some(name) => name,
+            None => continue,
+        };
+        for claim_template in statefulset
+            .spec
+            .and_then(|spec| spec.volume_claim_templates)
+            .unwrap_or_default()
+        {
+            if let Some(claim_name) = claim_template.metadata.name {
+                volume_prefixes.push(format!("{}-{}-", claim_name, name));
+            }
+        }
+
+        statefulsets
+            .delete(&name, &DeleteParams::foreground())
+            .await
+            .map_err(to_error)?;
+    }
+
+    let is_database_volume = |pvc: &PersistentVolumeClaim| match &pvc.metadata.name {
+        Some(name) => volume_prefixes.iter().any(|prefix| name.starts_with(prefix)),
+        None => false,
+    };
+    let mut retained_volumes = vec![];
+    for pvc in pvcs.list(&ListParams::default()).await.map_err(to_error)? {
+        let name = match pvc.metadata.name.as_ref().filter(|_| is_database_volume(&pvc)) {
+            Some(name) => name,
+            None => continue,
+        };
+
+        if let Some(volume_name) = pvc.spec.as_ref().and_then(|spec| spec.volume_name.as_ref()) {
+            let volume = pvs.get(volume_name).await.map_err(to_error)?;
+            let reclaim_policy = volume
+                .spec
+                .and_then(|spec| spec.persistent_volume_reclaim_policy)
+                .unwrap_or_else(|| "Delete".to_string());
+            set_reclaim_policy(&pvs, volume_name, "Retain")
+                .await
+                .map_err(to_error)?;
+            retained_volumes.push(RetainedVolume {
+                name: volume_name.to_string(),
+                reclaim_policy,
+            });
+        }
+        pvcs.delete(name, &DeleteParams::default()).await.map_err(to_error)?;
+    }
+
+    // volumes are only removed once the database pods are gone, a new one must not be attached to them
+    for _ in 0..60 {
+        let remaining = pvcs.list(&ListParams::default()).await.map_err(to_error)?;
+        if !remaining.iter().any(is_database_volume) {
+            return Ok(retained_volumes);
+        }
+
+        tokio::time::sleep(Duration::from_secs(5)).await;
+    }
+
+    Err(CommandError::new_from_safe_message(
+        "Database volumes are still not deleted after 5 minutes".to_string(),
+    ))
+}
+
+async fn set_reclaim_policy(pvs: &Api<PersistentVolume>, name: &str, reclaim_policy: &str) -> Result<(), kube::Error> {
+    let patch = serde_json::json!({ "spec": { "persistentVolumeReclaimPolicy": reclaim_policy } });
+    pvs.patch(name, &PatchParams::default(), &Patch::Merge(&patch)).await?;
+    Ok(())
+}
+
+/// Set back the reclaim policy of the retained volumes, the storage class deletes them if they were not retained before
+async fn release_volumes(kube: &kube::Client, volumes: &[RetainedVolume]) -> Result<(), CommandError> {
+    let pvs: Api<PersistentVolume> = Api::all(kube.clone());
+    for volume in volumes {
+        set_reclaim_policy(&pvs, &volume.name, &volume.reclaim_policy)
+            .await
+            .map_err(|err| {
+                CommandError::new(
+                    format!("Cannot release persistent volume {}", volume.name),
+                    Some(err.to_string()),
+                    None,
+                )
+            })?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_release_series_of() {
+        assert_eq!(release_series_of("4.2.21"), "4.2");
+        assert_eq!(release_series_of("4.4"), "4.4");
+    }
+
+    #[test]
+    fn test_missing_databases() {
+        // setup:
+        let deployed = vec!["analytics".to_string(), "my-db".to_string(), "postgres".to_string()];
+        let restored = vec!["my-db".to_string(), "postgres".to_string()];
+
+        // execute & verify:
+        assert_eq!(vec!["analytics".to_string()], missing_databases(&deployed, &restored));
+        assert!(missing_databases(&restored, &deployed).is_empty());
+    }
+
+    #[test]
+    fn test_set_feature_compatibility_version_command_does_not_contain_password() {
+        let command = set_feature_compatibility_version_command(27017, "4.2");
+
+        assert!(command.contains("\"$MONGODB_ROOT_PASSWORD\""));
+        assert!(command.contains("setFeatureCompatibilityVersion: \"4.2\""));
+        assert!(command.contains("--port 27017"));
+    }
+}
@@ -0,0 +1,835 @@
+use crate::cloud_provider::service::{Action, Service};
+use crate::cloud_provider::DeploymentTarget;
+use crate::cmd::kubectl::kubectl_exec_with_output;
+use crate::constants::KUBECONFIG;
+use crate::deployment_action::pause_service::pause_service;
+use crate::deployment_report::logger::get_loggers;
+use crate::errors::{CommandError, EngineError};
+use crate::events::{EngineEvent, EventDetails, EventMessage};
+use crate::io_models::application::SnapshotSchedule;
+use crate::runtime::block_on;
+use crate::string::cut;
+use chrono::{DateTime, Duration, Utc};
+use k8s_openapi::api::core::v1::{
+    PersistentVolumeClaim, PersistentVolumeClaimSpec, ResourceRequirements, TypedLocalObjectReference,
+};
+use k8s_openapi::api::storage::v1::StorageClass;
+use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kube::api::{ApiResource, DeleteParams, DynamicObject, GroupVersionKind, ListParams, PostParams};
+use kube::Api;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::thread;
+use std::time::Instant;
+use uuid::Uuid;
+
+const VOLUME_SNAPSHOT_GROUP: &str = "snapshot.storage.k8s.io";
+const SERVICE_ID_LABEL: &str = "qovery.com/service-id";
+const STORAGE_ID_LABEL: &str = "qovery.com/storage-id";
+const RESTORED_FROM_ANNOTATION: &str = "qovery.com/restored-from-snapshot";
+const DEFAULT_SNAPSHOT_CLASS_ANNOTATION: &str = "snapshot.storage.kubernetes.io/is-default-class";
+const DEFAULT_STORAGE_CLASS_ANNOTATION: &str = "storageclass.kubernetes.io/is-default-class";
+const SCHEDULER_NAME: &str = "qovery-volume-snapshots";
+const KUBECTL_IMAGE: &str = "docker.io/bitnami/kubectl:1.24";
+const POLLING_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+const TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+
+/// Storage of a stateful service, backed by one persistent volume claim per replica
+pub(super) struct ServiceStorage<'a> {
+    pub long_id: Uuid,
+    pub storage_class: &'a str,
+    /// None to use the default VolumeSnapshotClass of the cluster
+    pub volume_snapshot_class: Option<&'static str>,
+    pub size_in_gib: u16,
+    pub snapshot_schedule: &'a SnapshotSchedule,
+    pub snapshot_retention_in_days: u16,
+    pub restore_from_snapshot: Option<&'a str>,
+    /// selects the persistent volume claims of the storage
+    pub pvc_selector: String,
+    /// claim of the first replica, created when restoring a service which has no volume yet
+    pub first_pvc_name: String,
+}
+
+fn volume_snapshot_resource() -> ApiResource {
+    ApiResource::from_gvk(&GroupVersionKind::gvk(VOLUME_SNAPSHOT_GROUP, "v1", "VolumeSnapshot"))
+}
+
+fn volume_snapshot_class_resource() -> ApiResource {
+    ApiResource::from_gvk(&GroupVersionKind::gvk(VOLUME_SNAPSHOT_GROUP, "v1", "VolumeSnapshotClass"))
+}
+
+fn volume_snapshots(target: &DeploymentTarget) -> Api<DynamicObject> {
+    Api::namespaced_with(target.kube.clone(), target.environment.namespace(), &volume_snapshot_resource())
+}
+
+fn pvcs(target: &DeploymentTarget) -> Api<PersistentVolumeClaim> {
+    Api::namespaced(target.kube.clone(), target.environment.namespace())
+}
+
+fn storage_selector(storage_long_id: &Uuid) -> String {
+    format!("{}={}", STORAGE_ID_LABEL, storage_long_id)
+}
+
+fn creation_time(snapshot: &DynamicObject) -> Option<DateTime<Utc>> {
+    snapshot.metadata.creation_timestamp.as_ref().map(|time| time.0)
+}
+
+/// Schedule of the CronJob taking the snapshots, snapshots are not scheduled when they are taken on deploy
+fn snapshot_cron_schedule(schedule: &SnapshotSchedule) -> Option<&'static str> {
+    match schedule {
+        SnapshotSchedule::Never | SnapshotSchedule::BeforeDeploy => None,
+        SnapshotSchedule::Daily => Some("@daily"),
+        SnapshotSchedule::Weekly => Some("@weekly"),
+    }
+}
+
+fn volume_snapshot_spec(pvc_name: &str, volume_snapshot_class: Option<&str>) -> serde_json::Value {
+    match volume_snapshot_class {
+        Some(class) => json!({ "volumeSnapshotClassName": class, "source": { "persistentVolumeClaimName": pvc_name } }),
+        None => json!({ "source": { "persistentVolumeClaimName": pvc_name } }),
+    }
+}
+
+/// Snapshots older than the retention, snapshots are kept forever when the retention is 0
+fn expired_snapshots(snapshots: &[(String, DateTime<Utc>)], retention_in_days: u16, now: DateTime<Utc>) -> Vec<String> {
+    if retention_in_days == 0 {
+        return vec![];
+    }
+
+    snapshots
+        .iter()
+        .filter(|(_, created_at)| now - *created_at > Duration::days(retention_in_days as i64))
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
+fn is_default_class(metadata: &ObjectMeta, annotation: &str) -> bool {
+    metadata
+        .annotations
+        .as_ref()
+        .and_then(|annotations| annotations.get(annotation))
+        .map(|is_default| is_default == "true")
+        .unwrap_or(false)
+}
+
+/// Driver of the VolumeSnapshotClass, the default one of the cluster when no class is given
+fn snapshot_class_driver(classes: &[DynamicObject], class_name: Option<&str>) -> Option<String> {
+    classes
+        .iter()
+        .find(|class| match class_name {
+            Some(name) => class.metadata.name.as_deref() == Some(name),
+            None => is_default_class(&class.metadata, DEFAULT_SNAPSHOT_CLASS_ANNOTATION),
+        })
+        .and_then(|class| class.data.get("driver")?.as_str().map(|driver| driver.to_string()))
+}
+
+/// Provisioner of the StorageClass, the default one of the cluster when the name is empty
+fn storage_class_provisioner(classes: &[StorageClass], class_name: &str) -> Option<String> {
+    classes
+        .iter()
+        .find(|class| match class_name.is_empty() {
+            true => is_default_class(&class.metadata, DEFAULT_STORAGE_CLASS_ANNOTATION),
+            false => class.metadata.name.as_deref() == Some(class_name),
+        })
+        .map(|class| class.provisioner.clone())
+}
+
+/// Why the volumes of the storage cannot be snapshotted: the snapshot CRDs are missing, there is no VolumeSnapshotClass,
+/// or the volumes are not provisioned by the CSI driver of the class (i.e in-tree `kubernetes.io/aws-ebs` volumes)
+fn volume_snapshots_unsupported_reason(storage: &ServiceStorage, target: &DeploymentTarget) -> Option<String> {
+    let snapshot_classes: Api<DynamicObject> = Api::all_with(target.kube.clone(), &volume_snapshot_class_resource());
+    let snapshot_classes = match block_on(snapshot_classes.list(&ListParams::default())) {
+        Ok(classes) => classes.items,
+        Err(kube::Error::Api(err)) if err.code == 404 => {
+            return Some("VolumeSnapshot CRDs are not installed on the cluster".to_string())
+        }
+        Err(err) => return Some(err.to_string()),
+    };
+    let driver = match snapshot_class_driver(&snapshot_classes, storage.volume_snapshot_class) {
+        Some(driver) => driver,
+        None => {
+            return Some(match storage.volume_snapshot_class {
+                Some(class) => format!("VolumeSnapshotClass {} does not exist on the cluster", class),
+                None => "the cluster has no default VolumeSnapshotClass".to_string(),
+            })
+        }
+    };
+
+    let storage_classes: Api<StorageClass> = Api::all(target.kube.clone());
+    let storage_classes = match block_on(storage_classes.list(&ListParams::default())) {
+        Ok(classes) => classes.items,
+        Err(err) => return Some(err.to_string()),
+    };
+    match storage_class_provisioner(&storage_classes, storage.storage_class) {
+        Some(provisioner) if provisioner == driver => None,
+        Some(provisioner) => Some(format!(
+            "volumes of storage class {} are provisioned by {}, snapshots are only taken by {}",
+            storage.storage_class, provisioner, driver
+        )),
+        None => Some(format!("storage class {} does not exist on the cluster", storage.storage_class)),
+    }
+}
+
+/// Reject snapshots and restores requested on a cluster which cannot take them, instead of waiting for snapshots
+/// never cut by the cluster
+pub(super) fn check_volume_snapshots_support<S: Service>(
+    service: &S,
+    storages: &[ServiceStorage],
+    target: &DeploymentTarget,
+    event_details: &EventDetails,
+) -> Result<(), EngineError> {
+    for storage in storages.iter().filter(|storage| {
+        storage.snapshot_schedule != &SnapshotSchedule::Never || storage.restore_from_snapshot.is_some()
+    }) {
+        if let Some(reason) = volume_snapshots_unsupported_reason(storage, target) {
+            return Err(EngineError::new_k8s_volume_snapshots_not_supported(
+                event_details.clone(),
+                service.name().to_string(),
+                CommandError::new_from_safe_message(reason),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Take a snapshot of each volume of the storage, and wait for the CSI driver to cut it
+fn create_volume_snapshots(
+    service_long_id: &Uuid,
+    storage: &ServiceStorage,
+    target: &DeploymentTarget,
+    event_details: &EventDetails,
+) -> Result<Vec<String>, EngineError> {
+    let claims = block_on(pvcs(target).list(&ListParams::default().labels(&storage.pvc_selector))).map_err(|err| {
+        EngineError::new_k8s_cannot_create_volume_snapshot(
+            event_details.clone(),
+            storage.pvc_selector.clone(),
+            CommandError::new_from_safe_message(err.to_string()),
+        )
+    })?;
+
+    let api = volume_snapshots(target);
+    let mut snapshot_names = vec![];
+    for pvc_name in claims.items.into_iter().filter_map(|pvc| pvc.metadata.name) {
+        let error = |raw_error: String| {
+            EngineError::new_k8s_cannot_create_volume_snapshot(
+                event_details.clone(),
+                pvc_name.clone(),
+                CommandError::new_from_safe_message(raw_error),
+            )
+        };
+
+        let name = format!("{}-{}", cut(pvc_name.clone(), 200), Utc::now().format("%Y%m%d%H%M%S"));
+        let mut snapshot = DynamicObject::new(&name, &volume_snapshot_resource())
+            .within(target.environment.namespace())
+            .data(json!({ "spec": volume_snapshot_spec(&pvc_name, storage.volume_snapshot_class) }));
+        snapshot.metadata.labels = Some(BTreeMap::from([
+            (SERVICE_ID_LABEL.to_string(), service_long_id.to_string()),
+            (STORAGE_ID_LABEL.to_string(), storage.long_id.to_string()),
+        ]));
+        block_on(api.create(&PostParams::default(), &snapshot)).map_err(|err| error(err.to_string()))?;
+
+        // the snapshot is cut once its creation time is set, it may still be uploaded by the provider afterwards
+        let started_at = Instant::now();
+        loop {
+            let status = block_on(api.get(&name))
+                .map_err(|err| error(err.to_string()))?
+                .data
+                .get("status")
+                .cloned()
+                .unwrap_or_default();
+            if let Some(message) = status.pointer("/error/message").and_then(|message| message.as_str()) {
+                return Err(error(message.to_string()));
+            }
+            if status.get("creationTime").is_some() {
+                break;
+            }
+            if started_at.elapsed() > TIMEOUT {
+                return Err(error(format!("snapshot {} has not been taken after {:?}", name, TIMEOUT)));
+            }
+
+            thread::sleep(POLLING_INTERVAL);
+        }
+
+        snapshot_names.push(name);
+    }
+
+    Ok(snapshot_names)
+}
+
+/// Take a snapshot of the storages which are snapshotted before each deployment
+pub(super) fn create_pre_deploy_volume_snapshots<S: Service>(
+    service: &S,
+    storages: &[ServiceStorage],
+    target: &DeploymentTarget,
+    event_details: &EventDetails,
+) -> Result<(), EngineError> {
+    let logger = get_loggers(service, Action::Create);
+
+    for storage in storages
+        .iter()
+        .filter(|storage| storage.snapshot_schedule == &SnapshotSchedule::BeforeDeploy)
+    {
+        for name in create_volume_snapshots(service.long_id(), storage, target, event_details)? {
+            (logger.send_progress)(format!("📸 Volume snapshot {} has been taken", name));
+        }
+    }
+
+    Ok(())
+}
+
+/// Create a CronJob per storage snapshotted daily or weekly, so snapshots are taken and pruned even without deployments.
+/// CronJobs of the other storages of the service are removed.
+pub(super) fn schedule_volume_snapshots<S: Service>(
+    service: &S,
+    storages: &[ServiceStorage],
+    target: &DeploymentTarget,
+    event_details: &EventDetails,
+) -> Result<(), EngineError> {
+    let to_error = |err: CommandError| {
+        EngineError::new_k8s_cannot_schedule_volume_snapshots(event_details.clone(), service.name().to_string(), err)
+    };
+    let namespace = target.environment.namespace();
+    let kubeconfig = target.kubernetes.get_kubeconfig_file_path()?;
+    let scheduled_storages: Vec<(&ServiceStorage, &str)> = storages
+        .iter()
+        .filter_map(|storage| Some((storage, snapshot_cron_schedule(storage.snapshot_schedule)?)))
+        .collect();
+
+    if !scheduled_storages.is_empty() {
+        let mut items = snapshot_scheduler_rbac_manifests(namespace);
+        items.extend(
+            scheduled_storages
+                .iter()
+                .map(|(storage, schedule)| snapshot_cronjob_manifest(service.long_id(), storage, schedule, namespace)),
+        );
+        let manifest_path = Path::new(&service.workspace_directory()).join("qovery-volume-snapshots.json");
+        fs::write(
+            &manifest_path,
+            json!({ "apiVersion": "v1", "kind": "List", "items": items }).to_string(),
+        )
+        .map_err(|err| {
+            to_error(CommandError::new(
+                "Cannot write volume snapshots manifest".to_string(),
+                Some(err.to_string()),
+                None,
+            ))
+        })?;
+        kubectl(
+            target,
+            &kubeconfig,
+            vec!["apply", "-f", manifest_path.to_str().unwrap_or_default()],
+        )
+        .map_err(to_error)?;
+    }
+
+    let selector = match scheduled_storages.is_empty() {
+        true => format!("{}={}", SERVICE_ID_LABEL, service.long_id()),
+        false => format!(
+            "{}={},{} notin ({})",
+            SERVICE_ID_LABEL,
+            service.long_id(),
+            STORAGE_ID_LABEL,
+            scheduled_storages
+                .iter()
+                .map(|(storage, _)| storage.long_id.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+    };
+    kubectl(
+        target,
+        &kubeconfig,
+        vec![
+            "delete",
+            "-n",
+            namespace,
+            "cronjob",
+            "-l",
+            selector.as_str(),
+            "--ignore-not-found",
+        ],
+    )
+    .map_err(to_error)
+}
+
+fn kubectl(target: &DeploymentTarget, kubeconfig: &str, args: Vec<&str>) -> Result<(), CommandError> {
+    let mut envs = vec![(KUBECONFIG, kubeconfig)];
+    envs.extend(target.kubernetes.cloud_provider().credentials_environment_variables());
+
+    kubectl_exec_with_output(args, envs, &mut |line| info!("{}", line), &mut |line| warn!("{}", line))
+}
+
+/// Identity of the CronJobs, only allowed to snapshot the volumes of the namespace and to prune their snapshots
+fn snapshot_scheduler_rbac_manifests(namespace: &str) -> Vec<serde_json::Value> {
+    let metadata = json!({ "name": SCHEDULER_NAME, "namespace": namespace });
+
+    vec![
+        json!({ "apiVersion": "v1", "kind": "ServiceAccount", "metadata": metadata }),
+        json!({
+            "apiVersion": "rbac.authorization.k8s.io/v1",
+            "kind": "Role",
+            "metadata": metadata,
+            "rules": [
+                { "apiGroups": [""], "resources": ["persistentvolumeclaims"], "verbs": ["list"] },
+                { "apiGroups": [VOLUME_SNAPSHOT_GROUP], "resources": ["volumesnapshots"], "verbs": ["create", "list", "delete"] },
+            ]
+        }),
+        json!({
+            "apiVersion": "rbac.authorization.k8s.io/v1",
+            "kind": "RoleBinding",
+            "metadata": metadata,
+            "roleRef": { "apiGroup": "rbac.authorization.k8s.io", "kind": "Role", "name": SCHEDULER_NAME },
+            "subjects": [{ "kind": "ServiceAccount", "name": SCHEDULER_NAME, "namespace": namespace }],
+        }),
+    ]
+}
+
+/// Script of the CronJob: snapshot each volume of the storage, then delete the snapshots older than the retention
+fn snapshot_script(service_long_id: &Uuid, storage: &ServiceStorage) -> String {
+    let snapshot_class = match storage.volume_snapshot_class {
+        Some(class) => format!("\n  volumeSnapshotClassName: {}", class),
+        None => String::new(),
+    };
+
+    format!(
+        r#"set -eu
+timestamp=$(date -u +%Y%m%d%H%M%S)
+for pvc in $(kubectl get pvc -l '{pvc_selector}' -o jsonpath='{{.items[*].metadata.name}}'); do
+  printf 'apiVersion: {group}/v1
+kind: VolumeSnapshot
+metadata:
+  name: %s-%s
+  labels:
+    {service_label}: "{service_id}"
+    {storage_label}: "{storage_id}"
+spec:{snapshot_class}
+  source:
+    persistentVolumeClaimName: %s
+' "${{pvc:0:200}}" "$timestamp" "$pvc" | kubectl create -f -
+done
+retention_in_days={retention}
+if [ "$retention_in_days" -gt 0 ]; then
+  expired_before=$(date -u -d "-$retention_in_days days" +%Y-%m-%dT%H:%M:%SZ)
+  kubectl get volumesnapshots -l '{storage_label}={storage_id}' -o jsonpath='{{range .items[*]}}{{.metadata.name}} {{.metadata.creationTimestamp}}{{"\n"}}{{end}}' | while read -r name created_at; do
+    if [[ "$created_at" < "$expired_before" && "$name" != '{keep}' ]]; then
+      kubectl delete volumesnapshot "$name"
+    fi
+  done
+fi"#,
+        pvc_selector = storage.pvc_selector,
+        group = VOLUME_SNAPSHOT_GROUP,
+        service_label = SERVICE_ID_LABEL,
+        service_id = service_long_id,
+        storage_label = STORAGE_ID_LABEL,
+        storage_id = storage.long_id,
+        snapshot_class = snapshot_class,
+        retention = storage.snapshot_retention_in_days,
+        // never prune the snapshot being restored
+        keep = storage.restore_from_snapshot.unwrap_or_default(),
+    )
+}
+
+fn snapshot_cronjob_name(storage_long_id: &Uuid) -> String {
+    format!("snapshots-{}", storage_long_id)
+}
+
+fn snapshot_cronjob_manifest(
+    service_long_id: &Uuid,
+    storage: &ServiceStorage,
+    schedule: &str,
+    namespace: &str,
+) -> serde_json::Value {
+    let labels =
+        json!({ SERVICE_ID_LABEL: service_long_id.to_string(), STORAGE_ID_LABEL: storage.long_id.to_string() });
+    // snapshot pods must not match the selector of the service
+    let pod_labels = json!({ "qovery.com/volume-snapshots": storage.long_id.to_string() });
+
+    json!({
+        "apiVersion": "batch/v1",
+        "kind": "CronJob",
+        "metadata": { "name": snapshot_cronjob_name(&storage.long_id), "namespace": namespace, "labels": labels },
+        "spec": {
+            "schedule": schedule,
+            "concurrencyPolicy": "Forbid",
+            "successfulJobsHistoryLimit": 1,
+            "failedJobsHistoryLimit": 1,
+            "jobTemplate": {
+                "spec": {
+                    "backoffLimit": 1,
+                    "template": {
+                        "metadata": { "labels": pod_labels },
+                        "spec": {
+                            "restartPolicy": "Never",
+                            "serviceAccountName": SCHEDULER_NAME,
+                            "containers": [{
+                                "name": "snapshot",
+                                "image": KUBECTL_IMAGE,
+                                "command": ["bash", "-c", snapshot_script(service_long_id, storage)],
+                            }],
+                        }
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Delete the snapshots of each storage older than its retention.
+/// Pruning is best effort, snapshots left behind are pruned on the next deployment.
+pub(super) fn prune_volume_snapshots<S: Service>(
+    service: &S,
+    storages: &[ServiceStorage],
+    target: &DeploymentTarget,
+    event_details: &EventDetails,
+) {
+    let api = volume_snapshots(target);
+    let now = Utc::now();
+
+    for storage in storages {
+        let snapshots = match block_on(api.list(&ListParams::default().labels(&storage_selector(&storage.long_id)))) {
+            Ok(snapshots) => snapshots
+                .items
+                .iter()
+                .filter_map(|snapshot| Some((snapshot.metadata.name.clone()?, creation_time(snapshot)?)))
+                // never prune the snapshot being restored
+                .filter(|(name, _)| Some(name.as_str()) != storage.restore_from_snapshot)
+                .collect::<Vec<_>>(),
+            Err(_) => continue,
+        };
+
+        for name in expired_snapshots(&snapshots, storage.snapshot_retention_in_days, now) {
+            if let Err(err) = block_on(api.delete(&name, &DeleteParams::default())) {
+                service.logger().log(EngineEvent::Warning(
+                    event_details.clone(),
+                    EventMessage::new(format!("Cannot delete expired volume snapshot {}", name), Some(err.to_string())),
+                ));
+            }
+        }
+    }
+}
+
+/// Rebind the volumes of the storages to restore to the chosen snapshot.
+/// The service is scaled down, its current volumes are snapshotted then replaced by volumes created from the snapshot.
+/// Volumes already restored from the same snapshot are left untouched, so a restore happens only once.
+pub(super) fn restore_volume_snapshots<S: Service>(
+    service: &S,
+    storages: &[ServiceStorage],
+    target: &DeploymentTarget,
+    event_details: &EventDetails,
+) -> Result<(), EngineError> {
+    let logger = get_loggers(service, Action::Create);
+
+    for storage in storages {
+        let snapshot_name = match storage.restore_from_snapshot {
+            Some(snapshot_name) => snapshot_name,
+            None => continue,
+        };
+        let error = |raw_error: String| {
+            EngineError::new_k8s_cannot_restore_volume_snapshot(
+                event_details.clone(),
+                snapshot_name.to_string(),
+                CommandError::new_from_safe_message(raw_error),
+            )
+        };
+
+        let snapshot = block_on(volume_snapshots(target).get_opt(snapshot_name))
+            .map_err(|err| error(err.to_string()))?
+            .ok_or_else(|| error("volume snapshot does not exist".to_string()))?;
+        let snapshot_storage_id = snapshot
+            .metadata
+            .labels
+            .as_ref()
+            .and_then(|labels| labels.get(STORAGE_ID_LABEL));
+        if snapshot_storage_id != Some(&storage.long_id.to_string()) {
+            return Err(error(format!(
+                "volume snapshot has not been taken from storage {}",
+                storage.long_id
+            )));
+        }
+        if snapshot.data.pointer("/status/readyToUse") != Some(&json!(true)) {
+            return Err(error("volume snapshot is not ready to be used yet".to_string()));
+        }
+
+        let current_pvcs = block_on(pvcs(target).list(&ListParams::default().labels(&storage.pvc_selector)))
+            .map_err(|err| error(err.to_string()))?
+            .items;
+        let is_restored = |pvc: &PersistentVolumeClaim| {
+            pvc.metadata
+                .annotations
+                .as_ref()
+                .and_then(|annotations| annotations.get(RESTORED_FROM_ANNOTATION))
+                .map(|restored_from| restored_from == snapshot_name)
+                .unwrap_or(false)
+        };
+        if !current_pvcs.is_empty() && current_pvcs.iter().all(is_restored) {
+            continue;
+        }
+
+        (logger.send_progress)(format!("⏪ Restoring volumes from snapshot {}", snapshot_name));
+        if let Some(selector) = service.selector() {
+            block_on(pause_service(&target.kube, target.environment.namespace(), &selector, 0, true))
+                .map_err(|err| error(err.to_string()))?;
+        }
+
+        // keep the current data, in case the wrong snapshot has been chosen
+        for name in create_volume_snapshots(service.long_id(), storage, target, event_details)? {
+            (logger.send_progress)(format!("📸 Volume snapshot {} of the replaced volume has been taken", name));
+        }
+
+        let pvc_names = match current_pvcs.is_empty() {
+            true => vec![storage.first_pvc_name.clone()],
+            false => current_pvcs.into_iter().filter_map(|pvc| pvc.metadata.name).collect(),
+        };
+        for pvc_name in pvc_names {
+            replace_pvc(&pvc_name, storage, snapshot_name, target).map_err(error)?;
+        }
+        (logger.send_progress)(format!("⏪ Volumes have been restored from snapshot {}", snapshot_name));
+    }
+
+    Ok(())
+}
+
+/// Labels matching the selector, so the statefulset and the engine find the new claim
+fn selector_labels(selector: &str) -> BTreeMap<String, String> {
+    selector
+        .split(',')
+        .filter_map(|label| label.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect()
+}
+
+fn replace_pvc(
+    pvc_name: &str,
+    storage: &ServiceStorage,
+    snapshot_name: &str,
+    target: &DeploymentTarget,
+) -> Result<(), String> {
+    let api = pvcs(target);
+
+    match block_on(api.delete(pvc_name, &DeleteParams::default())) {
+        Ok(_) => {}
+        Err(kube::Error::Api(err)) if err.code == 404 => {}
+        Err(err) => return Err(err.to_string()),
+    };
+    // the claim is only gone once its volume is released by the pods
+    let started_at = Instant::now();
+    while block_on(api.get_opt(pvc_name))
+        .map_err(|err| err.to_string())?
+        .is_some()
+    {
+        if started_at.elapsed() > TIMEOUT {
+            return Err(format!("pvc {} has not been deleted after {:?}", pvc_name, TIMEOUT));
+        }
+        thread::sleep(POLLING_INTERVAL);
+    }
+
+    let pvc = PersistentVolumeClaim {
+        metadata: ObjectMeta {
+            name: Some(pvc_name.to_string()),
+            labels: Some(selector_labels(&storage.pvc_selector)),
+            annotations: Some(BTreeMap::from([(
+                RESTORED_FROM_ANNOTATION.to_string(),
+                snapshot_name.to_string(),
+            )])),
+            ..Default::default()
+        },
+        spec: Some(PersistentVolumeClaimSpec {
+            access_modes: Some(vec!["ReadWriteOnce".to_string()]),
+            // an empty storage class means the default one of the cluster
+            storage_class_name: match storage.storage_class.is_empty() {
+                true => None,
+                false => Some(storage.storage_class.to_string()),
+            },
+            resources: Some(ResourceRequirements {
+                requests: Some(BTreeMap::from([(
+                    "storage".to_string(),
+                    Quantity(format!("{}Gi", storage.size_in_gib)),
+                )])),
+                ..Default::default()
+            }),
+            data_source: Some(TypedLocalObjectReference {
+                api_group: Some(VOLUME_SNAPSHOT_GROUP.to_string()),
+                kind: "VolumeSnapshot".to_string(),
+                name: snapshot_name.to_string(),
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    block_on(api.create(&PostParams::default(), &pvc))
+        .map(|_| ())
+        .map_err(|err| err.to_string())
+}
+
+/// Delete the snapshot CronJobs and all the snapshots of a deleted service, failures are ignored
+pub(super) fn delete_volume_snapshots(service_long_id: &Uuid, target: &DeploymentTarget) {
+    let selector = format!("{}={}", SERVICE_ID_LABEL, service_long_id);
+    if let Ok(kubeconfig) = target.kubernetes.get_kubeconfig_file_path() {
+        let _ = kubectl(
+            target,
+            &kubeconfig,
+            vec![
+                "delete",
+                "-n",
+                target.environment.namespace(),
+                "cronjob",
+                "-l",
+                selector.as_str(),
+                "--ignore-not-found",
+            ],
+        );
+    }
+    let _ = block_on(
+        volume_snapshots(target).delete_collection(&DeleteParams::default(), &ListParams::default().labels(&selector)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::deployment_action::volume_snapshot::{
+        expired_snapshots, selector_labels, snapshot_class_driver, snapshot_cron_schedule, snapshot_cronjob_manifest,
+        storage_class_provisioner, volume_snapshot_class_resource, ServiceStorage, DEFAULT_SNAPSHOT_CLASS_ANNOTATION,
+        DEFAULT_STORAGE_CLASS_ANNOTATION, SCHEDULER_NAME, STORAGE_ID_LABEL,
+    };
+    use crate::io_models::application::SnapshotSchedule;
+    use chrono::{Duration, TimeZone, Utc};
+    use k8s_openapi::api::storage::v1::StorageClass;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+    use kube::api::DynamicObject;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_snapshot_cron_schedule() {
+        assert_eq!(snapshot_cron_schedule(&SnapshotSchedule::Never), None);
+        assert_eq!(snapshot_cron_schedule(&SnapshotSchedule::BeforeDeploy), None);
+        assert_eq!(snapshot_cron_schedule(&SnapshotSchedule::Daily), Some("@daily"));
+        assert_eq!(snapshot_cron_schedule(&SnapshotSchedule::Weekly), Some("@weekly"));
+    }
+
+    #[test]
+    fn test_snapshot_cronjob_manifest() {
+        // setup:
+        let service_long_id = Uuid::new_v4();
+        let storage_long_id = Uuid::new_v4();
+        let storage = ServiceStorage {
+            long_id: storage_long_id,
+            storage_class: "aws-ebs-gp2-0",
+            volume_snapshot_class: Some("aws-ebs-snapshot-0"),
+            size_in_gib: 10,
+            snapshot_schedule: &SnapshotSchedule::Daily,
+            snapshot_retention_in_days: 7,
+            restore_from_snapshot: None,
+            pvc_selector: "appId=app-1,diskId=disk-1".to_string(),
+            first_pvc_name: "disk-1-app-1-0".to_string(),
+        };
+
+        // execute:
+        let manifest = snapshot_cronjob_manifest(&service_long_id, &storage, "@daily", "z1-z2");
+
+        // verify:
+        assert_eq!(manifest["metadata"]["name"], format!("snapshots-{}", storage_long_id));
+        assert_eq!(manifest["metadata"]["labels"][STORAGE_ID_LABEL], storage_long_id.to_string());
+        assert_eq!(manifest["spec"]["schedule"], "@daily");
+        let pod = &manifest["spec"]["jobTemplate"]["spec"]["template"];
+        assert!(pod["metadata"]["labels"].get("appId").is_none());
+        assert_eq!(pod["spec"]["serviceAccountName"], SCHEDULER_NAME);
+        let script = pod["spec"]["containers"][0]["command"][2].as_str().unwrap_or_default();
+        assert!(script.contains("kubectl get pvc -l 'appId=app-1,diskId=disk-1'"));
+        assert!(script.contains("volumeSnapshotClassName: aws-ebs-snapshot-0"));
+        assert!(script.contains("retention_in_days=7"));
+    }
+
+    #[test]
+    fn test_expired_snapshots() {
+        // setup:
+        let now = Utc.ymd(2022, 10, 1).and_hms(12, 0, 0);
+        let snapshots = vec![
+            ("old".to_string(), now - Duration::days(8)),
+            ("recent".to_string(), now - Duration::days(2)),
+        ];
+
+        // execute & verify:
+        assert_eq!(expired_snapshots(&snapshots, 7, now), vec!["old".to_string()]);
+        assert_eq!(expired_snapshots(&snapshots, 1, now).len(), 2);
+        assert!(expired_snapshots(&snapshots, 0, now).is_empty());
+    }
+
+    #[test]
+    fn test_selector_labels() {
+        assert_eq!(
+            selector_labels("appId=app-1,diskId=disk-1"),
+            BTreeMap::from([
+                ("appId".to_string(), "app-1".to_string()),
+                ("diskId".to_string(), "disk-1".to_string())
+            ])
+        );
+    }
+
+    #[test]
+    fn test_snapshot_class_driver() {
+        // setup:
+        let snapshot_class = |name: &str, driver: &str, is_default: bool| {
+            let mut class =
+                DynamicObject::new(name, &volume_snapshot_class_resource()).data(json!({ "driver": driver }));
+            if is_default {
+                class.metadata.annotations = Some(BTreeMap::from([(
+                    DEFAULT_SNAPSHOT_CLASS_ANNOTATION.to_string(),
+                    "true".to_string(),
+                )]));
+            }
+            class
+        };
+        let classes = vec![
+            snapshot_class("aws-ebs-snapshot-0", "ebs.csi.aws.com", false),
+            snapshot_class("default", "csi.scaleway.com", true),
+        ];
+
+        // execute & verify:
+        assert_eq!(
+            snapshot_class_driver(&classes, Some("aws-ebs-snapshot-0")),
+            Some("ebs.csi.aws.com".to_string())
+        );
+        assert_eq!(snapshot_class_driver(&classes, None), Some("csi.scaleway.com".to_string()));
+        assert_eq!(snapshot_class_driver(&classes, Some("missing")), None);
+        assert_eq!(snapshot_class_driver(&[], None), None);
+    }
+
+    #[test]
+    fn test_storage_class_provisioner() {
+        // setup:
+        let storage_class = |name: &str, provisioner: &str, is_default: bool| StorageClass {
+            metadata: ObjectMeta {
+                name: Some(name.to_string()),
+                annotations: match is_default {
+                    true => Some(BTreeMap::from([(
+                        DEFAULT_STORAGE_CLASS_ANNOTATION.to_string(),
+                        "true".to_string(),
+                    )])),
+                    false => None,
+                },
+                ..Default::default()
+            },
+            provisioner: provisioner.to_string(),
+            ..Default::default()
+        };
+        let classes = vec![
+            storage_class("aws-ebs-gp2-0", "kubernetes.io/aws-ebs", false),
+            storage_class("gp3", "ebs.csi.aws.com", true),
+        ];
+
+        // execute & verify:
+        assert_eq!(
+            storage_class_provisioner(&classes, "aws-ebs-gp2-0"),
+            Some("kubernetes.io/aws-ebs".to_string())
+        );
+        assert_eq!(storage_class_provisioner(&classes, ""), Some("ebs.csi.aws.com".to_string()));
+        assert_eq!(storage_class_provisioner(&classes, "missing"), None);
+    }
+}
@@ -0,0 +1,2 @@
+mod renderer;
+pub mod reporter;
@@ -0,0 +1,135 @@
+use crate::deployment_report::job::reporter::JobDeploymentReport;
+use crate::deployment_report::utils::{get_tera_instance, to_pods_render_context, PodRenderContext};
+use crate::utilities::to_short_id;
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct JobRunRenderContext {
+    pub name: String,
+    pub nb_active: i32,
+    pub nb_succeeded: i32,
+    pub nb_failed: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JobDeploymentRenderContext {
+    pub name: String,
+    pub job_type: String,
+    pub runs: Vec<JobRunRenderContext>,
+    pub nb_pods: usize,
+    pub pods_failing: Vec<PodRenderContext>,
+    pub pods_starting: Vec<PodRenderContext>,
+}
+
+const REPORT_TEMPLATE: &str = r#"
+┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+┃ {{ job_type }} {{ name }} deployment is in progress ⏳, below the current status:
+{%- for run in runs %}
+┃ 🏃 Run {{ run.name }} has {{ run.nb_active }} running, {{ run.nb_succeeded }} succeeded and {{ run.nb_failed }} failed pods
+{%- endfor %}
+┃
+{% set all_pods = pods_failing | concat(with=pods_starting) -%}
+┃ 🛰 {{ job_type }} has {{ nb_pods }} pods. {{ pods_starting | length }} starting and {{ pods_failing | length }} in error
+{%- for pod in all_pods %}
+┃  |__ Pod {{ pod.name }} is {{ pod.state | upper }} {{ pod.message }}
+{%- for event in pod.events %}
+┃     |__ {{ event.type_ | fmt_event_type }} {{ event.message }}
+{%- endfor -%}
+{%- endfor %}
+┃
+┃ ⛑ Need Help ? Please consult our FAQ to troubleshoot your deployment https://hub.qovery.com/docs/using-qovery/troubleshoot/ and visit the forum https://discuss.qovery.com/
+┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━"#;
+
+pub(super) fn render_job_deployment_report(
+    is_cron_job: bool,
+    deployment_info: &JobDeploymentReport,
+) -> Result<String, tera::Error> {
+    let (pods_starting, _, pods_failing) = to_pods_render_context(&deployment_info.pods, &deployment_info.events);
+    let runs = deployment_info
+        .jobs
+        .iter()
+        .map(|job| {
+            let status = job.status.as_ref();
+            JobRunRenderContext {
+                name: job.metadata.name.clone().unwrap_or_default(),
+                nb_active: status.and_then(|s| s.active).unwrap_or(0),
+                nb_succeeded: status.and_then(|s| s.succeeded).unwrap_or(0),
+                nb_failed: status.and_then(|s| s.failed).unwrap_or(0),
+            }
+        })
+        .collect();
+
+    let render_ctx = JobDeploymentRenderContext {
+        name: to_short_id(&deployment_info.id),
+        job_type: if is_cron_job { "Cron job" } else { "Job" }.to_string(),
+        runs,
+        nb_pods: deployment_info.pods.len(),
+        pods_failing,
+        pods_starting,
+    };
+    let ctx = tera::Context::from_serialize(render_ctx)?;
+    get_tera_instance().render_str(REPORT_TEMPLATE, &ctx)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::deployment_report::job::renderer::{JobDeploymentRenderContext, JobRunRenderContext, REPORT_TEMPLATE};
+    use crate::deployment_report::utils::{fmt_event_type, DeploymentState, EventRenderContext, PodRenderContext};
+    use tera::Tera;
+
+    #[test]
+    fn test_job_rendering() {
+        let render_ctx = JobDeploymentRenderContext {
+            name: "z85ba6759".to_string(),
+            job_type: "Job".to_string(),
+            runs: vec![JobRunRenderContext {
+                name: "job-z85ba6759".to_string(),
+                nb_active: 1,
+                nb_succeeded: 0,
+                nb_failed: 2,
+            }],
+            nb_pods: 3,
+            pods_failing: vec![PodRenderContext {
+                name: "job-z85ba6759-1".to_string(),
+                state: DeploymentState::Failing,
+                message: Some("container exited with code 1".to_string()),
+                restart_count: 0,
+                events: vec![],
+            }],
+            pods_starting: vec![PodRenderContext {
+                name: "job-z85ba6759-2".to_string(),
+                state: DeploymentState::Starting,
+                message: None,
+                restart_count: 0,
+                events: vec![EventRenderContext {
+                    message: "Pulling image".to_string(),
+                    type_: "Normal".to_string(),
+                }],
+            }],
+        };
+
+        let ctx = tera::Context::from_serialize(render_ctx).unwrap();
+        let mut tera = Tera::default();
+        tera.register_filter("fmt_event_type", fmt_event_type);
+
+        let rendered_report = tera.render_str(REPORT_TEMPLATE, &ctx).unwrap();
+
+        let gold_standard = r#"
+┏━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+┃ Job z85ba6759 deployment is in progress ⏳, below the current status:
+┃ 🏃 Run job-z85ba6759 has 1 running, 0 succeeded and 2 failed pods
+┃
+┃ 🛰 Job has 3 pods. 1 starting and 1 in error
+┃  |__ Pod job-z85ba6759-1 is FAILING container exited with code 1
+┃  |__ Pod job-z85ba6759-2 is STARTING
+┃     |__ ℹ️ Pulling image
+┃
+┃ ⛑ Need Help ? Please consult our FAQ to troubleshoot your deployment https://hub.qovery.com/docs/using-qovery/troubleshoot/ and visit the forum https://discuss.qovery.com/
+┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━"#;
+
+        assert_eq!(rendered_report.lines().count(), gold_standard.lines().count());
+        for (rendered_line, gold_line) in rendered_report.lines().zip(gold_standard.lines()) {
+            assert_eq!(rendered_line.trim_end(), gold_line);
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use crate::cloud_provider::service::Action;
+use crate::cloud_provider::DeploymentTarget;
+use crate::deployment_report::job::renderer::render_job_deployment_report;
+use crate::deployment_report::logger::{get_loggers, Loggers};
+use crate::deployment_report::DeploymentReporter;
+use crate::errors::EngineError;
+use crate::errors::Tag::HelmDeployTimeout;
+use crate::models::job::JobService;
+use crate::runtime::block_on;
+use crate::utilities::to_short_id;
+use k8s_openapi::api::batch::v1::Job;
+use k8s_openapi::api::core::v1::{Event, Pod};
+use kube::api::ListParams;
+use kube::Api;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+const MAX_ELASPED_TIME_WITHOUT_REPORT: Duration = Duration::from_secs(60 * 2);
+
+pub struct JobDeploymentReporter {
+    long_id: Uuid,
+    is_cron_job: bool,
+    action: Action,
+    namespace: String,
+    kube_client: kube::Client,
+    selector: String,
+    last_report: (String, Instant),
+    send_progress: Box<dyn Fn(String) + Send>,
+    send_success: Box<dyn Fn(String) + Send>,
+    send_error: Box<dyn Fn(EngineError) + Send>,
+}
+
+impl JobDeploymentReporter {
+    pub fn new(job: &impl JobService, deployment_target: &DeploymentTarget, action: Action) -> JobDeploymentReporter {
+        let Loggers {
+            send_progress,
+            send_success,
+            send_error,
+        } = get_loggers(job, action);
+
+        JobDeploymentReporter {
+            long_id: *job.long_id(),
+            is_cron_job: job.is_cron_job(),
+            action,
+            namespace: deployment_target.environment.namespace().to_string(),
+            kube_client: deployment_target.kube.clone(),
+            selector: job.selector().unwrap_or_default(),
+            last_report: ("".to_string(), Instant::now()),
+            send_progress,
+            send_success,
+            send_error,
+        }
+    }
+
+    fn job_type(&self) -> &str {
+        if self.is_cron_job {
+            "cron job"
+        } else {
+            "job"
+        }
+    }
+}
+
+impl DeploymentReporter for JobDeploymentReporter {
+    type DeploymentResult = Result<(), EngineError>;
+
+    fn before_deployment_start(&mut self) {
+        (self.send_progress)(format!(
+            "🚀 Deployment of {} `{}` is starting",
+            self.job_type(),
+            to_short_id(&self.long_id)
+        ));
+    }
+
+    fn deployment_in_progress(&mut self) {
+        // Fetch deployment information from kube api
+        let report = match block_on(fetch_job_deployment_report(
+            &self.kube_client,
+            &self.long_id,
+            &self.selector,
+            &self.namespace,
+        )) {
+            Ok(deployment_info) => deployment_info,
+            Err(err) => {
+                (self.send_progress)(format!("Error while retrieving deployment information: {}", err));
+                return;
+            }
+        };
+
+        // Format the deployment information and send to it to user
+        let rendered_report = match render_job_deployment_report(self.is_cron_job, &report) {
+            Ok(deployment_status_report) => deployment_status_report,
+            Err(err) => {
+                (self.send_progress)(format!("Cannot render deployment status report. Please contact us: {}", err));
+                return;
+            }
+        };
+
+        // don't spam log same report unless it has been too long time elapsed without one
+        if rendered_report == self.last_report.0 && self.last_report.1.elapsed() < MAX_ELASPED_TIME_WITHOUT_REPORT {
+            return;
+        }
+        self.last_report = (rendered_report, Instant::now());
+
+        // Send it to user
+        for line in self.last_report.0.trim_end().split('\n').map(str::to_string) {
+            (self.send_progress)(line);
+        }
+    }
+
+    fn deployment_terminated(&mut self, result: &Self::DeploymentResult) {
+        let error = match result {
+            Ok(_) if !self.is_cron_job && self.action == Action::Create => {
+                (self.send_success)("✅ Job has run successfully".to_string());
+                return;
+            }
+            Ok(_) => {
+                (self.send_success)(format!("✅ Deployment of {} succeeded", self.job_type()));
+                return;
+            }
+            Err(err) => err,
+        };
+
+        // helm waits for one-off jobs to complete, a timeout means the job has been too long to run
+        if error.tag() == &HelmDeployTimeout {
+            (self.send_error)(EngineError::new_engine_error(
+                error.clone(),
+                r#"
+❌ Job failed to complete in the given time frame.
+Look at the report from above to understand why, and check your job logs.
+
+⛑ Need Help ? Please consult our FAQ to troubleshoot your deployment https://hub.qovery.com/docs/using-qovery/troubleshoot/ and visit the forum https://discuss.qovery.com/
+                "#
+                .trim()
+                .to_string(),
+                None,
+            ));
+        } else {
+            (self.send_error)(error.clone());
+            (self.send_error)(EngineError::new_engine_error(
+                error.clone(),
+                format!(r#"
+❌ Deployment of {} failed ! Look at the report above and to understand why.
+⛑ Need Help ? Please consult our FAQ to troubleshoot your deployment https://hub.qovery.com/docs/using-qovery/troubleshoot/ and visit the forum https://discuss.qovery.com/
+                "#, self.job_type()).trim().to_string(),
+                None,
+            ));
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(super) struct JobDeploymentReport {
+    pub id: Uuid,
+    pub jobs: Vec<Job>,
+    pub pods: Vec<Pod>,
+    pub events: Vec<Event>,
+}
+
+async fn fetch_job_deployment_report(
+    kube: &kube::Client,
+    service_id: &Uuid,
+    selector: &str,
+    namespace: &str,
+) -> Result<JobDeploymentReport, kube::Error> {
+    let jobs_api: Api<Job> = Api::namespaced(kube.clone(), namespace);
+    let pods_api: Api<Pod> = Api::namespaced(kube.clone(), namespace);
+    let event_api: Api<Event> = Api::namespaced(kube.clone(), namespace);
+
+    let list_params = ListParams::default().labels(selector).timeout(15);
+    let jobs = jobs_api.list(&list_params);
+    let pods = pods_api.list(&list_params);
+    let events_params = ListParams::default().timeout(15);
+    let events = event_api.list(&events_params);
+    let (jobs, pods, events) = futures::future::try_join3(jobs, pods, events).await?;
+
+    Ok(JobDeploymentReport {
+        id: *service_id,
+        jobs: jobs.items,
+        pods: pods.items,
+        events: events.items,
+    })
+}
@@ -14,17 +14,20 @@ use crate::io_models::QoveryIdentifier;
 pub mod cloudflare;
 pub mod errors;
 pub mod io;
+pub mod noop;
 pub mod qoverydns;
 
 #[derive(Clone, Debug)]
 pub enum Kind {
     Cloudflare,
     QoveryDns,
+    NoOp,
 }
 
 pub enum DnsProviderConfiguration {
     Cloudflare(CloudflareDnsConfig),
     QoveryDns(QoveryDnsConfig),
+    NoOp,
 }
 
 impl DnsProviderConfiguration {
@@ -32,12 +35,13 @@ impl DnsProviderConfiguration {
         match self {
             DnsProviderConfiguration::Cloudflare(_) => "cloudflare",
             DnsProviderConfiguration::QoveryDns(_) => "pdns",
+            DnsProviderConfiguration::NoOp => "none",
         }
         .to_string()
     }
 }
 
-pub trait DnsProvider {
+pub trait DnsProvider: Send + Sync {
     fn context(&self) -> &Context;
     fn provider_name(&self) -> &str;
     fn kind(&self) -> Kind;
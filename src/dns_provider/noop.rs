@@ -0,0 +1,71 @@
+use std::net::Ipv4Addr;
+use tera::Context as TeraContext;
+use uuid::Uuid;
+
+use crate::dns_provider::errors::DnsProviderError;
+use crate::dns_provider::{DnsProvider, DnsProviderConfiguration, Kind};
+use crate::io_models::context::Context;
+use crate::io_models::domain::Domain;
+
+/// DNS provider which doesn't manage any record.
+/// Useful for self-managed clusters where domains are resolved by the user (i.e: /etc/hosts, nip.io).
+pub struct NoOpDns {
+    context: Context,
+    long_id: Uuid,
+    name: String,
+    domain: Domain,
+}
+
+impl NoOpDns {
+    pub fn new(context: Context, long_id: Uuid, name: &str, domain: Domain) -> Self {
+        NoOpDns {
+            context,
+            long_id,
+            name: name.to_string(),
+            domain,
+        }
+    }
+}
+
+impl DnsProvider for NoOpDns {
+    fn context(&self) -> &Context {
+        &self.context
+    }
+
+    fn provider_name(&self) -> &str {
+        "noop"
+    }
+
+    fn kind(&self) -> Kind {
+        Kind::NoOp
+    }
+
+    fn long_id(&self) -> &Uuid {
+        &self.long_id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn insert_into_teracontext<'a>(&self, context: &'a mut TeraContext) -> &'a mut TeraContext {
+        context.insert("external_dns_provider", &self.provider_name());
+        context
+    }
+
+    fn provider_configuration(&self) -> DnsProviderConfiguration {
+        DnsProviderConfiguration::NoOp
+    }
+
+    fn domain(&self) -> &Domain {
+        &self.domain
+    }
+
+    fn resolvers(&self) -> Vec<Ipv4Addr> {
+        vec![Ipv4Addr::new(1, 1, 1, 1), Ipv4Addr::new(8, 8, 8, 8)]
+    }
+
+    fn is_valid(&self) -> Result<(), DnsProviderError> {
+        Ok(())
+    }
+}
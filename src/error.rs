@@ -48,6 +48,7 @@ pub enum EngineErrorScope {
     Database(Id, Type, Name),
     Application(Id, Name, Version),
     Container(Id, Name, Version),
+    Job(Id, Name, Version),
     Router(Id, Name),
     SecretManager(Name),
 }
@@ -68,6 +69,7 @@ impl From<Transmitter> for EngineErrorScope {
             Transmitter::Router(id, name) => EngineErrorScope::Router(id, name),
             Transmitter::SecretManager(name) => EngineErrorScope::SecretManager(name),
             Transmitter::Container(id, name, version) => EngineErrorScope::Container(id, name, version),
+            Transmitter::Job(id, name, version) => EngineErrorScope::Job(id, name, version),
         }
     }
 }
@@ -28,6 +28,8 @@ pub enum Tag {
     ClusterWorkerNodeNotFound,
     CannotGetWorkspaceDirectory,
     UnsupportedInstanceType,
+    UnsupportedNodeGroupCapacityType,
+    NodeGroupReservedLabel,
     CannotRetrieveClusterConfigFile,
     CannotCreateFile,
     CannotGetClusterNodes,
@@ -41,11 +43,23 @@ pub enum Tag {
     CannotDetermineK8sKubeProxyVersion,
     CannotExecuteK8sApiCustomMetrics,
     K8sPodDisruptionBudgetInInvalidState,
+    K8sCannotPlanUpgrade,
+    K8sUpgradeBlocked,
     K8sPodsDisruptionBudgetCannotBeRetrieved,
     K8sCannotDeletePod,
     K8sCannotDeletePvc,
+    K8sCannotCreateVolumeSnapshot,
+    K8sCannotRestoreVolumeSnapshot,
+    K8sCannotScheduleVolumeSnapshots,
+    K8sVolumeSnapshotsNotSupported,
+    InvalidHpaConfiguration,
+    HpaMetricNotServed,
     K8sCannotGetCrashLoopingPods,
     K8sCannotDeleteCompletedJobs,
+    K8sJobFailed,
+    K8sCannotSuspendCronJob,
+    K8sPromotionGateFailed,
+    K8sCannotApplyCanaryIngress,
     K8sCannotGetPods,
     K8sUpgradeDeployedVsRequestedVersionsInconsistency,
     K8sScaleReplicas,
@@ -56,6 +70,7 @@ pub enum Tag {
     K8sDescribe,
     K8sHistory,
     K8sCannotCreateNamespace,
+    K8sCannotConfigureScaleToZero,
     K8sPodIsNotReady,
     K8sNodeIsNotReadyWithTheRequestedVersion,
     K8sNodeIsNotReady,
@@ -90,7 +105,26 @@ pub enum Tag {
     ClientServiceFailedToStart,
     ClientServiceFailedToDeployBeforeStart,
     DatabaseFailedToStartAfterSeveralRetries,
+    DatabaseBackupsNotSupported,
+    DatabaseCannotScheduleBackups,
+    DatabaseCannotRestoreBackup,
+    DatabaseVersionUpgradeNotSupported,
+    DatabaseCannotUpgradeVersion,
+    DatabaseCannotSetupReadReplicas,
+    DatabaseCannotSetupConnectionPooling,
     RouterFailedToDeploy,
+    RouterInvalidRoute,
+    RouterCertificateNotIssued,
+    RouterCertificateDnsMismatch,
+    RouterCertificateRateLimited,
+    RouterCertificateAcmeChallengeFailed,
+    RouterCertificateAcmeChallengePending,
+    RouterCertificateOrderFailed,
+    RouterInvalidCustomCertificate,
+    RouterCannotApplyCustomCertificate,
+    EnvironmentServicesDependencyCycle,
+    EnvironmentCannotBeCloned,
+    EnvironmentVariableSecretCannotBeResolved,
     CloudProviderClientInvalidCredentials,
     VersionNumberParsingError,
     NotImplementedError,
@@ -133,11 +167,14 @@ pub enum Tag {
     ObjectStorageCannotCreateBucket,
     ObjectStorageCannotPutFileIntoBucket,
     ObjectStorageCannotDeleteFileIntoBucket,
+    ObjectStorageCannotListObjects,
+    ObjectStorageCannotManageAccessCredentials,
     KubeconfigFileDoNotPermitToConnectToK8sCluster,
     KubeconfigSecurityCheckError,
     DeleteLocalKubeconfigFileError,
     VaultConnectionError,
     VaultSecretCouldNotBeRetrieved,
+    SecretManagerError,
     VaultSecretCouldNotBeCreatedOrUpdated,
     VaultSecretCouldNotBeDeleted,
     JsonDeserializationError,
@@ -187,6 +224,8 @@ impl From<errors::Tag> for Tag {
             errors::Tag::TerraformAccountBlockedByProvider => Tag::TerraformAccountBlockedByProvider,
             errors::Tag::InvalidEngineApiInputCannotBeDeserialized => Tag::InvalidEngineApiInputCannotBeDeserialized,
             errors::Tag::UnsupportedInstanceType => Tag::UnsupportedInstanceType,
+            errors::Tag::UnsupportedNodeGroupCapacityType => Tag::UnsupportedNodeGroupCapacityType,
+            errors::Tag::NodeGroupReservedLabel => Tag::NodeGroupReservedLabel,
             errors::Tag::CannotRetrieveClusterConfigFile => Tag::CannotRetrieveClusterConfigFile,
             errors::Tag::CannotCreateFile => Tag::CannotCreateFile,
             errors::Tag::CannotGetClusterNodes => Tag::CannotGetClusterNodes,
@@ -204,10 +243,16 @@ impl From<errors::Tag> for Tag {
             errors::Tag::CannotDetermineK8sKubeProxyVersion => Tag::CannotDetermineK8sKubeProxyVersion,
             errors::Tag::CannotExecuteK8sApiCustomMetrics => Tag::CannotExecuteK8sApiCustomMetrics,
             errors::Tag::K8sPodDisruptionBudgetInInvalidState => Tag::K8sPodDisruptionBudgetInInvalidState,
+            errors::Tag::K8sCannotPlanUpgrade => Tag::K8sCannotPlanUpgrade,
+            errors::Tag::K8sUpgradeBlocked => Tag::K8sUpgradeBlocked,
             errors::Tag::K8sPodsDisruptionBudgetCannotBeRetrieved => Tag::K8sPodsDisruptionBudgetCannotBeRetrieved,
             errors::Tag::K8sCannotDeletePod => Tag::K8sCannotDeletePod,
             errors::Tag::K8sCannotGetCrashLoopingPods => Tag::K8sCannotGetCrashLoopingPods,
             errors::Tag::K8sCannotDeleteCompletedJobs => Tag::K8sCannotDeleteCompletedJobs,
+            errors::Tag::K8sJobFailed => Tag::K8sJobFailed,
+            errors::Tag::K8sCannotSuspendCronJob => Tag::K8sCannotSuspendCronJob,
+            errors::Tag::K8sPromotionGateFailed => Tag::K8sPromotionGateFailed,
+            errors::Tag::K8sCannotApplyCanaryIngress => Tag::K8sCannotApplyCanaryIngress,
             errors::Tag::K8sCannotGetPods => Tag::K8sCannotGetPods,
             errors::Tag::K8sUpgradeDeployedVsRequestedVersionsInconsistency => {
                 Tag::K8sUpgradeDeployedVsRequestedVersionsInconsistency
@@ -220,6 +265,7 @@ impl From<errors::Tag> for Tag {
             errors::Tag::K8sDescribe => Tag::K8sDescribe,
             errors::Tag::K8sHistory => Tag::K8sHistory,
             errors::Tag::K8sCannotCreateNamespace => Tag::K8sCannotCreateNamespace,
+            errors::Tag::K8sCannotConfigureScaleToZero => Tag::K8sCannotConfigureScaleToZero,
             errors::Tag::K8sPodIsNotReady => Tag::K8sPodIsNotReady,
             errors::Tag::CannotFindRequiredBinary => Tag::CannotFindRequiredBinary,
             errors::Tag::SubnetsCountShouldBeEven => Tag::SubnetsCountShouldBeEven,
@@ -255,7 +301,26 @@ impl From<errors::Tag> for Tag {
             errors::Tag::ClientServiceFailedToStart => Tag::ClientServiceFailedToStart,
             errors::Tag::ClientServiceFailedToDeployBeforeStart => Tag::ClientServiceFailedToDeployBeforeStart,
             errors::Tag::DatabaseFailedToStartAfterSeveralRetries => Tag::DatabaseFailedToStartAfterSeveralRetries,
+            errors::Tag::DatabaseBackupsNotSupported => Tag::DatabaseBackupsNotSupported,
+            errors::Tag::DatabaseCannotScheduleBackups => Tag::DatabaseCannotScheduleBackups,
+            errors::Tag::DatabaseCannotRestoreBackup => Tag::DatabaseCannotRestoreBackup,
+            errors::Tag::DatabaseVersionUpgradeNotSupported => Tag::DatabaseVersionUpgradeNotSupported,
+            errors::Tag::DatabaseCannotUpgradeVersion => Tag::DatabaseCannotUpgradeVersion,
+            errors::Tag::DatabaseCannotSetupReadReplicas => Tag::DatabaseCannotSetupReadReplicas,
+            errors::Tag::DatabaseCannotSetupConnectionPooling => Tag::DatabaseCannotSetupConnectionPooling,
             errors::Tag::RouterFailedToDeploy => Tag::RouterFailedToDeploy,
+            errors::Tag::RouterInvalidRoute => Tag::RouterInvalidRoute,
+            errors::Tag::RouterCertificateNotIssued => Tag::RouterCertificateNotIssued,
+            errors::Tag::RouterCertificateDnsMismatch => Tag::RouterCertificateDnsMismatch,
+            errors::Tag::RouterCertificateRateLimited => Tag::RouterCertificateRateLimited,
+            errors::Tag::RouterCertificateAcmeChallengeFailed => Tag::RouterCertificateAcmeChallengeFailed,
+            errors::Tag::RouterCertificateAcmeChallengePending => Tag::RouterCertificateAcmeChallengePending,
+            errors::Tag::RouterCertificateOrderFailed => Tag::RouterCertificateOrderFailed,
+            errors::Tag::RouterInvalidCustomCertificate => Tag::RouterInvalidCustomCertificate,
+            errors::Tag::RouterCannotApplyCustomCertificate => Tag::RouterCannotApplyCustomCertificate,
+            errors::Tag::EnvironmentServicesDependencyCycle => Tag::EnvironmentServicesDependencyCycle,
+            errors::Tag::EnvironmentCannotBeCloned => Tag::EnvironmentCannotBeCloned,
+            errors::Tag::EnvironmentVariableSecretCannotBeResolved => Tag::EnvironmentVariableSecretCannotBeResolved,
             errors::Tag::CloudProviderClientInvalidCredentials => Tag::CloudProviderClientInvalidCredentials,
             errors::Tag::VersionNumberParsingError => Tag::VersionNumberParsingError,
             errors::Tag::NotImplementedError => Tag::NotImplementedError,
@@ -313,11 +378,14 @@ impl From<errors::Tag> for Tag {
             errors::Tag::DeleteLocalKubeconfigFileError => Tag::DeleteLocalKubeconfigFileError,
             errors::Tag::VaultConnectionError => Tag::VaultConnectionError,
             errors::Tag::VaultSecretCouldNotBeRetrieved => Tag::VaultSecretCouldNotBeRetrieved,
+            errors::Tag::SecretManagerError => Tag::SecretManagerError,
             errors::Tag::VaultSecretCouldNotBeCreatedOrUpdated => Tag::VaultSecretCouldNotBeCreatedOrUpdated,
             errors::Tag::JsonDeserializationError => Tag::JsonDeserializationError,
             errors::Tag::ClusterSecretsManipulationError => Tag::ClusterSecretsManipulationError,
             errors::Tag::VaultSecretCouldNotBeDeleted => Tag::VaultSecretCouldNotBeDeleted,
             errors::Tag::ObjectStorageCannotDeleteFileIntoBucket => Tag::ObjectStorageCannotDeleteFileIntoBucket,
+            errors::Tag::ObjectStorageCannotListObjects => Tag::ObjectStorageCannotListObjects,
+            errors::Tag::ObjectStorageCannotManageAccessCredentials => Tag::ObjectStorageCannotManageAccessCredentials,
             errors::Tag::CannotGetNodeGroupList => Tag::CannotGetNodeGroupList,
             errors::Tag::CannotGetNodeGroupInfo => Tag::CannotGetNodeGroupInfo,
             errors::Tag::NumberOfRequestedMaxNodesIsBelowThanCurrentUsage => {
@@ -356,6 +424,12 @@ impl From<errors::Tag> for Tag {
             errors::Tag::CloudProviderGetLoadBalancer => Tag::CloudProviderGetLoadBalancer,
             errors::Tag::CloudProviderGetLoadBalancerTags => Tag::CloudProviderGetLoadBalancerTags,
             errors::Tag::K8sCannotDeletePvc => Tag::K8sCannotDeletePvc,
+            errors::Tag::K8sCannotCreateVolumeSnapshot => Tag::K8sCannotCreateVolumeSnapshot,
+            errors::Tag::K8sCannotRestoreVolumeSnapshot => Tag::K8sCannotRestoreVolumeSnapshot,
+            errors::Tag::K8sCannotScheduleVolumeSnapshots => Tag::K8sCannotScheduleVolumeSnapshots,
+            errors::Tag::K8sVolumeSnapshotsNotSupported => Tag::K8sVolumeSnapshotsNotSupported,
+            errors::Tag::InvalidHpaConfiguration => Tag::InvalidHpaConfiguration,
+            errors::Tag::HpaMetricNotServed => Tag::HpaMetricNotServed,
             errors::Tag::CloudProviderDeleteLoadBalancer => Tag::CloudProviderDeleteLoadBalancer,
             errors::Tag::InvalidEnginePayload => Tag::InvalidEnginePayload,
         }
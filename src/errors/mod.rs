@@ -4,12 +4,14 @@ extern crate derivative;
 extern crate url;
 
 use crate::build_platform::BuildError;
+use crate::cloud_provider::models::NodeGroupCapacityType;
 use crate::cloud_provider::Kind;
 use crate::cmd;
 use crate::cmd::docker::DockerError;
 use crate::cmd::helm::HelmError;
 use crate::cmd::terraform::{QuotaExceededError, TerraformError};
 use crate::container_registry::errors::ContainerRegistryError;
+use crate::deployment_action::check_certificate::CertificateIssue;
 
 use crate::error::{EngineError as LegacyEngineError, EngineErrorCause, EngineErrorScope};
 use crate::events::{EventDetails, Stage};
@@ -21,6 +23,7 @@ use std::fmt::{Display, Formatter};
 use std::io::Error;
 use thiserror::Error;
 use url::Url;
+use uuid::Uuid;
 
 const DEFAULT_HINT_MESSAGE: &str = "Need Help ? Please consult our FAQ to troubleshoot your deployment https://hub.qovery.com/docs/using-qovery/troubleshoot/ and visit the forum https://discuss.qovery.com/";
 
@@ -226,6 +229,25 @@ impl From<ObjectStorageError> for CommandError {
                 Some(raw_error_message),
                 None,
             ),
+            ObjectStorageError::CannotListObjects {
+                bucket_name,
+                raw_error_message,
+            } => CommandError::new(
+                format!("Object storage error, cannot list objects of bucket: `{}`", bucket_name),
+                Some(raw_error_message),
+                None,
+            ),
+            ObjectStorageError::CannotManageAccessCredentials {
+                bucket_name,
+                raw_error_message,
+            } => CommandError::new(
+                format!(
+                    "Object storage error, cannot manage access credentials of bucket: `{}`",
+                    bucket_name
+                ),
+                Some(raw_error_message),
+                None,
+            ),
             ObjectStorageError::CannotDeleteFile {
                 bucket_name,
                 file_name,
@@ -448,6 +470,27 @@ impl From<BuildError> for CommandError {
                 Some(raw_error.to_string()),
                 None,
             ),
+            BuildError::BuildKitError { application, raw_error } => CommandError::new(
+                format!(
+                    "Build error, cannot build application `{}` due to a BuildKit error",
+                    application
+                ),
+                Some(raw_error.to_string()),
+                None,
+            ),
+            BuildError::BuildahError { application, raw_error } => CommandError::new(
+                format!("Build error, cannot build application `{}` due to a buildah error", application),
+                Some(raw_error.to_string()),
+                None,
+            ),
+            BuildError::KanikoError {
+                application,
+                raw_error_message,
+            } => CommandError::new(
+                format!("Build error, cannot build application `{}` due to a Kaniko error", application),
+                Some(raw_error_message),
+                None,
+            ),
         }
     }
 }
@@ -512,6 +555,10 @@ pub enum Tag {
     CannotGetWorkspaceDirectory,
     /// UnsupportedInstanceType: represents an unsupported instance type for the given cloud provider.
     UnsupportedInstanceType,
+    /// UnsupportedNodeGroupCapacityType: represents a node group capacity type (spot, on demand) not supported by the cloud provider.
+    UnsupportedNodeGroupCapacityType,
+    /// NodeGroupReservedLabel: represents a node group label using a key reserved to Qovery.
+    NodeGroupReservedLabel,
     /// NotAllowedInstanceType: represents not allowed instance type for a specific kind of cluster
     NotAllowedInstanceType,
     /// UnsupportedClusterKind: represents an unsupported cluster kind by Qovery.
@@ -564,15 +611,39 @@ pub enum Tag {
     K8sCannotReachToApi,
     /// K8sPodDisruptionBudgetInInvalidState: represents an error where pod disruption budget is in an invalid state.
     K8sPodDisruptionBudgetInInvalidState,
+    /// K8sCannotPlanUpgrade: represents an error while planning a kubernetes upgrade.
+    K8sCannotPlanUpgrade,
+    /// K8sUpgradeBlocked: represents an error where some cluster objects would break with the requested kubernetes version.
+    K8sUpgradeBlocked,
     /// K8sPodDisruptionBudgetCqnnotBeRetrieved: represents an error where pod disruption budget cannot be retrieved.
     K8sPodsDisruptionBudgetCannotBeRetrieved,
     /// K8sCannotDeletePod: represents an error where we are not able to delete a pod.
     K8sCannotDeletePod,
     K8sCannotDeletePvc,
+    /// K8sCannotCreateVolumeSnapshot: represents an error where we are not able to snapshot the volume of a pvc.
+    K8sCannotCreateVolumeSnapshot,
+    /// K8sCannotRestoreVolumeSnapshot: represents an error where we are not able to restore pvcs from a volume snapshot.
+    K8sCannotRestoreVolumeSnapshot,
+    /// K8sCannotScheduleVolumeSnapshots: represents an error where we are not able to create the CronJobs snapshotting the volumes of a service.
+    K8sCannotScheduleVolumeSnapshots,
+    /// K8sVolumeSnapshotsNotSupported: represents an error where volume snapshots are requested on a cluster which cannot take them.
+    K8sVolumeSnapshotsNotSupported,
+    /// InvalidHpaConfiguration: represents an error where the pod autoscaler settings of a service are invalid.
+    InvalidHpaConfiguration,
+    /// HpaMetricNotServed: represents an error where a metric the pod autoscaler relies on is not served by the cluster.
+    HpaMetricNotServed,
     /// K8sCannotGetCrashLoopingPods: represents an error where we are not able to get crash looping pods.
     K8sCannotGetCrashLoopingPods,
     /// K8sCannotDeleteCompletedJobs: represents an error where we are not able to delete completed jobs.
     K8sCannotDeleteCompletedJobs,
+    /// K8sJobFailed: represents an error where a job has failed or has not completed in time.
+    K8sJobFailed,
+    /// K8sCannotSuspendCronJob: represents an error where we are not able to suspend a cron job.
+    K8sCannotSuspendCronJob,
+    /// K8sPromotionGateFailed: represents an error where the preview of a new version has not passed its promotion gate.
+    K8sPromotionGateFailed,
+    /// K8sCannotApplyCanaryIngress: represents an error where we are not able to route traffic to a preview.
+    K8sCannotApplyCanaryIngress,
     /// K8sCannotGetPods: represents an error where we are not able to get pods.
     K8sCannotGetPods,
     /// K8sUpgradeDeployedVsRequestedVersionsInconsistency: represents an error where there is a K8s versions inconsistency between deployed and requested.
@@ -593,6 +664,8 @@ pub enum Tag {
     K8sHistory,
     /// K8sCannotCreateNamespace: represents an error while trying to create a k8s namespace.
     K8sCannotCreateNamespace,
+    /// K8sCannotConfigureScaleToZero: represents an error while trying to configure the scale-to-zero of a k8s namespace.
+    K8sCannotConfigureScaleToZero,
     /// K8sPodIsNotReady: represents an error where the given pod is not ready.
     K8sPodIsNotReady,
     /// K8sNodeIsNotReadyInTheGivenVersion: represents an error where the given node is not ready in the given version.
@@ -692,8 +765,46 @@ pub enum Tag {
     ClientServiceFailedToDeployBeforeStart,
     /// DatabaseFailedToStartAfterSeveralRetries: represents an error while trying to start a database after several retries.
     DatabaseFailedToStartAfterSeveralRetries,
+    /// DatabaseBackupsNotSupported: represents an error where backups cannot be managed by Qovery for a database.
+    DatabaseBackupsNotSupported,
+    /// DatabaseCannotScheduleBackups: represents an error while trying to schedule backups of a database.
+    DatabaseCannotScheduleBackups,
+    /// DatabaseCannotRestoreBackup: represents an error while trying to restore a database from a backup.
+    DatabaseCannotRestoreBackup,
+    /// DatabaseVersionUpgradeNotSupported: represents an error where a database cannot be upgraded to the requested version.
+    DatabaseVersionUpgradeNotSupported,
+    /// DatabaseCannotUpgradeVersion: represents an error while trying to upgrade a database to a new version.
+    DatabaseCannotUpgradeVersion,
+    /// DatabaseCannotSetupReadReplicas: represents an error while trying to add read replicas to a database.
+    DatabaseCannotSetupReadReplicas,
+    /// DatabaseCannotSetupConnectionPooling: represents an error while trying to deploy the connection pooler of a database.
+    DatabaseCannotSetupConnectionPooling,
     /// RouterFailedToDeploy: represents an error while trying to deploy a router.
     RouterFailedToDeploy,
+    /// RouterInvalidRoute: represents an error where a route of a router cannot be exposed.
+    RouterInvalidRoute,
+    /// RouterCertificateNotIssued: represents an error where the TLS certificate of custom domains has not been issued in time.
+    RouterCertificateNotIssued,
+    /// RouterCertificateDnsMismatch: represents an error where a custom domain does not point to the cluster, so its TLS certificate cannot be issued.
+    RouterCertificateDnsMismatch,
+    /// RouterCertificateRateLimited: represents an error where Let's Encrypt refuses to issue the TLS certificate of custom domains for now.
+    RouterCertificateRateLimited,
+    /// RouterCertificateAcmeChallengeFailed: represents an error where Let's Encrypt has not been able to validate the HTTP-01 challenge of a custom domain.
+    RouterCertificateAcmeChallengeFailed,
+    /// RouterCertificateAcmeChallengePending: represents an error where the HTTP-01 challenge of a custom domain has not been validated yet.
+    RouterCertificateAcmeChallengePending,
+    /// RouterCertificateOrderFailed: represents an error where the request of the TLS certificate of custom domains has failed.
+    RouterCertificateOrderFailed,
+    /// RouterInvalidCustomCertificate: represents an error where the TLS certificate supplied for a custom domain cannot be used.
+    RouterInvalidCustomCertificate,
+    /// RouterCannotApplyCustomCertificate: represents an error while trying to store the TLS certificate of a custom domain into the cluster.
+    RouterCannotApplyCustomCertificate,
+    /// EnvironmentServicesDependencyCycle: represents an error where services of an environment depend on each other.
+    EnvironmentServicesDependencyCycle,
+    /// EnvironmentCannotBeCloned: represents an error while trying to copy the data of an environment into its clone.
+    EnvironmentCannotBeCloned,
+    /// EnvironmentVariableSecretCannotBeResolved: represents an error while trying to read the value of an environment variable from its secret manager.
+    EnvironmentVariableSecretCannotBeResolved,
     /// CloudProviderInformationError: represents an error when checking cloud provider information provided.
     CloudProviderInformationError,
     /// CloudProviderClientInvalidCredentials: represents an error where client credentials for a cloud providers appear to be invalid.
@@ -774,6 +885,8 @@ pub enum Tag {
     VaultConnectionError,
     /// VaultSecretCouldNotBeRetrieved: represents an error to get the desired secret
     VaultSecretCouldNotBeRetrieved,
+    /// SecretManagerError: represents an error while reading or writing a secret from the configured secret manager
+    SecretManagerError,
     /// VaultSecretCouldNotBeCreatedOrUpdated: represent a vault secret creation or update error
     VaultSecretCouldNotBeCreatedOrUpdated,
     /// VaultSecretCouldNotBeDeleted, represent a vault secret deletion error
@@ -796,6 +909,11 @@ pub enum Tag {
     ObjectStorageCannotDeleteFileIntoBucket,
     /// ObjectStorageCannotDeleteBucket: represents an error while trying to delete a bucket.
     ObjectStorageCannotDeleteBucket,
+    /// ObjectStorageCannotListObjects: represents an error while trying to list the objects of a bucket.
+    ObjectStorageCannotListObjects,
+    /// ObjectStorageCannotManageAccessCredentials: represents an error while trying to issue or revoke credentials
+    /// restricted to a bucket.
+    ObjectStorageCannotManageAccessCredentials,
     /// ObjectStorageCannotActivateBucketVersioning: represents an error while trying to activate bucket versioning for bucket.
     ObjectStorageCannotActivateBucketVersioning,
     /// ObjectStorageQuotaExceeded: represents an error, quotas has been exceeded.
@@ -1176,6 +1294,65 @@ impl EngineError {
         )
     }
 
+    /// Creates new error for unsupported node group capacity type.
+    ///
+    /// Cloud provider doesn't offer the requested capacity type, for instance spot instances.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `node_group_name`: Name of the node group requesting the capacity type.
+    /// * `requested_capacity_type`: Requested capacity type.
+    pub fn new_unsupported_node_group_capacity_type(
+        event_details: EventDetails,
+        node_group_name: &str,
+        requested_capacity_type: NodeGroupCapacityType,
+    ) -> EngineError {
+        let message = format!(
+            "`{}` capacity type of node group `{}` is not supported",
+            requested_capacity_type, node_group_name
+        );
+        EngineError::new(
+            event_details,
+            Tag::UnsupportedNodeGroupCapacityType,
+            message,
+            None,
+            None,
+            Some(
+                "Selected capacity type is not supported by this cloud provider, please use on demand instances."
+                    .to_string(),
+            ),
+        )
+    }
+
+    /// Creates new error for a node group label using a reserved key.
+    ///
+    /// `qovery.com/*` label keys are set by Qovery on every node, users can't override them.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `node_group_name`: Name of the node group declaring the label.
+    /// * `label_key`: Reserved label key.
+    pub fn new_node_group_reserved_label(
+        event_details: EventDetails,
+        node_group_name: &str,
+        label_key: &str,
+    ) -> EngineError {
+        let message = format!(
+            "`{}` label of node group `{}` uses a key reserved to Qovery",
+            label_key, node_group_name
+        );
+        EngineError::new(
+            event_details,
+            Tag::NodeGroupReservedLabel,
+            message,
+            None,
+            None,
+            Some("Node group labels can't start with `qovery.com/`, please rename this label.".to_string()),
+        )
+    }
+
     /// Creates new error for unsupported cluster kind.
     ///
     /// Qovery doesn't support this kind of clusters.
@@ -1727,6 +1904,28 @@ impl EngineError {
         )
     }
 
+    /// Creates new error for kubernetes pods disruption budgets not getting healthy between upgrade stages.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `raw_k8s_error`: Raw error message.
+    pub fn new_k8s_pod_disruption_budgets_not_healthy(
+        event_details: EventDetails,
+        raw_k8s_error: CommandError,
+    ) -> EngineError {
+        let message = "Unable to continue Kubernetes upgrade, pods disruptions budgets are not healthy.";
+
+        EngineError::new(
+            event_details,
+            Tag::K8sPodDisruptionBudgetInInvalidState,
+            message.to_string(),
+            Some(raw_k8s_error),
+            None,
+            Some("Upgraded node groups are kept, the upgrade will resume from the next node group.".to_string()),
+        )
+    }
+
     /// Creates new error for kubernetes not being able to retrieve pods disruption budget.
     ///
     /// Arguments:
@@ -1749,6 +1948,48 @@ impl EngineError {
         )
     }
 
+    /// Creates new error for kubernetes upgrade not being able to be planned.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `raw_k8s_error`: Raw error message.
+    pub fn new_k8s_cannot_plan_upgrade(event_details: EventDetails, raw_k8s_error: CommandError) -> EngineError {
+        let message = "Unable to upgrade Kubernetes, can't check cluster objects against the requested version.";
+
+        EngineError::new(
+            event_details,
+            Tag::K8sCannotPlanUpgrade,
+            message.to_string(),
+            Some(raw_k8s_error),
+            None,
+            None,
+        )
+    }
+
+    /// Creates new error for kubernetes upgrade blocked by cluster objects.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `blockers`: Description of the objects blocking the upgrade.
+    pub fn new_k8s_upgrade_blocked(event_details: EventDetails, blockers: Vec<String>) -> EngineError {
+        let message = format!(
+            "Unable to upgrade Kubernetes, {} item(s) must be fixed first: {}",
+            blockers.len(),
+            blockers.join(", ")
+        );
+
+        EngineError::new(
+            event_details,
+            Tag::K8sUpgradeBlocked,
+            message,
+            None,
+            None,
+            Some("Please migrate those objects to the supported API versions, and relax disruption budgets preventing nodes to be drained.".to_string()),
+        )
+    }
+
     /// Creates new error for kubernetes not being able to delete a pod.
     ///
     /// Arguments:
@@ -1782,215 +2023,460 @@ impl EngineError {
         EngineError::new(event_details, Tag::K8sCannotDeletePvc, message, Some(raw_k8s_error), None, None)
     }
 
-    /// Creates new error for kubernetes not being able to get crash looping pods.
+    /// Creates new error for kubernetes not being able to snapshot the volume of a pvc.
     ///
     /// Arguments:
     ///
     /// * `event_details`: Error linked event details.
+    /// * `pvc_name`: Pvc's name.
     /// * `raw_k8s_error`: Raw error message.
-    pub fn new_k8s_cannot_get_crash_looping_pods(
+    pub fn new_k8s_cannot_create_volume_snapshot(
         event_details: EventDetails,
+        pvc_name: String,
         raw_k8s_error: CommandError,
     ) -> EngineError {
-        let message = "Unable to get crash looping Kubernetes pods.";
+        let message = format!("Unable to create a snapshot of Kubernetes pvc `{}`.", pvc_name);
 
         EngineError::new(
             event_details,
-            Tag::K8sCannotGetCrashLoopingPods,
-            message.to_string(),
+            Tag::K8sCannotCreateVolumeSnapshot,
+            message,
             Some(raw_k8s_error),
             None,
-            None,
+            Some("Please check that the CSI snapshot controller and the VolumeSnapshotClass of the cluster are installed.".to_string()),
         )
     }
 
-    /// Creates new error for kubernetes not being able to delete completed jobs.
+    /// Creates new error for kubernetes not being able to restore pvcs from a volume snapshot.
     ///
     /// Arguments:
     ///
     /// * `event_details`: Error linked event details.
+    /// * `snapshot_name`: Volume snapshot's name.
     /// * `raw_k8s_error`: Raw error message.
-    pub fn new_k8s_cannot_delete_completed_jobs(
+    pub fn new_k8s_cannot_restore_volume_snapshot(
         event_details: EventDetails,
+        snapshot_name: String,
         raw_k8s_error: CommandError,
     ) -> EngineError {
-        let message = "Unable to delete completed Kubernetes jobs.";
+        let message = format!("Unable to restore volumes from Kubernetes volume snapshot `{}`.", snapshot_name);
 
         EngineError::new(
             event_details,
-            Tag::K8sCannotDeleteCompletedJobs,
-            message.to_string(),
+            Tag::K8sCannotRestoreVolumeSnapshot,
+            message,
             Some(raw_k8s_error),
             None,
             None,
         )
     }
 
-    /// Creates new error for kubernetes not being able to get pods.
+    /// Creates new error for kubernetes not being able to schedule the snapshots of the volumes of a service.
     ///
     /// Arguments:
     ///
     /// * `event_details`: Error linked event details.
+    /// * `service_name`: Service's name.
     /// * `raw_k8s_error`: Raw error message.
-    pub fn new_k8s_cannot_get_pods(event_details: EventDetails, raw_k8s_error: CommandError) -> EngineError {
-        let message = "Unable to get Kubernetes pods.";
+    pub fn new_k8s_cannot_schedule_volume_snapshots(
+        event_details: EventDetails,
+        service_name: String,
+        raw_k8s_error: CommandError,
+    ) -> EngineError {
+        let message = format!("Unable to schedule the volume snapshots of service `{}`.", service_name);
 
         EngineError::new(
             event_details,
-            Tag::K8sCannotGetPods,
-            message.to_string(),
+            Tag::K8sCannotScheduleVolumeSnapshots,
+            message,
             Some(raw_k8s_error),
             None,
             None,
         )
     }
 
-    /// Creates new error for kubernetes upgrade version inconsistency.
+    /// Creates new error when volume snapshots are requested for a service on a cluster which cannot take them.
     ///
     /// Arguments:
     ///
     /// * `event_details`: Error linked event details.
-    /// * `deployed_version`: Deployed k8s version.
-    /// * `requested_version`: Requested k8s version.
-    /// * `raw_error`: Raw error message.
-    pub fn new_k8s_version_upgrade_deployed_vs_requested_versions_inconsistency(
+    /// * `service_name`: Service's name.
+    /// * `raw_k8s_error`: Raw error message.
+    pub fn new_k8s_volume_snapshots_not_supported(
         event_details: EventDetails,
-        deployed_version: VersionsNumber,
-        requested_version: VersionsNumber,
-        raw_error: CommandError,
+        service_name: String,
+        raw_k8s_error: CommandError,
     ) -> EngineError {
         let message = format!(
-            "Unable to upgrade Kubernetes due to version inconsistency. Deployed version: {}, requested version: {}.",
-            deployed_version, requested_version
+            "Volume snapshots of service `{}` cannot be taken, the cluster does not support them.",
+            service_name
         );
 
         EngineError::new(
             event_details,
-            Tag::K8sUpgradeDeployedVsRequestedVersionsInconsistency,
+            Tag::K8sVolumeSnapshotsNotSupported,
             message,
-            Some(raw_error),
-            None,
+            Some(raw_k8s_error),
             None,
+            Some("Please disable the volume snapshots of this service, or install the CSI snapshot controller and a VolumeSnapshotClass matching its storage class.".to_string()),
         )
     }
 
-    /// Creates new error for kubernetes scale replicas.
+    /// Creates new error when the pod autoscaler settings of a service are invalid.
     ///
     /// Arguments:
     ///
     /// * `event_details`: Error linked event details.
-    /// * `selector`: K8s selector.
-    /// * `namespace`: K8s namespace.
-    /// * `requested_replicas`: Number of requested replicas.
-    /// * `raw_error`: Raw error message.
-    pub fn new_k8s_scale_replicas(
-        event_details: EventDetails,
-        selector: String,
-        namespace: String,
-        requested_replicas: u32,
-        raw_error: CommandError,
-    ) -> EngineError {
-        let message = format!(
-            "Unable to scale Kubernetes `{}` replicas to `{}` in namespace `{}`.",
-            selector, requested_replicas, namespace,
-        );
+    /// * `reason`: Why the settings are invalid.
+    pub fn new_invalid_hpa_configuration(event_details: EventDetails, reason: String) -> EngineError {
+        let message = format!("Invalid horizontal pod autoscaler configuration: {}.", reason);
 
-        EngineError::new(event_details, Tag::K8sScaleReplicas, message, Some(raw_error), None, None)
+        EngineError::new(event_details, Tag::InvalidHpaConfiguration, message, None, None, None)
     }
 
-    /// Creates new error for kubernetes load balancer configuration issue.
+    /// Creates new error when a metric the pod autoscaler relies on is not served by the cluster.
     ///
     /// Arguments:
     ///
     /// * `event_details`: Error linked event details.
-    /// * `raw_error`: Raw error message.
-    pub fn new_k8s_loadbalancer_configuration_issue(
+    /// * `metric_name`: Name of the custom or external metric.
+    /// * `raw_k8s_error`: Raw error message.
+    pub fn new_hpa_metric_not_served(
         event_details: EventDetails,
-        raw_error: CommandError,
+        metric_name: String,
+        raw_k8s_error: CommandError,
     ) -> EngineError {
-        let message = "Error, there is an issue with loadbalancer configuration.";
+        let message = format!(
+            "Metric `{}` used by the horizontal pod autoscaler is not served by the cluster.",
+            metric_name
+        );
 
         EngineError::new(
             event_details,
-            Tag::K8sLoadBalancerConfigurationIssue,
-            message.to_string(),
-            Some(raw_error),
-            None,
+            Tag::HpaMetricNotServed,
+            message,
+            Some(raw_k8s_error),
             None,
+            Some(
+                "Please check that the metric is exposed to Prometheus and declared in the prometheus adapter rules."
+                    .to_string(),
+            ),
         )
     }
 
-    /// Creates new error for kubernetes service issue.
+    /// Creates new error for kubernetes not being able to get crash looping pods.
     ///
     /// Arguments:
     ///
     /// * `event_details`: Error linked event details.
-    /// * `raw_error`: Raw error message.
-    pub fn new_k8s_service_issue(event_details: EventDetails, raw_error: CommandError) -> EngineError {
-        let message = "Error, there is an issue with service.";
+    /// * `raw_k8s_error`: Raw error message.
+    pub fn new_k8s_cannot_get_crash_looping_pods(
+        event_details: EventDetails,
+        raw_k8s_error: CommandError,
+    ) -> EngineError {
+        let message = "Unable to get crash looping Kubernetes pods.";
 
         EngineError::new(
             event_details,
-            Tag::K8sServiceError,
+            Tag::K8sCannotGetCrashLoopingPods,
             message.to_string(),
-            Some(raw_error),
+            Some(raw_k8s_error),
             None,
             None,
         )
     }
 
-    /// Creates new error for kubernetes get logs.
+    /// Creates new error for kubernetes not being able to delete completed jobs.
     ///
     /// Arguments:
     ///
     /// * `event_details`: Error linked event details.
-    /// * `selector`: Selector to get logs for.
-    /// * `namespace`: Resource's namespace to get logs for.
-    /// * `raw_error`: Raw error message.
-    pub fn new_k8s_get_logs_error(
+    /// * `raw_k8s_error`: Raw error message.
+    pub fn new_k8s_cannot_delete_completed_jobs(
         event_details: EventDetails,
-        selector: String,
-        namespace: String,
-        raw_error: CommandError,
+        raw_k8s_error: CommandError,
     ) -> EngineError {
-        let message = format!(
-            "Error, unable to retrieve logs for pod with selector `{}` in namespace `{}`.",
-            selector, namespace
-        );
+        let message = "Unable to delete completed Kubernetes jobs.";
 
-        EngineError::new(event_details, Tag::K8sGetLogs, message, Some(raw_error), None, None)
+        EngineError::new(
+            event_details,
+            Tag::K8sCannotDeleteCompletedJobs,
+            message.to_string(),
+            Some(raw_k8s_error),
+            None,
+            None,
+        )
     }
 
-    /// Creates new error for kubernetes get events.
+    /// Creates new error for a kubernetes job which has failed or has not completed in time.
     ///
     /// Arguments:
     ///
     /// * `event_details`: Error linked event details.
-    /// * `namespace`: Resource's namespace to get json events for.
-    /// * `raw_error`: Raw error message.
-    pub fn new_k8s_get_json_events(
+    /// * `job_name`: Job's name.
+    /// * `raw_k8s_error`: Raw error message.
+    pub fn new_k8s_job_failed(
         event_details: EventDetails,
-        namespace: String,
-        raw_error: CommandError,
+        job_name: String,
+        raw_k8s_error: CommandError,
     ) -> EngineError {
-        let message = format!("Error, unable to retrieve events in namespace `{}`.", namespace);
+        let message = format!("Kubernetes job `{}` has failed.", job_name);
 
-        EngineError::new(event_details, Tag::K8sGetLogs, message, Some(raw_error), None, None)
+        EngineError::new(
+            event_details,
+            Tag::K8sJobFailed,
+            message,
+            Some(raw_k8s_error),
+            None,
+            Some("Look at the logs of your job to understand why it failed.".to_string()),
+        )
     }
 
-    /// Creates new error for kubernetes describe.
+    /// Creates new error for kubernetes not being able to suspend a cron job.
     ///
     /// Arguments:
     ///
     /// * `event_details`: Error linked event details.
-    /// * `selector`: Selector to get description for.
-    /// * `namespace`: Resource's namespace to get description for.
-    /// * `raw_error`: Raw error message.
-    pub fn new_k8s_describe(
+    /// * `selector`: Cron job's selector.
+    /// * `namespace`: Cron job's namespace.
+    /// * `raw_k8s_error`: Raw error message.
+    pub fn new_k8s_cannot_suspend_cronjob(
         event_details: EventDetails,
         selector: String,
         namespace: String,
-        raw_error: CommandError,
+        raw_k8s_error: CommandError,
+    ) -> EngineError {
+        let message = format!(
+            "Unable to suspend Kubernetes cron job `{}` in namespace `{}`.",
+            selector, namespace
+        );
+
+        EngineError::new(
+            event_details,
+            Tag::K8sCannotSuspendCronJob,
+            message,
+            Some(raw_k8s_error),
+            None,
+            None,
+        )
+    }
+
+    /// Creates new error for the preview of a new version not passing its promotion gate.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `service_name`: Service's name.
+    /// * `reason`: Why the preview has failed the promotion gate.
+    pub fn new_k8s_promotion_gate_failed(
+        event_details: EventDetails,
+        service_name: String,
+        reason: String,
+    ) -> EngineError {
+        let message = format!(
+            "New version of `{}` has not been promoted: {}. The current version has been kept.",
+            service_name, reason
+        );
+
+        EngineError::new(
+            event_details,
+            Tag::K8sPromotionGateFailed,
+            message,
+            None,
+            None,
+            Some("Check the logs of your service, and the deployment strategy advanced settings.".to_string()),
+        )
+    }
+
+    /// Creates new error for kubernetes not being able to route traffic to the preview of a new version.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `service_name`: Preview service's name.
+    /// * `raw_k8s_error`: Raw error message.
+    pub fn new_k8s_cannot_apply_canary_ingress(
+        event_details: EventDetails,
+        service_name: String,
+        raw_k8s_error: CommandError,
+    ) -> EngineError {
+        let message = format!("Unable to update Kubernetes canary ingresses of `{}`.", service_name);
+
+        EngineError::new(
+            event_details,
+            Tag::K8sCannotApplyCanaryIngress,
+            message,
+            Some(raw_k8s_error),
+            None,
+            None,
+        )
+    }
+
+    /// Creates new error for kubernetes not being able to get pods.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `raw_k8s_error`: Raw error message.
+    pub fn new_k8s_cannot_get_pods(event_details: EventDetails, raw_k8s_error: CommandError) -> EngineError {
+        let message = "Unable to get Kubernetes pods.";
+
+        EngineError::new(
+            event_details,
+            Tag::K8sCannotGetPods,
+            message.to_string(),
+            Some(raw_k8s_error),
+            None,
+            None,
+        )
+    }
+
+    /// Creates new error for kubernetes upgrade version inconsistency.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `deployed_version`: Deployed k8s version.
+    /// * `requested_version`: Requested k8s version.
+    /// * `raw_error`: Raw error message.
+    pub fn new_k8s_version_upgrade_deployed_vs_requested_versions_inconsistency(
+        event_details: EventDetails,
+        deployed_version: VersionsNumber,
+        requested_version: VersionsNumber,
+        raw_error: CommandError,
+    ) -> EngineError {
+        let message = format!(
+            "Unable to upgrade Kubernetes due to version inconsistency. Deployed version: {}, requested version: {}.",
+            deployed_version, requested_version
+        );
+
+        EngineError::new(
+            event_details,
+            Tag::K8sUpgradeDeployedVsRequestedVersionsInconsistency,
+            message,
+            Some(raw_error),
+            None,
+            None,
+        )
+    }
+
+    /// Creates new error for kubernetes scale replicas.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `selector`: K8s selector.
+    /// * `namespace`: K8s namespace.
+    /// * `requested_replicas`: Number of requested replicas.
+    /// * `raw_error`: Raw error message.
+    pub fn new_k8s_scale_replicas(
+        event_details: EventDetails,
+        selector: String,
+        namespace: String,
+        requested_replicas: u32,
+        raw_error: CommandError,
+    ) -> EngineError {
+        let message = format!(
+            "Unable to scale Kubernetes `{}` replicas to `{}` in namespace `{}`.",
+            selector, requested_replicas, namespace,
+        );
+
+        EngineError::new(event_details, Tag::K8sScaleReplicas, message, Some(raw_error), None, None)
+    }
+
+    /// Creates new error for kubernetes load balancer configuration issue.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `raw_error`: Raw error message.
+    pub fn new_k8s_loadbalancer_configuration_issue(
+        event_details: EventDetails,
+        raw_error: CommandError,
+    ) -> EngineError {
+        let message = "Error, there is an issue with loadbalancer configuration.";
+
+        EngineError::new(
+            event_details,
+            Tag::K8sLoadBalancerConfigurationIssue,
+            message.to_string(),
+            Some(raw_error),
+            None,
+            None,
+        )
+    }
+
+    /// Creates new error for kubernetes service issue.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `raw_error`: Raw error message.
+    pub fn new_k8s_service_issue(event_details: EventDetails, raw_error: CommandError) -> EngineError {
+        let message = "Error, there is an issue with service.";
+
+        EngineError::new(
+            event_details,
+            Tag::K8sServiceError,
+            message.to_string(),
+            Some(raw_error),
+            None,
+            None,
+        )
+    }
+
+    /// Creates new error for kubernetes get logs.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `selector`: Selector to get logs for.
+    /// * `namespace`: Resource's namespace to get logs for.
+    /// * `raw_error`: Raw error message.
+    pub fn new_k8s_get_logs_error(
+        event_details: EventDetails,
+        selector: String,
+        namespace: String,
+        raw_error: CommandError,
+    ) -> EngineError {
+        let message = format!(
+            "Error, unable to retrieve logs for pod with selector `{}` in namespace `{}`.",
+            selector, namespace
+        );
+
+        EngineError::new(event_details, Tag::K8sGetLogs, message, Some(raw_error), None, None)
+    }
+
+    /// Creates new error for kubernetes get events.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `namespace`: Resource's namespace to get json events for.
+    /// * `raw_error`: Raw error message.
+    pub fn new_k8s_get_json_events(
+        event_details: EventDetails,
+        namespace: String,
+        raw_error: CommandError,
+    ) -> EngineError {
+        let message = format!("Error, unable to retrieve events in namespace `{}`.", namespace);
+
+        EngineError::new(event_details, Tag::K8sGetLogs, message, Some(raw_error), None, None)
+    }
+
+    /// Creates new error for kubernetes describe.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `selector`: Selector to get description for.
+    /// * `namespace`: Resource's namespace to get description for.
+    /// * `raw_error`: Raw error message.
+    pub fn new_k8s_describe(
+        event_details: EventDetails,
+        selector: String,
+        namespace: String,
+        raw_error: CommandError,
     ) -> EngineError {
         let message = format!(
             "Error, unable to describe pod with selector `{}` in namespace `{}`.",
@@ -2037,6 +2523,30 @@ impl EngineError {
         )
     }
 
+    /// Creates new error for kubernetes namespace scale-to-zero configuration issue.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `namespace`: Namespace of the environment.
+    /// * `raw_error`: Raw error message.
+    pub fn new_k8s_configure_scale_to_zero(
+        event_details: EventDetails,
+        namespace: String,
+        raw_error: CommandError,
+    ) -> EngineError {
+        let message = format!("Error, unable to configure scale-to-zero of namespace `{}`.", namespace);
+
+        EngineError::new(
+            event_details,
+            Tag::K8sCannotConfigureScaleToZero,
+            message,
+            Some(raw_error),
+            None,
+            None,
+        )
+    }
+
     /// Creates new error for kubernetes pod not being ready.
     ///
     /// Arguments:
@@ -2853,24 +3363,400 @@ impl EngineError {
         )
     }
 
-    /// Creates new error while trying to deploy a router.
+    /// Creates new error for a database whose backups cannot be managed by Qovery.
     ///
     /// Arguments:
     ///
     /// * `event_details`: Error linked event details.
-    pub fn new_router_failed_to_deploy(event_details: EventDetails) -> EngineError {
-        let message = "Router has failed to be deployed.";
+    /// * `service_name`: Database's name.
+    /// * `reason`: Why backups are not supported.
+    pub fn new_database_backups_not_supported(
+        event_details: EventDetails,
+        service_name: String,
+        reason: String,
+    ) -> EngineError {
+        let message = format!("Backups of database `{}` are not supported: {}", service_name, reason);
 
-        EngineError::new(event_details, Tag::RouterFailedToDeploy, message.to_string(), None, None, None)
+        EngineError::new(
+            event_details,
+            Tag::DatabaseBackupsNotSupported,
+            message,
+            None,
+            None,
+            Some("Managed databases backups are handled by your cloud provider.".to_string()),
+        )
     }
 
-    /// Creates new error when trying to connect to user's account with its credentials.
+    /// Creates new error while trying to schedule backups of a database.
     ///
     /// Arguments:
     ///
     /// * `event_details`: Error linked event details.
-    pub fn new_client_invalid_cloud_provider_credentials(event_details: EventDetails) -> EngineError {
-        let message = "Your cloud provider account seems to be no longer valid (bad credentials).";
+    /// * `service_name`: Database's name.
+    /// * `raw_error`: Raw error message.
+    pub fn new_database_cannot_schedule_backups(
+        event_details: EventDetails,
+        service_name: String,
+        raw_error: CommandError,
+    ) -> EngineError {
+        let message = format!("Unable to schedule backups of database `{}`.", service_name);
+
+        EngineError::new(
+            event_details,
+            Tag::DatabaseCannotScheduleBackups,
+            message,
+            Some(raw_error),
+            None,
+            None,
+        )
+    }
+
+    /// Creates new error while trying to restore a database from a backup.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `service_name`: Database's name.
+    /// * `backup_key`: Object storage key of the backup.
+    /// * `raw_error`: Raw error message.
+    pub fn new_database_cannot_restore_backup(
+        event_details: EventDetails,
+        service_name: String,
+        backup_key: String,
+        raw_error: CommandError,
+    ) -> EngineError {
+        let message = format!("Unable to restore database `{}` from backup `{}`.", service_name, backup_key);
+
+        EngineError::new(
+            event_details,
+            Tag::DatabaseCannotRestoreBackup,
+            message,
+            Some(raw_error),
+            None,
+            Some(
+                "The database may be partially restored, restore it again from this backup or a previous one."
+                    .to_string(),
+            ),
+        )
+    }
+
+    /// Creates new error for a database which cannot be upgraded to the requested version.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `service_name`: Database's name.
+    /// * `deployed_version`: Version currently running.
+    /// * `requested_version`: Version requested.
+    /// * `raw_error`: Raw error message.
+    pub fn new_database_version_upgrade_not_supported(
+        event_details: EventDetails,
+        service_name: String,
+        deployed_version: String,
+        requested_version: String,
+        raw_error: CommandError,
+    ) -> EngineError {
+        let message = format!(
+            "Database `{}` cannot be upgraded from version `{}` to version `{}`.",
+            service_name, deployed_version, requested_version
+        );
+
+        EngineError::new(
+            event_details,
+            Tag::DatabaseVersionUpgradeNotSupported,
+            message,
+            Some(raw_error),
+            None,
+            Some("Nothing has been changed, request a version supported by this upgrade path.".to_string()),
+        )
+    }
+
+    /// Creates new error while trying to upgrade a database to a new version.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `service_name`: Database's name.
+    /// * `backup_key`: Object storage key of the backup taken before the upgrade.
+    /// * `raw_error`: Raw error message.
+    pub fn new_database_cannot_upgrade_version(
+        event_details: EventDetails,
+        service_name: String,
+        backup_key: String,
+        raw_error: CommandError,
+    ) -> EngineError {
+        let message = format!("Unable to upgrade version of database `{}`.", service_name);
+
+        EngineError::new(
+            event_details,
+            Tag::DatabaseCannotUpgradeVersion,
+            message,
+            Some(raw_error),
+            None,
+            Some(format!(
+                "Data of the database before the upgrade are kept in backup `{}`, restore it if the database is not usable.",
+                backup_key
+            )),
+        )
+    }
+
+    /// Creates new error while trying to add read replicas to a database.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `service_name`: Database's name.
+    /// * `raw_error`: Raw error message.
+    pub fn new_database_cannot_setup_read_replicas(
+        event_details: EventDetails,
+        service_name: String,
+        raw_error: CommandError,
+    ) -> EngineError {
+        let message = format!("Unable to setup read replicas of database `{}`.", service_name);
+
+        EngineError::new(
+            event_details,
+            Tag::DatabaseCannotSetupReadReplicas,
+            message,
+            Some(raw_error),
+            None,
+            Some("The database keeps serving its data without read replicas, remove them from its configuration or create a new database with read replicas.".to_string()),
+        )
+    }
+
+    /// Creates new error while trying to deploy the connection pooler of a database.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `service_name`: Database's name.
+    /// * `raw_error`: Raw error message.
+    pub fn new_database_cannot_setup_connection_pooling(
+        event_details: EventDetails,
+        service_name: String,
+        raw_error: CommandError,
+    ) -> EngineError {
+        let message = format!("Unable to setup the connection pooler of database `{}`.", service_name);
+
+        EngineError::new(
+            event_details,
+            Tag::DatabaseCannotSetupConnectionPooling,
+            message,
+            Some(raw_error),
+            None,
+            None,
+        )
+    }
+
+    /// Creates new error while trying to deploy a router.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    pub fn new_router_failed_to_deploy(event_details: EventDetails) -> EngineError {
+        let message = "Router has failed to be deployed.";
+
+        EngineError::new(event_details, Tag::RouterFailedToDeploy, message.to_string(), None, None, None)
+    }
+
+    /// Creates new error when a route of a router cannot be exposed.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `reason`: Why the route cannot be exposed.
+    pub fn new_router_invalid_route(event_details: EventDetails, reason: String) -> EngineError {
+        let message = format!("Router has an invalid route: {}.", reason);
+
+        EngineError::new(
+            event_details,
+            Tag::RouterInvalidRoute,
+            message,
+            None,
+            None,
+            Some("Please check the ports exposed by the services targeted by the router.".to_string()),
+        )
+    }
+
+    /// Creates new error when the TLS certificate of custom domains cannot be issued, tagged after the issue.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `domains`: Custom domains of the certificate.
+    /// * `issue`: Why the certificate cannot be issued, from the status reported by cert-manager.
+    pub fn new_router_certificate_not_issued(
+        event_details: EventDetails,
+        domains: Vec<String>,
+        issue: &CertificateIssue,
+    ) -> EngineError {
+        let message = format!(
+            "TLS certificate of domains {} has not been issued: {}.",
+            domains.join(", "),
+            issue
+        );
+        let tag = match issue {
+            CertificateIssue::WrongDnsConfiguration { .. } => Tag::RouterCertificateDnsMismatch,
+            CertificateIssue::RateLimited { .. } => Tag::RouterCertificateRateLimited,
+            CertificateIssue::ChallengeFailed { .. } => Tag::RouterCertificateAcmeChallengeFailed,
+            CertificateIssue::ChallengePending { .. } => Tag::RouterCertificateAcmeChallengePending,
+            CertificateIssue::OrderFailed { .. } => Tag::RouterCertificateOrderFailed,
+            CertificateIssue::Other { .. } => Tag::RouterCertificateNotIssued,
+        };
+
+        EngineError::new(
+            event_details,
+            tag,
+            message.to_string(),
+            Some(CommandError::new(message, Some(issue.details().to_string()), None)),
+            None,
+            Some(issue.hint()),
+        )
+    }
+
+    /// Creates new error when the TLS certificate supplied for a custom domain cannot be used.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `domain`: Custom domain of the certificate.
+    /// * `reason`: Why the certificate cannot be used.
+    pub fn new_router_invalid_custom_certificate(
+        event_details: EventDetails,
+        domain: String,
+        reason: String,
+    ) -> EngineError {
+        let message = format!("TLS certificate supplied for domain {} cannot be used: {}.", domain, reason);
+
+        EngineError::new(
+            event_details,
+            Tag::RouterInvalidCustomCertificate,
+            message.to_string(),
+            Some(CommandError::new_from_safe_message(message)),
+            None,
+            Some("Please check that the certificate chain is PEM encoded, valid for the domain and matches the private key.".to_string()),
+        )
+    }
+
+    /// Creates new error when the TLS certificate of a custom domain cannot be stored into the cluster.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `domain`: Custom domain of the certificate.
+    /// * `raw_k8s_error`: Raw error message.
+    pub fn new_router_cannot_apply_custom_certificate(
+        event_details: EventDetails,
+        domain: String,
+        raw_k8s_error: CommandError,
+    ) -> EngineError {
+        let message = format!("Unable to store TLS certificate of domain {} into Kubernetes.", domain);
+
+        EngineError::new(
+            event_details,
+            Tag::RouterCannotApplyCustomCertificate,
+            message,
+            Some(raw_k8s_error),
+            None,
+            None,
+        )
+    }
+
+    /// Creates new error when services of an environment have circular dependencies.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `services_long_ids`: Services being part of the dependency cycle.
+    pub fn new_environment_services_dependency_cycle(
+        event_details: EventDetails,
+        services_long_ids: Vec<Uuid>,
+    ) -> EngineError {
+        let message = format!(
+            "Services of the environment have circular dependencies, they can't be deployed: {}.",
+            services_long_ids
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(" -> ")
+        );
+
+        EngineError::new(
+            event_details,
+            Tag::EnvironmentServicesDependencyCycle,
+            message,
+            None,
+            None,
+            Some("Please remove one of the dependencies between those services.".to_string()),
+        )
+    }
+
+    /// Creates new error while trying to copy the data of a service from the environment it is cloned from.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `service_name`: Cloned service's name.
+    /// * `source_environment_id`: Long id of the environment being cloned.
+    /// * `raw_error`: Raw error message.
+    pub fn new_environment_cannot_be_cloned(
+        event_details: EventDetails,
+        service_name: String,
+        source_environment_id: String,
+        raw_error: CommandError,
+    ) -> EngineError {
+        let message = format!(
+            "Unable to copy the data of `{}` from environment `{}`.",
+            service_name, source_environment_id
+        );
+
+        EngineError::new(
+            event_details,
+            Tag::EnvironmentCannotBeCloned,
+            message,
+            Some(raw_error),
+            None,
+            Some("Make sure the environment being cloned is still running and try again.".to_string()),
+        )
+    }
+
+    /// Creates new error when the value of an environment variable cannot be read from its secret manager.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `variable_name`: Environment variable name.
+    /// * `raw_error`: Raw error message.
+    pub fn new_environment_variable_secret_cannot_be_resolved(
+        event_details: EventDetails,
+        variable_name: String,
+        raw_error: CommandError,
+    ) -> EngineError {
+        let message = format!(
+            "Value of environment variable `{}` cannot be read from its secret manager.",
+            variable_name
+        );
+
+        EngineError::new(
+            event_details,
+            Tag::EnvironmentVariableSecretCannotBeResolved,
+            message,
+            Some(raw_error),
+            None,
+            Some(
+                "Please check that the secret exists and that the cluster credentials are allowed to read it."
+                    .to_string(),
+            ),
+        )
+    }
+
+    /// Creates new error when trying to connect to user's account with its credentials.
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    pub fn new_client_invalid_cloud_provider_credentials(event_details: EventDetails) -> EngineError {
+        let message = "Your cloud provider account seems to be no longer valid (bad credentials).";
 
         EngineError::new(
             event_details,
@@ -3414,6 +4300,25 @@ impl EngineError {
                 None,
                 None,
             ),
+            ObjectStorageError::CannotListObjects { ref bucket_name, .. } => EngineError::new(
+                event_details,
+                Tag::ObjectStorageCannotListObjects,
+                format!("Error, cannot list objects of object storage bucket `{}`.", bucket_name),
+                Some(object_storage_error.into()),
+                None,
+                None,
+            ),
+            ObjectStorageError::CannotManageAccessCredentials { ref bucket_name, .. } => EngineError::new(
+                event_details,
+                Tag::ObjectStorageCannotManageAccessCredentials,
+                format!(
+                    "Error, cannot manage access credentials of object storage bucket `{}`.",
+                    bucket_name
+                ),
+                Some(object_storage_error.into()),
+                None,
+                None,
+            ),
             ObjectStorageError::CannotDeleteFile {
                 ref bucket_name,
                 ref file_name,
@@ -3451,6 +4356,25 @@ impl EngineError {
         )
     }
 
+    /// Creates new error when the secret manager cannot be used
+    ///
+    /// Arguments:
+    ///
+    /// * `event_details`: Error linked event details.
+    /// * `raw_error`: Raw error message.
+    pub fn new_secret_manager_error(event_details: EventDetails, raw_error: CommandError) -> EngineError {
+        let message_safe = "Error while using the secret manager".to_string();
+
+        EngineError::new(
+            event_details,
+            Tag::SecretManagerError,
+            message_safe,
+            Some(raw_error),
+            None,
+            None,
+        )
+    }
+
     /// Creates new error when Vault secret couldn't be retrieved
     ///
     /// Arguments:
@@ -185,6 +185,7 @@ pub enum EnvironmentStep {
     ScaledUp,
     ScaleDown,
     ScaledDown,
+    Rollback,
     Start,
     Terminated,
 }
@@ -209,6 +210,7 @@ impl From<events::EnvironmentStep> for EnvironmentStep {
             events::EnvironmentStep::Deleted => EnvironmentStep::Deleted,
             events::EnvironmentStep::ScaledUp => EnvironmentStep::ScaledUp,
             events::EnvironmentStep::ScaledDown => EnvironmentStep::ScaledDown,
+            events::EnvironmentStep::Rollback => EnvironmentStep::Rollback,
             events::EnvironmentStep::Start => EnvironmentStep::Start,
             events::EnvironmentStep::Cancel => EnvironmentStep::Cancel,
             events::EnvironmentStep::Cancelled => EnvironmentStep::Cancelled,
@@ -270,6 +272,11 @@ pub enum Transmitter {
         name: TransmitterName,
         image: TransmitterVersion,
     },
+    Job {
+        id: TransmitterId,
+        name: TransmitterName,
+        version: TransmitterVersion,
+    },
     Router {
         id: TransmitterId,
         name: TransmitterName,
@@ -299,6 +306,7 @@ impl From<events::Transmitter> for Transmitter {
                 name,
                 image: version,
             },
+            events::Transmitter::Job(id, name, version) => Transmitter::Job { id, name, version },
         }
     }
 }
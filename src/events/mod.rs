@@ -343,6 +343,8 @@ pub enum EnvironmentStep {
     ScaleDown,
     /// ScaledDown: env has been scaled-down.
     ScaledDown,
+    /// Rollback: revert services of an env to their state before a failed deployment.
+    Rollback,
 }
 
 impl Display for EnvironmentStep {
@@ -368,6 +370,7 @@ impl Display for EnvironmentStep {
                 EnvironmentStep::Deleted => "deleted",
                 EnvironmentStep::ScaledUp => "scaled-up",
                 EnvironmentStep::ScaledDown => "scaled-down",
+                EnvironmentStep::Rollback => "rollback",
                 EnvironmentStep::Start => "start",
                 EnvironmentStep::Cancel => "cancel",
                 EnvironmentStep::Cancelled => "cancelled",
@@ -411,6 +414,8 @@ pub enum Transmitter {
     Application(TransmitterId, TransmitterName, TransmitterVersion),
     /// Application: application engine part.
     Container(TransmitterId, TransmitterName, TransmitterVersion),
+    /// Job: cron job or one-off job engine part.
+    Job(TransmitterId, TransmitterName, TransmitterVersion),
     /// Router: router engine part.
     Router(TransmitterId, TransmitterName),
     /// SecretManager: secret manager part
@@ -438,6 +443,7 @@ impl Display for Transmitter {
                 Transmitter::SecretManager(name) => format!("secret_manager({})", name),
                 Transmitter::Container(id, name, version) =>
                     format!("container({}, {}, version: {})", id, name, version),
+                Transmitter::Job(id, name, version) => format!("job({}, {}, version: {})", id, name, version),
             }
         )
     }
@@ -1,6 +1,6 @@
 use crate::build_platform::{Build, Credentials, GitRepository, Image, SshKey};
 use crate::cloud_provider::kubernetes::Kind as KubernetesKind;
-use crate::cloud_provider::models::EnvironmentVariable;
+use crate::cloud_provider::models::{EnvironmentVariable, SecretEnvironmentVariable, TaintEffect};
 use crate::cloud_provider::{CloudProvider, Kind as CPKind};
 use crate::container_registry::ContainerRegistryInfo;
 use crate::io_models::context::Context;
@@ -12,7 +12,8 @@ use crate::models::aws::{AwsAppExtraSettings, AwsStorageType};
 use crate::models::aws_ec2::{AwsEc2AppExtraSettings, AwsEc2StorageType};
 use crate::models::digital_ocean::{DoAppExtraSettings, DoStorageType};
 use crate::models::scaleway::{ScwAppExtraSettings, ScwStorageType};
-use crate::models::types::{AWSEc2, AWS, DO, SCW};
+use crate::models::self_managed::{SelfManagedAppExtraSettings, SelfManagedStorageType};
+use crate::models::types::{AWSEc2, SelfManaged, AWS, DO, SCW};
 use crate::utilities::to_short_id;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -48,6 +49,110 @@ pub enum AdvancedSettingsProbeType {
     Http,
 }
 
+/// Metric the pod autoscaler scales on, in addition to the CPU utilization
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Hash)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum HpaMetric {
+    /// Average memory utilization of the pods, in percent of their memory request
+    Memory { average_utilization_percent: u32 },
+    /// Per pod metric exposed by Prometheus and served by the prometheus adapter
+    Custom { name: String, average_value: String },
+    /// Metric not related to Kubernetes objects, such as the length of a queue, divided among the pods
+    External {
+        name: String,
+        #[serde(default)]
+        selector: BTreeMap<String, String>,
+        average_value: String,
+    },
+}
+
+impl HpaMetric {
+    pub fn name(&self) -> &str {
+        match self {
+            HpaMetric::Memory { .. } => "memory",
+            HpaMetric::Custom { name, .. } => name,
+            HpaMetric::External { name, .. } => name,
+        }
+    }
+}
+
+/// How fast the pod autoscaler scales in one direction, named as the Kubernetes `HPAScalingRules`
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Hash)]
+pub struct HpaScalingRules {
+    #[serde(default)]
+    pub stabilization_window_seconds: Option<u32>,
+    #[serde(default)]
+    pub select_policy: Option<HpaScalingPolicySelect>,
+    #[serde(default)]
+    pub policies: Vec<HpaScalingPolicy>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Hash)]
+pub enum HpaScalingPolicySelect {
+    Max,
+    Min,
+    Disabled,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Hash)]
+pub struct HpaScalingPolicy {
+    #[serde(rename = "type")]
+    pub policy_type: HpaScalingPolicyType,
+    pub value: u32,
+    pub period_seconds: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Hash)]
+pub enum HpaScalingPolicyType {
+    Pods,
+    Percent,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DeploymentStrategyType {
+    RollingUpdate,
+    /// New version is deployed next to the current one, and receives all the traffic once validated
+    BlueGreen,
+    /// New version is deployed next to the current one, and receives a part of the traffic until validated
+    Canary,
+}
+
+/// Toleration of a node group taint, named as the Kubernetes `Toleration`
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Toleration {
+    pub key: String,
+    #[serde(default)]
+    pub operator: TolerationOperator,
+    /// ignored with the `Exists` operator
+    #[serde(default)]
+    pub value: Option<String>,
+    /// all effects are tolerated when not set
+    #[serde(default)]
+    pub effect: Option<TaintEffect>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub enum TolerationOperator {
+    #[default]
+    Equal,
+    Exists,
+}
+
+/// External secret holding the value of an environment variable.
+/// It is read at deploy time, so the value never travels in the environment request.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Hash)]
+pub enum SecretReference {
+    /// Field of a Vault KV2 secret, stored in the folder of the organization: `<organization id>/...`
+    Vault { mount: String, path: String, key: String },
+    /// Secret stored in AWS Secrets Manager, in the region of the cluster.
+    /// `key` selects a field when the secret is a JSON object
+    AwsSecretsManager { secret_id: String, key: Option<String> },
+    /// Secret stored in Scaleway Secret Manager, in the region of the cluster.
+    /// `key` selects a field when the secret is a JSON object
+    ScalewaySecretManager { secret_id: String, key: Option<String> },
+}
+
 pub fn to_environment_variable(env_vars: &BTreeMap<String, String>) -> Vec<EnvironmentVariable> {
     env_vars
         .iter()
@@ -58,6 +163,18 @@ pub fn to_environment_variable(env_vars: &BTreeMap<String, String>) -> Vec<Envir
         .collect()
 }
 
+pub fn to_secret_environment_variable(
+    secret_env_vars: &BTreeMap<String, SecretReference>,
+) -> Vec<SecretEnvironmentVariable> {
+    secret_env_vars
+        .iter()
+        .map(|(k, v)| SecretEnvironmentVariable {
+            key: k.clone(),
+            secret: v.clone(),
+        })
+        .collect()
+}
+
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
 pub struct GitCredentials {
     pub login: String,
@@ -75,6 +192,12 @@ pub struct ApplicationAdvancedSettings {
     pub deployment_delay_start_time_sec: u32,
     #[serde(alias = "deployment.custom_domain_check_enabled")]
     pub deployment_custom_domain_check_enabled: bool,
+    /// how long the issuance of the TLS certificate of custom domains is watched, 0 to only report its current status
+    #[serde(alias = "deployment.custom_domain_certificate_timeout_sec")]
+    pub deployment_custom_domain_certificate_timeout_sec: u32,
+    /// fail the deployment if the TLS certificate of custom domains is not issued in time
+    #[serde(alias = "deployment.custom_domain_certificate_required")]
+    pub deployment_custom_domain_certificate_required: bool,
     #[serde(alias = "build.timeout_max_sec")]
     pub build_timeout_max_sec: u32,
     #[serde(alias = "network.ingress.proxy_body_size_mb")]
@@ -121,6 +244,29 @@ pub struct ApplicationAdvancedSettings {
     pub liveness_probe_failure_threshold: u32,
     #[serde(alias = "hpa.cpu.average_utilization_percent")]
     pub hpa_cpu_average_utilization_percent: i8,
+    #[serde(alias = "hpa.metrics")]
+    pub hpa_metrics: Vec<HpaMetric>,
+    #[serde(alias = "hpa.behavior.scale_up")]
+    pub hpa_scale_up_behavior: Option<HpaScalingRules>,
+    #[serde(alias = "hpa.behavior.scale_down")]
+    pub hpa_scale_down_behavior: Option<HpaScalingRules>,
+    #[serde(alias = "deployment.strategy.type")]
+    pub deployment_strategy_type: DeploymentStrategyType,
+    #[serde(alias = "deployment.strategy.rolling_update.max_surge_percent")]
+    pub deployment_strategy_max_surge_percent: u32,
+    #[serde(alias = "deployment.strategy.rolling_update.max_unavailable_percent")]
+    pub deployment_strategy_max_unavailable_percent: u32,
+    #[serde(alias = "deployment.strategy.canary.weight_percent")]
+    pub deployment_strategy_canary_weight_percent: u32,
+    #[serde(alias = "deployment.strategy.analysis_duration_sec")]
+    pub deployment_strategy_analysis_duration_sec: u32,
+    #[serde(alias = "deployment.strategy.max_error_rate_percent")]
+    pub deployment_strategy_max_error_rate_percent: Option<u32>,
+    /// labels of the nodes the pods are scheduled on, `qovery.com/node-group-name` targets a node group
+    #[serde(alias = "deployment.node_selector")]
+    pub deployment_node_selector: BTreeMap<String, String>,
+    #[serde(alias = "deployment.tolerations")]
+    pub deployment_tolerations: Vec<Toleration>,
 }
 
 impl Default for ApplicationAdvancedSettings {
@@ -129,6 +275,8 @@ impl Default for ApplicationAdvancedSettings {
             deployment_delay_start_time_sec: 30,
             build_timeout_max_sec: 30 * 60, // 30min
             deployment_custom_domain_check_enabled: true,
+            deployment_custom_domain_certificate_timeout_sec: 0,
+            deployment_custom_domain_certificate_required: false,
             network_ingress_proxy_body_size_mb: 100,
             network_ingress_cors_enable: false,
             network_ingress_cors_allow_origin: "*".to_string(),
@@ -151,6 +299,17 @@ impl Default for ApplicationAdvancedSettings {
             liveness_probe_success_threshold: 1,
             liveness_probe_failure_threshold: 9,
             hpa_cpu_average_utilization_percent: 60,
+            hpa_metrics: vec![],
+            hpa_scale_up_behavior: None,
+            hpa_scale_down_behavior: None,
+            deployment_strategy_type: DeploymentStrategyType::RollingUpdate,
+            deployment_strategy_max_surge_percent: 25,
+            deployment_strategy_max_unavailable_percent: 10,
+            deployment_strategy_canary_weight_percent: 10,
+            deployment_strategy_analysis_duration_sec: 2 * 60,
+            deployment_strategy_max_error_rate_percent: None,
+            deployment_node_selector: BTreeMap::new(),
+            deployment_tolerations: vec![],
         }
     }
 }
@@ -178,11 +337,20 @@ pub struct Application {
     /// Key is a String, Value is a base64 encoded String
     /// Use BTreeMap to get Hash trait which is not available on HashMap
     pub environment_vars: BTreeMap<String, String>,
+    /// Key is a String, Value references the external secret holding its value
+    #[serde(default)]
+    pub secret_environment_vars: BTreeMap<String, SecretReference>,
     #[serde(default)]
     pub advanced_settings: ApplicationAdvancedSettings,
+    /// Long ids of the services which must be deployed before this one
+    #[serde(default)]
+    pub depends_on: Vec<Uuid>,
+    /// Long id of the service this one is cloned from, when the environment is cloned
+    #[serde(default)]
+    pub clone_from_long_id: Option<Uuid>,
 }
 
-fn default_root_path_value() -> String {
+pub(crate) fn default_root_path_value() -> String {
     "/".to_string()
 }
 
@@ -195,6 +363,7 @@ impl Application {
         logger: Box<dyn Logger>,
     ) -> Result<Box<dyn ApplicationService>, ApplicationError> {
         let environment_variables = to_environment_variable(&self.environment_vars);
+        let secret_environment_variables = to_secret_environment_variable(&self.secret_environment_vars);
         let listeners = cloud_provider.listeners().clone();
 
         match cloud_provider.kind() {
@@ -217,6 +386,8 @@ impl Application {
                         build,
                         self.storage.iter().map(|s| s.to_aws_storage()).collect::<Vec<_>>(),
                         environment_variables,
+                        secret_environment_variables,
+                        self.depends_on.clone(),
                         self.advanced_settings.clone(),
                         AwsAppExtraSettings {},
                         listeners,
@@ -237,6 +408,8 @@ impl Application {
                         build,
                         self.storage.iter().map(|s| s.to_aws_ec2_storage()).collect::<Vec<_>>(),
                         environment_variables,
+                        secret_environment_variables,
+                        self.depends_on.clone(),
                         self.advanced_settings.clone(),
                         AwsEc2AppExtraSettings {},
                         listeners,
@@ -258,6 +431,8 @@ impl Application {
                 build,
                 self.storage.iter().map(|s| s.to_do_storage()).collect::<Vec<_>>(),
                 environment_variables,
+                secret_environment_variables,
+                self.depends_on.clone(),
                 self.advanced_settings.clone(),
                 DoAppExtraSettings {},
                 listeners,
@@ -277,126 +452,177 @@ impl Application {
                 build,
                 self.storage.iter().map(|s| s.to_scw_storage()).collect::<Vec<_>>(),
                 environment_variables,
+                secret_environment_variables,
+                self.depends_on.clone(),
                 self.advanced_settings.clone(),
                 ScwAppExtraSettings {},
                 listeners,
                 logger.clone(),
             )?)),
+            CPKind::SelfManaged => Ok(Box::new(models::application::Application::<SelfManaged>::new(
+                context.clone(),
+                self.long_id,
+                self.action.to_service_action(),
+                self.name.as_str(),
+                self.ports.clone(),
+                self.total_cpus.clone(),
+                self.cpu_burst.clone(),
+                self.total_ram_in_mib,
+                self.min_instances,
+                self.max_instances,
+                build,
+                self.storage
+                    .iter()
+                    .map(|s| s.to_self_managed_storage())
+                    .collect::<Vec<_>>(),
+                environment_variables,
+                secret_environment_variables,
+                self.depends_on.clone(),
+                self.advanced_settings.clone(),
+                SelfManagedAppExtraSettings {},
+                listeners,
+                logger.clone(),
+            )?)),
         }
     }
 
-    fn to_image(&self, cr_info: &ContainerRegistryInfo) -> Image {
-        Image {
-            application_id: to_short_id(&self.long_id),
-            name: (cr_info.get_image_name)(&self.name),
-            tag: "".to_string(), // It needs to be compute after creation
-            commit_id: self.commit_id.clone(),
-            registry_name: cr_info.registry_name.clone(),
-            registry_url: cr_info.endpoint.clone(),
-            registry_docker_json_config: cr_info.registry_docker_json_config.clone(),
-            repository_name: (cr_info.get_repository_name)(&self.name),
-        }
+    pub fn to_build(&self, registry_url: &ContainerRegistryInfo) -> Build {
+        to_git_build(
+            &self.long_id,
+            &self.name,
+            &self.git_url,
+            self.git_credentials.as_ref(),
+            &self.commit_id,
+            self.dockerfile_path.as_deref(),
+            &self.root_path,
+            self.buildpack_language.clone(),
+            &self.environment_vars,
+            self.advanced_settings.build_timeout_max_sec,
+            registry_url,
+        )
     }
+}
 
-    pub fn to_build(&self, registry_url: &ContainerRegistryInfo) -> Build {
-        // Retrieve ssh keys from env variables
-        const ENV_GIT_PREFIX: &str = "GIT_SSH_KEY";
-        let env_ssh_keys: Vec<(String, String)> = self
-            .environment_vars
-            .iter()
-            .filter_map(|(name, value)| {
-                if name.starts_with(ENV_GIT_PREFIX) {
-                    Some((name.clone(), value.clone()))
-                } else {
-                    None
-                }
-            })
-            .collect();
+fn to_image(long_id: &Uuid, name: &str, commit_id: &str, cr_info: &ContainerRegistryInfo) -> Image {
+    Image {
+        application_id: to_short_id(long_id),
+        name: (cr_info.get_image_name)(name),
+        tag: "".to_string(), // It needs to be compute after creation
+        commit_id: commit_id.to_string(),
+        registry_name: cr_info.registry_name.clone(),
+        registry_url: cr_info.endpoint.clone(),
+        registry_docker_json_config: cr_info.registry_docker_json_config.clone(),
+        repository_name: (cr_info.get_repository_name)(name),
+    }
+}
 
-        // Get passphrase and public key if provided by the user
-        let mut ssh_keys: Vec<SshKey> = Vec::with_capacity(env_ssh_keys.len());
-        for (ssh_key_name, private_key) in env_ssh_keys {
-            let private_key = if let Ok(Ok(private_key)) = base64::decode(private_key).map(String::from_utf8) {
-                private_key
+/// Build of a service whose image is built from a git repository (applications and jobs)
+pub(crate) fn to_git_build(
+    long_id: &Uuid,
+    name: &str,
+    git_url: &str,
+    git_credentials: Option<&GitCredentials>,
+    commit_id: &str,
+    dockerfile_path: Option<&str>,
+    root_path: &str,
+    buildpack_language: Option<String>,
+    environment_vars: &BTreeMap<String, String>,
+    build_timeout_max_sec: u32,
+    registry_url: &ContainerRegistryInfo,
+) -> Build {
+    // Retrieve ssh keys from env variables
+    const ENV_GIT_PREFIX: &str = "GIT_SSH_KEY";
+    let env_ssh_keys: Vec<(String, String)> = environment_vars
+        .iter()
+        .filter_map(|(name, value)| {
+            if name.starts_with(ENV_GIT_PREFIX) {
+                Some((name.clone(), value.clone()))
             } else {
-                error!("Invalid base64 environment variable for {}", ssh_key_name);
-                continue;
-            };
-
-            let passphrase = self
-                .environment_vars
-                .get(&ssh_key_name.replace(ENV_GIT_PREFIX, "GIT_SSH_PASSPHRASE"))
-                .and_then(|val| base64::decode(val).ok())
-                .and_then(|str| String::from_utf8(str).ok());
-
-            let public_key = self
-                .environment_vars
-                .get(&ssh_key_name.replace(ENV_GIT_PREFIX, "GIT_SSH_PUBLIC_KEY"))
-                .and_then(|val| base64::decode(val).ok())
-                .and_then(|str| String::from_utf8(str).ok());
-
-            ssh_keys.push(SshKey {
-                private_key,
-                passphrase,
-                public_key,
-            });
-        }
+                None
+            }
+        })
+        .collect();
 
-        // Convert our root path to an relative path to be able to append them correctly
-        let root_path = if Path::new(&self.root_path).is_absolute() {
-            PathBuf::from(self.root_path.trim_start_matches('/'))
+    // Get passphrase and public key if provided by the user
+    let mut ssh_keys: Vec<SshKey> = Vec::with_capacity(env_ssh_keys.len());
+    for (ssh_key_name, private_key) in env_ssh_keys {
+        let private_key = if let Ok(Ok(private_key)) = base64::decode(private_key).map(String::from_utf8) {
+            private_key
         } else {
-            PathBuf::from(&self.root_path)
+            error!("Invalid base64 environment variable for {}", ssh_key_name);
+            continue;
         };
-        assert!(root_path.is_relative(), "root path is not a relative path");
 
-        let dockerfile_path = self.dockerfile_path.as_ref().map(|path| {
-            if Path::new(&path).is_absolute() {
-                root_path.join(path.trim_start_matches('/'))
-            } else {
-                root_path.join(&path)
-            }
-        });
+        let passphrase = environment_vars
+            .get(&ssh_key_name.replace(ENV_GIT_PREFIX, "GIT_SSH_PASSPHRASE"))
+            .and_then(|val| base64::decode(val).ok())
+            .and_then(|str| String::from_utf8(str).ok());
 
-        //FIXME: Return a result the function
-        let url = Url::parse(&self.git_url).unwrap_or_else(|_| Url::parse("https://invalid-git-url.com").unwrap());
-
-        let mut disable_build_cache = false;
-        let mut build = Build {
-            git_repository: GitRepository {
-                url,
-                credentials: self.git_credentials.as_ref().map(|credentials| Credentials {
-                    login: credentials.login.clone(),
-                    password: credentials.access_token.clone(),
-                }),
-                ssh_keys,
-                commit_id: self.commit_id.clone(),
-                dockerfile_path,
-                root_path,
-                buildpack_language: self.buildpack_language.clone(),
-            },
-            image: self.to_image(registry_url),
-            environment_variables: self
-                .environment_vars
-                .iter()
-                .filter_map(|(k, v)| {
-                    // Remove special vars
-                    let v = String::from_utf8_lossy(&base64::decode(v.as_bytes()).unwrap_or_default()).into_owned();
-                    if k == "QOVERY_DISABLE_BUILD_CACHE" && v.to_lowercase() == "true" {
-                        disable_build_cache = true;
-                        return None;
-                    }
-
-                    Some((k.clone(), v))
-                })
-                .collect::<BTreeMap<_, _>>(),
-            disable_cache: disable_build_cache,
-            timeout: Duration::from_secs(self.advanced_settings.build_timeout_max_sec as u64),
-        };
+        let public_key = environment_vars
+            .get(&ssh_key_name.replace(ENV_GIT_PREFIX, "GIT_SSH_PUBLIC_KEY"))
+            .and_then(|val| base64::decode(val).ok())
+            .and_then(|str| String::from_utf8(str).ok());
 
-        build.compute_image_tag();
-        build
+        ssh_keys.push(SshKey {
+            private_key,
+            passphrase,
+            public_key,
+        });
     }
+
+    // Convert our root path to an relative path to be able to append them correctly
+    let root_path = if Path::new(root_path).is_absolute() {
+        PathBuf::from(root_path.trim_start_matches('/'))
+    } else {
+        PathBuf::from(root_path)
+    };
+    assert!(root_path.is_relative(), "root path is not a relative path");
+
+    let dockerfile_path = dockerfile_path.map(|path| {
+        if Path::new(path).is_absolute() {
+            root_path.join(path.trim_start_matches('/'))
+        } else {
+            root_path.join(path)
+        }
+    });
+
+    //FIXME: Return a result the function
+    let url = Url::parse(git_url).unwrap_or_else(|_| Url::parse("https://invalid-git-url.com").unwrap());
+
+    let mut disable_build_cache = false;
+    let mut build = Build {
+        git_repository: GitRepository {
+            url,
+            credentials: git_credentials.map(|credentials| Credentials {
+                login: credentials.login.clone(),
+                password: credentials.access_token.clone(),
+            }),
+            ssh_keys,
+            commit_id: commit_id.to_string(),
+            dockerfile_path,
+            root_path,
+            buildpack_language,
+        },
+        image: to_image(long_id, name, commit_id, registry_url),
+        environment_variables: environment_vars
+            .iter()
+            .filter_map(|(k, v)| {
+                // Remove special vars
+                let v = String::from_utf8_lossy(&base64::decode(v.as_bytes()).unwrap_or_default()).into_owned();
+                if k == "QOVERY_DISABLE_BUILD_CACHE" && v.to_lowercase() == "true" {
+                    disable_build_cache = true;
+                    return None;
+                }
+
+                Some((k.clone(), v))
+            })
+            .collect::<BTreeMap<_, _>>(),
+        disable_cache: disable_build_cache,
+        timeout: Duration::from_secs(build_timeout_max_sec as u64),
+    };
+
+    build.compute_image_tag();
+    build
 }
 
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
@@ -408,6 +634,24 @@ pub struct Storage {
     pub size_in_gib: u16,
     pub mount_point: String,
     pub snapshot_retention_in_days: u16,
+    #[serde(default)]
+    pub snapshot_schedule: SnapshotSchedule,
+    /// Name of a snapshot of this storage its volumes are restored from before deploying
+    #[serde(default)]
+    pub restore_from_snapshot: Option<String>,
+}
+
+/// When volume snapshots of a storage are taken, they are pruned after `snapshot_retention_in_days`
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Hash, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SnapshotSchedule {
+    #[default]
+    Never,
+    /// taken by the engine before each deployment
+    BeforeDeploy,
+    /// taken by a CronJob in the environment namespace, which also prunes them
+    Daily,
+    Weekly,
 }
 
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
@@ -434,6 +678,8 @@ impl Storage {
             size_in_gib: self.size_in_gib,
             mount_point: self.mount_point.clone(),
             snapshot_retention_in_days: self.snapshot_retention_in_days,
+            snapshot_schedule: self.snapshot_schedule.clone(),
+            restore_from_snapshot: self.restore_from_snapshot.clone(),
         }
     }
 
@@ -451,6 +697,8 @@ impl Storage {
             size_in_gib: self.size_in_gib,
             mount_point: self.mount_point.clone(),
             snapshot_retention_in_days: self.snapshot_retention_in_days,
+            snapshot_schedule: self.snapshot_schedule.clone(),
+            restore_from_snapshot: self.restore_from_snapshot.clone(),
         }
     }
 
@@ -463,6 +711,8 @@ impl Storage {
             size_in_gib: self.size_in_gib,
             mount_point: self.mount_point.clone(),
             snapshot_retention_in_days: self.snapshot_retention_in_days,
+            snapshot_schedule: self.snapshot_schedule.clone(),
+            restore_from_snapshot: self.restore_from_snapshot.clone(),
         }
     }
 
@@ -475,6 +725,22 @@ impl Storage {
             size_in_gib: self.size_in_gib,
             mount_point: self.mount_point.clone(),
             snapshot_retention_in_days: self.snapshot_retention_in_days,
+            snapshot_schedule: self.snapshot_schedule.clone(),
+            restore_from_snapshot: self.restore_from_snapshot.clone(),
+        }
+    }
+
+    pub fn to_self_managed_storage(&self) -> crate::cloud_provider::models::Storage<SelfManagedStorageType> {
+        crate::cloud_provider::models::Storage {
+            id: self.id.clone(),
+            long_id: self.long_id,
+            name: self.name.clone(),
+            storage_type: SelfManagedStorageType::Default,
+            size_in_gib: self.size_in_gib,
+            mount_point: self.mount_point.clone(),
+            snapshot_retention_in_days: self.snapshot_retention_in_days,
+            snapshot_schedule: self.snapshot_schedule.clone(),
+            restore_from_snapshot: self.restore_from_snapshot.clone(),
         }
     }
 }
@@ -1,7 +1,10 @@
 use crate::cloud_provider::kubernetes::Kind as KubernetesKind;
 use crate::cloud_provider::{CloudProvider, Kind as CPKind};
 use crate::container_registry::ContainerRegistry;
-use crate::io_models::application::{to_environment_variable, AdvancedSettingsProbeType, Port, Storage};
+use crate::io_models::application::{
+    to_environment_variable, to_secret_environment_variable, AdvancedSettingsProbeType, DeploymentStrategyType,
+    HpaMetric, HpaScalingRules, Port, SecretReference, Storage, Toleration,
+};
 use crate::io_models::context::Context;
 use crate::io_models::Action;
 use crate::logger::Logger;
@@ -10,8 +13,9 @@ use crate::models::aws::AwsAppExtraSettings;
 use crate::models::aws_ec2::AwsEc2AppExtraSettings;
 use crate::models::container::{ContainerError, ContainerService};
 use crate::models::scaleway::ScwAppExtraSettings;
+use crate::models::self_managed::SelfManagedAppExtraSettings;
 use crate::models::types::CloudProvider as CP;
-use crate::models::types::{AWSEc2, AWS, DO, SCW};
+use crate::models::types::{AWSEc2, SelfManaged, AWS, DO, SCW};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use url::Url;
@@ -101,6 +105,12 @@ pub struct ContainerAdvancedSettings {
     // Deployment
     #[serde(alias = "deployment.custom_domain_check_enabled")]
     pub deployment_custom_domain_check_enabled: bool,
+    /// how long the issuance of the TLS certificate of custom domains is watched, 0 to only report its current status
+    #[serde(alias = "deployment.custom_domain_certificate_timeout_sec")]
+    pub deployment_custom_domain_certificate_timeout_sec: u32,
+    /// fail the deployment if the TLS certificate of custom domains is not issued in time
+    #[serde(alias = "deployment.custom_domain_certificate_required")]
+    pub deployment_custom_domain_certificate_required: bool,
 
     // Ingress
     #[serde(alias = "network.ingress.proxy_body_size_mb")]
@@ -153,12 +163,41 @@ pub struct ContainerAdvancedSettings {
     // Pod autoscaler
     #[serde(alias = "hpa.cpu.average_utilization_percent")]
     pub hpa_cpu_average_utilization_percent: u8,
+    #[serde(alias = "hpa.metrics")]
+    pub hpa_metrics: Vec<HpaMetric>,
+    #[serde(alias = "hpa.behavior.scale_up")]
+    pub hpa_scale_up_behavior: Option<HpaScalingRules>,
+    #[serde(alias = "hpa.behavior.scale_down")]
+    pub hpa_scale_down_behavior: Option<HpaScalingRules>,
+
+    // Deployment strategy
+    #[serde(alias = "deployment.strategy.type")]
+    pub deployment_strategy_type: DeploymentStrategyType,
+    #[serde(alias = "deployment.strategy.rolling_update.max_surge_percent")]
+    pub deployment_strategy_max_surge_percent: u32,
+    #[serde(alias = "deployment.strategy.rolling_update.max_unavailable_percent")]
+    pub deployment_strategy_max_unavailable_percent: u32,
+    #[serde(alias = "deployment.strategy.canary.weight_percent")]
+    pub deployment_strategy_canary_weight_percent: u32,
+    #[serde(alias = "deployment.strategy.analysis_duration_sec")]
+    pub deployment_strategy_analysis_duration_sec: u32,
+    #[serde(alias = "deployment.strategy.max_error_rate_percent")]
+    pub deployment_strategy_max_error_rate_percent: Option<u32>,
+
+    // Scheduling
+    /// labels of the nodes the pods are scheduled on, `qovery.com/node-group-name` targets a node group
+    #[serde(alias = "deployment.node_selector")]
+    pub deployment_node_selector: BTreeMap<String, String>,
+    #[serde(alias = "deployment.tolerations")]
+    pub deployment_tolerations: Vec<Toleration>,
 }
 
 impl Default for ContainerAdvancedSettings {
     fn default() -> Self {
         ContainerAdvancedSettings {
             deployment_custom_domain_check_enabled: true,
+            deployment_custom_domain_certificate_timeout_sec: 0,
+            deployment_custom_domain_certificate_required: false,
             network_ingress_proxy_body_size_mb: 100,
             network_ingress_cors_enable: false,
             network_ingress_cors_allow_origin: "*".to_string(),
@@ -181,6 +220,17 @@ impl Default for ContainerAdvancedSettings {
             liveness_probe_success_threshold: 1,
             liveness_probe_failure_threshold: 9,
             hpa_cpu_average_utilization_percent: 60,
+            hpa_metrics: vec![],
+            hpa_scale_up_behavior: None,
+            hpa_scale_down_behavior: None,
+            deployment_strategy_type: DeploymentStrategyType::RollingUpdate,
+            deployment_strategy_max_surge_percent: 25,
+            deployment_strategy_max_unavailable_percent: 10,
+            deployment_strategy_canary_weight_percent: 10,
+            deployment_strategy_analysis_duration_sec: 2 * 60,
+            deployment_strategy_max_error_rate_percent: None,
+            deployment_node_selector: BTreeMap::new(),
+            deployment_tolerations: vec![],
         }
     }
 }
@@ -206,8 +256,17 @@ pub struct Container {
     /// Key is a String, Value is a base64 encoded String
     /// Use BTreeMap to get Hash trait which is not available on HashMap
     pub environment_vars: BTreeMap<String, String>,
+    /// Key is a String, Value references the external secret holding its value
+    #[serde(default)]
+    pub secret_environment_vars: BTreeMap<String, SecretReference>,
     #[serde(default)]
     pub advanced_settings: ContainerAdvancedSettings,
+    /// Long ids of the services which must be deployed before this one
+    #[serde(default)]
+    pub depends_on: Vec<Uuid>,
+    /// Long id of the service this one is cloned from, when the environment is cloned
+    #[serde(default)]
+    pub clone_from_long_id: Option<Uuid>,
 }
 
 impl Container {
@@ -219,6 +278,7 @@ impl Container {
         logger: Box<dyn Logger>,
     ) -> Result<Box<dyn ContainerService>, ContainerError> {
         let environment_variables = to_environment_variable(&self.environment_vars);
+        let secret_environment_variables = to_secret_environment_variable(&self.secret_environment_vars);
         let listeners = cloud_provider.listeners().clone();
 
         // Default registry is a bit special as the core does not knows its url/credentials as it is retrieved
@@ -250,6 +310,8 @@ impl Container {
                         self.ports,
                         self.storages.iter().map(|s| s.to_aws_storage()).collect::<Vec<_>>(),
                         environment_variables,
+                        secret_environment_variables,
+                        self.depends_on,
                         self.advanced_settings,
                         AwsAppExtraSettings {},
                         listeners,
@@ -275,6 +337,8 @@ impl Container {
                         self.ports,
                         self.storages.iter().map(|s| s.to_aws_ec2_storage()).collect::<Vec<_>>(),
                         environment_variables,
+                        secret_environment_variables,
+                        self.depends_on,
                         self.advanced_settings,
                         AwsEc2AppExtraSettings {},
                         listeners,
@@ -307,11 +371,42 @@ impl Container {
                 self.ports,
                 self.storages.iter().map(|s| s.to_scw_storage()).collect::<Vec<_>>(),
                 environment_variables,
+                secret_environment_variables,
+                self.depends_on,
                 self.advanced_settings,
                 ScwAppExtraSettings {},
                 listeners,
                 logger.clone(),
             )?),
+            CPKind::SelfManaged => Box::new(models::container::Container::<SelfManaged>::new(
+                context.clone(),
+                self.long_id,
+                self.name,
+                self.action.to_service_action(),
+                self.registry,
+                self.image,
+                self.tag,
+                self.command_args,
+                self.entrypoint,
+                self.cpu_request_in_mili,
+                self.cpu_limit_in_mili,
+                self.ram_request_in_mib,
+                self.ram_limit_in_mib,
+                self.min_instances,
+                self.max_instances,
+                self.ports,
+                self.storages
+                    .iter()
+                    .map(|s| s.to_self_managed_storage())
+                    .collect::<Vec<_>>(),
+                environment_variables,
+                secret_environment_variables,
+                self.depends_on,
+                self.advanced_settings,
+                SelfManagedAppExtraSettings {},
+                listeners,
+                logger.clone(),
+            )?),
         };
 
         Ok(service)
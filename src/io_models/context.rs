@@ -126,6 +126,13 @@ impl Context {
         }
     }
 
+    pub fn prometheus_pushgateway_url(&self) -> Option<&Url> {
+        match &self.metadata {
+            Some(meta) => meta.prometheus_pushgateway_url.as_ref(),
+            _ => None,
+        }
+    }
+
     // Qovery features
     pub fn is_feature_enabled(&self, name: &Features) -> bool {
         for feature in &self.features {
@@ -146,6 +153,8 @@ pub struct Metadata {
     pub resource_expiration_in_seconds: Option<u32>,
     pub disable_pleco: Option<bool>,
     pub is_first_cluster_deployment: Option<bool>,
+    /// push gateway receiving the timing metrics of each execution
+    pub prometheus_pushgateway_url: Option<Url>,
 }
 
 impl Metadata {
@@ -155,6 +164,7 @@ impl Metadata {
         forced_upgrade: Option<bool>,
         disable_pleco: Option<bool>,
         is_first_cluster_deployment: Option<bool>,
+        prometheus_pushgateway_url: Option<Url>,
     ) -> Self {
         Metadata {
             dry_run_deploy,
@@ -162,6 +172,7 @@ impl Metadata {
             forced_upgrade,
             disable_pleco,
             is_first_cluster_deployment,
+            prometheus_pushgateway_url,
         }
     }
 }
@@ -209,6 +220,7 @@ mod tests {
         assert_eq!(None, result.forced_upgrade);
         assert_eq!(None, result.disable_pleco);
         assert_eq!(None, result.dry_run_deploy);
+        assert_eq!(None, result.prometheus_pushgateway_url);
     }
 
     #[test]
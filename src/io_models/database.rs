@@ -1,12 +1,17 @@
 use crate::cloud_provider::kubernetes::Kind as KubernetesKind;
 use crate::cloud_provider::{service, CloudProvider, Kind as CPKind};
+use crate::io_models::application::SnapshotSchedule;
 use crate::io_models::context::Context;
+use crate::io_models::progress_listener::Listeners;
 use crate::io_models::Action;
 use crate::logger::Logger;
 use crate::models;
-use crate::models::database::{Container, DatabaseError, DatabaseService, Managed, MongoDB, MySQL, PostgresSQL, Redis};
+use crate::models::database::{
+    ClickHouse, Container, DatabaseError, DatabaseService, DatabaseType, Kafka, Managed, MongoDB, MySQL, OpenSearch,
+    PostgresSQL, RabbitMQ, Redis,
+};
 use crate::models::types::CloudProvider as CloudProviderTrait;
-use crate::models::types::{AWSEc2, VersionsNumber, AWS, DO, SCW};
+use crate::models::types::{AWSEc2, SelfManaged, VersionsNumber, AWS, DO, SCW};
 use core::result::Result;
 use core::result::Result::{Err, Ok};
 use serde::{Deserialize, Serialize};
@@ -41,10 +46,66 @@ pub struct Database {
     pub activate_high_availability: bool,
     #[serde(default)] // => false if not present in input
     pub activate_backups: bool,
+    /// Only used by container databases, managed databases backups are handled by the cloud provider.
+    /// Scheduled backups of container databases need an object storage able to issue credentials restricted
+    /// to the backups of the database, only AWS S3 does for now.
+    #[serde(default)]
+    pub backup_policy: DatabaseBackupPolicy,
+    /// Long id of the service this one is cloned from, when the environment is cloned
+    #[serde(default)]
+    pub clone_from_long_id: Option<Uuid>,
+    /// Only used by container databases: when the volumes of the database are snapshotted
+    #[serde(default)]
+    pub snapshot_schedule: SnapshotSchedule,
+    /// Snapshots older than this are deleted, 0 to keep them forever
+    #[serde(default)]
+    pub snapshot_retention_in_days: u16,
+    /// Only used by container PostgreSQL and MySQL: read-only replicas streaming from the primary
+    #[serde(default)]
+    pub read_replicas: u8,
+    /// Only used by container PostgreSQL and MySQL: PgBouncer or ProxySQL deployed in front of the database
+    #[serde(default)]
+    pub connection_pooling: Option<DatabaseConnectionPooling>,
     pub publicly_accessible: bool,
     pub mode: DatabaseMode,
 }
 
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug)]
+#[serde(default)]
+pub struct DatabaseBackupPolicy {
+    /// cron expression, i.e: `0 3 * * *` for every day at 3am UTC
+    pub schedule: String,
+    /// backups older than this are deleted
+    pub retention_in_days: u32,
+}
+
+impl Default for DatabaseBackupPolicy {
+    fn default() -> Self {
+        DatabaseBackupPolicy {
+            schedule: "0 3 * * *".to_string(),
+            retention_in_days: 7,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug)]
+#[serde(default)]
+pub struct DatabaseConnectionPooling {
+    /// connections opened by the pooler to each database instance
+    pub pool_size: u32,
+    /// connections the pooler accepts from applications
+    pub max_client_connections: u32,
+}
+
+impl Default for DatabaseConnectionPooling {
+    fn default() -> Self {
+        DatabaseConnectionPooling {
+            pool_size: 20,
+            max_client_connections: 1000,
+        }
+    }
+}
+
 impl Database {
     pub fn to_database_domain(
         &self,
@@ -63,9 +124,23 @@ impl Database {
             encrypt_disk: self.encrypt_disk,
             activate_high_availability: self.activate_high_availability,
             activate_backups: self.activate_backups,
+            backup_policy: self.backup_policy.clone(),
+            snapshot_schedule: self.snapshot_schedule.clone(),
+            snapshot_retention_in_days: self.snapshot_retention_in_days,
             publicly_accessible: self.publicly_accessible,
+            read_replicas: self.read_replicas,
+            connection_pooling: self.connection_pooling.clone(),
         };
 
+        let supports_replicas_and_pooling =
+            self.mode == DatabaseMode::CONTAINER && matches!(self.kind, DatabaseKind::Postgresql | DatabaseKind::Mysql);
+        if (self.read_replicas > 0 || self.connection_pooling.is_some()) && !supports_replicas_and_pooling {
+            return Err(DatabaseError::InvalidConfig(format!(
+                "read replicas and connection pooling are only available for container PostgreSQL and MySQL databases, not for {} databases",
+                self.kind.db_type().to_string()
+            )));
+        }
+
         let listeners = cloud_provider.listeners().clone();
         let version = VersionsNumber::from_str(self.version.as_str())
             .map_err(|_| DatabaseError::InvalidConfig(format!("Bad version number: {}", self.version)))?;
@@ -643,8 +718,301 @@ impl Database {
                 service::DatabaseType::MongoDB,
                 SCW::full_name().to_string(),
             )),
+
+            (CPKind::SelfManaged, DatabaseKind::Postgresql, DatabaseMode::CONTAINER) => {
+                let db = models::database::Database::<SelfManaged, Container, PostgresSQL>::new(
+                    context.clone(),
+                    self.long_id,
+                    self.action.to_service_action(),
+                    self.name.as_str(),
+                    version,
+                    self.fqdn.as_str(),
+                    self.fqdn_id.as_str(),
+                    self.total_cpus.clone(),
+                    self.total_ram_in_mib,
+                    self.database_instance_type.as_str(),
+                    database_options.publicly_accessible,
+                    database_options.port,
+                    database_options,
+                    listeners,
+                    logger,
+                )?;
+
+                Ok(Box::new(db))
+            }
+            (CPKind::SelfManaged, DatabaseKind::Mysql, DatabaseMode::CONTAINER) => {
+                let db = models::database::Database::<SelfManaged, Container, MySQL>::new(
+                    context.clone(),
+                    self.long_id,
+                    self.action.to_service_action(),
+                    self.name.as_str(),
+                    version,
+                    self.fqdn.as_str(),
+                    self.fqdn_id.as_str(),
+                    self.total_cpus.clone(),
+                    self.total_ram_in_mib,
+                    self.database_instance_type.as_str(),
+                    database_options.publicly_accessible,
+                    database_options.port,
+                    database_options,
+                    listeners,
+                    logger,
+                )?;
+
+                Ok(Box::new(db))
+            }
+            (CPKind::SelfManaged, DatabaseKind::Redis, DatabaseMode::CONTAINER) => {
+                let db = models::database::Database::<SelfManaged, Container, Redis>::new(
+                    context.clone(),
+                    self.long_id,
+                    self.action.to_service_action(),
+                    self.name.as_str(),
+                    version,
+                    self.fqdn.as_str(),
+                    self.fqdn_id.as_str(),
+                    self.total_cpus.clone(),
+                    self.total_ram_in_mib,
+                    self.database_instance_type.as_str(),
+                    database_options.publicly_accessible,
+                    database_options.port,
+                    database_options,
+                    listeners,
+                    logger,
+                )?;
+
+                Ok(Box::new(db))
+            }
+            (CPKind::SelfManaged, DatabaseKind::Mongodb, DatabaseMode::CONTAINER) => {
+                let db = models::database::Database::<SelfManaged, Container, MongoDB>::new(
+                    context.clone(),
+                    self.long_id,
+                    self.action.to_service_action(),
+                    self.name.as_str(),
+                    version,
+                    self.fqdn.as_str(),
+                    self.fqdn_id.as_str(),
+                    self.total_cpus.clone(),
+                    self.total_ram_in_mib,
+                    self.database_instance_type.as_str(),
+                    database_options.publicly_accessible,
+                    database_options.port,
+                    database_options,
+                    listeners,
+                    logger,
+                )?;
+
+                Ok(Box::new(db))
+            }
+            (CPKind::SelfManaged, DatabaseKind::Postgresql, DatabaseMode::MANAGED) => {
+                Err(DatabaseError::UnsupportedManagedMode(
+                    service::DatabaseType::PostgreSQL,
+                    SelfManaged::full_name().to_string(),
+                ))
+            }
+            (CPKind::SelfManaged, DatabaseKind::Mysql, DatabaseMode::MANAGED) => {
+                Err(DatabaseError::UnsupportedManagedMode(
+                    service::DatabaseType::MySQL,
+                    SelfManaged::full_name().to_string(),
+                ))
+            }
+            (CPKind::SelfManaged, DatabaseKind::Redis, DatabaseMode::MANAGED) => {
+                Err(DatabaseError::UnsupportedManagedMode(
+                    service::DatabaseType::Redis,
+                    SelfManaged::full_name().to_string(),
+                ))
+            }
+            (CPKind::SelfManaged, DatabaseKind::Mongodb, DatabaseMode::MANAGED) => {
+                Err(DatabaseError::UnsupportedManagedMode(
+                    service::DatabaseType::MongoDB,
+                    SelfManaged::full_name().to_string(),
+                ))
+            }
+
+            (CPKind::Aws, DatabaseKind::Opensearch, DatabaseMode::MANAGED) => {
+                if cloud_provider.kubernetes_kind() == KubernetesKind::Eks {
+                    self.to_database::<AWS, Managed, OpenSearch>(context, version, database_options, listeners, logger)
+                } else {
+                    self.to_database::<AWSEc2, Managed, OpenSearch>(
+                        context,
+                        version,
+                        database_options,
+                        listeners,
+                        logger,
+                    )
+                }
+            }
+            (CPKind::Aws, DatabaseKind::Opensearch, DatabaseMode::CONTAINER) => {
+                if cloud_provider.kubernetes_kind() == KubernetesKind::Eks {
+                    self.to_database::<AWS, Container, OpenSearch>(
+                        context,
+                        version,
+                        database_options,
+                        listeners,
+                        logger,
+                    )
+                } else {
+                    self.to_database::<AWSEc2, Container, OpenSearch>(
+                        context,
+                        version,
+                        database_options,
+                        listeners,
+                        logger,
+                    )
+                }
+            }
+            (CPKind::Aws, DatabaseKind::Clickhouse, DatabaseMode::CONTAINER) => {
+                if cloud_provider.kubernetes_kind() == KubernetesKind::Eks {
+                    self.to_database::<AWS, Container, ClickHouse>(
+                        context,
+                        version,
+                        database_options,
+                        listeners,
+                        logger,
+                    )
+                } else {
+                    self.to_database::<AWSEc2, Container, ClickHouse>(
+                        context,
+                        version,
+                        database_options,
+                        listeners,
+                        logger,
+                    )
+                }
+            }
+            (CPKind::Aws, DatabaseKind::Rabbitmq, DatabaseMode::CONTAINER) => {
+                if cloud_provider.kubernetes_kind() == KubernetesKind::Eks {
+                    self.to_database::<AWS, Container, RabbitMQ>(context, version, database_options, listeners, logger)
+                } else {
+                    self.to_database::<AWSEc2, Container, RabbitMQ>(
+                        context,
+                        version,
+                        database_options,
+                        listeners,
+                        logger,
+                    )
+                }
+            }
+            (CPKind::Aws, DatabaseKind::Kafka, DatabaseMode::CONTAINER) => {
+                if cloud_provider.kubernetes_kind() == KubernetesKind::Eks {
+                    self.to_database::<AWS, Container, Kafka>(context, version, database_options, listeners, logger)
+                } else {
+                    self.to_database::<AWSEc2, Container, Kafka>(context, version, database_options, listeners, logger)
+                }
+            }
+            (
+                CPKind::Aws,
+                DatabaseKind::Clickhouse | DatabaseKind::Rabbitmq | DatabaseKind::Kafka,
+                DatabaseMode::MANAGED,
+            ) => Err(DatabaseError::UnsupportedManagedMode(
+                self.kind.db_type(),
+                AWS::full_name().to_string(),
+            )),
+            (CPKind::Do, DatabaseKind::Opensearch, DatabaseMode::CONTAINER) => {
+                self.to_database::<DO, Container, OpenSearch>(context, version, database_options, listeners, logger)
+            }
+            (CPKind::Do, DatabaseKind::Clickhouse, DatabaseMode::CONTAINER) => {
+                self.to_database::<DO, Container, ClickHouse>(context, version, database_options, listeners, logger)
+            }
+            (CPKind::Do, DatabaseKind::Rabbitmq, DatabaseMode::CONTAINER) => {
+                self.to_database::<DO, Container, RabbitMQ>(context, version, database_options, listeners, logger)
+            }
+            (CPKind::Do, DatabaseKind::Kafka, DatabaseMode::CONTAINER) => {
+                self.to_database::<DO, Container, Kafka>(context, version, database_options, listeners, logger)
+            }
+            (
+                CPKind::Do,
+                DatabaseKind::Opensearch | DatabaseKind::Clickhouse | DatabaseKind::Rabbitmq | DatabaseKind::Kafka,
+                DatabaseMode::MANAGED,
+            ) => Err(DatabaseError::UnsupportedManagedMode(
+                self.kind.db_type(),
+                DO::full_name().to_string(),
+            )),
+            (CPKind::Scw, DatabaseKind::Opensearch, DatabaseMode::CONTAINER) => {
+                self.to_database::<SCW, Container, OpenSearch>(context, version, database_options, listeners, logger)
+            }
+            (CPKind::Scw, DatabaseKind::Clickhouse, DatabaseMode::CONTAINER) => {
+                self.to_database::<SCW, Container, ClickHouse>(context, version, database_options, listeners, logger)
+            }
+            (CPKind::Scw, DatabaseKind::Rabbitmq, DatabaseMode::CONTAINER) => {
+                self.to_database::<SCW, Container, RabbitMQ>(context, version, database_options, listeners, logger)
+            }
+            (CPKind::Scw, DatabaseKind::Kafka, DatabaseMode::CONTAINER) => {
+                self.to_database::<SCW, Container, Kafka>(context, version, database_options, listeners, logger)
+            }
+            (
+                CPKind::Scw,
+                DatabaseKind::Opensearch | DatabaseKind::Clickhouse | DatabaseKind::Rabbitmq | DatabaseKind::Kafka,
+                DatabaseMode::MANAGED,
+            ) => Err(DatabaseError::UnsupportedManagedMode(
+                self.kind.db_type(),
+                SCW::full_name().to_string(),
+            )),
+            (CPKind::SelfManaged, DatabaseKind::Opensearch, DatabaseMode::CONTAINER) => self
+                .to_database::<SelfManaged, Container, OpenSearch>(
+                    context,
+                    version,
+                    database_options,
+                    listeners,
+                    logger,
+                ),
+            (CPKind::SelfManaged, DatabaseKind::Clickhouse, DatabaseMode::CONTAINER) => self
+                .to_database::<SelfManaged, Container, ClickHouse>(
+                    context,
+                    version,
+                    database_options,
+                    listeners,
+                    logger,
+                ),
+            (CPKind::SelfManaged, DatabaseKind::Rabbitmq, DatabaseMode::CONTAINER) => self
+                .to_database::<SelfManaged, Container, RabbitMQ>(context, version, database_options, listeners, logger),
+            (CPKind::SelfManaged, DatabaseKind::Kafka, DatabaseMode::CONTAINER) => {
+                self.to_database::<SelfManaged, Container, Kafka>(context, version, database_options, listeners, logger)
+            }
+            (
+                CPKind::SelfManaged,
+                DatabaseKind::Opensearch | DatabaseKind::Clickhouse | DatabaseKind::Rabbitmq | DatabaseKind::Kafka,
+                DatabaseMode::MANAGED,
+            ) => Err(DatabaseError::UnsupportedManagedMode(
+                self.kind.db_type(),
+                SelfManaged::full_name().to_string(),
+            )),
         }
     }
+
+    fn to_database<C, M, T>(
+        &self,
+        context: &Context,
+        version: VersionsNumber,
+        database_options: DatabaseOptions,
+        listeners: Listeners,
+        logger: Box<dyn Logger>,
+    ) -> Result<Box<dyn DatabaseService>, DatabaseError>
+    where
+        C: CloudProviderTrait + 'static,
+        M: models::database::DatabaseMode + 'static,
+        T: DatabaseType<C, M, DatabaseOptions = DatabaseOptions> + 'static,
+        models::database::Database<C, M, T>: DatabaseService,
+    {
+        let db = models::database::Database::<C, M, T>::new(
+            context.clone(),
+            self.long_id,
+            self.action.to_service_action(),
+            self.name.as_str(),
+            version,
+            self.fqdn.as_str(),
+            self.fqdn_id.as_str(),
+            self.total_cpus.clone(),
+            self.total_ram_in_mib,
+            self.database_instance_type.as_str(),
+            database_options.publicly_accessible,
+            database_options.port,
+            database_options,
+            listeners,
+            logger,
+        )?;
+
+        Ok(Box::new(db))
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug)]
@@ -654,6 +1022,10 @@ pub enum DatabaseKind {
     Mysql,
     Mongodb,
     Redis,
+    Opensearch,
+    Clickhouse,
+    Rabbitmq,
+    Kafka,
 }
 
 impl DatabaseKind {
@@ -663,6 +1035,23 @@ impl DatabaseKind {
             DatabaseKind::Mysql => "mysql",
             DatabaseKind::Postgresql => "postgresql",
             DatabaseKind::Redis => "redis",
+            DatabaseKind::Opensearch => "opensearch",
+            DatabaseKind::Clickhouse => "clickhouse",
+            DatabaseKind::Rabbitmq => "rabbitmq",
+            DatabaseKind::Kafka => "kafka",
+        }
+    }
+
+    pub fn db_type(&self) -> service::DatabaseType {
+        match self {
+            DatabaseKind::Mongodb => service::DatabaseType::MongoDB,
+            DatabaseKind::Mysql => service::DatabaseType::MySQL,
+            DatabaseKind::Postgresql => service::DatabaseType::PostgreSQL,
+            DatabaseKind::Redis => service::DatabaseType::Redis,
+            DatabaseKind::Opensearch => service::DatabaseType::OpenSearch,
+            DatabaseKind::Clickhouse => service::DatabaseType::ClickHouse,
+            DatabaseKind::Rabbitmq => service::DatabaseType::RabbitMQ,
+            DatabaseKind::Kafka => service::DatabaseType::Kafka,
         }
     }
 }
@@ -679,5 +1068,10 @@ pub struct DatabaseOptions {
     pub encrypt_disk: bool,
     pub activate_high_availability: bool,
     pub activate_backups: bool,
+    pub backup_policy: DatabaseBackupPolicy,
+    pub snapshot_schedule: SnapshotSchedule,
+    pub snapshot_retention_in_days: u16,
     pub publicly_accessible: bool,
+    pub read_replicas: u8,
+    pub connection_pooling: Option<DatabaseConnectionPooling>,
 }
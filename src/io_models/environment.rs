@@ -1,18 +1,24 @@
-use crate::cloud_provider::environment::Environment;
+use crate::cloud_provider::environment::{Environment, EnvironmentCloneSource};
 use crate::cloud_provider::CloudProvider;
 use crate::container_registry::ContainerRegistry;
 use crate::io_models::application::Application;
 use crate::io_models::container::Container;
 use crate::io_models::context::Context;
 use crate::io_models::database::Database;
+use crate::io_models::job::Job;
 use crate::io_models::router::Router;
 use crate::io_models::Action;
 use crate::logger::Logger;
 use crate::models::application::ApplicationError;
 use crate::models::container::ContainerError;
 use crate::models::database::DatabaseError;
-use crate::models::router::RouterError;
+use crate::models::job::JobError;
+use crate::models::router::{RouterAdvancedSettings, RouterError};
 use serde::{Deserialize, Serialize};
+use std::cmp::max;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Duration;
 use uuid::Uuid;
 
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
@@ -24,9 +30,14 @@ pub struct EnvironmentRequest {
     pub action: Action,
     pub applications: Vec<Application>,
     pub containers: Vec<Container>,
+    #[serde(default)]
+    pub jobs: Vec<Job>,
     pub routers: Vec<Router>,
     pub databases: Vec<Database>,
     pub clone_from_environment_id: Option<String>,
+    /// scale the environment to zero after this many minutes without ingress traffic
+    #[serde(default)]
+    pub idle_scale_to_zero_after_minutes: Option<u32>,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -35,10 +46,14 @@ pub enum DomainError {
     ApplicationError(ApplicationError),
     #[error("Invalid container: {0}")]
     ContainerError(ContainerError),
+    #[error("Invalid job: {0}")]
+    JobError(JobError),
     #[error("Invalid router: {0}")]
     RouterError(RouterError),
     #[error("Invalid database: {0}")]
     DatabaseError(DatabaseError),
+    #[error("Invalid environment to clone from: {0}")]
+    CloneSourceError(String),
 }
 
 impl EnvironmentRequest {
@@ -49,6 +64,21 @@ impl EnvironmentRequest {
         container_registry: &dyn ContainerRegistry,
         logger: Box<dyn Logger>,
     ) -> Result<Environment, DomainError> {
+        let cloned_services = self.cloned_services();
+        let clone_source = match &self.clone_from_environment_id {
+            Some(id) => Some(
+                Uuid::from_str(id)
+                    .map(|long_id| EnvironmentCloneSource::new(long_id, &self.project_long_id, cloned_services.clone()))
+                    .map_err(|_| DomainError::CloneSourceError(id.to_string()))?,
+            ),
+            None => None,
+        };
+        // long id of the source service => long id of its clone
+        let clones_by_source: HashMap<Uuid, Uuid> = cloned_services
+            .into_iter()
+            .map(|(clone, source)| (source, clone))
+            .collect();
+
         let mut applications = Vec::with_capacity(self.applications.len());
         for app in &self.applications {
             match app.to_application_domain(
@@ -77,20 +107,44 @@ impl EnvironmentRequest {
             }
         }
 
+        let mut jobs = Vec::with_capacity(self.jobs.len());
+        for job in &self.jobs {
+            match job
+                .clone()
+                .to_job_domain(context, cloud_provider, container_registry, logger.clone())
+            {
+                Ok(job) => jobs.push(job),
+                Err(err) => {
+                    return Err(DomainError::JobError(err));
+                }
+            }
+        }
+
         let mut routers = Vec::with_capacity(self.routers.len());
         for router in &self.routers {
-            let mut custom_domain_check_enabled = true;
-            let mut whitelist_source_range = "0.0.0.0/0".to_string();
+            let router = match (&clone_source, router.clone_from_long_id) {
+                (Some(_), Some(_)) => router.to_cloned_router(&clones_by_source),
+                _ => router.clone(),
+            };
+            let mut advanced_settings = RouterAdvancedSettings::default();
 
             for app in &self.applications {
                 for route in &router.routes {
                     if route.service_long_id == app.long_id {
                         if !app.advanced_settings.deployment_custom_domain_check_enabled {
                             // disable custom domain check for this router
-                            custom_domain_check_enabled = false;
+                            advanced_settings.custom_domain_check_enabled = false;
+                        }
+                        if app.advanced_settings.deployment_custom_domain_certificate_required {
+                            advanced_settings.custom_domain_certificate_required = true;
                         }
 
-                        whitelist_source_range = app.advanced_settings.network_ingress_whitelist_source_range.clone();
+                        advanced_settings.whitelist_source_range =
+                            app.advanced_settings.network_ingress_whitelist_source_range.clone();
+                        advanced_settings.custom_domain_certificate_timeout_sec = max(
+                            advanced_settings.custom_domain_certificate_timeout_sec,
+                            app.advanced_settings.deployment_custom_domain_certificate_timeout_sec,
+                        );
                     }
                 }
             }
@@ -100,24 +154,30 @@ impl EnvironmentRequest {
                     if route.service_long_id == container.long_id {
                         if !container.advanced_settings.deployment_custom_domain_check_enabled {
                             // disable custom domain check for this router
-                            custom_domain_check_enabled = false;
+                            advanced_settings.custom_domain_check_enabled = false;
+                        }
+                        if container
+                            .advanced_settings
+                            .deployment_custom_domain_certificate_required
+                        {
+                            advanced_settings.custom_domain_certificate_required = true;
                         }
 
-                        whitelist_source_range = container
+                        advanced_settings.whitelist_source_range = container
                             .advanced_settings
                             .network_ingress_whitelist_source_range
                             .clone();
+                        advanced_settings.custom_domain_certificate_timeout_sec = max(
+                            advanced_settings.custom_domain_certificate_timeout_sec,
+                            container
+                                .advanced_settings
+                                .deployment_custom_domain_certificate_timeout_sec,
+                        );
                     }
                 }
             }
 
-            match router.to_router_domain(
-                context,
-                custom_domain_check_enabled,
-                whitelist_source_range,
-                cloud_provider,
-                logger.clone(),
-            ) {
+            match router.to_router_domain(context, advanced_settings, cloud_provider, logger.clone()) {
                 Ok(router) => routers.push(router),
                 Err(err) => {
                     return Err(DomainError::RouterError(err));
@@ -135,15 +195,39 @@ impl EnvironmentRequest {
             }
         }
 
-        Ok(Environment::new(
+        let mut environment = Environment::new(
             self.long_id,
             self.project_long_id,
             self.organization_long_id,
             self.action.to_service_action(),
             applications,
             containers,
+            jobs,
             routers,
             databases,
-        ))
+        );
+        environment.clone_source = clone_source;
+        environment.idle_timeout = self
+            .idle_scale_to_zero_after_minutes
+            .map(|minutes| Duration::from_secs(minutes as u64 * 60));
+
+        Ok(environment)
+    }
+
+    /// Long id of the cloned services => long id of the service they are cloned from
+    fn cloned_services(&self) -> HashMap<Uuid, Uuid> {
+        let applications = self.applications.iter().map(|x| (x.long_id, x.clone_from_long_id));
+        let containers = self.containers.iter().map(|x| (x.long_id, x.clone_from_long_id));
+        let routers = self.routers.iter().map(|x| (x.long_id, x.clone_from_long_id));
+        let databases = self.databases.iter().map(|x| (x.long_id, x.clone_from_long_id));
+        let jobs = self.jobs.iter().map(|x| (x.long_id, x.clone_from_long_id));
+
+        applications
+            .chain(containers)
+            .chain(routers)
+            .chain(databases)
+            .chain(jobs)
+            .filter_map(|(long_id, clone_from_long_id)| clone_from_long_id.map(|source| (long_id, source)))
+            .collect()
     }
 }
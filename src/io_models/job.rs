@@ -0,0 +1,262 @@
+use crate::cloud_provider::kubernetes::Kind as KubernetesKind;
+use crate::cloud_provider::{CloudProvider, Kind as CPKind};
+use crate::container_registry::ContainerRegistry;
+use crate::io_models::application::{
+    default_root_path_value, to_environment_variable, to_git_build, to_secret_environment_variable, GitCredentials,
+    SecretReference, Toleration,
+};
+use crate::io_models::container::Registry;
+use crate::io_models::context::Context;
+use crate::io_models::Action;
+use crate::logger::Logger;
+use crate::models;
+use crate::models::aws::AwsAppExtraSettings;
+use crate::models::aws_ec2::AwsEc2AppExtraSettings;
+use crate::models::job::{ImageSource, JobError, JobService};
+use crate::models::scaleway::ScwAppExtraSettings;
+use crate::models::self_managed::SelfManagedAppExtraSettings;
+use crate::models::types::CloudProvider as CP;
+use crate::models::types::{AWSEc2, SelfManaged, AWS, DO, SCW};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Hash)]
+pub enum JobSchedule {
+    /// Run once at each deployment of the environment (i.e: database migrations)
+    OnStart,
+    /// Run periodically, `schedule` being a cron expression (i.e: `*/5 * * * *`)
+    Cron { schedule: String },
+}
+
+/// What to do when a cron job run is scheduled while the previous one is still running
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum JobConcurrencyPolicy {
+    Allow,
+    #[default]
+    Forbid,
+    Replace,
+}
+
+impl JobConcurrencyPolicy {
+    pub fn to_k8s_concurrency_policy(&self) -> &str {
+        match self {
+            JobConcurrencyPolicy::Allow => "Allow",
+            JobConcurrencyPolicy::Forbid => "Forbid",
+            JobConcurrencyPolicy::Replace => "Replace",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
+pub enum JobSource {
+    Image {
+        registry: Registry,
+        image: String,
+        tag: String,
+    },
+    Docker {
+        git_url: String,
+        git_credentials: Option<GitCredentials>,
+        branch: String,
+        commit_id: String,
+        dockerfile_path: Option<String>,
+        #[serde(default = "default_root_path_value")]
+        root_path: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Hash)]
+#[serde(default)]
+pub struct JobAdvancedSettings {
+    // Build
+    #[serde(alias = "build.timeout_max_sec")]
+    pub build_timeout_max_sec: u32,
+
+    // Cron jobs
+    #[serde(alias = "cronjob.failed_jobs_history_limit")]
+    pub cronjob_failed_jobs_history_limit: u32,
+    #[serde(alias = "cronjob.success_jobs_history_limit")]
+    pub cronjob_success_jobs_history_limit: u32,
+
+    // Scheduling
+    /// labels of the nodes the job pods are scheduled on, `qovery.com/node-group-name` targets a node group
+    #[serde(alias = "deployment.node_selector")]
+    pub deployment_node_selector: BTreeMap<String, String>,
+    #[serde(alias = "deployment.tolerations")]
+    pub deployment_tolerations: Vec<Toleration>,
+}
+
+impl Default for JobAdvancedSettings {
+    fn default() -> Self {
+        JobAdvancedSettings {
+            build_timeout_max_sec: 30 * 60, // 30min
+            cronjob_failed_jobs_history_limit: 1,
+            cronjob_success_jobs_history_limit: 1,
+            deployment_node_selector: BTreeMap::new(),
+            deployment_tolerations: vec![],
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
+pub struct Job {
+    pub long_id: Uuid,
+    pub name: String,
+    pub action: Action,
+    pub schedule: JobSchedule,
+    pub source: JobSource,
+    pub command_args: Vec<String>,
+    pub entrypoint: Option<String>,
+    /// Number of retries before considering a run as failed
+    pub max_nb_restart: u32,
+    /// A run taking longer, retries included, is killed and considered as failed
+    pub max_duration_in_sec: u32,
+    /// Only used by cron jobs
+    #[serde(default)]
+    pub concurrency_policy: JobConcurrencyPolicy,
+    pub cpu_request_in_mili: u32,
+    pub cpu_limit_in_mili: u32,
+    pub ram_request_in_mib: u32,
+    pub ram_limit_in_mib: u32,
+    /// Key is a String, Value is a base64 encoded String
+    /// Use BTreeMap to get Hash trait which is not available on HashMap
+    pub environment_vars: BTreeMap<String, String>,
+    /// Key is a String, Value references the external secret holding its value
+    #[serde(default)]
+    pub secret_environment_vars: BTreeMap<String, SecretReference>,
+    #[serde(default)]
+    pub advanced_settings: JobAdvancedSettings,
+    /// Long ids of the services which must be deployed before this one
+    #[serde(default)]
+    pub depends_on: Vec<Uuid>,
+    /// Long id of the service this one is cloned from, when the environment is cloned
+    #[serde(default)]
+    pub clone_from_long_id: Option<Uuid>,
+}
+
+impl Job {
+    pub fn to_job_domain(
+        self,
+        context: &Context,
+        cloud_provider: &dyn CloudProvider,
+        default_container_registry: &dyn ContainerRegistry,
+        logger: Box<dyn Logger>,
+    ) -> Result<Box<dyn JobService>, JobError> {
+        let image_source = match &self.source {
+            JobSource::Image { registry, image, tag } => {
+                let mut registry = registry.clone();
+                // Default registry is a bit special as the core does not knows its url/credentials as it is retrieved
+                // by us with some tags
+                if registry.id() == default_container_registry.long_id() {
+                    registry.set_url(default_container_registry.registry_info().endpoint.clone());
+                }
+
+                ImageSource::Registry {
+                    registry: Box::new(registry),
+                    image: image.clone(),
+                    tag: tag.clone(),
+                }
+            }
+            JobSource::Docker {
+                git_url,
+                git_credentials,
+                commit_id,
+                dockerfile_path,
+                root_path,
+                ..
+            } => ImageSource::Build(Box::new(to_git_build(
+                &self.long_id,
+                &self.name,
+                git_url,
+                git_credentials.as_ref(),
+                commit_id,
+                dockerfile_path.as_deref(),
+                root_path,
+                None,
+                &self.environment_vars,
+                self.advanced_settings.build_timeout_max_sec,
+                default_container_registry.registry_info(),
+            ))),
+        };
+
+        let service: Box<dyn JobService> = match cloud_provider.kind() {
+            CPKind::Aws => {
+                if cloud_provider.kubernetes_kind() == KubernetesKind::Eks {
+                    Box::new(self.build_job::<AWS>(
+                        context,
+                        image_source,
+                        AwsAppExtraSettings {},
+                        cloud_provider,
+                        logger,
+                    )?)
+                } else {
+                    Box::new(self.build_job::<AWSEc2>(
+                        context,
+                        image_source,
+                        AwsEc2AppExtraSettings {},
+                        cloud_provider,
+                        logger,
+                    )?)
+                }
+            }
+            CPKind::Do => {
+                return Err(JobError::InvalidConfig(format!(
+                    "Unsupported cloud provider {} for job service",
+                    DO::full_name()
+                )));
+            }
+            CPKind::Scw => Box::new(self.build_job::<SCW>(
+                context,
+                image_source,
+                ScwAppExtraSettings {},
+                cloud_provider,
+                logger,
+            )?),
+            CPKind::SelfManaged => Box::new(self.build_job::<SelfManaged>(
+                context,
+                image_source,
+                SelfManagedAppExtraSettings {},
+                cloud_provider,
+                logger,
+            )?),
+        };
+
+        Ok(service)
+    }
+
+    fn build_job<T: CP>(
+        self,
+        context: &Context,
+        image_source: ImageSource,
+        extra_settings: T::AppExtraSettings,
+        cloud_provider: &dyn CloudProvider,
+        logger: Box<dyn Logger>,
+    ) -> Result<models::job::Job<T>, JobError> {
+        models::job::Job::<T>::new(
+            context.clone(),
+            self.long_id,
+            self.name,
+            self.action.to_service_action(),
+            self.schedule,
+            image_source,
+            self.command_args,
+            self.entrypoint,
+            self.max_nb_restart,
+            self.max_duration_in_sec,
+            self.concurrency_policy,
+            self.cpu_request_in_mili,
+            self.cpu_limit_in_mili,
+            self.ram_request_in_mib,
+            self.ram_limit_in_mib,
+            to_environment_variable(&self.environment_vars),
+            to_secret_environment_variable(&self.secret_environment_vars),
+            self.depends_on,
+            self.advanced_settings,
+            extra_settings,
+            cloud_provider.listeners().clone(),
+            logger,
+        )
+    }
+}
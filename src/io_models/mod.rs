@@ -10,6 +10,7 @@ pub mod context;
 pub mod database;
 pub mod domain;
 pub mod environment;
+pub mod job;
 pub mod progress_listener;
 pub mod router;
 
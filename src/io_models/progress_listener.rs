@@ -37,6 +37,7 @@ pub enum ProgressScope {
     Database { id: String },
     Application { id: String },
     Container { id: Uuid },
+    Job { id: Uuid },
     Router { id: String },
     Environment { id: String },
 }
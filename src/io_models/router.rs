@@ -9,8 +9,10 @@ use crate::models::aws_ec2::AwsEc2RouterExtraSettings;
 use crate::models::digital_ocean::DoRouterExtraSettings;
 use crate::models::router::{RouterAdvancedSettings, RouterError, RouterService};
 use crate::models::scaleway::ScwRouterExtraSettings;
-use crate::models::types::{AWSEc2, AWS, DO, SCW};
+use crate::models::self_managed::SelfManagedRouterExtraSettings;
+use crate::models::types::{AWSEc2, SelfManaged, AWS, DO, SCW};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use uuid::Uuid;
 
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
@@ -26,26 +28,71 @@ pub struct Router {
     pub sticky_sessions_enabled: bool,
     pub custom_domains: Vec<CustomDomain>,
     pub routes: Vec<Route>,
+    /// Long id of the service this one is cloned from, when the environment is cloned
+    #[serde(default)]
+    pub clone_from_long_id: Option<Uuid>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
 pub struct CustomDomain {
     pub domain: String,
     pub target_domain: String,
+    /// certificate to use instead of requesting one to Let's Encrypt
+    #[serde(default)]
+    pub certificate: Option<CustomDomainCertificate>,
+}
+
+/// User supplied TLS certificate of a custom domain, PEM encoded
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum CustomDomainCertificate {
+    Inline {
+        /// certificate chain, the certificate of the domain first
+        certificate: String,
+        private_key: String,
+    },
+    /// kv2 Vault secret holding `certificate` and `private_key` fields, stored in the folder of the organization: `<organization id>/...`
+    Vault { mount: String, path: String },
 }
 
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
 pub struct Route {
+    /// path prefix routed to the service
     pub path: String,
     pub service_long_id: Uuid,
+    /// the path prefix is replaced by this one before reaching the service, `/` strips it
+    #[serde(default)]
+    pub rewrite_path: Option<String>,
+    /// public port of the service to route to, its default port if not set
+    #[serde(default)]
+    pub service_port: Option<u16>,
 }
 
 impl Router {
+    /// Router of a cloned environment: routes targeting services of the environment being cloned are sent to
+    /// their clones, and custom domains are dropped as they still point to the environment being cloned.
+    ///
+    /// `cloned_services`: long id of the source service => long id of its clone
+    pub(super) fn to_cloned_router(&self, cloned_services: &HashMap<Uuid, Uuid>) -> Router {
+        Router {
+            custom_domains: vec![],
+            routes: self
+                .routes
+                .iter()
+                .map(|route| Route {
+                    service_long_id: *cloned_services
+                        .get(&route.service_long_id)
+                        .unwrap_or(&route.service_long_id),
+                    ..route.clone()
+                })
+                .collect(),
+            ..self.clone()
+        }
+    }
+
     pub fn to_router_domain(
         &self,
         context: &Context,
-        custom_domain_check_enabled: bool,
-        whitelist_source_range: String,
+        advanced_settings: RouterAdvancedSettings,
         cloud_provider: &dyn CloudProvider,
         logger: Box<dyn Logger>,
     ) -> Result<Box<dyn RouterService>, RouterError> {
@@ -55,6 +102,7 @@ impl Router {
             .map(|x| crate::cloud_provider::models::CustomDomain {
                 domain: x.domain.clone(),
                 target_domain: x.target_domain.clone(),
+                certificate: x.certificate.clone(),
             })
             .collect::<Vec<_>>();
 
@@ -64,15 +112,12 @@ impl Router {
             .map(|x| crate::cloud_provider::models::Route {
                 path: x.path.clone(),
                 service_long_id: x.service_long_id,
+                rewrite_path: x.rewrite_path.clone(),
+                service_port: x.service_port,
             })
             .collect::<Vec<_>>();
 
         let listeners = cloud_provider.listeners().clone();
-        let advanced_settings = RouterAdvancedSettings {
-            custom_domain_check_enabled,
-            whitelist_source_range,
-        };
-
         match cloud_provider.kind() {
             CPKind::Aws => {
                 // Note: we check if kubernetes is EC2 to map to the proper implementation
@@ -144,6 +189,78 @@ impl Router {
                 )?);
                 Ok(router)
             }
+            CPKind::SelfManaged => {
+                let router = Box::new(models::router::Router::<SelfManaged>::new(
+                    context.clone(),
+                    self.long_id,
+                    self.name.as_str(),
+                    self.action.to_service_action(),
+                    self.default_domain.as_str(),
+                    custom_domains,
+                    routes,
+                    self.sticky_sessions_enabled,
+                    SelfManagedRouterExtraSettings {},
+                    advanced_settings,
+                    listeners,
+                    logger,
+                )?);
+                Ok(router)
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::io_models::router::{CustomDomain, Route, Router};
+    use crate::io_models::Action;
+    use std::collections::HashMap;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_to_cloned_router() {
+        // setup:
+        let source_app = Uuid::new_v4();
+        let cloned_app = Uuid::new_v4();
+        let other_app = Uuid::new_v4();
+        let router = Router {
+            long_id: Uuid::new_v4(),
+            name: "router".to_string(),
+            action: Action::Create,
+            default_domain: "clone.example.com".to_string(),
+            public_port: 443,
+            sticky_sessions_enabled: false,
+            custom_domains: vec![CustomDomain {
+                domain: "www.example.com".to_string(),
+                target_domain: "source.example.com".to_string(),
+                certificate: None,
+            }],
+            routes: vec![
+                Route {
+                    path: "/".to_string(),
+                    service_long_id: source_app,
+                    rewrite_path: None,
+                    service_port: None,
+                },
+                Route {
+                    path: "/api".to_string(),
+                    service_long_id: other_app,
+                    rewrite_path: None,
+                    service_port: None,
+                },
+            ],
+            clone_from_long_id: Some(Uuid::new_v4()),
+        };
+
+        // execute:
+        let cloned_router = router.to_cloned_router(&HashMap::from([(source_app, cloned_app)]));
+
+        // verify:
+        assert!(cloned_router.custom_domains.is_empty());
+        assert_eq!(cloned_router.default_domain, "clone.example.com");
+        assert_eq!(cloned_router.routes[0].path, "/");
+        assert_eq!(cloned_router.routes[0].service_long_id, cloned_app);
+        assert_eq!(cloned_router.routes[1].path, "/api");
+        assert_eq!(cloned_router.routes[1].service_long_id, other_app);
+    }
+}
@@ -24,12 +24,16 @@ pub mod git;
 pub mod io_models;
 mod kubers_utils;
 pub mod logger;
+pub mod metrics;
 pub mod models;
 pub mod object_storage;
 pub mod runtime;
-mod secret_manager;
+pub mod secret_manager;
 mod string;
 mod template;
 pub mod transaction;
 mod unit_conversion;
 pub mod utilities;
+
+// run by the cluster agent, next to the environments it scales to zero
+pub use deployment_action::scale_to_zero;
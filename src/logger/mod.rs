@@ -1,3 +1,6 @@
+pub mod ndjson;
+pub mod otlp;
+
 use crate::events::{EngineEvent, EventMessageVerbosity};
 use tracing;
 
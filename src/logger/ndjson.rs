@@ -0,0 +1,244 @@
+use crate::events::{io, EngineEvent};
+use crate::logger::Logger;
+use std::fs::OpenOptions;
+use std::io::{BufWriter, Write};
+use std::net::TcpStream;
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::sync::mpsc::{channel, sync_channel, Receiver, Sender, SyncSender, TrySendError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Events waiting to be written, next ones are dropped while the writer is stalled (i.e a log shipper not reading)
+const MAX_PENDING_EVENTS: usize = 4096;
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(10);
+const FLUSH_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+enum WriteMessage {
+    Line(Vec<u8>),
+    Flush(Sender<()>),
+}
+
+/// NdJsonLogger: writes every event as a JSON document of `events::io::EngineEvent` on its own line,
+/// so log shippers can index them without parsing human readable lines.
+///
+/// Events are written by a background thread, so deployments are never blocked by a stalled file or socket.
+#[derive(Clone)]
+pub struct NdJsonLogger {
+    sender: SyncSender<WriteMessage>,
+}
+
+impl NdJsonLogger {
+    pub fn new(writer: Box<dyn Write + Send>) -> NdJsonLogger {
+        let (sender, receiver) = sync_channel(MAX_PENDING_EVENTS);
+        thread::Builder::new()
+            .name("ndjson-logger".to_string())
+            .spawn(move || write_lines(writer, receiver))
+            .expect("cannot spawn ndjson logger thread");
+
+        NdJsonLogger { sender }
+    }
+
+    /// Appends events to a file, created if it does not exist.
+    pub fn new_file(path: &Path) -> std::io::Result<NdJsonLogger> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(NdJsonLogger::new(Box::new(BufWriter::new(file))))
+    }
+
+    /// Streams events to a TCP socket, i.e a log shipper listening on `address`.
+    pub fn new_tcp_socket(address: &str) -> std::io::Result<NdJsonLogger> {
+        let stream = TcpStream::connect(address)?;
+        stream.set_nodelay(true)?;
+        Ok(NdJsonLogger::new(Box::new(stream)))
+    }
+
+    /// Streams events to a unix socket, i.e a log shipper running next to the engine.
+    #[cfg(unix)]
+    pub fn new_unix_socket(path: &Path) -> std::io::Result<NdJsonLogger> {
+        Ok(NdJsonLogger::new(Box::new(UnixStream::connect(path)?)))
+    }
+
+    /// Returns once every event received so far has been written.
+    /// To be called once an execution is done.
+    pub fn flush(&self) {
+        let (done_sender, done_receiver) = channel();
+        let deadline = Instant::now() + FLUSH_TIMEOUT;
+        let mut message = WriteMessage::Flush(done_sender);
+        // waits for room in the channel until the deadline, as the caller waits for the writer anyway
+        loop {
+            match self.sender.try_send(message) {
+                Ok(()) => break,
+                Err(TrySendError::Full(pending)) if Instant::now() < deadline => {
+                    message = pending;
+                    thread::sleep(FLUSH_RETRY_INTERVAL);
+                }
+                Err(TrySendError::Full(_)) => {
+                    error!("Cannot flush engine events, ndjson logger is stalled");
+                    return;
+                }
+                Err(TrySendError::Disconnected(_)) => {
+                    error!("Cannot flush engine events, ndjson logger thread is stopped");
+                    return;
+                }
+            }
+        }
+        let _ = done_receiver.recv_timeout(deadline.saturating_duration_since(Instant::now()));
+    }
+}
+
+/// JSON line of an event, terminated by a new line
+fn to_json_line(event: EngineEvent) -> serde_json::Result<Vec<u8>> {
+    let mut line = serde_json::to_vec(&io::EngineEvent::from(event))?;
+    line.push(b'\n');
+    Ok(line)
+}
+
+/// Writes lines until every logger has been dropped, a write error does not prevent next lines to be written
+fn write_lines(mut writer: Box<dyn Write + Send>, receiver: Receiver<WriteMessage>) {
+    for message in receiver {
+        match message {
+            WriteMessage::Line(line) => {
+                if let Err(err) = writer.write_all(&line).and_then(|_| writer.flush()) {
+                    error!("Cannot write engine event: {}", err);
+                }
+            }
+            WriteMessage::Flush(done) => {
+                let _ = done.send(());
+            }
+        }
+    }
+}
+
+impl Logger for NdJsonLogger {
+    fn log(&self, event: EngineEvent) {
+        let line = match to_json_line(event) {
+            Ok(line) => line,
+            Err(err) => {
+                error!("Cannot serialize engine event: {}", err);
+                return;
+            }
+        };
+
+        match self.sender.try_send(WriteMessage::Line(line)) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => error!("Cannot write engine event, ndjson logger is stalled"),
+            Err(TrySendError::Disconnected(_)) => error!("Cannot write engine event, ndjson logger thread is stopped"),
+        }
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Logger> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cloud_provider::Kind;
+    use crate::errors::{CommandError, EngineError};
+    use crate::events::{EnvironmentStep, EventDetails, EventMessage, Stage, Transmitter};
+    use crate::io_models::QoveryIdentifier;
+    use crate::models::scaleway::ScwRegion;
+    use std::fs;
+    use tempfile::tempdir;
+    use uuid::Uuid;
+
+    /// Writer of a log shipper which stopped reading, until `release` is dropped
+    struct StalledWriter {
+        release: Receiver<()>,
+    }
+
+    impl Write for StalledWriter {
+        fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+            let _ = self.release.recv();
+            Err(std::io::Error::new(std::io::ErrorKind::BrokenPipe, "stalled"))
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_log_writes_one_json_document_per_event() {
+        // setup:
+        let dir = tempdir().expect("cannot create temporary directory");
+        let path = dir.path().join("engine-events.ndjson");
+        let orga_id = QoveryIdentifier::new_random();
+        let execution_id = QoveryIdentifier::new_random().to_string();
+        let event_details = EventDetails::new(
+            Some(Kind::Scw),
+            orga_id.clone(),
+            QoveryIdentifier::new_random(),
+            execution_id.to_string(),
+            Some(ScwRegion::Paris.as_str().to_string()),
+            Stage::Environment(EnvironmentStep::Deploy),
+            Transmitter::Application(Uuid::new_v4(), "app".to_string(), "commit".to_string()),
+        );
+        let logger = NdJsonLogger::new_file(&path).expect("cannot create logger");
+
+        // execute:
+        logger.log(EngineEvent::Info(
+            event_details.clone(),
+            EventMessage::new("Raw message".to_string(), Some("Safe message".to_string())),
+        ));
+        logger.clone_dyn().log(EngineEvent::Error(
+            EngineError::new_unknown(
+                event_details,
+                "User message".to_string(),
+                Some(CommandError::new_from_safe_message("Safe message".to_string())),
+                None,
+                None,
+            ),
+            None,
+        ));
+        logger.flush();
+
+        // verify:
+        let content = fs::read_to_string(&path).expect("cannot read events");
+        let lines: Vec<serde_json::Value> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("cannot parse event"))
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["type"], "info");
+        assert_eq!(lines[0]["details"]["organization_id"], orga_id.to_string());
+        assert_eq!(lines[0]["details"]["execution_id"], execution_id);
+        assert_eq!(lines[0]["details"]["transmitter"]["type"], "application");
+        assert_eq!(lines[1]["type"], "error");
+        assert_eq!(lines[1]["error"]["event_details"]["execution_id"], execution_id);
+    }
+
+    #[test]
+    fn test_log_does_not_block_on_stalled_writer() {
+        // setup:
+        let (release_sender, release_receiver) = channel();
+        let event_details = EventDetails::new(
+            Some(Kind::Scw),
+            QoveryIdentifier::new_random(),
+            QoveryIdentifier::new_random(),
+            QoveryIdentifier::new_random().to_string(),
+            Some(ScwRegion::Paris.as_str().to_string()),
+            Stage::Environment(EnvironmentStep::Deploy),
+            Transmitter::Application(Uuid::new_v4(), "app".to_string(), "commit".to_string()),
+        );
+        let logger = NdJsonLogger::new(Box::new(StalledWriter {
+            release: release_receiver,
+        }));
+
+        // execute:
+        let start = Instant::now();
+        for _ in 0..MAX_PENDING_EVENTS * 2 {
+            logger.log(EngineEvent::Info(
+                event_details.clone(),
+                EventMessage::new_from_safe("Deploying".to_string()),
+            ));
+        }
+
+        // verify:
+        assert!(start.elapsed() < FLUSH_TIMEOUT);
+        drop(release_sender);
+        logger.flush();
+    }
+}
@@ -0,0 +1,536 @@
+use crate::events::{io, EngineEvent, EventDetails, EventMessageVerbosity, Stage};
+use crate::logger::Logger;
+use rand::Rng;
+use reqwest::blocking::Client;
+use serde_json::{json, Value};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::mpsc::{channel, sync_channel, Receiver, RecvTimeoutError, Sender, SyncSender, TrySendError};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use url::Url;
+use uuid::Uuid;
+
+const SERVICE_NAME: &str = "qovery-engine";
+const EXPORT_INTERVAL: Duration = Duration::from_secs(5);
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_BATCH_SIZE: usize = 512;
+/// Events waiting to be exported, next ones are dropped while the collector is slow to answer
+const MAX_PENDING_EVENTS: usize = 4096;
+const FLUSH_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+// https://opentelemetry.io/docs/reference/specification/logs/data-model/#field-severitynumber
+const SEVERITY_DEBUG: u8 = 5;
+const SEVERITY_INFO: u8 = 9;
+const SEVERITY_WARN: u8 = 13;
+const SEVERITY_ERROR: u8 = 17;
+const SPAN_STATUS_OK: u8 = 1;
+const SPAN_STATUS_ERROR: u8 = 2;
+const SPAN_KIND_INTERNAL: u8 = 1;
+
+enum ExportMessage {
+    Event(Box<EngineEvent>, SystemTime),
+    Flush(Sender<()>),
+}
+
+/// OtlpLogger: exports events to an OpenTelemetry collector, using OTLP over HTTP with JSON encoding.
+/// Every event is exported as a log record, and each step of a transmitter (i.e deploying an application) as a span,
+/// both carrying event details as attributes. All spans of an execution share the same trace.
+///
+/// Events are exported in batches by a background thread, so deployments are never slowed down by the collector.
+#[derive(Clone)]
+pub struct OtlpLogger {
+    sender: SyncSender<ExportMessage>,
+}
+
+impl OtlpLogger {
+    /// Creates a new OtlpLogger.
+    ///
+    /// Arguments
+    ///
+    /// * `endpoint`: Collector base url, logs are sent to `<endpoint>/v1/logs` and spans to `<endpoint>/v1/traces`.
+    /// * `headers`: Headers added to every export request, i.e to authenticate against the collector.
+    pub fn new(mut endpoint: Url, headers: Vec<(String, String)>) -> OtlpLogger {
+        // export paths are joined to the endpoint one, it must be kept as a base
+        if !endpoint.path().ends_with('/') {
+            endpoint.set_path(&format!("{}/", endpoint.path()));
+        }
+        let (sender, receiver) = sync_channel(MAX_PENDING_EVENTS);
+        thread::Builder::new()
+            .name("otlp-logger".to_string())
+            .spawn(move || {
+                // the blocking client must be created and dropped outside of any async runtime
+                let exporter = OtlpExporter {
+                    client: Client::new(),
+                    endpoint,
+                    headers,
+                    log_records: vec![],
+                    open_spans: HashMap::new(),
+                    closed_spans: vec![],
+                };
+                exporter.run(receiver)
+            })
+            .expect("cannot spawn otlp logger thread");
+
+        OtlpLogger { sender }
+    }
+
+    /// Closes spans still open and exports everything received so far, returns once the collector has been called.
+    /// To be called once an execution is done.
+    pub fn flush(&self) {
+        let (done_sender, done_receiver) = channel();
+        let deadline = Instant::now() + EXPORT_TIMEOUT * 2;
+        let mut message = ExportMessage::Flush(done_sender);
+        // waits for room in the channel until the deadline, as the caller waits for the export anyway
+        loop {
+            match self.sender.try_send(message) {
+                Ok(()) => break,
+                Err(TrySendError::Full(pending)) if Instant::now() < deadline => {
+                    message = pending;
+                    thread::sleep(FLUSH_RETRY_INTERVAL);
+                }
+                Err(TrySendError::Full(_)) => {
+                    error!("Cannot flush engine events, otlp logger is stalled");
+                    return;
+                }
+                Err(TrySendError::Disconnected(_)) => {
+                    error!("Cannot flush engine events, otlp logger thread is stopped");
+                    return;
+                }
+            }
+        }
+        let _ = done_receiver.recv_timeout(deadline.saturating_duration_since(Instant::now()));
+    }
+}
+
+impl Logger for OtlpLogger {
+    fn log(&self, event: EngineEvent) {
+        match self
+            .sender
+            .try_send(ExportMessage::Event(Box::new(event), SystemTime::now()))
+        {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => error!("Cannot export engine event, otlp logger is stalled"),
+            Err(TrySendError::Disconnected(_)) => error!("Cannot export engine event, otlp logger thread is stopped"),
+        }
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Logger> {
+        Box::new(self.clone())
+    }
+}
+
+struct OpenSpan {
+    span_id: String,
+    trace_id: String,
+    stage: Stage,
+    name: String,
+    attributes: Vec<Value>,
+    start: SystemTime,
+    end: SystemTime,
+    has_error: bool,
+}
+
+struct OtlpExporter {
+    client: Client,
+    endpoint: Url,
+    headers: Vec<(String, String)>,
+    log_records: Vec<Value>,
+    /// spans by transmitter, a span is closed once its transmitter moves to another step
+    open_spans: HashMap<String, OpenSpan>,
+    closed_spans: Vec<Value>,
+}
+
+impl OtlpExporter {
+    fn run(mut self, receiver: Receiver<ExportMessage>) {
+        let mut last_export = Instant::now();
+        loop {
+            match receiver.recv_timeout(EXPORT_INTERVAL) {
+                Ok(ExportMessage::Event(event, time)) => self.record(&event, time),
+                Ok(ExportMessage::Flush(done)) => {
+                    self.close_spans();
+                    self.export();
+                    last_export = Instant::now();
+                    let _ = done.send(());
+                    continue;
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                // every logger has been dropped
+                Err(RecvTimeoutError::Disconnected) => {
+                    self.close_spans();
+                    self.export();
+                    return;
+                }
+            }
+
+            if self.log_records.len() + self.closed_spans.len() >= MAX_BATCH_SIZE
+                || last_export.elapsed() >= EXPORT_INTERVAL
+            {
+                self.export();
+                last_export = Instant::now();
+            }
+        }
+    }
+
+    fn record(&mut self, event: &EngineEvent, time: SystemTime) {
+        let details = event.get_details();
+        let transmitter = details.transmitter().to_string();
+
+        if let Some(span) = self.open_spans.get(&transmitter) {
+            if span.stage != *details.stage() {
+                if let Some(span) = self.open_spans.remove(&transmitter) {
+                    self.closed_spans.push(span_record(span));
+                }
+            }
+        }
+        let span = self.open_spans.entry(transmitter).or_insert_with(|| OpenSpan {
+            span_id: span_id(),
+            trace_id: trace_id(details.execution_id()),
+            stage: details.stage().clone(),
+            name: format!("{}:{}", details.stage(), details.stage().sub_step_name()),
+            attributes: event_details_attributes(details),
+            start: time,
+            end: time,
+            has_error: false,
+        });
+        span.end = time;
+        span.has_error |= matches!(event, EngineEvent::Error(_, _));
+
+        self.log_records
+            .push(log_record(event, &span.trace_id, &span.span_id, time));
+    }
+
+    fn close_spans(&mut self) {
+        let spans: Vec<OpenSpan> = self.open_spans.drain().map(|(_, span)| span).collect();
+        self.closed_spans.extend(spans.into_iter().map(span_record));
+    }
+
+    fn export(&mut self) {
+        if !self.log_records.is_empty() {
+            let log_records: Vec<Value> = self.log_records.drain(..).collect();
+            self.post(
+                "v1/logs",
+                json!({ "resourceLogs": [{
+                    "resource": resource(),
+                    "scopeLogs": [{ "scope": { "name": SERVICE_NAME }, "logRecords": log_records }],
+                }]}),
+            );
+        }
+
+        if !self.closed_spans.is_empty() {
+            let spans: Vec<Value> = self.closed_spans.drain(..).collect();
+            self.post(
+                "v1/traces",
+                json!({ "resourceSpans": [{
+                    "resource": resource(),
+                    "scopeSpans": [{ "scope": { "name": SERVICE_NAME }, "spans": spans }],
+                }]}),
+            );
+        }
+    }
+
+    /// Export failures are only reported, events are not kept to not grow memory while the collector is down
+    fn post(&self, path: &str, body: Value) {
+        let url = match self.endpoint.join(path) {
+            Ok(url) => url,
+            Err(err) => {
+                error!("Invalid otlp endpoint {}: {}", self.endpoint, err);
+                return;
+            }
+        };
+        let mut request = self.client.post(url).timeout(EXPORT_TIMEOUT).json(&body);
+        for (name, value) in &self.headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+
+        match request.send() {
+            Ok(response) if response.status().is_success() => {}
+            Ok(response) => error!("Cannot export engine events to {}: {}", path, response.status()),
+            Err(err) => error!("Cannot export engine events to {}: {}", path, err),
+        }
+    }
+}
+
+fn resource() -> Value {
+    json!({ "attributes": [string_attribute("service.name", SERVICE_NAME)] })
+}
+
+fn string_attribute(key: &str, value: &str) -> Value {
+    json!({ "key": key, "value": { "stringValue": value } })
+}
+
+/// OTLP/JSON encodes 64 bits integers as strings
+fn unix_nanos(time: SystemTime) -> String {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+        .to_string()
+}
+
+/// Attributes of logs and spans, so they can be searched by organization, cluster, execution, stage and transmitter
+fn event_details_attributes(details: &EventDetails) -> Vec<Value> {
+    let mut attributes = vec![
+        string_attribute("qovery.organization_id", &details.organisation_id().to_string()),
+        string_attribute("qovery.cluster_id", &details.cluster_id().to_string()),
+        string_attribute("qovery.execution_id", details.execution_id()),
+        string_attribute("qovery.stage", &details.stage().to_string()),
+        string_attribute("qovery.step", &details.stage().sub_step_name()),
+    ];
+    if let Some(provider_kind) = details.provider_kind() {
+        attributes.push(string_attribute("qovery.provider", &provider_kind.to_string()));
+    }
+    if let Some(region) = details.region() {
+        attributes.push(string_attribute("qovery.region", &region));
+    }
+
+    // transmitter fields are the ones of its serialized form: type, id, name and version like fields
+    if let Ok(Value::Object(fields)) = serde_json::to_value(io::Transmitter::from(details.transmitter())) {
+        for (field, value) in fields {
+            let value = match value {
+                Value::String(value) => value,
+                value => value.to_string(),
+            };
+            attributes.push(string_attribute(&format!("qovery.transmitter.{}", field), &value));
+        }
+    }
+
+    attributes
+}
+
+fn log_record(event: &EngineEvent, trace_id: &str, span_id: &str, time: SystemTime) -> Value {
+    let (severity_number, severity_text) = match event {
+        EngineEvent::Debug(_, _) => (SEVERITY_DEBUG, "DEBUG"),
+        EngineEvent::Info(_, _) => (SEVERITY_INFO, "INFO"),
+        EngineEvent::Warning(_, _) => (SEVERITY_WARN, "WARN"),
+        EngineEvent::Error(_, _) => (SEVERITY_ERROR, "ERROR"),
+    };
+
+    json!({
+        "timeUnixNano": unix_nanos(time),
+        "severityNumber": severity_number,
+        "severityText": severity_text,
+        // events leave the engine, environment variables values must not be part of it
+        "body": { "stringValue": event.message(EventMessageVerbosity::FullDetailsWithoutEnvVars) },
+        "attributes": event_details_attributes(event.get_details()),
+        "traceId": trace_id,
+        "spanId": span_id,
+    })
+}
+
+fn span_record(span: OpenSpan) -> Value {
+    json!({
+        "traceId": span.trace_id,
+        "spanId": span.span_id,
+        "name": span.name,
+        "kind": SPAN_KIND_INTERNAL,
+        "startTimeUnixNano": unix_nanos(span.start),
+        "endTimeUnixNano": unix_nanos(span.end),
+        "attributes": span.attributes,
+        "status": { "code": if span.has_error { SPAN_STATUS_ERROR } else { SPAN_STATUS_OK } },
+    })
+}
+
+fn hash_of<T: Hash>(value: &T, seed: u64) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    seed.hash(&mut hasher);
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// 128 bits trace id of an execution, the execution id itself when it is an uuid
+fn trace_id(execution_id: &str) -> String {
+    match Uuid::parse_str(execution_id) {
+        Ok(uuid) => uuid.simple().to_string(),
+        Err(_) => format!("{:016x}{:016x}", hash_of(&execution_id, 0), hash_of(&execution_id, 1)),
+    }
+}
+
+/// Random 64 bits span id, unique even when a transmitter runs the same step twice in an execution.
+/// An all zeros span id is invalid.
+fn span_id() -> String {
+    format!("{:016x}", rand::thread_rng().gen_range(1..=u64::MAX))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cloud_provider::Kind;
+    use crate::errors::{CommandError, EngineError};
+    use crate::events::{EnvironmentStep, EventMessage, Transmitter};
+    use crate::io_models::QoveryIdentifier;
+    use crate::models::scaleway::ScwRegion;
+    use hyper::service::{make_service_fn, service_fn};
+    use hyper::{Body, Request, Response, Server};
+    use std::convert::Infallible;
+    use std::net::SocketAddr;
+    use std::sync::{Arc, Mutex};
+
+    type ReceivedRequests = Arc<Mutex<Vec<(String, Value)>>>;
+
+    /// Stand-in of an OTLP collector, keeping the requests it receives
+    fn start_collector(runtime: &tokio::runtime::Runtime) -> (SocketAddr, ReceivedRequests) {
+        let received: ReceivedRequests = Arc::new(Mutex::new(vec![]));
+        let requests = received.clone();
+        let make_service = make_service_fn(move |_| {
+            let requests = requests.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+                    let requests = requests.clone();
+                    async move {
+                        let path = request.uri().path().to_string();
+                        let body = hyper::body::to_bytes(request.into_body()).await.unwrap_or_default();
+                        let body = serde_json::from_slice(&body).unwrap_or(Value::Null);
+                        requests.lock().expect("cannot lock requests").push((path, body));
+                        Ok::<_, Infallible>(Response::new(Body::from("{}")))
+                    }
+                }))
+            }
+        });
+
+        let _guard = runtime.enter();
+        let server = Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(make_service);
+        let addr = server.local_addr();
+        runtime.spawn(server);
+        (addr, received)
+    }
+
+    fn attribute<'a>(attributes: &'a Value, key: &str) -> Option<&'a str> {
+        attributes
+            .as_array()?
+            .iter()
+            .find(|attribute| attribute["key"] == key)?["value"]["stringValue"]
+            .as_str()
+    }
+
+    #[test]
+    fn test_trace_id() {
+        let execution_id = Uuid::new_v4();
+
+        assert_eq!(trace_id(&execution_id.to_string()), execution_id.simple().to_string());
+        assert_eq!(trace_id("execution-1").len(), 32);
+        assert_eq!(trace_id("execution-1"), trace_id("execution-1"));
+        assert_ne!(trace_id("execution-1"), trace_id("execution-2"));
+    }
+
+    #[test]
+    fn test_span_id() {
+        let span_id = span_id();
+
+        assert_eq!(span_id.len(), 16);
+        assert_ne!(span_id, "0000000000000000");
+        assert_ne!(span_id, super::span_id());
+    }
+
+    #[test]
+    fn test_export_to_collector() {
+        // setup:
+        let runtime = tokio::runtime::Runtime::new().expect("cannot create runtime");
+        let (addr, received) = start_collector(&runtime);
+        let orga_id = QoveryIdentifier::new_random();
+        let cluster_id = QoveryIdentifier::new_random();
+        let execution_id = Uuid::new_v4();
+        let app_id = Uuid::new_v4();
+        let event_details = |step: EnvironmentStep| {
+            EventDetails::new(
+                Some(Kind::Scw),
+                orga_id.clone(),
+                cluster_id.clone(),
+                execution_id.to_string(),
+                Some(ScwRegion::Paris.as_str().to_string()),
+                Stage::Environment(step),
+                Transmitter::Application(app_id, "app".to_string(), "commit".to_string()),
+            )
+        };
+        let logger = OtlpLogger::new(Url::parse(&format!("http://{}", addr)).expect("cannot parse url"), vec![]);
+
+        // execute:
+        logger.log(EngineEvent::Info(
+            event_details(EnvironmentStep::Deploy),
+            EventMessage::new_from_safe("Deploying".to_string()),
+        ));
+        logger.log(EngineEvent::Error(
+            EngineError::new_unknown(
+                event_details(EnvironmentStep::Deploy),
+                "Cannot deploy".to_string(),
+                Some(CommandError::new_from_safe_message("Safe message".to_string())),
+                None,
+                None,
+            ),
+            None,
+        ));
+        logger.log(EngineEvent::Info(
+            event_details(EnvironmentStep::Delete),
+            EventMessage::new_from_safe("Deleting".to_string()),
+        ));
+        logger.log(EngineEvent::Info(
+            event_details(EnvironmentStep::Deploy),
+            EventMessage::new_from_safe("Deploying again".to_string()),
+        ));
+        logger.flush();
+
+        // verify:
+        let received = received.lock().expect("cannot lock requests");
+        let logs = received
+            .iter()
+            .find(|(path, _)| path == "/v1/logs")
+            .map(|(_, body)| body["resourceLogs"][0]["scopeLogs"][0]["logRecords"].clone())
+            .expect("no logs exported");
+        let spans = received
+            .iter()
+            .find(|(path, _)| path == "/v1/traces")
+            .map(|(_, body)| body["resourceSpans"][0]["scopeSpans"][0]["spans"].clone())
+            .expect("no spans exported");
+
+        let logs = logs.as_array().expect("log records should be an array");
+        assert_eq!(logs.len(), 4);
+        assert_eq!(logs[0]["severityText"], "INFO");
+        assert_eq!(logs[1]["severityText"], "ERROR");
+        assert_eq!(logs[0]["traceId"], execution_id.simple().to_string());
+        assert_eq!(logs[0]["spanId"], logs[1]["spanId"]);
+        assert_ne!(logs[0]["spanId"], logs[2]["spanId"]);
+        // the same step run twice is two spans
+        assert_ne!(logs[0]["spanId"], logs[3]["spanId"]);
+        assert_eq!(
+            attribute(&logs[0]["attributes"], "qovery.organization_id"),
+            Some(orga_id.to_string().as_str())
+        );
+        assert_eq!(
+            attribute(&logs[0]["attributes"], "qovery.cluster_id"),
+            Some(cluster_id.to_string().as_str())
+        );
+        assert_eq!(
+            attribute(&logs[0]["attributes"], "qovery.execution_id"),
+            Some(execution_id.to_string().as_str())
+        );
+        assert_eq!(attribute(&logs[0]["attributes"], "qovery.stage"), Some("environment"));
+        assert_eq!(attribute(&logs[2]["attributes"], "qovery.step"), Some("delete"));
+        assert_eq!(
+            attribute(&logs[0]["attributes"], "qovery.transmitter.type"),
+            Some("application")
+        );
+        assert_eq!(
+            attribute(&logs[0]["attributes"], "qovery.transmitter.id"),
+            Some(app_id.to_string().as_str())
+        );
+
+        let spans = spans.as_array().expect("spans should be an array");
+        assert_eq!(spans.len(), 3);
+        let deploy_span = spans
+            .iter()
+            .find(|span| span["spanId"] == logs[0]["spanId"])
+            .expect("no deploy span");
+        assert_eq!(deploy_span["name"], "environment:deploy");
+        assert_eq!(deploy_span["traceId"], execution_id.simple().to_string());
+        assert_eq!(deploy_span["status"]["code"], SPAN_STATUS_ERROR);
+        assert_eq!(
+            attribute(&deploy_span["attributes"], "qovery.execution_id"),
+            Some(execution_id.to_string().as_str())
+        );
+        let delete_span = spans
+            .iter()
+            .find(|span| span["spanId"] == logs[2]["spanId"])
+            .expect("no delete span");
+        assert_eq!(delete_span["status"]["code"], SPAN_STATUS_OK);
+    }
+}
@@ -0,0 +1,423 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use reqwest::blocking::Client;
+use tracing::field;
+use tracing::span::EnteredSpan;
+use url::Url;
+
+const PROMETHEUS_JOB_NAME: &str = "qovery-engine";
+
+thread_local! {
+    /// Timings of the execution running on the current thread
+    static CURRENT_TIMINGS: RefCell<Option<ExecutionTimings>> = RefCell::new(None);
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum TimingKind {
+    /// A stage of the transaction, i.e creating the cluster or deploying the environment
+    Stage,
+    /// An action on a service, i.e deploying an application
+    ServiceAction,
+    /// An external command, i.e helm or terraform
+    Command,
+}
+
+impl Display for TimingKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            TimingKind::Stage => "stage",
+            TimingKind::ServiceAction => "service_action",
+            TimingKind::Command => "command",
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Timing {
+    pub kind: TimingKind,
+    pub name: String,
+    pub duration: Duration,
+}
+
+/// Timings recorded during an execution, shared by all the threads working on it
+#[derive(Clone, Default)]
+pub struct ExecutionTimings {
+    timings: Arc<Mutex<Vec<Timing>>>,
+}
+
+impl ExecutionTimings {
+    /// Timings of the execution running on the current thread, if any
+    pub fn current() -> Option<ExecutionTimings> {
+        CURRENT_TIMINGS.with(|current| current.borrow().clone())
+    }
+
+    /// Record timers started on the current thread into these timings, until the guard is dropped.
+    /// Threads working on the execution must enter its timings too.
+    pub fn enter(&self) -> ExecutionTimingsGuard {
+        let previous = CURRENT_TIMINGS.with(|current| current.replace(Some(self.clone())));
+        ExecutionTimingsGuard { previous }
+    }
+
+    fn record(&self, timing: Timing) {
+        let mut timings = match self.timings.lock() {
+            Ok(timings) => timings,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        timings.push(timing);
+    }
+
+    pub fn report(&self, execution_id: &str, total: Duration) -> TimingReport {
+        let timings = match self.timings.lock() {
+            Ok(timings) => timings.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        };
+
+        TimingReport {
+            execution_id: execution_id.to_string(),
+            total,
+            timings,
+        }
+    }
+}
+
+pub struct ExecutionTimingsGuard {
+    previous: Option<ExecutionTimings>,
+}
+
+impl Drop for ExecutionTimingsGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        CURRENT_TIMINGS.with(|current| current.replace(previous));
+    }
+}
+
+/// StepTimer: opens a tracing span for a step of the execution, and records its duration once dropped
+pub struct StepTimer {
+    kind: TimingKind,
+    name: String,
+    started_at: Instant,
+    timings: Option<ExecutionTimings>,
+    span: EnteredSpan,
+}
+
+impl StepTimer {
+    pub fn start(kind: TimingKind, name: String) -> StepTimer {
+        // span names must be static
+        let span = match kind {
+            TimingKind::Stage => info_span!("stage", step = name.as_str(), elapsed_ms = field::Empty),
+            TimingKind::ServiceAction => {
+                info_span!("service_action", step = name.as_str(), elapsed_ms = field::Empty)
+            }
+            TimingKind::Command => info_span!("command", step = name.as_str(), elapsed_ms = field::Empty),
+        };
+
+        StepTimer {
+            kind,
+            name,
+            started_at: Instant::now(),
+            timings: ExecutionTimings::current(),
+            span: span.entered(),
+        }
+    }
+}
+
+impl Drop for StepTimer {
+    fn drop(&mut self) {
+        let duration = self.started_at.elapsed();
+        self.span.record("elapsed_ms", &(duration.as_millis() as u64));
+
+        if let Some(timings) = &self.timings {
+            timings.record(Timing {
+                kind: self.kind,
+                name: self.name.clone(),
+                duration,
+            });
+        }
+    }
+}
+
+/// Durations of the stages, service actions and commands of an execution
+pub struct TimingReport {
+    pub execution_id: String,
+    pub total: Duration,
+    pub timings: Vec<Timing>,
+}
+
+/// Total duration and count of the timings sharing the same kind and name
+#[derive(Default)]
+struct TimingSummary {
+    total: Duration,
+    max: Duration,
+    count: u32,
+}
+
+impl TimingReport {
+    fn summaries(&self) -> BTreeMap<(TimingKind, &str), TimingSummary> {
+        let mut summaries: BTreeMap<(TimingKind, &str), TimingSummary> = BTreeMap::new();
+        for timing in &self.timings {
+            let summary = summaries.entry((timing.kind, timing.name.as_str())).or_default();
+            summary.total += timing.duration;
+            summary.max = summary.max.max(timing.duration);
+            summary.count += 1;
+        }
+
+        summaries
+    }
+
+    /// Metrics in the Prometheus text exposition format
+    pub fn to_prometheus_text(&self) -> String {
+        let execution_id = escape_label_value(&self.execution_id);
+        let mut text = String::new();
+        text.push_str("# HELP qovery_engine_execution_duration_seconds Duration of the last engine execution\n");
+        text.push_str("# TYPE qovery_engine_execution_duration_seconds gauge\n");
+        text.push_str(&format!(
+            "qovery_engine_execution_duration_seconds{{execution_id=\"{}\"}} {}\n",
+            execution_id,
+            self.total.as_secs_f64()
+        ));
+
+        let summaries = self.summaries();
+        text.push_str("# HELP qovery_engine_step_duration_seconds Duration of the stages and service actions of the last engine execution\n");
+        text.push_str("# TYPE qovery_engine_step_duration_seconds gauge\n");
+        for ((kind, name), summary) in summaries.iter().filter(|((kind, _), _)| *kind != TimingKind::Command) {
+            text.push_str(&format!(
+                "qovery_engine_step_duration_seconds{{execution_id=\"{}\",kind=\"{}\",name=\"{}\"}} {}\n",
+                execution_id,
+                kind,
+                escape_label_value(name),
+                summary.total.as_secs_f64()
+            ));
+        }
+
+        text.push_str("# HELP qovery_engine_command_duration_seconds Time spent in external commands during the last engine execution\n");
+        text.push_str("# TYPE qovery_engine_command_duration_seconds summary\n");
+        for ((_, name), summary) in summaries.iter().filter(|((kind, _), _)| *kind == TimingKind::Command) {
+            let labels = format!("execution_id=\"{}\",command=\"{}\"", execution_id, escape_label_value(name));
+            text.push_str(&format!(
+                "qovery_engine_command_duration_seconds_sum{{{}}} {}\n",
+                labels,
+                summary.total.as_secs_f64()
+            ));
+            text.push_str(&format!(
+                "qovery_engine_command_duration_seconds_count{{{}}} {}\n",
+                labels, summary.count
+            ));
+        }
+
+        text
+    }
+
+    /// Push gateway group of the execution: executions running in parallel on the same cluster must not
+    /// replace each other metrics
+    fn prometheus_gateway_url(&self, gateway_url: &Url, cluster_id: &str) -> Result<Url, String> {
+        gateway_url
+            .join(&format!(
+                "metrics/job/{}/cluster_id/{}/execution_id/{}",
+                PROMETHEUS_JOB_NAME,
+                urlencoding::encode(cluster_id),
+                urlencoding::encode(&self.execution_id)
+            ))
+            .map_err(|err| format!("Invalid Prometheus push gateway url: {}", err))
+    }
+
+    /// Push the metrics to a Prometheus push gateway, in a group of their own per execution
+    pub fn push_to_prometheus_gateway(&self, gateway_url: &Url, cluster_id: &str) -> Result<(), String> {
+        let url = self.prometheus_gateway_url(gateway_url, cluster_id)?;
+
+        let response = Client::new()
+            .put(url)
+            .header("Content-Type", "text/plain; version=0.0.4")
+            .timeout(Duration::from_secs(10))
+            .body(self.to_prometheus_text())
+            .send()
+            .map_err(|err| format!("Cannot push metrics to Prometheus push gateway: {}", err))?;
+        if !response.status().is_success() {
+            return Err(format!("Cannot push metrics to Prometheus push gateway: {}", response.status()));
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for TimingReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "⏱ Execution {} took {}", self.execution_id, human_duration(self.total))?;
+
+        let summaries = self.summaries();
+        for (kind, title) in [
+            (TimingKind::Stage, "Stages"),
+            (TimingKind::ServiceAction, "Service actions"),
+            (TimingKind::Command, "Commands"),
+        ] {
+            // longest first, that is where the time goes
+            let mut lines: Vec<(&str, &TimingSummary)> = summaries
+                .iter()
+                .filter(|((timing_kind, _), _)| *timing_kind == kind)
+                .map(|((_, name), summary)| (*name, summary))
+                .collect();
+            if lines.is_empty() {
+                continue;
+            }
+            lines.sort_by(|(_, left), (_, right)| right.total.cmp(&left.total));
+
+            writeln!(f, "{}:", title)?;
+            for (name, summary) in lines {
+                match kind {
+                    TimingKind::Command => writeln!(
+                        f,
+                        "  {:<40} {:>10} ({} runs, longest {})",
+                        name,
+                        human_duration(summary.total),
+                        summary.count,
+                        human_duration(summary.max)
+                    )?,
+                    _ => writeln!(f, "  {:<40} {:>10}", name, human_duration(summary.total))?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn human_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs_f64();
+    if seconds < 60.0 {
+        return format!("{:.1}s", seconds);
+    }
+
+    let minutes = (seconds / 60.0).floor();
+    format!("{}m{:04.1}s", minutes, seconds - minutes * 60.0)
+}
+
+/// https://prometheus.io/docs/instrumenting/exposition_formats/#comments-help-text-and-type-information
+fn escape_label_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    fn report() -> TimingReport {
+        TimingReport {
+            execution_id: "execution-1".to_string(),
+            total: Duration::from_secs(1203),
+            timings: vec![
+                Timing {
+                    kind: TimingKind::Stage,
+                    name: "infrastructure:create".to_string(),
+                    duration: Duration::from_secs(1200),
+                },
+                Timing {
+                    kind: TimingKind::Command,
+                    name: "terraform apply".to_string(),
+                    duration: Duration::from_secs(900),
+                },
+                Timing {
+                    kind: TimingKind::Command,
+                    name: "helm upgrade".to_string(),
+                    duration: Duration::from_secs(40),
+                },
+                Timing {
+                    kind: TimingKind::Command,
+                    name: "helm upgrade".to_string(),
+                    duration: Duration::from_secs(20),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_timers_are_recorded_in_entered_timings() {
+        // setup:
+        let timings = ExecutionTimings::default();
+
+        // execute:
+        {
+            let _timings = timings.enter();
+            let _stage = StepTimer::start(TimingKind::Stage, "environment:deploy".to_string());
+            let thread_timings = ExecutionTimings::current();
+            thread::spawn(move || {
+                let _timings = thread_timings.as_ref().map(|timings| timings.enter());
+                let _command = StepTimer::start(TimingKind::Command, "helm upgrade".to_string());
+            })
+            .join()
+            .expect("timer thread has panicked");
+        }
+        let _not_recorded = StepTimer::start(TimingKind::Command, "kubectl get".to_string());
+
+        // verify:
+        assert!(ExecutionTimings::current().is_none());
+        let report = timings.report("execution-1", Duration::from_secs(1));
+        let recorded: Vec<(TimingKind, &str)> = report
+            .timings
+            .iter()
+            .map(|timing| (timing.kind, timing.name.as_str()))
+            .collect();
+        assert_eq!(
+            recorded,
+            vec![
+                (TimingKind::Command, "helm upgrade"),
+                (TimingKind::Stage, "environment:deploy")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_report_display() {
+        let report = report().to_string();
+
+        assert!(report.starts_with("⏱ Execution execution-1 took 20m03.0s\n"));
+        assert!(report.contains("Stages:\n  infrastructure:create"));
+        // longest commands first
+        let terraform = report.find("terraform apply").expect("no terraform timing");
+        let helm = report.find("helm upgrade").expect("no helm timing");
+        assert!(terraform < helm);
+        assert!(report.contains("1m00.0s (2 runs, longest 40.0s)"));
+        assert!(!report.contains("Service actions:"));
+    }
+
+    #[test]
+    fn test_report_to_prometheus_text() {
+        let text = report().to_prometheus_text();
+
+        assert!(text.contains("qovery_engine_execution_duration_seconds{execution_id=\"execution-1\"} 1203\n"));
+        assert!(text.contains(
+            "qovery_engine_step_duration_seconds{execution_id=\"execution-1\",kind=\"stage\",name=\"infrastructure:create\"} 1200\n"
+        ));
+        assert!(text.contains(
+            "qovery_engine_command_duration_seconds_sum{execution_id=\"execution-1\",command=\"helm upgrade\"} 60\n"
+        ));
+        assert!(text.contains(
+            "qovery_engine_command_duration_seconds_count{execution_id=\"execution-1\",command=\"helm upgrade\"} 2\n"
+        ));
+    }
+
+    #[test]
+    fn test_prometheus_gateway_url() {
+        // setup:
+        let gateway_url = Url::parse("http://pushgateway.prometheus.svc:9091/").expect("invalid url");
+
+        // execute:
+        let url = report()
+            .prometheus_gateway_url(&gateway_url, "cluster-1")
+            .expect("cannot build url");
+
+        // verify:
+        assert_eq!(
+            url.as_str(),
+            "http://pushgateway.prometheus.svc:9091/metrics/job/qovery-engine/cluster_id/cluster-1/execution_id/execution-1"
+        );
+    }
+
+    #[test]
+    fn test_escape_label_value() {
+        assert_eq!(escape_label_value("a \"b\"\\c\n"), "a \\\"b\\\"\\\\c\\n");
+    }
+}
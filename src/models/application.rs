@@ -1,16 +1,21 @@
 use crate::build_platform::Build;
 use crate::cloud_provider::environment::Environment;
 use crate::cloud_provider::kubernetes::Kubernetes;
-use crate::cloud_provider::models::{EnvironmentVariable, EnvironmentVariableDataTemplate, Storage};
+use crate::cloud_provider::models::{
+    EnvironmentVariable, EnvironmentVariableDataTemplate, SecretEnvironmentVariable, Storage,
+};
 use crate::cloud_provider::service::{Action, Service, ServiceType};
 use crate::cloud_provider::utilities::sanitize_name;
+use crate::cloud_provider::DeploymentTarget;
 use crate::deployment_action::DeploymentAction;
+use crate::errors::EngineError;
 use crate::events::{EventDetails, Stage, Transmitter};
 use crate::io_models::application::{AdvancedSettingsProbeType, ApplicationAdvancedSettings, Port};
 use crate::io_models::context::Context;
 use crate::io_models::progress_listener::{Listener, Listeners};
 use crate::io_models::QoveryIdentifier;
 use crate::logger::Logger;
+use crate::models::deployment_strategy::DeploymentStrategy;
 use crate::models::types::{CloudProvider, ToTeraContext};
 use crate::utilities::to_short_id;
 use itertools::Itertools;
@@ -40,9 +45,12 @@ pub struct Application<T: CloudProvider> {
     pub(super) build: Build,
     pub(super) storage: Vec<Storage<T::StorageTypes>>,
     pub(super) environment_variables: Vec<EnvironmentVariable>,
+    pub(super) secret_environment_variables: Vec<SecretEnvironmentVariable>,
+    pub(super) depends_on: Vec<Uuid>,
     pub(super) listeners: Listeners,
     pub(super) logger: Box<dyn Logger>,
     pub(super) advanced_settings: ApplicationAdvancedSettings,
+    pub(super) deployment_strategy: DeploymentStrategy,
     pub(super) _extra_settings: T::AppExtraSettings,
 }
 
@@ -62,12 +70,23 @@ impl<T: CloudProvider> Application<T> {
         build: Build,
         storage: Vec<Storage<T::StorageTypes>>,
         environment_variables: Vec<EnvironmentVariable>,
+        secret_environment_variables: Vec<SecretEnvironmentVariable>,
+        depends_on: Vec<Uuid>,
         advanced_settings: ApplicationAdvancedSettings,
         extra_settings: T::AppExtraSettings,
         listeners: Listeners,
         logger: Box<dyn Logger>,
     ) -> Result<Self, ApplicationError> {
         // TODO: Check that the information provided are coherent
+        let deployment_strategy = DeploymentStrategy::new(
+            advanced_settings.deployment_strategy_type,
+            advanced_settings.deployment_strategy_max_surge_percent,
+            advanced_settings.deployment_strategy_max_unavailable_percent,
+            advanced_settings.deployment_strategy_canary_weight_percent,
+            advanced_settings.deployment_strategy_analysis_duration_sec,
+            advanced_settings.deployment_strategy_max_error_rate_percent,
+        )
+        .map_err(ApplicationError::InvalidConfig)?;
 
         Ok(Self {
             _marker: PhantomData,
@@ -85,9 +104,12 @@ impl<T: CloudProvider> Application<T> {
             build,
             storage,
             environment_variables,
+            secret_environment_variables,
+            depends_on,
             listeners,
             logger,
             advanced_settings,
+            deployment_strategy,
             _extra_settings: extra_settings,
         })
     }
@@ -108,6 +130,29 @@ impl<T: CloudProvider> Application<T> {
         )
     }
 
+    /// Release used to deploy the new version next to the current one, for blue/green and canary deployments
+    pub fn preview_helm_release_name(&self) -> String {
+        format!("application-{}-preview", self.id())
+    }
+
+    pub fn preview_sanitized_name(&self) -> String {
+        format!("{}-preview", self.sanitized_name())
+    }
+
+    /// Same chart as the application, but with resources named after the preview and pods which are not selected by
+    /// the application service
+    pub fn to_preview_tera_context(&self, target: &DeploymentTarget) -> Result<TeraContext, EngineError>
+    where
+        Self: ToTeraContext,
+    {
+        let mut context = self.to_tera_context(target)?;
+        context.insert("sanitized_name", &self.preview_sanitized_name());
+        context.insert("is_preview", &true);
+        context.insert("service_id_label", "qovery.com/preview-service-id");
+
+        Ok(context)
+    }
+
     fn public_ports(&self) -> impl Iterator<Item = &Port> + '_ {
         self.ports.iter().filter(|port| port.publicly_accessible)
     }
@@ -137,6 +182,21 @@ impl<T: CloudProvider> Application<T> {
             "hpa_cpu_average_utilization_percent",
             &self.advanced_settings.hpa_cpu_average_utilization_percent,
         );
+        context.insert("hpa_metrics", &self.advanced_settings.hpa_metrics);
+        context.insert("hpa_scale_up_behavior", &self.advanced_settings.hpa_scale_up_behavior);
+        context.insert("hpa_scale_down_behavior", &self.advanced_settings.hpa_scale_down_behavior);
+        context.insert("node_selector", &self.advanced_settings.deployment_node_selector);
+        context.insert("tolerations", &self.advanced_settings.deployment_tolerations);
+        context.insert(
+            "deployment_strategy_max_surge_percent",
+            &self.advanced_settings.deployment_strategy_max_surge_percent,
+        );
+        context.insert(
+            "deployment_strategy_max_unavailable_percent",
+            &self.advanced_settings.deployment_strategy_max_unavailable_percent,
+        );
+        context.insert("is_preview", &false);
+        context.insert("service_id_label", "qovery.com/service-id");
 
         if let Some(default_port) = self.ports.iter().find(|p| p.is_default) {
             context.insert("is_private_port", &true);
@@ -270,10 +330,30 @@ impl<T: CloudProvider> Application<T> {
         !self.storage.is_empty()
     }
 
+    pub fn is_autoscaled(&self) -> bool {
+        !self.is_stateful() && self.min_instances != self.max_instances
+    }
+
+    pub fn advanced_settings(&self) -> &ApplicationAdvancedSettings {
+        &self.advanced_settings
+    }
+
+    pub fn storages(&self) -> &[Storage<T::StorageTypes>] {
+        &self.storage
+    }
+
     pub fn context(&self) -> &Context {
         &self.context
     }
 
+    pub fn environment_variables(&self) -> &[EnvironmentVariable] {
+        &self.environment_variables
+    }
+
+    pub fn secret_environment_variables(&self) -> &[SecretEnvironmentVariable] {
+        &self.secret_environment_variables
+    }
+
     pub fn service_type(&self) -> ServiceType {
         ServiceType::Application
     }
@@ -326,10 +406,18 @@ impl<T: CloudProvider> Application<T> {
         format!("appId={}", self.id())
     }
 
+    pub fn preview_selector(&self) -> String {
+        format!("qovery.com/preview-service-id={}", self.long_id)
+    }
+
     pub fn build(&self) -> &Build {
         &self.build
     }
 
+    pub fn deployment_strategy(&self) -> &DeploymentStrategy {
+        &self.deployment_strategy
+    }
+
     pub fn build_mut(&mut self) -> &mut Build {
         &mut self.build
     }
@@ -415,6 +503,9 @@ pub trait ApplicationService: Service + DeploymentAction + ToTeraContext {
     fn get_build_mut(&mut self) -> &mut Build;
     fn public_ports(&self) -> Vec<&Port>;
     fn advanced_settings(&self) -> &ApplicationAdvancedSettings;
+    fn helm_release_name(&self) -> String;
+    /// services (by long id) which must be deployed before this application
+    fn depends_on(&self) -> &[Uuid];
     fn startup_timeout(&self) -> std::time::Duration {
         let settings = self.advanced_settings();
         let readiness_probe_timeout = settings.readiness_probe_initial_delay_seconds
@@ -448,4 +539,12 @@ where
     fn advanced_settings(&self) -> &ApplicationAdvancedSettings {
         &self.advanced_settings
     }
+
+    fn helm_release_name(&self) -> String {
+        self.helm_release_name()
+    }
+
+    fn depends_on(&self) -> &[Uuid] {
+        &self.depends_on
+    }
 }
@@ -1,9 +1,8 @@
 use crate::cloud_provider::models::StorageDataTemplate;
 use crate::cloud_provider::DeploymentTarget;
 use crate::errors::EngineError;
-use crate::models::aws::AwsStorageType;
 use crate::models::container::Container;
-use crate::models::types::{ToTeraContext, AWS};
+use crate::models::types::{ToK8sStorageClass, ToTeraContext, AWS};
 use tera::Context as TeraContext;
 
 impl ToTeraContext for Container<AWS> {
@@ -16,13 +15,7 @@ impl ToTeraContext for Container<AWS> {
                 id: s.id.clone(),
                 long_id: self.long_id,
                 name: s.name.clone(),
-                storage_type: match s.storage_type {
-                    AwsStorageType::SC1 => "aws-ebs-sc1-0",
-                    AwsStorageType::ST1 => "aws-ebs-st1-0",
-                    AwsStorageType::GP2 => "aws-ebs-gp2-0",
-                    AwsStorageType::IO1 => "aws-ebs-io1-0",
-                }
-                .to_string(),
+                storage_type: s.storage_type.to_k8s_storage_class().to_string(),
                 size_in_gib: s.size_in_gib,
                 mount_point: s.mount_point.clone(),
                 snapshot_retention_in_days: s.snapshot_retention_in_days,
@@ -2,13 +2,18 @@ use crate::cloud_provider::service::{
     check_service_version, default_tera_context, get_tfstate_name, get_tfstate_suffix, Service,
     ServiceVersionCheckResult,
 };
+use crate::cloud_provider::utilities::opensearch_domain_name;
 use crate::cloud_provider::{service, DeploymentTarget};
-use crate::errors::EngineError;
+use crate::errors::{CommandError, EngineError};
 use crate::events::{EnvironmentStep, EventDetails, Stage};
 use crate::models::aws::database_utils::{
-    get_managed_mongodb_version, get_managed_mysql_version, get_managed_postgres_version, get_managed_redis_version,
+    get_managed_mongodb_version, get_managed_mysql_version, get_managed_opensearch_version,
+    get_managed_postgres_version, get_managed_redis_version,
+};
+use crate::models::database::{
+    ClickHouse, Container, Database, DatabaseType, Kafka, Managed, MongoDB, MySQL, OpenSearch, PostgresSQL, RabbitMQ,
+    Redis,
 };
-use crate::models::database::{Container, Database, DatabaseType, Managed, MongoDB, MySQL, PostgresSQL, Redis};
 
 use crate::io_models::database::DatabaseOptions;
 use crate::models::types::{ToTeraContext, AWS};
@@ -165,6 +170,144 @@ impl DatabaseType<AWS, Container> for MongoDB {
     }
 }
 
+impl DatabaseType<AWS, Container> for OpenSearch {
+    type DatabaseOptions = DatabaseOptions;
+
+    fn short_name() -> &'static str {
+        "OpenSearch"
+    }
+    fn lib_directory_name() -> &'static str {
+        "opensearch"
+    }
+    fn db_type() -> service::DatabaseType {
+        service::DatabaseType::OpenSearch
+    }
+
+    fn cpu_validate(desired_cpu: String) -> String {
+        // todo: update core side to avoid passing String and keep u32 #ENG-1277
+        let cpu_size = cpu_string_to_float(desired_cpu.clone());
+        if cpu_size < 0.25 {
+            // todo: return an error instead?
+            "250m".to_string()
+        } else {
+            desired_cpu
+        }
+    }
+
+    // the JVM heap is half of the container memory, lower than 1GiB OpenSearch fails its bootstrap checks
+    fn memory_validate(desired_memory: u32) -> u32 {
+        if desired_memory < 1024 {
+            // todo: return an error instead?
+            1024
+        } else {
+            desired_memory
+        }
+    }
+}
+
+impl DatabaseType<AWS, Container> for ClickHouse {
+    type DatabaseOptions = DatabaseOptions;
+
+    fn short_name() -> &'static str {
+        "ClickHouse"
+    }
+    fn lib_directory_name() -> &'static str {
+        "clickhouse"
+    }
+    fn db_type() -> service::DatabaseType {
+        service::DatabaseType::ClickHouse
+    }
+
+    fn cpu_validate(desired_cpu: String) -> String {
+        // todo: update core side to avoid passing String and keep u32 #ENG-1277
+        let cpu_size = cpu_string_to_float(desired_cpu.clone());
+        if cpu_size < 0.25 {
+            // todo: return an error instead?
+            "250m".to_string()
+        } else {
+            desired_cpu
+        }
+    }
+
+    fn memory_validate(desired_memory: u32) -> u32 {
+        if desired_memory < 512 {
+            // todo: return an error instead?
+            512
+        } else {
+            desired_memory
+        }
+    }
+}
+
+impl DatabaseType<AWS, Container> for RabbitMQ {
+    type DatabaseOptions = DatabaseOptions;
+
+    fn short_name() -> &'static str {
+        "RabbitMQ"
+    }
+    fn lib_directory_name() -> &'static str {
+        "rabbitmq"
+    }
+    fn db_type() -> service::DatabaseType {
+        service::DatabaseType::RabbitMQ
+    }
+
+    fn cpu_validate(desired_cpu: String) -> String {
+        // todo: update core side to avoid passing String and keep u32 #ENG-1277
+        let cpu_size = cpu_string_to_float(desired_cpu.clone());
+        if cpu_size < 0.25 {
+            // todo: return an error instead?
+            "250m".to_string()
+        } else {
+            desired_cpu
+        }
+    }
+
+    fn memory_validate(desired_memory: u32) -> u32 {
+        if desired_memory < 256 {
+            // todo: return an error instead?
+            256
+        } else {
+            desired_memory
+        }
+    }
+}
+
+impl DatabaseType<AWS, Container> for Kafka {
+    type DatabaseOptions = DatabaseOptions;
+
+    fn short_name() -> &'static str {
+        "Kafka"
+    }
+    fn lib_directory_name() -> &'static str {
+        "kafka"
+    }
+    fn db_type() -> service::DatabaseType {
+        service::DatabaseType::Kafka
+    }
+
+    fn cpu_validate(desired_cpu: String) -> String {
+        // todo: update core side to avoid passing String and keep u32 #ENG-1277
+        let cpu_size = cpu_string_to_float(desired_cpu.clone());
+        if cpu_size < 0.25 {
+            // todo: return an error instead?
+            "250m".to_string()
+        } else {
+            desired_cpu
+        }
+    }
+
+    // the JVM heap is half of the container memory, lower than 512MiB the broker is OOM killed on startup
+    fn memory_validate(desired_memory: u32) -> u32 {
+        if desired_memory < 512 {
+            // todo: return an error instead?
+            512
+        } else {
+            desired_memory
+        }
+    }
+}
+
 /////////////////////////////////////////////////////////////////
 // MANAGED
 impl DatabaseType<AWS, Managed> for PostgresSQL {
@@ -228,19 +371,43 @@ impl DatabaseType<AWS, Managed> for MongoDB {
     }
 }
 
+impl DatabaseType<AWS, Managed> for OpenSearch {
+    type DatabaseOptions = DatabaseOptions;
+
+    fn short_name() -> &'static str {
+        "OpenSearch Service"
+    }
+
+    fn lib_directory_name() -> &'static str {
+        "opensearch"
+    }
+
+    fn db_type() -> service::DatabaseType {
+        service::DatabaseType::OpenSearch
+    }
+}
+
 impl<T: DatabaseType<AWS, Managed>> Database<AWS, Managed, T>
 where
     Database<AWS, Managed, T>: Service,
 {
     fn get_version_aws_managed(&self, event_details: EventDetails) -> Result<ServiceVersionCheckResult, EngineError> {
-        let fn_version = match T::db_type() {
-            service::DatabaseType::PostgreSQL => get_managed_postgres_version,
-            service::DatabaseType::MongoDB => get_managed_mongodb_version,
-            service::DatabaseType::MySQL => get_managed_mysql_version,
-            service::DatabaseType::Redis => get_managed_redis_version,
+        let requested_version = self.version.to_string();
+        let version = match T::db_type() {
+            service::DatabaseType::PostgreSQL => get_managed_postgres_version(requested_version),
+            service::DatabaseType::MongoDB => get_managed_mongodb_version(requested_version),
+            service::DatabaseType::MySQL => get_managed_mysql_version(requested_version),
+            service::DatabaseType::Redis => get_managed_redis_version(requested_version),
+            service::DatabaseType::OpenSearch => get_managed_opensearch_version(requested_version),
+            service::DatabaseType::ClickHouse | service::DatabaseType::RabbitMQ | service::DatabaseType::Kafka => {
+                Err(CommandError::new_from_safe_message(format!(
+                    "{} is not available as a managed database",
+                    T::db_type().to_string()
+                )))
+            }
         };
 
-        check_service_version(fn_version(self.version.to_string()), self, event_details, self.logger())
+        check_service_version(version, self, event_details, self.logger())
     }
 
     fn to_tera_context_for_aws_managed(
@@ -290,6 +457,11 @@ where
             context.insert("database_elasticache_instances_number", &1);
         }
 
+        // Specific for opensearch, domain names are limited to 28 characters
+        if T::db_type() == service::DatabaseType::OpenSearch {
+            context.insert("opensearch_domain_name", &opensearch_domain_name(&self.fqdn_id));
+        }
+
         for (k, v) in kubernetes.cloud_provider().tera_context_environment_variables() {
             context.insert(k, v);
         }
@@ -408,3 +580,56 @@ where
         self.to_tera_context_for_container(target, &self.options)
     }
 }
+
+////////////////////////////////////////////////////////////////////////:
+// OpenSearch
+impl ToTeraContext for Database<AWS, Managed, OpenSearch>
+where
+    OpenSearch: DatabaseType<AWS, Managed>,
+{
+    fn to_tera_context(&self, target: &DeploymentTarget) -> Result<TeraContext, EngineError> {
+        self.to_tera_context_for_aws_managed(target, &self.options)
+    }
+}
+
+impl ToTeraContext for Database<AWS, Container, OpenSearch>
+where
+    OpenSearch: DatabaseType<AWS, Container>,
+{
+    fn to_tera_context(&self, target: &DeploymentTarget) -> Result<TeraContext, EngineError> {
+        self.to_tera_context_for_container(target, &self.options)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////:
+// ClickHouse
+impl ToTeraContext for Database<AWS, Container, ClickHouse>
+where
+    ClickHouse: DatabaseType<AWS, Container>,
+{
+    fn to_tera_context(&self, target: &DeploymentTarget) -> Result<TeraContext, EngineError> {
+        self.to_tera_context_for_container(target, &self.options)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////:
+// RabbitMQ
+impl ToTeraContext for Database<AWS, Container, RabbitMQ>
+where
+    RabbitMQ: DatabaseType<AWS, Container>,
+{
+    fn to_tera_context(&self, target: &DeploymentTarget) -> Result<TeraContext, EngineError> {
+        self.to_tera_context_for_container(target, &self.options)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////:
+// Kafka
+impl ToTeraContext for Database<AWS, Container, Kafka>
+where
+    Kafka: DatabaseType<AWS, Container>,
+{
+    fn to_tera_context(&self, target: &DeploymentTarget) -> Result<TeraContext, EngineError> {
+        self.to_tera_context_for_container(target, &self.options)
+    }
+}
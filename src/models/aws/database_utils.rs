@@ -71,11 +71,24 @@ pub(super) fn get_managed_redis_version(requested_version: String) -> Result<Str
     get_supported_version_to_use("Elasticache", supported_redis_versions, requested_version)
 }
 
+pub(super) fn get_managed_opensearch_version(requested_version: String) -> Result<String, CommandError> {
+    let mut supported_opensearch_versions = HashMap::with_capacity(4);
+    // https://docs.aws.amazon.com/opensearch-service/latest/developerguide/what-is.html#choosing-version
+
+    supported_opensearch_versions.insert("2".to_string(), "OpenSearch_2.3".to_string());
+    supported_opensearch_versions.insert("2.3".to_string(), "OpenSearch_2.3".to_string());
+    supported_opensearch_versions.insert("1".to_string(), "OpenSearch_1.3".to_string());
+    supported_opensearch_versions.insert("1.3".to_string(), "OpenSearch_1.3".to_string());
+
+    get_supported_version_to_use("OpenSearch Service", supported_opensearch_versions, requested_version)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::errors::ErrorMessageVerbosity::SafeOnly;
     use crate::models::aws::database_utils::{
-        get_managed_mongodb_version, get_managed_mysql_version, get_managed_postgres_version, get_managed_redis_version,
+        get_managed_mongodb_version, get_managed_mysql_version, get_managed_opensearch_version,
+        get_managed_postgres_version, get_managed_redis_version,
     };
     use crate::models::database_utils::{
         get_self_hosted_mongodb_version, get_self_hosted_mysql_version, get_self_hosted_postgres_version,
@@ -192,4 +205,18 @@ mod tests {
             "MongoDB 3.4 version is not supported"
         );
     }
+
+    #[test]
+    fn check_opensearch_version() {
+        // managed version
+        assert_eq!(get_managed_opensearch_version("2".to_string()).unwrap(), "OpenSearch_2.3");
+        assert_eq!(get_managed_opensearch_version("1.3".to_string()).unwrap(), "OpenSearch_1.3");
+        assert_eq!(
+            get_managed_opensearch_version("1.2".to_string())
+                .unwrap_err()
+                .message(SafeOnly)
+                .as_str(),
+            "OpenSearch Service 1.2 version is not supported"
+        );
+    }
 }
@@ -2,10 +2,11 @@ mod application;
 mod container;
 mod database;
 mod database_utils;
+mod job;
 mod router;
 
-use crate::models::types::CloudProvider;
 use crate::models::types::AWS;
+use crate::models::types::{CloudProvider, ToK8sStorageClass};
 
 pub struct AwsAppExtraSettings {}
 pub struct AwsDbExtraSettings {}
@@ -36,6 +37,10 @@ impl CloudProvider for AWS {
     fn lib_directory_name() -> &'static str {
         "aws"
     }
+
+    fn volume_snapshot_class() -> Option<&'static str> {
+        Some("aws-ebs-snapshot-0")
+    }
 }
 
 impl AWS {}
@@ -47,3 +52,14 @@ pub enum AwsStorageType {
     GP2,
     IO1,
 }
+
+impl ToK8sStorageClass for AwsStorageType {
+    fn to_k8s_storage_class(&self) -> &str {
+        match self {
+            AwsStorageType::SC1 => "aws-ebs-sc1-0",
+            AwsStorageType::ST1 => "aws-ebs-st1-0",
+            AwsStorageType::GP2 => "aws-ebs-gp2-0",
+            AwsStorageType::IO1 => "aws-ebs-io1-0",
+        }
+    }
+}
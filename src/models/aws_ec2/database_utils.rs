@@ -71,6 +71,18 @@ pub(super) fn get_managed_redis_version(requested_version: String) -> Result<Str
     get_supported_version_to_use("Elasticache", supported_redis_versions, requested_version)
 }
 
+pub(super) fn get_managed_opensearch_version(requested_version: String) -> Result<String, CommandError> {
+    let mut supported_opensearch_versions = HashMap::with_capacity(4);
+    // https://docs.aws.amazon.com/opensearch-service/latest/developerguide/what-is.html#choosing-version
+
+    supported_opensearch_versions.insert("2".to_string(), "OpenSearch_2.3".to_string());
+    supported_opensearch_versions.insert("2.3".to_string(), "OpenSearch_2.3".to_string());
+    supported_opensearch_versions.insert("1".to_string(), "OpenSearch_1.3".to_string());
+    supported_opensearch_versions.insert("1.3".to_string(), "OpenSearch_1.3".to_string());
+
+    get_supported_version_to_use("OpenSearch Service", supported_opensearch_versions, requested_version)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::errors::ErrorMessageVerbosity::SafeOnly;
@@ -0,0 +1,12 @@
+use crate::cloud_provider::DeploymentTarget;
+use crate::errors::EngineError;
+use crate::models::job::Job;
+use crate::models::types::{AWSEc2, ToTeraContext};
+use tera::Context as TeraContext;
+
+impl ToTeraContext for Job<AWSEc2> {
+    fn to_tera_context(&self, target: &DeploymentTarget) -> Result<TeraContext, EngineError> {
+        let context = self.default_tera_context(target);
+        Ok(TeraContext::from_serialize(context).unwrap_or_default())
+    }
+}
@@ -2,9 +2,10 @@ mod application;
 mod container;
 mod database;
 mod database_utils;
+mod job;
 mod router;
 
-use crate::models::types::{AWSEc2, CloudProvider};
+use crate::models::types::{AWSEc2, CloudProvider, ToK8sStorageClass};
 
 pub struct AwsEc2AppExtraSettings {}
 pub struct AwsEc2DbExtraSettings {}
@@ -35,6 +36,10 @@ impl CloudProvider for AWSEc2 {
     fn lib_directory_name() -> &'static str {
         "aws-ec2"
     }
+
+    fn volume_snapshot_class() -> Option<&'static str> {
+        Some("aws-ebs-snapshot-0")
+    }
 }
 
 #[derive(Clone, Eq, PartialEq, Hash)]
@@ -44,3 +49,14 @@ pub enum AwsEc2StorageType {
     GP2,
     IO1,
 }
+
+impl ToK8sStorageClass for AwsEc2StorageType {
+    fn to_k8s_storage_class(&self) -> &str {
+        match self {
+            AwsEc2StorageType::SC1 => "aws-ebs-sc1-0",
+            AwsEc2StorageType::ST1 => "aws-ebs-st1-0",
+            AwsEc2StorageType::GP2 => "aws-ebs-gp2-0",
+            AwsEc2StorageType::IO1 => "aws-ebs-io1-0",
+        }
+    }
+}
@@ -1,7 +1,8 @@
-use crate::cloud_provider::models::{EnvironmentVariable, Storage, StorageDataTemplate};
+use crate::cloud_provider::models::{EnvironmentVariable, SecretEnvironmentVariable, Storage, StorageDataTemplate};
 use crate::cloud_provider::service::{Action, Service, ServiceType};
 use crate::cloud_provider::DeploymentTarget;
 use crate::deployment_action::DeploymentAction;
+use crate::errors::EngineError;
 use crate::events::{EventDetails, Stage, Transmitter};
 use crate::io_models::application::Port;
 use crate::io_models::container::{ContainerAdvancedSettings, Registry};
@@ -9,12 +10,14 @@ use crate::io_models::context::Context;
 use crate::io_models::progress_listener::{Listener, Listeners};
 use crate::io_models::QoveryIdentifier;
 use crate::logger::Logger;
+use crate::models::deployment_strategy::DeploymentStrategy;
 use crate::models::types::{CloudProvider, ToTeraContext};
 use crate::string::cut;
 use crate::utilities::to_short_id;
 use itertools::Itertools;
 use serde::Serialize;
 use std::marker::PhantomData;
+use tera::Context as TeraContext;
 use uuid::Uuid;
 
 #[derive(thiserror::Error, Debug)]
@@ -44,9 +47,12 @@ pub struct Container<T: CloudProvider> {
     pub(super) ports: Vec<Port>,
     pub(super) storages: Vec<Storage<T::StorageTypes>>,
     pub(super) environment_variables: Vec<EnvironmentVariable>,
+    pub(super) secret_environment_variables: Vec<SecretEnvironmentVariable>,
+    pub(super) depends_on: Vec<Uuid>,
     pub(super) listeners: Listeners,
     pub(super) logger: Box<dyn Logger>,
     pub(super) advanced_settings: ContainerAdvancedSettings,
+    pub(super) deployment_strategy: DeploymentStrategy,
     pub(super) _extra_settings: T::AppExtraSettings,
 }
 
@@ -73,6 +79,8 @@ impl<T: CloudProvider> Container<T> {
         ports: Vec<Port>,
         storages: Vec<Storage<T::StorageTypes>>,
         environment_variables: Vec<EnvironmentVariable>,
+        secret_environment_variables: Vec<SecretEnvironmentVariable>,
+        depends_on: Vec<Uuid>,
         advanced_settings: ContainerAdvancedSettings,
         extra_settings: T::AppExtraSettings,
         listeners: Listeners,
@@ -114,6 +122,16 @@ impl<T: CloudProvider> Container<T> {
             ));
         }
 
+        let deployment_strategy = DeploymentStrategy::new(
+            advanced_settings.deployment_strategy_type,
+            advanced_settings.deployment_strategy_max_surge_percent,
+            advanced_settings.deployment_strategy_max_unavailable_percent,
+            advanced_settings.deployment_strategy_canary_weight_percent,
+            advanced_settings.deployment_strategy_analysis_duration_sec,
+            advanced_settings.deployment_strategy_max_error_rate_percent,
+        )
+        .map_err(ContainerError::InvalidConfig)?;
+
         Ok(Self {
             _marker: PhantomData,
             context,
@@ -135,9 +153,12 @@ impl<T: CloudProvider> Container<T> {
             ports,
             storages,
             environment_variables,
+            secret_environment_variables,
+            depends_on,
             listeners,
             logger,
             advanced_settings,
+            deployment_strategy,
             _extra_settings: extra_settings,
         })
     }
@@ -146,6 +167,10 @@ impl<T: CloudProvider> Container<T> {
         Some(self.selector())
     }
 
+    pub fn storages(&self) -> &[Storage<T::StorageTypes>] {
+        &self.storages
+    }
+
     pub fn helm_release_name(&self) -> String {
         format!("container-{}", self.long_id)
     }
@@ -154,10 +179,36 @@ impl<T: CloudProvider> Container<T> {
         format!("{}/common/charts/q-container", self.context.lib_root_dir())
     }
 
-    fn kube_service_name(&self) -> String {
+    pub fn kube_service_name(&self) -> String {
         format!("container-{}", to_short_id(&self.long_id))
     }
 
+    /// Release used to deploy the new version next to the current one, for blue/green and canary deployments
+    pub fn preview_helm_release_name(&self) -> String {
+        format!("container-{}-preview", to_short_id(&self.long_id))
+    }
+
+    pub fn preview_kube_service_name(&self) -> String {
+        format!("{}-preview", self.kube_service_name())
+    }
+
+    /// Same chart as the container, but with resources named after the preview and pods which are not selected by
+    /// the container service
+    pub fn to_preview_tera_context(&self, target: &DeploymentTarget) -> Result<TeraContext, EngineError>
+    where
+        Self: ToTeraContext,
+    {
+        let mut context = self.to_tera_context(target)?;
+        if let Some(mut service) = context.get("service").cloned() {
+            service["name"] = self.preview_kube_service_name().into();
+            context.insert("service", &service);
+        }
+        context.insert("is_preview", &true);
+        context.insert("service_id_label", "qovery.com/preview-service-id");
+
+        Ok(context)
+    }
+
     pub fn registry(&self) -> &Registry {
         &self.registry
     }
@@ -218,19 +269,41 @@ impl<T: CloudProvider> Container<T> {
                 }),
             environment_variables: self.environment_variables.clone(),
             resource_expiration_in_seconds: Some(kubernetes.advanced_settings().pleco_resources_ttl),
+            is_preview: false,
+            service_id_label: "qovery.com/service-id".to_string(),
         };
 
         ctx
     }
 
+    pub fn advanced_settings(&self) -> &ContainerAdvancedSettings {
+        &self.advanced_settings
+    }
+
+    pub fn is_autoscaled(&self) -> bool {
+        !self.is_stateful() && self.min_instances != self.max_instances
+    }
+
     pub fn is_stateful(&self) -> bool {
         !self.storages.is_empty()
     }
 
+    pub fn deployment_strategy(&self) -> &DeploymentStrategy {
+        &self.deployment_strategy
+    }
+
     pub fn context(&self) -> &Context {
         &self.context
     }
 
+    pub fn environment_variables(&self) -> &[EnvironmentVariable] {
+        &self.environment_variables
+    }
+
+    pub fn secret_environment_variables(&self) -> &[SecretEnvironmentVariable] {
+        &self.secret_environment_variables
+    }
+
     pub fn service_type(&self) -> ServiceType {
         ServiceType::Container
     }
@@ -269,6 +342,10 @@ impl<T: CloudProvider> Container<T> {
         format!("qovery.com/service-id={}", self.long_id)
     }
 
+    pub fn preview_selector(&self) -> String {
+        format!("qovery.com/preview-service-id={}", self.long_id)
+    }
+
     pub(crate) fn get_event_details(&self, stage: Stage) -> EventDetails {
         let context = self.context();
         EventDetails::new(
@@ -346,6 +423,9 @@ pub trait ContainerService: Service + DeploymentAction + ToTeraContext {
     fn advanced_settings(&self) -> &ContainerAdvancedSettings;
     fn image_full(&self) -> String;
     fn kube_service_name(&self) -> String;
+    fn helm_release_name(&self) -> String;
+    /// services (by long id) which must be deployed before this container
+    fn depends_on(&self) -> &[Uuid];
     fn startup_timeout(&self) -> std::time::Duration {
         let settings = self.advanced_settings();
         let readiness_probe_timeout = settings.readiness_probe_initial_delay_seconds
@@ -384,6 +464,14 @@ where
     fn kube_service_name(&self) -> String {
         self.kube_service_name()
     }
+
+    fn helm_release_name(&self) -> String {
+        self.helm_release_name()
+    }
+
+    fn depends_on(&self) -> &[Uuid] {
+        &self.depends_on
+    }
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -434,4 +522,7 @@ pub(super) struct ContainerTeraContext {
     pub(super) registry: Option<RegistryTeraContext>,
     pub(super) environment_variables: Vec<EnvironmentVariable>,
     pub(super) resource_expiration_in_seconds: Option<i32>,
+    pub(super) is_preview: bool,
+    /// label identifying the pods of the service, the preview ones must not be selected by the service
+    pub(super) service_id_label: String,
 }
@@ -3,6 +3,7 @@ use crate::cloud_provider::service::{
 };
 use crate::cloud_provider::utilities::managed_db_name_sanitizer;
 use crate::cloud_provider::{service, DeploymentTarget};
+use crate::deployment_action::database_backup::DatabaseBackupAction;
 use crate::deployment_action::DeploymentAction;
 use crate::errors::EngineError;
 use crate::events::{EnvironmentStep, EventDetails, Stage, Transmitter};
@@ -11,8 +12,9 @@ use crate::io_models::database::DatabaseOptions;
 use crate::io_models::progress_listener::{Listener, Listeners};
 use crate::logger::Logger;
 use crate::models::database_utils::{
-    get_self_hosted_mongodb_version, get_self_hosted_mysql_version, get_self_hosted_postgres_version,
-    get_self_hosted_redis_version,
+    get_self_hosted_clickhouse_version, get_self_hosted_kafka_version, get_self_hosted_mongodb_version,
+    get_self_hosted_mysql_version, get_self_hosted_opensearch_version, get_self_hosted_postgres_version,
+    get_self_hosted_rabbitmq_version, get_self_hosted_redis_version,
 };
 use crate::models::types::{CloudProvider, ToTeraContext, VersionsNumber};
 use crate::utilities::to_short_id;
@@ -25,7 +27,7 @@ use uuid::Uuid;
 // Database mode
 pub struct Managed {}
 pub struct Container {}
-pub trait DatabaseMode {
+pub trait DatabaseMode: Send + Sync {
     fn is_managed() -> bool;
     fn is_container() -> bool {
         !Self::is_managed()
@@ -50,9 +52,13 @@ pub struct PostgresSQL {}
 pub struct MySQL {}
 pub struct MongoDB {}
 pub struct Redis {}
+pub struct OpenSearch {}
+pub struct ClickHouse {}
+pub struct RabbitMQ {}
+pub struct Kafka {}
 
-pub trait DatabaseType<T: CloudProvider, M: DatabaseMode> {
-    type DatabaseOptions;
+pub trait DatabaseType<T: CloudProvider, M: DatabaseMode>: Send + Sync {
+    type DatabaseOptions: Send + Sync;
 
     fn short_name() -> &'static str;
     fn lib_directory_name() -> &'static str;
@@ -143,6 +149,10 @@ impl<C: CloudProvider, M: DatabaseMode, T: DatabaseType<C, M>> Database<C, M, T>
         format!("databaseId={}", self.id)
     }
 
+    pub fn helm_chart_external_name_service_dir(&self) -> String {
+        format!("{}/common/charts/external-name-svc", self.context.lib_root_dir())
+    }
+
     pub(super) fn fqdn(&self, target: &DeploymentTarget, fqdn: &str) -> String {
         match &self.publicly_accessible {
             true => fqdn.to_string(),
@@ -219,10 +229,14 @@ impl<C: CloudProvider, M: DatabaseMode, T: DatabaseType<C, M>> Service for Datab
     }
 }
 
+fn container_database_helm_release_name(lib_directory_name: &str, database_id: &str) -> String {
+    format!("{}-{}", lib_directory_name, database_id)
+}
+
 // Mzthod Only For all container database
 impl<C: CloudProvider, T: DatabaseType<C, Container>> Database<C, Container, T> {
     pub fn helm_release_name(&self) -> String {
-        format!("{}-{}", T::lib_directory_name(), self.id)
+        container_database_helm_release_name(T::lib_directory_name(), &self.id)
     }
 
     pub fn helm_chart_dir(&self) -> String {
@@ -274,11 +288,15 @@ impl<C: CloudProvider, T: DatabaseType<C, Container>> Database<C, Container, T>
         context.insert("database_instance_type", &self.database_instance_type);
         context.insert("database_disk_type", &options.database_disk_type);
         context.insert("database_ram_size_in_mib", &self.total_ram_in_mib);
+        // JVM based engines (OpenSearch, Kafka) keep the other half for off-heap and page cache
+        context.insert("database_heap_size_in_mib", &(self.total_ram_in_mib / 2));
         context.insert("database_total_cpus", &self.total_cpus);
         context.insert("database_total_cpus_burst", &T::cpu_burst_value(self.total_cpus.clone()));
         context.insert("database_fqdn", &options.host.as_str());
         context.insert("database_id", &self.id());
         context.insert("publicly_accessible", &self.publicly_accessible);
+        context.insert("database_read_replicas", &options.read_replicas);
+        context.insert("database_replication_enabled", &(options.read_replicas > 0));
 
         context.insert(
             "resource_expiration_in_seconds",
@@ -288,12 +306,16 @@ impl<C: CloudProvider, T: DatabaseType<C, Container>> Database<C, Container, T>
         Ok(context)
     }
 
-    fn get_version(&self, event_details: EventDetails) -> Result<ServiceVersionCheckResult, EngineError> {
+    pub(crate) fn get_version(&self, event_details: EventDetails) -> Result<ServiceVersionCheckResult, EngineError> {
         let fn_version = match T::db_type() {
             service::DatabaseType::PostgreSQL => get_self_hosted_postgres_version,
             service::DatabaseType::MongoDB => get_self_hosted_mongodb_version,
             service::DatabaseType::MySQL => get_self_hosted_mysql_version,
             service::DatabaseType::Redis => get_self_hosted_redis_version,
+            service::DatabaseType::OpenSearch => get_self_hosted_opensearch_version,
+            service::DatabaseType::ClickHouse => get_self_hosted_clickhouse_version,
+            service::DatabaseType::RabbitMQ => get_self_hosted_rabbitmq_version,
+            service::DatabaseType::Kafka => get_self_hosted_kafka_version,
         };
 
         check_service_version(fn_version(self.version.to_string()), self, event_details, self.logger())
@@ -302,10 +324,6 @@ impl<C: CloudProvider, T: DatabaseType<C, Container>> Database<C, Container, T>
 
 // methods for all Managed databases
 impl<C: CloudProvider, T: DatabaseType<C, Managed>> Database<C, Managed, T> {
-    pub fn helm_chart_external_name_service_dir(&self) -> String {
-        format!("{}/common/charts/external-name-svc", self.context.lib_root_dir())
-    }
-
     pub fn terraform_common_resource_dir_path(&self) -> String {
         format!("{}/{}/services/common", self.context.lib_root_dir(), C::lib_directory_name())
     }
@@ -320,15 +338,18 @@ impl<C: CloudProvider, T: DatabaseType<C, Managed>> Database<C, Managed, T> {
     }
 }
 
-pub trait DatabaseService: Service + DeploymentAction + ToTeraContext {
+pub trait DatabaseService: Service + DeploymentAction + DatabaseBackupAction + ToTeraContext {
     fn is_managed_service(&self) -> bool;
 
     fn db_type(&self) -> service::DatabaseType;
+
+    /// helm release of the database, managed databases are deployed with Terraform and don't have one
+    fn helm_release_name(&self) -> Option<String>;
 }
 
 impl<C: CloudProvider, M: DatabaseMode, T: DatabaseType<C, M>> DatabaseService for Database<C, M, T>
 where
-    Database<C, M, T>: Service + DeploymentAction + ToTeraContext,
+    Database<C, M, T>: Service + DeploymentAction + DatabaseBackupAction + ToTeraContext,
 {
     fn is_managed_service(&self) -> bool {
         M::is_managed()
@@ -337,4 +358,12 @@ where
     fn db_type(&self) -> service::DatabaseType {
         T::db_type()
     }
+
+    fn helm_release_name(&self) -> Option<String> {
+        if M::is_managed() {
+            return None;
+        }
+
+        Some(container_database_helm_release_name(T::lib_directory_name(), &self.id))
+    }
 }
@@ -1,3 +1,4 @@
+use crate::cloud_provider::service::DatabaseType;
 use crate::errors::CommandError;
 use crate::models::types::VersionsNumber;
 use std::collections::HashMap;
@@ -85,6 +86,175 @@ pub fn get_self_hosted_redis_version(requested_version: String) -> Result<String
     get_supported_version_to_use("Redis", supported_redis_versions, requested_version)
 }
 
+pub fn get_self_hosted_opensearch_version(requested_version: String) -> Result<String, CommandError> {
+    let mut supported_opensearch_versions = HashMap::new();
+    // https://hub.docker.com/r/bitnami/opensearch/tags?page=1&ordering=last_updated
+
+    // v1.3
+    let v13 = generate_supported_version(1, 3, 3, Some(0), Some(6), None);
+    supported_opensearch_versions.extend(v13);
+
+    // v2.3
+    let v23 = generate_supported_version(2, 3, 3, Some(0), Some(0), None);
+    supported_opensearch_versions.extend(v23);
+
+    get_supported_version_to_use("OpenSearch", supported_opensearch_versions, requested_version)
+}
+
+pub fn get_self_hosted_clickhouse_version(requested_version: String) -> Result<String, CommandError> {
+    let mut supported_clickhouse_versions = HashMap::new();
+    // https://hub.docker.com/r/bitnami/clickhouse/tags?page=1&ordering=last_updated
+
+    // v22.3 LTS
+    let v223 = generate_supported_version(22, 3, 3, Some(0), Some(13), None);
+    supported_clickhouse_versions.extend(v223);
+
+    // v22.8 LTS, last one so the major version targets it
+    let v228 = generate_supported_version(22, 8, 8, Some(0), Some(6), None);
+    supported_clickhouse_versions.extend(v228);
+
+    get_supported_version_to_use("ClickHouse", supported_clickhouse_versions, requested_version)
+}
+
+pub fn get_self_hosted_rabbitmq_version(requested_version: String) -> Result<String, CommandError> {
+    let mut supported_rabbitmq_versions = HashMap::new();
+    // https://hub.docker.com/r/bitnami/rabbitmq/tags?page=1&ordering=last_updated
+
+    // v3.9
+    let v39 = generate_supported_version(3, 9, 9, Some(0), Some(24), None);
+    supported_rabbitmq_versions.extend(v39);
+
+    // v3.10
+    let v310 = generate_supported_version(3, 10, 10, Some(0), Some(10), None);
+    supported_rabbitmq_versions.extend(v310);
+
+    // v3.11, last one so the major version targets it
+    let v311 = generate_supported_version(3, 11, 11, Some(0), Some(1), None);
+    supported_rabbitmq_versions.extend(v311);
+
+    get_supported_version_to_use("RabbitMQ", supported_rabbitmq_versions, requested_version)
+}
+
+pub fn get_self_hosted_kafka_version(requested_version: String) -> Result<String, CommandError> {
+    let mut supported_kafka_versions = HashMap::new();
+    // https://hub.docker.com/r/bitnami/kafka/tags?page=1&ordering=last_updated
+    // brokers run in KRaft mode (without Zookeeper), which is only available from 3.x
+
+    // v3.2
+    let v32 = generate_supported_version(3, 2, 2, Some(0), Some(3), None);
+    supported_kafka_versions.extend(v32);
+
+    // v3.3, last one so the major version targets it
+    let v33 = generate_supported_version(3, 3, 3, Some(0), Some(1), None);
+    supported_kafka_versions.extend(v33);
+
+    get_supported_version_to_use("Kafka", supported_kafka_versions, requested_version)
+}
+
+/// MongoDB release series, each one only reads the data files of the previous one
+const MONGODB_RELEASE_SERIES: [(u32, u32); 4] = [(3, 6), (4, 0), (4, 2), (4, 4)];
+
+/// How a container database is moved from its deployed version to the requested one
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DatabaseVersionUpgrade {
+    /// same release series, the new image reads the data files as they are
+    Minor,
+    /// the new version upgrades the data files of the previous release series on start
+    InPlace,
+    /// data files of another major cannot be read, they are dumped with the deployed version and restored into a
+    /// fresh database running the requested one. pg_upgrade is not an option as images ship the binaries of one major
+    DumpAndRestore,
+    /// each release series up to the requested one is deployed in turn, then its featureCompatibilityVersion is set
+    FeatureCompatibilityVersions(Vec<String>),
+}
+
+impl DatabaseVersionUpgrade {
+    /// Data files are rewritten: a dump must be taken before upgrading
+    pub fn is_major(&self) -> bool {
+        !matches!(self, DatabaseVersionUpgrade::Minor)
+    }
+}
+
+fn parse_version_numbers(version: &str) -> Result<(u32, u32, u32), CommandError> {
+    let version_number = VersionsNumber::from_str(version)?;
+    let parse = |number: Option<&String>| -> Result<u32, CommandError> {
+        match number {
+            Some(number) => number
+                .parse::<u32>()
+                .map_err(|_| CommandError::new_from_safe_message(format!("{} is not a valid version number", version))),
+            None => Ok(0),
+        }
+    };
+
+    Ok((
+        parse(Some(&version_number.major))?,
+        parse(version_number.minor.as_ref())?,
+        parse(version_number.patch.as_ref())?,
+    ))
+}
+
+/// Upgrade path of a container database from its deployed version to the requested one, both being supported versions.
+/// Downgrades to a previous release series and upgrades engines cannot perform on their data files are refused.
+pub fn get_database_version_upgrade(
+    db_type: DatabaseType,
+    deployed_version: &str,
+    requested_version: &str,
+) -> Result<DatabaseVersionUpgrade, CommandError> {
+    let deployed = parse_version_numbers(deployed_version)?;
+    let requested = parse_version_numbers(requested_version)?;
+    // since v10, PostgreSQL data files are compatible between minor versions
+    let release_series = |version: (u32, u32, u32)| match db_type {
+        DatabaseType::PostgreSQL => (version.0, 0),
+        _ => (version.0, version.1),
+    };
+    let (deployed_series, requested_series) = (release_series(deployed), release_series(requested));
+
+    if deployed_series == requested_series {
+        return Ok(DatabaseVersionUpgrade::Minor);
+    }
+
+    if requested_series < deployed_series {
+        return Err(CommandError::new_from_safe_message(format!(
+            "{} cannot be downgraded from {} to {}, restore a backup taken with version {} instead",
+            db_type.to_string(),
+            deployed_version,
+            requested_version,
+            requested_version
+        )));
+    }
+
+    match db_type {
+        DatabaseType::PostgreSQL => Ok(DatabaseVersionUpgrade::DumpAndRestore),
+        // only 5.7 to 8.0 exists, MySQL >= 8.0.16 upgrades the data dictionary on start
+        DatabaseType::MySQL | DatabaseType::Redis => Ok(DatabaseVersionUpgrade::InPlace),
+        DatabaseType::MongoDB => {
+            if !MONGODB_RELEASE_SERIES.contains(&deployed_series) || !MONGODB_RELEASE_SERIES.contains(&requested_series)
+            {
+                return Err(CommandError::new_from_safe_message(format!(
+                    "MongoDB has no upgrade path from {} to {}",
+                    deployed_version, requested_version
+                )));
+            }
+
+            Ok(DatabaseVersionUpgrade::FeatureCompatibilityVersions(
+                MONGODB_RELEASE_SERIES
+                    .iter()
+                    .filter(|series| **series > deployed_series && **series <= requested_series)
+                    .map(|(major, minor)| format!("{}.{}", major, minor))
+                    .collect(),
+            ))
+        }
+        DatabaseType::OpenSearch | DatabaseType::ClickHouse | DatabaseType::RabbitMQ | DatabaseType::Kafka => {
+            Err(CommandError::new_from_safe_message(format!(
+                "{} can only be upgraded within its {}.{} release series",
+                db_type.to_string(),
+                deployed_series.0,
+                deployed_series.1
+            )))
+        }
+    }
+}
+
 pub fn get_supported_version_to_use(
     database_name: &str,
     all_supported_versions: HashMap<String, String>,
@@ -202,3 +372,121 @@ pub fn generate_supported_version(
 
     supported_versions
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::cloud_provider::service::DatabaseType;
+    use crate::errors::ErrorMessageVerbosity::SafeOnly;
+    use crate::models::database_utils::{
+        get_database_version_upgrade, get_self_hosted_clickhouse_version, get_self_hosted_kafka_version,
+        get_self_hosted_opensearch_version, get_self_hosted_rabbitmq_version, DatabaseVersionUpgrade,
+    };
+
+    #[test]
+    fn check_opensearch_version() {
+        assert_eq!(get_self_hosted_opensearch_version("2".to_string()).unwrap(), "2.3.0");
+        assert_eq!(get_self_hosted_opensearch_version("1".to_string()).unwrap(), "1.3.6");
+        assert_eq!(get_self_hosted_opensearch_version("1.3.2".to_string()).unwrap(), "1.3.2");
+        assert_eq!(
+            get_self_hosted_opensearch_version("1.2".to_string())
+                .unwrap_err()
+                .message(SafeOnly)
+                .as_str(),
+            "OpenSearch 1.2 version is not supported"
+        );
+    }
+
+    #[test]
+    fn check_clickhouse_version() {
+        assert_eq!(get_self_hosted_clickhouse_version("22".to_string()).unwrap(), "22.8.6");
+        assert_eq!(get_self_hosted_clickhouse_version("22.3".to_string()).unwrap(), "22.3.13");
+        assert_eq!(
+            get_self_hosted_clickhouse_version("21".to_string())
+                .unwrap_err()
+                .message(SafeOnly)
+                .as_str(),
+            "ClickHouse 21 version is not supported"
+        );
+    }
+
+    #[test]
+    fn check_rabbitmq_version() {
+        assert_eq!(get_self_hosted_rabbitmq_version("3".to_string()).unwrap(), "3.11.1");
+        assert_eq!(get_self_hosted_rabbitmq_version("3.10".to_string()).unwrap(), "3.10.10");
+        assert_eq!(get_self_hosted_rabbitmq_version("3.9.20".to_string()).unwrap(), "3.9.20");
+        assert_eq!(
+            get_self_hosted_rabbitmq_version("3.8".to_string())
+                .unwrap_err()
+                .message(SafeOnly)
+                .as_str(),
+            "RabbitMQ 3.8 version is not supported"
+        );
+    }
+
+    #[test]
+    fn check_kafka_version() {
+        assert_eq!(get_self_hosted_kafka_version("3".to_string()).unwrap(), "3.3.1");
+        assert_eq!(get_self_hosted_kafka_version("3.2".to_string()).unwrap(), "3.2.3");
+        assert_eq!(
+            get_self_hosted_kafka_version("2.8".to_string())
+                .unwrap_err()
+                .message(SafeOnly)
+                .as_str(),
+            "Kafka 2.8 version is not supported"
+        );
+    }
+
+    #[test]
+    fn check_database_version_upgrade() {
+        // minor upgrades, or patch downgrades, keep the data files as they are
+        assert_eq!(
+            get_database_version_upgrade(DatabaseType::PostgreSQL, "13.1.0", "13.7.0").unwrap(),
+            DatabaseVersionUpgrade::Minor
+        );
+        assert_eq!(
+            get_database_version_upgrade(DatabaseType::MongoDB, "4.4.15", "4.4.10").unwrap(),
+            DatabaseVersionUpgrade::Minor
+        );
+
+        assert_eq!(
+            get_database_version_upgrade(DatabaseType::PostgreSQL, "12.11.0", "14.4.0").unwrap(),
+            DatabaseVersionUpgrade::DumpAndRestore
+        );
+        assert_eq!(
+            get_database_version_upgrade(DatabaseType::MySQL, "5.7.39", "8.0.30").unwrap(),
+            DatabaseVersionUpgrade::InPlace
+        );
+        assert_eq!(
+            get_database_version_upgrade(DatabaseType::Redis, "6.2.7", "7.0.4").unwrap(),
+            DatabaseVersionUpgrade::InPlace
+        );
+        assert_eq!(
+            get_database_version_upgrade(DatabaseType::MongoDB, "3.6.23", "4.4.15").unwrap(),
+            DatabaseVersionUpgrade::FeatureCompatibilityVersions(vec![
+                "4.0".to_string(),
+                "4.2".to_string(),
+                "4.4".to_string()
+            ])
+        );
+        assert!(get_database_version_upgrade(DatabaseType::MySQL, "5.7.39", "8.0.30")
+            .unwrap()
+            .is_major());
+
+        // refused upgrades
+        assert_eq!(
+            get_database_version_upgrade(DatabaseType::PostgreSQL, "14.4.0", "13.7.0")
+                .unwrap_err()
+                .message(SafeOnly)
+                .as_str(),
+            "PostgreSQL cannot be downgraded from 14.4.0 to 13.7.0, restore a backup taken with version 13.7.0 instead"
+        );
+        assert_eq!(
+            get_database_version_upgrade(DatabaseType::RabbitMQ, "3.10.10", "3.11.1")
+                .unwrap_err()
+                .message(SafeOnly)
+                .as_str(),
+            "RabbitMQ can only be upgraded within its 3.10 release series"
+        );
+        assert!(get_database_version_upgrade(DatabaseType::MongoDB, "4.4.15", "5.0.0").is_err());
+    }
+}
@@ -0,0 +1,133 @@
+use crate::io_models::application::DeploymentStrategyType;
+use std::time::Duration;
+
+/// How a new version of a stateless service replaces the current one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeploymentStrategy {
+    /// Pods are replaced progressively by Kubernetes, it is the default behavior
+    RollingUpdate {
+        max_surge_percent: u32,
+        max_unavailable_percent: u32,
+    },
+    /// A preview of the new version is deployed next to the current one, and receives all the ingress traffic once
+    /// it has passed the promotion gate
+    BlueGreen { gate: PromotionGate },
+    /// A preview of the new version is deployed next to the current one, and receives `weight_percent` of the ingress
+    /// traffic while it is evaluated by the promotion gate
+    Canary { weight_percent: u32, gate: PromotionGate },
+}
+
+/// Conditions the preview of a new version must meet to be promoted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromotionGate {
+    /// How long the preview pods are watched once ready
+    pub analysis_duration: Duration,
+    /// Max percentage of preview pods which are not ready or have restarted, None to only check readiness
+    pub max_error_rate_percent: Option<u32>,
+}
+
+impl DeploymentStrategy {
+    pub fn new(
+        strategy_type: DeploymentStrategyType,
+        max_surge_percent: u32,
+        max_unavailable_percent: u32,
+        canary_weight_percent: u32,
+        analysis_duration_sec: u32,
+        max_error_rate_percent: Option<u32>,
+    ) -> Result<DeploymentStrategy, String> {
+        let gate = PromotionGate {
+            analysis_duration: Duration::from_secs(analysis_duration_sec as u64),
+            max_error_rate_percent,
+        };
+
+        if max_error_rate_percent.unwrap_or(0) > 100 {
+            return Err("deployment strategy max error rate must be a percentage between 0 and 100".to_string());
+        }
+
+        // the stable version is still updated by a rolling update once a preview is promoted
+        if max_surge_percent > 100 || max_unavailable_percent > 100 {
+            return Err(
+                "deployment strategy max surge and max unavailable must be percentages between 0 and 100".to_string(),
+            );
+        }
+        if max_surge_percent == 0 && max_unavailable_percent == 0 {
+            return Err("deployment strategy max surge and max unavailable cannot be both 0".to_string());
+        }
+
+        match strategy_type {
+            DeploymentStrategyType::RollingUpdate => Ok(DeploymentStrategy::RollingUpdate {
+                max_surge_percent,
+                max_unavailable_percent,
+            }),
+            DeploymentStrategyType::BlueGreen => Ok(DeploymentStrategy::BlueGreen { gate }),
+            DeploymentStrategyType::Canary => {
+                if canary_weight_percent == 0 || canary_weight_percent >= 100 {
+                    return Err("canary weight must be a percentage between 1 and 99".to_string());
+                }
+
+                Ok(DeploymentStrategy::Canary {
+                    weight_percent: canary_weight_percent,
+                    gate,
+                })
+            }
+        }
+    }
+
+    /// None for a rolling update, which is entirely managed by Kubernetes
+    pub fn promotion_gate(&self) -> Option<&PromotionGate> {
+        match self {
+            DeploymentStrategy::RollingUpdate { .. } => None,
+            DeploymentStrategy::BlueGreen { gate } => Some(gate),
+            DeploymentStrategy::Canary { gate, .. } => Some(gate),
+        }
+    }
+
+    /// Percentage of the ingress traffic sent to the preview while it is evaluated
+    pub fn preview_traffic_weight_percent(&self) -> u32 {
+        match self {
+            DeploymentStrategy::Canary { weight_percent, .. } => *weight_percent,
+            DeploymentStrategy::RollingUpdate { .. } | DeploymentStrategy::BlueGreen { .. } => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deployment_strategy_new() {
+        // setup:
+        let gate = PromotionGate {
+            analysis_duration: Duration::from_secs(60),
+            max_error_rate_percent: Some(20),
+        };
+
+        // execute & verify:
+        assert_eq!(
+            Ok(DeploymentStrategy::RollingUpdate {
+                max_surge_percent: 25,
+                max_unavailable_percent: 10
+            }),
+            DeploymentStrategy::new(DeploymentStrategyType::RollingUpdate, 25, 10, 10, 60, Some(20))
+        );
+        assert!(DeploymentStrategy::new(DeploymentStrategyType::RollingUpdate, 0, 0, 10, 60, None).is_err());
+        assert_eq!(
+            Ok(DeploymentStrategy::BlueGreen { gate: gate.clone() }),
+            DeploymentStrategy::new(DeploymentStrategyType::BlueGreen, 25, 10, 10, 60, Some(20))
+        );
+        assert_eq!(
+            Ok(DeploymentStrategy::Canary {
+                weight_percent: 10,
+                gate
+            }),
+            DeploymentStrategy::new(DeploymentStrategyType::Canary, 25, 10, 10, 60, Some(20))
+        );
+        assert!(DeploymentStrategy::new(DeploymentStrategyType::Canary, 25, 10, 0, 60, None).is_err());
+        assert!(DeploymentStrategy::new(DeploymentStrategyType::Canary, 25, 10, 100, 60, None).is_err());
+        assert!(DeploymentStrategy::new(DeploymentStrategyType::BlueGreen, 25, 10, 10, 60, Some(101)).is_err());
+        assert!(DeploymentStrategy::new(DeploymentStrategyType::RollingUpdate, 101, 10, 10, 60, None).is_err());
+        assert!(DeploymentStrategy::new(DeploymentStrategyType::BlueGreen, 0, 0, 10, 60, None).is_err());
+        assert!(DeploymentStrategy::new(DeploymentStrategyType::Canary, 25, 101, 10, 60, None).is_err());
+    }
+}
@@ -5,8 +5,7 @@ use crate::errors::EngineError;
 use crate::events::{EnvironmentStep, Stage};
 use crate::io_models::progress_listener::ListenersHelper;
 use crate::models::application::Application;
-use crate::models::digital_ocean::DoStorageType;
-use crate::models::types::{ToTeraContext, DO};
+use crate::models::types::{ToK8sStorageClass, ToTeraContext, DO};
 use tera::Context as TeraContext;
 
 impl ToTeraContext for Application<DO> {
@@ -48,10 +47,7 @@ impl ToTeraContext for Application<DO> {
                 id: s.id.clone(),
                 long_id: self.long_id,
                 name: s.name.clone(),
-                storage_type: match s.storage_type {
-                    DoStorageType::Standard => "do-block-storage",
-                }
-                .to_string(),
+                storage_type: s.storage_type.to_k8s_storage_class().to_string(),
                 size_in_gib: s.size_in_gib,
                 mount_point: s.mount_point.clone(),
                 snapshot_retention_in_days: s.snapshot_retention_in_days,
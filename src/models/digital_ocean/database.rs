@@ -2,7 +2,9 @@ use crate::cloud_provider::service::{check_service_version, Service};
 use crate::cloud_provider::{service, DeploymentTarget};
 use crate::errors::EngineError;
 use crate::io_models::database::DatabaseOptions;
-use crate::models::database::{Container, Database, DatabaseType, MongoDB, MySQL, PostgresSQL, Redis};
+use crate::models::database::{
+    ClickHouse, Container, Database, DatabaseType, Kafka, MongoDB, MySQL, OpenSearch, PostgresSQL, RabbitMQ, Redis,
+};
 use crate::models::database_utils::{
     get_self_hosted_mongodb_version, get_self_hosted_mysql_version, get_self_hosted_postgres_version,
     get_self_hosted_redis_version,
@@ -71,6 +73,62 @@ impl DatabaseType<DO, Container> for MongoDB {
     }
 }
 
+impl DatabaseType<DO, Container> for OpenSearch {
+    type DatabaseOptions = DatabaseOptions;
+
+    fn short_name() -> &'static str {
+        "OpenSearch"
+    }
+    fn lib_directory_name() -> &'static str {
+        "opensearch"
+    }
+    fn db_type() -> service::DatabaseType {
+        service::DatabaseType::OpenSearch
+    }
+}
+
+impl DatabaseType<DO, Container> for ClickHouse {
+    type DatabaseOptions = DatabaseOptions;
+
+    fn short_name() -> &'static str {
+        "ClickHouse"
+    }
+    fn lib_directory_name() -> &'static str {
+        "clickhouse"
+    }
+    fn db_type() -> service::DatabaseType {
+        service::DatabaseType::ClickHouse
+    }
+}
+
+impl DatabaseType<DO, Container> for RabbitMQ {
+    type DatabaseOptions = DatabaseOptions;
+
+    fn short_name() -> &'static str {
+        "RabbitMQ"
+    }
+    fn lib_directory_name() -> &'static str {
+        "rabbitmq"
+    }
+    fn db_type() -> service::DatabaseType {
+        service::DatabaseType::RabbitMQ
+    }
+}
+
+impl DatabaseType<DO, Container> for Kafka {
+    type DatabaseOptions = DatabaseOptions;
+
+    fn short_name() -> &'static str {
+        "Kafka"
+    }
+    fn lib_directory_name() -> &'static str {
+        "kafka"
+    }
+    fn db_type() -> service::DatabaseType {
+        service::DatabaseType::Kafka
+    }
+}
+
 /////////////////////////////////////////////////////////////////
 // MANAGED
 // DO don't support managed databases for now
@@ -151,3 +209,47 @@ where
         self.to_tera_context_for_container(target, &self.options)
     }
 }
+
+////////////////////////////////////////////////////////////////////////:
+// OpenSearch
+impl ToTeraContext for Database<DO, Container, OpenSearch>
+where
+    OpenSearch: DatabaseType<DO, Container>,
+{
+    fn to_tera_context(&self, target: &DeploymentTarget) -> Result<TeraContext, EngineError> {
+        self.to_tera_context_for_container(target, &self.options)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////:
+// ClickHouse
+impl ToTeraContext for Database<DO, Container, ClickHouse>
+where
+    ClickHouse: DatabaseType<DO, Container>,
+{
+    fn to_tera_context(&self, target: &DeploymentTarget) -> Result<TeraContext, EngineError> {
+        self.to_tera_context_for_container(target, &self.options)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////:
+// RabbitMQ
+impl ToTeraContext for Database<DO, Container, RabbitMQ>
+where
+    RabbitMQ: DatabaseType<DO, Container>,
+{
+    fn to_tera_context(&self, target: &DeploymentTarget) -> Result<TeraContext, EngineError> {
+        self.to_tera_context_for_container(target, &self.options)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////:
+// Kafka
+impl ToTeraContext for Database<DO, Container, Kafka>
+where
+    Kafka: DatabaseType<DO, Container>,
+{
+    fn to_tera_context(&self, target: &DeploymentTarget) -> Result<TeraContext, EngineError> {
+        self.to_tera_context_for_container(target, &self.options)
+    }
+}
@@ -3,8 +3,8 @@ mod database;
 mod router;
 
 use crate::errors::CommandError;
-use crate::models::types::CloudProvider;
 use crate::models::types::DO;
+use crate::models::types::{CloudProvider, ToK8sStorageClass};
 use std::fmt;
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
@@ -38,6 +38,10 @@ impl CloudProvider for DO {
     fn lib_directory_name() -> &'static str {
         "digitalocean"
     }
+
+    fn volume_snapshot_class() -> Option<&'static str> {
+        Some("do-volume-snapshot-0")
+    }
 }
 
 #[derive(Clone, Eq, PartialEq, Hash)]
@@ -45,6 +49,14 @@ pub enum DoStorageType {
     Standard,
 }
 
+impl ToK8sStorageClass for DoStorageType {
+    fn to_k8s_storage_class(&self) -> &str {
+        match self {
+            DoStorageType::Standard => "do-block-storage",
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 pub enum DoRegion {
     NewYorkCity1,
@@ -0,0 +1,497 @@
+use crate::build_platform::Build;
+use crate::cloud_provider::models::{EnvironmentVariable, SecretEnvironmentVariable};
+use crate::cloud_provider::service::{Action, Service, ServiceType};
+use crate::cloud_provider::DeploymentTarget;
+use crate::deployment_action::DeploymentAction;
+use crate::events::{EventDetails, Stage, Transmitter};
+use crate::io_models::container::Registry;
+use crate::io_models::context::Context;
+use crate::io_models::job::{JobAdvancedSettings, JobConcurrencyPolicy, JobSchedule};
+use crate::io_models::progress_listener::{Listener, Listeners};
+use crate::io_models::QoveryIdentifier;
+use crate::logger::Logger;
+use crate::models::container::{ClusterTeraContext, RegistryTeraContext};
+use crate::models::types::{CloudProvider, ToTeraContext};
+use crate::string::cut;
+use crate::utilities::to_short_id;
+use serde::Serialize;
+use std::marker::PhantomData;
+use std::time::Duration;
+use uuid::Uuid;
+
+#[derive(thiserror::Error, Debug)]
+pub enum JobError {
+    #[error("Job invalid configuration: {0}")]
+    InvalidConfig(String),
+}
+
+pub enum ImageSource {
+    /// Image pulled from a registry, it is mirrored into the cluster registry before being used
+    Registry {
+        registry: Box<Registry>,
+        image: String,
+        tag: String,
+    },
+    /// Image built from a git repository during the build of the environment
+    Build(Box<Build>),
+}
+
+pub struct Job<T: CloudProvider> {
+    _marker: PhantomData<T>,
+    pub(super) context: Context,
+    pub(super) id: String,
+    pub(super) long_id: Uuid,
+    pub(super) name: String,
+    pub(super) action: Action,
+    pub(super) schedule: JobSchedule,
+    pub(super) image_source: ImageSource,
+    pub(super) command_args: Vec<String>,
+    pub(super) entrypoint: Option<String>,
+    pub(super) max_nb_restart: u32,
+    pub(super) max_duration_in_sec: u32,
+    pub(super) concurrency_policy: JobConcurrencyPolicy,
+    pub(super) cpu_request_in_mili: u32,
+    pub(super) cpu_limit_in_mili: u32,
+    pub(super) ram_request_in_mib: u32,
+    pub(super) ram_limit_in_mib: u32,
+    pub(super) environment_variables: Vec<EnvironmentVariable>,
+    pub(super) secret_environment_variables: Vec<SecretEnvironmentVariable>,
+    pub(super) depends_on: Vec<Uuid>,
+    pub(super) listeners: Listeners,
+    pub(super) logger: Box<dyn Logger>,
+    pub(super) advanced_settings: JobAdvancedSettings,
+    pub(super) _extra_settings: T::AppExtraSettings,
+}
+
+// Here we define the common behavior among all providers
+impl<T: CloudProvider> Job<T> {
+    pub const QOVERY_MIRROR_REPOSITORY_NAME: &'static str = "qovery-mirror";
+
+    pub fn new(
+        context: Context,
+        long_id: Uuid,
+        name: String,
+        action: Action,
+        schedule: JobSchedule,
+        image_source: ImageSource,
+        command_args: Vec<String>,
+        entrypoint: Option<String>,
+        max_nb_restart: u32,
+        max_duration_in_sec: u32,
+        concurrency_policy: JobConcurrencyPolicy,
+        cpu_request_in_mili: u32,
+        cpu_limit_in_mili: u32,
+        ram_request_in_mib: u32,
+        ram_limit_in_mib: u32,
+        environment_variables: Vec<EnvironmentVariable>,
+        secret_environment_variables: Vec<SecretEnvironmentVariable>,
+        depends_on: Vec<Uuid>,
+        advanced_settings: JobAdvancedSettings,
+        extra_settings: T::AppExtraSettings,
+        listeners: Listeners,
+        logger: Box<dyn Logger>,
+    ) -> Result<Self, JobError> {
+        if let JobSchedule::Cron { schedule } = &schedule {
+            if !is_valid_cron_schedule(schedule) {
+                return Err(JobError::InvalidConfig(format!(
+                    "schedule `{}` is not a valid cron expression",
+                    schedule
+                )));
+            }
+        }
+
+        if max_duration_in_sec == 0 {
+            return Err(JobError::InvalidConfig(
+                "max_duration_in_sec must be greater than 0".to_string(),
+            ));
+        }
+
+        if cpu_request_in_mili > cpu_limit_in_mili {
+            return Err(JobError::InvalidConfig(
+                "cpu_request_in_mili must be less or equal to cpu_limit_in_mili".to_string(),
+            ));
+        }
+
+        if cpu_request_in_mili == 0 {
+            return Err(JobError::InvalidConfig(
+                "cpu_request_in_mili must be greater than 0".to_string(),
+            ));
+        }
+
+        if ram_request_in_mib > ram_limit_in_mib {
+            return Err(JobError::InvalidConfig(
+                "ram_request_in_mib must be less or equal to ram_limit_in_mib".to_string(),
+            ));
+        }
+
+        if ram_request_in_mib == 0 {
+            return Err(JobError::InvalidConfig("ram_request_in_mib must be greater than 0".to_string()));
+        }
+
+        Ok(Self {
+            _marker: PhantomData,
+            context,
+            id: to_short_id(&long_id),
+            long_id,
+            name,
+            action,
+            schedule,
+            image_source,
+            command_args,
+            entrypoint,
+            max_nb_restart,
+            max_duration_in_sec,
+            concurrency_policy,
+            cpu_request_in_mili,
+            cpu_limit_in_mili,
+            ram_request_in_mib,
+            ram_limit_in_mib,
+            environment_variables,
+            secret_environment_variables,
+            depends_on,
+            listeners,
+            logger,
+            advanced_settings,
+            _extra_settings: extra_settings,
+        })
+    }
+
+    pub fn helm_release_name(&self) -> String {
+        format!("job-{}", self.long_id)
+    }
+
+    pub fn helm_chart_dir(&self) -> String {
+        format!("{}/common/services/q-job", self.context.lib_root_dir())
+    }
+
+    fn kube_service_name(&self) -> String {
+        format!("job-{}", to_short_id(&self.long_id))
+    }
+
+    pub fn image_source(&self) -> &ImageSource {
+        &self.image_source
+    }
+
+    pub fn is_cron_job(&self) -> bool {
+        matches!(self.schedule, JobSchedule::Cron { .. })
+    }
+
+    /// Image used by the job, from the cluster registry
+    pub fn image_full(&self, target: &DeploymentTarget) -> String {
+        match &self.image_source {
+            // FIXME: We mirror images to cluster private registry
+            ImageSource::Registry { .. } => {
+                let registry_info = target.container_registry.registry_info();
+                format!(
+                    "{}/{}:{}",
+                    registry_info.endpoint.host_str().unwrap_or_default(),
+                    (registry_info.get_image_name)(Self::QOVERY_MIRROR_REPOSITORY_NAME),
+                    self.tag_for_mirror().unwrap_or_default()
+                )
+            }
+            ImageSource::Build(build) => build.image.full_image_name_with_tag(),
+        }
+    }
+
+    /// Tag of the image once mirrored into the cluster registry, None if the image is built by us
+    pub fn tag_for_mirror(&self) -> Option<String> {
+        match &self.image_source {
+            // A tag name must be valid ASCII and may contain lowercase and uppercase letters, digits, underscores, periods and dashes.
+            // A tag name may not start with a period or a dash and may contain a maximum of 128 characters.
+            ImageSource::Registry { image, tag, .. } => {
+                Some(cut(format!("{}.{}.{}", image.replace('/', "."), tag, self.long_id), 128))
+            }
+            ImageSource::Build(_) => None,
+        }
+    }
+
+    pub(super) fn default_tera_context(&self, target: &DeploymentTarget) -> JobTeraContext {
+        let environment = &target.environment;
+        let kubernetes = &target.kubernetes;
+        let registry_info = target.container_registry.registry_info();
+
+        JobTeraContext {
+            organization_long_id: environment.organization_long_id,
+            project_long_id: environment.project_long_id,
+            environment_short_id: to_short_id(&environment.long_id),
+            environment_long_id: environment.long_id,
+            cluster: ClusterTeraContext {
+                long_id: *kubernetes.long_id(),
+                name: kubernetes.name().to_string(),
+                region: kubernetes.region().to_string(),
+                zone: kubernetes.zone().to_string(),
+            },
+            namespace: environment.namespace().to_string(),
+            service: ServiceTeraContext {
+                short_id: to_short_id(&self.long_id),
+                long_id: self.long_id,
+                name: self.kube_service_name(),
+                user_unsafe_name: self.name.clone(),
+                image_full: self.image_full(target),
+                command_args: self.command_args.clone(),
+                entrypoint: self.entrypoint.clone(),
+                cpu_request_in_mili: format!("{}m", self.cpu_request_in_mili),
+                cpu_limit_in_mili: format!("{}m", self.cpu_limit_in_mili),
+                ram_request_in_mib: format!("{}Mi", self.ram_request_in_mib),
+                ram_limit_in_mib: format!("{}Mi", self.ram_limit_in_mib),
+                cronjob_schedule: match &self.schedule {
+                    JobSchedule::OnStart => None,
+                    JobSchedule::Cron { schedule } => Some(schedule.clone()),
+                },
+                concurrency_policy: self.concurrency_policy.to_k8s_concurrency_policy().to_string(),
+                max_nb_restart: self.max_nb_restart,
+                max_duration_in_sec: self.max_duration_in_sec,
+                advanced_settings: self.advanced_settings.clone(),
+            },
+            registry: registry_info
+                .registry_docker_json_config
+                .as_ref()
+                .map(|docker_json| RegistryTeraContext {
+                    secret_name: format!("{}-registry", self.kube_service_name()),
+                    docker_json_config: docker_json.to_string(),
+                }),
+            environment_variables: self.environment_variables.clone(),
+            resource_expiration_in_seconds: Some(kubernetes.advanced_settings().pleco_resources_ttl),
+        }
+    }
+
+    pub fn context(&self) -> &Context {
+        &self.context
+    }
+
+    pub fn environment_variables(&self) -> &[EnvironmentVariable] {
+        &self.environment_variables
+    }
+
+    pub fn secret_environment_variables(&self) -> &[SecretEnvironmentVariable] {
+        &self.secret_environment_variables
+    }
+
+    pub fn service_type(&self) -> ServiceType {
+        ServiceType::Job
+    }
+
+    pub fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn action(&self) -> &Action {
+        &self.action
+    }
+
+    pub fn logger(&self) -> &dyn Logger {
+        &*self.logger
+    }
+
+    pub fn selector(&self) -> String {
+        format!("qovery.com/service-id={}", self.long_id)
+    }
+
+    fn version(&self) -> String {
+        match &self.image_source {
+            ImageSource::Registry { image, tag, .. } => format!("{}:{}", image, tag),
+            ImageSource::Build(build) => build.git_repository.commit_id.clone(),
+        }
+    }
+
+    pub(crate) fn get_event_details(&self, stage: Stage) -> EventDetails {
+        let context = self.context();
+        EventDetails::new(
+            None,
+            QoveryIdentifier::new(*context.organization_long_id()),
+            QoveryIdentifier::new(*context.cluster_long_id()),
+            context.execution_id().to_string(),
+            None,
+            stage,
+            self.to_transmitter(),
+        )
+    }
+}
+
+impl<T: CloudProvider> Service for Job<T> {
+    fn context(&self) -> &Context {
+        self.context()
+    }
+
+    fn service_type(&self) -> ServiceType {
+        self.service_type()
+    }
+
+    fn id(&self) -> &str {
+        self.id()
+    }
+
+    fn long_id(&self) -> &Uuid {
+        &self.long_id
+    }
+
+    fn name(&self) -> &str {
+        self.name()
+    }
+
+    fn sanitized_name(&self) -> String {
+        self.name.to_string()
+    }
+
+    fn version(&self) -> String {
+        self.version()
+    }
+
+    fn action(&self) -> &Action {
+        self.action()
+    }
+
+    fn selector(&self) -> Option<String> {
+        Some(self.selector())
+    }
+
+    fn logger(&self) -> &dyn Logger {
+        self.logger()
+    }
+
+    fn listeners(&self) -> &Listeners {
+        &self.listeners
+    }
+
+    fn add_listener(&mut self, listener: Listener) {
+        self.listeners.push(listener);
+    }
+
+    fn to_transmitter(&self) -> Transmitter {
+        Transmitter::Job(self.long_id, self.name.to_string(), self.version())
+    }
+
+    fn as_service(&self) -> &dyn Service {
+        self
+    }
+}
+
+pub trait JobService: Service + DeploymentAction + ToTeraContext {
+    fn helm_release_name(&self) -> String;
+    fn is_cron_job(&self) -> bool;
+    /// None if the image is not built from a git repository
+    fn get_build(&self) -> Option<&Build>;
+    fn get_build_mut(&mut self) -> Option<&mut Build>;
+    fn max_duration(&self) -> Duration;
+    fn advanced_settings(&self) -> &JobAdvancedSettings;
+    /// services (by long id) which must be deployed before this job
+    fn depends_on(&self) -> &[Uuid];
+}
+
+impl<T: CloudProvider> JobService for Job<T>
+where
+    Job<T>: Service + ToTeraContext + DeploymentAction,
+{
+    fn helm_release_name(&self) -> String {
+        self.helm_release_name()
+    }
+
+    fn is_cron_job(&self) -> bool {
+        self.is_cron_job()
+    }
+
+    fn get_build(&self) -> Option<&Build> {
+        match &self.image_source {
+            ImageSource::Registry { .. } => None,
+            ImageSource::Build(build) => Some(build),
+        }
+    }
+
+    fn get_build_mut(&mut self) -> Option<&mut Build> {
+        match &mut self.image_source {
+            ImageSource::Registry { .. } => None,
+            ImageSource::Build(build) => Some(build),
+        }
+    }
+
+    fn max_duration(&self) -> Duration {
+        Duration::from_secs(self.max_duration_in_sec as u64)
+    }
+
+    fn advanced_settings(&self) -> &JobAdvancedSettings {
+        &self.advanced_settings
+    }
+
+    fn depends_on(&self) -> &[Uuid] {
+        &self.depends_on
+    }
+}
+
+/// Only checks the shape of the expression, Kubernetes validates the fields themselves
+fn is_valid_cron_schedule(schedule: &str) -> bool {
+    let schedule = schedule.trim();
+    if schedule.starts_with('@') {
+        return matches!(
+            schedule,
+            "@yearly" | "@annually" | "@monthly" | "@weekly" | "@daily" | "@midnight" | "@hourly"
+        );
+    }
+
+    schedule.split_whitespace().count() == 5
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub(super) struct ServiceTeraContext {
+    pub(super) short_id: String,
+    pub(super) long_id: Uuid,
+    pub(super) name: String,
+    pub(super) user_unsafe_name: String,
+    pub(super) image_full: String,
+    pub(super) command_args: Vec<String>,
+    pub(super) entrypoint: Option<String>,
+    pub(super) cpu_request_in_mili: String,
+    pub(super) cpu_limit_in_mili: String,
+    pub(super) ram_request_in_mib: String,
+    pub(super) ram_limit_in_mib: String,
+    /// None for jobs running once at each deployment
+    pub(super) cronjob_schedule: Option<String>,
+    pub(super) concurrency_policy: String,
+    pub(super) max_nb_restart: u32,
+    pub(super) max_duration_in_sec: u32,
+    pub(super) advanced_settings: JobAdvancedSettings,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub(super) struct JobTeraContext {
+    pub(super) organization_long_id: Uuid,
+    pub(super) project_long_id: Uuid,
+    pub(super) environment_short_id: String,
+    pub(super) environment_long_id: Uuid,
+    pub(super) cluster: ClusterTeraContext,
+    pub(super) namespace: String,
+    pub(super) service: ServiceTeraContext,
+    pub(super) registry: Option<RegistryTeraContext>,
+    pub(super) environment_variables: Vec<EnvironmentVariable>,
+    pub(super) resource_expiration_in_seconds: Option<i32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_cron_schedule() {
+        // setup:
+        let test_cases = vec![
+            ("*/5 * * * *", true),
+            ("0 3 * * 1-5", true),
+            (" @hourly ", true),
+            ("@every 5m", false),
+            ("* * * *", false),
+            ("0 0 * * * *", false),
+            ("", false),
+        ];
+
+        for (schedule, expected) in test_cases {
+            // execute:
+            let result = is_valid_cron_schedule(schedule);
+
+            // verify:
+            assert_eq!(expected, result, "schedule `{}`", schedule);
+        }
+    }
+}
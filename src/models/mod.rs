@@ -4,7 +4,10 @@ pub mod aws_ec2;
 pub mod container;
 pub mod database;
 pub(crate) mod database_utils;
+pub mod deployment_strategy;
 pub mod digital_ocean;
+pub mod job;
 pub mod router;
 pub mod scaleway;
+pub mod self_managed;
 pub mod types;
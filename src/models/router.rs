@@ -1,17 +1,25 @@
-use crate::cloud_provider::models::{CustomDomain, CustomDomainDataTemplate, HostDataTemplate, Route};
+use crate::cloud_provider::models::{
+    CustomCertificateDataTemplate, CustomDomain, CustomDomainDataTemplate, HostDataTemplate, HostPathDataTemplate,
+    IngressDataTemplate, Route,
+};
 use crate::cloud_provider::service::{default_tera_context, Action, Service, ServiceType};
 use crate::cloud_provider::utilities::sanitize_name;
 use crate::cloud_provider::DeploymentTarget;
+use crate::deployment_action::scale_to_zero::ACTIVATOR_SERVICE_NAME;
 use crate::deployment_action::DeploymentAction;
 use crate::errors::EngineError;
 use crate::events::{EnvironmentStep, Stage, Transmitter};
+use crate::io_models::application::Port;
 use crate::io_models::context::Context;
 use crate::io_models::progress_listener::{Listener, Listeners};
 use crate::logger::Logger;
 use crate::models::types::CloudProvider;
 use crate::models::types::ToTeraContext;
 use crate::utilities::to_short_id;
+use itertools::Itertools;
 use std::borrow::Borrow;
+use std::collections::HashSet;
+use std::iter;
 use std::marker::PhantomData;
 use tera::Context as TeraContext;
 use uuid::Uuid;
@@ -25,6 +33,20 @@ pub enum RouterError {
 pub struct RouterAdvancedSettings {
     pub custom_domain_check_enabled: bool,
     pub whitelist_source_range: String,
+    /// longest timeout of the services behind the router, 0 to not wait for the certificate
+    pub custom_domain_certificate_timeout_sec: u32,
+    pub custom_domain_certificate_required: bool,
+}
+
+impl Default for RouterAdvancedSettings {
+    fn default() -> Self {
+        RouterAdvancedSettings {
+            custom_domain_check_enabled: true,
+            whitelist_source_range: "0.0.0.0/0".to_string(),
+            custom_domain_certificate_timeout_sec: 0,
+            custom_domain_certificate_required: false,
+        }
+    }
 }
 
 pub struct Router<T: CloudProvider> {
@@ -90,71 +112,60 @@ impl<T: CloudProvider> Router<T> {
         let mut context = default_tera_context(self, kubernetes, environment);
         let event_details = self.get_event_details(Stage::Environment(EnvironmentStep::LoadConfiguration));
 
+        // certificates of custom domains are requested by cert-manager, unless supplied by the user
         let custom_domain_data_templates = self
             .custom_domains
             .iter()
+            .filter(|cd| cd.certificate.is_none())
             .map(|cd| CustomDomainDataTemplate {
                 domain: cd.domain.clone(),
             })
             .collect::<Vec<_>>();
+        let custom_certificate_data_templates = self
+            .custom_domains
+            .iter()
+            .filter(|cd| cd.certificate.is_some())
+            .map(|cd| CustomCertificateDataTemplate {
+                domain: cd.domain.clone(),
+                secret_name: self.custom_certificate_secret_name(&cd.domain),
+            })
+            .collect::<Vec<_>>();
 
-        // We can only have 1 router per application/container.
-        // Core never mix multiple services inside one router
-        let service_id = self
-            .routes
-            .first()
-            .map(|r| r.service_long_id)
-            .ok_or_else(|| EngineError::new_router_failed_to_deploy(event_details.clone()))?;
-
-        // Check if the service is an application
-        let (service_name, ports) =
-            if let Some(application) = &environment.applications.iter().find(|app| app.long_id() == &service_id) {
+        // a router fans out to several applications/containers by path prefix
+        let mut routed_services = Vec::with_capacity(self.routes.len());
+        for route in &self.routes {
+            let (service_name, ports) = if let Some(application) = environment
+                .applications
+                .iter()
+                .find(|app| app.long_id() == &route.service_long_id)
+            {
                 (application.sanitized_name(), application.public_ports())
             } else {
                 let container = environment
                     .containers
                     .iter()
-                    .find(|container| container.long_id() == &service_id)
-                    .ok_or_else(|| EngineError::new_router_failed_to_deploy(event_details))?;
+                    .find(|container| container.long_id() == &route.service_long_id)
+                    .ok_or_else(|| EngineError::new_router_failed_to_deploy(event_details.clone()))?;
 
                 (container.kube_service_name(), container.public_ports())
             };
 
-        // (custom_domain + default_domain) * (ports + default_port)
-        let mut hosts: Vec<HostDataTemplate> =
-            Vec::with_capacity((custom_domain_data_templates.len() + 1) * (ports.len() + 1));
-        for port in ports {
-            hosts.push(HostDataTemplate {
-                domain_name: format!("p{}-{}", port.port, self.default_domain),
-                service_name: service_name.clone(),
-                service_port: port.port,
+            routed_services.push(RoutedService {
+                route,
+                service_name,
+                ports,
             });
-
-            if port.is_default {
-                hosts.push(HostDataTemplate {
-                    domain_name: self.default_domain.clone(),
-                    service_name: service_name.clone(),
-                    service_port: port.port,
-                });
-            }
-
-            for custom_domain in &self.custom_domains {
-                hosts.push(HostDataTemplate {
-                    domain_name: format!("p{}.{}", port.port, custom_domain.domain),
-                    service_name: service_name.clone(),
-                    service_port: port.port,
-                });
-
-                if port.is_default {
-                    hosts.push(HostDataTemplate {
-                        domain_name: custom_domain.domain.clone(),
-                        service_name: service_name.clone(),
-                        service_port: port.port,
-                    });
-                }
-            }
         }
 
+        let ingresses = router_ingresses(
+            &self.sanitized_name(),
+            &self.default_domain,
+            &self.custom_domains,
+            &custom_certificate_data_templates,
+            &routed_services,
+        )
+        .map_err(|reason| EngineError::new_router_invalid_route(event_details.clone(), reason))?;
+
         // whitelist source ranges
         if self.advanced_settings.whitelist_source_range.contains("0.0.0.0") {
             // if whitelist source range contains 0.0.0.0, then we don't need to add the whitelist source range
@@ -187,7 +198,7 @@ impl<T: CloudProvider> Router<T> {
             &router_should_declare_domain_to_external_dns,
         );
         context.insert("custom_domains", &custom_domain_data_templates);
-        context.insert("hosts", &hosts);
+        context.insert("ingresses", &ingresses);
         context.insert("spec_acme_email", "tls@qovery.com"); // TODO CHANGE ME
         context.insert("metadata_annotations_cert_manager_cluster_issuer", "letsencrypt-qovery");
 
@@ -199,9 +210,11 @@ impl<T: CloudProvider> Router<T> {
 
         // Nginx
         context.insert("sticky_sessions_enabled", &self.sticky_sessions_enabled);
+        context.insert("scale_to_zero_enabled", &environment.idle_timeout.is_some());
+        context.insert("scale_to_zero_activator_service_name", ACTIVATOR_SERVICE_NAME);
 
         // ingress advanced settings
-        // 1 app == 1 ingress, we filter only on the app to retrieve advanced settings
+        // annotations are shared by all the routes, advanced settings are the ones of the first routed service
         if let Some(route) = self.routes.first() {
             if let Some(advanced_settings) = environment
                 .applications
@@ -229,6 +242,11 @@ impl<T: CloudProvider> Router<T> {
         crate::string::cut(format!("router-{}", self.id), 50)
     }
 
+    /// Kubernetes TLS secret holding the user supplied certificate of a custom domain
+    pub fn custom_certificate_secret_name(&self, domain: &str) -> String {
+        format!("router-tls-{}-{}", self.id, domain.replace('.', "-"))
+    }
+
     pub fn helm_chart_dir(&self) -> String {
         format!("{}/common/charts/q-ingress-tls", self.context.lib_root_dir(),)
     }
@@ -292,9 +310,204 @@ impl<T: CloudProvider> Service for Router<T> {
     }
 }
 
+/// A route along with the service it targets
+struct RoutedService<'a> {
+    route: &'a Route,
+    service_name: String,
+    ports: Vec<&'a Port>,
+}
+
+/// Ingresses of the router, one per distinct rewrite as nginx only allows it per ingress.
+/// The one named after the router holds the routes without rewrite, if any.
+///
+/// A route is exposed on the router domains through the requested port, or the default one of its service,
+/// and on the `p<port>` sub domains for every public port of its service.
+///
+/// Domains with a user supplied certificate get their own ingresses, as cert-manager requests a certificate
+/// for every TLS entry of the ingresses it watches.
+fn router_ingresses(
+    sanitized_name: &str,
+    default_domain: &str,
+    custom_domains: &[CustomDomain],
+    custom_certificates: &[CustomCertificateDataTemplate],
+    routed_services: &[RoutedService],
+) -> Result<Vec<IngressDataTemplate>, String> {
+    // ingresses by rewrite and whether their domains have a user supplied certificate
+    let mut ingresses: Vec<(Option<String>, bool, Vec<HostDataTemplate>)> = vec![(None, false, vec![])];
+    // the first route declared for a path of a domain wins
+    let mut routed_paths: HashSet<(String, String)> = HashSet::new();
+
+    for routed_service in routed_services {
+        let route = routed_service.route;
+        let port = match route.service_port {
+            Some(service_port) => Some(
+                routed_service
+                    .ports
+                    .iter()
+                    .find(|port| port.port == service_port)
+                    .ok_or_else(|| {
+                        format!(
+                            "route `{}` targets port {} which is not publicly exposed by service `{}`",
+                            route.path, service_port, routed_service.service_name
+                        )
+                    })?,
+            ),
+            None => routed_service.ports.iter().find(|port| port.is_default),
+        };
+
+        let (path, path_type) = match route.rewrite_path {
+            Some(_) => (
+                format!("{}(/|$)(.*)", regex::escape(route.path.trim_end_matches('/'))),
+                "ImplementationSpecific",
+            ),
+            None => (route.path.clone(), "Prefix"),
+        };
+
+        let domains = port.into_iter().flat_map(|port| {
+            iter::once(default_domain.to_string())
+                .chain(custom_domains.iter().map(|cd| cd.domain.clone()))
+                .map(move |domain| (domain, port.port))
+        });
+        let port_domains = routed_service.ports.iter().flat_map(|port| {
+            iter::once(format!("p{}-{}", port.port, default_domain))
+                .chain(
+                    custom_domains
+                        .iter()
+                        .map(move |cd| format!("p{}.{}", port.port, cd.domain)),
+                )
+                .map(move |domain| (domain, port.port))
+        });
+
+        for (domain, service_port) in domains.chain(port_domains) {
+            if !routed_paths.insert((domain.clone(), route.path.clone())) {
+                continue;
+            }
+
+            let has_custom_certificate = custom_certificates.iter().any(|cert| cert.domain == domain);
+            let ingress_index = match ingresses.iter().position(|(rewrite, custom_certificate, _)| {
+                rewrite == &route.rewrite_path && *custom_certificate == has_custom_certificate
+            }) {
+                Some(index) => index,
+                None => {
+                    ingresses.push((route.rewrite_path.clone(), has_custom_certificate, vec![]));
+                    ingresses.len() - 1
+                }
+            };
+
+            let host_path = HostPathDataTemplate {
+                path: path.clone(),
+                path_type: path_type.to_string(),
+                service_name: routed_service.service_name.clone(),
+                service_port,
+            };
+            let hosts = &mut ingresses[ingress_index].2;
+            match hosts.iter_mut().find(|host| host.domain_name == domain) {
+                Some(host) => host.paths.push(host_path),
+                None => hosts.push(HostDataTemplate {
+                    domain_name: domain,
+                    paths: vec![host_path],
+                }),
+            }
+        }
+    }
+
+    // nginx applies `use-regex` to every path of a host once an ingress of this host sets it: the prefix paths
+    // sharing a host with a rewrite are written as regexes matching the same requests
+    let regex_domains: HashSet<String> = ingresses
+        .iter()
+        .filter(|(rewrite, _, _)| rewrite.is_some())
+        .flat_map(|(_, _, hosts)| hosts.iter().map(|host| host.domain_name.clone()))
+        .collect();
+    for (_, _, hosts) in ingresses.iter_mut().filter(|(rewrite, _, _)| rewrite.is_none()) {
+        for host in hosts
+            .iter_mut()
+            .filter(|host| regex_domains.contains(&host.domain_name))
+        {
+            for host_path in host.paths.iter_mut() {
+                host_path.path = prefix_path_regex(&host_path.path);
+                host_path.path_type = "ImplementationSpecific".to_string();
+            }
+        }
+    }
+
+    let mut ingress_names: HashSet<String> = HashSet::new();
+    Ok(ingresses
+        .into_iter()
+        .filter(|(_, _, hosts)| !hosts.is_empty())
+        .map(|(rewrite, has_custom_certificate, hosts)| {
+            let name = unique_ingress_name(
+                &mut ingress_names,
+                ingress_name(sanitized_name, rewrite.as_deref(), has_custom_certificate),
+            );
+
+            IngressDataTemplate {
+                name,
+                rewrite_target: rewrite.map(|rewrite| format!("{}/$2", rewrite.trim_end_matches('/'))),
+                custom_certificates: match has_custom_certificate {
+                    true => custom_certificates
+                        .iter()
+                        .filter(|cert| hosts.iter().any(|host| host.domain_name == cert.domain))
+                        .cloned()
+                        .collect(),
+                    false => vec![],
+                },
+                hosts,
+            }
+        })
+        .collect())
+}
+
+/// Regex matching the same requests as the given `Prefix` path
+fn prefix_path_regex(path: &str) -> String {
+    match path.trim_end_matches('/') {
+        "" => "/".to_string(),
+        path => format!("{}(/|$)", regex::escape(path)),
+    }
+}
+
+/// Ingresses are named after what they group, so adding a route does not rename the existing ones:
+/// the router name for the routes without rewrite, suffixed by the rewrite and `tls` for the domains with a user
+/// supplied certificate.
+fn ingress_name(sanitized_name: &str, rewrite: Option<&str>, has_custom_certificate: bool) -> String {
+    let mut name = sanitized_name.to_string();
+    if let Some(rewrite) = rewrite {
+        let rewrite = rewrite
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect::<String>();
+        let rewrite = rewrite.trim_matches('-');
+        name.push_str("-rewrite-");
+        name.push_str(match rewrite.is_empty() {
+            true => "root",
+            false => &rewrite[..rewrite.len().min(40)],
+        });
+    }
+    if has_custom_certificate {
+        name.push_str("-tls");
+    }
+
+    name.trim_end_matches('-').to_string()
+}
+
+/// Rewrites only differing by their special characters share the same name, the later ones get a suffix
+fn unique_ingress_name(names: &mut HashSet<String>, name: String) -> String {
+    let mut unique_name = name.clone();
+    let mut index = 1;
+    while !names.insert(unique_name.clone()) {
+        index += 1;
+        unique_name = format!("{}-{}", name, index);
+    }
+
+    unique_name
+}
+
 pub trait RouterService: Service + DeploymentAction + ToTeraContext {
     /// all domains (auto-generated by Qovery and user custom domains) associated to the router
     fn has_custom_domains(&self) -> bool;
+    /// services (by long id) targeted by the routes of this router
+    fn routed_services(&self) -> Vec<Uuid>;
+    fn helm_release_name(&self) -> String;
 }
 
 impl<T: CloudProvider> RouterService for Router<T>
@@ -304,4 +517,292 @@ where
     fn has_custom_domains(&self) -> bool {
         !self.custom_domains.is_empty()
     }
+
+    fn routed_services(&self) -> Vec<Uuid> {
+        self.routes.iter().map(|route| route.service_long_id).unique().collect()
+    }
+
+    fn helm_release_name(&self) -> String {
+        self.helm_release_name()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cloud_provider::models::{
+        CustomCertificateDataTemplate, CustomDomain, HostDataTemplate, HostPathDataTemplate, Route,
+    };
+    use crate::io_models::application::{Port, Protocol};
+    use crate::io_models::router::CustomDomainCertificate;
+    use crate::models::router::{ingress_name, prefix_path_regex, router_ingresses, RoutedService};
+    use uuid::Uuid;
+
+    fn port(port: u16, is_default: bool) -> Port {
+        Port {
+            id: port.to_string(),
+            long_id: Uuid::new_v4(),
+            port,
+            is_default,
+            name: None,
+            publicly_accessible: true,
+            protocol: Protocol::HTTP,
+        }
+    }
+
+    fn route(path: &str, rewrite_path: Option<&str>, service_port: Option<u16>) -> Route {
+        Route {
+            path: path.to_string(),
+            service_long_id: Uuid::new_v4(),
+            rewrite_path: rewrite_path.map(str::to_string),
+            service_port,
+        }
+    }
+
+    fn host_path(path: &str, path_type: &str, service_name: &str, service_port: u16) -> HostPathDataTemplate {
+        HostPathDataTemplate {
+            path: path.to_string(),
+            path_type: path_type.to_string(),
+            service_name: service_name.to_string(),
+            service_port,
+        }
+    }
+
+    #[test]
+    fn test_router_ingresses_single_service() {
+        // setup:
+        let ports = vec![port(8080, true), port(9090, false)];
+        let route = route("/", None, None);
+        let custom_domains = vec![CustomDomain {
+            domain: "www.example.com".to_string(),
+            target_domain: "router.example.com".to_string(),
+            certificate: None,
+        }];
+
+        // execute:
+        let ingresses = router_ingresses(
+            "router-abc",
+            "router.example.com",
+            &custom_domains,
+            &[],
+            &[RoutedService {
+                route: &route,
+                service_name: "app".to_string(),
+                ports: ports.iter().collect(),
+            }],
+        )
+        .unwrap();
+
+        // verify:
+        assert_eq!(ingresses.len(), 1);
+        assert_eq!(ingresses[0].name, "router-abc");
+        assert_eq!(ingresses[0].rewrite_target, None);
+        let hosts = &ingresses[0].hosts;
+        assert_eq!(
+            hosts.iter().map(|host| host.domain_name.as_str()).collect::<Vec<_>>(),
+            vec![
+                "router.example.com",
+                "www.example.com",
+                "p8080-router.example.com",
+                "p8080.www.example.com",
+                "p9090-router.example.com",
+                "p9090.www.example.com",
+            ]
+        );
+        assert_eq!(hosts[1].paths, vec![host_path("/", "Prefix", "app", 8080)]);
+        assert_eq!(hosts[4].paths, vec![host_path("/", "Prefix", "app", 9090)]);
+    }
+
+    #[test]
+    fn test_router_ingresses_fan_out_by_path() {
+        // setup:
+        let frontend_ports = vec![port(80, true)];
+        let backend_ports = vec![port(80, false), port(8080, true)];
+        let frontend_route = route("/", None, None);
+        let backend_route = route("/api/", Some("/"), Some(80));
+        let routed_services = vec![
+            RoutedService {
+                route: &frontend_route,
+                service_name: "frontend".to_string(),
+                ports: frontend_ports.iter().collect(),
+            },
+            RoutedService {
+                route: &backend_route,
+                service_name: "backend".to_string(),
+                ports: backend_ports.iter().collect(),
+            },
+        ];
+
+        // execute:
+        let ingresses = router_ingresses("router-abc", "router.example.com", &[], &[], &routed_services).unwrap();
+
+        // verify:
+        assert_eq!(ingresses.len(), 2);
+        assert_eq!(ingresses[0].name, "router-abc");
+        assert_eq!(
+            ingresses[0].hosts,
+            vec![
+                // these hosts also have a rewrite, their paths are regexes
+                HostDataTemplate {
+                    domain_name: "router.example.com".to_string(),
+                    paths: vec![host_path("/", "ImplementationSpecific", "frontend", 80)],
+                },
+                HostDataTemplate {
+                    domain_name: "p80-router.example.com".to_string(),
+                    paths: vec![host_path("/", "ImplementationSpecific", "frontend", 80)],
+                },
+            ]
+        );
+        assert_eq!(ingresses[1].name, "router-abc-rewrite-root");
+        assert_eq!(ingresses[1].rewrite_target, Some("/$2".to_string()));
+        let api_path = host_path("/api(/|$)(.*)", "ImplementationSpecific", "backend", 80);
+        assert_eq!(
+            ingresses[1].hosts,
+            vec![
+                HostDataTemplate {
+                    domain_name: "router.example.com".to_string(),
+                    paths: vec![api_path.clone()],
+                },
+                HostDataTemplate {
+                    domain_name: "p80-router.example.com".to_string(),
+                    paths: vec![api_path],
+                },
+                HostDataTemplate {
+                    domain_name: "p8080-router.example.com".to_string(),
+                    paths: vec![host_path("/api(/|$)(.*)", "ImplementationSpecific", "backend", 8080)],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_router_ingresses_unexposed_port() {
+        // setup:
+        let ports = vec![port(8080, true)];
+        let route = route("/", None, Some(9090));
+
+        // execute:
+        let ingresses = router_ingresses(
+            "router-abc",
+            "router.example.com",
+            &[],
+            &[],
+            &[RoutedService {
+                route: &route,
+                service_name: "app".to_string(),
+                ports: ports.iter().collect(),
+            }],
+        );
+
+        // verify:
+        assert!(ingresses.is_err());
+    }
+
+    #[test]
+    fn test_router_ingresses_custom_certificate() {
+        // setup:
+        let ports = vec![port(8080, true)];
+        let route = route("/", None, None);
+        let custom_domains = vec![
+            CustomDomain {
+                domain: "www.example.com".to_string(),
+                target_domain: "router.example.com".to_string(),
+                certificate: None,
+            },
+            CustomDomain {
+                domain: "corp.example.com".to_string(),
+                target_domain: "router.example.com".to_string(),
+                certificate: Some(CustomDomainCertificate::Vault {
+                    mount: "certificates".to_string(),
+                    path: "corp".to_string(),
+                }),
+            },
+        ];
+        let custom_certificates = vec![CustomCertificateDataTemplate {
+            domain: "corp.example.com".to_string(),
+            secret_name: "router-tls-abc-corp-example-com".to_string(),
+        }];
+
+        // execute:
+        let ingresses = router_ingresses(
+            "router-abc",
+            "router.example.com",
+            &custom_domains,
+            &custom_certificates,
+            &[RoutedService {
+                route: &route,
+                service_name: "app".to_string(),
+                ports: ports.iter().collect(),
+            }],
+        )
+        .unwrap();
+
+        // verify:
+        assert_eq!(ingresses.len(), 2);
+        assert_eq!(ingresses[0].name, "router-abc");
+        assert!(ingresses[0].custom_certificates.is_empty());
+        assert!(!ingresses[0]
+            .hosts
+            .iter()
+            .any(|host| host.domain_name == "corp.example.com"));
+        assert_eq!(ingresses[1].name, "router-abc-tls");
+        assert_eq!(ingresses[1].custom_certificates, custom_certificates);
+        assert_eq!(
+            ingresses[1].hosts,
+            vec![HostDataTemplate {
+                domain_name: "corp.example.com".to_string(),
+                paths: vec![host_path("/", "Prefix", "app", 8080)],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_router_ingresses_regex_paths_are_escaped() {
+        // setup:
+        let ports = vec![port(80, true)];
+        let docs_route = route("/docs.v1/", None, None);
+        let api_route = route("/api.v1", Some("/"), None);
+        let routed_services = vec![
+            RoutedService {
+                route: &docs_route,
+                service_name: "docs".to_string(),
+                ports: ports.iter().collect(),
+            },
+            RoutedService {
+                route: &api_route,
+                service_name: "api".to_string(),
+                ports: ports.iter().collect(),
+            },
+        ];
+
+        // execute:
+        let ingresses = router_ingresses("router-abc", "router.example.com", &[], &[], &routed_services).unwrap();
+
+        // verify:
+        assert_eq!(
+            ingresses[0].hosts[0].paths,
+            vec![host_path("/docs\\.v1(/|$)", "ImplementationSpecific", "docs", 80)]
+        );
+        assert_eq!(
+            ingresses[1].hosts[0].paths,
+            vec![host_path("/api\\.v1(/|$)(.*)", "ImplementationSpecific", "api", 80)]
+        );
+    }
+
+    #[test]
+    fn test_ingress_name() {
+        assert_eq!(ingress_name("router-abc", None, false), "router-abc");
+        assert_eq!(ingress_name("router-abc", None, true), "router-abc-tls");
+        assert_eq!(ingress_name("router-abc", Some("/"), false), "router-abc-rewrite-root");
+        assert_eq!(
+            ingress_name("router-abc", Some("/V1/Api/"), true),
+            "router-abc-rewrite-v1-api-tls"
+        );
+    }
+
+    #[test]
+    fn test_prefix_path_regex() {
+        assert_eq!(prefix_path_regex("/"), "/");
+        assert_eq!(prefix_path_regex("/api/"), "/api(/|$)");
+        assert_eq!(prefix_path_regex("/a.b"), "/a\\.b(/|$)");
+    }
 }
@@ -5,8 +5,7 @@ use crate::errors::EngineError;
 use crate::events::{EnvironmentStep, Stage};
 use crate::io_models::progress_listener::ListenersHelper;
 use crate::models::application::Application;
-use crate::models::scaleway::ScwStorageType;
-use crate::models::types::{ToTeraContext, SCW};
+use crate::models::types::{ToK8sStorageClass, ToTeraContext, SCW};
 use tera::Context as TeraContext;
 
 impl ToTeraContext for Application<SCW> {
@@ -56,14 +55,7 @@ impl ToTeraContext for Application<SCW> {
                 id: s.id.clone(),
                 long_id: self.long_id,
                 name: s.name.clone(),
-                storage_type: match s.storage_type {
-                    // TODO(benjaminch): Switch to proper storage class
-                    // Note: Seems volume storage type are not supported, only blocked storage for the time being
-                    // https://github.com/scaleway/scaleway-csi/tree/master/examples/kubernetes#different-storageclass
-                    ScwStorageType::BlockSsd => "scw-sbv-ssd-0", // "b_ssd",
-                    ScwStorageType::LocalSsd => "l_ssd",
-                }
-                .to_string(),
+                storage_type: s.storage_type.to_k8s_storage_class().to_string(),
                 size_in_gib: s.size_in_gib,
                 mount_point: s.mount_point.clone(),
                 snapshot_retention_in_days: s.snapshot_retention_in_days,
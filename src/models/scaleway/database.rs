@@ -7,7 +7,8 @@ use crate::errors::EngineError;
 use crate::events::{EnvironmentStep, EventDetails, Stage};
 use crate::io_models::database::DatabaseOptions;
 use crate::models::database::{
-    Container, Database, DatabaseMode, DatabaseType, Managed, MongoDB, MySQL, PostgresSQL, Redis,
+    ClickHouse, Container, Database, DatabaseMode, DatabaseType, Kafka, Managed, MongoDB, MySQL, OpenSearch,
+    PostgresSQL, RabbitMQ, Redis,
 };
 use crate::models::database_utils::{
     get_self_hosted_mongodb_version, get_self_hosted_mysql_version, get_self_hosted_postgres_version,
@@ -78,6 +79,62 @@ impl DatabaseType<SCW, Container> for MongoDB {
     }
 }
 
+impl DatabaseType<SCW, Container> for OpenSearch {
+    type DatabaseOptions = DatabaseOptions;
+
+    fn short_name() -> &'static str {
+        "OpenSearch"
+    }
+    fn lib_directory_name() -> &'static str {
+        "opensearch"
+    }
+    fn db_type() -> service::DatabaseType {
+        service::DatabaseType::OpenSearch
+    }
+}
+
+impl DatabaseType<SCW, Container> for ClickHouse {
+    type DatabaseOptions = DatabaseOptions;
+
+    fn short_name() -> &'static str {
+        "ClickHouse"
+    }
+    fn lib_directory_name() -> &'static str {
+        "clickhouse"
+    }
+    fn db_type() -> service::DatabaseType {
+        service::DatabaseType::ClickHouse
+    }
+}
+
+impl DatabaseType<SCW, Container> for RabbitMQ {
+    type DatabaseOptions = DatabaseOptions;
+
+    fn short_name() -> &'static str {
+        "RabbitMQ"
+    }
+    fn lib_directory_name() -> &'static str {
+        "rabbitmq"
+    }
+    fn db_type() -> service::DatabaseType {
+        service::DatabaseType::RabbitMQ
+    }
+}
+
+impl DatabaseType<SCW, Container> for Kafka {
+    type DatabaseOptions = DatabaseOptions;
+
+    fn short_name() -> &'static str {
+        "Kafka"
+    }
+    fn lib_directory_name() -> &'static str {
+        "kafka"
+    }
+    fn db_type() -> service::DatabaseType {
+        service::DatabaseType::Kafka
+    }
+}
+
 /////////////////////////////////////////////////////////////////
 // MANAGED
 impl DatabaseType<SCW, Managed> for PostgresSQL {
@@ -285,3 +342,47 @@ where
         self.to_tera_context_for_container(target, &self.options)
     }
 }
+
+////////////////////////////////////////////////////////////////////////:
+// OpenSearch
+impl ToTeraContext for Database<SCW, Container, OpenSearch>
+where
+    OpenSearch: DatabaseType<SCW, Container>,
+{
+    fn to_tera_context(&self, target: &DeploymentTarget) -> Result<TeraContext, EngineError> {
+        self.to_tera_context_for_container(target, &self.options)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////:
+// ClickHouse
+impl ToTeraContext for Database<SCW, Container, ClickHouse>
+where
+    ClickHouse: DatabaseType<SCW, Container>,
+{
+    fn to_tera_context(&self, target: &DeploymentTarget) -> Result<TeraContext, EngineError> {
+        self.to_tera_context_for_container(target, &self.options)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////:
+// RabbitMQ
+impl ToTeraContext for Database<SCW, Container, RabbitMQ>
+where
+    RabbitMQ: DatabaseType<SCW, Container>,
+{
+    fn to_tera_context(&self, target: &DeploymentTarget) -> Result<TeraContext, EngineError> {
+        self.to_tera_context_for_container(target, &self.options)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////:
+// Kafka
+impl ToTeraContext for Database<SCW, Container, Kafka>
+where
+    Kafka: DatabaseType<SCW, Container>,
+{
+    fn to_tera_context(&self, target: &DeploymentTarget) -> Result<TeraContext, EngineError> {
+        self.to_tera_context_for_container(target, &self.options)
+    }
+}
@@ -2,11 +2,12 @@ mod application;
 mod container;
 mod database;
 mod database_utils;
+mod job;
 mod router;
 
 use crate::errors::CommandError;
-use crate::models::types::CloudProvider;
 use crate::models::types::SCW;
+use crate::models::types::{CloudProvider, ToK8sStorageClass};
 use std::fmt;
 use std::str::FromStr;
 
@@ -39,6 +40,10 @@ impl CloudProvider for SCW {
     fn lib_directory_name() -> &'static str {
         "scaleway"
     }
+
+    fn volume_snapshot_class() -> Option<&'static str> {
+        Some("scw-sbv-snapshot-0")
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Hash, serde_derive::Serialize, serde_derive::Deserialize)]
@@ -49,6 +54,17 @@ pub enum ScwStorageType {
     LocalSsd,
 }
 
+impl ToK8sStorageClass for ScwStorageType {
+    fn to_k8s_storage_class(&self) -> &str {
+        match self {
+            // Note: Seems volume storage type are not supported, only blocked storage for the time being
+            // https://github.com/scaleway/scaleway-csi/tree/master/examples/kubernetes#different-storageclass
+            ScwStorageType::BlockSsd => "scw-sbv-ssd-0",
+            ScwStorageType::LocalSsd => "l_ssd",
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 pub enum ScwRegion {
     Paris,
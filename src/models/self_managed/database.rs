@@ -0,0 +1,255 @@
+use crate::cloud_provider::service::{check_service_version, Service};
+use crate::cloud_provider::{service, DeploymentTarget};
+use crate::errors::EngineError;
+use crate::io_models::database::DatabaseOptions;
+use crate::models::database::{
+    ClickHouse, Container, Database, DatabaseType, Kafka, MongoDB, MySQL, OpenSearch, PostgresSQL, RabbitMQ, Redis,
+};
+use crate::models::database_utils::{
+    get_self_hosted_mongodb_version, get_self_hosted_mysql_version, get_self_hosted_postgres_version,
+    get_self_hosted_redis_version,
+};
+use crate::models::types::{SelfManaged, ToTeraContext};
+use tera::Context as TeraContext;
+
+/////////////////////////////////////////////////////////////////
+// CONTAINER
+impl DatabaseType<SelfManaged, Container> for PostgresSQL {
+    type DatabaseOptions = DatabaseOptions;
+
+    fn short_name() -> &'static str {
+        "PostgresSQL"
+    }
+    fn lib_directory_name() -> &'static str {
+        "postgresql"
+    }
+    fn db_type() -> service::DatabaseType {
+        service::DatabaseType::PostgreSQL
+    }
+}
+
+impl DatabaseType<SelfManaged, Container> for MySQL {
+    type DatabaseOptions = DatabaseOptions;
+
+    fn short_name() -> &'static str {
+        "MySQL"
+    }
+    fn lib_directory_name() -> &'static str {
+        "mysql"
+    }
+    fn db_type() -> service::DatabaseType {
+        service::DatabaseType::MySQL
+    }
+}
+
+impl DatabaseType<SelfManaged, Container> for Redis {
+    type DatabaseOptions = DatabaseOptions;
+
+    fn short_name() -> &'static str {
+        "Redis"
+    }
+    fn lib_directory_name() -> &'static str {
+        "redis"
+    }
+
+    fn db_type() -> service::DatabaseType {
+        service::DatabaseType::Redis
+    }
+}
+
+impl DatabaseType<SelfManaged, Container> for MongoDB {
+    type DatabaseOptions = DatabaseOptions;
+
+    fn short_name() -> &'static str {
+        "MongoDB"
+    }
+
+    fn lib_directory_name() -> &'static str {
+        "mongodb"
+    }
+
+    fn db_type() -> service::DatabaseType {
+        service::DatabaseType::MongoDB
+    }
+}
+
+impl DatabaseType<SelfManaged, Container> for OpenSearch {
+    type DatabaseOptions = DatabaseOptions;
+
+    fn short_name() -> &'static str {
+        "OpenSearch"
+    }
+    fn lib_directory_name() -> &'static str {
+        "opensearch"
+    }
+    fn db_type() -> service::DatabaseType {
+        service::DatabaseType::OpenSearch
+    }
+}
+
+impl DatabaseType<SelfManaged, Container> for ClickHouse {
+    type DatabaseOptions = DatabaseOptions;
+
+    fn short_name() -> &'static str {
+        "ClickHouse"
+    }
+    fn lib_directory_name() -> &'static str {
+        "clickhouse"
+    }
+    fn db_type() -> service::DatabaseType {
+        service::DatabaseType::ClickHouse
+    }
+}
+
+impl DatabaseType<SelfManaged, Container> for RabbitMQ {
+    type DatabaseOptions = DatabaseOptions;
+
+    fn short_name() -> &'static str {
+        "RabbitMQ"
+    }
+    fn lib_directory_name() -> &'static str {
+        "rabbitmq"
+    }
+    fn db_type() -> service::DatabaseType {
+        service::DatabaseType::RabbitMQ
+    }
+}
+
+impl DatabaseType<SelfManaged, Container> for Kafka {
+    type DatabaseOptions = DatabaseOptions;
+
+    fn short_name() -> &'static str {
+        "Kafka"
+    }
+    fn lib_directory_name() -> &'static str {
+        "kafka"
+    }
+    fn db_type() -> service::DatabaseType {
+        service::DatabaseType::Kafka
+    }
+}
+
+/////////////////////////////////////////////////////////////////
+// MANAGED
+// Self-managed clusters don't have any managed database offer
+
+////////////////////////////////////////////////////////////////////////:
+// POSTGRES SQL
+impl ToTeraContext for Database<SelfManaged, Container, PostgresSQL>
+where
+    PostgresSQL: DatabaseType<SelfManaged, Container>,
+{
+    fn to_tera_context(&self, target: &DeploymentTarget) -> Result<TeraContext, EngineError> {
+        let _check_version = |event_details| {
+            check_service_version(
+                get_self_hosted_postgres_version(self.version.to_string()),
+                self,
+                event_details,
+                self.logger(),
+            )
+        };
+        self.to_tera_context_for_container(target, &self.options)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////:
+// MySQL
+impl ToTeraContext for Database<SelfManaged, Container, MySQL>
+where
+    MySQL: DatabaseType<SelfManaged, Container>,
+{
+    fn to_tera_context(&self, target: &DeploymentTarget) -> Result<TeraContext, EngineError> {
+        let _check_version = |event_details| {
+            check_service_version(
+                get_self_hosted_mysql_version(self.version.to_string()),
+                self,
+                event_details,
+                self.logger(),
+            )
+        };
+        self.to_tera_context_for_container(target, &self.options)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////:
+// MongoDB
+impl ToTeraContext for Database<SelfManaged, Container, MongoDB>
+where
+    MongoDB: DatabaseType<SelfManaged, Container>,
+{
+    fn to_tera_context(&self, target: &DeploymentTarget) -> Result<TeraContext, EngineError> {
+        let _check_version = |event_details| {
+            check_service_version(
+                get_self_hosted_mongodb_version(self.version.to_string()),
+                self,
+                event_details,
+                self.logger(),
+            )
+        };
+
+        self.to_tera_context_for_container(target, &self.options)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////:
+// Redis
+impl ToTeraContext for Database<SelfManaged, Container, Redis>
+where
+    Redis: DatabaseType<SelfManaged, Container>,
+{
+    fn to_tera_context(&self, target: &DeploymentTarget) -> Result<TeraContext, EngineError> {
+        let _check_version = |event_details| {
+            check_service_version(
+                get_self_hosted_redis_version(self.version.to_string()),
+                self,
+                event_details,
+                self.logger(),
+            )
+        };
+        self.to_tera_context_for_container(target, &self.options)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////:
+// OpenSearch
+impl ToTeraContext for Database<SelfManaged, Container, OpenSearch>
+where
+    OpenSearch: DatabaseType<SelfManaged, Container>,
+{
+    fn to_tera_context(&self, target: &DeploymentTarget) -> Result<TeraContext, EngineError> {
+        self.to_tera_context_for_container(target, &self.options)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////:
+// ClickHouse
+impl ToTeraContext for Database<SelfManaged, Container, ClickHouse>
+where
+    ClickHouse: DatabaseType<SelfManaged, Container>,
+{
+    fn to_tera_context(&self, target: &DeploymentTarget) -> Result<TeraContext, EngineError> {
+        self.to_tera_context_for_container(target, &self.options)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////:
+// RabbitMQ
+impl ToTeraContext for Database<SelfManaged, Container, RabbitMQ>
+where
+    RabbitMQ: DatabaseType<SelfManaged, Container>,
+{
+    fn to_tera_context(&self, target: &DeploymentTarget) -> Result<TeraContext, EngineError> {
+        self.to_tera_context_for_container(target, &self.options)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////:
+// Kafka
+impl ToTeraContext for Database<SelfManaged, Container, Kafka>
+where
+    Kafka: DatabaseType<SelfManaged, Container>,
+{
+    fn to_tera_context(&self, target: &DeploymentTarget) -> Result<TeraContext, EngineError> {
+        self.to_tera_context_for_container(target, &self.options)
+    }
+}
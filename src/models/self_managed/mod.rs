@@ -0,0 +1,59 @@
+mod application;
+mod container;
+mod database;
+mod job;
+mod router;
+
+use crate::models::types::SelfManaged;
+use crate::models::types::{CloudProvider, ToK8sStorageClass};
+
+pub struct SelfManagedAppExtraSettings {}
+pub struct SelfManagedDbExtraSettings {}
+pub struct SelfManagedRouterExtraSettings {}
+
+impl CloudProvider for SelfManaged {
+    type AppExtraSettings = SelfManagedAppExtraSettings;
+    type DbExtraSettings = SelfManagedDbExtraSettings;
+    type RouterExtraSettings = SelfManagedRouterExtraSettings;
+    type StorageTypes = SelfManagedStorageType;
+
+    fn short_name() -> &'static str {
+        "SelfManaged"
+    }
+
+    fn full_name() -> &'static str {
+        "Self-managed"
+    }
+
+    fn registry_short_name() -> &'static str {
+        "Registry"
+    }
+
+    fn registry_full_name() -> &'static str {
+        "Container Registry"
+    }
+
+    fn lib_directory_name() -> &'static str {
+        "self-managed"
+    }
+
+    fn volume_snapshot_class() -> Option<&'static str> {
+        // the default volume snapshot class of the cluster is used
+        None
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum SelfManagedStorageType {
+    // Use the default storage class of the cluster (i.e: local-path for kind and k3s)
+    Default,
+}
+
+impl ToK8sStorageClass for SelfManagedStorageType {
+    fn to_k8s_storage_class(&self) -> &str {
+        match self {
+            // empty storage class name makes the templates omit `storageClassName`
+            SelfManagedStorageType::Default => "",
+        }
+    }
+}
@@ -0,0 +1,11 @@
+use crate::cloud_provider::DeploymentTarget;
+use crate::errors::EngineError;
+use crate::models::router::Router;
+use crate::models::types::{SelfManaged, ToTeraContext};
+use tera::Context as TeraContext;
+
+impl ToTeraContext for Router<SelfManaged> {
+    fn to_tera_context(&self, target: &DeploymentTarget) -> Result<TeraContext, EngineError> {
+        self.default_tera_context(target)
+    }
+}
@@ -12,20 +12,28 @@ pub struct AWS {}
 pub struct AWSEc2 {}
 pub struct DO {}
 pub struct SCW {}
+pub struct SelfManaged {}
 
 // CloudProvider trait allows to derive all the custom type we need per provider,
 // with our marker type defined above to be able to select the correct one
-pub trait CloudProvider {
-    type AppExtraSettings;
-    type DbExtraSettings;
-    type RouterExtraSettings;
-    type StorageTypes;
+pub trait CloudProvider: Send + Sync {
+    type AppExtraSettings: Send + Sync;
+    type DbExtraSettings: Send + Sync;
+    type RouterExtraSettings: Send + Sync;
+    type StorageTypes: Send + Sync + ToK8sStorageClass;
 
     fn short_name() -> &'static str;
     fn full_name() -> &'static str;
     fn registry_short_name() -> &'static str;
     fn registry_full_name() -> &'static str;
     fn lib_directory_name() -> &'static str;
+    /// VolumeSnapshotClass deployed with the storage classes, None to use the default one of the cluster
+    fn volume_snapshot_class() -> Option<&'static str>;
+}
+
+// Kubernetes storage class backing a storage type of the cloud provider
+pub trait ToK8sStorageClass {
+    fn to_k8s_storage_class(&self) -> &str;
 }
 
 pub trait ToTeraContext {
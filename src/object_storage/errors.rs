@@ -37,6 +37,11 @@ pub enum ObjectStorageError {
         bucket_name: String,
         raw_error_message: String,
     },
+    #[error("Cannot list objects error for `{bucket_name:?}`: {raw_error_message:?}.")]
+    CannotListObjects {
+        bucket_name: String,
+        raw_error_message: String,
+    },
     #[error("Cannot get object file `{file_name:?}` error in `{bucket_name:?}`: {raw_error_message:?}.")]
     CannotGetObjectFile {
         bucket_name: String,
@@ -49,6 +54,11 @@ pub enum ObjectStorageError {
         file_name: String,
         raw_error_message: String,
     },
+    #[error("Cannot manage access credentials for `{bucket_name:?}`: {raw_error_message:?}.")]
+    CannotManageAccessCredentials {
+        bucket_name: String,
+        raw_error_message: String,
+    },
     #[error("Cannot delete file error for `{bucket_name:?}`: {raw_error_message:?}.")]
     CannotDeleteFile {
         bucket_name: String,
@@ -0,0 +1,313 @@
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use crate::io_models::context::Context;
+use crate::io_models::domain::StringPath;
+use crate::object_storage::errors::ObjectStorageError;
+use crate::object_storage::{Kind, ObjectStorage};
+
+/// Object storage backed by a directory of the local filesystem.
+/// Each bucket is a sub directory of `root_dir` and each object a file inside it.
+pub struct LocalFileSystem {
+    context: Context,
+    id: String,
+    name: String,
+    root_dir: PathBuf,
+}
+
+impl LocalFileSystem {
+    pub fn new(context: Context, id: String, name: String, root_dir: PathBuf) -> Self {
+        LocalFileSystem {
+            context,
+            id,
+            name,
+            root_dir,
+        }
+    }
+
+    fn is_bucket_name_valid(bucket_name: &str) -> Result<(), ObjectStorageError> {
+        if bucket_name.is_empty() {
+            return Err(ObjectStorageError::InvalidBucketName {
+                bucket_name: bucket_name.to_string(),
+                raw_error_message: "bucket name cannot be empty".to_string(),
+            });
+        }
+        if bucket_name.contains('/') || bucket_name == "." || bucket_name == ".." {
+            return Err(ObjectStorageError::InvalidBucketName {
+                bucket_name: bucket_name.to_string(),
+                raw_error_message: "bucket name cannot be a path".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    fn bucket_path(&self, bucket_name: &str) -> PathBuf {
+        self.root_dir.join(bucket_name)
+    }
+
+    fn object_path(&self, bucket_name: &str, object_key: &str) -> Result<PathBuf, ObjectStorageError> {
+        // object keys can contain `/` but must never escape the bucket directory
+        if object_key.is_empty() || Path::new(object_key).components().any(|c| c.as_os_str() == "..") {
+            return Err(ObjectStorageError::CannotGetObjectFile {
+                bucket_name: bucket_name.to_string(),
+                file_name: object_key.to_string(),
+                raw_error_message: "invalid object key".to_string(),
+            });
+        }
+
+        Ok(self.bucket_path(bucket_name).join(object_key.trim_start_matches('/')))
+    }
+}
+
+impl ObjectStorage for LocalFileSystem {
+    fn context(&self) -> &Context {
+        &self.context
+    }
+
+    fn kind(&self) -> Kind {
+        Kind::LocalFileSystem
+    }
+
+    fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn is_valid(&self) -> Result<(), ObjectStorageError> {
+        Ok(())
+    }
+
+    fn workspace_dir_relative_path(&self) -> String {
+        "object-storage/local".to_string()
+    }
+
+    fn create_bucket(&self, bucket_name: &str) -> Result<(), ObjectStorageError> {
+        LocalFileSystem::is_bucket_name_valid(bucket_name)?;
+
+        std::fs::create_dir_all(self.bucket_path(bucket_name)).map_err(|e| ObjectStorageError::CannotCreateBucket {
+            bucket_name: bucket_name.to_string(),
+            raw_error_message: e.to_string(),
+        })
+    }
+
+    fn delete_bucket(&self, bucket_name: &str) -> Result<(), ObjectStorageError> {
+        LocalFileSystem::is_bucket_name_valid(bucket_name)?;
+
+        let bucket_path = self.bucket_path(bucket_name);
+        if !bucket_path.exists() {
+            return Ok(());
+        }
+
+        std::fs::remove_dir_all(bucket_path).map_err(|e| ObjectStorageError::CannotDeleteBucket {
+            bucket_name: bucket_name.to_string(),
+            raw_error_message: e.to_string(),
+        })
+    }
+
+    fn get(
+        &self,
+        bucket_name: &str,
+        object_key: &str,
+        _use_cache: bool,
+    ) -> Result<(StringPath, File), ObjectStorageError> {
+        LocalFileSystem::is_bucket_name_valid(bucket_name)?;
+
+        // files are already local, there is nothing to cache
+        let file_path = self.object_path(bucket_name, object_key)?;
+        match File::open(&file_path) {
+            Ok(file) => Ok((file_path.to_string_lossy().to_string(), file)),
+            Err(e) => Err(ObjectStorageError::CannotGetObjectFile {
+                bucket_name: bucket_name.to_string(),
+                file_name: object_key.to_string(),
+                raw_error_message: e.to_string(),
+            }),
+        }
+    }
+
+    fn put(&self, bucket_name: &str, object_key: &str, file_path: &str) -> Result<(), ObjectStorageError> {
+        LocalFileSystem::is_bucket_name_valid(bucket_name)?;
+
+        let to_upload_error = |e: std::io::Error| ObjectStorageError::CannotUploadFile {
+            bucket_name: bucket_name.to_string(),
+            file_name: object_key.to_string(),
+            raw_error_message: e.to_string(),
+        };
+
+        let destination =
+            self.object_path(bucket_name, object_key)
+                .map_err(|e| ObjectStorageError::CannotUploadFile {
+                    bucket_name: bucket_name.to_string(),
+                    file_name: object_key.to_string(),
+                    raw_error_message: e.to_string(),
+                })?;
+        if let Some(parent_dir) = destination.parent() {
+            std::fs::create_dir_all(parent_dir).map_err(to_upload_error)?;
+        }
+
+        std::fs::copy(file_path, destination).map_err(to_upload_error)?;
+        Ok(())
+    }
+
+    fn ensure_file_is_absent(&self, bucket_name: &str, object_key: &str) -> Result<(), ObjectStorageError> {
+        if LocalFileSystem::is_bucket_name_valid(bucket_name).is_err() {
+            // bucket is missing it's ok as file can't be present
+            return Ok(());
+        };
+
+        let file_path = match self.object_path(bucket_name, object_key) {
+            Ok(path) if path.exists() => path,
+            _ => return Ok(()),
+        };
+
+        std::fs::remove_file(file_path).map_err(|e| ObjectStorageError::CannotDeleteFile {
+            bucket_name: bucket_name.to_string(),
+            file_name: object_key.to_string(),
+            raw_error_message: e.to_string(),
+        })
+    }
+
+    fn list_objects(&self, bucket_name: &str, prefix: &str) -> Result<Vec<String>, ObjectStorageError> {
+        LocalFileSystem::is_bucket_name_valid(bucket_name)?;
+
+        let bucket_path = self.bucket_path(bucket_name);
+        if !bucket_path.exists() {
+            return Ok(vec![]);
+        }
+
+        let to_list_error = |e: std::io::Error| ObjectStorageError::CannotListObjects {
+            bucket_name: bucket_name.to_string(),
+            raw_error_message: e.to_string(),
+        };
+        let mut keys = vec![];
+        let mut dirs = vec![bucket_path.clone()];
+        while let Some(dir) = dirs.pop() {
+            for entry in std::fs::read_dir(dir).map_err(to_list_error)? {
+                let path = entry.map_err(to_list_error)?.path();
+                if path.is_dir() {
+                    dirs.push(path);
+                    continue;
+                }
+
+                // object keys are the paths relative to the bucket directory
+                if let Ok(key) = path.strip_prefix(&bucket_path) {
+                    let key = key.to_string_lossy().to_string();
+                    if key.starts_with(prefix) {
+                        keys.push(key);
+                    }
+                }
+            }
+        }
+
+        keys.sort();
+        Ok(keys)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cmd::docker::Docker;
+    use std::io::{Read, Write};
+    use uuid::Uuid;
+
+    fn local_filesystem(root_dir: &Path) -> LocalFileSystem {
+        let context = Context::new(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4().to_string(),
+            root_dir.to_string_lossy().to_string(),
+            "lib".to_string(),
+            true,
+            None,
+            vec![],
+            None,
+            Docker::new_with_options(false, None).expect("cannot create docker client"),
+        );
+
+        LocalFileSystem::new(context, "local-id".to_string(), "local".to_string(), root_dir.join("buckets"))
+    }
+
+    #[test]
+    fn test_is_bucket_name_valid() {
+        // setup:
+        let test_cases: Vec<(&str, bool)> = vec![("", false), ("..", false), ("a/b", false), ("valid", true)];
+
+        for (bucket_name, is_valid) in test_cases {
+            // execute:
+            let result = LocalFileSystem::is_bucket_name_valid(bucket_name);
+
+            // verify:
+            assert_eq!(is_valid, result.is_ok(), "bucket name `{}`", bucket_name);
+        }
+    }
+
+    #[test]
+    fn test_put_get_delete_object() {
+        // setup:
+        let tmp_dir = tempfile::tempdir().expect("cannot create temp dir");
+        let storage = local_filesystem(tmp_dir.path());
+        let source_path = tmp_dir.path().join("kubeconfig.yaml");
+        File::create(&source_path)
+            .and_then(|mut f| f.write_all(b"apiVersion: v1"))
+            .expect("cannot write source file");
+
+        // execute & verify:
+        assert!(storage.create_bucket("kubeconfigs").is_ok());
+        assert!(storage
+            .put("kubeconfigs", "cluster.yaml", source_path.to_str().unwrap())
+            .is_ok());
+
+        let (path, mut file) = storage
+            .get("kubeconfigs", "cluster.yaml", false)
+            .expect("cannot get file");
+        let mut content = String::new();
+        file.read_to_string(&mut content).expect("cannot read file");
+        assert_eq!("apiVersion: v1", content);
+        assert!(path.ends_with("buckets/kubeconfigs/cluster.yaml"));
+
+        assert!(storage.get("kubeconfigs", "../kubeconfig.yaml", false).is_err());
+
+        assert!(storage.ensure_file_is_absent("kubeconfigs", "cluster.yaml").is_ok());
+        assert!(storage.get("kubeconfigs", "cluster.yaml", false).is_err());
+        assert!(storage.ensure_file_is_absent("kubeconfigs", "cluster.yaml").is_ok());
+
+        assert!(storage.delete_bucket("kubeconfigs").is_ok());
+        assert!(!tmp_dir.path().join("buckets/kubeconfigs").exists());
+    }
+
+    #[test]
+    fn test_list_objects() {
+        // setup:
+        let tmp_dir = tempfile::tempdir().expect("cannot create temp dir");
+        let storage = local_filesystem(tmp_dir.path());
+        let source_path = tmp_dir.path().join("dump");
+        File::create(&source_path).expect("cannot write source file");
+        for key in [
+            "db-1/20221001T030000Z.dump",
+            "db-1/20221002T030000Z.dump",
+            "db-2/20221001T030000Z.dump",
+        ] {
+            storage
+                .put("backups", key, source_path.to_str().unwrap())
+                .expect("cannot put file");
+        }
+
+        // execute:
+        let db_1_keys = storage.list_objects("backups", "db-1/");
+        let missing_bucket_keys = storage.list_objects("missing", "");
+
+        // verify:
+        assert_eq!(
+            Ok(vec![
+                "db-1/20221001T030000Z.dump".to_string(),
+                "db-1/20221002T030000Z.dump".to_string()
+            ]),
+            db_1_keys
+        );
+        assert_eq!(Ok(vec![]), missing_bucket_keys);
+    }
+}
@@ -1,11 +1,14 @@
+use rusoto_s3::{ListObjectsV2Request, S3Client, S3};
 use serde::{Deserialize, Serialize};
 
 use crate::io_models::context::Context;
 use crate::io_models::domain::StringPath;
 use crate::object_storage::errors::ObjectStorageError;
+use crate::runtime::block_on;
 use std::fs::File;
 
 pub mod errors;
+pub mod local_filesystem;
 pub mod s3;
 pub mod scaleway_object_storage;
 pub mod spaces;
@@ -40,6 +43,60 @@ pub trait ObjectStorage {
     ) -> Result<(StringPath, File), ObjectStorageError>;
     fn put(&self, bucket_name: &str, object_key: &str, file_path: &str) -> Result<(), ObjectStorageError>;
     fn ensure_file_is_absent(&self, bucket_name: &str, object_key: &str) -> Result<(), ObjectStorageError>;
+    /// keys of the objects of the bucket starting with `prefix`
+    fn list_objects(&self, bucket_name: &str, prefix: &str) -> Result<Vec<String>, ObjectStorageError>;
+    /// credentials allowing workloads running in the cluster to write objects under `prefix` of the bucket with an S3 client,
+    /// and nothing else. Previous credentials issued for this prefix are revoked.
+    /// None when the object storage cannot issue credentials restricted to a prefix
+    fn issue_prefix_write_access(
+        &self,
+        _bucket_name: &str,
+        _prefix: &str,
+    ) -> Result<Option<S3Access>, ObjectStorageError> {
+        Ok(None)
+    }
+    /// revoke credentials issued by `issue_prefix_write_access`
+    fn revoke_prefix_write_access(&self, _bucket_name: &str, _prefix: &str) -> Result<(), ObjectStorageError> {
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct S3Access {
+    // None for AWS S3, the endpoint being deduced from the region
+    pub endpoint_url: Option<String>,
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+}
+
+/// list objects with any S3 compatible client, following pagination
+pub(crate) fn list_s3_objects(
+    s3_client: &S3Client,
+    bucket_name: &str,
+    prefix: &str,
+) -> Result<Vec<String>, ObjectStorageError> {
+    let mut keys = vec![];
+    let mut continuation_token = None;
+
+    loop {
+        let response = block_on(s3_client.list_objects_v2(ListObjectsV2Request {
+            bucket: bucket_name.to_string(),
+            prefix: Some(prefix.to_string()),
+            continuation_token: continuation_token.take(),
+            ..Default::default()
+        }))
+        .map_err(|e| ObjectStorageError::CannotListObjects {
+            bucket_name: bucket_name.to_string(),
+            raw_error_message: e.to_string(),
+        })?;
+
+        keys.extend(response.contents.unwrap_or_default().into_iter().filter_map(|o| o.key));
+        match response.next_continuation_token {
+            Some(token) if response.is_truncated == Some(true) => continuation_token = Some(token),
+            _ => return Ok(keys),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -48,4 +105,5 @@ pub enum Kind {
     S3,
     Spaces,
     ScalewayOs,
+    LocalFileSystem,
 }
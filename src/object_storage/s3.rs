@@ -6,7 +6,12 @@ use std::str::FromStr;
 
 use crate::cloud_provider::aws::regions::AwsRegion;
 use rusoto_core::credential::StaticProvider;
-use rusoto_core::{Client, HttpClient, Region as RusotoRegion};
+use rusoto_core::{Client, HttpClient, Region as RusotoRegion, RusotoError};
+use rusoto_iam::{
+    CreateAccessKeyRequest, CreateUserError, CreateUserRequest, DeleteAccessKeyRequest, DeleteUserError,
+    DeleteUserPolicyError, DeleteUserPolicyRequest, DeleteUserRequest, GetUserRequest, Iam, IamClient,
+    ListAccessKeysRequest, PutUserPolicyRequest,
+};
 use rusoto_s3::{
     CreateBucketConfiguration, CreateBucketRequest, Delete, DeleteBucketRequest, DeleteObjectRequest,
     DeleteObjectsRequest, GetObjectRequest, HeadBucketRequest, ListObjectsRequest, ObjectIdentifier,
@@ -18,9 +23,11 @@ use tokio::io;
 use crate::io_models::context::Context;
 use crate::io_models::domain::StringPath;
 use crate::object_storage::errors::ObjectStorageError;
-use crate::object_storage::{Kind, ObjectStorage};
+use crate::object_storage::{list_s3_objects, Kind, ObjectStorage, S3Access};
 use crate::runtime::block_on;
 
+const PREFIX_WRITE_POLICY_NAME: &str = "qovery-prefix-write";
+
 pub struct S3 {
     context: Context,
     id: String,
@@ -70,6 +77,42 @@ impl S3 {
         S3Client::new_with_client(client, region)
     }
 
+    fn get_iam_client(&self) -> IamClient {
+        let client = Client::new_with(
+            self.get_credentials(),
+            HttpClient::new().expect("unable to create new Http client"),
+        );
+
+        // IAM is a global service
+        IamClient::new_with_client(client, RusotoRegion::UsEast1)
+    }
+
+    fn user_exists(&self, iam_client: &IamClient, user_name: &str) -> bool {
+        block_on(iam_client.get_user(GetUserRequest {
+            user_name: Some(user_name.to_string()),
+        }))
+        .is_ok()
+    }
+
+    fn delete_access_keys(&self, iam_client: &IamClient, user_name: &str) -> Result<(), String> {
+        let access_keys = block_on(iam_client.list_access_keys(ListAccessKeysRequest {
+            user_name: Some(user_name.to_string()),
+            ..Default::default()
+        }))
+        .map_err(|e| e.to_string())?
+        .access_key_metadata;
+
+        for access_key_id in access_keys.into_iter().filter_map(|key| key.access_key_id) {
+            block_on(iam_client.delete_access_key(DeleteAccessKeyRequest {
+                access_key_id,
+                user_name: Some(user_name.to_string()),
+            }))
+            .map_err(|e| e.to_string())?;
+        }
+
+        Ok(())
+    }
+
     fn is_bucket_name_valid(bucket_name: &str) -> Result<(), ObjectStorageError> {
         if bucket_name.is_empty() {
             return Err(ObjectStorageError::InvalidBucketName {
@@ -377,6 +420,110 @@ impl ObjectStorage for S3 {
             }),
         }
     }
+
+    fn list_objects(&self, bucket_name: &str, prefix: &str) -> Result<Vec<String>, ObjectStorageError> {
+        S3::is_bucket_name_valid(bucket_name)?;
+
+        list_s3_objects(&self.get_s3_client(), bucket_name, prefix)
+    }
+
+    fn issue_prefix_write_access(
+        &self,
+        bucket_name: &str,
+        prefix: &str,
+    ) -> Result<Option<S3Access>, ObjectStorageError> {
+        S3::is_bucket_name_valid(bucket_name)?;
+
+        let iam_client = self.get_iam_client();
+        let user_name = prefix_writer_user_name(prefix);
+        let to_error = |raw_error_message: String| ObjectStorageError::CannotManageAccessCredentials {
+            bucket_name: bucket_name.to_string(),
+            raw_error_message,
+        };
+
+        match block_on(iam_client.create_user(CreateUserRequest {
+            user_name: user_name.clone(),
+            ..Default::default()
+        })) {
+            Ok(_) | Err(RusotoError::Service(CreateUserError::EntityAlreadyExists(_))) => {}
+            Err(e) => return Err(to_error(e.to_string())),
+        }
+        block_on(iam_client.put_user_policy(PutUserPolicyRequest {
+            user_name: user_name.clone(),
+            policy_name: PREFIX_WRITE_POLICY_NAME.to_string(),
+            policy_document: prefix_write_policy(bucket_name, prefix),
+        }))
+        .map_err(|e| to_error(e.to_string()))?;
+
+        // a user can only have two access keys, the ones issued previously are revoked
+        self.delete_access_keys(&iam_client, &user_name).map_err(to_error)?;
+        let access_key = block_on(iam_client.create_access_key(CreateAccessKeyRequest {
+            user_name: Some(user_name),
+        }))
+        .map_err(|e| to_error(e.to_string()))?
+        .access_key;
+
+        Ok(Some(S3Access {
+            endpoint_url: None,
+            region: self.region.to_aws_format().to_string(),
+            access_key_id: access_key.access_key_id,
+            secret_access_key: access_key.secret_access_key,
+        }))
+    }
+
+    fn revoke_prefix_write_access(&self, bucket_name: &str, prefix: &str) -> Result<(), ObjectStorageError> {
+        let iam_client = self.get_iam_client();
+        let user_name = prefix_writer_user_name(prefix);
+        let to_error = |raw_error_message: String| ObjectStorageError::CannotManageAccessCredentials {
+            bucket_name: bucket_name.to_string(),
+            raw_error_message,
+        };
+
+        match self.delete_access_keys(&iam_client, &user_name) {
+            Ok(_) => {}
+            // nothing has ever been issued for this prefix
+            Err(_) if !self.user_exists(&iam_client, &user_name) => return Ok(()),
+            Err(e) => return Err(to_error(e)),
+        }
+        match block_on(iam_client.delete_user_policy(DeleteUserPolicyRequest {
+            user_name: user_name.clone(),
+            policy_name: PREFIX_WRITE_POLICY_NAME.to_string(),
+        })) {
+            Ok(_) | Err(RusotoError::Service(DeleteUserPolicyError::NoSuchEntity(_))) => {}
+            Err(e) => return Err(to_error(e.to_string())),
+        }
+        match block_on(iam_client.delete_user(DeleteUserRequest { user_name })) {
+            Ok(_) | Err(RusotoError::Service(DeleteUserError::NoSuchEntity(_))) => Ok(()),
+            Err(e) => Err(to_error(e.to_string())),
+        }
+    }
+}
+
+/// IAM user holding the credentials issued for a prefix, i.e: `qovery-writer-<database long id>`
+fn prefix_writer_user_name(prefix: &str) -> String {
+    let name: String = format!("qovery-writer-{}", prefix.trim_matches('/'))
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() || "+=,.@_-".contains(c) {
+            true => c,
+            false => '-',
+        })
+        .collect();
+
+    // IAM user names are limited to 64 characters
+    name.chars().take(64).collect()
+}
+
+/// Objects can only be written under the prefix: they can neither be read, listed nor deleted
+fn prefix_write_policy(bucket_name: &str, prefix: &str) -> String {
+    serde_json::json!({
+        "Version": "2012-10-17",
+        "Statement": [{
+            "Effect": "Allow",
+            "Action": ["s3:PutObject"],
+            "Resource": [format!("arn:aws:s3:::{}/{}*", bucket_name, prefix)],
+        }]
+    })
+    .to_string()
 }
 
 #[cfg(test)]
@@ -416,4 +563,21 @@ mod tests {
             assert_eq!(tc.expected_output, result, "{}", tc.description);
         }
     }
+
+    #[test]
+    fn test_prefix_write_policy() {
+        // execute:
+        let user_name = prefix_writer_user_name("e1b1d2c8-4cb2-4b0e-9a0e-2f1f7fd5b1f0/");
+        let policy: serde_json::Value =
+            serde_json::from_str(&prefix_write_policy("qovery-db-backups-z1", "e1b1d2c8/")).unwrap();
+
+        // verify:
+        assert_eq!("qovery-writer-e1b1d2c8-4cb2-4b0e-9a0e-2f1f7fd5b1f0", user_name);
+        assert!(prefix_writer_user_name(&"a/".repeat(64)).len() <= 64);
+        assert_eq!(serde_json::json!(["s3:PutObject"]), policy["Statement"][0]["Action"]);
+        assert_eq!(
+            serde_json::json!(["arn:aws:s3:::qovery-db-backups-z1/e1b1d2c8/*"]),
+            policy["Statement"][0]["Resource"]
+        );
+    }
 }
@@ -3,7 +3,7 @@ use std::fs::File;
 use std::path::Path;
 
 use crate::io_models::domain::StringPath;
-use crate::object_storage::{Kind, ObjectStorage};
+use crate::object_storage::{list_s3_objects, Kind, ObjectStorage};
 
 use crate::io_models::context::Context;
 use crate::models::scaleway::ScwZone;
@@ -412,6 +412,12 @@ impl ObjectStorage for ScalewayOS {
             }),
         }
     }
+
+    fn list_objects(&self, bucket_name: &str, prefix: &str) -> Result<Vec<String>, ObjectStorageError> {
+        ScalewayOS::is_bucket_name_valid(bucket_name)?;
+
+        list_s3_objects(&self.get_s3_client(), bucket_name, prefix)
+    }
 }
 
 struct ScalewayObjectStorageErrorManager {}
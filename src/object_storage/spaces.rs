@@ -15,7 +15,7 @@ use crate::io_models::context::Context;
 use crate::io_models::domain::StringPath;
 use crate::models::digital_ocean::DoRegion;
 use crate::object_storage::errors::ObjectStorageError;
-use crate::object_storage::{Kind, ObjectStorage};
+use crate::object_storage::{list_s3_objects, Kind, ObjectStorage};
 use crate::runtime::block_on;
 
 pub enum BucketDeleteStrategy {
@@ -404,4 +404,10 @@ impl ObjectStorage for Spaces {
             }),
         }
     }
+
+    fn list_objects(&self, bucket_name: &str, prefix: &str) -> Result<Vec<String>, ObjectStorageError> {
+        Spaces::is_bucket_name_valid(bucket_name)?;
+
+        list_s3_objects(&self.get_s3_client(), bucket_name, prefix)
+    }
 }
@@ -0,0 +1,78 @@
+use crate::errors::CommandError;
+use crate::runtime::block_on;
+use rusoto_core::signature::SignedRequest;
+use rusoto_core::{Client, HttpClient, Region};
+use rusoto_credential::StaticProvider;
+use serde::Deserialize;
+use std::str::FromStr;
+
+/// Read only client of AWS Secrets Manager
+pub struct AwsSecretsManager {
+    client: Client,
+    region: Region,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GetSecretValueResponse {
+    secret_string: Option<String>,
+}
+
+impl AwsSecretsManager {
+    pub fn new(access_key_id: &str, secret_access_key: &str, region: &str) -> Result<AwsSecretsManager, CommandError> {
+        let region = Region::from_str(region).map_err(|err| {
+            CommandError::new(
+                format!("Invalid AWS region `{}` for Secrets Manager", region),
+                Some(err.to_string()),
+                None,
+            )
+        })?;
+        let http_client = HttpClient::new().map_err(|err| {
+            CommandError::new(
+                "Cannot create AWS Secrets Manager client".to_string(),
+                Some(err.to_string()),
+                None,
+            )
+        })?;
+        let credentials = StaticProvider::new(access_key_id.to_string(), secret_access_key.to_string(), None, None);
+
+        Ok(AwsSecretsManager {
+            client: Client::new_with(credentials, http_client),
+            region,
+        })
+    }
+
+    /// Current version of a text secret
+    pub fn get_secret(&self, secret_id: &str) -> Result<String, CommandError> {
+        let to_error = |raw_error: String| {
+            CommandError::new(
+                format!("Cannot read secret `{}` from AWS Secrets Manager", secret_id),
+                Some(raw_error),
+                None,
+            )
+        };
+
+        let mut request = SignedRequest::new("POST", "secretsmanager", &self.region, "/");
+        request.set_content_type("application/x-amz-json-1.1".to_string());
+        request.add_header("x-amz-target", "secretsmanager.GetSecretValue");
+        request.set_payload(Some(serde_json::json!({ "SecretId": secret_id }).to_string().into_bytes()));
+
+        let response = block_on(async {
+            let mut response = self
+                .client
+                .sign_and_dispatch(request)
+                .await
+                .map_err(|err| format!("{:?}", err))?;
+            response.buffer().await.map_err(|err| err.to_string())
+        })
+        .map_err(to_error)?;
+        if !response.status.is_success() {
+            return Err(to_error(String::from_utf8_lossy(&response.body).to_string()));
+        }
+
+        serde_json::from_slice::<GetSecretValueResponse>(&response.body)
+            .map_err(|err| to_error(err.to_string()))?
+            .secret_string
+            .ok_or_else(|| to_error("binary secrets are not supported".to_string()))
+    }
+}
@@ -0,0 +1,106 @@
+use crate::cloud_provider::kubernetes::Kubernetes;
+use crate::cloud_provider::TerraformStateCredentials;
+use crate::events::{EngineEvent, EventDetails, EventMessage};
+use crate::secret_manager::vault::get_vault_mount_name;
+use crate::secret_manager::{
+    create_or_update_secret, get_secret, new_secret_manager, SecretManager, SecretManagerError, SecretVersion,
+};
+
+/// Cluster secrets are stored under the cluster id, whatever the cloud provider
+pub fn terraform_state_credentials_path(cluster_id: &str) -> String {
+    format!("{}/terraform-state-credentials", cluster_id)
+}
+
+pub fn get_terraform_state_credentials(
+    secret_manager: &dyn SecretManager,
+    cluster_id: &str,
+) -> Result<TerraformStateCredentials, SecretManagerError> {
+    get_secret(secret_manager, &terraform_state_credentials_path(cluster_id))
+}
+
+pub fn create_or_update_terraform_state_credentials(
+    secret_manager: &dyn SecretManager,
+    cluster_id: &str,
+    credentials: &TerraformStateCredentials,
+) -> Result<Option<SecretVersion>, SecretManagerError> {
+    create_or_update_secret(secret_manager, &terraform_state_credentials_path(cluster_id), credentials)
+}
+
+pub fn delete_terraform_state_credentials(
+    secret_manager: &dyn SecretManager,
+    cluster_id: &str,
+) -> Result<(), SecretManagerError> {
+    secret_manager.delete_secret(&terraform_state_credentials_path(cluster_id))
+}
+
+/// Store the Terraform state credentials of the cluster into the secret manager.
+/// The secret manager should not be on the critical deployment path, failures are only logged.
+pub fn store_cluster_terraform_state_credentials(kubernetes: &dyn Kubernetes, event_details: EventDetails) {
+    let result = new_secret_manager(
+        &get_vault_mount_name(kubernetes.context().is_test_cluster()),
+        event_details.clone(),
+    )
+    .map_err(|err| err.user_log_message().to_string())
+    .and_then(|secret_manager| {
+        create_or_update_terraform_state_credentials(
+            secret_manager.as_ref(),
+            kubernetes.id(),
+            kubernetes.cloud_provider().terraform_state_credentials(),
+        )
+        .map_err(|err| err.to_string())
+    });
+
+    if let Err(err) = result {
+        kubernetes.logger().log(EngineEvent::Warning(
+            event_details,
+            EventMessage::new(
+                "Cannot store Terraform state credentials in the secret manager".to_string(),
+                Some(err),
+            ),
+        ));
+    }
+}
+
+/// Remove the Terraform state credentials of a deleted cluster, failures are ignored
+pub fn delete_cluster_terraform_state_credentials(kubernetes: &dyn Kubernetes, event_details: EventDetails) {
+    if let Ok(secret_manager) =
+        new_secret_manager(&get_vault_mount_name(kubernetes.context().is_test_cluster()), event_details)
+    {
+        let _ = delete_terraform_state_credentials(secret_manager.as_ref(), kubernetes.id());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cloud_provider::TerraformStateCredentials;
+    use crate::secret_manager::cluster_secrets::{
+        create_or_update_terraform_state_credentials, delete_terraform_state_credentials,
+        get_terraform_state_credentials,
+    };
+    use crate::secret_manager::local::LocalSecretManager;
+    use crate::secret_manager::SecretManagerError;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_terraform_state_credentials() {
+        // setup:
+        let dir = tempdir().unwrap();
+        let secret_manager = LocalSecretManager::new(&dir.path().join("secrets.json"), "passphrase", "clusters");
+        let credentials = TerraformStateCredentials::new("access_key", "secret_key", "fr-par");
+
+        // execute:
+        let created = create_or_update_terraform_state_credentials(&secret_manager, "cluster-id", &credentials);
+        let unchanged = create_or_update_terraform_state_credentials(&secret_manager, "cluster-id", &credentials);
+
+        // verify:
+        assert_eq!(created.unwrap().map(|v| v.version), Some(1));
+        assert_eq!(unchanged, Ok(None));
+        assert_eq!(get_terraform_state_credentials(&secret_manager, "cluster-id"), Ok(credentials));
+
+        delete_terraform_state_credentials(&secret_manager, "cluster-id").unwrap();
+        assert!(matches!(
+            get_terraform_state_credentials(&secret_manager, "cluster-id"),
+            Err(SecretManagerError::NotFound { .. })
+        ));
+    }
+}
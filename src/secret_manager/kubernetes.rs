@@ -0,0 +1,206 @@
+use crate::errors::{CommandError, EngineError};
+use crate::events::EventDetails;
+use crate::runtime::block_on;
+use crate::secret_manager::versioned_secret::VersionedSecret;
+use crate::secret_manager::{SecretManager, SecretManagerError, SecretVersion};
+use k8s_openapi::api::core::v1::Secret;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use k8s_openapi::ByteString;
+use kube::api::{DeleteParams, ListParams, PostParams};
+use kube::Api;
+use openssl::sha::sha256;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::env;
+
+pub const SECRET_MANAGER_KUBERNETES_NAMESPACE: &str = "SECRET_MANAGER_KUBERNETES_NAMESPACE";
+
+const MOUNT_LABEL: &str = "qovery.com/secret-manager-mount";
+const PATH_ANNOTATION: &str = "qovery.com/secret-path";
+const VERSIONS_KEY: &str = "versions";
+
+/// Secrets stored as Kubernetes secrets, one per path holding all its versions.
+/// Access is granted by the RBAC rules of the namespace.
+pub struct KubernetesSecretManager {
+    client: kube::Client,
+    namespace: String,
+    mount: String,
+}
+
+impl KubernetesSecretManager {
+    pub fn new(client: kube::Client, namespace: &str, mount: &str) -> KubernetesSecretManager {
+        KubernetesSecretManager {
+            client,
+            namespace: namespace.to_string(),
+            mount: mount.to_string(),
+        }
+    }
+
+    /// Uses the cluster the engine is running into, or the one of `KUBECONFIG`
+    pub fn new_from_env(mount: &str, event_details: EventDetails) -> Result<KubernetesSecretManager, EngineError> {
+        let namespace = env::var(SECRET_MANAGER_KUBERNETES_NAMESPACE).unwrap_or_else(|_| "qovery".to_string());
+        let client = block_on(kube::Client::try_default()).map_err(|err| {
+            EngineError::new_secret_manager_error(
+                event_details,
+                CommandError::new(
+                    "Cannot connect to Kubernetes to read secrets".to_string(),
+                    Some(err.to_string()),
+                    None,
+                ),
+            )
+        })?;
+
+        Ok(KubernetesSecretManager::new(client, &namespace, mount))
+    }
+
+    fn api(&self) -> Api<Secret> {
+        Api::namespaced(self.client.clone(), &self.namespace)
+    }
+
+    /// Paths can contain any character, so Kubernetes secrets are named after their hash
+    fn secret_name(&self, path: &str) -> String {
+        let hash = sha256(format!("{}/{}", self.mount, path).as_bytes());
+        let hash = hash.iter().map(|byte| format!("{:02x}", byte)).collect::<String>();
+
+        format!("qovery-secret-{}", &hash[..40])
+    }
+
+    fn mount_label(&self) -> String {
+        let label = self
+            .mount
+            .chars()
+            .map(|c| match c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                true => c,
+                false => '-',
+            })
+            .collect::<String>();
+
+        crate::string::cut(label, 63)
+    }
+
+    fn read(&self, path: &str) -> Result<Option<(Secret, VersionedSecret)>, SecretManagerError> {
+        let secret = match block_on(self.api().get_opt(&self.secret_name(path)))
+            .map_err(|err| SecretManagerError::Backend(err.to_string()))?
+        {
+            Some(secret) => secret,
+            None => return Ok(None),
+        };
+
+        let versions = secret
+            .data
+            .as_ref()
+            .and_then(|data| data.get(VERSIONS_KEY))
+            .map(|versions| serde_json::from_slice::<VersionedSecret>(&versions.0))
+            .transpose()
+            .map_err(|err| SecretManagerError::InvalidContent {
+                path: path.to_string(),
+                raw_error: err.to_string(),
+            })?
+            .unwrap_or_default();
+
+        Ok(Some((secret, versions)))
+    }
+
+    /// Replacing a secret fails if it has been updated since it has been read
+    fn write(
+        &self,
+        path: &str,
+        current_secret: Option<Secret>,
+        versions: &VersionedSecret,
+    ) -> Result<(), SecretManagerError> {
+        let versions = serde_json::to_vec(versions).map_err(|err| SecretManagerError::Backend(err.to_string()))?;
+        let data = BTreeMap::from([(VERSIONS_KEY.to_string(), ByteString(versions))]);
+
+        let result = match current_secret {
+            Some(mut secret) => {
+                secret.data = Some(data);
+                block_on(
+                    self.api()
+                        .replace(&self.secret_name(path), &PostParams::default(), &secret),
+                )
+            }
+            None => {
+                let secret = Secret {
+                    metadata: ObjectMeta {
+                        name: Some(self.secret_name(path)),
+                        labels: Some(BTreeMap::from([(MOUNT_LABEL.to_string(), self.mount_label())])),
+                        annotations: Some(BTreeMap::from([(PATH_ANNOTATION.to_string(), path.to_string())])),
+                        ..Default::default()
+                    },
+                    type_: Some("Opaque".to_string()),
+                    data: Some(data),
+                    ..Default::default()
+                };
+                block_on(self.api().create(&PostParams::default(), &secret))
+            }
+        };
+
+        result
+            .map(|_| ())
+            .map_err(|err| SecretManagerError::Backend(err.to_string()))
+    }
+}
+
+impl SecretManager for KubernetesSecretManager {
+    fn name(&self) -> &str {
+        "kubernetes"
+    }
+
+    fn get_secret_version(&self, path: &str, version: Option<u64>) -> Result<Value, SecretManagerError> {
+        match self.read(path)? {
+            Some((_, versions)) => versions.get(path, version).cloned(),
+            None => Err(SecretManagerError::NotFound { path: path.to_string() }),
+        }
+    }
+
+    fn put_secret(&self, path: &str, content: &Value) -> Result<SecretVersion, SecretManagerError> {
+        let (current_secret, mut versions) = match self.read(path)? {
+            Some((secret, versions)) => (Some(secret), versions),
+            None => (None, VersionedSecret::default()),
+        };
+        let version = versions.push(content.clone());
+        self.write(path, current_secret, &versions)?;
+
+        Ok(version)
+    }
+
+    fn list_secrets(&self) -> Result<Vec<String>, SecretManagerError> {
+        let secrets = block_on(self.api().list(&ListParams::default().labels(&format!(
+            "{}={}",
+            MOUNT_LABEL,
+            self.mount_label()
+        ))))
+        .map_err(|err| SecretManagerError::Backend(err.to_string()))?;
+
+        Ok(secrets
+            .items
+            .into_iter()
+            .filter_map(|secret| secret.metadata.annotations?.remove(PATH_ANNOTATION))
+            .collect())
+    }
+
+    fn list_versions(&self, path: &str) -> Result<Vec<SecretVersion>, SecretManagerError> {
+        match self.read(path)? {
+            Some((_, versions)) => Ok(versions.versions()),
+            None => Err(SecretManagerError::NotFound { path: path.to_string() }),
+        }
+    }
+
+    fn destroy_versions(&self, path: &str, versions_to_destroy: &[u64]) -> Result<(), SecretManagerError> {
+        match self.read(path)? {
+            Some((secret, mut versions)) => {
+                versions.destroy(versions_to_destroy);
+                self.write(path, Some(secret), &versions)
+            }
+            None => Err(SecretManagerError::NotFound { path: path.to_string() }),
+        }
+    }
+
+    fn delete_secret(&self, path: &str) -> Result<(), SecretManagerError> {
+        match block_on(self.api().delete(&self.secret_name(path), &DeleteParams::default())) {
+            Ok(_) => Ok(()),
+            Err(kube::Error::Api(err)) if err.code == 404 => Ok(()),
+            Err(err) => Err(SecretManagerError::Backend(err.to_string())),
+        }
+    }
+}
@@ -0,0 +1,293 @@
+use crate::errors::EngineError;
+use crate::events::EventDetails;
+use crate::secret_manager::versioned_secret::VersionedSecret;
+use crate::secret_manager::{SecretManager, SecretManagerError, SecretVersion};
+use openssl::hash::MessageDigest;
+use openssl::pkcs5::pbkdf2_hmac;
+use openssl::rand::rand_bytes;
+use openssl::symm::{decrypt_aead, encrypt_aead, Cipher};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub const SECRET_MANAGER_LOCAL_FILE: &str = "SECRET_MANAGER_LOCAL_FILE";
+pub const SECRET_MANAGER_LOCAL_PASSPHRASE: &str = "SECRET_MANAGER_LOCAL_PASSPHRASE";
+
+const KEY_DERIVATION_ITERATIONS: usize = 100_000;
+
+/// Secrets of every mount, by path
+type Secrets = BTreeMap<String, BTreeMap<String, VersionedSecret>>;
+
+/// Secrets stored into a single file, encrypted with AES-256-GCM using a key derived from a passphrase.
+/// Meant for development and tests, as it does not need any external service.
+pub struct LocalSecretManager {
+    file: PathBuf,
+    passphrase: String,
+    mount: String,
+    /// serializes read-modify-write cycles of the file
+    lock: Mutex<()>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EncryptedFile {
+    salt: String,
+    iv: String,
+    tag: String,
+    data: String,
+}
+
+impl LocalSecretManager {
+    pub fn new(file: &Path, passphrase: &str, mount: &str) -> LocalSecretManager {
+        LocalSecretManager {
+            file: file.to_path_buf(),
+            passphrase: passphrase.to_string(),
+            mount: mount.to_string(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    pub fn new_from_env(mount: &str, event_details: EventDetails) -> Result<LocalSecretManager, EngineError> {
+        let file = env::var(SECRET_MANAGER_LOCAL_FILE).map_err(|_| {
+            EngineError::new_missing_required_env_variable(event_details.clone(), SECRET_MANAGER_LOCAL_FILE.to_string())
+        })?;
+        let passphrase = env::var(SECRET_MANAGER_LOCAL_PASSPHRASE).map_err(|_| {
+            EngineError::new_missing_required_env_variable(event_details, SECRET_MANAGER_LOCAL_PASSPHRASE.to_string())
+        })?;
+
+        Ok(LocalSecretManager::new(Path::new(&file), &passphrase, mount))
+    }
+
+    fn read(&self) -> Result<Secrets, SecretManagerError> {
+        if !self.file.exists() {
+            return Ok(Secrets::new());
+        }
+
+        let content = fs::read(&self.file).map_err(|err| SecretManagerError::Backend(err.to_string()))?;
+        let encrypted_file: EncryptedFile = serde_json::from_slice(&content)
+            .map_err(|err| SecretManagerError::Backend(format!("invalid secrets file: {}", err)))?;
+        let decode = |value: &str| {
+            base64::decode(value).map_err(|err| SecretManagerError::Backend(format!("invalid secrets file: {}", err)))
+        };
+        let salt = decode(&encrypted_file.salt)?;
+        let data = decrypt_aead(
+            Cipher::aes_256_gcm(),
+            &self.key(&salt)?,
+            Some(&decode(&encrypted_file.iv)?),
+            &[],
+            &decode(&encrypted_file.data)?,
+            &decode(&encrypted_file.tag)?,
+        )
+        .map_err(|_| SecretManagerError::Backend("cannot decrypt secrets file, check the passphrase".to_string()))?;
+
+        serde_json::from_slice(&data).map_err(|err| SecretManagerError::Backend(format!("invalid secrets: {}", err)))
+    }
+
+    fn write(&self, secrets: &Secrets) -> Result<(), SecretManagerError> {
+        let data = serde_json::to_vec(secrets).map_err(|err| SecretManagerError::Backend(err.to_string()))?;
+        // a new salt and iv for every write, a GCM iv must never be reused with the same key
+        let mut salt = [0u8; 16];
+        let mut iv = [0u8; 12];
+        rand_bytes(&mut salt).map_err(|err| SecretManagerError::Backend(err.to_string()))?;
+        rand_bytes(&mut iv).map_err(|err| SecretManagerError::Backend(err.to_string()))?;
+        let mut tag = [0u8; 16];
+        let encrypted_data = encrypt_aead(Cipher::aes_256_gcm(), &self.key(&salt)?, Some(&iv), &[], &data, &mut tag)
+            .map_err(|err| SecretManagerError::Backend(err.to_string()))?;
+        let encrypted_file = EncryptedFile {
+            salt: base64::encode(salt),
+            iv: base64::encode(iv),
+            tag: base64::encode(tag),
+            data: base64::encode(encrypted_data),
+        };
+        let content =
+            serde_json::to_vec(&encrypted_file).map_err(|err| SecretManagerError::Backend(err.to_string()))?;
+
+        // replace the file at once, to never leave it half written
+        let tmp_file = self.file.with_extension("tmp");
+        fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(&tmp_file)
+            .and_then(|mut file| file.write_all(&content))
+            .and_then(|_| fs::rename(&tmp_file, &self.file))
+            .map_err(|err| SecretManagerError::Backend(err.to_string()))
+    }
+
+    fn key(&self, salt: &[u8]) -> Result<[u8; 32], SecretManagerError> {
+        let mut key = [0u8; 32];
+        pbkdf2_hmac(
+            self.passphrase.as_bytes(),
+            salt,
+            KEY_DERIVATION_ITERATIONS,
+            MessageDigest::sha256(),
+            &mut key,
+        )
+        .map_err(|err| SecretManagerError::Backend(err.to_string()))?;
+
+        Ok(key)
+    }
+
+    fn update<R, F>(&self, update: F) -> Result<R, SecretManagerError>
+    where
+        F: FnOnce(&mut BTreeMap<String, VersionedSecret>) -> Result<R, SecretManagerError>,
+    {
+        let _lock = self.lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut secrets = self.read()?;
+        let result = update(secrets.entry(self.mount.clone()).or_default())?;
+        self.write(&secrets)?;
+
+        Ok(result)
+    }
+
+    fn with_secret<R, F>(&self, path: &str, read: F) -> Result<R, SecretManagerError>
+    where
+        F: FnOnce(&VersionedSecret) -> Result<R, SecretManagerError>,
+    {
+        let secrets = self.read()?;
+        let secret = secrets
+            .get(&self.mount)
+            .and_then(|mount| mount.get(path))
+            .ok_or_else(|| SecretManagerError::NotFound { path: path.to_string() })?;
+
+        read(secret)
+    }
+}
+
+impl SecretManager for LocalSecretManager {
+    fn name(&self) -> &str {
+        "local"
+    }
+
+    fn get_secret_version(&self, path: &str, version: Option<u64>) -> Result<Value, SecretManagerError> {
+        self.with_secret(path, |secret| secret.get(path, version).cloned())
+    }
+
+    fn put_secret(&self, path: &str, content: &Value) -> Result<SecretVersion, SecretManagerError> {
+        self.update(|secrets| Ok(secrets.entry(path.to_string()).or_default().push(content.clone())))
+    }
+
+    fn list_secrets(&self) -> Result<Vec<String>, SecretManagerError> {
+        Ok(self
+            .read()?
+            .remove(&self.mount)
+            .map(|secrets| secrets.into_keys().collect())
+            .unwrap_or_default())
+    }
+
+    fn list_versions(&self, path: &str) -> Result<Vec<SecretVersion>, SecretManagerError> {
+        self.with_secret(path, |secret| Ok(secret.versions()))
+    }
+
+    fn destroy_versions(&self, path: &str, versions: &[u64]) -> Result<(), SecretManagerError> {
+        self.update(|secrets| match secrets.get_mut(path) {
+            Some(secret) => {
+                secret.destroy(versions);
+                Ok(())
+            }
+            None => Err(SecretManagerError::NotFound { path: path.to_string() }),
+        })
+    }
+
+    fn delete_secret(&self, path: &str) -> Result<(), SecretManagerError> {
+        self.update(|secrets| {
+            secrets.remove(path);
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::secret_manager::local::LocalSecretManager;
+    use crate::secret_manager::{
+        create_or_update_secret, get_secret, rotate_secret, SecretManager, SecretManagerError,
+    };
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_local_secret_manager() {
+        // setup:
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("secrets.json");
+        let secret_manager = LocalSecretManager::new(&file, "passphrase", "clusters");
+
+        // execute:
+        let first_version = create_or_update_secret(&secret_manager, "cluster-1", &json!({"token": "a"})).unwrap();
+        let unchanged = create_or_update_secret(&secret_manager, "cluster-1", &json!({"token": "a"})).unwrap();
+        let second_version = create_or_update_secret(&secret_manager, "cluster-1", &json!({"token": "b"})).unwrap();
+        LocalSecretManager::new(&file, "passphrase", "other")
+            .put_secret("cluster-2", &json!({}))
+            .unwrap();
+
+        // verify:
+        assert_eq!(first_version.map(|v| v.version), Some(1));
+        assert!(unchanged.is_none());
+        assert_eq!(second_version.map(|v| v.version), Some(2));
+        assert_eq!(
+            get_secret::<serde_json::Value>(&secret_manager, "cluster-1"),
+            Ok(json!({"token": "b"}))
+        );
+        assert_eq!(
+            secret_manager.get_secret_version("cluster-1", Some(1)),
+            Ok(json!({"token": "a"}))
+        );
+        assert_eq!(secret_manager.list_secrets(), Ok(vec!["cluster-1".to_string()]));
+        assert!(!std::fs::read_to_string(&file).unwrap().contains("token"));
+
+        secret_manager.delete_secret("cluster-1").unwrap();
+        assert_eq!(
+            secret_manager.get_secret_version("cluster-1", None),
+            Err(SecretManagerError::NotFound {
+                path: "cluster-1".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_local_secret_manager_rotation() {
+        // setup:
+        let dir = tempdir().unwrap();
+        let secret_manager = LocalSecretManager::new(&dir.path().join("secrets.json"), "passphrase", "clusters");
+
+        // execute:
+        for _ in 0..3 {
+            rotate_secret::<u32, _>(&secret_manager, "counter", 2, |current| current.unwrap_or_default() + 1).unwrap();
+        }
+
+        // verify:
+        assert_eq!(get_secret::<u32>(&secret_manager, "counter"), Ok(3));
+        assert_eq!(
+            secret_manager
+                .list_versions("counter")
+                .unwrap()
+                .iter()
+                .map(|v| v.version)
+                .collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+    }
+
+    #[test]
+    fn test_local_secret_manager_wrong_passphrase() {
+        // setup:
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("secrets.json");
+        LocalSecretManager::new(&file, "passphrase", "clusters")
+            .put_secret("cluster-1", &json!({"token": "a"}))
+            .unwrap();
+
+        // execute:
+        let result = LocalSecretManager::new(&file, "wrong", "clusters").get_secret_version("cluster-1", None);
+
+        // verify:
+        assert!(matches!(result, Err(SecretManagerError::Backend(_))));
+    }
+}
@@ -1 +1,153 @@
+pub mod aws_secrets_manager;
+pub mod cluster_secrets;
+pub mod kubernetes;
+pub mod local;
+pub mod scaleway_secret_manager;
 pub mod vault;
+mod versioned_secret;
+
+use crate::errors::{CommandError, EngineError};
+use crate::events::{EventDetails, Transmitter};
+use crate::secret_manager::kubernetes::KubernetesSecretManager;
+use crate::secret_manager::local::LocalSecretManager;
+use crate::secret_manager::vault::{QVaultClient, VaultSecretManager};
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::env;
+
+pub const SECRET_MANAGER_BACKEND: &str = "SECRET_MANAGER_BACKEND";
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum SecretManagerError {
+    #[error("Secret `{path}` does not exist")]
+    NotFound { path: String },
+    #[error("Version {version} of secret `{path}` does not exist")]
+    VersionNotFound { path: String, version: u64 },
+    #[error("Secret `{path}` has an unexpected content: {raw_error}")]
+    InvalidContent { path: String, raw_error: String },
+    #[error("Secret manager backend error: {0}")]
+    Backend(String),
+}
+
+impl From<SecretManagerError> for CommandError {
+    fn from(err: SecretManagerError) -> Self {
+        CommandError::new("Secret manager error".to_string(), Some(err.to_string()), None)
+    }
+}
+
+/// Metadata of a version of a secret
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SecretVersion {
+    pub version: u64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Storage of versioned secrets.
+/// A secret is a JSON document identified by its path, within the mount the secret manager has been created for.
+/// Storing a secret creates a new version of it, previous versions are kept until destroyed.
+pub trait SecretManager: Send + Sync {
+    fn name(&self) -> &str;
+    /// Content of a version of the secret, the latest one when `version` is None
+    fn get_secret_version(&self, path: &str, version: Option<u64>) -> Result<Value, SecretManagerError>;
+    /// Store a new version of the secret, creating the secret if needed
+    fn put_secret(&self, path: &str, content: &Value) -> Result<SecretVersion, SecretManagerError>;
+    /// Paths of all the secrets of the mount
+    fn list_secrets(&self) -> Result<Vec<String>, SecretManagerError>;
+    /// Versions of the secret which have not been destroyed, oldest first
+    fn list_versions(&self, path: &str) -> Result<Vec<SecretVersion>, SecretManagerError>;
+    /// Permanently remove versions of the secret
+    fn destroy_versions(&self, path: &str, versions: &[u64]) -> Result<(), SecretManagerError>;
+    /// Remove the secret and all its versions
+    fn delete_secret(&self, path: &str) -> Result<(), SecretManagerError>;
+}
+
+/// Latest version of the secret
+pub fn get_secret<T: DeserializeOwned>(
+    secret_manager: &dyn SecretManager,
+    path: &str,
+) -> Result<T, SecretManagerError> {
+    let content = secret_manager.get_secret_version(path, None)?;
+    serde_json::from_value(content).map_err(|err| SecretManagerError::InvalidContent {
+        path: path.to_string(),
+        raw_error: err.to_string(),
+    })
+}
+
+/// Store the secret only if its content changed, to avoid creating useless versions.
+/// Returns the created version, if any.
+pub fn create_or_update_secret<T: Serialize>(
+    secret_manager: &dyn SecretManager,
+    path: &str,
+    content: &T,
+) -> Result<Option<SecretVersion>, SecretManagerError> {
+    let content = serde_json::to_value(content).map_err(|err| SecretManagerError::InvalidContent {
+        path: path.to_string(),
+        raw_error: err.to_string(),
+    })?;
+
+    match secret_manager.get_secret_version(path, None) {
+        Ok(current_content) if current_content == content => Ok(None),
+        Ok(_) | Err(SecretManagerError::NotFound { .. }) => secret_manager.put_secret(path, &content).map(Some),
+        Err(err) => Err(err),
+    }
+}
+
+/// Store a new version of the secret generated from the current one, if any.
+/// Only the `keep_versions` most recent versions are kept, older ones are destroyed.
+pub fn rotate_secret<T, F>(
+    secret_manager: &dyn SecretManager,
+    path: &str,
+    keep_versions: usize,
+    rotate: F,
+) -> Result<SecretVersion, SecretManagerError>
+where
+    T: Serialize + DeserializeOwned,
+    F: FnOnce(Option<T>) -> T,
+{
+    let current = match get_secret::<T>(secret_manager, path) {
+        Ok(current) => Some(current),
+        Err(SecretManagerError::NotFound { .. }) => None,
+        Err(err) => return Err(err),
+    };
+
+    let rotated = serde_json::to_value(rotate(current)).map_err(|err| SecretManagerError::InvalidContent {
+        path: path.to_string(),
+        raw_error: err.to_string(),
+    })?;
+    let version = secret_manager.put_secret(path, &rotated)?;
+
+    let versions = secret_manager.list_versions(path)?;
+    let outdated_versions: Vec<u64> = versions
+        .iter()
+        .take(versions.len().saturating_sub(keep_versions.max(1)))
+        .map(|version| version.version)
+        .collect();
+    if !outdated_versions.is_empty() {
+        secret_manager.destroy_versions(path, &outdated_versions)?;
+    }
+
+    Ok(version)
+}
+
+/// Secret manager selected by the `SECRET_MANAGER_BACKEND` environment variable: `vault` (default), `kubernetes` or `local`.
+/// Secrets are scoped to the given mount.
+pub fn new_secret_manager(mount: &str, event_details: EventDetails) -> Result<Box<dyn SecretManager>, EngineError> {
+    let backend = env::var(SECRET_MANAGER_BACKEND).unwrap_or_else(|_| "vault".to_string());
+    let event_details =
+        EventDetails::clone_changing_transmitter(event_details, Transmitter::SecretManager(backend.clone()));
+
+    match backend.as_str() {
+        "vault" => Ok(Box::new(VaultSecretManager::new(QVaultClient::new(event_details)?, mount))),
+        "kubernetes" => Ok(Box::new(KubernetesSecretManager::new_from_env(mount, event_details)?)),
+        "local" => Ok(Box::new(LocalSecretManager::new_from_env(mount, event_details)?)),
+        _ => Err(EngineError::new_secret_manager_error(
+            event_details,
+            CommandError::new_from_safe_message(format!(
+                "Unknown secret manager backend `{}`, expected one of vault, kubernetes or local",
+                backend
+            )),
+        )),
+    }
+}
@@ -0,0 +1,57 @@
+use crate::errors::CommandError;
+use reqwest::blocking::Client;
+use serde::Deserialize;
+
+const SCALEWAY_API_URL: &str = "https://api.scaleway.com";
+
+/// Read only client of Scaleway Secret Manager
+pub struct ScalewaySecretManager {
+    secret_key: String,
+    region: String,
+}
+
+#[derive(Deserialize)]
+struct AccessSecretVersionResponse {
+    /// base64 encoded
+    data: String,
+}
+
+impl ScalewaySecretManager {
+    pub fn new(secret_key: &str, region: &str) -> ScalewaySecretManager {
+        ScalewaySecretManager {
+            secret_key: secret_key.to_string(),
+            region: region.to_string(),
+        }
+    }
+
+    /// Latest version of a text secret
+    pub fn get_secret(&self, secret_id: &str) -> Result<String, CommandError> {
+        let to_error = |raw_error: String| {
+            CommandError::new(
+                format!("Cannot read secret `{}` from Scaleway Secret Manager", secret_id),
+                Some(raw_error),
+                None,
+            )
+        };
+
+        let url = format!(
+            "{}/secret-manager/v1beta1/regions/{}/secrets/{}/versions/latest/access",
+            SCALEWAY_API_URL, self.region, secret_id
+        );
+        let response = Client::new()
+            .get(&url)
+            .header("X-Auth-Token", &self.secret_key)
+            .send()
+            .map_err(|err| to_error(err.to_string()))?;
+        if !response.status().is_success() {
+            return Err(to_error(response.text().unwrap_or_default()));
+        }
+
+        let secret_version = response
+            .json::<AccessSecretVersionResponse>()
+            .map_err(|err| to_error(err.to_string()))?;
+        let data = base64::decode(secret_version.data).map_err(|err| to_error(err.to_string()))?;
+
+        String::from_utf8(data).map_err(|_| to_error("binary secrets are not supported".to_string()))
+    }
+}
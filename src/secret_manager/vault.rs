@@ -1,13 +1,18 @@
 use crate::errors::{CommandError, EngineError};
 use crate::events::{EventDetails, Transmitter};
+use crate::io_models::QoveryIdentifier;
 use crate::runtime::block_on;
+use crate::secret_manager::{SecretManager, SecretManagerError, SecretVersion};
+use chrono::{DateTime, Utc};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::env;
+use std::fs;
 use vaultrs::api::kv2::responses::SecretVersionMetadata;
-use vaultrs::client::{VaultClient, VaultClientSettingsBuilder};
+use vaultrs::client::{Client, VaultClient, VaultClientSettingsBuilder};
 use vaultrs::error::ClientError;
-use vaultrs::kv2;
+use vaultrs::{auth, kv2};
 use vaultrs_login::engines::approle::AppRoleLogin;
 use vaultrs_login::LoginClient;
 
@@ -19,14 +24,47 @@ pub struct QVaultClient {
 pub enum VaultAuthType {
     Token,
     AppRole,
+    /// Login with the service account token of the pod the engine is running into
+    Kubernetes,
     Invalid,
 }
 
+const KUBERNETES_SERVICE_ACCOUNT_TOKEN_PATH: &str = "/var/run/secrets/kubernetes.io/serviceaccount/token";
+/// Mount holding the secrets referenced by users, each organization has its own `<organization id>/` folder
+const VAULT_ORGANIZATIONS_MOUNT: &str = "VAULT_ORGANIZATIONS_MOUNT";
+const DEFAULT_ORGANIZATIONS_MOUNT: &str = "organizations";
+
 impl QVaultClient {
     pub fn get_secret<D: DeserializeOwned>(&self, mount: &str, secret_name_path: &str) -> Result<D, ClientError> {
         block_on(kv2::read(&self.connection, mount, secret_name_path))
     }
 
+    /// Read a secret referenced by a user. The engine credentials can read every secret, so only the ones stored in
+    /// the folder of the organization are allowed, cluster secrets and secrets of other organizations are refused.
+    pub fn get_organization_secret<D: DeserializeOwned>(
+        &self,
+        organization_id: &QoveryIdentifier,
+        mount: &str,
+        secret_name_path: &str,
+    ) -> Result<D, CommandError> {
+        let organizations_mount =
+            env::var(VAULT_ORGANIZATIONS_MOUNT).unwrap_or_else(|_| DEFAULT_ORGANIZATIONS_MOUNT.to_string());
+        if !is_organization_secret(&organizations_mount, &organization_id.to_string(), mount, secret_name_path) {
+            return Err(CommandError::new_from_safe_message(format!(
+                "Vault secret {}/{} cannot be read, secrets must be stored under {}/{}/",
+                mount, secret_name_path, organizations_mount, organization_id
+            )));
+        }
+
+        self.get_secret(mount, secret_name_path).map_err(|err| {
+            CommandError::new(
+                format!("Cannot read Vault secret {}/{}", mount, secret_name_path),
+                Some(err.to_string()),
+                None,
+            )
+        })
+    }
+
     pub fn crate_update_secret<T: Serialize>(
         &self,
         mount: &str,
@@ -61,9 +99,11 @@ impl QVaultClient {
         if Self::get_env_var("VAULT_TOKEN", event_details.clone()).is_ok() {
             auth_type = VaultAuthType::Token;
         } else if Self::get_env_var("VAULT_ROLE_ID", event_details.clone()).is_ok()
-            && Self::get_env_var("VAULT_SECRET_ID", event_details).is_ok()
+            && Self::get_env_var("VAULT_SECRET_ID", event_details.clone()).is_ok()
         {
             auth_type = VaultAuthType::AppRole;
+        } else if Self::get_env_var("VAULT_KUBERNETES_ROLE", event_details).is_ok() {
+            auth_type = VaultAuthType::Kubernetes;
         };
 
         auth_type
@@ -131,6 +171,52 @@ impl QVaultClient {
 
                 client
             }
+            VaultAuthType::Kubernetes => {
+                let role = Self::get_env_var("VAULT_KUBERNETES_ROLE", event_details.clone())?;
+                let mount = env::var("VAULT_KUBERNETES_MOUNT").unwrap_or_else(|_| "kubernetes".to_string());
+
+                let mut client = match VaultClient::new(
+                    VaultClientSettingsBuilder::default()
+                        .address(vault_addr)
+                        .build()
+                        .unwrap(),
+                ) {
+                    Ok(x) => x,
+                    Err(e) => {
+                        let cmd_error = CommandError::new(
+                            "error: wasn't able to contact Vault server".to_string(),
+                            Some(format!(
+                                "error: wasn't able to contact Vault server with the kubernetes service account. {:?}",
+                                e
+                            )),
+                            None,
+                        );
+                        return Err(EngineError::new_vault_connection_error(event_details, cmd_error));
+                    }
+                };
+
+                let login = fs::read_to_string(KUBERNETES_SERVICE_ACCOUNT_TOKEN_PATH)
+                    .map_err(|e| e.to_string())
+                    .and_then(|jwt| {
+                        block_on(auth::kubernetes::login(&client, &mount, &role, jwt.trim())).map_err(|e| e.to_string())
+                    });
+                match login {
+                    Ok(auth_info) => client.set_token(&auth_info.client_token),
+                    Err(e) => {
+                        let cmd_error = CommandError::new(
+                            "error: wasn't able to login to Vault server".to_string(),
+                            Some(format!(
+                                "error: wasn't able to login to Vault server with the kubernetes service account. {}",
+                                e
+                            )),
+                            None,
+                        );
+                        return Err(EngineError::new_vault_connection_error(event_details, cmd_error));
+                    }
+                }
+
+                client
+            }
             VaultAuthType::Invalid => {
                 let cmd_error = CommandError::new(
                     "error: can't contact Vault server".to_string(),
@@ -145,6 +231,133 @@ impl QVaultClient {
     }
 }
 
+/// Secrets of a KV2 mount, Vault handles their versions
+pub struct VaultSecretManager {
+    client: QVaultClient,
+    mount: String,
+}
+
+impl VaultSecretManager {
+    pub fn new(client: QVaultClient, mount: &str) -> VaultSecretManager {
+        VaultSecretManager {
+            client,
+            mount: mount.to_string(),
+        }
+    }
+
+    fn to_error(path: &str, err: ClientError) -> SecretManagerError {
+        match err {
+            ClientError::APIError { code: 404, .. } => SecretManagerError::NotFound { path: path.to_string() },
+            err => SecretManagerError::Backend(err.to_string()),
+        }
+    }
+
+    /// Secrets of a folder, and of its sub folders
+    fn list_folder(&self, folder: &str) -> Result<Vec<String>, SecretManagerError> {
+        let keys = match block_on(kv2::list(&self.client.connection, &self.mount, folder)) {
+            Ok(keys) => keys,
+            // Vault answers not found for empty folders
+            Err(ClientError::APIError { code: 404, .. }) => return Ok(vec![]),
+            Err(err) => return Err(SecretManagerError::Backend(err.to_string())),
+        };
+
+        let mut paths = vec![];
+        for key in keys {
+            let path = format!("{}{}", folder, key);
+            match key.ends_with('/') {
+                true => paths.extend(self.list_folder(&path)?),
+                false => paths.push(path),
+            }
+        }
+
+        Ok(paths)
+    }
+}
+
+/// Whether a secret path is within the folder of the organization, without escaping it
+fn is_organization_secret(organizations_mount: &str, organization_id: &str, mount: &str, path: &str) -> bool {
+    let mut segments = path.trim_start_matches('/').split('/');
+    mount.trim_matches('/') == organizations_mount
+        && segments.next() == Some(organization_id)
+        && segments.clone().next().is_some()
+        && segments.all(|segment| !segment.is_empty() && segment != "." && segment != "..")
+}
+
+fn parse_vault_time(time: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(time)
+        .map(|time| time.with_timezone(&Utc))
+        .unwrap_or_else(|_| Utc::now())
+}
+
+impl SecretManager for VaultSecretManager {
+    fn name(&self) -> &str {
+        "vault"
+    }
+
+    fn get_secret_version(&self, path: &str, version: Option<u64>) -> Result<Value, SecretManagerError> {
+        let content = match version {
+            Some(version) => block_on(kv2::read_version(&self.client.connection, &self.mount, path, version)),
+            None => block_on(kv2::read(&self.client.connection, &self.mount, path)),
+        };
+
+        content.map_err(|err| match (err, version) {
+            (ClientError::APIError { code: 404, .. }, Some(version)) => SecretManagerError::VersionNotFound {
+                path: path.to_string(),
+                version,
+            },
+            (err, _) => Self::to_error(path, err),
+        })
+    }
+
+    fn put_secret(&self, path: &str, content: &Value) -> Result<SecretVersion, SecretManagerError> {
+        block_on(kv2::set(&self.client.connection, &self.mount, path, content))
+            .map(|metadata| SecretVersion {
+                version: metadata.version,
+                created_at: parse_vault_time(&metadata.created_time),
+            })
+            .map_err(|err| Self::to_error(path, err))
+    }
+
+    fn list_secrets(&self) -> Result<Vec<String>, SecretManagerError> {
+        self.list_folder("")
+    }
+
+    fn list_versions(&self, path: &str) -> Result<Vec<SecretVersion>, SecretManagerError> {
+        let metadata = block_on(kv2::read_metadata(&self.client.connection, &self.mount, path))
+            .map_err(|err| Self::to_error(path, err))?;
+
+        let mut versions: Vec<SecretVersion> = metadata
+            .versions
+            .into_iter()
+            .filter(|(_, version)| !version.destroyed && version.deletion_time.is_empty())
+            .filter_map(|(version, metadata)| {
+                Some(SecretVersion {
+                    version: version.parse().ok()?,
+                    created_at: parse_vault_time(&metadata.created_time),
+                })
+            })
+            .collect();
+        versions.sort_by_key(|version| version.version);
+
+        Ok(versions)
+    }
+
+    fn destroy_versions(&self, path: &str, versions: &[u64]) -> Result<(), SecretManagerError> {
+        block_on(kv2::destroy_versions(
+            &self.client.connection,
+            &self.mount,
+            path,
+            versions.to_vec(),
+        ))
+        .map_err(|err| Self::to_error(path, err))
+    }
+
+    fn delete_secret(&self, path: &str) -> Result<(), SecretManagerError> {
+        block_on(kv2::delete_metadata(&self.client.connection, &self.mount, path))
+            .map_err(|err| Self::to_error(path, err))
+    }
+}
+
 pub fn get_vault_mount_name(is_test_cluster: bool) -> String {
     match is_test_cluster {
         false => "official-clusters-access",
@@ -152,3 +365,53 @@ pub fn get_vault_mount_name(is_test_cluster: bool) -> String {
     }
     .to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::secret_manager::vault::is_organization_secret;
+
+    #[test]
+    fn test_is_organization_secret() {
+        // setup:
+        let orga_id = "8b6c5b4e-46f8-4e5f-a0f4-0c2c9e4b1d2a";
+
+        // execute & verify:
+        assert!(is_organization_secret(
+            "organizations",
+            orga_id,
+            "organizations",
+            &format!("{}/database", orga_id)
+        ));
+        assert!(is_organization_secret(
+            "organizations",
+            orga_id,
+            "/organizations/",
+            &format!("/{}/env/api", orga_id)
+        ));
+        assert!(!is_organization_secret(
+            "organizations",
+            orga_id,
+            "official-clusters-access",
+            &format!("{}/database", orga_id)
+        ));
+        assert!(!is_organization_secret(
+            "organizations",
+            orga_id,
+            "organizations",
+            "another-orga/database"
+        ));
+        assert!(!is_organization_secret("organizations", orga_id, "organizations", orga_id));
+        assert!(!is_organization_secret(
+            "organizations",
+            orga_id,
+            "organizations",
+            &format!("{}/", orga_id)
+        ));
+        assert!(!is_organization_secret(
+            "organizations",
+            orga_id,
+            "organizations",
+            &format!("{}/../another-orga/database", orga_id)
+        ));
+    }
+}
@@ -0,0 +1,103 @@
+use crate::secret_manager::{SecretManagerError, SecretVersion};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Versions of a secret, for backends which do not handle versioning themselves
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub(super) struct VersionedSecret {
+    /// Last version ever created, version numbers are never reused even if destroyed
+    current_version: u64,
+    versions: Vec<StoredSecretVersion>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct StoredSecretVersion {
+    version: u64,
+    created_at: DateTime<Utc>,
+    content: Value,
+}
+
+impl VersionedSecret {
+    pub(super) fn get(&self, path: &str, version: Option<u64>) -> Result<&Value, SecretManagerError> {
+        let stored_version = match version {
+            Some(version) => self
+                .versions
+                .iter()
+                .find(|stored_version| stored_version.version == version)
+                .ok_or_else(|| SecretManagerError::VersionNotFound {
+                    path: path.to_string(),
+                    version,
+                })?,
+            None => self
+                .versions
+                .last()
+                .ok_or_else(|| SecretManagerError::NotFound { path: path.to_string() })?,
+        };
+
+        Ok(&stored_version.content)
+    }
+
+    pub(super) fn push(&mut self, content: Value) -> SecretVersion {
+        self.current_version += 1;
+        let stored_version = StoredSecretVersion {
+            version: self.current_version,
+            created_at: Utc::now(),
+            content,
+        };
+        let version = SecretVersion {
+            version: stored_version.version,
+            created_at: stored_version.created_at,
+        };
+        self.versions.push(stored_version);
+
+        version
+    }
+
+    pub(super) fn versions(&self) -> Vec<SecretVersion> {
+        self.versions
+            .iter()
+            .map(|stored_version| SecretVersion {
+                version: stored_version.version,
+                created_at: stored_version.created_at,
+            })
+            .collect()
+    }
+
+    pub(super) fn destroy(&mut self, versions: &[u64]) {
+        self.versions
+            .retain(|stored_version| !versions.contains(&stored_version.version));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::secret_manager::versioned_secret::VersionedSecret;
+    use crate::secret_manager::SecretManagerError;
+    use serde_json::json;
+
+    #[test]
+    fn test_versioned_secret() {
+        // setup:
+        let mut secret = VersionedSecret::default();
+
+        // execute:
+        secret.push(json!({"password": "v1"}));
+        secret.push(json!({"password": "v2"}));
+        secret.destroy(&[2]);
+        let version = secret.push(json!({"password": "v3"}));
+
+        // verify:
+        assert_eq!(version.version, 3);
+        assert_eq!(secret.get("db", None), Ok(&json!({"password": "v3"})));
+        assert_eq!(secret.get("db", Some(1)), Ok(&json!({"password": "v1"})));
+        assert_eq!(
+            secret.get("db", Some(2)),
+            Err(SecretManagerError::VersionNotFound {
+                path: "db".to_string(),
+                version: 2
+            })
+        );
+        assert_eq!(secret.versions().iter().map(|v| v.version).collect::<Vec<_>>(), vec![1, 3]);
+    }
+}
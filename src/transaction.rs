@@ -3,26 +3,34 @@ use crate::cloud_provider::environment::Environment;
 use std::cell::RefCell;
 use std::collections::HashSet;
 use std::rc::Rc;
+use std::time::Instant;
 use uuid::Uuid;
 
 use crate::cloud_provider::kubernetes::Kubernetes;
 use crate::cloud_provider::service::{Action, Service};
+use crate::cmd::helm::Helm;
 use crate::container_registry::errors::ContainerRegistryError;
 use crate::container_registry::to_engine_error;
 use crate::deployment_action::deploy_environment::EnvironmentDeployment;
+use crate::deployment_action::deployment_plan::EnvironmentPlan;
+use crate::deployment_action::helm_revisions::{rollback_helm_revisions, HelmReleaseRevision};
 use crate::engine::{EngineConfig, EngineConfigError};
 use crate::errors::{EngineError, Tag};
-use crate::events::{EngineEvent, EnvironmentStep, EventDetails, EventMessage, Stage, Transmitter};
-use crate::io_models::progress_listener::{ListenersHelper, ProgressInfo, ProgressLevel, ProgressScope};
+use crate::events::{EngineEvent, EnvironmentStep, EventDetails, EventMessage, InfrastructureStep, Stage, Transmitter};
+use crate::io_models::progress_listener::{ListenersHelper, ProgressInfo, ProgressLevel};
 use crate::io_models::QoveryIdentifier;
 use crate::logger::Logger;
-use crate::models::application::ApplicationService;
+use crate::metrics::{ExecutionTimings, StepTimer, TimingKind, TimingReport};
+use crate::runtime::block_on;
+use crate::utilities::get_kube_client;
 
 pub struct Transaction<'a> {
     engine: &'a EngineConfig,
     logger: Box<dyn Logger>,
     steps: Vec<Step>,
     executed_steps: Vec<Step>,
+    // helm revisions, before the failed environment step, of the services it has touched
+    helm_revisions: RefCell<Vec<HelmReleaseRevision>>,
     current_step: StepName,
     is_transaction_aborted: Box<dyn Fn() -> bool>,
     on_step_change: Box<dyn Fn(&StepName)>,
@@ -45,6 +53,7 @@ impl<'a> Transaction<'a> {
             logger,
             steps: vec![],
             executed_steps: vec![],
+            helm_revisions: RefCell::new(vec![]),
             current_step: StepName::Waiting,
             is_transaction_aborted,
             on_step_change,
@@ -131,20 +140,29 @@ impl<'a> Transaction<'a> {
         Ok(())
     }
 
-    fn build_and_push_applications(
+    fn build_and_push_images(
         &self,
-        applications: &mut [Box<dyn ApplicationService>],
+        environment: &mut Environment,
         option: &DeploymentOption,
     ) -> Result<(), EngineError> {
-        // do the same for applications
-        let mut apps_to_build = applications
+        // build only applications and jobs that are set with Action: Create
+        let applications = environment
+            .applications
             .iter_mut()
-            // build only applications that are set with Action: Create
             .filter(|app| *app.action() == Action::Create)
-            .collect::<Vec<_>>();
+            .map(|app| (app.progress_scope(), app.get_build_mut()));
+        let jobs = environment
+            .jobs
+            .iter_mut()
+            .filter(|job| *job.action() == Action::Create)
+            .filter_map(|job| {
+                let scope = job.progress_scope();
+                job.get_build_mut().map(|build| (scope, build))
+            });
+        let mut builds_to_build = applications.chain(jobs).collect::<Vec<_>>();
 
         // If nothing to build, do nothing
-        if apps_to_build.is_empty() {
+        if builds_to_build.is_empty() {
             return Ok(());
         }
 
@@ -174,9 +192,9 @@ impl<'a> Transaction<'a> {
         let cr_registry = self.engine.container_registry();
         cr_registry.create_registry().map_err(cr_to_engine_error)?;
 
-        for app in apps_to_build.iter_mut() {
+        for (progress_scope, build) in builds_to_build.iter_mut() {
             // If image already exist in the registry, skip the build
-            if !option.force_build && cr_registry.does_image_exists(&app.get_build().image) {
+            if !option.force_build && cr_registry.does_image_exists(&build.image) {
                 continue;
             }
 
@@ -184,7 +202,7 @@ impl<'a> Transaction<'a> {
             self.engine
                 .container_registry()
                 .create_repository(
-                    app.get_build().image.repository_name(),
+                    build.image.repository_name(),
                     self.engine
                         .kubernetes()
                         .advanced_settings()
@@ -192,14 +210,11 @@ impl<'a> Transaction<'a> {
                 )
                 .map_err(cr_to_engine_error)?;
 
-            // Ok now everything is setup, we can try to build the app
-            let build_result = self
-                .engine
-                .build_platform()
-                .build(app.get_build_mut(), &self.is_transaction_aborted);
+            // Ok now everything is setup, we can try to build the image
+            let build_result = self.engine.build_platform().build(build, &self.is_transaction_aborted);
 
             // logging
-            let image_name = app.get_build().image.full_image_name_with_tag();
+            let image_name = build.image.full_image_name_with_tag();
             let msg = match &build_result {
                 Ok(_) => format!("✅ Container image {} is built and ready to use", &image_name),
                 Err(BuildError::Aborted { .. }) => {
@@ -209,9 +224,7 @@ impl<'a> Transaction<'a> {
             };
 
             let progress_info = ProgressInfo::new(
-                ProgressScope::Application {
-                    id: app.id().to_string(),
-                },
+                progress_scope.clone(),
                 match build_result.is_ok() {
                     true => ProgressLevel::Info,
                     false => ProgressLevel::Error,
@@ -232,6 +245,33 @@ impl<'a> Transaction<'a> {
         Ok(())
     }
 
+    /// Plan mode: render the charts and Terraform files of every environment step, and diff them with what is
+    /// currently deployed. Nothing is built, applied nor deleted.
+    pub fn plan(&self) -> Result<Vec<EnvironmentPlan>, EngineError> {
+        let mut plans = vec![];
+        for step in &self.steps {
+            let (environment, forced_action, environment_step) = match step {
+                Step::DeployEnvironment(environment) => (environment, None, EnvironmentStep::Deploy),
+                Step::PauseEnvironment(environment) => (environment, Some(Action::Pause), EnvironmentStep::Pause),
+                Step::DeleteEnvironment(environment) => (environment, Some(Action::Delete), EnvironmentStep::Delete),
+                Step::CreateKubernetes
+                | Step::DeleteKubernetes
+                | Step::PauseKubernetes
+                | Step::BuildEnvironment(_, _) => continue,
+            };
+
+            let event_details = self
+                .engine
+                .kubernetes()
+                .get_event_details(Stage::Environment(environment_step));
+            let environment = environment.as_ref().borrow();
+            let env_deployment = EnvironmentDeployment::new(self.engine, &environment, event_details)?;
+            plans.push(env_deployment.plan(forced_action)?);
+        }
+
+        Ok(plans)
+    }
+
     pub fn rollback(&self) -> Result<(), RollbackError> {
         for step in self.executed_steps.iter() {
             match step {
@@ -256,20 +296,90 @@ impl<'a> Transaction<'a> {
                 Step::BuildEnvironment(_environment_action, _option) => {
                     // revert build applications
                 }
-                Step::DeployEnvironment(_) => {}
-                Step::PauseEnvironment(_) => {}
-                Step::DeleteEnvironment(_) => {}
+                Step::DeployEnvironment(_) | Step::PauseEnvironment(_) | Step::DeleteEnvironment(_) => {
+                    // revert services to the helm revision they had before the environment step
+                    self.rollback_environment_services()?;
+                }
             }
         }
 
         Ok(())
     }
 
+    fn rollback_environment_services(&self) -> Result<(), RollbackError> {
+        let helm_revisions = self.helm_revisions.take();
+        if helm_revisions.is_empty() {
+            return Ok(());
+        }
+
+        let kubernetes = self.engine.kubernetes();
+        let event_details = kubernetes.get_event_details(Stage::Environment(EnvironmentStep::Rollback));
+        let kubeconfig_path = kubernetes
+            .get_kubeconfig_file_path()
+            .map_err(|err| RollbackError::CommitError(Box::new(err)))?;
+        let helm = Helm::new(
+            &kubeconfig_path,
+            &kubernetes.cloud_provider().credentials_environment_variables(),
+        )
+        .map_err(|err| RollbackError::CommitError(Box::new(crate::cmd::helm::to_engine_error(&event_details, err))))?;
+        let kube_credentials: Vec<(String, String)> = kubernetes
+            .cloud_provider()
+            .credentials_environment_variables()
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let kube = block_on(get_kube_client(&kubeconfig_path, kube_credentials.as_slice())).map_err(|err| {
+            RollbackError::CommitError(Box::new(EngineError::new_cannot_connect_to_k8s_cluster(
+                event_details.clone(),
+                err,
+            )))
+        })?;
+
+        rollback_helm_revisions(&helm, &kube, &helm_revisions, self.logger.as_ref());
+
+        Ok(())
+    }
+
     pub fn commit(mut self) -> TransactionResult {
+        let started_at = Instant::now();
+        let timings = ExecutionTimings::default();
+        let result = {
+            let _timings = timings.enter();
+            self.commit_steps()
+        };
+
+        self.report_timings(&timings.report(self.engine.context().execution_id(), started_at.elapsed()));
+        result
+    }
+
+    /// Log where the time of the execution went, and push it to Prometheus when a push gateway is configured
+    fn report_timings(&self, report: &TimingReport) {
+        let stage = match self.executed_steps.last() {
+            Some(step) => step.stage(),
+            None => return,
+        };
+        let event_details = self.get_event_details(stage, Transmitter::TaskManager);
+        self.logger.log(EngineEvent::Info(
+            event_details.clone(),
+            EventMessage::new_from_safe(report.to_string()),
+        ));
+
+        let context = self.engine.context();
+        if let Some(gateway_url) = context.prometheus_pushgateway_url() {
+            if let Err(err) = report.push_to_prometheus_gateway(gateway_url, &context.cluster_long_id().to_string()) {
+                self.logger
+                    .log(EngineEvent::Warning(event_details, EventMessage::new_from_safe(err)));
+            }
+        }
+    }
+
+    fn commit_steps(&mut self) -> TransactionResult {
         for step in self.steps.clone().into_iter() {
             // execution loop
             self.executed_steps.push(step.clone());
             self.set_current_step(step.step_name());
+            let stage = step.stage();
+            let _timer = StepTimer::start(TimingKind::Stage, format!("{}:{}", stage, stage.sub_step_name()));
 
             match step {
                 Step::CreateKubernetes => {
@@ -307,9 +417,8 @@ impl<'a> Transaction<'a> {
                         return TransactionResult::Canceled;
                     }
 
-                    // build applications
-                    let applications = &mut (environment.as_ref().borrow_mut()).applications;
-                    match self.build_and_push_applications(applications, &option) {
+                    // build applications and jobs
+                    match self.build_and_push_images(&mut environment.as_ref().borrow_mut(), &option) {
                         Ok(apps) => apps,
                         Err(engine_err) => {
                             self.logger.log(EngineEvent::Error(
@@ -345,6 +454,7 @@ impl<'a> Transaction<'a> {
 
                         env_deployment.on_create().map_err(|err| {
                             error!("Error while deploying environment: {:?}", err);
+                            *self.helm_revisions.borrow_mut() = env_deployment.touched_helm_revisions();
                             (env_deployment.deployed_services, err)
                         })
                     }) {
@@ -375,6 +485,7 @@ impl<'a> Transaction<'a> {
 
                         env_deployment.on_pause().map_err(|err| {
                             error!("Error while pausing environment: {:?}", err);
+                            *self.helm_revisions.borrow_mut() = env_deployment.touched_helm_revisions();
                             (env_deployment.deployed_services, err)
                         })
                     }) {
@@ -405,6 +516,7 @@ impl<'a> Transaction<'a> {
 
                         env_deployment.on_delete().map_err(|err| {
                             error!("Error while deleting environment: {:?}", err);
+                            *self.helm_revisions.borrow_mut() = env_deployment.touched_helm_revisions();
                             (env_deployment.deployed_services, err)
                         })
                     }) {
@@ -521,6 +633,20 @@ impl<'a> Transaction<'a> {
                 );
             }
 
+            for service in &environment.jobs {
+                if deployed_services.contains(service.long_id()) {
+                    continue;
+                }
+
+                send_progress(
+                    self.engine.kubernetes(),
+                    &environment.action,
+                    service.as_service(),
+                    execution_id,
+                    true,
+                );
+            }
+
             for service in &environment.routers {
                 if deployed_services.contains(service.long_id()) {
                     continue;
@@ -587,6 +713,18 @@ pub enum Step {
 }
 
 impl Step {
+    fn stage(&self) -> Stage {
+        match self {
+            Step::CreateKubernetes => Stage::Infrastructure(InfrastructureStep::Create),
+            Step::DeleteKubernetes => Stage::Infrastructure(InfrastructureStep::Delete),
+            Step::PauseKubernetes => Stage::Infrastructure(InfrastructureStep::Pause),
+            Step::BuildEnvironment(_, _) => Stage::Environment(EnvironmentStep::Build),
+            Step::DeployEnvironment(_) => Stage::Environment(EnvironmentStep::Deploy),
+            Step::PauseEnvironment(_) => Stage::Environment(EnvironmentStep::Pause),
+            Step::DeleteEnvironment(_) => Stage::Environment(EnvironmentStep::Delete),
+        }
+    }
+
     fn step_name(&self) -> StepName {
         match self {
             Step::CreateKubernetes => StepName::CreateKubernetes,
@@ -7,10 +7,10 @@ use crate::helpers::utilities::{generate_id, get_svc_name, is_pod_restarted_env}
 use ::function_name::named;
 use qovery_engine::cloud_provider::kubernetes::Kind as KubernetesKind;
 use qovery_engine::cloud_provider::Kind;
-use qovery_engine::io_models::application::{Port, Protocol};
+use qovery_engine::io_models::application::{Port, Protocol, SnapshotSchedule};
 use qovery_engine::io_models::context::CloneForTest;
 use qovery_engine::io_models::database::DatabaseMode::{CONTAINER, MANAGED};
-use qovery_engine::io_models::database::{Database, DatabaseKind, DatabaseMode};
+use qovery_engine::io_models::database::{Database, DatabaseBackupPolicy, DatabaseKind, DatabaseMode};
 use qovery_engine::io_models::Action;
 use qovery_engine::transaction::TransactionResult;
 use qovery_engine::utilities::to_short_id;
@@ -266,8 +266,14 @@ fn postgresql_deploy_a_working_environment_and_redeploy() {
             encrypt_disk: false,
             activate_high_availability: false,
             activate_backups: false,
+            backup_policy: DatabaseBackupPolicy::default(),
+            snapshot_schedule: SnapshotSchedule::Never,
+            snapshot_retention_in_days: 0,
+            read_replicas: 0,
+            connection_pooling: None,
             publicly_accessible: false,
             mode: CONTAINER,
+            clone_from_long_id: None,
         }];
         environment.applications = environment
             .applications
@@ -900,3 +906,100 @@ fn private_redis_v5_deploy_a_working_prod_environment() {
 fn private_redis_v6_deploy_a_working_prod_environment() {
     test_redis_configuration("6", function_name!(), MANAGED, KubernetesKind::Eks, false);
 }
+
+/**
+**
+** OpenSearch, ClickHouse, RabbitMQ and Kafka tests
+**
+**/
+#[allow(dead_code)]
+pub fn test_self_hosted_only_database_configuration(
+    db_kind: DatabaseKind,
+    version: &str,
+    test_name: &str,
+    kubernetes_kind: KubernetesKind,
+    is_public: bool,
+) {
+    let secrets = FuncTestsSecrets::new();
+    let cluster_id = secrets
+        .AWS_TEST_CLUSTER_LONG_ID
+        .expect("AWS_TEST_CLUSTER_LONG_ID is not set");
+    let context = context(
+        secrets
+            .AWS_TEST_ORGANIZATION_LONG_ID
+            .expect("AWS_TEST_ORGANIZATION_LONG_ID is not set"),
+        cluster_id,
+    );
+    let environment = helpers::database::database_test_environment(&context);
+
+    engine_run_test(|| {
+        test_db(
+            context,
+            logger(),
+            environment,
+            secrets,
+            version,
+            test_name,
+            db_kind,
+            kubernetes_kind,
+            CONTAINER,
+            is_public,
+            ClusterDomain::Default {
+                cluster_id: cluster_id.to_string(),
+            },
+            None,
+        )
+    })
+}
+
+#[cfg(feature = "test-aws-self-hosted")]
+#[named]
+#[test]
+fn private_opensearch_v2_deploy_a_working_dev_environment() {
+    test_self_hosted_only_database_configuration(
+        DatabaseKind::Opensearch,
+        "2",
+        function_name!(),
+        KubernetesKind::Eks,
+        false,
+    );
+}
+
+#[cfg(feature = "test-aws-self-hosted")]
+#[named]
+#[test]
+fn private_clickhouse_v22_deploy_a_working_dev_environment() {
+    test_self_hosted_only_database_configuration(
+        DatabaseKind::Clickhouse,
+        "22",
+        function_name!(),
+        KubernetesKind::Eks,
+        false,
+    );
+}
+
+#[cfg(feature = "test-aws-self-hosted")]
+#[named]
+#[test]
+fn private_rabbitmq_v3_deploy_a_working_dev_environment() {
+    test_self_hosted_only_database_configuration(
+        DatabaseKind::Rabbitmq,
+        "3",
+        function_name!(),
+        KubernetesKind::Eks,
+        false,
+    );
+}
+
+#[cfg(feature = "test-aws-self-hosted")]
+#[named]
+#[test]
+fn private_kafka_v3_deploy_a_working_dev_environment() {
+    test_self_hosted_only_database_configuration(
+        DatabaseKind::Kafka,
+        "3",
+        function_name!(),
+        KubernetesKind::Eks,
+        false,
+    );
+}
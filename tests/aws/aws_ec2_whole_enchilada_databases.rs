@@ -11,6 +11,7 @@ use qovery_engine::io_models::database::{DatabaseKind, DatabaseMode};
 use qovery_engine::logger::Logger;
 use qovery_engine::transaction::{Transaction, TransactionResult};
 use qovery_engine::utilities::to_short_id;
+use tracing::info;
 
 use crate::helpers;
 use crate::helpers::aws::AWS_TEST_REGION;
@@ -235,6 +236,16 @@ fn test_ec2_database(
 ) {
     engine_run_test(|| {
         init();
+
+        // these databases are only deployed as containers, their tests run on the shared EKS cluster
+        if matches!(
+            database_kind,
+            DatabaseKind::Opensearch | DatabaseKind::Clickhouse | DatabaseKind::Rabbitmq | DatabaseKind::Kafka
+        ) {
+            info!("skipping {}, no EC2 test covers {} databases", test_name, database_kind.name());
+            return test_name.to_string();
+        }
+
         let logger = logger();
         let organization_id = generate_id();
         let cluster_id = generate_cluster_id(AWS_TEST_REGION.to_aws_format());
@@ -311,6 +322,8 @@ fn test_ec2_database(
                 cluster_domain,
                 &engine_config,
             ),
+            // skipped before the cluster creation
+            DatabaseKind::Opensearch | DatabaseKind::Clickhouse | DatabaseKind::Rabbitmq | DatabaseKind::Kafka => {}
         };
 
         // Delete
@@ -6,11 +6,12 @@ use crate::helpers::utilities::{context, get_pvc, init, is_pod_restarted_env, ku
 use crate::helpers::utilities::{engine_run_test, get_pods, logger, FuncTestsSecrets};
 use ::function_name::named;
 use qovery_engine::cloud_provider::Kind;
-use qovery_engine::io_models::application::{Port, Protocol, Storage, StorageType};
+use qovery_engine::io_models::application::{Port, Protocol, SnapshotSchedule, Storage, StorageType};
 use qovery_engine::io_models::container::{Container, Registry};
 use qovery_engine::io_models::context::CloneForTest;
 use qovery_engine::io_models::router::{CustomDomain, Route, Router};
 use qovery_engine::io_models::Action;
+use qovery_engine::models::router::RouterAdvancedSettings;
 use qovery_engine::transaction::TransactionResult;
 use qovery_engine::utilities::to_short_id;
 use retry::delay::Fibonacci;
@@ -428,6 +429,7 @@ fn deploy_a_working_environment_with_custom_domain_and_disable_check_on_custom_d
             let cd = CustomDomain {
                 domain: format!("fake-custom-domain-{}.qovery.io", idx),
                 target_domain: format!("validation-domain-{}", idx),
+                certificate: None,
             };
 
             router.custom_domains = vec![cd];
@@ -499,6 +501,8 @@ fn deploy_a_working_environment_with_storage_on_aws_eks() {
                     size_in_gib: storage_size,
                     mount_point: "/mnt/photos".to_string(),
                     snapshot_retention_in_days: 0,
+                    restore_from_snapshot: None,
+                    snapshot_schedule: SnapshotSchedule::Never,
                 }];
                 app
             })
@@ -572,6 +576,8 @@ fn redeploy_same_app_with_ebs() {
                     size_in_gib: storage_size,
                     mount_point: "/mnt/photos".to_string(),
                     snapshot_retention_in_days: 0,
+                    restore_from_snapshot: None,
+                    snapshot_schedule: SnapshotSchedule::Never,
                 }];
                 app
             })
@@ -864,8 +870,7 @@ fn aws_eks_deploy_a_working_environment_with_sticky_session() {
             .unwrap()
             .to_router_domain(
                 engine_config.context(),
-                true,
-                "0.0.0.0/0".to_string(),
+                RouterAdvancedSettings::default(),
                 engine_config.cloud_provider(),
                 logger.clone(),
             )
@@ -990,6 +995,9 @@ fn deploy_container_with_no_router_on_aws_eks() {
             storages: vec![],
             environment_vars: btreemap! { "MY_VAR".to_string() => base64::encode("my_value") },
             advanced_settings: Default::default(),
+            depends_on: vec![],
+            clone_from_long_id: None,
+            secret_environment_vars: BTreeMap::new(),
         }];
 
         let mut environment_for_delete = environment.clone();
@@ -1073,6 +1081,9 @@ fn deploy_container_with_router_on_aws_eks() {
             storages: vec![],
             environment_vars: btreemap! { "MY_VAR".to_string() => base64::encode("my_value") },
             advanced_settings: Default::default(),
+            depends_on: vec![],
+            clone_from_long_id: None,
+            secret_environment_vars: BTreeMap::new(),
         }];
 
         environment.routers = vec![Router {
@@ -1086,7 +1097,10 @@ fn deploy_container_with_router_on_aws_eks() {
             routes: vec![Route {
                 path: "/".to_string(),
                 service_long_id: environment.containers[0].long_id,
+                rewrite_path: None,
+                service_port: None,
             }],
+            clone_from_long_id: None,
         }];
 
         let mut environment_for_delete = environment.clone();
@@ -2,6 +2,7 @@ use crate::helpers::aws::{AWS_KUBERNETES_VERSION, AWS_TEST_REGION};
 use crate::helpers::common::{compute_test_cluster_endpoint, Cluster, ClusterDomain, Infrastructure};
 use crate::helpers::kubernetes::{KUBERNETES_MAX_NODES, KUBERNETES_MIN_NODES};
 use crate::helpers::scaleway::{SCW_KUBERNETES_VERSION, SCW_TEST_ZONE};
+use crate::helpers::self_managed::{SELF_MANAGED_KUBERNETES_VERSION, SELF_MANAGED_TEST_REGION};
 use crate::helpers::utilities::{
     db_disk_type, db_infos, db_instance_type, generate_id, generate_password, get_pvc, get_svc, get_svc_name, init,
     FuncTestsSecrets,
@@ -17,13 +18,16 @@ use qovery_engine::cloud_provider::environment::Environment;
 use qovery_engine::cloud_provider::kubernetes::Kind as KubernetesKind;
 use qovery_engine::cloud_provider::qovery::EngineLocation;
 use qovery_engine::cloud_provider::scaleway::Scaleway;
+use qovery_engine::cloud_provider::self_managed::SelfManaged;
 use qovery_engine::cloud_provider::Kind;
 use qovery_engine::cmd::structs::SVCItem;
 use qovery_engine::engine::EngineConfig;
-use qovery_engine::io_models::application::{Application, GitCredentials, Port, Protocol, Storage, StorageType};
+use qovery_engine::io_models::application::{
+    Application, GitCredentials, Port, Protocol, SnapshotSchedule, Storage, StorageType,
+};
 use qovery_engine::io_models::context::{CloneForTest, Context};
 use qovery_engine::io_models::database::DatabaseMode::{CONTAINER, MANAGED};
-use qovery_engine::io_models::database::{Database, DatabaseKind, DatabaseMode};
+use qovery_engine::io_models::database::{Database, DatabaseBackupPolicy, DatabaseKind, DatabaseMode};
 use qovery_engine::io_models::environment::EnvironmentRequest;
 use qovery_engine::io_models::Action;
 use qovery_engine::logger::Logger;
@@ -205,6 +209,8 @@ pub fn environment_3_apps_3_databases(
                         size_in_gib: 10,
                         mount_point: "/mnt/photos".to_string(),
                         snapshot_retention_in_days: 0,
+                        restore_from_snapshot: None,
+                        snapshot_schedule: SnapshotSchedule::Never,
                     }]
                 },
                 environment_vars: btreemap! {
@@ -230,6 +236,9 @@ pub fn environment_3_apps_3_databases(
                 max_instances: 2,
                 cpu_burst: "100m".to_string(),
                 advanced_settings: Default::default(),
+                depends_on: vec![],
+                clone_from_long_id: None,
+                secret_environment_vars: BTreeMap::new(),
             },
             Application {
                 long_id: Uuid::new_v4(),
@@ -269,6 +278,9 @@ pub fn environment_3_apps_3_databases(
                 max_instances: 2,
                 cpu_burst: "100m".to_string(),
                 advanced_settings: Default::default(),
+                depends_on: vec![],
+                clone_from_long_id: None,
+                secret_environment_vars: BTreeMap::new(),
             },
             Application {
                 long_id: Uuid::new_v4(),
@@ -310,9 +322,13 @@ pub fn environment_3_apps_3_databases(
                 max_instances: 2,
                 cpu_burst: "100m".to_string(),
                 advanced_settings: Default::default(),
+                depends_on: vec![],
+                clone_from_long_id: None,
+                secret_environment_vars: BTreeMap::new(),
             },
         ],
         containers: vec![],
+        jobs: vec![],
         routers: vec![],
         databases: vec![
             Database {
@@ -334,8 +350,14 @@ pub fn environment_3_apps_3_databases(
                 encrypt_disk: true,
                 activate_high_availability: false,
                 activate_backups: false,
+                backup_policy: DatabaseBackupPolicy::default(),
+                snapshot_schedule: SnapshotSchedule::Never,
+                snapshot_retention_in_days: 0,
+                read_replicas: 0,
+                connection_pooling: None,
                 publicly_accessible: false,
                 mode: CONTAINER,
+                clone_from_long_id: None,
             },
             Database {
                 kind: DatabaseKind::Postgresql,
@@ -356,8 +378,14 @@ pub fn environment_3_apps_3_databases(
                 encrypt_disk: true,
                 activate_high_availability: false,
                 activate_backups: false,
+                backup_policy: DatabaseBackupPolicy::default(),
+                snapshot_schedule: SnapshotSchedule::Never,
+                snapshot_retention_in_days: 0,
+                read_replicas: 0,
+                connection_pooling: None,
                 publicly_accessible: false,
                 mode: CONTAINER,
+                clone_from_long_id: None,
             },
             Database {
                 kind: DatabaseKind::Mongodb,
@@ -378,11 +406,18 @@ pub fn environment_3_apps_3_databases(
                 encrypt_disk: true,
                 activate_high_availability: false,
                 activate_backups: false,
+                backup_policy: DatabaseBackupPolicy::default(),
+                snapshot_schedule: SnapshotSchedule::Never,
+                snapshot_retention_in_days: 0,
+                read_replicas: 0,
+                connection_pooling: None,
                 publicly_accessible: false,
                 mode: CONTAINER,
+                clone_from_long_id: None,
             },
         ],
         clone_from_environment_id: None,
+        idle_scale_to_zero_after_minutes: None,
     }
 }
 
@@ -420,11 +455,16 @@ pub fn database_test_environment(context: &Context) -> EnvironmentRequest {
             max_instances: 1,
             cpu_burst: "100m".to_string(),
             advanced_settings: Default::default(),
+            depends_on: vec![],
+            clone_from_long_id: None,
+            secret_environment_vars: BTreeMap::new(),
         }],
         containers: vec![],
+        jobs: vec![],
         routers: vec![],
         databases: vec![],
         clone_from_environment_id: None,
+        idle_scale_to_zero_after_minutes: None,
     }
 }
 
@@ -462,11 +502,16 @@ pub fn database_test_environment_on_upgrade(context: &Context) -> EnvironmentReq
             max_instances: 1,
             cpu_burst: "100m".to_string(),
             advanced_settings: Default::default(),
+            depends_on: vec![],
+            clone_from_long_id: None,
+            secret_environment_vars: BTreeMap::new(),
         }],
         containers: vec![],
+        jobs: vec![],
         routers: vec![],
         databases: vec![],
         clone_from_environment_id: None,
+        idle_scale_to_zero_after_minutes: None,
     }
 }
 
@@ -560,35 +605,45 @@ pub fn test_db(
         encrypt_disk: true,
         activate_high_availability: false,
         activate_backups: false,
+        backup_policy: DatabaseBackupPolicy::default(),
+        snapshot_schedule: SnapshotSchedule::Never,
+        snapshot_retention_in_days: 0,
+        read_replicas: 0,
+        connection_pooling: None,
         publicly_accessible: is_public,
         mode: database_mode.clone(),
+        clone_from_long_id: None,
     };
 
     environment.databases = vec![db.clone()];
 
     let app_name = format!("{}-app-{}", db_kind_str, generate_id());
-    environment.applications = environment
-        .applications
-        .into_iter()
-        .map(|mut app| {
-            app.long_id = app_id;
-            app.name = to_short_id(&app_id);
-            app.branch = app_name.clone();
-            app.commit_id = db_infos.app_commit.clone();
-            app.ports = vec![Port {
-                id: "zdf7d6aad".to_string(),
-                long_id: Default::default(),
-                port: 1234,
-                is_default: true,
-                name: None,
-                publicly_accessible: true,
-                protocol: Protocol::HTTP,
-            }];
-            app.dockerfile_path = Some(format!("Dockerfile-{}", version));
-            app.environment_vars = db_infos.app_env_vars.clone();
-            app
-        })
-        .collect::<Vec<Application>>();
+    // without a test application for this kind of database, only its deployment is checked
+    environment.applications = match &db_infos.app_commit {
+        Some(app_commit) => environment
+            .applications
+            .into_iter()
+            .map(|mut app| {
+                app.long_id = app_id;
+                app.name = to_short_id(&app_id);
+                app.branch = app_name.clone();
+                app.commit_id = app_commit.clone();
+                app.ports = vec![Port {
+                    id: "zdf7d6aad".to_string(),
+                    long_id: Default::default(),
+                    port: 1234,
+                    is_default: true,
+                    name: None,
+                    publicly_accessible: true,
+                    protocol: Protocol::HTTP,
+                }];
+                app.dockerfile_path = Some(format!("Dockerfile-{}", version));
+                app.environment_vars = db_infos.app_env_vars.clone();
+                app
+            })
+            .collect::<Vec<Application>>(),
+        None => vec![],
+    };
 
     let mut environment_delete = environment.clone();
     environment_delete.action = Action::Delete;
@@ -598,6 +653,10 @@ pub fn test_db(
     let (localisation, kubernetes_version) = match provider_kind {
         Kind::Aws => (AWS_TEST_REGION.to_string(), AWS_KUBERNETES_VERSION.to_string()),
         Kind::Do => ("".to_string(), "".to_string()),
+        Kind::SelfManaged => (
+            SELF_MANAGED_TEST_REGION.to_string(),
+            SELF_MANAGED_KUBERNETES_VERSION.to_string(),
+        ),
         Kind::Scw => (SCW_TEST_ZONE.to_string(), SCW_KUBERNETES_VERSION.to_string()),
     };
 
@@ -631,6 +690,18 @@ pub fn test_db(
                     EngineLocation::QoverySide, // EC2 is not meant to run Engine
                 ),
                 KubernetesKind::Doks => todo!(),
+                KubernetesKind::SelfManaged => SelfManaged::docker_cr_engine(
+                    &context,
+                    logger.clone(),
+                    localisation.as_str(),
+                    KubernetesKind::SelfManaged,
+                    kubernetes_version.clone(),
+                    &cluster_domain,
+                    None,
+                    KUBERNETES_MIN_NODES,
+                    KUBERNETES_MAX_NODES,
+                    EngineLocation::ClientSide,
+                ),
                 KubernetesKind::ScwKapsule => Scaleway::docker_cr_engine(
                     &context,
                     logger.clone(),
@@ -734,6 +805,18 @@ pub fn test_db(
                     EngineLocation::QoverySide, // EC2 is not meant to run Engine
                 ),
                 KubernetesKind::Doks => todo!(),
+                KubernetesKind::SelfManaged => SelfManaged::docker_cr_engine(
+                    &context_for_delete,
+                    logger.clone(),
+                    localisation.as_str(),
+                    KubernetesKind::SelfManaged,
+                    kubernetes_version,
+                    &cluster_domain,
+                    None,
+                    KUBERNETES_MIN_NODES,
+                    KUBERNETES_MAX_NODES,
+                    EngineLocation::ClientSide,
+                ),
                 KubernetesKind::ScwKapsule => Scaleway::docker_cr_engine(
                     &context_for_delete,
                     logger.clone(),
@@ -838,8 +921,14 @@ pub fn test_pause_managed_db(
         encrypt_disk: true,
         activate_high_availability: false,
         activate_backups: false,
+        backup_policy: DatabaseBackupPolicy::default(),
+        snapshot_schedule: SnapshotSchedule::Never,
+        snapshot_retention_in_days: 0,
+        read_replicas: 0,
+        connection_pooling: None,
         publicly_accessible: is_public,
         mode: database_mode.clone(),
+        clone_from_long_id: None,
     };
 
     environment.databases = vec![db];
@@ -853,6 +942,10 @@ pub fn test_pause_managed_db(
     let (localisation, kubernetes_version) = match provider_kind {
         Kind::Aws => (AWS_TEST_REGION.to_string(), AWS_KUBERNETES_VERSION.to_string()),
         Kind::Do => ("".to_string(), "".to_string()),
+        Kind::SelfManaged => (
+            SELF_MANAGED_TEST_REGION.to_string(),
+            SELF_MANAGED_KUBERNETES_VERSION.to_string(),
+        ),
         Kind::Scw => (SCW_TEST_ZONE.to_string(), SCW_KUBERNETES_VERSION.to_string()),
     };
 
@@ -886,6 +979,18 @@ pub fn test_pause_managed_db(
                     EngineLocation::QoverySide, // EC2 is not meant to run Engine
                 ),
                 KubernetesKind::Doks => todo!(),
+                KubernetesKind::SelfManaged => SelfManaged::docker_cr_engine(
+                    &context,
+                    logger.clone(),
+                    localisation.as_str(),
+                    KubernetesKind::SelfManaged,
+                    kubernetes_version.clone(),
+                    &cluster_domain,
+                    None,
+                    KUBERNETES_MIN_NODES,
+                    KUBERNETES_MAX_NODES,
+                    EngineLocation::ClientSide,
+                ),
                 KubernetesKind::ScwKapsule => Scaleway::docker_cr_engine(
                     &context,
                     logger.clone(),
@@ -985,6 +1090,18 @@ pub fn test_pause_managed_db(
                     EngineLocation::QoverySide, // EC2 is not meant to run Engine
                 ),
                 KubernetesKind::Doks => todo!(),
+                KubernetesKind::SelfManaged => SelfManaged::docker_cr_engine(
+                    &context_for_delete,
+                    logger.clone(),
+                    localisation.as_str(),
+                    KubernetesKind::SelfManaged,
+                    kubernetes_version,
+                    &cluster_domain,
+                    None,
+                    KUBERNETES_MIN_NODES,
+                    KUBERNETES_MAX_NODES,
+                    EngineLocation::ClientSide,
+                ),
                 KubernetesKind::ScwKapsule => Scaleway::docker_cr_engine(
                     &context_for_delete,
                     logger.clone(),
@@ -1080,35 +1197,45 @@ pub fn test_db_on_upgrade(
         encrypt_disk: true,
         activate_high_availability: false,
         activate_backups: false,
+        backup_policy: DatabaseBackupPolicy::default(),
+        snapshot_schedule: SnapshotSchedule::Never,
+        snapshot_retention_in_days: 0,
+        read_replicas: 0,
+        connection_pooling: None,
         publicly_accessible: is_public,
         mode: database_mode.clone(),
+        clone_from_long_id: None,
     };
 
     environment.databases = vec![db];
 
     let app_name = format!("{}-app-{}", db_kind_str, generate_id());
-    environment.applications = environment
-        .applications
-        .into_iter()
-        .map(|mut app| {
-            app.long_id = app_id;
-            app.name = to_short_id(&app_id);
-            app.branch = app_name.clone();
-            app.commit_id = db_infos.app_commit.clone();
-            app.ports = vec![Port {
-                id: "zdf7d6aad".to_string(),
-                long_id: Default::default(),
-                port: 1234,
-                is_default: true,
-                name: None,
-                publicly_accessible: true,
-                protocol: Protocol::HTTP,
-            }];
-            app.dockerfile_path = Some(format!("Dockerfile-{}", version));
-            app.environment_vars = db_infos.app_env_vars.clone();
-            app
-        })
-        .collect::<Vec<Application>>();
+    // without a test application for this kind of database, only its deployment is checked
+    environment.applications = match &db_infos.app_commit {
+        Some(app_commit) => environment
+            .applications
+            .into_iter()
+            .map(|mut app| {
+                app.long_id = app_id;
+                app.name = to_short_id(&app_id);
+                app.branch = app_name.clone();
+                app.commit_id = app_commit.clone();
+                app.ports = vec![Port {
+                    id: "zdf7d6aad".to_string(),
+                    long_id: Default::default(),
+                    port: 1234,
+                    is_default: true,
+                    name: None,
+                    publicly_accessible: true,
+                    protocol: Protocol::HTTP,
+                }];
+                app.dockerfile_path = Some(format!("Dockerfile-{}", version));
+                app.environment_vars = db_infos.app_env_vars.clone();
+                app
+            })
+            .collect::<Vec<Application>>(),
+        None => vec![],
+    };
 
     let mut environment_delete = environment.clone();
     environment_delete.action = Action::Delete;
@@ -1118,6 +1245,10 @@ pub fn test_db_on_upgrade(
     let (localisation, kubernetes_version) = match provider_kind {
         Kind::Aws => (AWS_TEST_REGION.to_string(), AWS_KUBERNETES_VERSION.to_string()),
         Kind::Do => ("".to_string(), "".to_string()),
+        Kind::SelfManaged => (
+            SELF_MANAGED_TEST_REGION.to_string(),
+            SELF_MANAGED_KUBERNETES_VERSION.to_string(),
+        ),
         Kind::Scw => (SCW_TEST_ZONE.to_string(), SCW_KUBERNETES_VERSION.to_string()),
     };
 
@@ -1137,6 +1268,20 @@ pub fn test_db_on_upgrade(
             EngineLocation::ClientSide,
         ),
         Kind::Do => todo!(),
+        Kind::SelfManaged => SelfManaged::docker_cr_engine(
+            &context,
+            logger.clone(),
+            localisation.as_str(),
+            KubernetesKind::SelfManaged,
+            kubernetes_version.clone(),
+            &ClusterDomain::Default {
+                cluster_id: context.cluster_short_id().to_string(),
+            },
+            None,
+            KUBERNETES_MIN_NODES,
+            KUBERNETES_MAX_NODES,
+            EngineLocation::ClientSide,
+        ),
         Kind::Scw => Scaleway::docker_cr_engine(
             &context,
             logger.clone(),
@@ -1221,6 +1366,20 @@ pub fn test_db_on_upgrade(
             EngineLocation::ClientSide,
         ),
         Kind::Do => todo!(),
+        Kind::SelfManaged => SelfManaged::docker_cr_engine(
+            &context_for_delete,
+            logger.clone(),
+            localisation.as_str(),
+            KubernetesKind::SelfManaged,
+            kubernetes_version,
+            &ClusterDomain::Default {
+                cluster_id: context_for_delete.cluster_short_id().to_string(),
+            },
+            None,
+            KUBERNETES_MIN_NODES,
+            KUBERNETES_MAX_NODES,
+            EngineLocation::ClientSide,
+        ),
         Kind::Scw => Scaleway::docker_cr_engine(
             &context_for_delete,
             logger.clone(),
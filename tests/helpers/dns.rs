@@ -1,6 +1,7 @@
 use crate::helpers::common::ClusterDomain;
 use crate::helpers::utilities::FuncTestsSecrets;
 use qovery_engine::dns_provider::cloudflare::Cloudflare;
+use qovery_engine::dns_provider::noop::NoOpDns;
 use qovery_engine::dns_provider::qoverydns::QoveryDns;
 use qovery_engine::dns_provider::DnsProvider;
 use qovery_engine::io_models::context::Context;
@@ -58,3 +59,13 @@ pub fn dns_provider_qoverydns(context: &Context, cluster_domain: &ClusterDomain)
         domain,
     ))
 }
+
+pub fn dns_provider_noop(context: &Context, cluster_domain: &ClusterDomain) -> Box<dyn DnsProvider> {
+    // self-managed clusters are reached through their own ingress, records are never created
+    let domain = Domain::new(match cluster_domain {
+        ClusterDomain::Custom { domain } => domain.to_string(),
+        ClusterDomain::Default { cluster_id } => format!("{}.localhost", cluster_id),
+        ClusterDomain::QoveryOwnedDomain { cluster_id, domain } => format!("{}.{}", cluster_id, domain,),
+    });
+    Box::new(NoOpDns::new(context.clone(), Uuid::new_v4(), "Qovery Test NoOpDns", domain))
+}
@@ -2,10 +2,10 @@ use crate::helpers::utilities::{generate_id, generate_password, get_svc_name};
 use chrono::Utc;
 use qovery_engine::cloud_provider::utilities::sanitize_name;
 use qovery_engine::cloud_provider::Kind;
-use qovery_engine::io_models::application::{Application, GitCredentials, Port, Protocol};
+use qovery_engine::io_models::application::{Application, GitCredentials, Port, Protocol, SnapshotSchedule};
 use qovery_engine::io_models::context::Context;
 use qovery_engine::io_models::database::DatabaseMode::CONTAINER;
-use qovery_engine::io_models::database::{Database, DatabaseKind};
+use qovery_engine::io_models::database::{Database, DatabaseBackupPolicy, DatabaseKind};
 use qovery_engine::io_models::environment::EnvironmentRequest;
 use qovery_engine::io_models::router::{Route, Router};
 use qovery_engine::io_models::Action;
@@ -62,11 +62,16 @@ pub fn working_environment(
             max_instances: 1,
             cpu_burst: "100m".to_string(),
             advanced_settings: Default::default(),
+            depends_on: vec![],
+            clone_from_long_id: None,
+            secret_environment_vars: BTreeMap::new(),
         }],
         containers: vec![],
+        jobs: vec![],
         routers: vec![],
         databases: vec![],
         clone_from_environment_id: None,
+        idle_scale_to_zero_after_minutes: None,
     };
 
     if with_router {
@@ -80,8 +85,11 @@ pub fn working_environment(
             routes: vec![Route {
                 path: "/".to_string(),
                 service_long_id: application_id,
+                rewrite_path: None,
+                service_port: None,
             }],
             sticky_sessions_enabled: with_sticky,
+            clone_from_long_id: None,
         }]
     }
 
@@ -138,8 +146,14 @@ pub fn environment_2_app_2_routers_1_psql(
             encrypt_disk: true,
             activate_high_availability: false,
             activate_backups: false,
+            backup_policy: DatabaseBackupPolicy::default(),
+            snapshot_schedule: SnapshotSchedule::Never,
+            snapshot_retention_in_days: 0,
+            read_replicas: 0,
+            connection_pooling: None,
             publicly_accessible: false,
             mode: CONTAINER,
+            clone_from_long_id: None,
         }],
         applications: vec![
             Application {
@@ -180,6 +194,9 @@ pub fn environment_2_app_2_routers_1_psql(
                 max_instances: 1,
                 cpu_burst: "100m".to_string(),
                 advanced_settings: Default::default(),
+                depends_on: vec![],
+                clone_from_long_id: None,
+                secret_environment_vars: BTreeMap::new(),
             },
             Application {
                 long_id: application_id2,
@@ -219,9 +236,13 @@ pub fn environment_2_app_2_routers_1_psql(
                 max_instances: 1,
                 cpu_burst: "100m".to_string(),
                 advanced_settings: Default::default(),
+                depends_on: vec![],
+                clone_from_long_id: None,
+                secret_environment_vars: BTreeMap::new(),
             },
         ],
         containers: vec![],
+        jobs: vec![],
         routers: vec![
             Router {
                 long_id: Uuid::new_v4(),
@@ -233,8 +254,11 @@ pub fn environment_2_app_2_routers_1_psql(
                 routes: vec![Route {
                     path: "/".to_string(),
                     service_long_id: application_id1,
+                    rewrite_path: None,
+                    service_port: None,
                 }],
                 sticky_sessions_enabled: false,
+                clone_from_long_id: None,
             },
             Router {
                 long_id: Uuid::new_v4(),
@@ -246,11 +270,15 @@ pub fn environment_2_app_2_routers_1_psql(
                 routes: vec![Route {
                     path: "/coco".to_string(),
                     service_long_id: application_id2,
+                    rewrite_path: None,
+                    service_port: None,
                 }],
                 sticky_sessions_enabled: false,
+                clone_from_long_id: None,
             },
         ],
         clone_from_environment_id: None,
+        idle_scale_to_zero_after_minutes: None,
     }
 }
 
@@ -316,8 +344,12 @@ pub fn echo_app_environment(context: &Context, test_domain: &str) -> Environment
             max_instances: 1,
             cpu_burst: "100m".to_string(),
             advanced_settings: Default::default(),
+            depends_on: vec![],
+            clone_from_long_id: None,
+            secret_environment_vars: BTreeMap::new(),
         }],
         containers: vec![],
+        jobs: vec![],
         routers: vec![Router {
             long_id: Uuid::new_v4(),
             name: "main".to_string(),
@@ -328,11 +360,15 @@ pub fn echo_app_environment(context: &Context, test_domain: &str) -> Environment
             routes: vec![Route {
                 path: "/".to_string(),
                 service_long_id: application_id,
+                rewrite_path: None,
+                service_port: None,
             }],
             sticky_sessions_enabled: false,
+            clone_from_long_id: None,
         }],
         databases: vec![],
         clone_from_environment_id: None,
+        idle_scale_to_zero_after_minutes: None,
     }
 }
 
@@ -387,11 +423,16 @@ pub fn environment_only_http_server(
             max_instances: 1,
             cpu_burst: "100m".to_string(),
             advanced_settings: Default::default(),
+            depends_on: vec![],
+            clone_from_long_id: None,
+            secret_environment_vars: BTreeMap::new(),
         }],
         containers: vec![],
+        jobs: vec![],
         routers: vec![],
         databases: vec![],
         clone_from_environment_id: None,
+        idle_scale_to_zero_after_minutes: None,
     };
 
     if with_router {
@@ -405,8 +446,11 @@ pub fn environment_only_http_server(
             routes: vec![Route {
                 path: "/".to_string(),
                 service_long_id: application_id,
+                rewrite_path: None,
+                service_port: None,
             }],
             sticky_sessions_enabled: with_sticky,
+            clone_from_long_id: None,
         }]
     }
 
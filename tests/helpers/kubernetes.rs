@@ -1,6 +1,6 @@
 use crate::helpers::aws_ec2::ec2_kubernetes_instance;
 use crate::helpers::common::{Cluster, ClusterDomain};
-use crate::helpers::utilities::{init, FuncTestsSecrets};
+use crate::helpers::utilities::{init, self_managed_kubeconfig_path, FuncTestsSecrets};
 
 use core::cell::RefCell;
 use core::option::Option;
@@ -16,6 +16,8 @@ use qovery_engine::cloud_provider::kubernetes::{Kind as KubernetesKind, Kubernet
 use qovery_engine::cloud_provider::qovery::EngineLocation;
 use qovery_engine::cloud_provider::scaleway::kubernetes::Kapsule;
 use qovery_engine::cloud_provider::scaleway::Scaleway;
+use qovery_engine::cloud_provider::self_managed::kubernetes::SelfManagedKubernetes;
+use qovery_engine::cloud_provider::self_managed::SelfManaged;
 use qovery_engine::cloud_provider::{CloudProvider, Kind};
 use qovery_engine::dns_provider::DnsProvider;
 use qovery_engine::io_models::context::Context;
@@ -117,6 +119,18 @@ pub fn get_cluster_test_kubernetes<'a>(
             )
         }
         KubernetesKind::Doks => todo!(),
+        KubernetesKind::SelfManaged => Box::new(SelfManagedKubernetes::new(
+            context.clone(),
+            Uuid::new_v4(),
+            cluster_name,
+            boot_version,
+            localisation.to_string(),
+            cloud_provider,
+            dns_provider,
+            self_managed_kubeconfig_path(),
+            logger,
+            ClusterAdvancedSettings::default(),
+        )),
         KubernetesKind::ScwKapsule => Box::new(
             Kapsule::new(
                 context.clone(),
@@ -175,6 +189,18 @@ pub fn cluster_test(
             EngineLocation::ClientSide,
         ),
         Kind::Do => todo!(),
+        Kind::SelfManaged => SelfManaged::docker_cr_engine(
+            &context,
+            logger.clone(),
+            localisation,
+            KubernetesKind::SelfManaged,
+            boot_version,
+            cluster_domain,
+            vpc_network_mode.clone(),
+            KUBERNETES_MIN_NODES,
+            KUBERNETES_MAX_NODES,
+            EngineLocation::ClientSide,
+        ),
         Kind::Scw => Scaleway::docker_cr_engine(
             &context,
             logger.clone(),
@@ -258,6 +284,18 @@ pub fn cluster_test(
                     EngineLocation::ClientSide,
                 ),
                 Kind::Do => todo!(),
+                Kind::SelfManaged => SelfManaged::docker_cr_engine(
+                    &context,
+                    logger.clone(),
+                    localisation,
+                    KubernetesKind::SelfManaged,
+                    upgrade_to_version,
+                    cluster_domain,
+                    vpc_network_mode,
+                    KUBERNETES_MIN_NODES,
+                    KUBERNETES_MAX_NODES,
+                    EngineLocation::ClientSide,
+                ),
                 Kind::Scw => Scaleway::docker_cr_engine(
                     &context,
                     logger.clone(),
@@ -308,6 +346,18 @@ pub fn cluster_test(
                     EngineLocation::ClientSide,
                 ),
                 Kind::Do => todo!(),
+                Kind::SelfManaged => SelfManaged::docker_cr_engine(
+                    &context,
+                    logger.clone(),
+                    localisation,
+                    KubernetesKind::SelfManaged,
+                    kubernetes_version,
+                    cluster_domain,
+                    vpc_network_mode,
+                    min_nodes,
+                    max_nodes,
+                    EngineLocation::ClientSide,
+                ),
                 Kind::Scw => Scaleway::docker_cr_engine(
                     &context,
                     logger.clone(),
@@ -439,6 +489,18 @@ pub fn get_environment_test_kubernetes(
             )
         }
         KubernetesKind::Doks => todo!(),
+        KubernetesKind::SelfManaged => Box::new(SelfManagedKubernetes::new(
+            context.clone(),
+            *context.cluster_long_id(),
+            format!("qovery-{}", context.cluster_short_id()),
+            kubernetes_version.to_string(),
+            localisation.to_string(),
+            cloud_provider,
+            dns_provider,
+            self_managed_kubeconfig_path(),
+            logger,
+            ClusterAdvancedSettings::default(),
+        )),
         KubernetesKind::ScwKapsule => {
             let zone = ScwZone::from_str(localisation).expect("SCW zone not supported");
             Box::new(
@@ -8,4 +8,5 @@ pub mod edge_aws_rs;
 pub mod environment;
 pub mod kubernetes;
 pub mod scaleway;
+pub mod self_managed;
 pub mod utilities;
@@ -0,0 +1,115 @@
+use std::sync::Arc;
+
+use url::Url;
+use uuid::Uuid;
+
+use qovery_engine::cloud_provider::aws::kubernetes::VpcQoveryNetworkMode;
+use qovery_engine::cloud_provider::kubernetes::Kind as KubernetesKind;
+use qovery_engine::cloud_provider::models::NodeGroups;
+use qovery_engine::cloud_provider::qovery::EngineLocation;
+use qovery_engine::cloud_provider::self_managed::SelfManaged;
+use qovery_engine::cloud_provider::CloudProvider;
+use qovery_engine::container_registry::generic_cr::{GenericCr, GenericCrFlavor};
+use qovery_engine::dns_provider::DnsProvider;
+use qovery_engine::engine::EngineConfig;
+use qovery_engine::io_models::context::Context;
+use qovery_engine::logger::Logger;
+
+use crate::helpers::common::{Cluster, ClusterDomain};
+use crate::helpers::dns::dns_provider_noop;
+use crate::helpers::kubernetes::get_environment_test_kubernetes;
+use crate::helpers::utilities::{build_platform_local_docker, generate_id, FuncTestsSecrets};
+
+// self-managed clusters (i.e: kind, k3s) are not created by the engine, version and region are informative only
+pub const SELF_MANAGED_KUBERNETES_VERSION: &str = "1.23";
+pub const SELF_MANAGED_TEST_REGION: &str = "local";
+
+pub fn container_registry_self_managed(context: &Context) -> GenericCr {
+    let secrets = FuncTestsSecrets::new();
+    let random_id = generate_id();
+    let registry_url = secrets
+        .SELF_MANAGED_REGISTRY_URL
+        .expect("SELF_MANAGED_REGISTRY_URL is not set in secrets");
+
+    GenericCr::new(
+        context.clone(),
+        format!("default-registry-qovery-test-{}", random_id).as_str(),
+        Uuid::new_v4(),
+        format!("default-registry-qovery-test-{}", random_id).as_str(),
+        Url::parse(registry_url.as_str()).expect("SELF_MANAGED_REGISTRY_URL is not a valid URL"),
+        None,
+        None,
+        GenericCrFlavor::OciDistribution,
+    )
+    .unwrap()
+}
+
+impl Cluster<SelfManaged, ()> for SelfManaged {
+    fn docker_cr_engine(
+        context: &Context,
+        logger: Box<dyn Logger>,
+        localisation: &str,
+        kubernetes_kind: KubernetesKind,
+        kubernetes_version: String,
+        cluster_domain: &ClusterDomain,
+        vpc_network_mode: Option<VpcQoveryNetworkMode>,
+        min_nodes: i32,
+        max_nodes: i32,
+        engine_location: EngineLocation,
+    ) -> EngineConfig {
+        // use any OCI registry reachable from the cluster, i.e: a kind local registry
+        let container_registry = Box::new(container_registry_self_managed(context));
+
+        // use LocalDocker
+        let build_platform = Box::new(build_platform_local_docker(context, logger.clone()));
+
+        // use SelfManaged, without DNS records
+        let cloud_provider: Arc<Box<dyn CloudProvider>> = Arc::new(Self::cloud_provider(context, kubernetes_kind));
+        let dns_provider: Arc<Box<dyn DnsProvider>> = Arc::new(dns_provider_noop(context, cluster_domain));
+
+        let cluster = get_environment_test_kubernetes(
+            context,
+            cloud_provider.clone(),
+            kubernetes_version.as_str(),
+            dns_provider.clone(),
+            logger.clone(),
+            localisation,
+            vpc_network_mode,
+            min_nodes,
+            max_nodes,
+            engine_location,
+        );
+
+        EngineConfig::new(
+            context.clone(),
+            build_platform,
+            container_registry,
+            cloud_provider,
+            dns_provider,
+            cluster,
+        )
+    }
+
+    fn cloud_provider(context: &Context, _kubernetes_kind: KubernetesKind) -> Box<SelfManaged> {
+        Box::new(SelfManaged::new(
+            context.clone(),
+            *context.cluster_long_id(),
+            context.organization_short_id(),
+            *context.organization_long_id(),
+            format!("qovery-{}", context.cluster_short_id()).as_str(),
+            SELF_MANAGED_TEST_REGION,
+        ))
+    }
+
+    fn kubernetes_nodes(_min_nodes: i32, _max_nodes: i32) -> Vec<NodeGroups> {
+        // nodes are managed by the user
+        vec![]
+    }
+
+    fn kubernetes_cluster_options(
+        _secrets: FuncTestsSecrets,
+        _cluster_id: Option<String>,
+        _engine_location: EngineLocation,
+    ) {
+    }
+}
@@ -79,6 +79,7 @@ pub fn context(organization_id: Uuid, cluster_id: Uuid) -> Context {
         forced_upgrade: Option::from(env::var_os("forced_upgrade").is_some()),
         disable_pleco: Some(true),
         is_first_cluster_deployment: None,
+        prometheus_pushgateway_url: None,
     };
     let enabled_features = vec![Features::LogsHistory, Features::MetricsHistory];
 
@@ -148,6 +149,7 @@ pub struct FuncTestsSecrets {
     pub SCALEWAY_TEST_CLUSTER_LONG_ID: Option<Uuid>,
     pub SCALEWAY_TEST_ORGANIZATION_ID: Option<String>,
     pub SCALEWAY_TEST_ORGANIZATION_LONG_ID: Option<Uuid>,
+    pub SELF_MANAGED_REGISTRY_URL: Option<String>,
     pub TERRAFORM_AWS_ACCESS_KEY_ID: Option<String>,
     pub TERRAFORM_AWS_SECRET_ACCESS_KEY: Option<String>,
     pub TERRAFORM_AWS_REGION: Option<String>,
@@ -251,6 +253,7 @@ impl FuncTestsSecrets {
             SCALEWAY_TEST_CLUSTER_LONG_ID: None,
             SCALEWAY_TEST_ORGANIZATION_ID: None,
             SCALEWAY_TEST_ORGANIZATION_LONG_ID: None,
+            SELF_MANAGED_REGISTRY_URL: None,
             TERRAFORM_AWS_ACCESS_KEY_ID: None,
             TERRAFORM_AWS_SECRET_ACCESS_KEY: None,
             TERRAFORM_AWS_REGION: None,
@@ -392,6 +395,10 @@ impl FuncTestsSecrets {
                 "SCALEWAY_TEST_CLUSTER_LONG_ID",
                 secrets.SCALEWAY_TEST_CLUSTER_LONG_ID,
             ),
+            SELF_MANAGED_REGISTRY_URL: Self::select_secret(
+                "SELF_MANAGED_REGISTRY_URL",
+                secrets.SELF_MANAGED_REGISTRY_URL,
+            ),
             TERRAFORM_AWS_ACCESS_KEY_ID: Self::select_secret(
                 "TERRAFORM_AWS_ACCESS_KEY_ID",
                 secrets.TERRAFORM_AWS_ACCESS_KEY_ID,
@@ -519,6 +526,12 @@ fn curl_path(path: &str) -> bool {
     }
 }
 
+/// Kubeconfig of the self-managed test cluster (i.e: kind, k3s), `$KUBECONFIG` or `~/.kube/config` by default
+pub fn self_managed_kubeconfig_path() -> String {
+    std::env::var("KUBECONFIG")
+        .unwrap_or_else(|_| format!("{}/.kube/config", std::env::var("HOME").unwrap_or_default()))
+}
+
 pub fn kubernetes_config_path(
     context: Context,
     provider_kind: Kind,
@@ -673,6 +686,17 @@ where
 
                 Err(CommandError::new_from_safe_message("Test cluster not found".to_string()))
             }
+            Kind::SelfManaged => {
+                // self-managed clusters (i.e: kind, k3s) kubeconfig is provided by the user
+                let kubeconfig_path = self_managed_kubeconfig_path();
+                fs::read_to_string(&kubeconfig_path).map_err(|e| {
+                    CommandError::new(
+                        format!("Cannot read kubeconfig file `{}`", kubeconfig_path),
+                        Some(e.to_string()),
+                        None,
+                    )
+                })
+            }
         };
 
         match file_content {
@@ -748,6 +772,7 @@ fn get_cloud_provider_credentials(provider_kind: Kind, secrets: &FuncTestsSecret
                 secrets.SCALEWAY_DEFAULT_PROJECT_ID.as_ref().unwrap().as_str(),
             ),
         ],
+        Kind::SelfManaged => vec![],
     }
 }
 
@@ -941,6 +966,10 @@ pub fn _db_fqnd(db: Database) -> String {
                 DatabaseKind::Mysql => "mysqlmysqldatabase",
                 DatabaseKind::Mongodb => "mongodbmymongodb",
                 DatabaseKind::Redis => "redismyredis-master",
+                DatabaseKind::Opensearch => "opensearchmyopensearch",
+                DatabaseKind::Clickhouse => "clickhousemyclickhouse",
+                DatabaseKind::Rabbitmq => "rabbitmqmyrabbitmq",
+                DatabaseKind::Kafka => "kafkamykafka",
             }
             .to_string(),
         },
@@ -950,7 +979,8 @@ pub fn _db_fqnd(db: Database) -> String {
 pub struct DBInfos {
     pub db_port: u16,
     pub db_name: String,
-    pub app_commit: String,
+    /// Commit of the test application checking the connection to the database, None when there is none for this kind
+    pub app_commit: Option<String>,
     pub app_env_vars: BTreeMap<String, String>,
 }
 
@@ -973,7 +1003,7 @@ pub fn db_infos(
             DBInfos {
                 db_port: database_port,
                 db_name: database_db_name.to_string(),
-                app_commit: "e824fd7062e112d513baecb537114563b77dbaf4".to_string(),
+                app_commit: Some("e824fd7062e112d513baecb537114563b77dbaf4".to_string()),
                 app_env_vars: btreemap! {
                     "IS_DOCUMENTDB".to_string() => base64::encode((database_mode == MANAGED).to_string()),
                     "QOVERY_DATABASE_TESTING_DATABASE_FQDN".to_string() => base64::encode(db_fqdn),
@@ -991,7 +1021,7 @@ pub fn db_infos(
             DBInfos {
                 db_port: database_port,
                 db_name: database_db_name.to_string(),
-                app_commit: "ba3dde5e67d8bdd85b25709c90875348071591a3".to_string(),
+                app_commit: Some("ba3dde5e67d8bdd85b25709c90875348071591a3".to_string()),
                 app_env_vars: btreemap! {
                     "MYSQL_HOST".to_string() => base64::encode(db_fqdn),
                     "MYSQL_PORT".to_string() => base64::encode(database_port.to_string()),
@@ -1011,7 +1041,7 @@ pub fn db_infos(
             DBInfos {
                 db_port: database_port,
                 db_name: database_db_name.to_string(),
-                app_commit: "750d5e6d616493ae50b6c6099e196b0643ae459f".to_string(),
+                app_commit: Some("750d5e6d616493ae50b6c6099e196b0643ae459f".to_string()),
                 app_env_vars: btreemap! {
                      "PG_DBNAME".to_string() => base64::encode(database_db_name),
                      "PG_HOST".to_string() => base64::encode(db_fqdn),
@@ -1027,7 +1057,7 @@ pub fn db_infos(
             DBInfos {
                 db_port: database_port,
                 db_name: database_db_name,
-                app_commit: "81256d5cc4bedfccfe18e2aa7783ee7fb9438a25".to_string(),
+                app_commit: Some("81256d5cc4bedfccfe18e2aa7783ee7fb9438a25".to_string()),
                 app_env_vars: btreemap! {
                 "IS_ELASTICCACHE".to_string() => base64::encode((database_mode == MANAGED && database_username == "default").to_string()),
                 "REDIS_HOST".to_string()      => base64::encode(db_fqdn),
@@ -1037,6 +1067,21 @@ pub fn db_infos(
                 },
             }
         }
+        // no test application checks these databases yet, only their deployment is tested
+        DatabaseKind::Opensearch | DatabaseKind::Clickhouse | DatabaseKind::Rabbitmq | DatabaseKind::Kafka => {
+            let database_port = match db_kind {
+                DatabaseKind::Opensearch => 9200,
+                DatabaseKind::Clickhouse => 8123,
+                DatabaseKind::Rabbitmq => 5672,
+                _ => 9092,
+            };
+            DBInfos {
+                db_port: database_port,
+                db_name: db_id,
+                app_commit: None,
+                app_env_vars: BTreeMap::new(),
+            }
+        }
     }
 }
 
@@ -1051,6 +1096,8 @@ pub fn db_disk_type(provider_kind: Kind, database_mode: DatabaseMode) -> String
             MANAGED => SCW_MANAGED_DATABASE_DISK_TYPE,
             DatabaseMode::CONTAINER => SCW_SELF_HOSTED_DATABASE_DISK_TYPE,
         },
+        // empty disk type means cluster default storage class
+        Kind::SelfManaged => "",
     }
     .to_string()
 }
@@ -1062,6 +1109,9 @@ pub fn db_instance_type(provider_kind: Kind, db_kind: DatabaseKind, database_mod
             DatabaseKind::Mysql => "db.t3.micro",
             DatabaseKind::Postgresql => "db.t3.micro",
             DatabaseKind::Redis => "cache.t3.micro",
+            DatabaseKind::Opensearch => "t3.small.search",
+            // only deployed as container databases
+            DatabaseKind::Clickhouse | DatabaseKind::Rabbitmq | DatabaseKind::Kafka => "",
         },
         Kind::Do => match database_mode {
             MANAGED => "",
@@ -1071,6 +1121,7 @@ pub fn db_instance_type(provider_kind: Kind, db_kind: DatabaseKind, database_mod
             MANAGED => SCW_MANAGED_DATABASE_INSTANCE_TYPE,
             DatabaseMode::CONTAINER => SCW_SELF_HOSTED_DATABASE_INSTANCE_TYPE,
         },
+        Kind::SelfManaged => "",
     }
     .to_string()
 }
@@ -1093,5 +1144,21 @@ pub fn get_svc_name(db_kind: DatabaseKind, provider_kind: Kind) -> &'static str
             Kind::Aws => "redismyredis-master",
             _ => "redis-my-redis-master",
         },
+        DatabaseKind::Opensearch => match provider_kind {
+            Kind::Aws => "opensearchmyopensearch",
+            _ => "opensearch-my-opensearch",
+        },
+        DatabaseKind::Clickhouse => match provider_kind {
+            Kind::Aws => "clickhousemyclickhouse",
+            _ => "clickhouse-my-clickhouse",
+        },
+        DatabaseKind::Rabbitmq => match provider_kind {
+            Kind::Aws => "rabbitmqmyrabbitmq",
+            _ => "rabbitmq-my-rabbitmq",
+        },
+        DatabaseKind::Kafka => match provider_kind {
+            Kind::Aws => "kafkamykafka",
+            _ => "kafka-my-kafka",
+        },
     }
 }
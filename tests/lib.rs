@@ -5,3 +5,4 @@ mod aws;
 mod helm;
 pub mod helpers;
 mod scaleway;
+mod self_managed;
@@ -4,7 +4,7 @@ use crate::helpers::utilities::{
 };
 use ::function_name::named;
 use qovery_engine::cloud_provider::{Kind as ProviderKind, Kind};
-use qovery_engine::io_models::database::{Database, DatabaseKind, DatabaseMode};
+use qovery_engine::io_models::database::{Database, DatabaseBackupPolicy, DatabaseKind, DatabaseMode};
 use qovery_engine::transaction::TransactionResult;
 use tracing::{span, warn, Level};
 use uuid::Uuid;
@@ -18,7 +18,7 @@ use crate::helpers::scaleway::{
     SCW_SELF_HOSTED_DATABASE_DISK_TYPE, SCW_SELF_HOSTED_DATABASE_INSTANCE_TYPE, SCW_TEST_ZONE,
 };
 use qovery_engine::cloud_provider::kubernetes::Kind as KubernetesKind;
-use qovery_engine::io_models::application::{Port, Protocol};
+use qovery_engine::io_models::application::{Port, Protocol, SnapshotSchedule};
 use qovery_engine::io_models::context::CloneForTest;
 use qovery_engine::io_models::database::DatabaseMode::{CONTAINER, MANAGED};
 use qovery_engine::io_models::Action;
@@ -296,7 +296,13 @@ fn postgresql_deploy_a_working_environment_and_redeploy() {
             encrypt_disk: false,
             activate_high_availability: false,
             activate_backups: false,
+            backup_policy: DatabaseBackupPolicy::default(),
+            snapshot_schedule: SnapshotSchedule::Never,
+            snapshot_retention_in_days: 0,
+            read_replicas: 0,
+            connection_pooling: None,
             publicly_accessible: false,
+            clone_from_long_id: None,
         }];
         environment.applications = environment
             .applications
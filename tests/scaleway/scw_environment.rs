@@ -9,11 +9,12 @@ use crate::helpers::utilities::{
 use crate::helpers::utilities::{get_pvc, is_pod_restarted_env};
 use ::function_name::named;
 use qovery_engine::cloud_provider::Kind;
-use qovery_engine::io_models::application::{Port, Protocol, Storage, StorageType};
+use qovery_engine::io_models::application::{Port, Protocol, SnapshotSchedule, Storage, StorageType};
 use qovery_engine::io_models::container::{Container, Registry};
 use qovery_engine::io_models::context::CloneForTest;
 use qovery_engine::io_models::router::{Route, Router};
 use qovery_engine::io_models::Action;
+use qovery_engine::models::router::RouterAdvancedSettings;
 use qovery_engine::transaction::TransactionResult;
 use qovery_engine::utilities::to_short_id;
 use retry::delay::Fibonacci;
@@ -396,6 +397,8 @@ fn scaleway_kapsule_deploy_a_working_environment_with_storage() {
                     size_in_gib: storage_size,
                     mount_point: "/mnt/photos".to_string(),
                     snapshot_retention_in_days: 0,
+                    restore_from_snapshot: None,
+                    snapshot_schedule: SnapshotSchedule::Never,
                 }];
                 app
             })
@@ -544,6 +547,8 @@ fn scaleway_kapsule_redeploy_same_app() {
                     size_in_gib: storage_size,
                     mount_point: "/mnt/photos".to_string(),
                     snapshot_retention_in_days: 0,
+                    restore_from_snapshot: None,
+                    snapshot_schedule: SnapshotSchedule::Never,
                 }];
                 app
             })
@@ -869,8 +874,7 @@ fn scaleway_kapsule_deploy_a_working_environment_with_sticky_session() {
             .unwrap()
             .to_router_domain(
                 engine_config.context(),
-                true,
-                "0.0.0.0/0".to_string(),
+                RouterAdvancedSettings::default(),
                 engine_config.cloud_provider(),
                 logger.clone(),
             )
@@ -1002,6 +1006,9 @@ fn deploy_container_with_no_router_on_scw() {
             storages: vec![],
             environment_vars: btreemap! { "MY_VAR".to_string() => base64::encode("my_value") },
             advanced_settings: Default::default(),
+            depends_on: vec![],
+            clone_from_long_id: None,
+            secret_environment_vars: BTreeMap::new(),
         }];
 
         let mut environment_for_delete = environment.clone();
@@ -1086,6 +1093,9 @@ fn deploy_container_with_router_on_scw() {
             storages: vec![],
             environment_vars: btreemap! { "MY_VAR".to_string() => base64::encode("my_value") },
             advanced_settings: Default::default(),
+            depends_on: vec![],
+            clone_from_long_id: None,
+            secret_environment_vars: BTreeMap::new(),
         }];
 
         environment.routers = vec![Router {
@@ -1099,7 +1109,10 @@ fn deploy_container_with_router_on_scw() {
             routes: vec![Route {
                 path: "/".to_string(),
                 service_long_id: environment.containers[0].long_id,
+                rewrite_path: None,
+                service_port: None,
             }],
+            clone_from_long_id: None,
         }];
 
         let mut environment_for_delete = environment.clone();
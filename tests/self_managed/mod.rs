@@ -0,0 +1 @@
+mod self_managed_databases;
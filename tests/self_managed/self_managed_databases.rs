@@ -0,0 +1,40 @@
+use ::function_name::named;
+use qovery_engine::cloud_provider::kubernetes::Kind as KubernetesKind;
+use qovery_engine::io_models::database::DatabaseKind;
+use qovery_engine::io_models::database::DatabaseMode::CONTAINER;
+use qovery_engine::utilities::to_short_id;
+use uuid::Uuid;
+
+use crate::helpers::common::ClusterDomain;
+use crate::helpers::database::{database_test_environment, test_db};
+use crate::helpers::utilities::{context, engine_run_test, logger, FuncTestsSecrets};
+
+// The self-managed cluster (i.e: kind, k3s) is the one of the current kubeconfig, it is neither created nor deleted
+#[cfg(feature = "test-self-managed-self-hosted")]
+#[named]
+#[test]
+fn private_postgresql_v14_deploy_a_working_dev_environment() {
+    let secrets = FuncTestsSecrets::new();
+    let cluster_id = Uuid::new_v4();
+    let context = context(Uuid::new_v4(), cluster_id);
+    let environment = database_test_environment(&context);
+
+    engine_run_test(|| {
+        test_db(
+            context,
+            logger(),
+            environment,
+            secrets,
+            "14",
+            function_name!(),
+            DatabaseKind::Postgresql,
+            KubernetesKind::SelfManaged,
+            CONTAINER,
+            false,
+            ClusterDomain::Default {
+                cluster_id: to_short_id(&cluster_id),
+            },
+            None,
+        )
+    })
+}